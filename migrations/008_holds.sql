@@ -0,0 +1,54 @@
+-- ============================================================================
+-- Migration 008: Balance Holds
+-- Phase 8: Two-phase payments (hold → capture / release)
+-- ============================================================================
+-- Create account_holds table
+-- Create account_holds indexes
+-- ============================================================================
+
+-- ============================================================================
+-- Create account_holds table
+-- Projection of BalanceHeld / HoldCaptured / HoldReleased events
+-- ============================================================================
+CREATE TABLE account_holds (
+    id UUID PRIMARY KEY,
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    to_account_id UUID NOT NULL REFERENCES accounts(id),
+    amount NUMERIC(20, 8) NOT NULL,
+    status VARCHAR(10) NOT NULL DEFAULT 'held',
+    description TEXT,
+    transfer_id UUID,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT hold_positive_amount CHECK (amount > 0),
+    CONSTRAINT valid_hold_status CHECK (status IN ('held', 'captured', 'released'))
+);
+
+COMMENT ON TABLE account_holds IS 'Balance holds projection (read-only cache derived from events)';
+COMMENT ON COLUMN account_holds.account_id IS 'Account whose funds are held (payer)';
+COMMENT ON COLUMN account_holds.to_account_id IS 'Account that receives the funds on capture (payee)';
+COMMENT ON COLUMN account_holds.status IS 'held, captured, or released';
+COMMENT ON COLUMN account_holds.transfer_id IS 'Ledger journal ID created on capture';
+
+-- ============================================================================
+-- Create account_holds indexes
+-- ============================================================================
+CREATE INDEX idx_holds_account_active ON account_holds(account_id) WHERE status = 'held';
+CREATE INDEX idx_holds_to_account ON account_holds(to_account_id);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'account_holds'
+    ) THEN
+        RAISE EXCEPTION 'account_holds table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 008 completed successfully';
+    RAISE NOTICE '  - account_holds table: OK';
+    RAISE NOTICE '  - account_holds indexes: OK';
+END $$;
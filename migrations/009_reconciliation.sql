@@ -0,0 +1,47 @@
+-- ============================================================================
+-- Migration 009: Ledger Reconciliation
+-- Phase 9: Invariant checks between events, ledger and balance projections
+-- ============================================================================
+-- Create reconciliation_reports table
+-- Create reconciliation_reports indexes
+-- ============================================================================
+
+-- ============================================================================
+-- Create reconciliation_reports table
+-- One row per run of the reconciliation job
+-- ============================================================================
+CREATE TABLE reconciliation_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    started_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    journals_checked BIGINT NOT NULL DEFAULT 0,
+    accounts_checked BIGINT NOT NULL DEFAULT 0,
+    discrepancy_count INTEGER NOT NULL DEFAULT 0,
+    discrepancies JSONB NOT NULL DEFAULT '[]'::jsonb,
+
+    CONSTRAINT non_negative_discrepancy_count CHECK (discrepancy_count >= 0)
+);
+
+COMMENT ON TABLE reconciliation_reports IS 'Results of ledger reconciliation runs';
+COMMENT ON COLUMN reconciliation_reports.discrepancies IS 'Array of {kind, subject_id, expected, actual}';
+
+-- ============================================================================
+-- Create reconciliation_reports indexes
+-- ============================================================================
+CREATE INDEX idx_reconciliation_completed ON reconciliation_reports(completed_at DESC);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'reconciliation_reports'
+    ) THEN
+        RAISE EXCEPTION 'reconciliation_reports table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 009 completed successfully';
+    RAISE NOTICE '  - reconciliation_reports table: OK';
+    RAISE NOTICE '  - reconciliation_reports indexes: OK';
+END $$;
@@ -0,0 +1,31 @@
+-- ============================================================================
+-- Migration 010: Idempotency Command Hash
+-- Phase 10: Payload validation for idempotency keys at the EventStore level
+-- ============================================================================
+-- Add idempotency_keys.command_hash column
+-- ============================================================================
+
+-- ============================================================================
+-- Add idempotency_keys.command_hash column
+-- request_hash is set by the HTTP idempotency middleware (method + path + body);
+-- command_hash is set by the EventStore from the command payload
+-- ============================================================================
+ALTER TABLE idempotency_keys ADD COLUMN command_hash VARCHAR(64);
+
+COMMENT ON COLUMN idempotency_keys.command_hash IS 'SHA-256 hash of the command payload passed to EventStore::append_atomic';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'idempotency_keys' AND column_name = 'command_hash'
+    ) THEN
+        RAISE EXCEPTION 'idempotency_keys.command_hash column was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 010 completed successfully';
+    RAISE NOTICE '  - idempotency_keys.command_hash column: OK';
+END $$;
@@ -0,0 +1,105 @@
+-- ============================================================================
+-- Migration 011: Webhooks
+-- Phase 11: Outbound delivery of domain events to registered endpoints
+-- ============================================================================
+-- Create webhook_endpoints table
+-- Create webhook_deliveries table
+-- Create webhook_delivery_attempts table
+-- Create webhook indexes
+-- ============================================================================
+
+-- ============================================================================
+-- Create webhook_endpoints table
+-- Endpoints registered by admins; an empty event_types array receives all events
+-- ============================================================================
+CREATE TABLE webhook_endpoints (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    url TEXT NOT NULL,
+    secret VARCHAR(128) NOT NULL,
+    event_types TEXT[] NOT NULL DEFAULT '{}',
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE webhook_endpoints IS 'Registered webhook receivers';
+COMMENT ON COLUMN webhook_endpoints.secret IS 'HMAC-SHA256 signing secret';
+COMMENT ON COLUMN webhook_endpoints.event_types IS 'Event types to deliver (empty = all)';
+
+-- ============================================================================
+-- Create webhook_deliveries table
+-- One row per (event, endpoint); retried with exponential backoff
+-- ============================================================================
+CREATE TABLE webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    endpoint_id UUID NOT NULL REFERENCES webhook_endpoints(id),
+    event_id UUID NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending',
+    attempt_count INTEGER NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    delivered_at TIMESTAMPTZ,
+
+    CONSTRAINT valid_delivery_status CHECK (status IN ('pending', 'delivered', 'failed')),
+    CONSTRAINT non_negative_attempt_count CHECK (attempt_count >= 0)
+);
+
+COMMENT ON TABLE webhook_deliveries IS 'Queued and completed webhook deliveries';
+COMMENT ON COLUMN webhook_deliveries.event_id IS 'Webhook event ID, shared by all deliveries of the same event';
+
+-- ============================================================================
+-- Create webhook_delivery_attempts table
+-- One row per HTTP attempt
+-- ============================================================================
+CREATE TABLE webhook_delivery_attempts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    delivery_id UUID NOT NULL REFERENCES webhook_deliveries(id),
+    attempt_number INTEGER NOT NULL,
+    response_status INTEGER,
+    error TEXT,
+    duration_ms BIGINT NOT NULL DEFAULT 0,
+    attempted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE webhook_delivery_attempts IS 'HTTP attempts made for each webhook delivery';
+
+-- ============================================================================
+-- Create webhook indexes
+-- ============================================================================
+CREATE INDEX idx_webhook_deliveries_due ON webhook_deliveries(next_attempt_at)
+    WHERE status = 'pending';
+CREATE INDEX idx_webhook_deliveries_endpoint ON webhook_deliveries(endpoint_id, created_at DESC);
+CREATE INDEX idx_webhook_attempts_delivery ON webhook_delivery_attempts(delivery_id, attempt_number);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'webhook_endpoints'
+    ) THEN
+        RAISE EXCEPTION 'webhook_endpoints table was not created';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'webhook_deliveries'
+    ) THEN
+        RAISE EXCEPTION 'webhook_deliveries table was not created';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'webhook_delivery_attempts'
+    ) THEN
+        RAISE EXCEPTION 'webhook_delivery_attempts table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 011 completed successfully';
+    RAISE NOTICE '  - webhook_endpoints table: OK';
+    RAISE NOTICE '  - webhook_deliveries table: OK';
+    RAISE NOTICE '  - webhook_delivery_attempts table: OK';
+    RAISE NOTICE '  - webhook indexes: OK';
+END $$;
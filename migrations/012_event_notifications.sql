@@ -0,0 +1,60 @@
+-- ============================================================================
+-- Migration 012: Event Notifications
+-- Phase 12: LISTEN/NOTIFY feed of newly appended events
+-- ============================================================================
+-- Create notify_event_appended() trigger function
+-- Apply notify trigger to events
+-- ============================================================================
+
+-- ============================================================================
+-- Create notify_event_appended() trigger function
+-- Publishes event metadata (not event_data, to stay under the 8000 byte
+-- NOTIFY payload limit) on the 'events' channel
+-- ============================================================================
+CREATE OR REPLACE FUNCTION notify_event_appended()
+RETURNS TRIGGER AS $$
+BEGIN
+    PERFORM pg_notify(
+        'events',
+        json_build_object(
+            'id', NEW.id,
+            'aggregate_type', NEW.aggregate_type,
+            'aggregate_id', NEW.aggregate_id,
+            'event_type', NEW.event_type,
+            'version', NEW.version,
+            'created_at', NEW.created_at
+        )::text
+    );
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ============================================================================
+-- Apply notify trigger to events
+-- Notifications are delivered when the appending transaction commits
+-- ============================================================================
+CREATE TRIGGER notify_events_appended
+    AFTER INSERT ON events
+    FOR EACH ROW EXECUTE FUNCTION notify_event_appended();
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_proc WHERE proname = 'notify_event_appended'
+    ) THEN
+        RAISE EXCEPTION 'notify_event_appended function is not created';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_trigger WHERE tgname = 'notify_events_appended'
+    ) THEN
+        RAISE EXCEPTION 'notify_events_appended trigger is not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 012 completed successfully';
+    RAISE NOTICE '  - notify_event_appended function: OK';
+    RAISE NOTICE '  - notify_events_appended trigger: OK';
+END $$;
@@ -0,0 +1,47 @@
+-- ============================================================================
+-- Migration 013: Transfer Limits
+-- Phase 13: Per-transaction and rolling 24h limits for money movements
+-- ============================================================================
+-- Create transfer_limits table
+-- ============================================================================
+
+-- ============================================================================
+-- Create transfer_limits table
+-- One row per operation; a NULL column means "no limit"
+-- ============================================================================
+CREATE TABLE transfer_limits (
+    operation VARCHAR(20) PRIMARY KEY,
+    min_amount NUMERIC(20, 8),
+    max_amount NUMERIC(20, 8),
+    daily_limit NUMERIC(20, 8),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_limit_operation CHECK (operation IN ('transfer', 'mint', 'burn')),
+    CONSTRAINT positive_min_amount CHECK (min_amount IS NULL OR min_amount > 0),
+    CONSTRAINT positive_max_amount CHECK (max_amount IS NULL OR max_amount > 0),
+    CONSTRAINT positive_daily_limit CHECK (daily_limit IS NULL OR daily_limit > 0),
+    CONSTRAINT min_not_above_max CHECK (
+        min_amount IS NULL OR max_amount IS NULL OR min_amount <= max_amount
+    )
+);
+
+COMMENT ON TABLE transfer_limits IS 'Amount limits enforced by the transfer, mint and burn handlers';
+COMMENT ON COLUMN transfer_limits.min_amount IS 'Minimum amount per transaction';
+COMMENT ON COLUMN transfer_limits.max_amount IS 'Maximum amount per transaction';
+COMMENT ON COLUMN transfer_limits.daily_limit IS 'Maximum total per user over a rolling 24 hours';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'transfer_limits'
+    ) THEN
+        RAISE EXCEPTION 'transfer_limits table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 013 completed successfully';
+    RAISE NOTICE '  - transfer_limits table: OK';
+END $$;
@@ -0,0 +1,85 @@
+-- ============================================================================
+-- Migration 014: Global Event Stream
+-- Phase 14: Monotonic event sequence and catch-up subscription checkpoints
+-- ============================================================================
+-- Add events.global_sequence
+-- Backfill global_sequence for existing events
+-- Create subscriptions table
+-- ============================================================================
+
+-- ============================================================================
+-- Add events.global_sequence
+-- Assigned on insert; EventStore serializes appends with an advisory lock so
+-- sequence order matches commit order
+-- ============================================================================
+CREATE SEQUENCE events_global_sequence_seq AS BIGINT;
+
+ALTER TABLE events ADD COLUMN global_sequence BIGINT;
+
+COMMENT ON COLUMN events.global_sequence IS 'Position in the global event stream (monotonic, gap-tolerant)';
+
+-- ============================================================================
+-- Backfill global_sequence for existing events
+-- Existing events are numbered in (created_at, id) order
+-- ============================================================================
+ALTER TABLE events DISABLE TRIGGER no_modify_events;
+
+UPDATE events e
+SET global_sequence = numbered.seq
+FROM (
+    SELECT id, created_at, ROW_NUMBER() OVER (ORDER BY created_at, id) AS seq
+    FROM events
+) numbered
+WHERE e.id = numbered.id AND e.created_at = numbered.created_at;
+
+ALTER TABLE events ENABLE TRIGGER no_modify_events;
+
+SELECT setval(
+    'events_global_sequence_seq',
+    COALESCE((SELECT MAX(global_sequence) FROM events), 0) + 1,
+    false
+);
+
+ALTER TABLE events
+    ALTER COLUMN global_sequence SET DEFAULT nextval('events_global_sequence_seq'),
+    ALTER COLUMN global_sequence SET NOT NULL;
+
+ALTER SEQUENCE events_global_sequence_seq OWNED BY events.global_sequence;
+
+CREATE INDEX idx_events_global_sequence ON events(global_sequence);
+
+-- ============================================================================
+-- Create subscriptions table
+-- Checkpoint of each internal consumer of the global stream
+-- ============================================================================
+CREATE TABLE subscriptions (
+    name VARCHAR(100) PRIMARY KEY,
+    last_sequence BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT non_negative_last_sequence CHECK (last_sequence >= 0)
+);
+
+COMMENT ON TABLE subscriptions IS 'Catch-up subscription checkpoints';
+COMMENT ON COLUMN subscriptions.last_sequence IS 'global_sequence of the last processed event';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF EXISTS (SELECT 1 FROM events WHERE global_sequence IS NULL) THEN
+        RAISE EXCEPTION 'events.global_sequence was not backfilled';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'subscriptions'
+    ) THEN
+        RAISE EXCEPTION 'subscriptions table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 014 completed successfully';
+    RAISE NOTICE '  - events.global_sequence: OK';
+    RAISE NOTICE '  - subscriptions table: OK';
+END $$;
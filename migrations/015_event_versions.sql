@@ -0,0 +1,88 @@
+-- ============================================================================
+-- Migration 015: Event Version Constraint
+-- Phase 15: Database-enforced optimistic concurrency for event appends
+-- ============================================================================
+-- Create event_versions table
+-- Backfill event_versions from events
+-- Claim versions on insert into events
+-- ============================================================================
+
+-- ============================================================================
+-- Create event_versions table
+-- events is partitioned by created_at, so UNIQUE (aggregate_id, version)
+-- cannot be declared on it directly. Every event claims its version here
+-- instead; a second writer for the same version fails with a unique
+-- violation, and a version that skips ahead fails the previous-version
+-- foreign key.
+-- ============================================================================
+CREATE TABLE event_versions (
+    aggregate_id UUID NOT NULL,
+    version BIGINT NOT NULL,
+    previous_version BIGINT GENERATED ALWAYS AS (NULLIF(version - 1, 0)) STORED,
+
+    CONSTRAINT event_versions_pkey PRIMARY KEY (aggregate_id, version),
+    CONSTRAINT positive_event_version CHECK (version > 0)
+);
+
+COMMENT ON TABLE event_versions IS 'One row per appended event; enforces unique, contiguous aggregate versions';
+COMMENT ON COLUMN event_versions.previous_version IS 'Version that must already exist (NULL for the first event)';
+
+-- ============================================================================
+-- Backfill event_versions from events
+-- ============================================================================
+INSERT INTO event_versions (aggregate_id, version)
+SELECT DISTINCT aggregate_id, version
+FROM events;
+
+-- Existing streams are not re-checked for gaps; new appends are
+ALTER TABLE event_versions
+    ADD CONSTRAINT event_versions_previous_fkey
+    FOREIGN KEY (aggregate_id, previous_version)
+    REFERENCES event_versions (aggregate_id, version)
+    NOT VALID;
+
+-- ============================================================================
+-- Claim versions on insert into events
+-- Runs for every writer, including seeds and load tests
+-- ============================================================================
+CREATE OR REPLACE FUNCTION claim_event_version()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO event_versions (aggregate_id, version)
+    VALUES (NEW.aggregate_id, NEW.version);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER claim_event_version
+    BEFORE INSERT ON events
+    FOR EACH ROW
+    EXECUTE FUNCTION claim_event_version();
+
+COMMENT ON FUNCTION claim_event_version() IS 'Claims (aggregate_id, version) in event_versions before an event is stored';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+DECLARE
+    duplicate_versions BIGINT;
+BEGIN
+    SELECT COUNT(*) - (SELECT COUNT(*) FROM event_versions)
+    INTO duplicate_versions
+    FROM events;
+
+    IF duplicate_versions > 0 THEN
+        RAISE WARNING 'events contains % duplicate (aggregate_id, version) pairs', duplicate_versions;
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.triggers WHERE trigger_name = 'claim_event_version'
+    ) THEN
+        RAISE EXCEPTION 'claim_event_version trigger was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 015 completed successfully';
+    RAISE NOTICE '  - event_versions table: OK';
+    RAISE NOTICE '  - claim_event_version trigger: OK';
+END $$;
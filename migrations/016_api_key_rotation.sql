@@ -0,0 +1,46 @@
+-- ============================================================================
+-- Migration 016: API Key Rotation
+-- Phase 16: Secret rotation with a grace period for the previous secret
+-- ============================================================================
+-- Add previous secret columns to api_keys
+-- ============================================================================
+
+-- ============================================================================
+-- Add previous secret columns to api_keys
+-- After a rotation the old secret keeps authenticating the same key until
+-- previous_key_expires_at, so clients can roll over without downtime
+-- ============================================================================
+ALTER TABLE api_keys
+    ADD COLUMN previous_key_hash VARCHAR(64),
+    ADD COLUMN previous_key_expires_at TIMESTAMPTZ,
+    ADD COLUMN rotated_at TIMESTAMPTZ;
+
+ALTER TABLE api_keys
+    ADD CONSTRAINT previous_key_has_expiry CHECK (
+        previous_key_hash IS NULL OR previous_key_expires_at IS NOT NULL
+    );
+
+CREATE INDEX idx_api_keys_key_hash ON api_keys(key_hash);
+CREATE INDEX idx_api_keys_previous_key_hash ON api_keys(previous_key_hash)
+    WHERE previous_key_hash IS NOT NULL;
+
+COMMENT ON COLUMN api_keys.expires_at IS 'Key is rejected after this time (NULL = never expires)';
+COMMENT ON COLUMN api_keys.previous_key_hash IS 'SHA-256 hash of the secret replaced by the last rotation';
+COMMENT ON COLUMN api_keys.previous_key_expires_at IS 'End of the grace period for the previous secret';
+COMMENT ON COLUMN api_keys.rotated_at IS 'Time of the last secret rotation';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'api_keys' AND column_name = 'previous_key_hash'
+    ) THEN
+        RAISE EXCEPTION 'api_keys.previous_key_hash was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 016 completed successfully';
+    RAISE NOTICE '  - api_keys rotation columns: OK';
+END $$;
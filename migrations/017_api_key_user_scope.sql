@@ -0,0 +1,37 @@
+-- ============================================================================
+-- Migration 017: API Key User Scope
+-- Phase 17: Restrict partner API keys to acting for specific users
+-- ============================================================================
+-- Add api_keys.allowed_user_ids
+-- ============================================================================
+
+-- ============================================================================
+-- Add api_keys.allowed_user_ids
+-- NULL = the key may act for any user; otherwise X-Request-User-Id must be
+-- one of the listed users
+-- ============================================================================
+ALTER TABLE api_keys
+    ADD COLUMN allowed_user_ids UUID[];
+
+ALTER TABLE api_keys
+    ADD CONSTRAINT non_empty_allowed_user_ids CHECK (
+        allowed_user_ids IS NULL OR cardinality(allowed_user_ids) > 0
+    );
+
+COMMENT ON COLUMN api_keys.allowed_user_ids IS 'Users the key may act for via X-Request-User-Id (NULL = any user)';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'api_keys' AND column_name = 'allowed_user_ids'
+    ) THEN
+        RAISE EXCEPTION 'api_keys.allowed_user_ids was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 017 completed successfully';
+    RAISE NOTICE '  - api_keys.allowed_user_ids: OK';
+END $$;
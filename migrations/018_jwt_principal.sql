@@ -0,0 +1,40 @@
+-- ============================================================================
+-- Migration 018: JWT Principal
+-- Phase 18: Bearer token authentication alongside API keys
+-- ============================================================================
+-- Seed the api_keys row that bearer-token requests are attributed to
+-- ============================================================================
+
+-- ============================================================================
+-- Seed the JWT principal
+-- Requests authenticated with `Authorization: Bearer <JWT>` carry this id in
+-- their operation context, so rate limits, idempotency keys and audit logs
+-- work as for API keys. Permissions come from the token claims, not from
+-- this row. The key hash is random so no X-API-Key can match it; setting
+-- is_active = FALSE disables bearer authentication entirely.
+-- ============================================================================
+INSERT INTO api_keys (id, name, key_prefix, key_hash, permissions, rate_limit_per_minute)
+VALUES (
+    'a0000000-0000-0000-0000-000000000002',
+    'JWT Bearer Tokens',
+    'jwt',
+    md5(random()::text) || md5(random()::text),
+    '{}',
+    10000
+)
+ON CONFLICT (id) DO NOTHING;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM api_keys WHERE id = 'a0000000-0000-0000-0000-000000000002'
+    ) THEN
+        RAISE EXCEPTION 'JWT principal api_keys row was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 018 completed successfully';
+    RAISE NOTICE '  - JWT principal: OK';
+END $$;
@@ -0,0 +1,42 @@
+-- ============================================================================
+-- Migration 019: User Anonymization
+-- Phase 19: Retention of deactivated users and GDPR-style anonymization
+-- ============================================================================
+-- Track when users were deactivated and anonymized so the retention job can
+-- scrub personal data from the users projection after N days
+-- ============================================================================
+
+-- ============================================================================
+-- Columns
+-- ============================================================================
+ALTER TABLE users ADD COLUMN deactivated_at TIMESTAMPTZ;
+ALTER TABLE users ADD COLUMN anonymized_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN users.deactivated_at IS 'When the user was last deactivated (NULL while active)';
+COMMENT ON COLUMN users.anonymized_at IS 'When email/display_name were scrubbed (UserAnonymized event)';
+
+-- Users deactivated before this migration: best available timestamp
+UPDATE users
+SET deactivated_at = updated_at
+WHERE is_active = FALSE AND deactivated_at IS NULL;
+
+-- Retention job lookup: deactivated, not yet anonymized
+CREATE INDEX idx_users_pending_anonymization ON users(deactivated_at)
+    WHERE is_active = FALSE AND anonymized_at IS NULL AND is_system = FALSE;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'users' AND column_name = 'anonymized_at'
+    ) THEN
+        RAISE EXCEPTION 'users.anonymized_at column was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 019 completed successfully';
+    RAISE NOTICE '  - users.deactivated_at: OK';
+    RAISE NOTICE '  - users.anonymized_at: OK';
+END $$;
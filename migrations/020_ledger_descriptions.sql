@@ -0,0 +1,54 @@
+-- ============================================================================
+-- Migration 020: Ledger Descriptions
+-- Phase 20: Transfer memo search
+-- ============================================================================
+-- Store the event description (transfer memo, mint/burn reason) on each
+-- ledger entry and index it for full-text search
+-- ============================================================================
+
+-- ============================================================================
+-- Columns
+-- ============================================================================
+ALTER TABLE ledger_entries ADD COLUMN description TEXT;
+
+-- 'simple' configuration: no stemming or stop words, memos are free text
+-- in mixed languages
+ALTER TABLE ledger_entries ADD COLUMN description_search TSVECTOR
+    GENERATED ALWAYS AS (to_tsvector('simple', COALESCE(description, ''))) STORED;
+
+COMMENT ON COLUMN ledger_entries.description IS 'Description of the source event (memo for transfers)';
+COMMENT ON COLUMN ledger_entries.description_search IS 'Full-text search vector over description';
+
+-- ============================================================================
+-- Backfill from the debit/credit events that produced each entry
+-- ============================================================================
+UPDATE ledger_entries le
+SET description = e.event_data->>'description'
+FROM events e
+WHERE e.aggregate_type = 'Account'
+  AND e.aggregate_id = le.account_id
+  AND e.event_type = CASE le.entry_type WHEN 'debit' THEN 'MoneyDebited' ELSE 'MoneyCredited' END
+  AND e.event_data->>'transfer_id' = le.journal_id::text
+  AND le.description IS NULL;
+
+-- ============================================================================
+-- Index
+-- ============================================================================
+CREATE INDEX idx_ledger_description_search ON ledger_entries USING GIN (description_search);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_indexes
+        WHERE tablename = 'ledger_entries' AND indexname = 'idx_ledger_description_search'
+    ) THEN
+        RAISE EXCEPTION 'idx_ledger_description_search was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 020 completed successfully';
+    RAISE NOTICE '  - ledger_entries.description: OK';
+    RAISE NOTICE '  - idx_ledger_description_search: OK';
+END $$;
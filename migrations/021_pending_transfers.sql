@@ -0,0 +1,56 @@
+-- ============================================================================
+-- Migration 021: Pending Transfers
+-- Phase 21: Maker-checker approval for large transfers
+-- ============================================================================
+-- Transfers above TRANSFER_APPROVAL_THRESHOLD are held until a second API key
+-- approves or rejects them
+-- ============================================================================
+
+-- ============================================================================
+-- Create pending_transfers table
+-- Projection of TransferApprovalRequested / TransferApproved / TransferRejected
+-- ============================================================================
+CREATE TABLE pending_transfers (
+    transfer_id UUID PRIMARY KEY,
+    from_user_id UUID NOT NULL REFERENCES users(id),
+    to_user_id UUID NOT NULL REFERENCES users(id),
+    amount NUMERIC(20, 8) NOT NULL,
+    memo TEXT,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending_approval',
+    requested_by_api_key_id UUID,
+    decided_by_api_key_id UUID,
+    decision_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    decided_at TIMESTAMPTZ,
+
+    CONSTRAINT pending_transfer_positive_amount CHECK (amount > 0),
+    CONSTRAINT valid_pending_transfer_status
+        CHECK (status IN ('pending_approval', 'approved', 'rejected', 'failed'))
+);
+
+COMMENT ON TABLE pending_transfers IS 'Transfers awaiting or past maker-checker approval (read-only cache derived from events)';
+COMMENT ON COLUMN pending_transfers.status IS 'pending_approval, approved (executed), rejected, or failed (approved but not executable)';
+COMMENT ON COLUMN pending_transfers.requested_by_api_key_id IS 'API key that initiated the transfer (maker)';
+COMMENT ON COLUMN pending_transfers.decided_by_api_key_id IS 'API key that approved or rejected the transfer (checker)';
+
+-- ============================================================================
+-- Create pending_transfers indexes
+-- ============================================================================
+CREATE INDEX idx_pending_transfers_open ON pending_transfers(created_at)
+    WHERE status = 'pending_approval';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'pending_transfers'
+    ) THEN
+        RAISE EXCEPTION 'pending_transfers table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 021 completed successfully';
+    RAISE NOTICE '  - pending_transfers table: OK';
+    RAISE NOTICE '  - idx_pending_transfers_open: OK';
+END $$;
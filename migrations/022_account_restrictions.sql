@@ -0,0 +1,43 @@
+-- ============================================================================
+-- Migration 022: Account Restrictions
+-- Phase 22: Receive-only, send-only and allowlisted accounts
+-- ============================================================================
+-- Create account_restrictions table
+-- ============================================================================
+
+-- ============================================================================
+-- Create account_restrictions table
+-- At most one restriction per account; enforced by the transfer handler
+-- ============================================================================
+CREATE TABLE account_restrictions (
+    account_id UUID PRIMARY KEY REFERENCES accounts(id),
+    mode VARCHAR(20) NOT NULL,
+    allowed_counterparties UUID[] NOT NULL DEFAULT '{}',
+    reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_restriction_mode CHECK (mode IN ('receive_only', 'send_only', 'allowlist')),
+    CONSTRAINT allowlist_has_counterparties CHECK (
+        mode <> 'allowlist' OR cardinality(allowed_counterparties) > 0
+    )
+);
+
+COMMENT ON TABLE account_restrictions IS 'Per-account transfer restrictions enforced by the transfer handler';
+COMMENT ON COLUMN account_restrictions.mode IS 'receive_only, send_only, or allowlist';
+COMMENT ON COLUMN account_restrictions.allowed_counterparties IS 'Accounts this account may transfer with (allowlist mode)';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'account_restrictions'
+    ) THEN
+        RAISE EXCEPTION 'account_restrictions table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 022 completed successfully';
+    RAISE NOTICE '  - account_restrictions table: OK';
+END $$;
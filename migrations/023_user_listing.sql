@@ -0,0 +1,27 @@
+-- ============================================================================
+-- Migration 023: User Listing
+-- Phase 23: Keyset pagination for GET /users
+-- ============================================================================
+-- GET /users pages on (created_at, id) or (username, id); the username side
+-- is served by the UNIQUE constraint on users.username
+-- ============================================================================
+
+-- ============================================================================
+-- Indexes
+-- ============================================================================
+CREATE INDEX idx_users_created_at ON users(created_at, id);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_indexes WHERE indexname = 'idx_users_created_at'
+    ) THEN
+        RAISE EXCEPTION 'idx_users_created_at index was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 023 completed successfully';
+    RAISE NOTICE '  - idx_users_created_at: OK';
+END $$;
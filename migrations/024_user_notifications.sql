@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration 024: User Notifications
+-- Phase 24: Per-user activity feed
+-- ============================================================================
+-- Balance changes and freezes of user wallets, projected from Account events
+-- by the user_notifications subscription (catch-up consumer, migration 014)
+-- ============================================================================
+
+-- ============================================================================
+-- Create user_notifications table
+-- ============================================================================
+CREATE TABLE user_notifications (
+    event_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id),
+    account_id UUID NOT NULL REFERENCES accounts(id),
+    kind VARCHAR(30) NOT NULL,
+    amount NUMERIC(20, 8),
+    transfer_id UUID,
+    description TEXT,
+    created_at TIMESTAMPTZ NOT NULL,
+
+    CONSTRAINT valid_notification_kind
+        CHECK (kind IN ('credit_received', 'debit_executed', 'account_frozen'))
+);
+
+COMMENT ON TABLE user_notifications IS 'Per-user activity feed (read-only cache derived from events)';
+COMMENT ON COLUMN user_notifications.event_id IS 'Source event (makes re-delivery idempotent)';
+COMMENT ON COLUMN user_notifications.created_at IS 'Time of the source event';
+
+-- ============================================================================
+-- Create user_notifications indexes
+-- ============================================================================
+CREATE INDEX idx_user_notifications_feed ON user_notifications(user_id, created_at DESC);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'user_notifications'
+    ) THEN
+        RAISE EXCEPTION 'user_notifications table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 024 completed successfully';
+    RAISE NOTICE '  - user_notifications table: OK';
+    RAISE NOTICE '  - idx_user_notifications_feed: OK';
+END $$;
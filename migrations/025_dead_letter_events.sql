@@ -0,0 +1,50 @@
+-- ============================================================================
+-- Migration 025: Dead-letter Events
+-- Phase 25: Poison event handling during aggregate replay
+-- ============================================================================
+-- With EVENT_POISON_POLICY=skip, events whose data no longer deserializes are
+-- skipped during replay and recorded here until an admin re-processes them
+-- ============================================================================
+
+-- ============================================================================
+-- Create dead_letter_events table
+-- ============================================================================
+CREATE TABLE dead_letter_events (
+    event_id UUID PRIMARY KEY,
+    aggregate_type VARCHAR(50) NOT NULL,
+    aggregate_id UUID NOT NULL,
+    version BIGINT NOT NULL,
+    event_type VARCHAR(100) NOT NULL,
+    error TEXT NOT NULL,
+    failure_count INTEGER NOT NULL DEFAULT 1,
+    first_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ
+);
+
+COMMENT ON TABLE dead_letter_events IS 'Events skipped during replay because their data failed to deserialize';
+COMMENT ON COLUMN dead_letter_events.error IS 'Deserialization error of the most recent failure';
+COMMENT ON COLUMN dead_letter_events.resolved_at IS 'When the event was re-processed successfully (NULL while unresolved)';
+
+-- ============================================================================
+-- Create dead_letter_events indexes
+-- ============================================================================
+-- Snapshot guard: aggregates with unresolved dead letters are not snapshotted
+CREATE INDEX idx_dead_letter_events_unresolved ON dead_letter_events(aggregate_id)
+    WHERE resolved_at IS NULL;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'dead_letter_events'
+    ) THEN
+        RAISE EXCEPTION 'dead_letter_events table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 025 completed successfully';
+    RAISE NOTICE '  - dead_letter_events table: OK';
+    RAISE NOTICE '  - idx_dead_letter_events_unresolved: OK';
+END $$;
@@ -0,0 +1,43 @@
+-- ============================================================================
+-- Migration 026: API Key Caps
+-- Phase 26: Per-key mint/burn caps
+-- ============================================================================
+-- Limits how much a single admin key can mint or burn, per command and over
+-- a rolling 24 hours, on top of the global transfer_limits. NULL = no cap.
+-- ============================================================================
+
+-- ============================================================================
+-- Add cap columns to api_keys
+-- ============================================================================
+ALTER TABLE api_keys
+    ADD COLUMN mint_max_amount NUMERIC(20, 8),
+    ADD COLUMN mint_daily_limit NUMERIC(20, 8),
+    ADD COLUMN burn_max_amount NUMERIC(20, 8),
+    ADD COLUMN burn_daily_limit NUMERIC(20, 8),
+    ADD CONSTRAINT positive_key_caps CHECK (
+        (mint_max_amount IS NULL OR mint_max_amount > 0) AND
+        (mint_daily_limit IS NULL OR mint_daily_limit > 0) AND
+        (burn_max_amount IS NULL OR burn_max_amount > 0) AND
+        (burn_daily_limit IS NULL OR burn_daily_limit > 0)
+    );
+
+COMMENT ON COLUMN api_keys.mint_max_amount IS 'Maximum amount of a single mint by this key';
+COMMENT ON COLUMN api_keys.mint_daily_limit IS 'Maximum total minted by this key over a rolling 24 hours';
+COMMENT ON COLUMN api_keys.burn_max_amount IS 'Maximum amount of a single burn by this key';
+COMMENT ON COLUMN api_keys.burn_daily_limit IS 'Maximum total burned by this key over a rolling 24 hours';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'api_keys' AND column_name = 'burn_daily_limit'
+    ) THEN
+        RAISE EXCEPTION 'api_keys cap columns were not added';
+    END IF;
+
+    RAISE NOTICE 'Migration 026 completed successfully';
+    RAISE NOTICE '  - api_keys cap columns: OK';
+END $$;
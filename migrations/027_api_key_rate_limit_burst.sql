@@ -0,0 +1,37 @@
+-- ============================================================================
+-- Migration 027: API Key Rate Limit Burst
+-- Phase 27: Per-key burst allowance and rate limit exemption
+-- ============================================================================
+-- rate_limit_burst is the number of requests a key may make on top of
+-- rate_limit_per_minute within one window (NULL = RATE_LIMIT_BURST).
+-- Exempt keys (internal trusted services) are neither counted nor limited.
+-- ============================================================================
+
+-- ============================================================================
+-- Add burst/exempt columns to api_keys
+-- ============================================================================
+ALTER TABLE api_keys
+    ADD COLUMN rate_limit_burst INTEGER,
+    ADD COLUMN rate_limit_exempt BOOLEAN NOT NULL DEFAULT false,
+    ADD CONSTRAINT non_negative_rate_limit_burst CHECK (
+        rate_limit_burst IS NULL OR rate_limit_burst >= 0
+    );
+
+COMMENT ON COLUMN api_keys.rate_limit_burst IS 'Requests allowed above rate_limit_per_minute per window (NULL = global default)';
+COMMENT ON COLUMN api_keys.rate_limit_exempt IS 'Skip rate limiting for this key (internal trusted services)';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'api_keys' AND column_name = 'rate_limit_exempt'
+    ) THEN
+        RAISE EXCEPTION 'api_keys rate limit burst columns were not added';
+    END IF;
+
+    RAISE NOTICE 'Migration 027 completed successfully';
+    RAISE NOTICE '  - api_keys rate limit burst columns: OK';
+END $$;
@@ -0,0 +1,38 @@
+-- ============================================================================
+-- Migration 028: Transfer Categories
+-- Phase 28: Category reporting
+-- ============================================================================
+-- Store the category of a transfer (from the TRANSFER_CATEGORIES taxonomy)
+-- on both of its ledger entries, for per-category volume reports.
+-- Entries written before this migration stay uncategorized.
+-- ============================================================================
+
+-- ============================================================================
+-- Columns
+-- ============================================================================
+ALTER TABLE ledger_entries ADD COLUMN category VARCHAR(50);
+
+COMMENT ON COLUMN ledger_entries.category IS 'Transfer category (NULL = uncategorized)';
+
+-- ============================================================================
+-- Index
+-- ============================================================================
+CREATE INDEX idx_ledger_category ON ledger_entries(category, created_at)
+    WHERE category IS NOT NULL;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_indexes
+        WHERE tablename = 'ledger_entries' AND indexname = 'idx_ledger_category'
+    ) THEN
+        RAISE EXCEPTION 'idx_ledger_category was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 028 completed successfully';
+    RAISE NOTICE '  - ledger_entries.category: OK';
+    RAISE NOTICE '  - idx_ledger_category: OK';
+END $$;
@@ -0,0 +1,45 @@
+-- ============================================================================
+-- Migration 029: Daily Statistics
+-- Phase 29: Materialized dashboard stats
+-- ============================================================================
+-- One row per completed UTC day, written by the daily stats job from the
+-- ledger and users tables so dashboards do not aggregate over events.
+-- ============================================================================
+
+-- ============================================================================
+-- stats_daily table
+-- ============================================================================
+CREATE TABLE stats_daily (
+    day DATE PRIMARY KEY,
+    transfer_count BIGINT NOT NULL DEFAULT 0,
+    transfer_volume NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    mint_total NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    burn_total NUMERIC(20, 8) NOT NULL DEFAULT 0,
+    active_users BIGINT NOT NULL DEFAULT 0,
+    new_users BIGINT NOT NULL DEFAULT 0,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE stats_daily IS 'Daily aggregate statistics (UTC days)';
+COMMENT ON COLUMN stats_daily.transfer_count IS 'Journals between two non-system users';
+COMMENT ON COLUMN stats_daily.transfer_volume IS 'Amount moved by those journals';
+COMMENT ON COLUMN stats_daily.mint_total IS 'Amount debited from SYSTEM_MINT';
+COMMENT ON COLUMN stats_daily.burn_total IS 'Amount credited to SYSTEM_BURN';
+COMMENT ON COLUMN stats_daily.active_users IS 'Distinct non-system users with a ledger entry that day';
+COMMENT ON COLUMN stats_daily.new_users IS 'Non-system users created that day';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables
+        WHERE table_name = 'stats_daily'
+    ) THEN
+        RAISE EXCEPTION 'stats_daily table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 029 completed successfully';
+    RAISE NOTICE '  - stats_daily: OK';
+END $$;
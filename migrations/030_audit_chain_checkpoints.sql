@@ -0,0 +1,51 @@
+-- ============================================================================
+-- Migration 030: Audit Chain Checkpoints
+-- Phase 30: Incremental audit log verification and anchoring
+-- ============================================================================
+-- Each run of the audit verification job records how far the hash chain was
+-- verified, so the next run resumes after the last valid checkpoint instead
+-- of rehashing the whole log. When AUDIT_ANCHOR is set, the checkpoint's
+-- chain head is also published outside the database.
+-- ============================================================================
+
+-- ============================================================================
+-- audit_chain_checkpoints table
+-- ============================================================================
+CREATE TABLE audit_chain_checkpoints (
+    id BIGSERIAL PRIMARY KEY,
+    sequence_number BIGINT NOT NULL,
+    chain_head_hash VARCHAR(64) NOT NULL,
+    entries_verified BIGINT NOT NULL DEFAULT 0,
+    is_valid BOOLEAN NOT NULL,
+    first_invalid_entry UUID,
+    anchor_target TEXT,
+    anchored_at TIMESTAMPTZ,
+    anchor_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_checkpoints_valid ON audit_chain_checkpoints(sequence_number DESC) WHERE is_valid;
+
+COMMENT ON TABLE audit_chain_checkpoints IS 'Progress of incremental audit log hash chain verification';
+COMMENT ON COLUMN audit_chain_checkpoints.sequence_number IS 'Last audit_logs entry verified (0 before the first entry)';
+COMMENT ON COLUMN audit_chain_checkpoints.chain_head_hash IS 'current_hash of that entry';
+COMMENT ON COLUMN audit_chain_checkpoints.entries_verified IS 'Entries verified by this run';
+COMMENT ON COLUMN audit_chain_checkpoints.first_invalid_entry IS 'Entry that failed verification (is_valid = false)';
+COMMENT ON COLUMN audit_chain_checkpoints.anchor_target IS 'AUDIT_ANCHOR target the chain head was published to';
+COMMENT ON COLUMN audit_chain_checkpoints.anchor_error IS 'Error of a failed publication';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables
+        WHERE table_name = 'audit_chain_checkpoints'
+    ) THEN
+        RAISE EXCEPTION 'audit_chain_checkpoints table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 030 completed successfully';
+    RAISE NOTICE '  - audit_chain_checkpoints: OK';
+END $$;
@@ -0,0 +1,188 @@
+-- ============================================================================
+-- Migration 031: Audit Log Archives
+-- Phase 31: Audit log retention with verified archival
+-- ============================================================================
+-- The audit archival job exports old audit_logs entries to compressed NDJSON
+-- files after verifying their hash chain segment, records each file here and
+-- deletes the archived rows. The last hash of the newest archive anchors the
+-- remaining chain:
+--   - calculate_audit_hash() links the next entry to it when audit_logs is empty
+--   - verify_audit_chain() starts from it instead of the zero hash
+-- Rows may only be deleted by the archival transaction (it sets
+-- finance_atp.audit_archival) and only once they are covered by an archive.
+-- ============================================================================
+
+-- ============================================================================
+-- audit_archives table
+-- ============================================================================
+CREATE TABLE audit_archives (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    first_sequence BIGINT NOT NULL,
+    last_sequence BIGINT NOT NULL,
+    entry_count BIGINT NOT NULL,
+    anchor_hash VARCHAR(64) NOT NULL,
+    last_hash VARCHAR(64) NOT NULL,
+    archive_path TEXT NOT NULL,
+    archive_sha256 VARCHAR(64) NOT NULL,
+    archived_before TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT audit_archive_range CHECK (first_sequence <= last_sequence),
+    CONSTRAINT unique_audit_archive_last_sequence UNIQUE (last_sequence)
+);
+
+COMMENT ON TABLE audit_archives IS 'Verified audit_logs segments exported to NDJSON and deleted';
+COMMENT ON COLUMN audit_archives.anchor_hash IS 'previous_hash of the first archived entry';
+COMMENT ON COLUMN audit_archives.last_hash IS 'current_hash of the last archived entry (chain anchor for later entries)';
+COMMENT ON COLUMN audit_archives.archive_path IS 'Gzip-compressed NDJSON file of the archived entries';
+COMMENT ON COLUMN audit_archives.archive_sha256 IS 'SHA-256 of the archive file';
+COMMENT ON COLUMN audit_archives.archived_before IS 'Retention cutoff of the archival run';
+
+-- ============================================================================
+-- Chain anchor: hash the next entry links to
+-- ============================================================================
+CREATE OR REPLACE FUNCTION audit_chain_anchor()
+RETURNS VARCHAR(64) AS $$
+    SELECT COALESCE(
+        (SELECT last_hash FROM audit_archives ORDER BY last_sequence DESC LIMIT 1),
+        '0000000000000000000000000000000000000000000000000000000000000000'
+    );
+$$ LANGUAGE sql STABLE;
+
+COMMENT ON FUNCTION audit_chain_anchor IS
+    'Hash preceding the oldest remaining audit_logs entry (zero hash before any archival)';
+
+CREATE OR REPLACE FUNCTION calculate_audit_hash()
+RETURNS TRIGGER AS $$
+DECLARE
+    v_prev_hash VARCHAR(64);
+    v_hash_input TEXT;
+BEGIN
+    -- Exclusive lock to prevent race condition in hash chain
+    PERFORM pg_advisory_xact_lock(hashtext('audit_logs_chain'));
+
+    -- Get previous hash (by sequence number)
+    SELECT current_hash INTO v_prev_hash
+    FROM audit_logs
+    ORDER BY sequence_number DESC
+    LIMIT 1;
+
+    -- Continue from the archives (or the zero hash) when no entry is left
+    NEW.previous_hash := COALESCE(v_prev_hash, audit_chain_anchor());
+
+    -- Build hash input string
+    v_hash_input := NEW.id::text ||
+                    NEW.sequence_number::text ||
+                    NEW.action ||
+                    COALESCE(NEW.request_user_id::text, '') ||
+                    COALESCE(NEW.resource_type, '') ||
+                    COALESCE(NEW.resource_id::text, '') ||
+                    COALESCE(NEW.before_state::text, '') ||
+                    COALESCE(NEW.after_state::text, '') ||
+                    NEW.previous_hash;
+
+    -- Calculate SHA-256 hash
+    NEW.current_hash := encode(sha256(v_hash_input::bytea), 'hex');
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE FUNCTION verify_audit_chain()
+RETURNS TABLE(
+    id UUID,
+    sequence_number BIGINT,
+    is_valid BOOLEAN,
+    expected_hash VARCHAR(64),
+    actual_hash VARCHAR(64)
+) AS $$
+DECLARE
+    v_prev_hash VARCHAR(64) := audit_chain_anchor();
+    v_record RECORD;
+    v_expected_hash VARCHAR(64);
+    v_hash_input TEXT;
+BEGIN
+    FOR v_record IN
+        SELECT * FROM audit_logs ORDER BY audit_logs.sequence_number ASC
+    LOOP
+        -- Build hash input
+        v_hash_input := v_record.id::text ||
+                        v_record.sequence_number::text ||
+                        v_record.action ||
+                        COALESCE(v_record.request_user_id::text, '') ||
+                        COALESCE(v_record.resource_type, '') ||
+                        COALESCE(v_record.resource_id::text, '') ||
+                        COALESCE(v_record.before_state::text, '') ||
+                        COALESCE(v_record.after_state::text, '') ||
+                        v_prev_hash;
+
+        v_expected_hash := encode(sha256(v_hash_input::bytea), 'hex');
+
+        id := v_record.id;
+        sequence_number := v_record.sequence_number;
+        is_valid := (v_expected_hash = v_record.current_hash AND v_prev_hash = v_record.previous_hash);
+        expected_hash := v_expected_hash;
+        actual_hash := v_record.current_hash;
+
+        RETURN NEXT;
+
+        v_prev_hash := v_record.current_hash;
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+-- ============================================================================
+-- Immutability: allow archival deletes only
+-- ============================================================================
+CREATE OR REPLACE FUNCTION prevent_audit_modification()
+RETURNS TRIGGER AS $$
+BEGIN
+    IF TG_OP = 'DELETE'
+       AND current_setting('finance_atp.audit_archival', true) = 'on'
+       AND OLD.sequence_number <= (SELECT MAX(last_sequence) FROM audit_archives) THEN
+        RETURN OLD;
+    END IF;
+
+    IF TG_OP = 'DELETE' THEN
+        RAISE EXCEPTION 'DELETE is not allowed on % table. This table is immutable.', TG_TABLE_NAME
+            USING ERRCODE = 'restrict_violation';
+    ELSIF TG_OP = 'UPDATE' THEN
+        RAISE EXCEPTION 'UPDATE is not allowed on % table. This table is immutable.', TG_TABLE_NAME
+            USING ERRCODE = 'restrict_violation';
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+COMMENT ON FUNCTION prevent_audit_modification IS
+    'Keeps audit_logs immutable except for deleting entries covered by audit_archives during archival';
+
+DROP TRIGGER no_modify_audit ON audit_logs;
+CREATE TRIGGER no_modify_audit
+    BEFORE UPDATE OR DELETE ON audit_logs
+    FOR EACH ROW EXECUTE FUNCTION prevent_audit_modification();
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables
+        WHERE table_name = 'audit_archives'
+    ) THEN
+        RAISE EXCEPTION 'audit_archives table was not created';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.triggers
+        WHERE trigger_name = 'no_modify_audit'
+    ) THEN
+        RAISE EXCEPTION 'no_modify_audit trigger was not recreated';
+    END IF;
+
+    RAISE NOTICE 'Migration 031 completed successfully';
+    RAISE NOTICE '  - audit_archives: OK';
+    RAISE NOTICE '  - calculate_audit_hash / verify_audit_chain anchored: OK';
+    RAISE NOTICE '  - no_modify_audit (archival deletes only): OK';
+END $$;
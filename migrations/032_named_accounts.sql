@@ -0,0 +1,82 @@
+-- ============================================================================
+-- Migration 032: Named Accounts
+-- Phase 32: Additional named user_wallet accounts per user (sub-wallets)
+-- ============================================================================
+-- Every user keeps exactly one primary account per type (name IS NULL): the
+-- wallet created with the user, which user_id-based lookups resolve to.
+-- Users may open further user_wallet accounts with a name unique per user
+-- (e.g. "savings", "escrow"); transfers reach them by account ID.
+-- ============================================================================
+
+-- ============================================================================
+-- accounts.name
+-- ============================================================================
+ALTER TABLE accounts ADD COLUMN name VARCHAR(50);
+
+ALTER TABLE accounts ADD CONSTRAINT account_name_format
+    CHECK (name IS NULL OR name ~ '^[a-z0-9][a-z0-9_-]{0,49}$');
+
+COMMENT ON COLUMN accounts.name IS 'Name of an additional account (NULL for the primary account of its type)';
+
+-- One primary account per type per user, named accounts unique per user
+ALTER TABLE accounts DROP CONSTRAINT accounts_user_id_account_type_key;
+CREATE UNIQUE INDEX idx_accounts_primary ON accounts(user_id, account_type) WHERE name IS NULL;
+CREATE UNIQUE INDEX idx_accounts_named ON accounts(user_id, name) WHERE name IS NOT NULL;
+
+-- ============================================================================
+-- Primary wallet lookups
+-- ============================================================================
+CREATE OR REPLACE FUNCTION get_wallet_account_id(p_user_id UUID)
+RETURNS UUID AS $$
+DECLARE
+    v_account_id UUID;
+BEGIN
+    SELECT id INTO v_account_id
+    FROM accounts
+    WHERE user_id = p_user_id AND account_type = 'user_wallet' AND name IS NULL;
+
+    IF v_account_id IS NULL THEN
+        RAISE EXCEPTION 'Wallet account not found for user %', p_user_id
+            USING ERRCODE = 'no_data_found';
+    END IF;
+
+    RETURN v_account_id;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE OR REPLACE VIEW user_balances AS
+SELECT
+    u.id as user_id,
+    u.username,
+    u.display_name,
+    ab.balance,
+    ab.updated_at
+FROM users u
+JOIN accounts a ON u.id = a.user_id AND a.account_type = 'user_wallet' AND a.name IS NULL
+JOIN account_balances ab ON a.id = ab.account_id
+WHERE u.is_system = FALSE AND u.deleted_at IS NULL;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'accounts' AND column_name = 'name'
+    ) THEN
+        RAISE EXCEPTION 'accounts.name column was not added';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_indexes
+        WHERE tablename = 'accounts' AND indexname = 'idx_accounts_primary'
+    ) THEN
+        RAISE EXCEPTION 'idx_accounts_primary index was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 032 completed successfully';
+    RAISE NOTICE '  - accounts.name: OK';
+    RAISE NOTICE '  - one primary account per type, unique names per user: OK';
+    RAISE NOTICE '  - get_wallet_account_id / user_balances (primary wallet): OK';
+END $$;
@@ -0,0 +1,52 @@
+-- ============================================================================
+-- Migration 033: Job Runs
+-- Phase 33: Persistent history of background maintenance job executions
+-- ============================================================================
+-- The job scheduler records every execution of a maintenance job (scheduled
+-- or triggered through POST /admin/jobs/{name}/run) with its duration, the
+-- rows it affected and its error, if any. Runs older than the retention
+-- period are pruned by the job_run_cleanup job.
+-- ============================================================================
+
+-- ============================================================================
+-- job_runs table
+-- ============================================================================
+CREATE TABLE job_runs (
+    id BIGSERIAL PRIMARY KEY,
+    job_name VARCHAR(50) NOT NULL,
+    trigger VARCHAR(20) NOT NULL,
+    started_at TIMESTAMPTZ NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL,
+    duration_ms BIGINT NOT NULL,
+    rows_affected BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    triggered_by UUID,
+
+    CONSTRAINT job_run_trigger CHECK (trigger IN ('scheduled', 'manual')),
+    CONSTRAINT job_run_duration CHECK (duration_ms >= 0)
+);
+
+CREATE INDEX idx_job_runs_job_started ON job_runs(job_name, started_at DESC);
+CREATE INDEX idx_job_runs_started ON job_runs(started_at);
+
+COMMENT ON TABLE job_runs IS 'Executions of background maintenance jobs';
+COMMENT ON COLUMN job_runs.trigger IS 'scheduled (job scheduler) or manual (admin API)';
+COMMENT ON COLUMN job_runs.rows_affected IS 'Rows, entries or items the run processed';
+COMMENT ON COLUMN job_runs.error IS 'Failure of the run (NULL when it succeeded)';
+COMMENT ON COLUMN job_runs.triggered_by IS 'API key that triggered a manual run';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables
+        WHERE table_name = 'job_runs'
+    ) THEN
+        RAISE EXCEPTION 'job_runs table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 033 completed successfully';
+    RAISE NOTICE '  - job_runs: OK';
+END $$;
@@ -0,0 +1,128 @@
+-- ============================================================================
+-- Migration 034: Audit Log Partitions
+-- Phase 34: Monthly range partitions for audit_logs
+-- ============================================================================
+-- audit_logs becomes partitioned by created_at like events and ledger_entries,
+-- so the partition job can create its monthly partitions and drop old ones
+-- once the audit archival job has emptied them. Entries are copied as they
+-- are (hashes included) before the hash chain trigger is recreated.
+--
+-- Partitioned tables only enforce uniqueness that includes the partition
+-- key: the primary key becomes (id, created_at) and unique_sequence
+-- (sequence_number, created_at). sequence_number stays globally unique
+-- through audit_logs_sequence_number_seq.
+-- ============================================================================
+
+LOCK TABLE audit_logs IN ACCESS EXCLUSIVE MODE;
+
+-- Keep the sequence when the old table is dropped
+ALTER SEQUENCE audit_logs_sequence_number_seq OWNED BY NONE;
+ALTER TABLE audit_logs RENAME TO audit_logs_unpartitioned;
+
+-- ============================================================================
+-- Partitioned audit_logs
+-- ============================================================================
+CREATE TABLE audit_logs (
+    id UUID NOT NULL DEFAULT gen_random_uuid(),
+    sequence_number BIGINT NOT NULL DEFAULT nextval('audit_logs_sequence_number_seq'),
+    api_key_id UUID,
+    request_user_id UUID,
+    correlation_id UUID,
+    action VARCHAR(50) NOT NULL,
+    resource_type VARCHAR(50),
+    resource_id UUID,
+    before_state JSONB,
+    after_state JSONB,
+    changed_fields TEXT[],
+    client_ip INET,
+    previous_hash VARCHAR(64) NOT NULL,
+    current_hash VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+) PARTITION BY RANGE (created_at);
+
+-- Monthly partitions from the oldest entry through next month
+DO $$
+DECLARE
+    v_month DATE;
+    v_last DATE := (date_trunc('month', NOW()) + INTERVAL '1 month')::date;
+BEGIN
+    SELECT date_trunc('month', COALESCE(MIN(created_at), NOW()))::date
+    INTO v_month
+    FROM audit_logs_unpartitioned;
+
+    WHILE v_month <= v_last LOOP
+        EXECUTE format(
+            'CREATE TABLE %I PARTITION OF audit_logs FOR VALUES FROM (%L) TO (%L)',
+            'audit_logs_' || to_char(v_month, 'YYYY_MM'),
+            v_month,
+            (v_month + INTERVAL '1 month')::date
+        );
+        v_month := (v_month + INTERVAL '1 month')::date;
+    END LOOP;
+END $$;
+
+INSERT INTO audit_logs (
+    id, sequence_number, api_key_id, request_user_id, correlation_id, action,
+    resource_type, resource_id, before_state, after_state, changed_fields,
+    client_ip, previous_hash, current_hash, created_at
+)
+SELECT
+    id, sequence_number, api_key_id, request_user_id, correlation_id, action,
+    resource_type, resource_id, before_state, after_state, changed_fields,
+    client_ip, previous_hash, current_hash, created_at
+FROM audit_logs_unpartitioned;
+
+DROP TABLE audit_logs_unpartitioned;
+ALTER SEQUENCE audit_logs_sequence_number_seq OWNED BY audit_logs.sequence_number;
+
+ALTER TABLE audit_logs ADD CONSTRAINT audit_logs_pkey PRIMARY KEY (id, created_at);
+ALTER TABLE audit_logs ADD CONSTRAINT unique_sequence UNIQUE (sequence_number, created_at);
+ALTER TABLE audit_logs ADD CONSTRAINT audit_logs_api_key_id_fkey
+    FOREIGN KEY (api_key_id) REFERENCES api_keys(id);
+
+CREATE INDEX idx_audit_user ON audit_logs(request_user_id, created_at);
+CREATE INDEX idx_audit_action ON audit_logs(action, created_at);
+CREATE INDEX idx_audit_correlation ON audit_logs(correlation_id);
+CREATE INDEX idx_audit_resource ON audit_logs(resource_type, resource_id);
+CREATE INDEX idx_audit_sequence ON audit_logs(sequence_number);
+
+COMMENT ON TABLE audit_logs IS 'Immutable audit trail with hash chain for tamper detection (partitioned by month)';
+COMMENT ON COLUMN audit_logs.sequence_number IS 'Sequential number for ordering and hash chain';
+COMMENT ON COLUMN audit_logs.action IS 'Action performed (user.create, transfer.execute, etc.)';
+COMMENT ON COLUMN audit_logs.previous_hash IS 'Hash of previous audit log entry';
+COMMENT ON COLUMN audit_logs.current_hash IS 'Hash of this entry (includes previous_hash)';
+
+-- ============================================================================
+-- Hash chain and immutability triggers
+-- ============================================================================
+CREATE TRIGGER hash_audit_log
+    BEFORE INSERT ON audit_logs
+    FOR EACH ROW EXECUTE FUNCTION calculate_audit_hash();
+
+CREATE TRIGGER no_modify_audit
+    BEFORE UPDATE OR DELETE ON audit_logs
+    FOR EACH ROW EXECUTE FUNCTION prevent_audit_modification();
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_partitioned_table
+        WHERE partrelid = 'audit_logs'::regclass
+    ) THEN
+        RAISE EXCEPTION 'audit_logs is not partitioned';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_trigger
+        WHERE tgrelid = 'audit_logs'::regclass AND tgname = 'hash_audit_log'
+    ) THEN
+        RAISE EXCEPTION 'hash_audit_log trigger was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 034 completed successfully';
+    RAISE NOTICE '  - audit_logs partitioned by month: OK';
+    RAISE NOTICE '  - hash chain / immutability triggers: OK';
+END $$;
@@ -0,0 +1,75 @@
+-- ============================================================================
+-- Migration 035: Failed Transfers
+-- Phase 35: Read model of transfers that failed after initiation
+-- ============================================================================
+-- Transfers that fail after initiation (insufficient balance, limits, frozen
+-- accounts, restrictions) are recorded as TransferInitiated + TransferFailed
+-- but never reach the ledger. This projection makes them listable through
+-- GET /transfers?status=failed.
+-- ============================================================================
+
+-- ============================================================================
+-- Create failed_transfers table
+-- Projection of TransferInitiated + TransferFailed
+-- ============================================================================
+CREATE TABLE failed_transfers (
+    transfer_id UUID PRIMARY KEY,
+    from_user_id UUID NOT NULL,
+    to_user_id UUID NOT NULL,
+    from_account_id UUID NOT NULL,
+    to_account_id UUID NOT NULL,
+    amount NUMERIC(20, 8) NOT NULL,
+    memo TEXT,
+    reason VARCHAR(50) NOT NULL,
+    initiated_by UUID NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL
+);
+
+COMMENT ON TABLE failed_transfers IS 'Transfers recorded as failed (read-only cache derived from events)';
+COMMENT ON COLUMN failed_transfers.reason IS 'TransferFailureReason in snake_case, e.g. insufficient_balance';
+COMMENT ON COLUMN failed_transfers.initiated_by IS 'User who requested the transfer';
+
+-- ============================================================================
+-- Create failed_transfers indexes
+-- ============================================================================
+CREATE INDEX idx_failed_transfers_failed_at ON failed_transfers(failed_at DESC, transfer_id DESC);
+CREATE INDEX idx_failed_transfers_from_user ON failed_transfers(from_user_id, failed_at DESC);
+CREATE INDEX idx_failed_transfers_to_user ON failed_transfers(to_user_id, failed_at DESC);
+
+-- ============================================================================
+-- Backfill from existing events
+-- ============================================================================
+INSERT INTO failed_transfers (
+    transfer_id, from_user_id, to_user_id, from_account_id, to_account_id,
+    amount, memo, reason, initiated_by, failed_at
+)
+SELECT i.aggregate_id,
+       (i.event_data->>'from_user_id')::uuid,
+       (i.event_data->>'to_user_id')::uuid,
+       (i.event_data->>'from_account_id')::uuid,
+       (i.event_data->>'to_account_id')::uuid,
+       (i.event_data->>'amount')::numeric,
+       i.event_data->>'memo',
+       f.event_data->>'reason',
+       (i.event_data->>'initiated_by')::uuid,
+       f.created_at
+FROM events f
+JOIN events i ON i.aggregate_id = f.aggregate_id AND i.event_type = 'TransferInitiated'
+WHERE f.event_type = 'TransferFailed'
+ON CONFLICT (transfer_id) DO NOTHING;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'failed_transfers'
+    ) THEN
+        RAISE EXCEPTION 'failed_transfers table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 035 completed successfully';
+    RAISE NOTICE '  - failed_transfers table: OK';
+    RAISE NOTICE '  - failed_transfers indexes: OK';
+END $$;
@@ -0,0 +1,51 @@
+-- ============================================================================
+-- Migration 036: Alerts
+-- Phase 36: Soft caps that flag unusual activity without blocking it
+-- ============================================================================
+-- Transfers and mints are checked against the ALERT_* rules after they
+-- complete; every match is recorded here for the risk team and delivered to
+-- webhook endpoints subscribed to AlertRaised.
+-- ============================================================================
+
+-- ============================================================================
+-- Create alerts table
+-- ============================================================================
+CREATE TABLE alerts (
+    id UUID PRIMARY KEY,
+    rule VARCHAR(30) NOT NULL,
+    user_id UUID NOT NULL,
+    resource_id UUID NOT NULL,
+    amount NUMERIC(20, 8) NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT valid_alert_rule
+        CHECK (rule IN ('large_transfer', 'transfer_velocity', 'mint_outside_hours'))
+);
+
+COMMENT ON TABLE alerts IS 'Soft cap matches on completed transfers and mints (informational, nothing is blocked)';
+COMMENT ON COLUMN alerts.user_id IS 'Sender of the transfer, or recipient of the mint';
+COMMENT ON COLUMN alerts.resource_id IS 'Transfer or mint ID';
+COMMENT ON COLUMN alerts.details IS 'Rule threshold and observed values';
+
+-- ============================================================================
+-- Create alerts indexes
+-- ============================================================================
+CREATE INDEX idx_alerts_created_at ON alerts(created_at DESC, id DESC);
+CREATE INDEX idx_alerts_user ON alerts(user_id, created_at DESC);
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'alerts'
+    ) THEN
+        RAISE EXCEPTION 'alerts table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 036 completed successfully';
+    RAISE NOTICE '  - alerts table: OK';
+    RAISE NOTICE '  - alerts indexes: OK';
+END $$;
@@ -0,0 +1,89 @@
+-- ============================================================================
+-- Migration 037: Journals
+-- Phase 37: Journal headers for the double-entry ledger
+-- ============================================================================
+-- ledger_entries only holds the debit and credit legs of a journal. The
+-- journals table is the header: what kind of operation it records, its memo
+-- and who requested it. ProjectionService writes it in the same transaction
+-- as the legs.
+-- ============================================================================
+
+-- ============================================================================
+-- Create journals table
+-- ============================================================================
+CREATE TABLE journals (
+    id UUID PRIMARY KEY,
+    journal_type VARCHAR(20) NOT NULL,
+    from_account_id UUID NOT NULL REFERENCES accounts(id),
+    to_account_id UUID NOT NULL REFERENCES accounts(id),
+    amount NUMERIC(20, 8) NOT NULL,
+    memo TEXT,
+    category VARCHAR(50),
+    initiated_by UUID,
+    source_event_id UUID NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT journal_positive_amount CHECK (amount > 0),
+    CONSTRAINT valid_journal_type
+        CHECK (journal_type IN ('transfer', 'reversal', 'mint', 'burn', 'hold_capture'))
+);
+
+COMMENT ON TABLE journals IS 'Header of each ledger journal (read-only cache derived from events); legs are in ledger_entries';
+COMMENT ON COLUMN journals.id IS 'journal_id of the ledger entries (transfer, mint or burn ID)';
+COMMENT ON COLUMN journals.memo IS 'Transfer memo or mint/burn/reversal reason';
+COMMENT ON COLUMN journals.initiated_by IS 'User who requested the operation (NULL for admin operations without a request user)';
+COMMENT ON COLUMN journals.source_event_id IS 'Debit event the journal was projected from';
+
+-- ============================================================================
+-- Create journals indexes
+-- ============================================================================
+CREATE INDEX idx_journals_created_at ON journals(created_at DESC);
+CREATE INDEX idx_journals_type ON journals(journal_type, created_at DESC);
+
+-- ============================================================================
+-- Backfill from existing ledger entries and events
+-- ============================================================================
+INSERT INTO journals (
+    id, journal_type, from_account_id, to_account_id, amount, memo, category,
+    initiated_by, source_event_id, created_at
+)
+SELECT d.journal_id,
+       CASE
+           WHEN fa.user_id = '00000000-0000-0000-0000-000000000001' THEN 'mint'
+           WHEN ta.user_id = '00000000-0000-0000-0000-000000000002' THEN 'burn'
+           WHEN de.event_type = 'HoldCaptured' THEN 'hold_capture'
+           WHEN ti.event_data ? 'reversal_of' THEN 'reversal'
+           ELSE 'transfer'
+       END,
+       d.account_id,
+       c.account_id,
+       d.amount,
+       CASE WHEN ti.id IS NULL THEN de.event_data->>'description' ELSE ti.event_data->>'memo' END,
+       d.category,
+       (ti.event_data->>'initiated_by')::uuid,
+       d.transfer_event_id,
+       d.created_at
+FROM ledger_entries d
+JOIN ledger_entries c ON c.journal_id = d.journal_id AND c.entry_type = 'credit'
+JOIN accounts fa ON fa.id = d.account_id
+JOIN accounts ta ON ta.id = c.account_id
+LEFT JOIN events de ON de.id = d.transfer_event_id
+LEFT JOIN events ti ON ti.aggregate_id = d.journal_id AND ti.event_type = 'TransferInitiated'
+WHERE d.entry_type = 'debit'
+ON CONFLICT (id) DO NOTHING;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables WHERE table_name = 'journals'
+    ) THEN
+        RAISE EXCEPTION 'journals table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 037 completed successfully';
+    RAISE NOTICE '  - journals table: OK';
+    RAISE NOTICE '  - journals indexes: OK';
+END $$;
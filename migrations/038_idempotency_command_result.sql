@@ -0,0 +1,32 @@
+-- ============================================================================
+-- Migration 038: Idempotency Command Result
+-- Phase 38: Replay the original command result for a reused idempotency key
+-- ============================================================================
+-- Add idempotency_keys.command_result column
+-- ============================================================================
+
+-- ============================================================================
+-- Add idempotency_keys.command_result column
+-- response_body is set by the HTTP idempotency middleware (the HTTP response);
+-- command_result is set by the EventStore in the append transaction, so command
+-- handlers can return the original result verbatim on replay
+-- ============================================================================
+ALTER TABLE idempotency_keys ADD COLUMN command_result JSONB;
+
+COMMENT ON COLUMN idempotency_keys.command_result IS 'Result of the command (e.g. MintResult) stored with its events, returned on replay';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'idempotency_keys' AND column_name = 'command_result'
+    ) THEN
+        RAISE EXCEPTION 'idempotency_keys.command_result column was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 038 completed successfully';
+    RAISE NOTICE '  - idempotency_keys.command_result column: OK';
+END $$;
@@ -0,0 +1,41 @@
+-- ============================================================================
+-- Migration 039: User Rate Limit Buckets
+-- Phase 39: Per-user request counters within an API key
+-- ============================================================================
+-- Create user_rate_limit_buckets table
+-- ============================================================================
+
+-- ============================================================================
+-- Create user_rate_limit_buckets table
+-- One partner key serves many end users; counting each X-Request-User-Id
+-- separately keeps one user from exhausting the key's budget
+-- ============================================================================
+CREATE TABLE user_rate_limit_buckets (
+    api_key_id UUID NOT NULL REFERENCES api_keys(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL,
+    window_start TIMESTAMPTZ NOT NULL,
+    request_count INTEGER NOT NULL DEFAULT 1,
+    PRIMARY KEY (api_key_id, user_id, window_start)
+);
+
+CREATE INDEX idx_user_rate_limit_expires ON user_rate_limit_buckets(window_start);
+
+COMMENT ON TABLE user_rate_limit_buckets IS 'Rate limiting buckets per API key and request user per minute';
+COMMENT ON COLUMN user_rate_limit_buckets.user_id IS 'X-Request-User-Id (or token subject) of the counted requests';
+COMMENT ON COLUMN user_rate_limit_buckets.window_start IS 'Start of the 1-minute window (truncated to minute)';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.tables
+        WHERE table_name = 'user_rate_limit_buckets'
+    ) THEN
+        RAISE EXCEPTION 'user_rate_limit_buckets table was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 039 completed successfully';
+    RAISE NOTICE '  - user_rate_limit_buckets table: OK';
+END $$;
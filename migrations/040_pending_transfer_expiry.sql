@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration 040: Pending Transfer Expiry
+-- Phase 40: Transfers awaiting approval expire instead of lingering forever
+-- ============================================================================
+-- Add pending_transfers.expires_at column and the 'expired' status
+-- ============================================================================
+
+-- ============================================================================
+-- Add pending_transfers.expires_at column
+-- Set from TransferApprovalRequested.expires_at (PENDING_TRANSFER_TTL_HOURS
+-- after the request); the pending_transfer_expiry job fails transfers still
+-- pending after it with TransferFailed (reason expired)
+-- ============================================================================
+ALTER TABLE pending_transfers ADD COLUMN expires_at TIMESTAMPTZ;
+
+-- Transfers requested before this migration get the default TTL (72 hours)
+UPDATE pending_transfers
+SET expires_at = created_at + INTERVAL '72 hours'
+WHERE status = 'pending_approval';
+
+ALTER TABLE pending_transfers DROP CONSTRAINT valid_pending_transfer_status;
+ALTER TABLE pending_transfers ADD CONSTRAINT valid_pending_transfer_status
+    CHECK (status IN ('pending_approval', 'approved', 'rejected', 'failed', 'expired'));
+
+COMMENT ON COLUMN pending_transfers.expires_at IS 'When the transfer fails as expired unless approved or rejected before';
+COMMENT ON COLUMN pending_transfers.status IS 'pending_approval, approved (executed), rejected, failed (approved but not executable), or expired';
+
+-- ============================================================================
+-- Create pending_transfers expiry index
+-- ============================================================================
+CREATE INDEX idx_pending_transfers_expires_at ON pending_transfers(expires_at)
+    WHERE status = 'pending_approval';
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'pending_transfers' AND column_name = 'expires_at'
+    ) THEN
+        RAISE EXCEPTION 'pending_transfers.expires_at column was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 040 completed successfully';
+    RAISE NOTICE '  - pending_transfers.expires_at column: OK';
+    RAISE NOTICE '  - idx_pending_transfers_expires_at: OK';
+END $$;
@@ -0,0 +1,49 @@
+-- ============================================================================
+-- Migration 041: Account Closure
+-- Phase 41: Wallet accounts can be closed, sweeping any remaining balance
+-- ============================================================================
+-- Add accounts.closed_at column, the 'sweep' journal type and the
+-- 'account_closed' notification kind
+-- ============================================================================
+
+-- ============================================================================
+-- Add accounts.closed_at column
+-- Set with is_active = FALSE when AccountClosed is appended; closed accounts
+-- take no further debits, credits or holds
+-- ============================================================================
+ALTER TABLE accounts ADD COLUMN closed_at TIMESTAMPTZ;
+
+COMMENT ON COLUMN accounts.closed_at IS 'When the account was closed (NULL while open)';
+
+-- ============================================================================
+-- Allow 'sweep' journals
+-- Remaining balance moved out of an account as it is closed
+-- ============================================================================
+ALTER TABLE journals DROP CONSTRAINT valid_journal_type;
+ALTER TABLE journals ADD CONSTRAINT valid_journal_type
+    CHECK (journal_type IN ('transfer', 'reversal', 'mint', 'burn', 'hold_capture', 'sweep'));
+
+-- ============================================================================
+-- Allow 'account_closed' notifications
+-- ============================================================================
+ALTER TABLE user_notifications DROP CONSTRAINT valid_notification_kind;
+ALTER TABLE user_notifications ADD CONSTRAINT valid_notification_kind
+    CHECK (kind IN ('credit_received', 'debit_executed', 'account_frozen', 'account_closed'));
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'accounts' AND column_name = 'closed_at'
+    ) THEN
+        RAISE EXCEPTION 'accounts.closed_at column was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 041 completed successfully';
+    RAISE NOTICE '  - accounts.closed_at column: OK';
+    RAISE NOTICE '  - valid_journal_type allows sweep: OK';
+    RAISE NOTICE '  - valid_notification_kind allows account_closed: OK';
+END $$;
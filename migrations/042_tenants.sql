@@ -0,0 +1,121 @@
+-- ============================================================================
+-- Migration 042: Tenants
+-- Phase 42: Several isolated ATP economies on one deployment
+-- ============================================================================
+-- Create tenants table and seed the default tenant; add tenant_id to users,
+-- accounts, events, journals and api_keys. Existing rows belong to the
+-- default tenant. Usernames and emails become unique per tenant.
+-- ============================================================================
+
+-- ============================================================================
+-- Create tenants table
+-- The default tenant (nil UUID) owns the system users and accounts, and its
+-- API keys are the only ones that may use platform-wide admin areas
+-- ============================================================================
+CREATE TABLE tenants (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(100) NOT NULL UNIQUE,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE tenants IS 'Isolated economies sharing the deployment';
+COMMENT ON COLUMN tenants.is_active IS 'FALSE rejects every request authenticated for the tenant';
+
+INSERT INTO tenants (id, name)
+VALUES ('00000000-0000-0000-0000-000000000000', 'default')
+ON CONFLICT (id) DO NOTHING;
+
+-- ============================================================================
+-- Add tenant_id columns
+-- ============================================================================
+ALTER TABLE users
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES tenants(id);
+ALTER TABLE accounts
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES tenants(id);
+ALTER TABLE events
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000';
+ALTER TABLE journals
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES tenants(id);
+ALTER TABLE api_keys
+    ADD COLUMN tenant_id UUID NOT NULL DEFAULT '00000000-0000-0000-0000-000000000000' REFERENCES tenants(id);
+
+COMMENT ON COLUMN users.tenant_id IS 'Tenant the user belongs to';
+COMMENT ON COLUMN accounts.tenant_id IS 'Tenant of the owning user (set by trigger)';
+COMMENT ON COLUMN events.tenant_id IS 'Tenant the event was appended for (from the operation context)';
+COMMENT ON COLUMN journals.tenant_id IS 'Tenant of the journal''s user wallet leg (set by trigger)';
+COMMENT ON COLUMN api_keys.tenant_id IS 'Tenant whose data the key may access';
+
+CREATE INDEX idx_users_tenant ON users(tenant_id, created_at, id);
+CREATE INDEX idx_accounts_tenant ON accounts(tenant_id);
+CREATE INDEX idx_events_tenant ON events(tenant_id, created_at);
+CREATE INDEX idx_journals_tenant ON journals(tenant_id, created_at DESC);
+CREATE INDEX idx_api_keys_tenant ON api_keys(tenant_id);
+
+-- ============================================================================
+-- Usernames and emails are unique per tenant
+-- ============================================================================
+ALTER TABLE users DROP CONSTRAINT users_username_key;
+ALTER TABLE users DROP CONSTRAINT users_email_key;
+ALTER TABLE users ADD CONSTRAINT users_tenant_username_key UNIQUE (tenant_id, username);
+ALTER TABLE users ADD CONSTRAINT users_tenant_email_key UNIQUE (tenant_id, email);
+
+-- ============================================================================
+-- Accounts inherit the tenant of their user
+-- ============================================================================
+CREATE OR REPLACE FUNCTION set_account_tenant()
+RETURNS TRIGGER AS $$
+BEGIN
+    SELECT tenant_id INTO NEW.tenant_id FROM users WHERE id = NEW.user_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER account_tenant
+    BEFORE INSERT ON accounts
+    FOR EACH ROW EXECUTE FUNCTION set_account_tenant();
+
+-- ============================================================================
+-- Journals belong to the tenant of their user wallet leg (mints and burns
+-- have a system account on the other side)
+-- ============================================================================
+CREATE OR REPLACE FUNCTION set_journal_tenant()
+RETURNS TRIGGER AS $$
+BEGIN
+    SELECT tenant_id INTO NEW.tenant_id
+    FROM accounts
+    WHERE id IN (NEW.from_account_id, NEW.to_account_id)
+    ORDER BY account_type <> 'user_wallet'
+    LIMIT 1;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER journal_tenant
+    BEFORE INSERT ON journals
+    FOR EACH ROW EXECUTE FUNCTION set_journal_tenant();
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM tenants WHERE id = '00000000-0000-0000-0000-000000000000'
+    ) THEN
+        RAISE EXCEPTION 'Default tenant was not created';
+    END IF;
+
+    IF NOT EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_name = 'events' AND column_name = 'tenant_id'
+    ) THEN
+        RAISE EXCEPTION 'events.tenant_id column was not added';
+    END IF;
+
+    RAISE NOTICE 'Migration 042 completed successfully';
+    RAISE NOTICE '  - tenants table and default tenant: OK';
+    RAISE NOTICE '  - tenant_id on users, accounts, events, journals, api_keys: OK';
+    RAISE NOTICE '  - usernames and emails unique per tenant: OK';
+END $$;
@@ -0,0 +1,70 @@
+-- ============================================================================
+-- Migration 043: Event Integrity
+-- Phase 43: Append-only guard and tamper detection for the event store
+-- ============================================================================
+-- Re-create the no_modify_events guard so it also fires for sessions running
+-- with session_replication_role = replica, and create event_integrity_digests:
+-- one SHA-256 digest per UTC day of events, written by the event_integrity
+-- job and compared against a fresh computation by GET /admin/events/integrity.
+-- ============================================================================
+
+-- ============================================================================
+-- Append-only guard on events
+-- Row triggers on the partitioned table are cloned to every partition,
+-- including the ones the partition job creates later. ENABLE ALWAYS keeps
+-- the trigger active for replica-role sessions, which skip ordinary triggers.
+-- ============================================================================
+DROP TRIGGER IF EXISTS no_modify_events ON events;
+
+CREATE TRIGGER no_modify_events
+    BEFORE UPDATE OR DELETE ON events
+    FOR EACH ROW EXECUTE FUNCTION prevent_event_modification();
+
+ALTER TABLE events ENABLE ALWAYS TRIGGER no_modify_events;
+
+-- ============================================================================
+-- Create event_integrity_digests table
+-- ============================================================================
+CREATE TABLE event_integrity_digests (
+    day DATE PRIMARY KEY,
+    event_count BIGINT NOT NULL,
+    first_global_sequence BIGINT,
+    last_global_sequence BIGINT,
+    digest VARCHAR(64) NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE event_integrity_digests IS 'Daily digests of the event store for tamper detection';
+COMMENT ON COLUMN event_integrity_digests.day IS 'UTC day of events.created_at';
+COMMENT ON COLUMN event_integrity_digests.digest IS 'Hex SHA-256 over the day''s events in (created_at, id) order';
+
+-- Digests are as immutable as the events they cover
+CREATE TRIGGER no_modify_event_integrity_digests
+    BEFORE UPDATE OR DELETE ON event_integrity_digests
+    FOR EACH ROW EXECUTE FUNCTION prevent_event_modification();
+
+ALTER TABLE event_integrity_digests ENABLE ALWAYS TRIGGER no_modify_event_integrity_digests;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF EXISTS (
+        SELECT 1
+        FROM pg_inherits i
+        WHERE i.inhparent = 'events'::regclass
+          AND NOT EXISTS (
+              SELECT 1 FROM pg_trigger t
+              WHERE t.tgrelid = i.inhrelid
+                AND t.tgname = 'no_modify_events'
+                AND t.tgenabled = 'A'
+          )
+    ) THEN
+        RAISE EXCEPTION 'An events partition is not guarded by no_modify_events';
+    END IF;
+
+    RAISE NOTICE 'Migration 043 completed successfully';
+    RAISE NOTICE '  - no_modify_events enabled always on events and partitions: OK';
+    RAISE NOTICE '  - event_integrity_digests table: OK';
+END $$;
@@ -0,0 +1,57 @@
+-- ============================================================================
+-- Migration 044: Idempotency Key Scope
+-- Phase 44: Idempotency keys belong to the API key and tenant that sent them
+-- ============================================================================
+-- Add api_key_id and tenant_id to idempotency_keys and make a key unique per
+-- (key, api_key_id, tenant_id), so the same Idempotency-Key sent by another
+-- API key or tenant is a separate request instead of a replay of someone
+-- else's response. Keys written by internal callers (CLI, jobs) have neither.
+-- ============================================================================
+
+-- ============================================================================
+-- Add idempotency_keys.api_key_id and tenant_id columns
+-- ============================================================================
+ALTER TABLE idempotency_keys
+    ADD COLUMN api_key_id UUID,
+    ADD COLUMN tenant_id UUID;
+
+COMMENT ON COLUMN idempotency_keys.api_key_id IS 'API key that sent the Idempotency-Key (NULL for internal callers)';
+COMMENT ON COLUMN idempotency_keys.tenant_id IS 'Tenant of that API key (NULL for internal callers)';
+
+-- ============================================================================
+-- Replace the primary key on key with a unique scoped key
+-- NULL scopes are mapped to the nil UUID so unscoped keys of internal callers
+-- still conflict (UNIQUE NULLS NOT DISTINCT needs PostgreSQL 15)
+-- ============================================================================
+ALTER TABLE idempotency_keys DROP CONSTRAINT idempotency_keys_pkey;
+
+CREATE UNIQUE INDEX idx_idempotency_keys_scope ON idempotency_keys (
+    key,
+    COALESCE(api_key_id, '00000000-0000-0000-0000-000000000000'::uuid),
+    COALESCE(tenant_id, '00000000-0000-0000-0000-000000000000'::uuid)
+);
+
+-- ============================================================================
+-- events.idempotency_key is no longer unique on its own
+-- Two callers may append with the same key; idempotency_keys guards each scope
+-- ============================================================================
+DROP INDEX idx_events_idempotency;
+
+CREATE INDEX idx_events_idempotency ON events(idempotency_key)
+    WHERE idempotency_key IS NOT NULL;
+
+-- ============================================================================
+-- Verification
+-- ============================================================================
+DO $$
+BEGIN
+    IF NOT EXISTS (
+        SELECT 1 FROM pg_indexes WHERE indexname = 'idx_idempotency_keys_scope'
+    ) THEN
+        RAISE EXCEPTION 'idx_idempotency_keys_scope index was not created';
+    END IF;
+
+    RAISE NOTICE 'Migration 044 completed successfully';
+    RAISE NOTICE '  - idempotency_keys.api_key_id / tenant_id columns: OK';
+    RAISE NOTICE '  - idempotency_keys unique per (key, api_key_id, tenant_id): OK';
+END $$;
@@ -2,7 +2,7 @@
 -- Database Integration Test
 -- Tests all Phase 1-7 components
 -- ============================================================================
--- Run this after all migrations are complete
+-- Run this with psql after all migrations are complete (uses psql \echo)
 -- ============================================================================
 
 \echo '============================================'
@@ -4,25 +4,41 @@
 //! It applies events to maintain current state and generates events for commands.
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
-use crate::domain::{AccountEvent, Amount, Balance};
+use crate::domain::{AccountEvent, AccountType, Amount, Balance, DomainError};
 use crate::error::AppError;
 
 use super::Aggregate;
 
 /// Account status
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AccountStatus {
+    #[default]
     Active,
     Frozen,
+    /// Closed with a zero balance; terminal
+    Closed,
 }
 
-impl Default for AccountStatus {
-    fn default() -> Self {
-        Self::Active
-    }
+/// Invariant of an account's state broken by its event history.
+/// Commands never produce these; replaying corrupt or hand-written events can.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AccountInvariantViolation {
+    /// Balance below zero on an account type that may not go negative
+    #[error("Balance {balance} is negative")]
+    NegativeBalance { balance: Decimal },
+
+    /// Active holds reserve more than the balance
+    #[error("Held {held} exceeds balance {balance}")]
+    HoldsExceedBalance { held: Decimal, balance: Decimal },
+
+    /// A hold of zero or a negative amount
+    #[error("Hold {hold_id} has non-positive amount {amount}")]
+    NonPositiveHold { hold_id: Uuid, amount: Decimal },
 }
 
 /// Account Aggregate
@@ -37,8 +53,12 @@ pub struct Account {
     /// Owner user ID
     user_id: Uuid,
     
-    /// Account type (user_wallet, mint_source, etc.)
-    account_type: String,
+    /// Account type
+    account_type: AccountType,
+
+    /// Name of an additional account (None for the user's primary account)
+    #[serde(default)]
+    name: Option<String>,
     
     /// Current balance (derived from events)
     balance: Balance,
@@ -46,6 +66,10 @@ pub struct Account {
     /// Account status
     status: AccountStatus,
     
+    /// Active holds (hold_id → amount), reserved but not yet debited
+    #[serde(default)]
+    holds: BTreeMap<Uuid, Decimal>,
+    
     /// Current version (number of events applied)
     version: i64,
     
@@ -58,9 +82,11 @@ impl Default for Account {
         Self {
             id: Uuid::nil(),
             user_id: Uuid::nil(),
-            account_type: String::new(),
+            account_type: AccountType::default(),
+            name: None,
             balance: Balance::zero(),
             status: AccountStatus::Active,
+            holds: BTreeMap::new(),
             version: 0,
             created_at: None,
         }
@@ -76,14 +102,29 @@ impl Account {
     pub fn create(
         account_id: Uuid,
         user_id: Uuid,
-        account_type: String,
+        account_type: AccountType,
+    ) -> (Self, AccountEvent) {
+        Self::create_with_name(account_id, user_id, account_type, None)
+    }
+
+    /// Create an additional named account of a user
+    pub fn create_named(account_id: Uuid, user_id: Uuid, name: String) -> (Self, AccountEvent) {
+        Self::create_with_name(account_id, user_id, AccountType::UserWallet, Some(name))
+    }
+
+    fn create_with_name(
+        account_id: Uuid,
+        user_id: Uuid,
+        account_type: AccountType,
+        name: Option<String>,
     ) -> (Self, AccountEvent) {
         let now = Utc::now();
         
         let event = AccountEvent::AccountCreated {
             account_id,
             user_id,
-            account_type: account_type.clone(),
+            account_type,
+            name: name.clone(),
             created_at: now,
         };
         
@@ -91,8 +132,10 @@ impl Account {
             id: account_id,
             user_id,
             account_type,
+            name,
             balance: Balance::zero(),
             status: AccountStatus::Active,
+            holds: BTreeMap::new(),
             version: 1,
             created_at: Some(now),
         };
@@ -101,32 +144,35 @@ impl Account {
     }
 
     /// Create an account from database state (bypasses event sourcing)
-    /// Used for system accounts that are seeded directly in DB
+    /// Used for system accounts that are seeded directly in DB.
+    /// Fails if `account_type` is not a known AccountType code or the
+    /// balance is negative for a type that may not go negative.
     pub fn from_db_state(
         id: Uuid,
         user_id: Uuid,
-        account_type: String,
+        account_type: &str,
         balance: rust_decimal::Decimal,
         version: i64,
-    ) -> Self {
-        // System accounts (like SYSTEM_MINT) can have negative balances
-        // Use Balance::zero() if the value is negative, then set internal value directly
-        let balance_value = if balance >= rust_decimal::Decimal::ZERO {
-            Balance::new(balance).unwrap_or_else(|_| Balance::zero())
-        } else {
-            // For negative balances (SYSTEM_MINT liability), use internal construction
-            Balance::from_decimal_unchecked(balance)
-        };
+    ) -> Result<Self, DomainError> {
+        let account_type: AccountType = account_type.parse()?;
+
+        // Only account types that may go negative (SYSTEM_MINT liability)
+        // accept a negative projected balance
+        let balance_value = Balance::for_account_type(balance, account_type).inspect_err(|e| {
+            tracing::error!(account_id = %id, error = %e, "Account balance violates policy");
+        })?;
         
-        Self {
+        Ok(Self {
             id,
             user_id,
             account_type,
+            name: None,
             balance: balance_value,
             status: AccountStatus::Active,
+            holds: BTreeMap::new(),
             version,
             created_at: None, // Not tracked for DB-loaded accounts
-        }
+        })
     }
 
     // =========================================================================
@@ -141,13 +187,11 @@ impl Account {
         transfer_id: Uuid,
         description: String,
     ) -> Result<AccountEvent, AppError> {
-        // Check if account is frozen
-        if self.status == AccountStatus::Frozen {
-            return Err(AppError::AccountFrozen);
-        }
+        // Frozen and closed accounts take no debits or credits
+        self.ensure_open()?;
         
-        // Check if balance is sufficient
-        if !self.balance.is_sufficient_for(amount) {
+        // Check if available (unheld) balance is sufficient
+        if !self.account_type.may_go_negative() && self.available_balance() < amount.value() {
             return Err(AppError::InsufficientBalance);
         }
         
@@ -157,6 +201,7 @@ impl Account {
             transfer_id,
             description,
             debited_at: Utc::now(),
+            category: None,
         })
     }
 
@@ -172,10 +217,8 @@ impl Account {
         transfer_id: Uuid,
         description: String,
     ) -> Result<AccountEvent, AppError> {
-        // Check if account is frozen
-        if self.status == AccountStatus::Frozen {
-            return Err(AppError::AccountFrozen);
-        }
+        // Frozen and closed accounts take no debits or credits
+        self.ensure_open()?;
         
         Ok(AccountEvent::MoneyCredited {
             account_id: self.id,
@@ -183,13 +226,18 @@ impl Account {
             transfer_id,
             description,
             credited_at: Utc::now(),
+            category: None,
         })
     }
 
     /// Freeze the account
     pub fn freeze(&self, reason: String) -> Result<AccountEvent, AppError> {
-        if self.status == AccountStatus::Frozen {
-            return Err(AppError::InvalidRequest("Account is already frozen".to_string()));
+        match self.status {
+            AccountStatus::Frozen => {
+                return Err(AppError::InvalidRequest("Account is already frozen".to_string()));
+            }
+            AccountStatus::Closed => return Err(AppError::AccountClosed(self.id.to_string())),
+            AccountStatus::Active => {}
         }
         
         Ok(AccountEvent::AccountFrozen {
@@ -211,6 +259,111 @@ impl Account {
         })
     }
 
+    /// Close the account. Its balance must already be zero (swept by the
+    /// caller if needed) and no funds may be held.
+    pub fn close(
+        &self,
+        reason: Option<String>,
+        swept_to_account_id: Option<Uuid>,
+    ) -> Result<AccountEvent, AppError> {
+        if self.status == AccountStatus::Closed {
+            return Err(AppError::InvalidRequest("Account is already closed".to_string()));
+        }
+
+        if !self.holds.is_empty() {
+            return Err(AppError::InvalidRequest(
+                "Account has active holds; capture or release them before closing".to_string(),
+            ));
+        }
+
+        if !self.balance.value().is_zero() {
+            return Err(AppError::InvalidRequest(format!(
+                "Account balance must be zero to close (balance is {})",
+                self.balance.value()
+            )));
+        }
+
+        Ok(AccountEvent::AccountClosed {
+            account_id: self.id,
+            reason,
+            swept_to_account_id,
+            closed_at: Utc::now(),
+        })
+    }
+
+    /// Frozen and closed accounts take no debits, credits or holds
+    fn ensure_open(&self) -> Result<(), AppError> {
+        match self.status {
+            AccountStatus::Active => Ok(()),
+            AccountStatus::Frozen => Err(AppError::AccountFrozen),
+            AccountStatus::Closed => Err(AppError::AccountClosed(self.id.to_string())),
+        }
+    }
+
+    // =========================================================================
+    // Holds (two-phase payments)
+    // =========================================================================
+
+    /// Reserve funds for a later capture
+    pub fn hold(
+        &self,
+        amount: &Amount,
+        hold_id: Uuid,
+        to_account_id: Uuid,
+        description: String,
+    ) -> Result<AccountEvent, AppError> {
+        self.ensure_open()?;
+
+        if self.holds.contains_key(&hold_id) {
+            return Err(AppError::InvalidRequest("Hold already exists".to_string()));
+        }
+
+        if self.available_balance() < amount.value() {
+            return Err(AppError::InsufficientBalance);
+        }
+
+        Ok(AccountEvent::BalanceHeld {
+            account_id: self.id,
+            hold_id,
+            amount: amount.value(),
+            to_account_id,
+            description,
+            held_at: Utc::now(),
+        })
+    }
+
+    /// Capture previously held funds (debits the ledger balance)
+    pub fn capture_hold(&self, hold_id: Uuid, transfer_id: Uuid) -> Result<AccountEvent, AppError> {
+        self.ensure_open()?;
+
+        let amount = self
+            .holds
+            .get(&hold_id)
+            .copied()
+            .ok_or_else(|| AppError::HoldNotFound(hold_id.to_string()))?;
+
+        Ok(AccountEvent::HoldCaptured {
+            account_id: self.id,
+            hold_id,
+            amount,
+            transfer_id,
+            captured_at: Utc::now(),
+        })
+    }
+
+    /// Release previously held funds back to the available balance
+    pub fn release_hold(&self, hold_id: Uuid) -> Result<AccountEvent, AppError> {
+        if !self.holds.contains_key(&hold_id) {
+            return Err(AppError::HoldNotFound(hold_id.to_string()));
+        }
+
+        Ok(AccountEvent::HoldReleased {
+            account_id: self.id,
+            hold_id,
+            released_at: Utc::now(),
+        })
+    }
+
     // =========================================================================
     // Getters
     // =========================================================================
@@ -219,14 +372,34 @@ impl Account {
         self.user_id
     }
     
-    pub fn account_type(&self) -> &str {
-        &self.account_type
+    pub fn account_type(&self) -> AccountType {
+        self.account_type
+    }
+
+    /// Name of an additional account (None for the primary account)
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
     }
     
     pub fn balance(&self) -> &Balance {
         &self.balance
     }
     
+    /// Total amount currently held
+    pub fn held_balance(&self) -> Decimal {
+        self.holds.values().copied().sum()
+    }
+    
+    /// Ledger balance minus active holds
+    pub fn available_balance(&self) -> Decimal {
+        self.balance.value() - self.held_balance()
+    }
+    
+    /// Amount held under a given hold, if active
+    pub fn hold_amount(&self, hold_id: Uuid) -> Option<Decimal> {
+        self.holds.get(&hold_id).copied()
+    }
+    
     pub fn status(&self) -> &AccountStatus {
         &self.status
     }
@@ -234,10 +407,39 @@ impl Account {
     pub fn is_frozen(&self) -> bool {
         self.status == AccountStatus::Frozen
     }
+
+    pub fn is_closed(&self) -> bool {
+        self.status == AccountStatus::Closed
+    }
     
     pub fn created_at(&self) -> Option<DateTime<Utc>> {
         self.created_at
     }
+
+    // =========================================================================
+    // Invariants
+    // =========================================================================
+
+    /// Check the state invariants (pure; used by tests and the
+    /// reconciliation job)
+    pub fn check_invariants(&self) -> Result<(), AccountInvariantViolation> {
+        let balance = self.balance.value();
+        if balance < Decimal::ZERO && !self.account_type.may_go_negative() {
+            return Err(AccountInvariantViolation::NegativeBalance { balance });
+        }
+
+        if let Some((&hold_id, &amount)) = self.holds.iter().find(|(_, amount)| **amount <= Decimal::ZERO) {
+            return Err(AccountInvariantViolation::NonPositiveHold { hold_id, amount });
+        }
+
+        // Debits of accounts that may go negative ignore holds
+        let held = self.held_balance();
+        if held > balance && !self.account_type.may_go_negative() {
+            return Err(AccountInvariantViolation::HoldsExceedBalance { held, balance });
+        }
+
+        Ok(())
+    }
 }
 
 // =========================================================================
@@ -265,11 +467,13 @@ impl Aggregate for Account {
                 account_id,
                 user_id,
                 account_type,
+                name,
                 created_at,
             } => {
                 self.id = account_id;
                 self.user_id = user_id;
                 self.account_type = account_type;
+                self.name = name;
                 self.balance = Balance::zero();
                 self.status = AccountStatus::Active;
                 self.created_at = Some(created_at);
@@ -278,6 +482,10 @@ impl Aggregate for Account {
             AccountEvent::MoneyCredited { amount, .. } => {
                 // Safely handle invalid amount in event
                 match Amount::new(amount) {
+                    Ok(amt) if self.account_type.may_go_negative() => {
+                        // The balance may still be negative after the credit
+                        self.balance = Balance::from_decimal_unchecked(self.balance.value() + amt.value());
+                    }
                     Ok(amt) => {
                         match self.balance.credit(&amt) {
                             Ok(new_balance) => self.balance = new_balance,
@@ -302,6 +510,9 @@ impl Aggregate for Account {
             AccountEvent::MoneyDebited { amount, .. } => {
                 // Safely handle invalid amount in event
                 match Amount::new(amount) {
+                    Ok(amt) if self.account_type.may_go_negative() => {
+                        self.balance = Balance::from_decimal_unchecked(self.balance.value() - amt.value());
+                    }
                     Ok(amt) => {
                         match self.balance.debit(&amt) {
                             Ok(new_balance) => self.balance = new_balance,
@@ -330,6 +541,41 @@ impl Aggregate for Account {
             AccountEvent::AccountUnfrozen { .. } => {
                 self.status = AccountStatus::Active;
             }
+
+            AccountEvent::AccountClosed { .. } => {
+                self.status = AccountStatus::Closed;
+            }
+            
+            AccountEvent::BalanceHeld { hold_id, amount, .. } => {
+                self.holds.insert(hold_id, amount);
+            }
+            
+            AccountEvent::HoldCaptured { hold_id, amount, .. } => {
+                self.holds.remove(&hold_id);
+                match Amount::new(amount) {
+                    Ok(amt) => {
+                        match self.balance.debit(&amt) {
+                            Ok(new_balance) => self.balance = new_balance,
+                            Err(e) => {
+                                tracing::error!(
+                                    "Balance underflow during hold capture replay for account {}: {}",
+                                    self.id, e
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            "Invalid amount in HoldCaptured event for account {}: {}",
+                            self.id, e
+                        );
+                    }
+                }
+            }
+            
+            AccountEvent::HoldReleased { hold_id, .. } => {
+                self.holds.remove(&hold_id);
+            }
         }
         
         self.version += 1;
@@ -356,12 +602,12 @@ mod tests {
         let (account, event) = Account::create(
             account_id,
             user_id,
-            "user_wallet".to_string(),
+            AccountType::UserWallet,
         );
         
         assert_eq!(account.id(), account_id);
         assert_eq!(account.user_id(), user_id);
-        assert_eq!(account.account_type(), "user_wallet");
+        assert_eq!(account.account_type(), AccountType::UserWallet);
         assert_eq!(account.balance().value(), Decimal::ZERO);
         assert_eq!(account.version(), 1);
         assert!(matches!(event, AccountEvent::AccountCreated { .. }));
@@ -371,7 +617,7 @@ mod tests {
     fn test_account_credit() {
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         
         let amount = Amount::new(Decimal::new(100, 0)).unwrap();
         let transfer_id = Uuid::new_v4();
@@ -390,7 +636,7 @@ mod tests {
     fn test_account_debit() {
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         
         // First credit some money
         let credit_amount = Amount::new(Decimal::new(100, 0)).unwrap();
@@ -410,7 +656,7 @@ mod tests {
     fn test_account_insufficient_balance() {
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         
         let amount = Amount::new(Decimal::new(100, 0)).unwrap();
         let result = account.debit(&amount, Uuid::new_v4(), "Too much".to_string());
@@ -422,7 +668,7 @@ mod tests {
     fn test_account_frozen() {
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         
         // Freeze account
         let freeze_event = account.freeze("Suspicious activity".to_string()).unwrap();
@@ -444,7 +690,7 @@ mod tests {
     fn test_account_unfreeze() {
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         
         // Freeze then unfreeze
         let freeze_event = account.freeze("Test".to_string()).unwrap();
@@ -457,11 +703,35 @@ mod tests {
         assert_eq!(account.status(), &AccountStatus::Active);
     }
 
+    #[test]
+    fn test_account_close() {
+        let (account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        let amount = Amount::new(Decimal::new(100, 0)).unwrap();
+        let credit_event = account.credit(&amount, Uuid::new_v4(), "Credit".to_string()).unwrap();
+        let account = account.apply(credit_event);
+
+        // A remaining balance must be swept first
+        assert!(account.close(None, None).is_err());
+        let debit_event = account.debit(&amount, Uuid::new_v4(), "Sweep".to_string()).unwrap();
+        let account = account.apply(debit_event);
+
+        let close_event = account.close(Some("Customer request".to_string()), None).unwrap();
+        let account = account.apply(close_event);
+        assert!(account.is_closed());
+
+        assert!(matches!(
+            account.credit(&amount, Uuid::new_v4(), "Credit".to_string()),
+            Err(AppError::AccountClosed(_))
+        ));
+        assert!(matches!(account.freeze("Test".to_string()), Err(AppError::AccountClosed(_))));
+        assert!(account.close(None, None).is_err());
+    }
+
     #[test]
     fn test_should_snapshot() {
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
-        let (mut account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (mut account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         
         // Version 1 - no snapshot
         assert!(!account.should_snapshot());
@@ -476,4 +746,292 @@ mod tests {
         account.version = 200;
         assert!(account.should_snapshot());
     }
+
+    #[test]
+    fn test_hold_reduces_available_balance() {
+        let (account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        let credit = Amount::new(Decimal::new(100, 0)).unwrap();
+        let event = account.credit(&credit, Uuid::new_v4(), "Credit".to_string()).unwrap();
+        let account = account.apply(event);
+        
+        let hold_id = Uuid::new_v4();
+        let held = Amount::new(Decimal::new(60, 0)).unwrap();
+        let event = account.hold(&held, hold_id, Uuid::new_v4(), "Hold".to_string()).unwrap();
+        let account = account.apply(event);
+        
+        assert_eq!(account.balance().value(), Decimal::new(100, 0));
+        assert_eq!(account.available_balance(), Decimal::new(40, 0));
+        
+        // Held funds cannot be spent
+        let debit = Amount::new(Decimal::new(50, 0)).unwrap();
+        assert!(matches!(
+            account.debit(&debit, Uuid::new_v4(), "Debit".to_string()),
+            Err(AppError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            account.hold(&debit, Uuid::new_v4(), Uuid::new_v4(), "Hold".to_string()),
+            Err(AppError::InsufficientBalance)
+        ));
+    }
+    
+    #[test]
+    fn test_hold_capture_and_release() {
+        let (account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        let credit = Amount::new(Decimal::new(100, 0)).unwrap();
+        let event = account.credit(&credit, Uuid::new_v4(), "Credit".to_string()).unwrap();
+        let account = account.apply(event);
+        
+        let held = Amount::new(Decimal::new(30, 0)).unwrap();
+        let capture_id = Uuid::new_v4();
+        let release_id = Uuid::new_v4();
+        let event = account.hold(&held, capture_id, Uuid::new_v4(), "A".to_string()).unwrap();
+        let account = account.apply(event);
+        let event = account.hold(&held, release_id, Uuid::new_v4(), "B".to_string()).unwrap();
+        let account = account.apply(event);
+        assert_eq!(account.available_balance(), Decimal::new(40, 0));
+        
+        // Capture debits the ledger balance
+        let event = account.capture_hold(capture_id, Uuid::new_v4()).unwrap();
+        let account = account.apply(event);
+        assert_eq!(account.balance().value(), Decimal::new(70, 0));
+        assert_eq!(account.available_balance(), Decimal::new(40, 0));
+        
+        // Release restores available balance without touching the ledger balance
+        let event = account.release_hold(release_id).unwrap();
+        let account = account.apply(event);
+        assert_eq!(account.balance().value(), Decimal::new(70, 0));
+        assert_eq!(account.available_balance(), Decimal::new(70, 0));
+        
+        assert!(matches!(account.release_hold(release_id), Err(AppError::HoldNotFound(_))));
+        assert!(matches!(
+            account.capture_hold(capture_id, Uuid::new_v4()),
+            Err(AppError::HoldNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_mint_source_may_go_negative() {
+        let (account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::MintSource);
+        let amount = Amount::new(Decimal::new(100, 0)).unwrap();
+        
+        let event = account.debit(&amount, Uuid::new_v4(), "Mint".to_string()).unwrap();
+        let account = account.apply(event);
+        assert_eq!(account.balance().value(), Decimal::new(-100, 0));
+        
+        // Credits apply while the balance stays negative
+        let credit = Amount::new(Decimal::new(40, 0)).unwrap();
+        let event = account.credit(&credit, Uuid::new_v4(), "Burn".to_string()).unwrap();
+        let account = account.apply(event);
+        assert_eq!(account.balance().value(), Decimal::new(-60, 0));
+        
+        let (wallet, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        assert!(matches!(
+            wallet.debit(&amount, Uuid::new_v4(), "Transfer".to_string()),
+            Err(AppError::InsufficientBalance)
+        ));
+    }
+
+    #[test]
+    fn test_from_db_state_rejects_unknown_type() {
+        let account = Account::from_db_state(Uuid::new_v4(), Uuid::new_v4(), "mint_source", Decimal::new(-5, 0), 3)
+            .unwrap();
+        assert_eq!(account.account_type(), AccountType::MintSource);
+        
+        assert!(matches!(
+            Account::from_db_state(Uuid::new_v4(), Uuid::new_v4(), "savings", Decimal::ZERO, 0),
+            Err(DomainError::UnknownAccountType(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_db_state_rejects_negative_user_wallet() {
+        let result = Account::from_db_state(Uuid::new_v4(), Uuid::new_v4(), "user_wallet", Decimal::new(-1, 2), 7);
+        assert!(matches!(result, Err(DomainError::InvariantViolation(_))));
+
+        let result = Account::from_db_state(Uuid::new_v4(), Uuid::new_v4(), "fee_income", Decimal::new(-100, 0), 2);
+        assert!(matches!(result, Err(DomainError::InvariantViolation(_))));
+    }
+}
+
+// =========================================================================
+// Property-based invariant tests
+// =========================================================================
+
+#[cfg(test)]
+mod proptests {
+    use super::*;
+    use proptest::prelude::*;
+
+    /// A command against the account; hold indexes pick among active holds
+    #[derive(Debug, Clone)]
+    enum Command {
+        Credit(Decimal),
+        Debit(Decimal),
+        Hold(Decimal),
+        Capture(usize),
+        Release(usize),
+        Freeze,
+        Unfreeze,
+    }
+
+    fn amount() -> impl Strategy<Value = Decimal> {
+        // 0.0001 to 10,000,000 with 4 decimal places
+        (1i64..=100_000_000_000).prop_map(|units| Decimal::new(units, 4))
+    }
+
+    fn account_type() -> impl Strategy<Value = AccountType> {
+        proptest::sample::select(AccountType::ALL.to_vec())
+    }
+
+    fn command() -> impl Strategy<Value = Command> {
+        prop_oneof![
+            3 => amount().prop_map(Command::Credit),
+            3 => amount().prop_map(Command::Debit),
+            2 => amount().prop_map(Command::Hold),
+            1 => any::<usize>().prop_map(Command::Capture),
+            1 => any::<usize>().prop_map(Command::Release),
+            1 => Just(Command::Freeze),
+            1 => Just(Command::Unfreeze),
+        ]
+    }
+
+    /// Event the account emits for a command, if the command is allowed
+    fn decide(account: &Account, command: &Command) -> Option<AccountEvent> {
+        let active_hold = |index: usize| {
+            let holds: Vec<Uuid> = account.holds.keys().copied().collect();
+            (!holds.is_empty()).then(|| holds[index % holds.len()])
+        };
+        let result = match command {
+            Command::Credit(value) => account.credit(&Amount::new(*value).ok()?, Uuid::new_v4(), String::new()),
+            Command::Debit(value) => account.debit(&Amount::new(*value).ok()?, Uuid::new_v4(), String::new()),
+            Command::Hold(value) => {
+                account.hold(&Amount::new(*value).ok()?, Uuid::new_v4(), Uuid::new_v4(), String::new())
+            }
+            Command::Capture(index) => account.capture_hold(active_hold(*index)?, Uuid::new_v4()),
+            Command::Release(index) => account.release_hold(active_hold(*index)?),
+            Command::Freeze => account.freeze(String::new()),
+            Command::Unfreeze => account.unfreeze(),
+        };
+        result.ok()
+    }
+
+    /// Run commands, applying every event they produce
+    fn run(account_type: AccountType, commands: &[Command]) -> Account {
+        let (mut account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), account_type);
+        for command in commands {
+            if let Some(event) = decide(&account, command) {
+                account = account.apply(event);
+            }
+        }
+        account
+    }
+
+    proptest! {
+        #[test]
+        fn commands_preserve_invariants(
+            account_type in account_type(),
+            commands in prop::collection::vec(command(), 0..60),
+        ) {
+            let (mut account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), account_type);
+            for command in &commands {
+                let Some(event) = decide(&account, command) else { continue };
+                let version = account.version();
+                account = account.apply(event);
+
+                prop_assert_eq!(account.version(), version + 1);
+                prop_assert_eq!(account.check_invariants(), Ok(()));
+                if !account_type.may_go_negative() {
+                    prop_assert!(account.balance().value() >= Decimal::ZERO);
+                    prop_assert!(account.available_balance() >= Decimal::ZERO);
+                }
+            }
+        }
+
+        #[test]
+        fn replay_never_takes_wallet_negative(
+            events in prop::collection::vec((any::<bool>(), amount()), 0..60),
+        ) {
+            // Raw events bypass the command checks, as a corrupt stream would
+            let (mut account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+            for (index, (is_credit, amount)) in events.into_iter().enumerate() {
+                let event = if is_credit {
+                    AccountEvent::MoneyCredited {
+                        account_id: account.id(),
+                        amount,
+                        transfer_id: Uuid::new_v4(),
+                        description: String::new(),
+                        credited_at: Utc::now(),
+                        category: None,
+                    }
+                } else {
+                    AccountEvent::MoneyDebited {
+                        account_id: account.id(),
+                        amount,
+                        transfer_id: Uuid::new_v4(),
+                        description: String::new(),
+                        debited_at: Utc::now(),
+                        category: None,
+                    }
+                };
+                account = account.apply(event);
+
+                prop_assert_eq!(account.version(), index as i64 + 2);
+                prop_assert!(account.balance().value() >= Decimal::ZERO);
+                prop_assert_eq!(account.check_invariants(), Ok(()));
+            }
+        }
+
+        #[test]
+        fn credit_then_debit_restores_balance(
+            account_type in account_type(),
+            commands in prop::collection::vec(command(), 0..40),
+            value in amount(),
+        ) {
+            let mut account = run(account_type, &commands);
+            if let Ok(event) = account.unfreeze() {
+                account = account.apply(event);
+            }
+            let balance = account.balance().value();
+            let available = account.available_balance();
+            let version = account.version();
+
+            let amount = Amount::new(value).unwrap();
+            let credit = account.credit(&amount, Uuid::new_v4(), String::new()).unwrap();
+            let account = account.apply(credit);
+            let debit = account.debit(&amount, Uuid::new_v4(), String::new()).unwrap();
+            let account = account.apply(debit);
+
+            prop_assert_eq!(account.balance().value(), balance);
+            prop_assert_eq!(account.available_balance(), available);
+            prop_assert_eq!(account.version(), version + 2);
+        }
+    }
+
+    #[test]
+    fn test_check_invariants_detects_corrupt_state() {
+        let (mut account, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        account.balance = Balance::from_decimal_unchecked(Decimal::new(-1, 0));
+        assert!(matches!(
+            account.check_invariants(),
+            Err(AccountInvariantViolation::NegativeBalance { .. })
+        ));
+
+        account.account_type = AccountType::MintSource;
+        assert_eq!(account.check_invariants(), Ok(()));
+
+        account.account_type = AccountType::UserWallet;
+        account.balance = Balance::from_decimal_unchecked(Decimal::new(10, 0));
+        account.holds.insert(Uuid::new_v4(), Decimal::new(11, 0));
+        assert!(matches!(
+            account.check_invariants(),
+            Err(AccountInvariantViolation::HoldsExceedBalance { .. })
+        ));
+
+        account.holds.clear();
+        account.holds.insert(Uuid::new_v4(), Decimal::ZERO);
+        assert!(matches!(
+            account.check_invariants(),
+            Err(AccountInvariantViolation::NonPositiveHold { .. })
+        ));
+    }
 }
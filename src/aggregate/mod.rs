@@ -3,10 +3,12 @@
 //! Aggregate Root pattern implementation for Event Sourcing.
 
 pub mod account;
+pub mod transfer;
 pub mod user;
 
-pub use account::Account;
-pub use user::User;
+pub use account::{Account, AccountInvariantViolation};
+pub use transfer::{Transfer, TransferStatus};
+pub use user::{anonymized_email, User};
 
 /// Aggregate trait that all aggregates must implement
 pub trait Aggregate: Sized + Default {
@@ -0,0 +1,702 @@
+//! Transfer Aggregate
+//!
+//! Transfer tracks the lifecycle of a single ATP transfer (initiated → completed/failed).
+//! Account balances are still owned by the Account aggregate; this aggregate records
+//! the outcome so that failed transfers remain queryable.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{Amount, TransferEvent, TransferFailureReason};
+use crate::error::AppError;
+
+use super::{Account, Aggregate};
+
+/// Transfer status
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransferStatus {
+    #[default]
+    Pending,
+    /// Above the approval threshold, waiting for a second API key
+    PendingApproval,
+    Completed,
+    Failed,
+    Reversed,
+    Rejected,
+}
+
+impl TransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Pending => "pending",
+            TransferStatus::PendingApproval => "pending_approval",
+            TransferStatus::Completed => "completed",
+            TransferStatus::Failed => "failed",
+            TransferStatus::Reversed => "reversed",
+            TransferStatus::Rejected => "rejected",
+        }
+    }
+}
+
+/// Transfer Aggregate
+///
+/// Represents one transfer between two accounts.
+/// State is derived from events, never directly mutated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transfer {
+    /// Unique transfer ID
+    id: Uuid,
+
+    /// Source account
+    from_account_id: Uuid,
+
+    /// Destination account
+    to_account_id: Uuid,
+
+    /// Sender user ID
+    from_user_id: Uuid,
+
+    /// Recipient user ID
+    to_user_id: Uuid,
+
+    /// Transferred amount
+    amount: Decimal,
+
+    /// Optional memo
+    memo: Option<String>,
+
+    /// Who initiated the transfer
+    initiated_by: Uuid,
+
+    /// Transfer status
+    status: TransferStatus,
+
+    /// Failure reason (only set when status is Failed)
+    failure_reason: Option<TransferFailureReason>,
+
+    /// Current version
+    version: i64,
+
+    /// When the transfer was initiated
+    initiated_at: Option<DateTime<Utc>>,
+
+    /// When the transfer completed or failed
+    finished_at: Option<DateTime<Utc>>,
+
+    /// Original transfer when this transfer is a reversal
+    #[serde(default)]
+    reversal_of: Option<Uuid>,
+
+    /// Compensating transfer that reversed this one
+    #[serde(default)]
+    reversed_by_transfer_id: Option<Uuid>,
+
+    /// API key that initiated a transfer sent for approval
+    #[serde(default)]
+    approval_requested_by: Option<Uuid>,
+
+    /// API key that approved or rejected the transfer
+    #[serde(default)]
+    approval_decided_by: Option<Uuid>,
+
+    /// When a transfer pending approval expires
+    #[serde(default)]
+    approval_expires_at: Option<DateTime<Utc>>,
+
+    /// Reporting category
+    #[serde(default)]
+    category: Option<String>,
+}
+
+impl Default for Transfer {
+    fn default() -> Self {
+        Self {
+            id: Uuid::nil(),
+            from_account_id: Uuid::nil(),
+            to_account_id: Uuid::nil(),
+            from_user_id: Uuid::nil(),
+            to_user_id: Uuid::nil(),
+            amount: Decimal::ZERO,
+            memo: None,
+            initiated_by: Uuid::nil(),
+            status: TransferStatus::Pending,
+            failure_reason: None,
+            version: 0,
+            initiated_at: None,
+            finished_at: None,
+            reversal_of: None,
+            reversed_by_transfer_id: None,
+            approval_requested_by: None,
+            approval_decided_by: None,
+            approval_expires_at: None,
+            category: None,
+        }
+    }
+}
+
+impl Transfer {
+    /// Initiate a new transfer and generate the initiation event
+    pub fn initiate(
+        transfer_id: Uuid,
+        from_account: &Account,
+        to_account: &Account,
+        amount: &Amount,
+        memo: Option<String>,
+        category: Option<String>,
+        initiated_by: Uuid,
+    ) -> (Self, TransferEvent) {
+        let event = TransferEvent::TransferInitiated {
+            transfer_id,
+            from_account_id: from_account.id(),
+            to_account_id: to_account.id(),
+            from_user_id: from_account.user_id(),
+            to_user_id: to_account.user_id(),
+            amount: amount.value(),
+            memo,
+            initiated_by,
+            initiated_at: Utc::now(),
+            reversal_of: None,
+            category,
+        };
+
+        let transfer = Self::default().apply(event.clone());
+
+        (transfer, event)
+    }
+
+    /// Initiate the compensating transfer for `original`: money flows from the
+    /// original recipient (`from_account`) back to the original sender (`to_account`)
+    pub fn initiate_reversal(
+        reversal_id: Uuid,
+        original: &Transfer,
+        from_account: &Account,
+        to_account: &Account,
+        initiated_by: Uuid,
+    ) -> (Self, TransferEvent) {
+        let event = TransferEvent::TransferInitiated {
+            transfer_id: reversal_id,
+            from_account_id: from_account.id(),
+            to_account_id: to_account.id(),
+            from_user_id: from_account.user_id(),
+            to_user_id: to_account.user_id(),
+            amount: original.amount,
+            memo: Some(format!("Reversal of {}", original.id)),
+            initiated_by,
+            initiated_at: Utc::now(),
+            reversal_of: Some(original.id),
+            category: None,
+        };
+
+        let transfer = Self::default().apply(event.clone());
+
+        (transfer, event)
+    }
+
+    /// Mark the transfer as completed
+    pub fn complete(&self) -> Result<TransferEvent, AppError> {
+        if self.status != TransferStatus::Pending {
+            return Err(AppError::InvalidRequest(format!(
+                "Transfer is already {}",
+                self.status.as_str()
+            )));
+        }
+
+        Ok(TransferEvent::TransferCompleted {
+            transfer_id: self.id,
+            completed_at: Utc::now(),
+        })
+    }
+
+    /// Hold a newly initiated transfer for approval because its amount is
+    /// above `threshold`; it expires at `expires_at` unless decided before
+    pub fn request_approval(
+        &self,
+        threshold: Decimal,
+        requested_by_api_key_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<TransferEvent, AppError> {
+        if self.status != TransferStatus::Pending {
+            return Err(AppError::InvalidRequest(format!(
+                "Transfer is already {}",
+                self.status.as_str()
+            )));
+        }
+
+        Ok(TransferEvent::TransferApprovalRequested {
+            transfer_id: self.id,
+            threshold,
+            requested_by_api_key_id,
+            requested_at: Utc::now(),
+            expires_at: Some(expires_at),
+        })
+    }
+
+    /// Approve a pending transfer. The approver must use a different API key
+    /// than the one that initiated it. Approved transfers return to Pending
+    /// and are completed or failed like any other transfer.
+    pub fn approve(&self, approved_by_api_key_id: Uuid) -> Result<TransferEvent, AppError> {
+        self.check_approver(approved_by_api_key_id)?;
+
+        Ok(TransferEvent::TransferApproved {
+            transfer_id: self.id,
+            approved_by_api_key_id,
+            approved_at: Utc::now(),
+        })
+    }
+
+    /// Reject a pending transfer (same four-eyes rule as `approve`)
+    pub fn reject(
+        &self,
+        rejected_by_api_key_id: Uuid,
+        reason: Option<String>,
+    ) -> Result<TransferEvent, AppError> {
+        self.check_approver(rejected_by_api_key_id)?;
+
+        Ok(TransferEvent::TransferRejected {
+            transfer_id: self.id,
+            rejected_by_api_key_id,
+            reason,
+            rejected_at: Utc::now(),
+        })
+    }
+
+    fn check_approver(&self, api_key_id: Uuid) -> Result<(), AppError> {
+        if self.status != TransferStatus::PendingApproval {
+            return Err(AppError::InvalidRequest(format!(
+                "Transfer is not pending approval (transfer is {})",
+                self.status.as_str()
+            )));
+        }
+
+        if let Some(expires_at) = self.approval_expires_at.filter(|t| *t <= Utc::now()) {
+            return Err(AppError::InvalidRequest(format!(
+                "Transfer approval expired at {}",
+                expires_at.to_rfc3339()
+            )));
+        }
+
+        if self.approval_requested_by == Some(api_key_id) {
+            return Err(AppError::Forbidden(
+                "Transfer must be approved by a different API key than the initiator".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Fail a transfer whose approval expired before `now`
+    pub fn expire(&self, now: DateTime<Utc>) -> Result<TransferEvent, AppError> {
+        if self.status != TransferStatus::PendingApproval {
+            return Err(AppError::InvalidRequest(format!(
+                "Transfer is not pending approval (transfer is {})",
+                self.status.as_str()
+            )));
+        }
+
+        if self.approval_expires_at.is_none_or(|t| t > now) {
+            return Err(AppError::InvalidRequest("Transfer approval has not expired".to_string()));
+        }
+
+        Ok(TransferEvent::TransferFailed {
+            transfer_id: self.id,
+            reason: TransferFailureReason::Expired,
+            failed_at: now,
+        })
+    }
+
+    /// Mark the transfer as failed
+    pub fn fail(&self, reason: TransferFailureReason) -> Result<TransferEvent, AppError> {
+        if self.status != TransferStatus::Pending {
+            return Err(AppError::InvalidRequest(format!(
+                "Transfer is already {}",
+                self.status.as_str()
+            )));
+        }
+
+        Ok(TransferEvent::TransferFailed {
+            transfer_id: self.id,
+            reason,
+            failed_at: Utc::now(),
+        })
+    }
+
+    /// Mark a completed transfer as reversed by `reversal_transfer_id`
+    pub fn reverse(
+        &self,
+        reversal_transfer_id: Uuid,
+        reason: Option<String>,
+        reversed_by: Uuid,
+    ) -> Result<TransferEvent, AppError> {
+        match self.status {
+            TransferStatus::Completed => {}
+            TransferStatus::Reversed => {
+                return Err(AppError::InvalidRequest(format!(
+                    "Transfer {} has already been reversed",
+                    self.id
+                )));
+            }
+            _ => {
+                return Err(AppError::InvalidRequest(format!(
+                    "Only completed transfers can be reversed (transfer is {})",
+                    self.status.as_str()
+                )));
+            }
+        }
+
+        Ok(TransferEvent::TransferReversed {
+            transfer_id: self.id,
+            reversal_transfer_id,
+            reason,
+            reversed_by,
+            reversed_at: Utc::now(),
+        })
+    }
+
+    // =========================================================================
+    // Getters
+    // =========================================================================
+
+    pub fn from_account_id(&self) -> Uuid {
+        self.from_account_id
+    }
+
+    pub fn to_account_id(&self) -> Uuid {
+        self.to_account_id
+    }
+
+    pub fn from_user_id(&self) -> Uuid {
+        self.from_user_id
+    }
+
+    pub fn to_user_id(&self) -> Uuid {
+        self.to_user_id
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn memo(&self) -> Option<&str> {
+        self.memo.as_deref()
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn initiated_by(&self) -> Uuid {
+        self.initiated_by
+    }
+
+    pub fn status(&self) -> &TransferStatus {
+        &self.status
+    }
+
+    pub fn failure_reason(&self) -> Option<&TransferFailureReason> {
+        self.failure_reason.as_ref()
+    }
+
+    pub fn initiated_at(&self) -> Option<DateTime<Utc>> {
+        self.initiated_at
+    }
+
+    pub fn finished_at(&self) -> Option<DateTime<Utc>> {
+        self.finished_at
+    }
+
+    pub fn reversal_of(&self) -> Option<Uuid> {
+        self.reversal_of
+    }
+
+    pub fn reversed_by_transfer_id(&self) -> Option<Uuid> {
+        self.reversed_by_transfer_id
+    }
+
+    pub fn approval_requested_by(&self) -> Option<Uuid> {
+        self.approval_requested_by
+    }
+
+    pub fn approval_decided_by(&self) -> Option<Uuid> {
+        self.approval_decided_by
+    }
+
+    pub fn approval_expires_at(&self) -> Option<DateTime<Utc>> {
+        self.approval_expires_at
+    }
+}
+
+impl Aggregate for Transfer {
+    type Event = TransferEvent;
+
+    fn aggregate_type() -> &'static str {
+        "Transfer"
+    }
+
+    fn id(&self) -> Uuid {
+        self.id
+    }
+
+    fn version(&self) -> i64 {
+        self.version
+    }
+
+    fn apply(mut self, event: Self::Event) -> Self {
+        match event {
+            TransferEvent::TransferInitiated {
+                transfer_id,
+                from_account_id,
+                to_account_id,
+                from_user_id,
+                to_user_id,
+                amount,
+                memo,
+                initiated_by,
+                initiated_at,
+                reversal_of,
+                category,
+            } => {
+                self.id = transfer_id;
+                self.from_account_id = from_account_id;
+                self.to_account_id = to_account_id;
+                self.from_user_id = from_user_id;
+                self.to_user_id = to_user_id;
+                self.amount = amount;
+                self.memo = memo;
+                self.initiated_by = initiated_by;
+                self.status = TransferStatus::Pending;
+                self.initiated_at = Some(initiated_at);
+                self.reversal_of = reversal_of;
+                self.category = category;
+            }
+
+            TransferEvent::TransferCompleted { completed_at, .. } => {
+                self.status = TransferStatus::Completed;
+                self.finished_at = Some(completed_at);
+            }
+
+            TransferEvent::TransferFailed { reason, failed_at, .. } => {
+                self.status = TransferStatus::Failed;
+                self.failure_reason = Some(reason);
+                self.finished_at = Some(failed_at);
+            }
+
+            TransferEvent::TransferReversed { reversal_transfer_id, .. } => {
+                self.status = TransferStatus::Reversed;
+                self.reversed_by_transfer_id = Some(reversal_transfer_id);
+            }
+
+            TransferEvent::TransferApprovalRequested {
+                requested_by_api_key_id,
+                expires_at,
+                ..
+            } => {
+                self.status = TransferStatus::PendingApproval;
+                self.approval_requested_by = requested_by_api_key_id;
+                self.approval_expires_at = expires_at;
+            }
+
+            TransferEvent::TransferApproved {
+                approved_by_api_key_id,
+                ..
+            } => {
+                self.status = TransferStatus::Pending;
+                self.approval_decided_by = Some(approved_by_api_key_id);
+            }
+
+            TransferEvent::TransferRejected {
+                rejected_by_api_key_id,
+                rejected_at,
+                ..
+            } => {
+                self.status = TransferStatus::Rejected;
+                self.approval_decided_by = Some(rejected_by_api_key_id);
+                self.finished_at = Some(rejected_at);
+            }
+        }
+
+        self.version += 1;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::AccountType;
+
+    fn accounts() -> (Account, Account) {
+        let (from, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        let (to, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        (from, to)
+    }
+
+    #[test]
+    fn test_transfer_initiate() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(100, 0)).unwrap();
+        let transfer_id = Uuid::new_v4();
+
+        let (transfer, event) = Transfer::initiate(
+            transfer_id,
+            &from,
+            &to,
+            &amount,
+            None,
+            Some("reward".to_string()),
+            from.user_id(),
+        );
+
+        assert_eq!(transfer.id(), transfer_id);
+        assert_eq!(transfer.category(), Some("reward"));
+        assert_eq!(transfer.from_account_id(), from.id());
+        assert_eq!(transfer.to_user_id(), to.user_id());
+        assert_eq!(transfer.amount(), Decimal::new(100, 0));
+        assert_eq!(transfer.status(), &TransferStatus::Pending);
+        assert_eq!(transfer.version(), 1);
+        assert!(matches!(event, TransferEvent::TransferInitiated { .. }));
+    }
+
+    #[test]
+    fn test_transfer_complete() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(100, 0)).unwrap();
+        let (transfer, _) =
+            Transfer::initiate(Uuid::new_v4(), &from, &to, &amount, None, None, from.user_id());
+
+        let event = transfer.complete().unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::Completed);
+        assert!(transfer.finished_at().is_some());
+        assert_eq!(transfer.version(), 2);
+        assert!(transfer.fail(TransferFailureReason::InternalError).is_err());
+    }
+
+    #[test]
+    fn test_transfer_fail() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(100, 0)).unwrap();
+        let (transfer, _) =
+            Transfer::initiate(Uuid::new_v4(), &from, &to, &amount, None, None, from.user_id());
+
+        let event = transfer.fail(TransferFailureReason::InsufficientBalance).unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::Failed);
+        assert_eq!(
+            transfer.failure_reason(),
+            Some(&TransferFailureReason::InsufficientBalance)
+        );
+        assert!(transfer.complete().is_err());
+    }
+
+    #[test]
+    fn test_transfer_reverse() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(100, 0)).unwrap();
+        let (transfer, _) =
+            Transfer::initiate(Uuid::new_v4(), &from, &to, &amount, None, None, from.user_id());
+
+        // Pending transfers cannot be reversed
+        assert!(transfer.reverse(Uuid::new_v4(), None, Uuid::new_v4()).is_err());
+
+        let completed = transfer.complete().unwrap();
+        let transfer = transfer.apply(completed);
+        let reversal_id = Uuid::new_v4();
+        let (reversal, event) =
+            Transfer::initiate_reversal(reversal_id, &transfer, &to, &from, Uuid::new_v4());
+
+        assert_eq!(reversal.reversal_of(), Some(transfer.id()));
+        assert_eq!(reversal.from_account_id(), to.id());
+        assert_eq!(reversal.to_user_id(), from.user_id());
+        assert_eq!(reversal.amount(), transfer.amount());
+        assert!(matches!(
+            event,
+            TransferEvent::TransferInitiated { reversal_of: Some(_), .. }
+        ));
+
+        let event = transfer
+            .reverse(reversal_id, Some("Chargeback".to_string()), Uuid::new_v4())
+            .unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::Reversed);
+        assert_eq!(transfer.reversed_by_transfer_id(), Some(reversal_id));
+        // Double reversal is rejected
+        assert!(transfer.reverse(Uuid::new_v4(), None, Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_transfer_approval() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(5000, 0)).unwrap();
+        let (transfer, _) =
+            Transfer::initiate(Uuid::new_v4(), &from, &to, &amount, None, None, from.user_id());
+        let maker = Uuid::new_v4();
+        let checker = Uuid::new_v4();
+
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let event = transfer.request_approval(Decimal::new(1000, 0), Some(maker), expires_at).unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::PendingApproval);
+        assert_eq!(transfer.approval_expires_at(), Some(expires_at));
+        assert!(transfer.expire(Utc::now()).is_err());
+        assert!(transfer.complete().is_err());
+        // Four-eyes: the initiating key cannot approve or reject
+        assert!(matches!(transfer.approve(maker), Err(AppError::Forbidden(_))));
+        assert!(matches!(transfer.reject(maker, None), Err(AppError::Forbidden(_))));
+
+        let event = transfer.approve(checker).unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::Pending);
+        assert_eq!(transfer.approval_decided_by(), Some(checker));
+        assert!(transfer.approve(checker).is_err());
+        assert!(transfer.complete().is_ok());
+    }
+
+    #[test]
+    fn test_transfer_reject() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(5000, 0)).unwrap();
+        let (transfer, _) =
+            Transfer::initiate(Uuid::new_v4(), &from, &to, &amount, None, None, from.user_id());
+
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+        let event = transfer.request_approval(Decimal::new(1000, 0), Some(Uuid::new_v4()), expires_at).unwrap();
+        let transfer = transfer.apply(event);
+        let event = transfer.reject(Uuid::new_v4(), Some("Suspicious".to_string())).unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::Rejected);
+        assert!(transfer.finished_at().is_some());
+        assert!(transfer.complete().is_err());
+        assert!(transfer.approve(Uuid::new_v4()).is_err());
+    }
+
+    #[test]
+    fn test_transfer_approval_expiry() {
+        let (from, to) = accounts();
+        let amount = Amount::new(Decimal::new(5000, 0)).unwrap();
+        let (transfer, _) =
+            Transfer::initiate(Uuid::new_v4(), &from, &to, &amount, None, None, from.user_id());
+
+        let expires_at = Utc::now() - chrono::Duration::seconds(1);
+        let event = transfer.request_approval(Decimal::new(1000, 0), Some(Uuid::new_v4()), expires_at).unwrap();
+        let transfer = transfer.apply(event);
+
+        // Expired transfers can no longer be decided, only failed as expired
+        assert!(transfer.approve(Uuid::new_v4()).is_err());
+        assert!(transfer.reject(Uuid::new_v4(), None).is_err());
+
+        let event = transfer.expire(Utc::now()).unwrap();
+        let transfer = transfer.apply(event);
+
+        assert_eq!(transfer.status(), &TransferStatus::Failed);
+        assert_eq!(transfer.failure_reason(), Some(&TransferFailureReason::Expired));
+        assert!(transfer.expire(Utc::now()).is_err());
+    }
+}
@@ -12,16 +12,18 @@ use crate::error::AppError;
 use super::Aggregate;
 
 /// User status
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum UserStatus {
+    #[default]
     Active,
     Deactivated,
+    /// Deactivated and personal data scrubbed; cannot be reactivated
+    Anonymized,
 }
 
-impl Default for UserStatus {
-    fn default() -> Self {
-        Self::Active
-    }
+/// Placeholder email for anonymized users (unique and passes valid_email)
+pub fn anonymized_email(user_id: Uuid) -> String {
+    format!("anonymized+{}@anonymized.invalid", user_id.simple())
 }
 
 /// User Aggregate
@@ -112,7 +114,7 @@ impl User {
     
     /// Update user profile
     pub fn update(&self, changes: UserChanges) -> Result<UserEvent, AppError> {
-        if self.status == UserStatus::Deactivated {
+        if self.status != UserStatus::Active {
             return Err(AppError::UserNotFound(self.id.to_string()));
         }
         
@@ -130,7 +132,7 @@ impl User {
 
     /// Deactivate the user (soft delete)
     pub fn deactivate(&self, reason: Option<String>) -> Result<UserEvent, AppError> {
-        if self.status == UserStatus::Deactivated {
+        if self.status != UserStatus::Active {
             return Err(AppError::InvalidRequest("User is already deactivated".to_string()));
         }
         
@@ -153,6 +155,40 @@ impl User {
         })
     }
 
+    /// Scrub personal data (only after deactivation)
+    pub fn anonymize(&self, reason: Option<String>) -> Result<UserEvent, AppError> {
+        match self.status {
+            UserStatus::Deactivated => Ok(UserEvent::UserAnonymized {
+                user_id: self.id,
+                reason,
+                anonymized_at: Utc::now(),
+            }),
+            UserStatus::Active => Err(AppError::InvalidRequest(
+                "User must be deactivated before anonymization".to_string(),
+            )),
+            UserStatus::Anonymized => Err(AppError::InvalidRequest(
+                "User is already anonymized".to_string(),
+            )),
+        }
+    }
+
+    /// Change the username (admins only; users cannot rename themselves)
+    pub fn rename(&self, new_username: String) -> Result<UserEvent, AppError> {
+        if self.status == UserStatus::Anonymized {
+            return Err(AppError::InvalidRequest("Cannot rename an anonymized user".to_string()));
+        }
+        if new_username == self.username {
+            return Err(AppError::InvalidRequest("Username is unchanged".to_string()));
+        }
+
+        Ok(UserEvent::UserRenamed {
+            user_id: self.id,
+            old_username: self.username.clone(),
+            new_username,
+            renamed_at: Utc::now(),
+        })
+    }
+
     // =========================================================================
     // Getters
     // =========================================================================
@@ -242,6 +278,18 @@ impl Aggregate for User {
                 self.status = UserStatus::Active;
                 self.updated_at = Some(reactivated_at);
             }
+            
+            UserEvent::UserAnonymized { user_id, anonymized_at, .. } => {
+                self.email = anonymized_email(user_id);
+                self.display_name = None;
+                self.status = UserStatus::Anonymized;
+                self.updated_at = Some(anonymized_at);
+            }
+
+            UserEvent::UserRenamed { new_username, renamed_at, .. } => {
+                self.username = new_username;
+                self.updated_at = Some(renamed_at);
+            }
         }
         
         self.version += 1;
@@ -399,4 +447,57 @@ mod tests {
         let result = user.update(changes);
         assert!(matches!(result, Err(AppError::UserNotFound(_))));
     }
+
+    #[test]
+    fn test_user_anonymize() {
+        let user_id = Uuid::new_v4();
+        let (user, _) = User::create(
+            user_id,
+            "alice".to_string(),
+            "alice@example.com".to_string(),
+            Some("Alice Smith".to_string()),
+        );
+        
+        // Active users must be deactivated first
+        assert!(matches!(user.anonymize(None), Err(AppError::InvalidRequest(_))));
+        
+        let event = user.deactivate(None).unwrap();
+        let user = user.apply(event);
+        
+        let event = user.anonymize(Some("retention".to_string())).unwrap();
+        let user = user.apply(event);
+        
+        assert_eq!(user.status(), &UserStatus::Anonymized);
+        assert_eq!(user.email(), anonymized_email(user_id));
+        assert_eq!(user.display_name(), None);
+        
+        // Anonymization is final
+        assert!(user.anonymize(None).is_err());
+        assert!(user.reactivate().is_err());
+        assert!(user.deactivate(None).is_err());
+        assert!(user.rename("alice2".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_user_rename() {
+        let (user, _) = User::create(
+            Uuid::new_v4(),
+            "alice".to_string(),
+            "alice@example.com".to_string(),
+            None,
+        );
+
+        assert!(matches!(user.rename("alice".to_string()), Err(AppError::InvalidRequest(_))));
+
+        let event = user.rename("alice_smith".to_string()).unwrap();
+        assert!(matches!(
+            &event,
+            UserEvent::UserRenamed { old_username, new_username, .. }
+                if old_username == "alice" && new_username == "alice_smith"
+        ));
+
+        let user = user.apply(event);
+        assert_eq!(user.username(), "alice_smith");
+        assert_eq!(user.version(), 2);
+    }
 }
@@ -0,0 +1,423 @@
+//! Alerts
+//!
+//! Soft caps on unusual activity: completed transfers and mints are checked
+//! against the ALERT_* rules from the configuration, and every match is
+//! recorded in the alerts table and delivered to webhook endpoints
+//! subscribed to AlertRaised. Unlike transfer limits, nothing is blocked;
+//! the alerts give the risk team visibility.
+//!
+//! Rules are evaluated after the command's events are committed, so a
+//! failure to record an alert is logged and never fails the command.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Timelike, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+// =========================================================================
+// Rules
+// =========================================================================
+
+/// Kind of unusual activity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertRule {
+    /// A single transfer above ALERT_TRANSFER_AMOUNT
+    LargeTransfer,
+    /// More than ALERT_TRANSFERS_PER_MINUTE transfers from one user
+    TransferVelocity,
+    /// A mint outside ALERT_MINT_HOURS
+    MintOutsideHours,
+}
+
+impl AlertRule {
+    pub const ALL: [AlertRule; 3] = [
+        AlertRule::LargeTransfer,
+        AlertRule::TransferVelocity,
+        AlertRule::MintOutsideHours,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertRule::LargeTransfer => "large_transfer",
+            AlertRule::TransferVelocity => "transfer_velocity",
+            AlertRule::MintOutsideHours => "mint_outside_hours",
+        }
+    }
+}
+
+impl fmt::Display for AlertRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AlertRule {
+    type Err = AlertError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|rule| rule.as_str() == s)
+            .ok_or_else(|| AlertError::UnknownRule(s.to_string()))
+    }
+}
+
+/// Business hours as a range of whole UTC hours, e.g. "9-18" for 09:00 to
+/// 17:59. A range such as "22-6" wraps around midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusinessHours {
+    /// First hour inside business hours (0-23)
+    pub start: u32,
+    /// First hour after business hours (0-24)
+    pub end: u32,
+}
+
+impl BusinessHours {
+    /// Whether `at` falls inside business hours
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let hour = at.hour();
+        if self.start <= self.end {
+            (self.start..self.end).contains(&hour)
+        } else {
+            hour >= self.start || hour < self.end
+        }
+    }
+}
+
+impl fmt::Display for BusinessHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl FromStr for BusinessHours {
+    type Err = AlertError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AlertError::InvalidHours(s.to_string());
+        let (start, end) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let start: u32 = start.trim().parse().map_err(|_| invalid())?;
+        let end: u32 = end.trim().parse().map_err(|_| invalid())?;
+        if start > 23 || end > 24 || start == end {
+            return Err(invalid());
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Configured soft caps (a rule is disabled when unset)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AlertRules {
+    /// Transfers above this amount raise large_transfer
+    pub transfer_amount: Option<Decimal>,
+    /// More transfers than this from one user within a minute raise
+    /// transfer_velocity
+    pub transfers_per_minute: Option<i64>,
+    /// Mints outside these hours raise mint_outside_hours
+    pub mint_hours: Option<BusinessHours>,
+}
+
+impl AlertRules {
+    /// Rule matched by a transfer's amount
+    pub fn check_transfer_amount(&self, amount: Decimal) -> Option<AlertRule> {
+        self.transfer_amount
+            .filter(|threshold| amount > *threshold)
+            .map(|_| AlertRule::LargeTransfer)
+    }
+
+    /// Rule matched by the number of transfers a user made in the last
+    /// minute (including the one being checked)
+    pub fn check_transfer_count(&self, transfers_last_minute: i64) -> Option<AlertRule> {
+        self.transfers_per_minute
+            .filter(|max| transfers_last_minute > *max)
+            .map(|_| AlertRule::TransferVelocity)
+    }
+
+    /// Rule matched by the time of a mint
+    pub fn check_mint_time(&self, at: DateTime<Utc>) -> Option<AlertRule> {
+        self.mint_hours
+            .filter(|hours| !hours.contains(at))
+            .map(|_| AlertRule::MintOutsideHours)
+    }
+}
+
+// =========================================================================
+// Records
+// =========================================================================
+
+/// Recorded rule match
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct Alert {
+    pub id: Uuid,
+    pub rule: String,
+    /// Sender of the transfer, or recipient of the mint
+    pub user_id: Uuid,
+    /// Transfer or mint ID
+    pub resource_id: Uuid,
+    pub amount: Decimal,
+    /// Rule threshold and observed values
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Filters for GET /admin/alerts
+#[derive(Debug, Deserialize)]
+pub struct ListAlertsQuery {
+    #[serde(default)]
+    pub rule: Option<AlertRule>,
+    #[serde(default)]
+    pub user_id: Option<Uuid>,
+    #[serde(default = "crate::queries::default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+// =========================================================================
+// AlertService
+// =========================================================================
+
+/// Alert Service
+#[derive(Debug, Clone)]
+pub struct AlertService {
+    pool: PgPool,
+    rules: AlertRules,
+    webhooks: WebhookService,
+}
+
+impl AlertService {
+    pub fn new(pool: PgPool, rules: AlertRules) -> Self {
+        Self {
+            webhooks: WebhookService::new(pool.clone()),
+            pool,
+            rules,
+        }
+    }
+
+    /// Check a completed transfer against the transfer rules
+    pub async fn check_transfer(&self, from_user_id: Uuid, transfer_id: Uuid, amount: Decimal) {
+        if let Some(rule) = self.rules.check_transfer_amount(amount) {
+            self.raise(
+                rule,
+                from_user_id,
+                transfer_id,
+                amount,
+                json!({ "threshold": self.rules.transfer_amount }),
+            )
+            .await;
+        }
+
+        if self.rules.transfers_per_minute.is_some() {
+            match self.transfers_last_minute(from_user_id).await {
+                Ok(count) => {
+                    if let Some(rule) = self.rules.check_transfer_count(count) {
+                        self.raise(
+                            rule,
+                            from_user_id,
+                            transfer_id,
+                            amount,
+                            json!({
+                                "threshold": self.rules.transfers_per_minute,
+                                "transfers_last_minute": count,
+                            }),
+                        )
+                        .await;
+                    }
+                }
+                Err(e) => tracing::error!(user_id = %from_user_id, "Failed to count recent transfers: {}", e),
+            }
+        }
+    }
+
+    /// Check a completed mint against the mint rules
+    pub async fn check_mint(&self, recipient_user_id: Uuid, mint_id: Uuid, amount: Decimal) {
+        let now = Utc::now();
+        if let Some(rule) = self.rules.check_mint_time(now) {
+            self.raise(
+                rule,
+                recipient_user_id,
+                mint_id,
+                amount,
+                json!({
+                    "business_hours_utc": self.rules.mint_hours.map(|hours| hours.to_string()),
+                    "minted_at": now,
+                }),
+            )
+            .await;
+        }
+    }
+
+    /// Alerts newest first
+    pub async fn list(&self, query: &ListAlertsQuery) -> Result<Vec<Alert>, AlertError> {
+        let alerts = sqlx::query_as!(
+            Alert,
+            r#"
+            SELECT id, rule, user_id, resource_id, amount, details, created_at
+            FROM alerts
+            WHERE ($1::text IS NULL OR rule = $1)
+              AND ($2::uuid IS NULL OR user_id = $2)
+            ORDER BY created_at DESC, id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            query.rule.map(|rule| rule.as_str()),
+            query.user_id,
+            query.limit.clamp(1, 200),
+            query.offset.max(0)
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(alerts)
+    }
+
+    /// Transfers sent by the user over the last minute
+    async fn transfers_last_minute(&self, user_id: Uuid) -> Result<i64, AlertError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(DISTINCT e.journal_id) AS "count!"
+            FROM ledger_entries e
+            JOIN accounts a ON a.id = e.account_id
+            JOIN ledger_entries o ON o.journal_id = e.journal_id AND o.entry_type = 'credit'
+            JOIN accounts oa ON oa.id = o.account_id
+            WHERE a.user_id = $1
+              AND e.entry_type = 'debit'
+              AND e.created_at > NOW() - INTERVAL '1 minute'
+              AND oa.account_type = 'user_wallet'
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Record an alert and notify webhook endpoints; errors are logged
+    async fn raise(&self, rule: AlertRule, user_id: Uuid, resource_id: Uuid, amount: Decimal, details: serde_json::Value) {
+        let id = Uuid::new_v4();
+        tracing::warn!(alert_id = %id, rule = %rule, user_id = %user_id, resource_id = %resource_id, "Alert raised");
+
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO alerts (id, rule, user_id, resource_id, amount, details)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            id,
+            rule.as_str(),
+            user_id,
+            resource_id,
+            amount,
+            details
+        )
+        .execute(&self.pool)
+        .await;
+        if let Err(e) = inserted {
+            tracing::error!(alert_id = %id, rule = %rule, "Failed to record alert: {}", e);
+        }
+
+        self.webhooks
+            .notify(
+                WebhookEventType::AlertRaised,
+                json!({
+                    "alert_id": id,
+                    "rule": rule,
+                    "user_id": user_id,
+                    "resource_id": resource_id,
+                    "amount": amount,
+                    "details": details,
+                }),
+            )
+            .await;
+    }
+}
+
+/// Alert errors
+#[derive(Debug, thiserror::Error)]
+pub enum AlertError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Unknown alert rule: {0}")]
+    UnknownRule(String),
+
+    #[error("Invalid business hours: {0} (expected e.g. 9-18)")]
+    InvalidHours(String),
+}
+
+impl From<AlertError> for AppError {
+    fn from(e: AlertError) -> Self {
+        match e {
+            AlertError::Database(e) => AppError::Internal(e.to_string()),
+            e => AppError::InvalidRequest(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at_hour(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, hour, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn test_business_hours() {
+        let hours: BusinessHours = "9-18".parse().unwrap();
+        assert!(hours.contains(at_hour(9)));
+        assert!(hours.contains(at_hour(17)));
+        assert!(!hours.contains(at_hour(18)));
+        assert!(!hours.contains(at_hour(3)));
+
+        let overnight: BusinessHours = "22-6".parse().unwrap();
+        assert!(overnight.contains(at_hour(23)));
+        assert!(overnight.contains(at_hour(5)));
+        assert!(!overnight.contains(at_hour(12)));
+
+        for invalid in ["", "9", "9-9", "24-3", "9-25", "a-b"] {
+            assert!(invalid.parse::<BusinessHours>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_alert_rules() {
+        let rules = AlertRules {
+            transfer_amount: Some(Decimal::from(1000)),
+            transfers_per_minute: Some(5),
+            mint_hours: Some(BusinessHours { start: 9, end: 18 }),
+        };
+
+        assert_eq!(rules.check_transfer_amount(Decimal::from(1000)), None);
+        assert_eq!(
+            rules.check_transfer_amount(Decimal::from(1001)),
+            Some(AlertRule::LargeTransfer)
+        );
+        assert_eq!(rules.check_transfer_count(5), None);
+        assert_eq!(rules.check_transfer_count(6), Some(AlertRule::TransferVelocity));
+        assert_eq!(rules.check_mint_time(at_hour(10)), None);
+        assert_eq!(rules.check_mint_time(at_hour(2)), Some(AlertRule::MintOutsideHours));
+
+        let disabled = AlertRules::default();
+        assert_eq!(disabled.check_transfer_amount(Decimal::MAX), None);
+        assert_eq!(disabled.check_transfer_count(i64::MAX), None);
+        assert_eq!(disabled.check_mint_time(at_hour(2)), None);
+    }
+
+    #[test]
+    fn test_alert_rule_round_trip() {
+        for rule in AlertRule::ALL {
+            assert_eq!(rule.as_str().parse::<AlertRule>().unwrap(), rule);
+        }
+        assert!("unknown".parse::<AlertRule>().is_err());
+    }
+}
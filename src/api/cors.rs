@@ -0,0 +1,122 @@
+//! CORS
+//!
+//! Browser clients (the Next.js frontend in some deployments) call the API
+//! directly. Cross-origin access is off unless CORS_ALLOWED_ORIGINS is set;
+//! preflight requests are answered before authentication.
+
+use std::time::Duration;
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::config::ConfigError;
+
+/// Request headers browsers may send
+const ALLOWED_HEADERS: [&str; 7] = [
+    "content-type",
+    "authorization",
+    "x-api-key",
+    "x-request-user-id",
+    "idempotency-key",
+    "if-none-match",
+    "x-correlation-id",
+];
+
+/// Response headers browsers may read
+const EXPOSED_HEADERS: [&str; 10] = [
+    "etag",
+    "x-aggregate-version",
+    "x-correlation-id",
+    "x-next-cursor",
+    "x-receipt-signature",
+    "x-ratelimit-limit",
+    "x-ratelimit-burst",
+    "x-ratelimit-remaining",
+    "x-ratelimit-user-limit",
+    "x-ratelimit-user-remaining",
+];
+
+/// Origins allowed to call the API from a browser
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AllowedOrigins {
+    /// CORS_ALLOWED_ORIGINS=*
+    Any,
+    /// Exact origins, e.g. https://app.example.com
+    List(Vec<HeaderValue>),
+}
+
+/// Cross-origin settings (CORS_*)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorsConfig {
+    pub allowed_origins: AllowedOrigins,
+    /// How long browsers may cache a preflight response
+    pub max_age: Duration,
+}
+
+impl CorsConfig {
+    /// Parse CORS_ALLOWED_ORIGINS: `*` or a comma-separated list of origins
+    pub fn parse(origins: &str, max_age: Duration) -> Result<Self, ConfigError> {
+        let invalid = || ConfigError::InvalidValue("CORS_ALLOWED_ORIGINS");
+
+        let allowed_origins = if origins.trim() == "*" {
+            AllowedOrigins::Any
+        } else {
+            let origins = origins
+                .split(',')
+                .map(str::trim)
+                .filter(|origin| !origin.is_empty())
+                .map(|origin| {
+                    let origin = origin.trim_end_matches('/');
+                    if !(origin.starts_with("https://") || origin.starts_with("http://")) {
+                        return Err(invalid());
+                    }
+                    HeaderValue::from_str(origin).map_err(|_| invalid())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if origins.is_empty() {
+                return Err(invalid());
+            }
+            AllowedOrigins::List(origins)
+        };
+
+        Ok(Self { allowed_origins, max_age })
+    }
+
+    pub fn layer(&self) -> CorsLayer {
+        let origin = match &self.allowed_origins {
+            AllowedOrigins::Any => AllowOrigin::any(),
+            AllowedOrigins::List(origins) => AllowOrigin::list(origins.iter().cloned()),
+        };
+
+        CorsLayer::new()
+            .allow_origin(origin)
+            .allow_methods([Method::GET, Method::POST, Method::PUT, Method::PATCH, Method::DELETE])
+            .allow_headers(ALLOWED_HEADERS.map(HeaderName::from_static))
+            .expose_headers(EXPOSED_HEADERS.map(HeaderName::from_static))
+            .max_age(self.max_age)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_allowed_origins() {
+        let max_age = Duration::from_secs(600);
+
+        assert_eq!(CorsConfig::parse("*", max_age).unwrap().allowed_origins, AllowedOrigins::Any);
+        assert_eq!(
+            CorsConfig::parse("https://app.example.com/, http://localhost:3000", max_age)
+                .unwrap()
+                .allowed_origins,
+            AllowedOrigins::List(vec![
+                HeaderValue::from_static("https://app.example.com"),
+                HeaderValue::from_static("http://localhost:3000"),
+            ])
+        );
+
+        assert!(CorsConfig::parse("app.example.com", max_age).is_err());
+        assert!(CorsConfig::parse(" , ", max_age).is_err());
+    }
+}
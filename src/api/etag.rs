@@ -0,0 +1,88 @@
+//! Conditional GETs
+//!
+//! Read endpoints polled by the frontend tag their responses with a weak
+//! ETag built from the versions of the rows behind them (wallet aggregate
+//! versions, users.updated_at). A request whose If-None-Match lists the
+//! current tag gets 304 Not Modified without a body; where the version is
+//! cheaper to read than the body, the body is not loaded at all.
+
+use std::fmt;
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+/// Weak ETag for a version tag, e.g. `W/"12.3"`
+pub fn weak(tag: impl fmt::Display) -> HeaderValue {
+    HeaderValue::from_str(&format!("W/\"{}\"", tag)).expect("version tags are visible ASCII")
+}
+
+/// Whether If-None-Match lists `etag` (weak comparison, RFC 9110 13.1.2)
+pub fn is_fresh(headers: &HeaderMap, etag: &HeaderValue) -> bool {
+    let Ok(etag) = etag.to_str() else {
+        return false;
+    };
+    let etag = opaque_tag(etag);
+
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || opaque_tag(candidate) == etag)
+}
+
+/// 304 Not Modified for `etag`, or the body tagged with it
+pub fn respond(headers: &HeaderMap, etag: HeaderValue, body: impl IntoResponse) -> Response {
+    if is_fresh(headers, &etag) {
+        return not_modified(etag);
+    }
+    ([(header::ETAG, etag)], body).into_response()
+}
+
+/// 304 Not Modified carrying the current ETag
+pub fn not_modified(etag: HeaderValue) -> Response {
+    (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response()
+}
+
+fn opaque_tag(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn if_none_match(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn test_is_fresh() {
+        let etag = weak("12.3");
+        assert_eq!(etag, "W/\"12.3\"");
+
+        assert!(is_fresh(&if_none_match("W/\"12.3\""), &etag));
+        assert!(is_fresh(&if_none_match("\"12.3\""), &etag));
+        assert!(is_fresh(&if_none_match("\"1\", W/\"12.3\""), &etag));
+        assert!(is_fresh(&if_none_match("*"), &etag));
+
+        assert!(!is_fresh(&if_none_match("W/\"12.4\""), &etag));
+        assert!(!is_fresh(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn test_respond() {
+        let fresh = respond(&if_none_match("W/\"7\""), weak(7), "body");
+        assert_eq!(fresh.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(fresh.headers()[header::ETAG], "W/\"7\"");
+
+        let stale = respond(&if_none_match("W/\"6\""), weak(7), "body");
+        assert_eq!(stale.status(), StatusCode::OK);
+        assert_eq!(stale.headers()[header::ETAG], "W/\"7\"");
+    }
+}
@@ -0,0 +1,251 @@
+//! Bearer Token Authentication
+//!
+//! Validates `Authorization: Bearer <JWT>` tokens signed with a shared
+//! secret (HS256/384/512) or with a key published at a JWKS endpoint, and
+//! maps their claims to permissions and the acting user.
+//!
+//! Token claims:
+//! - `sub`: the acting user when it is a UUID (user tokens); any other value
+//!   marks a service token that may set X-Request-User-Id itself
+//! - `permissions` (array) and/or `scope` (space separated): permissions
+//! - `exp`: required; `iss` / `aud` are checked when configured
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::config::Config;
+
+/// api_keys row that bearer-token requests are attributed to (migration 018),
+/// used for rate limiting and audit logs
+pub const JWT_PRINCIPAL_ID: Uuid = Uuid::from_u128(0xa000_0000_0000_0000_0000_0000_0000_0002);
+
+/// Minimum time between JWKS refreshes triggered by unknown key IDs
+const JWKS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Claims read from a bearer token
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtClaims {
+    pub sub: String,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default)]
+    pub scope: Option<String>,
+    pub exp: i64,
+}
+
+impl JwtClaims {
+    /// `permissions` claim plus the entries of the `scope` claim
+    pub fn all_permissions(&self) -> Vec<String> {
+        let mut permissions = self.permissions.clone();
+        for scope in self.scope.iter().flat_map(|s| s.split_whitespace()) {
+            if !permissions.iter().any(|p| p == scope) {
+                permissions.push(scope.to_string());
+            }
+        }
+        permissions
+    }
+
+    /// The acting user for user tokens (None for service tokens)
+    pub fn user_id(&self) -> Option<Uuid> {
+        Uuid::parse_str(&self.sub).ok()
+    }
+}
+
+/// JWKS fetched from the configured endpoint
+struct CachedJwks {
+    keys: JwkSet,
+    fetched_at: Instant,
+}
+
+/// Validates bearer tokens
+pub struct JwtValidator {
+    secret: Option<DecodingKey>,
+    jwks_url: Option<String>,
+    jwks: RwLock<Option<CachedJwks>>,
+    issuer: Option<String>,
+    audience: Option<String>,
+    http: reqwest::Client,
+}
+
+impl fmt::Debug for JwtValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JwtValidator")
+            .field("shared_secret", &self.secret.is_some())
+            .field("jwks_url", &self.jwks_url)
+            .field("issuer", &self.issuer)
+            .field("audience", &self.audience)
+            .finish()
+    }
+}
+
+impl JwtValidator {
+    /// Build from the configuration; None when neither a shared secret nor a
+    /// JWKS endpoint is configured (bearer tokens are then rejected)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        if config.jwt_secret.is_none() && config.jwt_jwks_url.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            secret: config
+                .jwt_secret
+                .as_ref()
+                .map(|secret| DecodingKey::from_secret(secret.as_bytes())),
+            jwks_url: config.jwt_jwks_url.clone(),
+            jwks: RwLock::new(None),
+            issuer: config.jwt_issuer.clone(),
+            audience: config.jwt_audience.clone(),
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Verify a token's signature and registered claims
+    pub async fn validate(&self, token: &str) -> Result<JwtClaims, JwtError> {
+        let header = decode_header(token)?;
+
+        let key = match header.alg {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => self
+                .secret
+                .clone()
+                .ok_or(JwtError::UnsupportedAlgorithm(header.alg))?,
+            _ => {
+                let kid = header.kid.ok_or(JwtError::MissingKeyId)?;
+                self.jwks_key(&kid).await?
+            }
+        };
+
+        let mut validation = Validation::new(header.alg);
+        match &self.issuer {
+            Some(issuer) => validation.set_issuer(&[issuer]),
+            None => validation.iss = None,
+        }
+        match &self.audience {
+            Some(audience) => validation.set_audience(&[audience]),
+            None => validation.validate_aud = false,
+        }
+
+        Ok(decode::<JwtClaims>(token, &key, &validation)?.claims)
+    }
+
+    /// Decoding key for `kid`, refreshing the JWKS when the key is unknown
+    async fn jwks_key(&self, kid: &str) -> Result<DecodingKey, JwtError> {
+        let url = self
+            .jwks_url
+            .as_ref()
+            .ok_or_else(|| JwtError::UnknownKey(kid.to_string()))?;
+
+        {
+            let cached = self.jwks.read().await;
+            if let Some(cached) = cached.as_ref() {
+                if let Some(jwk) = cached.keys.find(kid) {
+                    return Ok(DecodingKey::from_jwk(jwk)?);
+                }
+                if cached.fetched_at.elapsed() < JWKS_REFRESH_INTERVAL {
+                    return Err(JwtError::UnknownKey(kid.to_string()));
+                }
+            }
+        }
+
+        let keys: JwkSet = self
+            .http
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| JwtError::Jwks(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| JwtError::Jwks(e.to_string()))?;
+
+        let key = keys.find(kid).map(DecodingKey::from_jwk).transpose()?;
+        *self.jwks.write().await = Some(CachedJwks {
+            keys,
+            fetched_at: Instant::now(),
+        });
+
+        key.ok_or_else(|| JwtError::UnknownKey(kid.to_string()))
+    }
+}
+
+/// Bearer token errors
+#[derive(Debug, thiserror::Error)]
+pub enum JwtError {
+    #[error("Invalid token: {0}")]
+    Invalid(#[from] jsonwebtoken::errors::Error),
+
+    #[error("Token algorithm {0:?} is not accepted")]
+    UnsupportedAlgorithm(Algorithm),
+
+    #[error("Token header has no key ID")]
+    MissingKeyId,
+
+    #[error("Unknown signing key: {0}")]
+    UnknownKey(String),
+
+    #[error("Failed to fetch JWKS: {0}")]
+    Jwks(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    fn validator(secret: &str, issuer: Option<&str>) -> JwtValidator {
+        JwtValidator {
+            secret: Some(DecodingKey::from_secret(secret.as_bytes())),
+            jwks_url: None,
+            jwks: RwLock::new(None),
+            issuer: issuer.map(str::to_string),
+            audience: None,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn token(secret: &str, claims: serde_json::Value) -> String {
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_validate_shared_secret_token() {
+        let user_id = Uuid::new_v4();
+        let exp = chrono::Utc::now().timestamp() + 300;
+        let token = token(
+            "secret",
+            json!({
+                "sub": user_id.to_string(),
+                "permissions": ["read:users"],
+                "scope": "write:transfers read:users",
+                "iss": "frontend",
+                "exp": exp,
+            }),
+        );
+
+        let claims = validator("secret", Some("frontend")).validate(&token).await.unwrap();
+        assert_eq!(claims.user_id(), Some(user_id));
+        assert_eq!(claims.all_permissions(), vec!["read:users", "write:transfers"]);
+
+        assert!(validator("other", None).validate(&token).await.is_err());
+        assert!(validator("secret", Some("elsewhere")).validate(&token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validate_rejects_expired_token() {
+        let token = token(
+            "secret",
+            json!({ "sub": "billing-service", "exp": chrono::Utc::now().timestamp() - 300 }),
+        );
+
+        assert!(validator("secret", None).validate(&token).await.is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! API Key Issuance
+//!
+//! Shared by POST /admin/api-keys and the `create-api-key` CLI command.
+//! Only the SHA-256 hash of a key is stored; the raw key is returned once.
+//! A key belongs to one tenant; keys of other tenants than the default one
+//! cannot hold platform permissions.
+
+use chrono::Utc;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::permission::Permission;
+use super::routes::{CreateApiKeyRequest, CreateApiKeyResponse};
+use crate::error::AppError;
+use crate::tenants::{TenantResource, TenantService, DEFAULT_TENANT_ID};
+
+/// Generate a random API key
+pub fn generate_api_key() -> String {
+    let mut rng = rand::thread_rng();
+    let random_bytes: [u8; 24] = rng.gen();
+    format!("sk_live_{}", hex::encode(random_bytes))
+}
+
+/// Hex SHA-256 of a raw key, as stored in api_keys.key_hash
+pub fn hash_api_key(raw_key: &str) -> String {
+    format!("{:x}", Sha256::digest(raw_key.as_bytes()))
+}
+
+/// Users the key may act for; an empty list means no restriction
+pub fn normalize_user_scope(allowed_user_ids: Option<Vec<Uuid>>) -> Option<Vec<Uuid>> {
+    allowed_user_ids.filter(|ids| !ids.is_empty())
+}
+
+/// Reject platform permissions on a key of a tenant other than the default
+pub fn check_tenant_permissions(tenant_id: Uuid, permissions: &[String]) -> Result<(), AppError> {
+    if tenant_id == DEFAULT_TENANT_ID {
+        return Ok(());
+    }
+
+    for permission in permissions {
+        if permission.parse::<Permission>().is_ok_and(|permission| permission.is_platform()) {
+            return Err(AppError::InvalidRequest(format!(
+                "{} is only available to keys of the default tenant",
+                permission
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Validate the request and store a new key (in the default tenant unless
+/// `tenant_id` is set)
+pub async fn issue_api_key(
+    pool: &PgPool,
+    request: CreateApiKeyRequest,
+) -> Result<CreateApiKeyResponse, AppError> {
+    let permissions = Permission::canonicalize(&request.permissions)?;
+
+    let tenants = TenantService::new(pool.clone());
+    let tenant_id = tenants.get(request.tenant_id.unwrap_or(DEFAULT_TENANT_ID)).await?.id;
+    check_tenant_permissions(tenant_id, &permissions)?;
+
+    let now = Utc::now();
+    if request.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(AppError::InvalidRequest("expires_at must be in the future".to_string()));
+    }
+
+    if request.rate_limit_burst.is_some_and(|burst| burst < 0) {
+        return Err(AppError::InvalidRequest("rate_limit_burst must not be negative".to_string()));
+    }
+
+    let allowed_user_ids = normalize_user_scope(request.allowed_user_ids);
+    tenants
+        .ensure_all(Some(tenant_id), TenantResource::User, allowed_user_ids.iter().flatten().copied())
+        .await?;
+
+    let id = Uuid::new_v4();
+    let raw_key = generate_api_key();
+    let key_prefix = raw_key[..8].to_string();
+    let key_hash = hash_api_key(&raw_key);
+
+    sqlx::query(
+        r#"
+        INSERT INTO api_keys (id, name, key_prefix, key_hash, permissions, rate_limit_per_minute,
+                              rate_limit_burst, rate_limit_exempt, expires_at, allowed_user_ids, tenant_id,
+                              created_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+        "#
+    )
+    .bind(id)
+    .bind(&request.name)
+    .bind(&key_prefix)
+    .bind(&key_hash)
+    .bind(&permissions)
+    .bind(request.rate_limit_per_minute)
+    .bind(request.rate_limit_burst)
+    .bind(request.rate_limit_exempt)
+    .bind(request.expires_at)
+    .bind(&allowed_user_ids)
+    .bind(tenant_id)
+    .bind(now)
+    .execute(pool)
+    .await?;
+
+    Ok(CreateApiKeyResponse {
+        id,
+        name: request.name,
+        api_key: raw_key,
+        key_prefix,
+        permissions,
+        rate_limit_per_minute: request.rate_limit_per_minute,
+        rate_limit_burst: request.rate_limit_burst,
+        rate_limit_exempt: request.rate_limit_exempt,
+        expires_at: request.expires_at,
+        allowed_user_ids,
+        tenant_id,
+        created_at: now,
+    })
+}
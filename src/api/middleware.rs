@@ -1,20 +1,29 @@
 //! API Middleware
 //!
-//! Authentication and rate limiting middleware.
+//! Authentication, rate limiting and latency budget middleware.
 
 use axum::{
-    body::Body,
-    extract::State,
-    http::{HeaderMap, Request, StatusCode},
+    body::{to_bytes, Body},
+    extract::{RawPathParams, State},
+    http::{header, HeaderMap, HeaderValue, Method, Request, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
+use chrono::{DateTime, Utc};
 use serde_json::json;
-use sqlx::PgPool;
+use tracing::Instrument;
 use uuid::Uuid;
 
+use super::jwt::JWT_PRINCIPAL_ID;
+use super::permission::Permission;
+use super::rate_limit::{RateLimitError, RateLimitPolicy};
+use super::timeout::RouteGroup;
 use crate::domain::OperationContext;
+use crate::error::AppError;
+use crate::idempotency::{IdempotencyError, IdempotencyRepository, IdempotencyScope, IdempotencyTrait};
+use crate::state::SharedState;
+use crate::tenants::{TenantResource, DEFAULT_TENANT_ID};
 
 /// API Key authentication result
 #[derive(Debug, Clone)]
@@ -22,12 +31,39 @@ pub struct AuthenticatedApiKey {
     pub id: Uuid,
     pub name: String,
     pub permissions: Vec<String>,
+    /// Effective rate limit of this key
+    pub rate_limit: RateLimitPolicy,
+    /// Users this key may act for (None = any user)
+    pub allowed_user_ids: Option<Vec<Uuid>>,
+    /// Tenant whose data the key may access
+    pub tenant_id: Uuid,
 }
 
 impl AuthenticatedApiKey {
-    /// Check if this API key has a specific permission
-    pub fn has_permission(&self, permission: &str) -> bool {
-        self.permissions.iter().any(|p| p == permission || p == "admin")
+    /// Check if this API key has (or implies) a permission; unknown
+    /// permission strings grant nothing, and platform permissions are only
+    /// granted to keys of the default tenant
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        if permission.is_platform() && self.tenant_id != DEFAULT_TENANT_ID {
+            return false;
+        }
+        self.permissions
+            .iter()
+            .filter_map(|p| p.parse::<Permission>().ok())
+            .any(|granted| granted.implies(permission))
+    }
+
+    /// Check if this API key may act on behalf of a user
+    pub fn can_act_for(&self, user_id: Uuid) -> bool {
+        self.allowed_user_ids
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(&user_id))
+    }
+
+    /// Check if this API key may read data of `user_id`: any user with
+    /// read:users, otherwise only the request user's own
+    pub fn can_read_user(&self, request_user_id: Option<Uuid>, user_id: Uuid) -> bool {
+        self.has_permission(Permission::ReadUsers) || request_user_id == Some(user_id)
     }
 }
 
@@ -37,134 +73,441 @@ pub struct RequestUser {
     pub user_id: Uuid,
 }
 
+/// Correlation ID of the request (from X-Correlation-Id or generated)
+#[derive(Debug, Clone, Copy)]
+pub struct CorrelationId(pub Uuid);
+
+impl CorrelationId {
+    /// Use a valid X-Correlation-Id header or generate a new ID
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self(
+            headers
+                .get("X-Correlation-Id")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|s| Uuid::parse_str(s).ok())
+                .unwrap_or_else(Uuid::new_v4),
+        )
+    }
+}
+
 // =========================================================================
 // M114: API Key Authentication Middleware
 // =========================================================================
 
-/// Extract and validate API key from X-API-Key header
+/// Authenticate the request with `Authorization: Bearer <JWT>` or, when
+/// enabled in Config, the X-API-Key header
 pub async fn auth_middleware(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
     headers: HeaderMap,
     mut request: Request<Body>,
     next: Next,
 ) -> Result<Response, Response> {
-    // Extract API key
-    let api_key = match headers.get("X-API-Key").and_then(|v| v.to_str().ok()) {
-        Some(key) => key,
-        None => {
-            return Err((
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "error": "Missing X-API-Key header",
-                    "error_code": "missing_api_key"
-                })),
-            )
-                .into_response());
-        }
-    };
-
-    // Validate API key
-    let api_key_record: Option<(Uuid, String, Vec<String>, bool)> = match sqlx::query_as(
-        r#"
-        SELECT id, name, permissions, is_active
-        FROM api_keys
-        WHERE key_hash = encode(sha256($1::bytea), 'hex')
-        "#,
-    )
-    .bind(api_key.as_bytes())
-    .fetch_optional(&pool)
-    .await
-    {
-        Ok(record) => record,
-        Err(e) => {
-            tracing::error!("Database error during API key validation: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": "Internal server error",
-                    "error_code": "database_error"
-                })),
-            )
-                .into_response());
-        }
-    };
+    let bearer_token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    let api_key = headers.get("X-API-Key").and_then(|v| v.to_str().ok());
 
-    let (api_key_id, name, permissions, is_active) = match api_key_record {
-        Some(record) => record,
-        None => {
-            return Err((
+    // User tokens act for their subject unless X-Request-User-Id says otherwise
+    let (authenticated, token_user_id) = match (bearer_token, api_key) {
+        (Some(token), _) => authenticate_bearer(&state, token).await?,
+        (None, Some(_)) if !state.config.api_key_auth_enabled => {
+            return Err(auth_error(
                 StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "error": "Invalid API key",
-                    "error_code": "invalid_api_key"
-                })),
-            )
-                .into_response());
+                "API key authentication is disabled; use a bearer token",
+                "api_key_auth_disabled",
+            ));
+        }
+        (None, Some(key)) => (authenticate_api_key(&state, key).await?, None),
+        (None, None) => {
+            let error = if state.config.api_key_auth_enabled {
+                "Missing X-API-Key header or bearer token"
+            } else {
+                "Missing bearer token"
+            };
+            return Err(auth_error(StatusCode::UNAUTHORIZED, error, "missing_api_key"));
         }
     };
 
-    if !is_active {
-        return Err((
-            StatusCode::UNAUTHORIZED,
-            Json(json!({
-                "error": "API key is disabled",
-                "error_code": "api_key_disabled"
-            })),
-        )
-            .into_response());
-    }
-
-    // Store authenticated API key in request extensions
-    request.extensions_mut().insert(AuthenticatedApiKey {
-        id: api_key_id,
-        name,
-        permissions,
-    });
-
     // Extract X-Request-User-Id if present
     // Note: Some endpoints require this header - they will check for RequestUser extension
-    if let Some(user_id_str) = headers.get("X-Request-User-Id").and_then(|v| v.to_str().ok()) {
+    let request_user_id = headers.get("X-Request-User-Id").and_then(|v| v.to_str().ok());
+    if let Some(user_id_str) = request_user_id {
         match Uuid::parse_str(user_id_str) {
+            Ok(user_id) if !authenticated.can_act_for(user_id) => {
+                return Err(auth_error(
+                    StatusCode::FORBIDDEN,
+                    "API key may not act for this user",
+                    "user_out_of_scope",
+                ));
+            }
             Ok(user_id) => {
+                ensure_user_in_tenant(&state, authenticated.tenant_id, user_id).await?;
                 request.extensions_mut().insert(RequestUser { user_id });
             }
             Err(_) => {
-                return Err((
+                return Err(auth_error(
                     StatusCode::BAD_REQUEST,
-                    Json(json!({
-                        "error": "Invalid X-Request-User-Id header format",
-                        "error_code": "invalid_user_id"
-                    })),
-                )
-                    .into_response());
+                    "Invalid X-Request-User-Id header format",
+                    "invalid_user_id",
+                ));
             }
         }
+    } else if let Some(user_id) = token_user_id {
+        ensure_user_in_tenant(&state, authenticated.tenant_id, user_id).await?;
+        request.extensions_mut().insert(RequestUser { user_id });
+    } else if authenticated.allowed_user_ids.is_some() {
+        // Scoped keys always act for a specific user
+        return Err(auth_error(
+            StatusCode::FORBIDDEN,
+            "X-Request-User-Id is required for user-scoped API keys",
+            "user_scope_required",
+        ));
     }
 
-    // Extract correlation ID or generate new one
-    let correlation_id = headers
-        .get("X-Correlation-Id")
-        .and_then(|v| v.to_str().ok())
-        .and_then(|s| Uuid::parse_str(s).ok())
-        .unwrap_or_else(Uuid::new_v4);
+    let api_key_id = authenticated.id;
+    let tenant_id = authenticated.tenant_id;
+    tracing::Span::current().record("api_key_id", tracing::field::display(api_key_id));
+
+    // Store authenticated API key in request extensions
+    request.extensions_mut().insert(authenticated);
+
+    // Correlation ID assigned by logging_middleware (or the header)
+    let CorrelationId(correlation_id) = request
+        .extensions()
+        .get::<CorrelationId>()
+        .copied()
+        .unwrap_or_else(|| CorrelationId::from_headers(&headers));
 
     // Build operation context
     let context = OperationContext::new()
         .with_api_key(api_key_id)
-        .with_correlation_id(correlation_id);
+        .with_correlation_id(correlation_id)
+        .with_tenant(tenant_id);
 
     request.extensions_mut().insert(context);
 
     Ok(next.run(request).await)
 }
 
+/// api_keys columns read when authenticating a secret
+#[derive(Debug, sqlx::FromRow)]
+struct ApiKeyAuthRow {
+    id: Uuid,
+    name: String,
+    permissions: Vec<String>,
+    rate_limit_per_minute: Option<i32>,
+    rate_limit_burst: Option<i32>,
+    rate_limit_exempt: bool,
+    is_active: bool,
+    expires_at: Option<DateTime<Utc>>,
+    allowed_user_ids: Option<Vec<Uuid>>,
+    tenant_id: Uuid,
+    tenant_active: bool,
+}
+
+/// Principal api_keys row of bearer token requests, with its tenant
+#[derive(Debug, sqlx::FromRow)]
+struct JwtPrincipalRow {
+    rate_limit_per_minute: Option<i32>,
+    rate_limit_burst: Option<i32>,
+    rate_limit_exempt: bool,
+    is_active: bool,
+    tenant_id: Uuid,
+    tenant_active: bool,
+}
+
+/// Validate an X-API-Key secret (the previous secret of a rotated key is
+/// accepted until its grace period ends)
+async fn authenticate_api_key(state: &SharedState, api_key: &str) -> Result<AuthenticatedApiKey, Response> {
+    let api_key_record: Option<ApiKeyAuthRow> = sqlx::query_as(
+        r#"
+        SELECT k.id, k.name, k.permissions, k.rate_limit_per_minute, k.rate_limit_burst,
+               k.rate_limit_exempt, k.is_active, k.expires_at, k.allowed_user_ids,
+               k.tenant_id, t.is_active AS tenant_active
+        FROM api_keys k
+        JOIN tenants t ON t.id = k.tenant_id
+        WHERE k.key_hash = encode(sha256($1::bytea), 'hex')
+           OR (k.previous_key_hash = encode(sha256($1::bytea), 'hex')
+               AND k.previous_key_expires_at > NOW())
+        "#,
+    )
+    .bind(api_key.as_bytes())
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Database error during API key validation: {}", e);
+        database_error()
+    })?;
+
+    let ApiKeyAuthRow {
+        id,
+        name,
+        permissions,
+        rate_limit_per_minute,
+        rate_limit_burst,
+        rate_limit_exempt,
+        is_active,
+        expires_at,
+        allowed_user_ids,
+        tenant_id,
+        tenant_active,
+    } = api_key_record.ok_or_else(|| auth_error(StatusCode::UNAUTHORIZED, "Invalid API key", "invalid_api_key"))?;
+
+    if !is_active {
+        return Err(auth_error(StatusCode::UNAUTHORIZED, "API key is disabled", "api_key_disabled"));
+    }
+
+    if expires_at.is_some_and(|expires_at| expires_at <= Utc::now()) {
+        return Err(auth_error(StatusCode::UNAUTHORIZED, "API key has expired", "api_key_expired"));
+    }
+
+    if !tenant_active {
+        return Err(tenant_disabled());
+    }
+
+    Ok(AuthenticatedApiKey {
+        id,
+        name,
+        permissions,
+        rate_limit: RateLimitPolicy::from_config(&state.config).with_overrides(
+            rate_limit_per_minute,
+            rate_limit_burst,
+            rate_limit_exempt,
+        ),
+        allowed_user_ids,
+        tenant_id,
+    })
+}
+
+/// Validate a bearer token; requests are attributed to the JWT principal
+/// api_keys row, and user tokens are scoped to their subject
+async fn authenticate_bearer(
+    state: &SharedState,
+    token: &str,
+) -> Result<(AuthenticatedApiKey, Option<Uuid>), Response> {
+    let validator = state.jwt.as_ref().ok_or_else(|| {
+        auth_error(
+            StatusCode::UNAUTHORIZED,
+            "Bearer token authentication is not configured",
+            "invalid_token",
+        )
+    })?;
+
+    let claims = validator.validate(token).await.map_err(|e| {
+        tracing::debug!(error = %e, "Rejected bearer token");
+        auth_error(StatusCode::UNAUTHORIZED, "Invalid bearer token", "invalid_token")
+    })?;
+
+    let principal: Option<JwtPrincipalRow> = sqlx::query_as(
+        r#"
+        SELECT k.rate_limit_per_minute, k.rate_limit_burst, k.rate_limit_exempt, k.is_active,
+               k.tenant_id, t.is_active AS tenant_active
+        FROM api_keys k
+        JOIN tenants t ON t.id = k.tenant_id
+        WHERE k.id = $1
+        "#,
+    )
+    .bind(JWT_PRINCIPAL_ID)
+    .fetch_optional(&state.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Database error during bearer token validation: {}", e);
+        database_error()
+    })?;
+
+    // Bearer tokens act in the tenant of the principal row
+    let (rate_limit, tenant_id) = match principal {
+        Some(principal) if principal.is_active && !principal.tenant_active => return Err(tenant_disabled()),
+        Some(principal) if principal.is_active => (
+            RateLimitPolicy::from_config(&state.config).with_overrides(
+                principal.rate_limit_per_minute,
+                principal.rate_limit_burst,
+                principal.rate_limit_exempt,
+            ),
+            principal.tenant_id,
+        ),
+        _ => {
+            return Err(auth_error(
+                StatusCode::UNAUTHORIZED,
+                "Bearer token authentication is disabled",
+                "api_key_disabled",
+            ));
+        }
+    };
+
+    let user_id = claims.user_id();
+    let authenticated = AuthenticatedApiKey {
+        id: JWT_PRINCIPAL_ID,
+        name: format!("jwt:{}", claims.sub),
+        permissions: claims.all_permissions(),
+        rate_limit,
+        allowed_user_ids: user_id.map(|user_id| vec![user_id]),
+        tenant_id,
+    };
+
+    Ok((authenticated, user_id))
+}
+
+/// JSON error response in the shape used by the auth middleware
+fn auth_error(status: StatusCode, error: &str, error_code: &str) -> Response {
+    (
+        status,
+        Json(json!({
+            "error": error,
+            "error_code": error_code
+        })),
+    )
+        .into_response()
+}
+
+fn database_error() -> Response {
+    auth_error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error", "database_error")
+}
+
+fn tenant_disabled() -> Response {
+    auth_error(StatusCode::UNAUTHORIZED, "Tenant is disabled", "tenant_disabled")
+}
+
+/// Reject acting for a user of another tenant (unknown users pass; the
+/// handler reports them)
+async fn ensure_user_in_tenant(state: &SharedState, tenant_id: Uuid, user_id: Uuid) -> Result<(), Response> {
+    let user_tenant = state
+        .tenants
+        .tenant_of(TenantResource::User, user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Database error during tenant check: {}", e);
+            database_error()
+        })?;
+
+    match user_tenant {
+        Some(user_tenant) if user_tenant != tenant_id => Err(auth_error(
+            StatusCode::FORBIDDEN,
+            "API key may not act for this user",
+            "user_out_of_scope",
+        )),
+        _ => Ok(()),
+    }
+}
+
+// =========================================================================
+// Tenant Scope Middleware
+// =========================================================================
+
+/// Answer 404 for paths naming a user, account, transfer, hold or API key of
+/// another tenant, as if it did not exist. Runs as a route layer, after
+/// routing has extracted the path parameters.
+pub async fn tenant_scope_middleware(
+    State(state): State<SharedState>,
+    params: RawPathParams,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, Response> {
+    let Some(tenant_id) = request.extensions().get::<AuthenticatedApiKey>().map(|key| key.tenant_id) else {
+        return Err(auth_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Auth middleware must run first",
+            "internal_error",
+        ));
+    };
+
+    for (name, value) in &params {
+        let Some(resource) = TenantResource::from_path_param(name) else {
+            continue;
+        };
+        // Malformed IDs are rejected by the handler's own extractor
+        let Ok(id) = Uuid::parse_str(value) else {
+            continue;
+        };
+        state
+            .tenants
+            .ensure(Some(tenant_id), resource, id)
+            .await
+            .map_err(|e| AppError::from(e).into_response())?;
+    }
+
+    Ok(next.run(request).await)
+}
+
 // =========================================================================
 // M115: Rate Limiting Middleware
 // =========================================================================
 
+/// Rate limit state for the current one-minute window
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitStatus {
+    pub limit: i32,
+    /// Requests allowed above `limit` before the key is throttled
+    pub burst: i32,
+    pub request_count: i32,
+    pub window_start: DateTime<Utc>,
+}
+
+impl RateLimitStatus {
+    pub fn is_allowed(&self) -> bool {
+        self.request_count <= self.limit.saturating_add(self.burst)
+    }
+
+    /// Requests left in the window, burst included
+    pub fn remaining(&self) -> i32 {
+        self.limit
+            .saturating_add(self.burst)
+            .saturating_sub(self.request_count)
+            .max(0)
+    }
+
+    /// Seconds until the current window ends
+    pub fn retry_after_secs(&self, now: DateTime<Utc>) -> i64 {
+        let window_end = self.window_start + chrono::Duration::minutes(1);
+        (window_end - now).num_seconds().clamp(1, 60)
+    }
+
+    /// Add X-RateLimit-Limit / X-RateLimit-Burst / X-RateLimit-Remaining
+    /// (and Retry-After when limited)
+    pub fn apply_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("X-RateLimit-Limit", HeaderValue::from(self.limit));
+        headers.insert("X-RateLimit-Burst", HeaderValue::from(self.burst));
+        headers.insert("X-RateLimit-Remaining", HeaderValue::from(self.remaining()));
+        if !self.is_allowed() {
+            headers.insert(
+                "Retry-After",
+                HeaderValue::from(self.retry_after_secs(Utc::now())),
+            );
+        }
+    }
+
+    /// Add X-RateLimit-User-Limit / X-RateLimit-User-Remaining for the
+    /// request user's own budget (and Retry-After when limited)
+    pub fn apply_user_headers(&self, headers: &mut HeaderMap) {
+        headers.insert("X-RateLimit-User-Limit", HeaderValue::from(self.limit));
+        headers.insert("X-RateLimit-User-Remaining", HeaderValue::from(self.remaining()));
+        if !self.is_allowed() {
+            headers.insert(
+                "Retry-After",
+                HeaderValue::from(self.retry_after_secs(Utc::now())),
+            );
+        }
+    }
+}
+
+/// 500 when a rate limit counter cannot be updated
+fn rate_limit_check_failed(e: RateLimitError) -> Response {
+    tracing::error!("Rate limit check error: {}", e);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({
+            "error": "Rate limit check failed",
+            "error_code": "database_error"
+        })),
+    )
+        .into_response()
+}
+
 /// Rate limiting middleware
 pub async fn rate_limit_middleware(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
     request: Request<Body>,
     next: Next,
 ) -> Result<Response, Response> {
@@ -183,46 +526,258 @@ pub async fn rate_limit_middleware(
         }
     };
 
-    // Get rate limit from environment variable (with default of 100)
-    let rate_limit: i32 = std::env::var("RATE_LIMIT_PER_MINUTE")
-        .ok()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(100);
+    // Trusted keys skip counting entirely
+    if api_key.rate_limit.exempt {
+        let mut response = next.run(request).await;
+        response
+            .headers_mut()
+            .insert("X-RateLimit-Exempt", HeaderValue::from_static("true"));
+        return Ok(response);
+    }
 
-    let allowed: bool = match sqlx::query_scalar(
-        r#"SELECT check_and_increment_rate_limit($1, $2)"#,
-    )
-    .bind(api_key.id)
-    .bind(rate_limit)
-    .fetch_one(&pool)
-    .await
-    {
-        Ok(result) => result,
-        Err(e) => {
-            tracing::error!("Rate limit check error: {}", e);
-            return Err((
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "error": "Rate limit check failed",
-                    "error_code": "database_error"
-                })),
-            )
-                .into_response());
-        }
+    // Increment the counter for the current minute window
+    let window = state
+        .rate_limits
+        .increment(api_key.id)
+        .await
+        .map_err(rate_limit_check_failed)?;
+
+    let status = RateLimitStatus {
+        limit: api_key.rate_limit.per_minute,
+        burst: api_key.rate_limit.burst,
+        request_count: window.request_count,
+        window_start: window.window_start,
     };
 
-    if !allowed {
-        return Err((
+    if !status.is_allowed() {
+        let mut response = (
             StatusCode::TOO_MANY_REQUESTS,
             Json(json!({
                 "error": "Rate limit exceeded",
                 "error_code": "rate_limit_exceeded"
             })),
         )
-            .into_response());
+            .into_response();
+        status.apply_headers(response.headers_mut());
+        return Err(response);
     }
 
-    Ok(next.run(request).await)
+    // Requests for a user also count against that user's budget within the key
+    let user_policy = RateLimitPolicy::per_user_from_config(&state.config);
+    let request_user = request.extensions().get::<RequestUser>().map(|user| user.user_id);
+    let user_status = match (user_policy, request_user) {
+        (Some(policy), Some(user_id)) => {
+            let window = state
+                .rate_limits
+                .increment_user(api_key.id, user_id)
+                .await
+                .map_err(rate_limit_check_failed)?;
+            Some(RateLimitStatus {
+                limit: policy.per_minute,
+                burst: policy.burst,
+                request_count: window.request_count,
+                window_start: window.window_start,
+            })
+        }
+        _ => None,
+    };
+
+    if let Some(user_status) = user_status.filter(|s| !s.is_allowed()) {
+        let mut response = (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(json!({
+                "error": "Rate limit exceeded for this user",
+                "error_code": "user_rate_limit_exceeded"
+            })),
+        )
+            .into_response();
+        status.apply_headers(response.headers_mut());
+        user_status.apply_user_headers(response.headers_mut());
+        return Err(response);
+    }
+
+    let mut response = next.run(request).await;
+    status.apply_headers(response.headers_mut());
+    if let Some(user_status) = user_status {
+        user_status.apply_user_headers(response.headers_mut());
+    }
+    Ok(response)
+}
+
+// =========================================================================
+// Idempotency Middleware
+// =========================================================================
+
+/// Largest response body buffered for idempotency handling (requests are
+/// capped by MAX_BODY_BYTES)
+const MAX_IDEMPOTENT_BODY_BYTES: usize = 1024 * 1024;
+
+/// Hash of caller, method, path and body identifying the request behind an
+/// Idempotency-Key. The caller (API key, tenant and request user) is part of
+/// the hash, so a key can never match another caller's request.
+fn idempotency_request_hash(
+    scope: IdempotencyScope,
+    request_user_id: Option<Uuid>,
+    method: &Method,
+    path: &str,
+    body: &[u8],
+) -> String {
+    let optional = |id: Option<Uuid>| id.map(|id| id.to_string()).unwrap_or_default();
+    let mut fingerprint = format!(
+        "{} {} {}\n{} {}\n",
+        optional(scope.api_key_id),
+        optional(scope.tenant_id),
+        optional(request_user_id),
+        method,
+        path
+    )
+    .into_bytes();
+    fingerprint.extend_from_slice(body);
+    IdempotencyRepository::compute_request_hash(&fingerprint)
+}
+
+/// Rebuild a response stored against an idempotency key
+fn cached_response(status: i32, body: Option<serde_json::Value>) -> Response {
+    let status = u16::try_from(status)
+        .ok()
+        .and_then(|s| StatusCode::from_u16(s).ok())
+        .unwrap_or(StatusCode::OK);
+
+    let mut response = match body {
+        Some(serde_json::Value::Null) | None => status.into_response(),
+        Some(body) => (status, Json(body)).into_response(),
+    };
+    response
+        .headers_mut()
+        .insert("Idempotent-Replayed", HeaderValue::from_static("true"));
+    response
+}
+
+/// Claim `key` for this request. Returns the stored response when the key
+/// already completed with one, and the error response when the key belongs
+/// to a different request or is still being processed.
+async fn claim_idempotency_key<I: IdempotencyTrait>(
+    repository: &I,
+    key: Uuid,
+    scope: IdempotencyScope,
+    request_hash: &str,
+) -> Result<Option<Response>, Response> {
+    match repository.start_processing(key, scope, request_hash).await {
+        Ok(None) => Ok(None),
+        Ok(Some(existing)) => match existing.response_status {
+            Some(status) => {
+                tracing::info!(idempotency_key = %key, "Replaying cached response");
+                Ok(Some(cached_response(status, existing.response_body)))
+            }
+            // Completed by the EventStore without a stored response:
+            // let the handler take its replay path
+            None => Ok(None),
+        },
+        Err(IdempotencyError::HashMismatch(_)) => Err(AppError::IdempotencyConflict.into_response()),
+        Err(IdempotencyError::KeyInProgress) => Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "A request with this Idempotency-Key is still being processed",
+                "error_code": "idempotency_key_in_progress"
+            })),
+        )
+            .into_response()),
+        Err(e) => {
+            tracing::error!("Idempotency check error: {}", e);
+            Err(AppError::Internal("Idempotency check failed".to_string()).into_response())
+        }
+    }
+}
+
+/// Record the handler's response: 2xx completes the key, anything else fails it
+async fn record_idempotent_response<I: IdempotencyTrait>(
+    repository: &I,
+    key: Uuid,
+    scope: IdempotencyScope,
+    status: StatusCode,
+    body: serde_json::Value,
+) {
+    let status_code = status.as_u16() as i32;
+    let result = if status.is_success() {
+        repository.store_response(key, scope, status_code, body).await
+    } else {
+        repository.mark_failed(key, scope, Some(status_code), Some(body)).await
+    };
+    if let Err(e) = result {
+        tracing::error!(idempotency_key = %key, "Failed to record idempotent response: {}", e);
+    }
+}
+
+/// Idempotency middleware
+///
+/// For mutating requests carrying an Idempotency-Key header: replays the stored
+/// response for completed keys, rejects reuse of a key with a different request
+/// (409), and records the handler's response (2xx → completed, otherwise failed).
+/// Keys are scoped to the API key and tenant that sent them. Runs as a route
+/// layer after the tenant scope check, so nothing is replayed to a caller
+/// that has not passed authentication and tenant resolution.
+pub async fn idempotency_middleware(
+    State(state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Result<Response, Response> {
+    if matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(next.run(request).await);
+    }
+
+    let key = match request.headers().get("Idempotency-Key") {
+        Some(value) => value
+            .to_str()
+            .ok()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .ok_or_else(|| {
+                AppError::InvalidRequest("Idempotency-Key must be a UUID".to_string()).into_response()
+            })?,
+        None => return Ok(next.run(request).await),
+    };
+
+    let Some(scope) = request.extensions().get::<OperationContext>().map(IdempotencyScope::from_context) else {
+        return Err(auth_error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Auth middleware must run first",
+            "internal_error",
+        ));
+    };
+    let request_user_id = request.extensions().get::<RequestUser>().map(|user| user.user_id);
+
+    let (parts, body) = request.into_parts();
+    let body = to_bytes(body, state.config.max_body_bytes)
+        .await
+        .map_err(|_| AppError::PayloadTooLarge.into_response())?;
+    let request_hash = idempotency_request_hash(scope, request_user_id, &parts.method, parts.uri.path(), &body);
+
+    if let Some(replay) = claim_idempotency_key(&state.idempotency, key, scope, &request_hash).await? {
+        return Ok(replay);
+    }
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, MAX_IDEMPOTENT_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!(idempotency_key = %key, "Failed to buffer response body: {}", e);
+            return Err(AppError::Internal("Failed to read response".to_string()).into_response());
+        }
+    };
+
+    let body_json = if body.is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_slice(&body)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&body).into_owned()))
+    };
+
+    record_idempotent_response(&state.idempotency, key, scope, parts.status, body_json).await;
+
+    let mut response = Response::from_parts(parts, Body::from(body));
+    response.headers_mut().remove(header::CONTENT_LENGTH);
+    Ok(response)
 }
 
 // =========================================================================
@@ -257,60 +812,116 @@ pub fn mask_headers_for_logging(headers: &HeaderMap) -> Vec<(String, String)> {
 // M119: Request Logging Middleware
 // =========================================================================
 
-/// Request logging middleware
+/// Request logging middleware. Assigns the correlation ID, runs the request
+/// in a span carrying correlation_id, path and (once authenticated)
+/// api_key_id, and echoes the ID in the X-Correlation-Id response header.
 pub async fn logging_middleware(
-    request: Request<Body>,
+    State(state): State<SharedState>,
+    mut request: Request<Body>,
     next: Next,
 ) -> Response {
     let method = request.method().clone();
     let uri = request.uri().clone();
     let version = request.version();
-    
+
     // Mask sensitive headers
     let headers = mask_headers_for_logging(request.headers());
-    
-    // Extract correlation ID if available
-    let correlation_id = request
-        .extensions()
-        .get::<crate::domain::OperationContext>()
-        .map(|ctx| ctx.correlation_id)
-        .flatten();
-    
-    let start = std::time::Instant::now();
-    
-    // Log request
-    tracing::info!(
-        method = %method,
-        uri = %uri,
-        version = ?version,
-        correlation_id = ?correlation_id,
-        headers = ?headers,
-        "Incoming request"
-    );
-    
-    // Process request
-    let response = next.run(request).await;
-    
-    let duration = start.elapsed();
-    let status = response.status();
-    
-    // Log response
-    tracing::info!(
+
+    let correlation_id = CorrelationId::from_headers(request.headers());
+    request.extensions_mut().insert(correlation_id);
+
+    let span = tracing::info_span!(
+        "request",
+        correlation_id = %correlation_id.0,
         method = %method,
-        uri = %uri,
-        status = %status,
-        duration_ms = %duration.as_millis(),
-        correlation_id = ?correlation_id,
-        "Request completed"
+        path = %uri.path(),
+        api_key_id = tracing::field::Empty,
     );
-    
-    response
+
+    async move {
+        let start = std::time::Instant::now();
+
+        // Log request
+        tracing::info!(
+            uri = %uri,
+            version = ?version,
+            headers = ?headers,
+            "Incoming request"
+        );
+
+        // Process request
+        let mut response = next.run(request).await;
+
+        let duration = start.elapsed();
+        let status = response.status();
+        state.metrics.record_response(status);
+
+        // Log response
+        tracing::info!(
+            status = %status,
+            duration_ms = %duration.as_millis(),
+            "Request completed"
+        );
+
+        if let Ok(value) = HeaderValue::from_str(&correlation_id.0.to_string()) {
+            response.headers_mut().insert("X-Correlation-Id", value);
+        }
+
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+// =========================================================================
+// Latency Budget Middleware
+// =========================================================================
+
+/// Answer requests that run past the budget of their route group (see
+/// [`super::timeout`]) with 503 and error_code "timeout". Reads are
+/// cancelled; writes and maintenance runs keep running to completion in
+/// their own task, so a commit is never cut off before its projections and
+/// stored result, and only the wait for their response is bounded. Runs
+/// inside the logging middleware, so the 503 is logged and counted like any
+/// other response.
+pub async fn latency_budget_middleware(
+    State(state): State<SharedState>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let group = RouteGroup::of(request.method(), request.uri().path());
+    let budget = state.config.latency_budgets.budget(group);
+
+    let response = match group {
+        RouteGroup::Read => tokio::time::timeout(budget, next.run(request)).await.ok(),
+        RouteGroup::Write | RouteGroup::Maintenance => {
+            // The task keeps the request's span for its logs
+            let handle = tokio::spawn(next.run(request).in_current_span());
+            match tokio::time::timeout(budget, handle).await {
+                Ok(Ok(response)) => Some(response),
+                Ok(Err(e)) => {
+                    tracing::error!(error = %e, "Request task failed");
+                    return auth_error(StatusCode::INTERNAL_SERVER_ERROR, "Internal server error", "internal_error");
+                }
+                Err(_) => None,
+            }
+        }
+    };
+
+    response.unwrap_or_else(|| {
+        state.metrics.record_timeout();
+        tracing::warn!(
+            route_group = group.as_str(),
+            budget_ms = budget.as_millis() as u64,
+            "Request exceeded its latency budget"
+        );
+        auth_error(StatusCode::SERVICE_UNAVAILABLE, "Request timed out", "timeout")
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use axum::http::header::HeaderName;
 
     #[test]
     fn test_mask_headers_for_logging() {
@@ -331,6 +942,229 @@ mod tests {
         assert_eq!(user_id.unwrap().1, "user-123");
     }
 
+    #[test]
+    fn test_correlation_id_from_headers() {
+        let id = Uuid::new_v4();
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Correlation-Id", id.to_string().parse().unwrap());
+        assert_eq!(CorrelationId::from_headers(&headers).0, id);
+
+        headers.insert("X-Correlation-Id", "not-a-uuid".parse().unwrap());
+        assert_ne!(CorrelationId::from_headers(&headers).0, id);
+    }
+
+    #[test]
+    fn test_api_key_user_scope() {
+        let allowed = Uuid::new_v4();
+        let mut key = AuthenticatedApiKey {
+            id: Uuid::new_v4(),
+            name: "partner".to_string(),
+            permissions: vec!["write:transfers".to_string()],
+            rate_limit: RateLimitPolicy {
+                per_minute: 100,
+                burst: 0,
+                exempt: false,
+            },
+            allowed_user_ids: None,
+            tenant_id: DEFAULT_TENANT_ID,
+        };
+        assert!(key.can_act_for(Uuid::new_v4()));
+
+        key.allowed_user_ids = Some(vec![allowed]);
+        assert!(key.can_act_for(allowed));
+        assert!(!key.can_act_for(Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_api_key_row_level_read_access() {
+        let own = Uuid::new_v4();
+        let mut key = AuthenticatedApiKey {
+            id: Uuid::new_v4(),
+            name: "frontend".to_string(),
+            permissions: vec!["read:accounts".to_string()],
+            rate_limit: RateLimitPolicy {
+                per_minute: 100,
+                burst: 0,
+                exempt: false,
+            },
+            allowed_user_ids: None,
+            tenant_id: DEFAULT_TENANT_ID,
+        };
+        assert!(key.can_read_user(Some(own), own));
+        assert!(!key.can_read_user(Some(own), Uuid::new_v4()));
+        assert!(!key.can_read_user(None, own));
+
+        key.permissions.push("read:users".to_string());
+        assert!(key.can_read_user(None, own));
+
+        key.permissions = vec!["admin".to_string()];
+        assert!(key.can_read_user(Some(own), Uuid::new_v4()));
+    }
+
+    #[test]
+    fn test_api_key_tenant_platform_permissions() {
+        let mut key = AuthenticatedApiKey {
+            id: Uuid::new_v4(),
+            name: "tenant-admin".to_string(),
+            permissions: vec!["admin".to_string()],
+            rate_limit: RateLimitPolicy {
+                per_minute: 100,
+                burst: 0,
+                exempt: false,
+            },
+            allowed_user_ids: None,
+            tenant_id: DEFAULT_TENANT_ID,
+        };
+        assert!(key.has_permission(Permission::AdminSupply));
+        assert!(key.has_permission(Permission::AdminTenants));
+
+        key.tenant_id = Uuid::new_v4();
+        assert!(!key.has_permission(Permission::AdminSupply));
+        assert!(!key.has_permission(Permission::AdminTenants));
+        assert!(key.has_permission(Permission::WriteTransfers));
+    }
+
+    #[test]
+    fn test_rate_limit_status_headers() {
+        let window_start = Utc::now();
+        let status = RateLimitStatus {
+            limit: 10,
+            burst: 0,
+            request_count: 4,
+            window_start,
+        };
+        let mut headers = HeaderMap::new();
+        status.apply_headers(&mut headers);
+
+        assert!(status.is_allowed());
+        assert_eq!(headers["X-RateLimit-Limit"], "10");
+        assert_eq!(headers["X-RateLimit-Remaining"], "6");
+        assert!(headers.get("Retry-After").is_none());
+
+        let limited = RateLimitStatus {
+            request_count: 11,
+            ..status
+        };
+        let mut headers = HeaderMap::new();
+        limited.apply_headers(&mut headers);
+
+        assert!(!limited.is_allowed());
+        assert_eq!(headers["X-RateLimit-Remaining"], "0");
+        assert!(headers.get("Retry-After").is_some());
+        assert_eq!(limited.retry_after_secs(window_start + chrono::Duration::seconds(45)), 15);
+    }
+
+    #[test]
+    fn test_rate_limit_status_burst() {
+        let status = RateLimitStatus {
+            limit: 10,
+            burst: 5,
+            request_count: 13,
+            window_start: Utc::now(),
+        };
+        let mut headers = HeaderMap::new();
+        status.apply_headers(&mut headers);
+
+        // Over the per-minute limit but within the burst allowance
+        assert!(status.is_allowed());
+        assert_eq!(headers["X-RateLimit-Limit"], "10");
+        assert_eq!(headers["X-RateLimit-Burst"], "5");
+        assert_eq!(headers["X-RateLimit-Remaining"], "2");
+
+        let limited = RateLimitStatus {
+            request_count: 16,
+            ..status
+        };
+        assert!(!limited.is_allowed());
+        assert_eq!(limited.remaining(), 0);
+    }
+
+    #[test]
+    fn test_rate_limit_status_user_headers() {
+        let status = RateLimitStatus {
+            limit: 5,
+            burst: 1,
+            request_count: 7,
+            window_start: Utc::now(),
+        };
+        let mut headers = HeaderMap::new();
+        status.apply_user_headers(&mut headers);
+
+        assert_eq!(headers["X-RateLimit-User-Limit"], "5");
+        assert_eq!(headers["X-RateLimit-User-Remaining"], "0");
+        assert!(headers.get("X-RateLimit-Limit").is_none());
+        assert!(headers.get("Retry-After").is_some());
+    }
+
+    #[test]
+    fn test_idempotency_request_hash() {
+        let scope = IdempotencyScope {
+            api_key_id: Some(Uuid::new_v4()),
+            tenant_id: Some(DEFAULT_TENANT_ID),
+        };
+        let hash_of = |scope, user, method: &Method, path, body: &[u8]| {
+            idempotency_request_hash(scope, user, method, path, body)
+        };
+        let hash = hash_of(scope, None, &Method::POST, "/transfers", b"{\"amount\":\"1\"}");
+
+        assert_eq!(hash, hash_of(scope, None, &Method::POST, "/transfers", b"{\"amount\":\"1\"}"));
+        assert_ne!(hash, hash_of(scope, None, &Method::POST, "/transfers", b"{\"amount\":\"2\"}"));
+        assert_ne!(hash, hash_of(scope, None, &Method::POST, "/admin/mint", b"{\"amount\":\"1\"}"));
+
+        // Another API key, tenant or request user never matches
+        let other_key = IdempotencyScope {
+            api_key_id: Some(Uuid::new_v4()),
+            ..scope
+        };
+        let other_tenant = IdempotencyScope {
+            tenant_id: Some(Uuid::new_v4()),
+            ..scope
+        };
+        assert_ne!(hash, hash_of(other_key, None, &Method::POST, "/transfers", b"{\"amount\":\"1\"}"));
+        assert_ne!(hash, hash_of(other_tenant, None, &Method::POST, "/transfers", b"{\"amount\":\"1\"}"));
+        assert_ne!(
+            hash,
+            hash_of(scope, Some(Uuid::new_v4()), &Method::POST, "/transfers", b"{\"amount\":\"1\"}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_key_lifecycle() {
+        let repository = crate::idempotency::InMemoryIdempotencyStore::new();
+        let key = Uuid::new_v4();
+        let scope = IdempotencyScope {
+            api_key_id: Some(Uuid::new_v4()),
+            tenant_id: Some(DEFAULT_TENANT_ID),
+        };
+
+        assert!(claim_idempotency_key(&repository, key, scope, "hash").await.unwrap().is_none());
+
+        // Concurrent duplicate while the first request is running
+        let in_progress = claim_idempotency_key(&repository, key, scope, "hash").await.unwrap_err();
+        assert_eq!(in_progress.status(), StatusCode::CONFLICT);
+
+        record_idempotent_response(&repository, key, scope, StatusCode::CREATED, json!({ "id": 1 })).await;
+        let replay = claim_idempotency_key(&repository, key, scope, "hash").await.unwrap().unwrap();
+        assert_eq!(replay.status(), StatusCode::CREATED);
+        assert_eq!(replay.headers()["Idempotent-Replayed"], "true");
+
+        let conflict = claim_idempotency_key(&repository, key, scope, "other").await.unwrap_err();
+        assert_eq!(conflict.status(), StatusCode::CONFLICT);
+
+        // The same key from another API key is its own, unclaimed key
+        let other_scope = IdempotencyScope {
+            api_key_id: Some(Uuid::new_v4()),
+            ..scope
+        };
+        assert!(claim_idempotency_key(&repository, key, other_scope, "hash").await.unwrap().is_none());
+
+        // Failed requests may be retried with the same key
+        let failed = Uuid::new_v4();
+        claim_idempotency_key(&repository, failed, scope, "hash").await.unwrap();
+        record_idempotent_response(&repository, failed, scope, StatusCode::BAD_REQUEST, json!({})).await;
+        assert!(claim_idempotency_key(&repository, failed, scope, "hash").await.unwrap().is_none());
+    }
+
     #[test]
     fn test_sensitive_headers_list() {
         assert!(SENSITIVE_HEADERS.contains(&"x-api-key"));
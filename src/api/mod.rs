@@ -2,7 +2,15 @@
 //!
 //! HTTP API endpoints and middleware.
 
+pub mod cors;
+pub mod etag;
+pub mod jwt;
+pub mod keys;
 pub mod middleware;
+pub mod permission;
+pub mod rate_limit;
 pub mod routes;
+pub mod timeout;
+pub mod validation;
 
 pub use routes::create_router;
@@ -0,0 +1,327 @@
+//! Permissions
+//!
+//! Typed API key / bearer token permissions and the `RequirePermission`
+//! extractor used by every route.
+//!
+//! Hierarchy:
+//! - `admin` implies every permission
+//! - `admin:*` implies every `admin:<area>` permission
+//! - every other permission only grants itself
+//!
+//! Legacy spellings (`mint`, `burn`) are accepted when parsing and map to
+//! their `admin:` equivalents. Unknown strings never grant anything.
+//!
+//! Platform areas (projections, jobs, audit, events, tenants, ...) act on
+//! every tenant's data; only keys of the default tenant can hold them.
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use uuid::Uuid;
+
+use super::middleware::AuthenticatedApiKey;
+use crate::error::AppError;
+
+/// A permission that can be granted to an API key or bearer token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Permission {
+    /// Superuser: implies every permission
+    Admin,
+    /// Implies every `admin:<area>` permission
+    AdminAll,
+    ReadUsers,
+    WriteUsers,
+    ReadAccounts,
+    WriteTransfers,
+    AdminMint,
+    AdminBurn,
+    AdminUsers,
+    AdminTransfers,
+    AdminAccounts,
+    AdminProjections,
+    AdminSupply,
+    AdminReconciliation,
+    AdminAudit,
+    AdminWebhooks,
+    AdminLimits,
+    AdminEvents,
+    AdminApiKeys,
+    AdminReports,
+    AdminJobs,
+    AdminTenants,
+}
+
+impl Permission {
+    /// Every permission, in documentation order
+    pub const ALL: [Permission; 22] = [
+        Permission::Admin,
+        Permission::AdminAll,
+        Permission::ReadUsers,
+        Permission::WriteUsers,
+        Permission::ReadAccounts,
+        Permission::WriteTransfers,
+        Permission::AdminMint,
+        Permission::AdminBurn,
+        Permission::AdminUsers,
+        Permission::AdminTransfers,
+        Permission::AdminAccounts,
+        Permission::AdminProjections,
+        Permission::AdminSupply,
+        Permission::AdminReconciliation,
+        Permission::AdminAudit,
+        Permission::AdminWebhooks,
+        Permission::AdminLimits,
+        Permission::AdminEvents,
+        Permission::AdminApiKeys,
+        Permission::AdminReports,
+        Permission::AdminJobs,
+        Permission::AdminTenants,
+    ];
+
+    /// Canonical string form (as stored in api_keys.permissions)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::Admin => "admin",
+            Permission::AdminAll => "admin:*",
+            Permission::ReadUsers => "read:users",
+            Permission::WriteUsers => "write:users",
+            Permission::ReadAccounts => "read:accounts",
+            Permission::WriteTransfers => "write:transfers",
+            Permission::AdminMint => "admin:mint",
+            Permission::AdminBurn => "admin:burn",
+            Permission::AdminUsers => "admin:users",
+            Permission::AdminTransfers => "admin:transfers",
+            Permission::AdminAccounts => "admin:accounts",
+            Permission::AdminProjections => "admin:projections",
+            Permission::AdminSupply => "admin:supply",
+            Permission::AdminReconciliation => "admin:reconciliation",
+            Permission::AdminAudit => "admin:audit",
+            Permission::AdminWebhooks => "admin:webhooks",
+            Permission::AdminLimits => "admin:limits",
+            Permission::AdminEvents => "admin:events",
+            Permission::AdminApiKeys => "admin:api-keys",
+            Permission::AdminReports => "admin:reports",
+            Permission::AdminJobs => "admin:jobs",
+            Permission::AdminTenants => "admin:tenants",
+        }
+    }
+
+    /// Whether this is one of the `admin:<area>` permissions
+    pub fn is_admin_area(&self) -> bool {
+        !matches!(self, Permission::Admin | Permission::AdminAll) && self.as_str().starts_with("admin:")
+    }
+
+    /// Whether this admin area spans all tenants (deployment-wide state such
+    /// as projections, jobs, the audit chain, the event stream or webhooks)
+    pub fn is_platform(&self) -> bool {
+        matches!(
+            self,
+            Permission::AdminProjections
+                | Permission::AdminSupply
+                | Permission::AdminReconciliation
+                | Permission::AdminAudit
+                | Permission::AdminWebhooks
+                | Permission::AdminLimits
+                | Permission::AdminEvents
+                | Permission::AdminReports
+                | Permission::AdminJobs
+                | Permission::AdminTenants
+        )
+    }
+
+    /// Whether holding this permission grants `required`
+    pub fn implies(&self, required: Permission) -> bool {
+        match self {
+            Permission::Admin => true,
+            Permission::AdminAll => required == Permission::AdminAll || required.is_admin_area(),
+            granted => *granted == required,
+        }
+    }
+
+    /// Parse and canonicalize permission strings, rejecting unknown ones
+    pub fn canonicalize(permissions: &[String]) -> Result<Vec<String>, AppError> {
+        let mut canonical: Vec<String> = Vec::with_capacity(permissions.len());
+        for permission in permissions {
+            let permission = permission
+                .parse::<Permission>()
+                .map_err(|e| AppError::InvalidRequest(e.to_string()))?
+                .as_str()
+                .to_string();
+            if !canonical.contains(&permission) {
+                canonical.push(permission);
+            }
+        }
+        Ok(canonical)
+    }
+}
+
+impl fmt::Display for Permission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Permission {
+    type Err = UnknownPermission;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "mint" => Ok(Permission::AdminMint),
+            "burn" => Ok(Permission::AdminBurn),
+            trimmed => Permission::ALL
+                .into_iter()
+                .find(|p| p.as_str() == trimmed)
+                .ok_or_else(|| UnknownPermission(s.to_string())),
+        }
+    }
+}
+
+/// Error for permission strings that do not name a Permission
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown permission: {0}")]
+pub struct UnknownPermission(pub String);
+
+/// Fail with 403 unless `api_key` holds (or implies) `permission`
+pub fn require_permission(api_key: &AuthenticatedApiKey, permission: Permission) -> Result<(), AppError> {
+    if api_key.has_permission(permission) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!("{} permission required", permission)))
+    }
+}
+
+/// Fail with 403 unless `api_key` may read data of `user_id` (see
+/// `AuthenticatedApiKey::can_read_user`)
+pub fn require_user_access(
+    api_key: &AuthenticatedApiKey,
+    request_user_id: Option<Uuid>,
+    user_id: Uuid,
+) -> Result<(), AppError> {
+    if api_key.can_read_user(request_user_id, user_id) {
+        Ok(())
+    } else {
+        Err(AppError::Forbidden(format!(
+            "{} permission required to read other users",
+            Permission::ReadUsers
+        )))
+    }
+}
+
+/// Type-level permission for `RequirePermission`
+pub trait RequiredPermission: Send + Sync + 'static {
+    const PERMISSION: Permission;
+}
+
+/// Marker types naming each permission, for `RequirePermission<perms::X>`
+pub mod perms {
+    use super::{Permission, RequiredPermission};
+
+    macro_rules! permission_markers {
+        ($($name:ident),* $(,)?) => {
+            $(
+                #[derive(Debug, Clone, Copy)]
+                pub struct $name;
+
+                impl RequiredPermission for $name {
+                    const PERMISSION: Permission = Permission::$name;
+                }
+            )*
+        };
+    }
+
+    permission_markers!(
+        ReadUsers,
+        WriteUsers,
+        ReadAccounts,
+        WriteTransfers,
+        AdminMint,
+        AdminBurn,
+        AdminUsers,
+        AdminTransfers,
+        AdminAccounts,
+        AdminProjections,
+        AdminSupply,
+        AdminReconciliation,
+        AdminAudit,
+        AdminWebhooks,
+        AdminLimits,
+        AdminEvents,
+        AdminApiKeys,
+        AdminReports,
+        AdminJobs,
+        AdminTenants,
+    );
+}
+
+/// Extractor that yields the authenticated API key if it holds `P`,
+/// rejecting the request with 403 otherwise
+#[derive(Debug, Clone)]
+pub struct RequirePermission<P: RequiredPermission>(pub AuthenticatedApiKey, PhantomData<P>);
+
+#[async_trait]
+impl<S, P> FromRequestParts<S> for RequirePermission<P>
+where
+    S: Send + Sync,
+    P: RequiredPermission,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let api_key = parts
+            .extensions
+            .get::<AuthenticatedApiKey>()
+            .cloned()
+            .ok_or(AppError::InvalidApiKey)?;
+
+        require_permission(&api_key, P::PERMISSION)?;
+
+        Ok(Self(api_key, PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(permission.as_str().parse::<Permission>().unwrap(), permission);
+        }
+        assert_eq!("mint".parse::<Permission>().unwrap(), Permission::AdminMint);
+        assert_eq!("burn".parse::<Permission>().unwrap(), Permission::AdminBurn);
+        assert!("admin:everything".parse::<Permission>().is_err());
+    }
+
+    #[test]
+    fn test_permission_hierarchy() {
+        assert!(Permission::Admin.implies(Permission::ReadUsers));
+        assert!(Permission::Admin.implies(Permission::AdminApiKeys));
+        assert!(Permission::AdminAll.implies(Permission::AdminMint));
+        assert!(!Permission::AdminAll.implies(Permission::WriteTransfers));
+        assert!(!Permission::AdminAll.implies(Permission::Admin));
+        assert!(!Permission::AdminMint.implies(Permission::AdminBurn));
+        assert!(!Permission::WriteUsers.implies(Permission::ReadUsers));
+    }
+
+    #[test]
+    fn test_platform_permissions() {
+        assert!(Permission::AdminTenants.is_platform());
+        assert!(Permission::AdminJobs.is_platform());
+        assert!(!Permission::AdminMint.is_platform());
+        assert!(!Permission::AdminApiKeys.is_platform());
+        assert!(!Permission::Admin.is_platform());
+    }
+
+    #[test]
+    fn test_canonicalize_permissions() {
+        let permissions = vec!["mint".to_string(), "admin:mint".to_string(), "read:users".to_string()];
+        assert_eq!(
+            Permission::canonicalize(&permissions).unwrap(),
+            vec!["admin:mint", "read:users"]
+        );
+        assert!(Permission::canonicalize(&["superuser".to_string()]).is_err());
+    }
+}
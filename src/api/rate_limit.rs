@@ -0,0 +1,319 @@
+//! Rate Limit Stores
+//!
+//! Per-API-key request counters for fixed one-minute windows. The default
+//! store keeps buckets in the rate_limit_buckets table; multi-instance
+//! deployments can move that write load off the primary with the Redis
+//! store (`redis` feature, RATE_LIMIT_BACKEND=redis).
+//!
+//! When USER_RATE_LIMIT_PER_MINUTE is set, requests made for a user
+//! (X-Request-User-Id or token subject) are also counted per key and user,
+//! so one end user of a partner key cannot exhaust the key's budget.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use axum::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::{Config, ConfigError};
+
+/// Where rate limit buckets are kept
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitBackend {
+    /// rate_limit_buckets table
+    #[default]
+    Postgres,
+    /// INCR/EXPIRE counters in Redis
+    #[cfg(feature = "redis")]
+    Redis,
+}
+
+impl FromStr for RateLimitBackend {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "postgres" | "postgresql" => Ok(RateLimitBackend::Postgres),
+            #[cfg(feature = "redis")]
+            "redis" => Ok(RateLimitBackend::Redis),
+            _ => Err(ConfigError::InvalidValue("RATE_LIMIT_BACKEND")),
+        }
+    }
+}
+
+/// Counter of the current window after counting a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitWindow {
+    pub request_count: i32,
+    pub window_start: DateTime<Utc>,
+}
+
+/// Effective rate limit of an API key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct RateLimitPolicy {
+    /// Requests allowed per one-minute window
+    pub per_minute: i32,
+    /// Extra requests allowed above `per_minute` within a window
+    pub burst: i32,
+    /// Requests are neither counted nor limited
+    pub exempt: bool,
+}
+
+impl RateLimitPolicy {
+    /// Global default of keys without their own settings
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            per_minute: config.rate_limit_per_minute,
+            burst: config.rate_limit_burst,
+            exempt: false,
+        }
+    }
+
+    /// Apply a key's stored settings (NULL columns keep the default)
+    pub fn with_overrides(self, per_minute: Option<i32>, burst: Option<i32>, exempt: bool) -> Self {
+        Self {
+            per_minute: per_minute.unwrap_or(self.per_minute),
+            burst: burst.unwrap_or(self.burst),
+            exempt,
+        }
+    }
+
+    /// Per-user limit within a key, if USER_RATE_LIMIT_PER_MINUTE is set.
+    /// Applies to every key that is not exempt.
+    pub fn per_user_from_config(config: &Config) -> Option<Self> {
+        config.user_rate_limit_per_minute.map(|per_minute| Self {
+            per_minute,
+            burst: config.user_rate_limit_burst,
+            exempt: false,
+        })
+    }
+
+    /// Most requests allowed in one window
+    pub fn ceiling(&self) -> i32 {
+        self.per_minute.saturating_add(self.burst)
+    }
+}
+
+/// Rate limit counter storage
+#[async_trait]
+pub trait RateLimitStore: Send + Sync + fmt::Debug {
+    /// Count one request of `api_key_id` in the current minute window
+    async fn increment(&self, api_key_id: Uuid) -> Result<RateLimitWindow, RateLimitError>;
+
+    /// Count one request `api_key_id` made for `user_id` in the current window
+    async fn increment_user(&self, api_key_id: Uuid, user_id: Uuid) -> Result<RateLimitWindow, RateLimitError>;
+}
+
+/// Build the store selected by RATE_LIMIT_BACKEND
+pub fn from_config(config: &Config, pool: PgPool) -> Arc<dyn RateLimitStore> {
+    match config.rate_limit_backend {
+        RateLimitBackend::Postgres => Arc::new(PgRateLimitStore::new(pool)),
+        #[cfg(feature = "redis")]
+        RateLimitBackend::Redis => Arc::new(RedisRateLimitStore::new(
+            config.redis_url.clone().unwrap_or_default(),
+        )),
+    }
+}
+
+// =========================================================================
+// Postgres
+// =========================================================================
+
+/// Buckets in the rate_limit_buckets table (cleaned up by the job runner)
+#[derive(Debug, Clone)]
+pub struct PgRateLimitStore {
+    pool: PgPool,
+}
+
+impl PgRateLimitStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for PgRateLimitStore {
+    async fn increment(&self, api_key_id: Uuid) -> Result<RateLimitWindow, RateLimitError> {
+        // Same bucket as check_and_increment_rate_limit, but returning the
+        // count for headers
+        let (request_count, window_start): (i32, DateTime<Utc>) = sqlx::query_as(
+            r#"
+            INSERT INTO rate_limit_buckets (api_key_id, window_start, request_count)
+            VALUES ($1, date_trunc('minute', NOW()), 1)
+            ON CONFLICT (api_key_id, window_start)
+            DO UPDATE SET request_count = rate_limit_buckets.request_count + 1
+            RETURNING request_count, window_start
+            "#,
+        )
+        .bind(api_key_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(RateLimitWindow {
+            request_count,
+            window_start,
+        })
+    }
+
+    async fn increment_user(&self, api_key_id: Uuid, user_id: Uuid) -> Result<RateLimitWindow, RateLimitError> {
+        let (request_count, window_start): (i32, DateTime<Utc>) = sqlx::query_as(
+            r#"
+            INSERT INTO user_rate_limit_buckets (api_key_id, user_id, window_start, request_count)
+            VALUES ($1, $2, date_trunc('minute', NOW()), 1)
+            ON CONFLICT (api_key_id, user_id, window_start)
+            DO UPDATE SET request_count = user_rate_limit_buckets.request_count + 1
+            RETURNING request_count, window_start
+            "#,
+        )
+        .bind(api_key_id)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(RateLimitWindow {
+            request_count,
+            window_start,
+        })
+    }
+}
+
+// =========================================================================
+// Redis
+// =========================================================================
+
+/// Seconds a Redis counter outlives its window
+#[cfg(feature = "redis")]
+const REDIS_COUNTER_TTL_SECS: i64 = 120;
+
+/// Start of the minute window containing `now`, and the key suffix for it
+#[cfg(any(feature = "redis", test))]
+fn minute_window(now: DateTime<Utc>) -> (i64, DateTime<Utc>) {
+    let minute = now.timestamp().div_euclid(60);
+    let window_start = DateTime::from_timestamp(minute * 60, 0).unwrap_or(now);
+    (minute, window_start)
+}
+
+/// Counters keyed `rate_limit:{api_key_id}:{minute}` (per user:
+/// `rate_limit:{api_key_id}:user:{user_id}:{minute}`), expired by Redis.
+/// The connection is opened on first use and shared (multiplexed) after that.
+#[cfg(feature = "redis")]
+pub struct RedisRateLimitStore {
+    url: String,
+    connection: tokio::sync::OnceCell<redis::aio::MultiplexedConnection>,
+}
+
+#[cfg(feature = "redis")]
+impl fmt::Debug for RedisRateLimitStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The URL may carry a password
+        f.debug_struct("RedisRateLimitStore")
+            .field("connected", &self.connection.initialized())
+            .finish()
+    }
+}
+
+#[cfg(feature = "redis")]
+impl RedisRateLimitStore {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            connection: tokio::sync::OnceCell::new(),
+        }
+    }
+
+    async fn connection(&self) -> Result<redis::aio::MultiplexedConnection, RateLimitError> {
+        let connection = self
+            .connection
+            .get_or_try_init(|| async {
+                redis::Client::open(self.url.as_str())?
+                    .get_multiplexed_tokio_connection()
+                    .await
+            })
+            .await?;
+        Ok(connection.clone())
+    }
+
+    /// INCR `{prefix}:{minute}` and refresh its expiry
+    async fn increment_key(&self, prefix: &str) -> Result<RateLimitWindow, RateLimitError> {
+        let (minute, window_start) = minute_window(Utc::now());
+        let key = format!("{}:{}", prefix, minute);
+
+        let mut connection = self.connection().await?;
+        let (request_count,): (i64,) = redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .expire(&key, REDIS_COUNTER_TTL_SECS)
+            .ignore()
+            .query_async(&mut connection)
+            .await?;
+
+        Ok(RateLimitWindow {
+            request_count: i32::try_from(request_count).unwrap_or(i32::MAX),
+            window_start,
+        })
+    }
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl RateLimitStore for RedisRateLimitStore {
+    async fn increment(&self, api_key_id: Uuid) -> Result<RateLimitWindow, RateLimitError> {
+        self.increment_key(&format!("rate_limit:{}", api_key_id)).await
+    }
+
+    async fn increment_user(&self, api_key_id: Uuid, user_id: Uuid) -> Result<RateLimitWindow, RateLimitError> {
+        self.increment_key(&format!("rate_limit:{}:user:{}", api_key_id, user_id)).await
+    }
+}
+
+/// Rate limit store errors
+#[derive(Debug, thiserror::Error)]
+pub enum RateLimitError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[cfg(feature = "redis")]
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_parse() {
+        assert_eq!("postgres".parse::<RateLimitBackend>().unwrap(), RateLimitBackend::Postgres);
+        assert_eq!(" Postgres ".parse::<RateLimitBackend>().unwrap(), RateLimitBackend::Postgres);
+        assert_eq!("redis".parse::<RateLimitBackend>().is_ok(), cfg!(feature = "redis"));
+        assert!("memcached".parse::<RateLimitBackend>().is_err());
+    }
+
+    #[test]
+    fn test_policy_overrides() {
+        let defaults = RateLimitPolicy {
+            per_minute: 100,
+            burst: 20,
+            exempt: false,
+        };
+        assert_eq!(defaults.with_overrides(None, None, false), defaults);
+        assert_eq!(defaults.ceiling(), 120);
+
+        let own = defaults.with_overrides(Some(10), Some(0), true);
+        assert_eq!((own.per_minute, own.burst, own.exempt), (10, 0, true));
+        assert_eq!(RateLimitPolicy { burst: i32::MAX, ..own }.ceiling(), i32::MAX);
+    }
+
+    #[test]
+    fn test_minute_window() {
+        let now = DateTime::parse_from_rfc3339("2024-05-01T12:34:56Z").unwrap().with_timezone(&Utc);
+        let (minute, window_start) = minute_window(now);
+
+        assert_eq!(window_start, DateTime::parse_from_rfc3339("2024-05-01T12:34:00Z").unwrap());
+        assert_eq!(minute * 60, window_start.timestamp());
+    }
+}
@@ -3,27 +3,74 @@
 //! HTTP endpoint definitions.
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
-    routing::{delete, get, patch, post},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
+    response::{IntoResponse, Response},
+    routing::{delete, get, patch, post, put},
     Json, Router,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use sha2::Digest;
-use sqlx::PgPool;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::{Stream, StreamExt};
 use uuid::Uuid;
 
-use crate::domain::OperationContext;
+use crate::aggregate::Account;
+use crate::alerts::{Alert, ListAlertsQuery};
+use crate::config::Config;
+use crate::audit::{
+    load_trace, AuditAction, AuditLogBuilder, AuditLogEntry, AuditLogFilter,
+    ChainVerificationResult, Trace,
+};
+use crate::domain::{ApiKeyId, DomainError, OperationContext};
 use crate::error::AppError;
+use crate::event_store::{
+    export_ndjson, DeadLetterEvent, EventExportFilter, StoredEvent, Subscription, SubscriptionStatus,
+};
+use crate::jobs::{
+    self, AuditArchive, AuditCheckpoint, DailyStats, EventIntegrityReport, Job, JobError, JobRun, JobScheduler,
+    JobSchedulerConfig, JobStatus, ReconciliationReport, SnapshotMaintenanceReport,
+};
+use crate::limits::{ApiKeyCaps, ApiKeyLimits, LimitOperation, TransferLimit};
+use crate::receipts;
+use crate::restrictions::{AccountRestriction, RestrictionError, RestrictionMode};
 use crate::handlers::{
-    CreateUserCommand, CreateUserHandler, MintCommand, MintHandler, TransferCommand,
-    TransferHandler, UpdateUserCommand, UpdateUserHandler, DeactivateUserCommand, DeactivateUserHandler,
+    CreateUserCommand, CreateUserHandler, MintCommand, MintHandler, SupplyCapStatus, TransferCommand,
+    TransferApprovalHandler, TransferHandler, TransferQuote, TransferResult, UpdateUserCommand, UpdateUserHandler, DeactivateUserCommand, DeactivateUserHandler,
+    ReactivateUserCommand, ReactivateUserHandler, AnonymizeUserCommand, AnonymizeUserHandler,
+    RenameUserCommand, RenameUserHandler,
+    FreezeAccountCommand, FreezeAccountHandler, FreezeAccountResult,
+    CloseAccountCommand, CloseAccountHandler,
+    CreateAccountCommand, CreateAccountHandler, CreateAccountResult,
+    HoldCommand, HoldHandler, HoldResult,
+    ReverseTransferCommand, ReverseTransferHandler,
+};
+use crate::state::{MetricsSnapshot, SharedState};
+use crate::tenants::{Tenant, TenantResource, TenantSummary, TenantUpdate};
+use crate::webhooks::{
+    generate_secret, WebhookDelivery, WebhookEndpoint, WebhookError, WebhookEventType,
+};
+use crate::queries::{
+    default_limit, AccountFreezeStatus, CategoryReport, CategoryReportQuery, EventPage, GetAccountStatusQuery,
+    GetHistoryQuery, GetTransferQuery, GetUserQuery,
+    ListEventsQuery, ListPendingTransfersQuery, ListUsersQuery, PendingTransferPage, QueryHandler,
+    SearchUsersQuery, TransferDetail, UserPage, UserView,
+};
+use crate::projection::{
+    self, AccountBalance, BalanceCacheStats, Notification, ProjectionError, ProjectionStatus, RebuildReport,
+    ShadowRebuildReport, StatementCursor,
+    StatementLine, SupplyReport, TransferCursor, TransferFilter, TransferStatusFilter,
 };
-use crate::projection::ProjectionService;
 
+use super::etag;
+use super::keys::{check_tenant_permissions, generate_api_key, hash_api_key, issue_api_key, normalize_user_scope};
 use super::middleware::{AuthenticatedApiKey, RequestUser};
+use super::permission::{perms, require_permission, require_user_access, Permission, RequirePermission};
+use super::rate_limit::RateLimitPolicy;
+use super::validation::{AmountInput, ApiJson, FieldErrors, ValidJson, Validate};
 
 // =========================================================================
 // Request/Response types
@@ -38,6 +85,14 @@ pub struct CreateUserRequest {
     pub display_name: Option<String>,
 }
 
+impl Validate for CreateUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.username("username", &self.username);
+        errors.email("email", &self.email);
+        errors.display_name("display_name", self.display_name.as_deref());
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct CreateUserResponse {
     pub user_id: Uuid,
@@ -49,15 +104,8 @@ pub struct CreateUserResponse {
 }
 
 #[derive(Debug, Serialize)]
-pub struct UserResponse {
-    pub id: Uuid,
-    pub username: String,
-    pub email: String,
-    pub display_name: Option<String>,
-    pub is_system: bool,
-    pub is_active: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
+pub struct UserSearchResponse {
+    pub users: Vec<UserView>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -68,13 +116,37 @@ pub struct UpdateUserRequest {
     pub email: Option<String>,
 }
 
+impl Validate for UpdateUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.display_name("display_name", self.display_name.as_deref());
+        if let Some(email) = &self.email {
+            errors.email("email", email);
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct TransferRequest {
     pub from_user_id: Uuid,
     pub to_user_id: Uuid,
-    pub amount: String,
+    pub amount: AmountInput,
     #[serde(default)]
     pub memo: Option<String>,
+    /// Reporting category (one of TRANSFER_CATEGORIES)
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Sender's account (primary wallet if omitted)
+    #[serde(default)]
+    pub from_account_id: Option<Uuid>,
+    /// Recipient's account (primary wallet if omitted)
+    #[serde(default)]
+    pub to_account_id: Option<Uuid>,
+}
+
+impl Validate for TransferRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.memo("memo", self.memo.as_deref());
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -84,40 +156,170 @@ pub struct TransferResponse {
     pub from_user_id: Uuid,
     pub to_user_id: Uuid,
     pub amount: Decimal,
+    /// Wallet versions after the transfer (null until money moves)
+    pub from_account_version: Option<i64>,
+    pub to_account_version: Option<i64>,
+    pub event_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RejectTransferRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Validate for RejectTransferRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", self.reason.as_deref());
+    }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct ReverseTransferRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Validate for ReverseTransferRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", self.reason.as_deref());
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReverseTransferResponse {
+    pub reversal_id: Uuid,
+    pub original_transfer_id: Uuid,
+    pub status: String,
+    /// Original recipient, debited by the reversal
+    pub from_user_id: Uuid,
+    /// Original sender, credited by the reversal
+    pub to_user_id: Uuid,
+    pub amount: Decimal,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct HoldRequest {
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: AmountInput,
+    #[serde(default)]
+    pub memo: Option<String>,
+}
+
+impl Validate for HoldRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.memo("memo", self.memo.as_deref());
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct HoldResponse {
+    pub hold_id: Uuid,
+    pub status: String,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListTransfersQuery {
+    #[serde(default)]
+    pub from_user_id: Option<Uuid>,
+    #[serde(default)]
+    pub to_user_id: Option<Uuid>,
+    #[serde(default)]
+    pub from_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub min_amount: Option<Decimal>,
+    #[serde(default)]
+    pub max_amount: Option<Decimal>,
+    /// completed (default), failed or all
+    #[serde(default)]
+    pub status: TransferStatusFilter,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// Full-text query for GET /transfers/search (other filters as ListTransfersQuery)
+#[derive(Debug, Deserialize)]
+pub struct SearchTransfersQuery {
+    pub q: String,
+}
+
 #[derive(Debug, Serialize)]
-pub struct TransferDetailResponse {
+pub struct TransferListItem {
     pub id: Uuid,
-    pub from_account_id: Uuid,
-    pub to_account_id: Uuid,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
     pub amount: Decimal,
-    pub description: String,
+    pub description: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// "completed" or "failed"
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    /// User who requested the transfer (failed transfers only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initiated_by: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TransferListResponse {
+    pub transfers: Vec<TransferListItem>,
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct MintRequest {
     pub recipient_user_id: Uuid,
-    pub amount: String,
+    pub amount: AmountInput,
     pub reason: String,
 }
 
+impl Validate for MintRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", Some(&self.reason));
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct MintResponse {
     pub mint_id: Uuid,
     pub status: String,
     pub to_user_id: Uuid,
     pub amount: Decimal,
+    /// Recipient's wallet version after the mint
+    pub to_account_version: Option<i64>,
+    pub event_ids: Vec<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct BurnRequest {
     pub from_user_id: Uuid,
-    pub amount: String,
+    pub amount: AmountInput,
     pub reason: String,
+    /// Burn without the wallet owner's consent (BURN_CONSENT_POLICY=consent)
+    #[serde(default)]
+    pub force: bool,
+    /// Required with force
+    pub force_reason: Option<String>,
+}
+
+impl Validate for BurnRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", Some(&self.reason));
+        errors.reason("force_reason", self.force_reason.as_deref());
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -126,143 +328,812 @@ pub struct BurnResponse {
     pub status: String,
     pub from_user_id: Uuid,
     pub amount: Decimal,
+    /// User's wallet version after the burn
+    pub from_account_version: Option<i64>,
+    pub event_ids: Vec<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct FreezeAccountRequest {
+    pub reason: String,
+}
+
+impl Validate for FreezeAccountRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", Some(&self.reason));
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DeactivateUserRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// Move the remaining balance to the recovery account (RECOVERY_ACCOUNT_ID)
+    #[serde(default)]
+    pub sweep_balance: bool,
+}
+
+impl Validate for DeactivateUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", self.reason.as_deref());
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct AnonymizeUserRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Validate for AnonymizeUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", self.reason.as_deref());
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenameUserRequest {
+    pub username: String,
+}
+
+impl Validate for RenameUserRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.username("username", &self.username);
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenameUserResponse {
+    pub user_id: Uuid,
+    pub old_username: String,
+    pub username: String,
+    pub renamed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnonymizeUserResponse {
+    pub user_id: Uuid,
+    pub anonymized_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountStatusResponse {
+    pub account_id: Uuid,
+    pub status: String,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CloseAccountRequest {
+    /// Account that receives the remaining balance (required unless it is zero)
+    #[serde(default)]
+    pub sweep_to_account_id: Option<Uuid>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl Validate for CloseAccountRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.reason("reason", self.reason.as_deref());
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CloseAccountResponse {
+    pub account_id: Uuid,
+    pub status: String,
+    pub swept_amount: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub swept_to_account_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sweep_id: Option<Uuid>,
+    pub closed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RebuildProjectionsQuery {
+    /// Rebuild only this account (all accounts if omitted)
+    #[serde(default)]
+    pub account_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ShadowRebuildQuery {
+    /// Verify and report drift without swapping the tables
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectionStatusQuery {
+    /// Lagging accounts to list (most lagging first)
+    #[serde(default = "default_projection_status_limit")]
+    pub limit: i64,
+}
+
+fn default_projection_status_limit() -> i64 {
+    50
+}
+
 #[derive(Debug, Deserialize)]
 pub struct BalanceQuery {
     pub user_id: Uuid,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct BalanceAsOfQuery {
+    /// Return the historical balance at this instant
+    #[serde(default)]
+    pub as_of: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Serialize)]
 pub struct BalanceResponse {
     pub user_id: Uuid,
     pub balance: Decimal,
+    /// Balance minus active holds
+    pub available_balance: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub as_of: Option<DateTime<Utc>>,
+    /// Every wallet account of the user (primary wallet first); the top-level
+    /// balances are those of the primary wallet. Omitted with as_of.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub accounts: Vec<AccountBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BalanceAssertionRequest {
+    /// Balance the primary wallet must currently hold
+    pub expected_balance: Decimal,
+    /// Wallet version the caller last observed; the assertion also fails if
+    /// the wallet has changed since
+    #[serde(default)]
+    pub as_of_version: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct HistoryEntry {
-    pub event_id: Uuid,
-    pub event_type: String,
-    pub amount: Option<Decimal>,
-    pub description: Option<String>,
-    pub created_at: DateTime<Utc>,
+pub struct BalanceAssertionResponse {
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub balance: Decimal,
+    /// Balance minus active holds
+    pub available_balance: Decimal,
+    /// Wallet version the assertion was checked against
+    pub version: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAccountRequest {
+    /// Unique per user (a-z, 0-9, '_' and '-')
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    /// Only notifications after this time (exclusive)
+    #[serde(default)]
+    pub since: Option<DateTime<Utc>>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
 }
 
 #[derive(Debug, Serialize)]
-pub struct HistoryResponse {
+pub struct NotificationsResponse {
     pub user_id: Uuid,
-    pub entries: Vec<HistoryEntry>,
+    pub notifications: Vec<Notification>,
 }
 
 #[derive(Debug, Deserialize)]
-pub struct EventsQuery {
+pub struct HistoryQuery {
+    /// Continue after this ledger entry (next_before_id of the previous page)
     #[serde(default)]
-    pub aggregate_type: Option<String>,
+    pub before_id: Option<Uuid>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StatementQuery {
+    /// Inclusive lower bound on entry time
+    #[serde(default)]
+    pub from_date: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on entry time
+    #[serde(default)]
+    pub to_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default = "default_statement_limit")]
+    pub limit: i64,
+}
+
+fn default_statement_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementResponse {
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    /// Balance before from_date
+    pub opening_balance: Decimal,
+    /// Balance at to_date (or now)
+    pub closing_balance: Decimal,
+    pub entries: Vec<StatementLine>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLettersQuery {
     #[serde(default)]
-    pub aggregate_id: Option<Uuid>,
+    pub include_resolved: bool,
     #[serde(default = "default_limit")]
     pub limit: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventExportQuery {
+    #[serde(default)]
+    pub aggregate_type: Option<String>,
+    #[serde(default)]
+    pub from_date: Option<DateTime<Utc>>,
     #[serde(default)]
-    pub offset: i64,
+    pub to_date: Option<DateTime<Utc>>,
+    /// Resume after this global_sequence (last line of a previous export)
+    #[serde(default)]
+    pub after_sequence: i64,
 }
 
-fn default_limit() -> i64 {
-    50
+#[derive(Debug, Deserialize)]
+pub struct EventStreamQuery {
+    #[serde(default)]
+    pub aggregate_type: Option<String>,
 }
 
+/// Full stored event (GET /admin/events/:event_id)
 #[derive(Debug, Serialize)]
-pub struct EventResponse {
+pub struct EventDetailResponse {
     pub id: Uuid,
+    pub global_sequence: i64,
     pub aggregate_type: String,
     pub aggregate_id: Uuid,
     pub event_type: String,
     pub version: i64,
+    pub event_data: serde_json::Value,
+    pub context: serde_json::Value,
+    pub idempotency_key: Option<Uuid>,
     pub created_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct EventsListResponse {
-    pub events: Vec<EventResponse>,
-    pub total: i64,
+impl From<StoredEvent> for EventDetailResponse {
+    fn from(event: StoredEvent) -> Self {
+        Self {
+            id: event.id,
+            global_sequence: event.global_sequence,
+            aggregate_type: event.aggregate_type,
+            aggregate_id: event.aggregate_id,
+            event_type: event.event_type,
+            version: event.version,
+            event_data: event.event_data,
+            context: event.context,
+            idempotency_key: event.idempotency_key,
+            created_at: event.created_at,
+        }
+    }
 }
 
-// =========================================================================
-// API Key Management Types
-// =========================================================================
+#[derive(Debug, Deserialize)]
+pub struct AuditLogsQuery {
+    #[serde(default)]
+    pub action: Option<String>,
+    #[serde(default)]
+    pub resource_type: Option<String>,
+    #[serde(default)]
+    pub resource_id: Option<Uuid>,
+    #[serde(default)]
+    pub correlation_id: Option<Uuid>,
+    #[serde(default)]
+    pub from_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to_date: Option<DateTime<Utc>>,
+    /// sequence_number of the last entry from the previous page
+    #[serde(default)]
+    pub cursor: Option<i64>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
 
-#[derive(Debug, Deserialize, Serialize)]
-pub struct CreateApiKeyRequest {
-    pub name: String,
-    pub permissions: Vec<String>,
-    #[serde(default = "default_rate_limit")]
-    pub rate_limit_per_minute: i32,
+#[derive(Debug, Serialize)]
+pub struct AuditLogListResponse {
+    pub entries: Vec<AuditLogEntry>,
+    pub next_cursor: Option<i64>,
 }
 
-fn default_rate_limit() -> i32 {
-    1000
+#[derive(Debug, Deserialize)]
+pub struct ReconciliationQuery {
+    /// Run a reconciliation now instead of only returning stored reports
+    #[serde(default)]
+    pub run: bool,
+    #[serde(default = "default_reconciliation_limit")]
+    pub limit: i64,
 }
 
-#[derive(Debug, Serialize)]
-pub struct CreateApiKeyResponse {
-    pub id: Uuid,
-    pub name: String,
-    pub api_key: String,  // Only returned on creation
-    pub key_prefix: String,
-    pub permissions: Vec<String>,
-    pub rate_limit_per_minute: i32,
-    pub created_at: DateTime<Utc>,
+fn default_reconciliation_limit() -> i64 {
+    10
 }
 
-#[derive(Debug, Serialize)]
-pub struct ApiKeyResponse {
-    pub id: Uuid,
-    pub name: String,
-    pub key_prefix: String,
-    pub permissions: Vec<String>,
-    pub rate_limit_per_minute: i32,
+/// Days of GET /admin/stats and GET /admin/events/integrity, inclusive
+/// (defaults to the last 30 days)
+#[derive(Debug, Default, Deserialize)]
+pub struct DailyStatsQuery {
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+}
+
+/// Longest range served by GET /admin/stats
+const MAX_STATS_DAYS: i64 = 366;
+
+impl DailyStatsQuery {
+    /// Resolve defaults relative to `today` and validate the range
+    fn range(&self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), AppError> {
+        let to = self.to.unwrap_or(today);
+        let from = self.from.unwrap_or(to - chrono::Duration::days(29));
+        if from > to {
+            return Err(AppError::InvalidRequest("from must not be after to".to_string()));
+        }
+        if (to - from).num_days() >= MAX_STATS_DAYS {
+            return Err(AppError::InvalidRequest(format!(
+                "date range must not exceed {} days",
+                MAX_STATS_DAYS
+            )));
+        }
+        Ok((from, to))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DailyStatsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Materialized days only; the current day appears after it has ended
+    pub days: Vec<DailyStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricsResponse {
+    pub requests: MetricsSnapshot,
+    /// Omitted when the balance cache is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_cache: Option<BalanceCacheStats>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyAuditLogsQuery {
+    /// Number of entries to check from the start of the chain
+    #[serde(default)]
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListAuditArchivesQuery {
+    #[serde(default = "default_reconciliation_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditCheckpointsQuery {
+    /// Verify new entries (and publish the anchor) now instead of only
+    /// returning stored checkpoints
+    #[serde(default)]
+    pub run: bool,
+    #[serde(default = "default_reconciliation_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    /// Only list runs of this job
+    #[serde(default)]
+    pub job: Option<String>,
+    #[serde(default = "default_job_runs_limit")]
+    pub limit: i64,
+}
+
+fn default_job_runs_limit() -> i64 {
+    50
+}
+
+/// GET /admin/jobs response
+#[derive(Debug, Serialize)]
+pub struct JobsResponse {
+    pub jobs: Vec<JobStatus>,
+    /// Most recent runs, newest first
+    pub runs: Vec<JobRun>,
+}
+
+// =========================================================================
+// Webhook Types
+// =========================================================================
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Event types to deliver (empty = all)
+    #[serde(default)]
+    pub event_types: Vec<WebhookEventType>,
+    /// Signing secret; generated when omitted
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateWebhookResponse {
+    #[serde(flatten)]
+    pub endpoint: WebhookEndpoint,
+    pub secret: String,  // Only returned on creation
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookDeliveriesQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+// =========================================================================
+// Transfer Limit Types
+// =========================================================================
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetTransferLimitRequest {
+    #[serde(default)]
+    pub min_amount: Option<Decimal>,
+    #[serde(default)]
+    pub max_amount: Option<Decimal>,
+    /// Maximum total per user over a rolling 24 hours
+    #[serde(default)]
+    pub daily_limit: Option<Decimal>,
+}
+
+// =========================================================================
+// Account Restriction Types
+// =========================================================================
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SetAccountRestrictionRequest {
+    pub mode: RestrictionMode,
+    /// Counterparty accounts (allowlist mode only)
+    #[serde(default)]
+    pub allowed_counterparties: Vec<Uuid>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+// =========================================================================
+// API Key Management Types
+// =========================================================================
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub permissions: Vec<String>,
+    #[serde(default = "default_rate_limit")]
+    pub rate_limit_per_minute: i32,
+    /// Requests allowed above the per-minute limit (None = RATE_LIMIT_BURST)
+    #[serde(default)]
+    pub rate_limit_burst: Option<i32>,
+    /// Skip rate limiting (internal trusted services)
+    #[serde(default)]
+    pub rate_limit_exempt: bool,
+    /// Key is rejected after this time (None = never expires)
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Users the key may act for (None or empty = any user)
+    #[serde(default)]
+    pub allowed_user_ids: Option<Vec<Uuid>>,
+    /// Tenant of the key (None = the caller's tenant; other tenants need
+    /// admin:tenants)
+    #[serde(default)]
+    pub tenant_id: Option<Uuid>,
+}
+
+impl Validate for CreateApiKeyRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.api_key_name("name", &self.name);
+        errors.permissions("permissions", &self.permissions);
+    }
+}
+
+fn default_rate_limit() -> i32 {
+    1000
+}
+
+#[derive(Debug, Serialize)]
+pub struct CreateApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub api_key: String,  // Only returned on creation
+    pub key_prefix: String,
+    pub permissions: Vec<String>,
+    pub rate_limit_per_minute: i32,
+    pub rate_limit_burst: Option<i32>,
+    pub rate_limit_exempt: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub allowed_user_ids: Option<Vec<Uuid>>,
+    pub tenant_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub key_prefix: String,
+    pub permissions: Vec<String>,
+    /// Own per-minute limit (None = RATE_LIMIT_PER_MINUTE)
+    pub rate_limit_per_minute: Option<i32>,
+    /// Own burst allowance (None = RATE_LIMIT_BURST)
+    pub rate_limit_burst: Option<i32>,
+    pub rate_limit_exempt: bool,
+    /// Limits applied to requests, with global defaults filled in
+    pub effective_rate_limit: RateLimitPolicy,
     pub is_active: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Users the key may act for (None = any user)
+    pub allowed_user_ids: Option<Vec<Uuid>>,
+    pub tenant_id: Uuid,
+    pub rotated_at: Option<DateTime<Utc>>,
+    /// End of the grace period of the secret replaced by the last rotation
+    pub previous_key_expires_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub last_used_at: Option<DateTime<Utc>>,
 }
 
+/// Row of `SELECT id, name, key_prefix, ... last_used_at FROM api_keys`
+#[derive(Debug, sqlx::FromRow)]
+struct ApiKeyRow {
+    id: Uuid,
+    name: String,
+    key_prefix: String,
+    permissions: Vec<String>,
+    rate_limit_per_minute: Option<i32>,
+    rate_limit_burst: Option<i32>,
+    rate_limit_exempt: bool,
+    is_active: bool,
+    expires_at: Option<DateTime<Utc>>,
+    allowed_user_ids: Option<Vec<Uuid>>,
+    tenant_id: Uuid,
+    rotated_at: Option<DateTime<Utc>>,
+    previous_key_expires_at: Option<DateTime<Utc>>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKeyResponse {
+    /// Build the response of a row; effective limits fall back to `config`
+    fn from_row(row: ApiKeyRow, config: &Config) -> Self {
+        let ApiKeyRow {
+            id,
+            name,
+            key_prefix,
+            permissions,
+            rate_limit_per_minute,
+            rate_limit_burst,
+            rate_limit_exempt,
+            is_active,
+            expires_at,
+            allowed_user_ids,
+            tenant_id,
+            rotated_at,
+            previous_key_expires_at,
+            created_at,
+            last_used_at,
+        } = row;
+        Self {
+            id,
+            name,
+            key_prefix,
+            permissions,
+            rate_limit_per_minute,
+            rate_limit_burst,
+            rate_limit_exempt,
+            effective_rate_limit: RateLimitPolicy::from_config(config).with_overrides(
+                rate_limit_per_minute,
+                rate_limit_burst,
+                rate_limit_exempt,
+            ),
+            is_active,
+            expires_at,
+            allowed_user_ids,
+            tenant_id,
+            rotated_at,
+            previous_key_expires_at,
+            created_at,
+            last_used_at,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateApiKeyRequest {
     pub name: Option<String>,
     pub permissions: Option<Vec<String>>,
     pub rate_limit_per_minute: Option<i32>,
+    pub rate_limit_burst: Option<i32>,
+    pub rate_limit_exempt: Option<bool>,
+    pub is_active: Option<bool>,
+    /// Replace the user scope; an empty list removes it
+    pub allowed_user_ids: Option<Vec<Uuid>>,
+}
+
+impl Validate for UpdateApiKeyRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        if let Some(name) = &self.name {
+            errors.api_key_name("name", name);
+        }
+        if let Some(permissions) = &self.permissions {
+            errors.permissions("permissions", permissions);
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RotateApiKeyRequest {
+    /// Seconds the previous secret stays valid (defaults to API_KEY_ROTATION_GRACE_SECS)
+    pub grace_period_secs: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RotateApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub api_key: String,  // Only returned on rotation
+    pub key_prefix: String,
+    pub previous_key_prefix: String,
+    pub previous_key_expires_at: DateTime<Utc>,
+    pub rotated_at: DateTime<Utc>,
+}
+
+// =========================================================================
+// Tenant Management Types
+// =========================================================================
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CreateTenantRequest {
+    pub name: String,
+}
+
+impl Validate for CreateTenantRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.tenant_name("name", &self.name);
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UpdateTenantRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// FALSE rejects every request authenticated for the tenant
+    #[serde(default)]
     pub is_active: Option<bool>,
 }
 
+impl Validate for UpdateTenantRequest {
+    fn validate(&self, errors: &mut FieldErrors) {
+        if let Some(name) = &self.name {
+            errors.tenant_name("name", name);
+        }
+    }
+}
+
 // =========================================================================
 // API Router
 // =========================================================================
 
 /// Create the API router
-pub fn create_router() -> Router<PgPool> {
+pub fn create_router() -> Router<SharedState> {
     Router::new()
         // M120: User endpoints
         .route("/users", post(create_user))
+        .route("/users", get(list_users))
+        .route("/users/search", get(search_users))
         // M121, M122, M123: User CRUD
         .route("/users/:user_id", get(get_user))
         .route("/users/:user_id", patch(update_user))
         .route("/users/:user_id", delete(delete_user))
+        .route("/users/:user_id/reactivate", post(reactivate_user))
         // M124: Balance
+        .route("/users/:user_id/accounts", post(create_account))
         .route("/users/:user_id/balance", get(get_user_balance))
+        .route("/users/:user_id/balance/assert", post(assert_user_balance))
         // M125: History
         .route("/users/:user_id/history", get(get_user_history))
+        .route("/users/:user_id/statement", get(get_user_statement))
+        .route("/users/:user_id/notifications", get(get_user_notifications))
+        // Views of the request user (X-Request-User-Id or token subject)
+        .route("/me/balance", get(get_my_balance))
+        .route("/me/history", get(get_my_history))
+        .route("/me/transfers", get(list_my_transfers))
         // M126, M127: Transfers
         .route("/transfers", post(transfer))
+        .route("/transfers", get(list_transfers))
+        .route("/transfers/search", get(search_transfers))
+        .route("/transfers/quote", post(quote_transfer))
         .route("/transfers/:transfer_id", get(get_transfer))
+        .route("/transfers/:transfer_id/receipt", get(get_transfer_receipt))
+        .route("/transfers/:transfer_id/reverse", post(reverse_transfer))
+        // Holds (two-phase payments)
+        .route("/holds", post(create_hold))
+        .route("/holds/:hold_id/capture", post(capture_hold))
+        .route("/holds/:hold_id/release", post(release_hold))
         // M128, M129, M130: Admin
         .route("/admin/mint", post(mint))
+        .route("/admin/transfers/pending", get(list_pending_transfers))
+        .route("/admin/transfers/:transfer_id/approve", post(approve_transfer))
+        .route("/admin/transfers/:transfer_id/reject", post(reject_transfer))
+        .route("/admin/users/:user_id/anonymize", post(anonymize_user))
+        .route("/admin/users/:user_id/rename", post(rename_user))
         .route("/admin/burn", post(burn))
         .route("/admin/events", get(get_events))
+        .route("/admin/events/stream", get(stream_events))
+        .route("/admin/events/export", get(export_events))
+        .route("/admin/events/integrity", get(get_event_integrity))
+        .route("/admin/events/:event_id", get(get_event))
+        .route("/admin/subscriptions", get(list_subscriptions))
+        .route("/admin/dead-letters", get(list_dead_letters))
+        .route("/admin/dead-letters/:event_id/reprocess", post(reprocess_dead_letter))
+        .route("/admin/snapshots", get(get_snapshot_coverage))
+        .route("/admin/snapshots/maintain", post(run_snapshot_maintenance))
+        .route("/admin/accounts/:account_id", delete(close_account))
+        .route("/admin/accounts/:account_id/freeze", post(freeze_account))
+        .route("/admin/accounts/:account_id/unfreeze", post(unfreeze_account))
+        .route("/admin/accounts/:account_id/status", get(get_account_status))
+        .route("/admin/restrictions", get(list_account_restrictions))
+        .route("/admin/accounts/:account_id/restriction", get(get_account_restriction))
+        .route("/admin/accounts/:account_id/restriction", put(set_account_restriction))
+        .route("/admin/accounts/:account_id/restriction", delete(delete_account_restriction))
+        .route("/admin/projections/rebuild", post(rebuild_projections))
+        .route("/admin/projections/shadow-rebuild", post(shadow_rebuild_balances))
+        .route("/admin/projections/status", get(get_projection_status))
+        .route("/admin/supply", get(get_supply))
+        .route("/admin/supply-cap", get(get_supply_cap))
+        .route("/admin/reconciliation", get(get_reconciliation))
+        .route("/admin/jobs", get(list_jobs))
+        .route("/admin/jobs/:name/run", post(run_job))
+        .route("/admin/reports/by-category", get(get_category_report))
+        .route("/admin/stats", get(get_daily_stats))
+        .route("/admin/metrics", get(get_metrics))
+        .route("/admin/audit-logs", get(list_audit_logs))
+        .route("/admin/audit-logs/verify", get(verify_audit_logs))
+        .route("/admin/audit-logs/checkpoints", get(list_audit_checkpoints))
+        .route("/admin/audit-logs/archives", get(list_audit_archives))
+        .route("/admin/trace/:correlation_id", get(get_trace))
+        // Webhooks
+        .route("/admin/webhooks", post(create_webhook))
+        .route("/admin/webhooks", get(list_webhooks))
+        .route("/admin/webhooks/:webhook_id", delete(delete_webhook))
+        .route("/admin/webhooks/:webhook_id/deliveries", get(list_webhook_deliveries))
         // API Key Management
+        .route("/admin/limits", get(list_transfer_limits))
+        .route("/admin/limits/:operation", put(set_transfer_limit))
+        .route("/admin/limits/:operation", delete(delete_transfer_limit))
+        .route("/admin/alerts", get(list_alerts))
+
         .route("/admin/api-keys", post(create_api_key))
         .route("/admin/api-keys", get(list_api_keys))
         .route("/admin/api-keys/:key_id", patch(update_api_key))
         .route("/admin/api-keys/:key_id", delete(delete_api_key))
+        .route("/admin/api-keys/:key_id/rotate", post(rotate_api_key))
+        .route("/admin/api-keys/:key_id/limits", get(get_api_key_limits))
+        .route("/admin/api-keys/:key_id/limits", put(set_api_key_limits))
+        // Tenant Management
+        .route("/admin/tenants", post(create_tenant))
+        .route("/admin/tenants", get(list_tenants))
+        .route("/admin/tenants/:tenant_id", get(get_tenant))
+        .route("/admin/tenants/:tenant_id", patch(update_tenant))
         // Legacy endpoints for compatibility
         .route("/transfer", post(transfer))
         .route("/mint", post(mint))
@@ -276,22 +1147,30 @@ pub fn create_router() -> Router<PgPool> {
 
 /// Create a new user
 async fn create_user(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::WriteUsers>,
     Extension(context): Extension<OperationContext>,
-    Json(request): Json<CreateUserRequest>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<CreateUserRequest>,
 ) -> Result<(StatusCode, Json<CreateUserResponse>), AppError> {
-    let handler = CreateUserHandler::new(pool);
+    // Extract idempotency key if present
+    let idem_key = headers
+        .get("Idempotency-Key")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    let handler = CreateUserHandler::from_state(&state);
 
     let email = request.email.clone();
     let display_name = request.display_name.clone();
-    let command = CreateUserCommand::new(request.user_id, request.username, email.clone());
+    let command = CreateUserCommand::new(request.user_id.into(), request.username, email.clone());
     let command = if let Some(ref dn) = display_name {
         command.with_display_name(dn.clone())
     } else {
         command
     };
 
-    let result = handler.execute(command, &context).await?;
+    let result = handler.execute(command, idem_key, &context).await?;
 
     Ok((
         StatusCode::CREATED,
@@ -312,34 +1191,56 @@ async fn create_user(
 
 /// Get user by ID
 async fn get_user(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    Extension(api_key): Extension<AuthenticatedApiKey>,
+    request_user: Option<Extension<RequestUser>>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<UserResponse>, AppError> {
-    let user: Option<(Uuid, String, String, Option<String>, bool, bool, DateTime<Utc>, DateTime<Utc>)> =
-        sqlx::query_as(
-            r#"
-            SELECT id, username, email, display_name, is_system, is_active, created_at, updated_at
-            FROM users
-            WHERE id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(&pool)
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    require_user_access(&api_key, request_user.as_ref().map(|u| u.user_id), user_id)?;
+
+    let user = QueryHandler::from_state(&state)
+        .for_tenant(Some(api_key.tenant_id))
+        .get_user(&GetUserQuery { user_id })
         .await?;
 
-    let (id, username, email, display_name, is_system, is_active, created_at, updated_at) =
-        user.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))?;
-
-    Ok(Json(UserResponse {
-        id,
-        username,
-        email,
-        display_name,
-        is_system,
-        is_active,
-        created_at,
-        updated_at,
-    }))
+    let etag = etag::weak(user.updated_at.timestamp_micros());
+    Ok(etag::respond(&headers, etag, Json(user)))
+}
+
+// =========================================================================
+// GET /users
+// =========================================================================
+
+/// List users with keyset pagination
+async fn list_users(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadUsers>,
+    Query(query): Query<ListUsersQuery>,
+) -> Result<Json<UserPage>, AppError> {
+    QueryHandler::from_state(&state)
+        .for_tenant(Some(permission.0.tenant_id))
+        .list_users(&query)
+        .await
+        .map(Json)
+}
+
+// =========================================================================
+// GET /users/search
+// =========================================================================
+
+/// Resolve users by username and/or email
+async fn search_users(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadUsers>,
+    Query(query): Query<SearchUsersQuery>,
+) -> Result<Json<UserSearchResponse>, AppError> {
+    let users = QueryHandler::from_state(&state)
+        .for_tenant(Some(permission.0.tenant_id))
+        .search_users(&query)
+        .await?;
+
+    Ok(Json(UserSearchResponse { users }))
 }
 
 // =========================================================================
@@ -348,22 +1249,19 @@ async fn get_user(
 
 /// Update user
 async fn update_user(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
     Extension(context): Extension<OperationContext>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
+    _: RequirePermission<perms::WriteUsers>,
     Path(user_id): Path<Uuid>,
-    Json(request): Json<UpdateUserRequest>,
-) -> Result<Json<UserResponse>, AppError> {
-    // Check permission
-    if !api_key.has_permission("write:users") {
-        return Err(AppError::Forbidden("write:users permission required".to_string()));
-    }
-
+    ValidJson(request): ValidJson<UpdateUserRequest>,
+) -> Result<Json<UserView>, AppError> {
     // Check if user is system user
-    let is_system: Option<bool> = sqlx::query_scalar("SELECT is_system FROM users WHERE id = $1")
-        .bind(user_id)
-        .fetch_optional(&pool)
-        .await?;
+    let is_system: Option<bool> = sqlx::query_scalar!(
+        "SELECT is_system FROM users WHERE id = $1",
+        user_id
+    )
+    .fetch_optional(&state.pool)
+    .await?;
 
     let is_system = is_system.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))?;
 
@@ -378,48 +1276,159 @@ async fn update_user(
     };
 
     // Execute via handler (event sourced)
-    let handler = UpdateUserHandler::new(pool.clone());
-    let command = UpdateUserCommand::new(user_id, changes);
+    let handler = UpdateUserHandler::from_state(&state);
+    let command = UpdateUserCommand::new(user_id.into(), changes);
     handler.execute(command, &context).await?;
 
     // Return updated user
-    get_user(State(pool), Path(user_id)).await
+    QueryHandler::from_state(&state)
+        .get_user(&GetUserQuery { user_id })
+        .await
+        .map(Json)
 }
 
 // =========================================================================
 // M123: DELETE /users/:user_id
 // =========================================================================
 
-/// Deactivate user (soft delete)
+/// Deactivate user (soft delete), optionally sweeping the balance to the
+/// recovery account
 async fn delete_user(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
     Extension(context): Extension<OperationContext>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
+    _: RequirePermission<perms::WriteUsers>,
     Path(user_id): Path<Uuid>,
+    request: Option<Json<DeactivateUserRequest>>,
 ) -> Result<StatusCode, AppError> {
-    // Check permission
-    if !api_key.has_permission("write:users") {
-        return Err(AppError::Forbidden("write:users permission required".to_string()));
-    }
+    let Json(request) = request.unwrap_or_default();
+    request.check()?;
 
     // Execute via handler (event sourced)
-    let handler = DeactivateUserHandler::new(pool);
-    let command = DeactivateUserCommand::new(user_id);
+    let handler = DeactivateUserHandler::from_state(&state);
+    let mut command = DeactivateUserCommand::new(user_id.into());
+    if let Some(reason) = request.reason {
+        command = command.with_reason(reason);
+    }
+    if request.sweep_balance {
+        command = command.with_sweep();
+    }
     handler.execute(command, &context).await?;
 
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Reactivate a previously deactivated user
+async fn reactivate_user(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::WriteUsers>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<UserView>, AppError> {
+    // Execute via handler (event sourced)
+    let handler = ReactivateUserHandler::from_state(&state);
+    let command = ReactivateUserCommand::new(user_id.into());
+    handler.execute(command, &context).await?;
+
+    // Return reactivated user
+    QueryHandler::from_state(&state)
+        .get_user(&GetUserQuery { user_id })
+        .await
+        .map(Json)
+}
+
+/// Scrub personal data of a deactivated user (admin only)
+async fn anonymize_user(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminUsers>,
+    Path(user_id): Path<Uuid>,
+    request: Option<Json<AnonymizeUserRequest>>,
+) -> Result<Json<AnonymizeUserResponse>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+    request.check()?;
+
+    let handler = AnonymizeUserHandler::from_state(&state);
+    let mut command = AnonymizeUserCommand::new(user_id.into());
+    if let Some(reason) = request.reason {
+        command = command.with_reason(reason);
+    }
+    let result = handler.execute(command, &context).await?;
+
+    Ok(Json(AnonymizeUserResponse {
+        user_id: result.user_id,
+        anonymized_at: result.anonymized_at,
+    }))
+}
+
+/// Change a user's username (admin only; usernames are otherwise immutable)
+async fn rename_user(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminUsers>,
+    Path(user_id): Path<Uuid>,
+    ValidJson(request): ValidJson<RenameUserRequest>,
+) -> Result<Json<RenameUserResponse>, AppError> {
+    let handler = RenameUserHandler::from_state(&state);
+    let command = RenameUserCommand::new(user_id.into(), request.username);
+    let result = handler.execute(command, &context).await?;
+
+    Ok(Json(RenameUserResponse {
+        user_id: result.user_id,
+        old_username: result.old_username,
+        username: result.new_username,
+        renamed_at: result.renamed_at,
+    }))
+}
+
+/// Open an additional named wallet account for a user
+async fn create_account(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::WriteUsers>,
+    Path(user_id): Path<Uuid>,
+    ApiJson(request): ApiJson<CreateAccountRequest>,
+) -> Result<(StatusCode, Json<CreateAccountResult>), AppError> {
+    let handler = CreateAccountHandler::from_state(&state);
+    let command = CreateAccountCommand { user_id: user_id.into(), name: request.name };
+    let result = handler.execute(command, &context).await?;
+
+    Ok((StatusCode::CREATED, Json(result)))
+}
+
 // =========================================================================
 // M124: GET /users/:user_id/balance
 // =========================================================================
 
-/// Get user balance
+/// Get user balance (current, or historical with ?as_of=)
 async fn get_user_balance(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<BalanceResponse>, AppError> {
-    let projection = ProjectionService::new(pool);
+    Query(query): Query<BalanceAsOfQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    require_user_access(&permission.0, request_user.as_ref().map(|u| u.user_id), user_id)?;
+
+    if let Some(as_of) = query.as_of {
+        return get_user_balance_as_of(&state, user_id, as_of)
+            .await
+            .map(|balance| Json(balance).into_response());
+    }
+
+    let etag = wallet_etag(&state, user_id).await?;
+    if etag::is_fresh(&headers, &etag) {
+        return Ok(etag::not_modified(etag));
+    }
+
+    let projection = &state.projection;
+
+    // Read before the balance, so a concurrent update can only make the
+    // version look older than the balance (the client then reads again)
+    let projected_version = projection
+        .get_user_projected_version(user_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
 
     let balance = projection
         .get_user_balance(user_id)
@@ -427,69 +1436,381 @@ async fn get_user_balance(
         .map_err(|e| AppError::Internal(e.to_string()))?
         .ok_or_else(|| AppError::UserNotFound(user_id.to_string()))?;
 
-    Ok(Json(BalanceResponse { user_id, balance }))
-}
-
-// =========================================================================
-// M125: GET /users/:user_id/history
-// =========================================================================
+    let held = projection
+        .get_user_held_balance(user_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
 
-/// Get user transaction history
-async fn get_user_history(
-    State(pool): State<PgPool>,
-    Path(user_id): Path<Uuid>,
-) -> Result<Json<HistoryResponse>, AppError> {
-    // Get user's account
-    let account_id: Option<Uuid> = sqlx::query_scalar(
-        "SELECT id FROM accounts WHERE user_id = $1 AND account_type = 'user_wallet'",
+    let accounts = projection
+        .get_user_account_balances(user_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut response = (
+        [(header::ETAG, etag)],
+        Json(BalanceResponse {
+            user_id,
+            balance,
+            available_balance: balance - held,
+            as_of: None,
+            accounts,
+        }),
     )
-    .bind(user_id)
-    .fetch_optional(&pool)
-    .await?;
+        .into_response();
+    if let Some(version) = projected_version {
+        response
+            .headers_mut()
+            .insert("X-Aggregate-Version", HeaderValue::from(version));
+    }
+
+    Ok(response)
+}
+
+/// ETag over the user's wallet versions, read before the balances or
+/// history it tags: a change in between leaves the tag older than the body,
+/// which only costs the client one more full response.
+async fn wallet_etag(state: &SharedState, user_id: Uuid) -> Result<HeaderValue, AppError> {
+    let versions = state
+        .projection
+        .get_user_wallet_versions(user_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if versions.is_empty() {
+        return Err(AppError::UserNotFound(user_id.to_string()));
+    }
+
+    let tag = versions.iter().map(i64::to_string).collect::<Vec<_>>().join(".");
+    Ok(etag::weak(tag))
+}
+
+/// Replay the user's wallet events up to `as_of`
+async fn get_user_balance_as_of(
+    state: &SharedState,
+    user_id: Uuid,
+    as_of: DateTime<Utc>,
+) -> Result<BalanceResponse, AppError> {
+    let account_id: Option<Uuid> = sqlx::query_scalar!(
+        "SELECT id FROM accounts WHERE user_id = $1 AND account_type = 'user_wallet' AND name IS NULL",
+        user_id
+    )
+    .fetch_optional(&state.pool)
+    .await?;
 
     let account_id = account_id.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))?;
 
-    // Get events for this account
-    let events: Vec<(Uuid, String, serde_json::Value, DateTime<Utc>)> = sqlx::query_as(
-        r#"
-        SELECT id, event_type, event_data, created_at
-        FROM events
-        WHERE aggregate_id = $1
-        ORDER BY created_at DESC
-        LIMIT 100
-        "#,
+    let account: Option<Account> = state
+        .event_store
+        .load_aggregate_at(account_id, as_of)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Wallet not yet created at `as_of`
+    let (mut balance, mut available_balance) = account
+        .map(|a| (a.balance().value(), a.available_balance()))
+        .unwrap_or_default();
+
+    // Match the NUMERIC(_, 8) scale of the projected balance
+    balance.rescale(8);
+    available_balance.rescale(8);
+
+    Ok(BalanceResponse {
+        user_id,
+        balance,
+        available_balance,
+        as_of: Some(as_of),
+        accounts: Vec::new(),
+    })
+}
+
+// =========================================================================
+// POST /users/:user_id/balance/assert
+// =========================================================================
+
+/// Check that the primary wallet holds the expected balance (and is still at
+/// the given version). Balance and version come from a single read, so a
+/// 200 means both held at the returned version; a 409 carries the actual
+/// values so the caller can decide without another read.
+async fn assert_user_balance(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    Path(user_id): Path<Uuid>,
+    ApiJson(request): ApiJson<BalanceAssertionRequest>,
+) -> Result<Json<BalanceAssertionResponse>, AppError> {
+    require_user_access(&permission.0, request_user.as_ref().map(|u| u.user_id), user_id)?;
+
+    let wallet = state
+        .projection
+        .get_user_wallet_balance(user_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::UserNotFound(user_id.to_string()))?;
+
+    if let Some(expected_version) = request.as_of_version {
+        if wallet.version != expected_version {
+            return Err(AppError::BalanceAssertionFailed(format!(
+                "expected version {}, wallet is at version {} (balance {})",
+                expected_version, wallet.version, wallet.balance
+            )));
+        }
+    }
+
+    if wallet.balance != request.expected_balance {
+        return Err(AppError::BalanceAssertionFailed(format!(
+            "expected balance {}, actual balance {} (version {})",
+            request.expected_balance, wallet.balance, wallet.version
+        )));
+    }
+
+    Ok(Json(BalanceAssertionResponse {
+        user_id,
+        account_id: wallet.account_id,
+        balance: wallet.balance,
+        available_balance: wallet.available_balance,
+        version: wallet.version,
+    }))
+}
+
+// =========================================================================
+// M125: GET /users/:user_id/history
+// =========================================================================
+
+/// Get user transaction history
+async fn get_user_history(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<HistoryQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    require_user_access(&permission.0, request_user.as_ref().map(|u| u.user_id), user_id)?;
+
+    let etag = wallet_etag(&state, user_id).await?;
+    if etag::is_fresh(&headers, &etag) {
+        return Ok(etag::not_modified(etag));
+    }
+
+    let history = QueryHandler::from_state(&state)
+        .get_history(&GetHistoryQuery {
+            user_id,
+            before_id: query.before_id,
+            limit: query.limit,
+        })
+        .await?;
+
+    Ok(([(header::ETAG, etag)], Json(history)).into_response())
+}
+
+// =========================================================================
+// GET /me/*
+// =========================================================================
+
+/// User the request acts for; /me endpoints never take a user ID from the URL
+fn request_user_id(request_user: Option<Extension<RequestUser>>) -> Result<Uuid, AppError> {
+    request_user
+        .map(|Extension(user)| user.user_id)
+        .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))
+}
+
+/// Balance of the request user
+async fn get_my_balance(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    query: Query<BalanceAsOfQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let user_id = request_user_id(request_user.clone())?;
+    get_user_balance(State(state), permission, request_user, Path(user_id), query, headers).await
+}
+
+/// Wallet history of the request user
+async fn get_my_history(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    query: Query<HistoryQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let user_id = request_user_id(request_user.clone())?;
+    get_user_history(State(state), permission, request_user, Path(user_id), query, headers).await
+}
+
+/// Transfers the request user sent or received (filters as GET /transfers)
+async fn list_my_transfers(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    Query(query): Query<ListTransfersQuery>,
+) -> Result<Json<TransferListResponse>, AppError> {
+    let user_id = request_user_id(request_user)?;
+    transfer_page(&state, permission.0.tenant_id, &query, None, Some(user_id)).await.map(Json)
+}
+
+// =========================================================================
+// GET /users/:user_id/notifications
+// =========================================================================
+
+/// Activity feed of a user (newest first). Filled asynchronously by the
+/// notification projection, so the latest operations can take a few seconds
+/// to appear.
+async fn get_user_notifications(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<NotificationsQuery>,
+) -> Result<Json<NotificationsResponse>, AppError> {
+    require_user_access(&permission.0, request_user.as_ref().map(|u| u.user_id), user_id)?;
+
+    let exists: bool = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM users WHERE id = $1) AS "exists!""#,
+        user_id
+    )
+    .fetch_one(&state.pool)
+    .await?;
+    if !exists {
+        return Err(AppError::UserNotFound(user_id.to_string()));
+    }
+
+    let notifications =
+        projection::user_notifications(&state.pool, user_id, query.since, query.limit.clamp(1, 200))
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(NotificationsResponse {
+        user_id,
+        notifications,
+    }))
+}
+
+// =========================================================================
+// GET /users/:user_id/statement
+// =========================================================================
+
+/// Ledger statement for a user's wallet with running balances.
+/// Returns CSV when the client sends `Accept: text/csv`.
+async fn get_user_statement(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
+    Path(user_id): Path<Uuid>,
+    Query(query): Query<StatementQuery>,
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    require_user_access(&permission.0, request_user.as_ref().map(|u| u.user_id), user_id)?;
+
+    let limit = query.limit.clamp(1, 1000);
+
+    let cursor = match query.cursor.as_deref() {
+        Some(raw) => Some(
+            StatementCursor::decode(raw)
+                .ok_or_else(|| AppError::InvalidRequest("Invalid cursor".to_string()))?,
+        ),
+        None => None,
+    };
+
+    if let (Some(from), Some(to)) = (query.from_date, query.to_date) {
+        if from > to {
+            return Err(AppError::InvalidRequest(
+                "from_date must not be after to_date".to_string(),
+            ));
+        }
+    }
+
+    let account_id: Option<Uuid> = sqlx::query_scalar!(
+        "SELECT id FROM accounts WHERE user_id = $1 AND account_type = 'user_wallet' AND name IS NULL",
+        user_id
     )
-    .bind(account_id)
-    .fetch_all(&pool)
+    .fetch_optional(&state.pool)
     .await?;
 
-    let entries: Vec<HistoryEntry> = events
-        .into_iter()
-        .map(|(id, event_type, data, created_at)| {
-            let amount = data.get("amount").and_then(|v| {
-                v.as_str()
-                    .and_then(|s| s.parse::<Decimal>().ok())
-                    .or_else(|| v.as_f64().map(|f| Decimal::from_f64_retain(f).unwrap_or_default()))
-            });
-            let description = data
-                .get("description")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            HistoryEntry {
-                event_id: id,
-                event_type,
-                amount,
-                description,
-                created_at,
+    let account_id = account_id.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))?;
+
+    let projection = &state.projection;
+    let mut opening_balance = match query.from_date {
+        Some(from) => projection.ledger_balance_before(account_id, Some(from)).await,
+        None => Ok(Decimal::ZERO),
+    }
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+    let mut closing_balance = projection
+        .ledger_balance_before(account_id, query.to_date)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    // Match the NUMERIC(_, 8) scale of ledger amounts
+    opening_balance.rescale(8);
+    closing_balance.rescale(8);
+
+    // Fetch one extra row to know whether another page exists
+    let mut entries = projection
+        .account_statement(
+            account_id,
+            query.from_date,
+            query.to_date,
+            opening_balance,
+            cursor.as_ref(),
+            limit + 1,
+        )
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let next_cursor = if entries.len() as i64 > limit {
+        entries.truncate(limit as usize);
+        entries.last().map(|e| {
+            StatementCursor {
+                created_at: e.created_at,
+                entry_id: e.entry_id,
             }
+            .encode()
         })
-        .collect();
+    } else {
+        None
+    };
+
+    let wants_csv = headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/csv"));
+
+    if wants_csv {
+        let mut body = String::from(StatementLine::CSV_HEADER);
+        body.push('\n');
+        for entry in &entries {
+            body.push_str(&entry.csv_record());
+            body.push('\n');
+        }
+
+        let mut response = (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!("attachment; filename=\"statement-{}.csv\"", user_id),
+                ),
+            ],
+            body,
+        )
+            .into_response();
+        if let Some(next_cursor) = next_cursor.and_then(|c| c.parse().ok()) {
+            response.headers_mut().insert("X-Next-Cursor", next_cursor);
+        }
+        return Ok(response);
+    }
 
-    Ok(Json(HistoryResponse {
+    Ok(Json(StatementResponse {
         user_id,
+        account_id,
+        from_date: query.from_date,
+        to_date: query.to_date,
+        opening_balance,
+        closing_balance,
         entries,
-    }))
+        next_cursor,
+    })
+    .into_response())
 }
 
 // =========================================================================
@@ -498,12 +1819,13 @@ async fn get_user_history(
 
 /// Transfer ATP between users
 async fn transfer(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::WriteTransfers>,
     Extension(context): Extension<OperationContext>,
     request_user: Option<Extension<RequestUser>>,
-    headers: axum::http::HeaderMap,
-    Json(request): Json<TransferRequest>,
-) -> Result<Json<TransferResponse>, AppError> {
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<TransferRequest>,
+) -> Result<(StatusCode, Json<TransferResponse>), AppError> {
     // X-Request-User-Id is required for transfer
     let request_user = request_user
         .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))?;
@@ -517,160 +1839,1193 @@ async fn transfer(
         .and_then(|h| h.to_str().ok())
         .and_then(|s| Uuid::parse_str(s).ok());
 
-    let handler = TransferHandler::new(pool);
+    let handler = TransferHandler::from_state(&state);
 
-    let command = TransferCommand::new(request.from_user_id, request.to_user_id, request.amount);
+    ensure_in_tenant(
+        &state,
+        &context,
+        &[request.from_user_id, request.to_user_id],
+        request.from_account_id.into_iter().chain(request.to_account_id),
+    )
+    .await?;
+
+    let command = TransferCommand::new(request.from_user_id.into(), request.to_user_id.into(), request.amount.into_amount("amount")?)
+        .with_accounts(request.from_account_id.map(Into::into), request.to_account_id.map(Into::into));
     let command = if let Some(memo) = request.memo {
         command.with_memo(memo)
     } else {
         command
     };
+    let command = if let Some(category) = request.category {
+        command.with_category(category)
+    } else {
+        command
+    };
+
+    let result = handler.execute(command, idem_key, &context).await?;
+
+    // Transfers held for approval have not moved any money yet
+    let status = if result.status == "pending_approval" {
+        StatusCode::ACCEPTED
+    } else {
+        StatusCode::OK
+    };
+
+    Ok((status, Json(transfer_response(result))))
+}
+
+/// Dry-run a transfer: same checks as POST /transfers, nothing persisted.
+/// Fails with the error the transfer would fail with.
+async fn quote_transfer(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::WriteTransfers>,
+    Extension(context): Extension<OperationContext>,
+    request_user: Option<Extension<RequestUser>>,
+    ValidJson(request): ValidJson<TransferRequest>,
+) -> Result<Json<TransferQuote>, AppError> {
+    let request_user = request_user
+        .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))?;
+    let context = context.with_request_user(request_user.user_id);
+
+    ensure_in_tenant(
+        &state,
+        &context,
+        &[request.from_user_id, request.to_user_id],
+        request.from_account_id.into_iter().chain(request.to_account_id),
+    )
+    .await?;
+
+    let command = TransferCommand::new(request.from_user_id.into(), request.to_user_id.into(), request.amount.into_amount("amount")?)
+        .with_accounts(request.from_account_id.map(Into::into), request.to_account_id.map(Into::into));
+    let command = if let Some(memo) = request.memo {
+        command.with_memo(memo)
+    } else {
+        command
+    };
+    let command = if let Some(category) = request.category {
+        command.with_category(category)
+    } else {
+        command
+    };
+
+    let quote = TransferHandler::from_state(&state)
+        .quote(&command, &context)
+        .await?;
+
+    Ok(Json(quote))
+}
+
+/// Fail as not found when a user or account named in the request body
+/// belongs to another tenant than the caller's
+async fn ensure_in_tenant(
+    state: &SharedState,
+    context: &OperationContext,
+    user_ids: &[Uuid],
+    account_ids: impl IntoIterator<Item = Uuid>,
+) -> Result<(), AppError> {
+    state
+        .tenants
+        .ensure_all(context.tenant_id, TenantResource::User, user_ids.iter().copied())
+        .await?;
+    state
+        .tenants
+        .ensure_all(context.tenant_id, TenantResource::Account, account_ids)
+        .await?;
+    Ok(())
+}
+
+fn transfer_response(result: TransferResult) -> TransferResponse {
+    TransferResponse {
+        transfer_id: result.transfer_id,
+        status: result.status,
+        from_user_id: result.from_user_id,
+        to_user_id: result.to_user_id,
+        amount: result.amount,
+        from_account_version: result.from_account_version,
+        to_account_version: result.to_account_version,
+        event_ids: result.event_ids,
+        created_at: chrono::Utc::now(),
+    }
+}
+
+// =========================================================================
+// Transfer approval (maker-checker)
+// =========================================================================
+
+/// Transfers waiting for approval, oldest first (admin only)
+async fn list_pending_transfers(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::AdminTransfers>,
+    Query(query): Query<ListPendingTransfersQuery>,
+) -> Result<Json<PendingTransferPage>, AppError> {
+    QueryHandler::from_state(&state)
+        .for_tenant(Some(permission.0.tenant_id))
+        .list_pending_transfers(&query)
+        .await
+        .map(Json)
+}
+
+/// Approve and execute a pending transfer; must use a different API key than
+/// the initiator (admin only)
+async fn approve_transfer(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminTransfers>,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<Json<TransferResponse>, AppError> {
+    let result = TransferApprovalHandler::from_state(&state)
+        .approve(transfer_id.into(), &context)
+        .await?;
+
+    Ok(Json(transfer_response(result)))
+}
+
+/// Reject a pending transfer; must use a different API key than the
+/// initiator (admin only)
+async fn reject_transfer(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminTransfers>,
+    Path(transfer_id): Path<Uuid>,
+    request: Option<Json<RejectTransferRequest>>,
+) -> Result<Json<TransferResponse>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+    request.check()?;
+
+    let result = TransferApprovalHandler::from_state(&state)
+        .reject(transfer_id.into(), request.reason, &context)
+        .await?;
+
+    Ok(Json(transfer_response(result)))
+}
+
+// =========================================================================
+// M127: GET /transfers/:transfer_id
+// =========================================================================
+
+/// Get transfer details
+async fn get_transfer(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::ReadAccounts>,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<Json<TransferDetail>, AppError> {
+    QueryHandler::from_state(&state)
+        .get_transfer(&GetTransferQuery { transfer_id })
+        .await
+        .map(Json)
+}
+
+// =========================================================================
+// GET /transfers/:transfer_id/receipt
+// =========================================================================
+
+/// Signed receipt for a transfer, mint or burn (for dispute handling).
+/// The body is the receipt JSON; X-Receipt-Signature is its detached
+/// HMAC-SHA256 signature.
+async fn get_transfer_receipt(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::ReadAccounts>,
+    Path(transfer_id): Path<Uuid>,
+) -> Result<Response, AppError> {
+    let secret = state
+        .config
+        .receipt_signing_secret
+        .as_deref()
+        .ok_or_else(|| AppError::Internal("Receipt signing is not configured".to_string()))?;
+
+    let receipt = receipts::build_receipt(&state.pool, &state.event_store, transfer_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Transfer {} not found", transfer_id)))?;
+
+    let body = serde_json::to_vec(&receipt).map_err(|e| AppError::Internal(e.to_string()))?;
+    let signature = receipts::sign_receipt(secret, &body);
+
+    let mut response = ([(header::CONTENT_TYPE, "application/json")], body).into_response();
+    if let Ok(value) = format!("sha256={}", signature).parse() {
+        response.headers_mut().insert("X-Receipt-Signature", value);
+    }
+    Ok(response)
+}
+
+// =========================================================================
+// POST /holds, /holds/:hold_id/capture, /holds/:hold_id/release
+// =========================================================================
+
+/// Place a hold on the sender's balance
+async fn create_hold(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::WriteTransfers>,
+    Extension(context): Extension<OperationContext>,
+    request_user: Option<Extension<RequestUser>>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<HoldRequest>,
+) -> Result<(StatusCode, Json<HoldResponse>), AppError> {
+    let request_user = request_user
+        .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))?;
+    let context = context.with_request_user(request_user.user_id);
+
+    let idem_key = headers
+        .get("Idempotency-Key")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    ensure_in_tenant(&state, &context, &[request.from_user_id, request.to_user_id], []).await?;
+
+    let mut command = HoldCommand::new(request.from_user_id.into(), request.to_user_id.into(), request.amount.into_amount("amount")?);
+    if let Some(memo) = request.memo {
+        command = command.with_memo(memo);
+    }
+
+    let result = HoldHandler::from_state(&state)
+        .hold(command, idem_key, &context)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(hold_response(result))))
+}
+
+/// Capture a hold (payee only)
+async fn capture_hold(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::WriteTransfers>,
+    Extension(context): Extension<OperationContext>,
+    request_user: Option<Extension<RequestUser>>,
+    headers: HeaderMap,
+    Path(hold_id): Path<Uuid>,
+) -> Result<Json<HoldResponse>, AppError> {
+    let request_user = request_user
+        .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))?;
+    let context = context.with_request_user(request_user.user_id);
+
+    let idem_key = headers
+        .get("Idempotency-Key")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    let result = HoldHandler::from_state(&state)
+        .capture(hold_id, idem_key, &context)
+        .await?;
+
+    Ok(Json(hold_response(result)))
+}
+
+/// Release a hold (payee only)
+async fn release_hold(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::WriteTransfers>,
+    Extension(context): Extension<OperationContext>,
+    request_user: Option<Extension<RequestUser>>,
+    headers: HeaderMap,
+    Path(hold_id): Path<Uuid>,
+) -> Result<Json<HoldResponse>, AppError> {
+    let request_user = request_user
+        .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))?;
+    let context = context.with_request_user(request_user.user_id);
+
+    let idem_key = headers
+        .get("Idempotency-Key")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    let result = HoldHandler::from_state(&state)
+        .release(hold_id, idem_key, &context)
+        .await?;
+
+    Ok(Json(hold_response(result)))
+}
+
+// =========================================================================
+// POST /transfers/:transfer_id/reverse
+// =========================================================================
+
+/// Reverse a completed transfer (admin only)
+async fn reverse_transfer(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminTransfers>,
+    Path(transfer_id): Path<Uuid>,
+    headers: HeaderMap,
+    request: Option<Json<ReverseTransferRequest>>,
+) -> Result<(StatusCode, Json<ReverseTransferResponse>), AppError> {
+    let idempotency_key = headers.get("Idempotency-Key");
+    let idem_key = idempotency_key
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    let Json(request) = request.unwrap_or_default();
+    request.check()?;
+
+    let handler = ReverseTransferHandler::from_state(&state);
+
+    let mut command = ReverseTransferCommand::new(transfer_id.into());
+    if let Some(reason) = request.reason {
+        command = command.with_reason(reason);
+    }
+
+    let result = handler.execute(command, idem_key, &context).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ReverseTransferResponse {
+            reversal_id: result.reversal_id,
+            original_transfer_id: result.original_transfer_id,
+            status: "completed".to_string(),
+            from_user_id: result.from_user_id,
+            to_user_id: result.to_user_id,
+            amount: result.amount,
+            created_at: Utc::now(),
+        }),
+    ))
+}
+
+fn hold_response(result: HoldResult) -> HoldResponse {
+    HoldResponse {
+        hold_id: result.hold_id,
+        status: result.status,
+        from_user_id: result.from_user_id,
+        to_user_id: result.to_user_id,
+        amount: result.amount,
+        transfer_id: result.transfer_id,
+    }
+}
+
+// =========================================================================
+// GET /transfers
+// =========================================================================
+
+/// List transfers (newest first) with cursor-based pagination
+async fn list_transfers(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    Query(query): Query<ListTransfersQuery>,
+) -> Result<Json<TransferListResponse>, AppError> {
+    transfer_page(&state, permission.0.tenant_id, &query, None, None).await.map(Json)
+}
+
+/// Search transfer memos/descriptions (same filters and pagination as GET /transfers)
+async fn search_transfers(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    Query(search): Query<SearchTransfersQuery>,
+    Query(query): Query<ListTransfersQuery>,
+) -> Result<Json<TransferListResponse>, AppError> {
+    let q = search.q.trim();
+    if q.is_empty() {
+        return Err(AppError::InvalidRequest("q must not be empty".to_string()));
+    }
+    if q.chars().count() > 200 {
+        return Err(AppError::InvalidRequest("q must be at most 200 characters".to_string()));
+    }
+
+    transfer_page(&state, permission.0.tenant_id, &query, Some(q), None).await.map(Json)
+}
+
+/// One page of a tenant's transfers, optionally restricted to a full-text
+/// match and to transfers the given user sent or received
+async fn transfer_page(
+    state: &SharedState,
+    tenant_id: Uuid,
+    query: &ListTransfersQuery,
+    search: Option<&str>,
+    participant_user_id: Option<Uuid>,
+) -> Result<TransferListResponse, AppError> {
+    let limit = query.limit.clamp(1, 200);
+
+    let cursor = match query.cursor.as_deref() {
+        Some(raw) => Some(
+            TransferCursor::decode(raw)
+                .ok_or_else(|| AppError::InvalidRequest("Invalid cursor".to_string()))?,
+        ),
+        None => None,
+    };
+
+    if let (Some(min), Some(max)) = (query.min_amount, query.max_amount) {
+        if min > max {
+            return Err(AppError::InvalidRequest(
+                "min_amount must not exceed max_amount".to_string(),
+            ));
+        }
+    }
+
+    let filter = TransferFilter {
+        from_user_id: query.from_user_id,
+        to_user_id: query.to_user_id,
+        participant_user_id,
+        from_date: query.from_date,
+        to_date: query.to_date,
+        min_amount: query.min_amount,
+        max_amount: query.max_amount,
+        status: query.status,
+        tenant_id: Some(tenant_id),
+    };
+
+    // Fetch one extra row to know whether another page exists
+    let mut transfers = match search {
+        Some(q) => state.projection.search_transfers(q, &filter, cursor.as_ref(), limit + 1).await,
+        None => state.projection.list_transfers(&filter, cursor.as_ref(), limit + 1).await,
+    }
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let next_cursor = if transfers.len() as i64 > limit {
+        transfers.truncate(limit as usize);
+        transfers.last().map(|t| {
+            TransferCursor {
+                created_at: t.created_at,
+                transfer_id: t.transfer_id,
+            }
+            .encode()
+        })
+    } else {
+        None
+    };
+
+    Ok(TransferListResponse {
+        transfers: transfers
+            .into_iter()
+            .map(|t| TransferListItem {
+                id: t.transfer_id,
+                from_user_id: t.from_user_id,
+                to_user_id: t.to_user_id,
+                amount: t.amount,
+                description: t.description,
+                created_at: t.created_at,
+                status: t.status,
+                failure_reason: t.failure_reason,
+                initiated_by: t.initiated_by,
+            })
+            .collect(),
+        next_cursor,
+    })
+}
+
+// =========================================================================
+// M128: POST /admin/mint
+// =========================================================================
+
+/// Mint new ATP (admin only)
+async fn mint(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminMint>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<MintRequest>,
+) -> Result<(StatusCode, Json<MintResponse>), AppError> {
+    let idempotency_key = headers.get("Idempotency-Key");
+    let idem_key = idempotency_key
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    ensure_in_tenant(&state, &context, &[request.recipient_user_id], []).await?;
+
+    let handler = MintHandler::from_state(&state);
+
+    let command = MintCommand::new(request.recipient_user_id.into(), request.amount.into_amount("amount")?, request.reason);
+
+    let result = handler.execute(command, idem_key, &context).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(MintResponse {
+            mint_id: result.mint_id,
+            status: "completed".to_string(),
+            to_user_id: result.recipient_user_id,
+            amount: result.amount,
+            to_account_version: result.recipient_account_version,
+            event_ids: result.event_ids,
+            created_at: chrono::Utc::now(),
+        }),
+    ))
+}
+
+// =========================================================================
+// M129: POST /admin/burn
+// =========================================================================
+
+/// Burn ATP (admin only) - removes ATP from circulation
+async fn burn(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminBurn>,
+    request_user: Option<Extension<RequestUser>>,
+    headers: HeaderMap,
+    ValidJson(request): ValidJson<BurnRequest>,
+) -> Result<(StatusCode, Json<BurnResponse>), AppError> {
+    // The wallet owner as request user consents to the burn
+    let context = match request_user {
+        Some(Extension(request_user)) => context.with_request_user(request_user.user_id),
+        None => context,
+    };
+
+    let idempotency_key = headers.get("Idempotency-Key");
+    let idem_key = idempotency_key
+        .and_then(|h| h.to_str().ok())
+        .and_then(|s| Uuid::parse_str(s).ok());
+
+    ensure_in_tenant(&state, &context, &[request.from_user_id], []).await?;
+
+    let handler = crate::handlers::BurnHandler::from_state(&state);
+
+    let mut command = crate::handlers::BurnCommand::new(
+        request.from_user_id.into(),
+        request.amount.into_amount("amount")?,
+        request.reason,
+    );
+    if request.force {
+        let force_reason = request
+            .force_reason
+            .ok_or_else(|| AppError::InvalidRequest("force_reason is required with force".to_string()))?;
+        command = command.forced(force_reason);
+    }
 
     let result = handler.execute(command, idem_key, &context).await?;
 
-    Ok(Json(TransferResponse {
-        transfer_id: result.transfer_id,
-        status: result.status,
-        from_user_id: result.from_user_id,
-        to_user_id: result.to_user_id,
-        amount: result.amount,
-        created_at: chrono::Utc::now(),
-    }))
+    Ok((
+        StatusCode::CREATED,
+        Json(BurnResponse {
+            burn_id: result.burn_id,
+            status: "completed".to_string(),
+            from_user_id: result.from_user_id,
+            amount: result.amount,
+            from_account_version: result.from_account_version,
+            event_ids: result.event_ids,
+            created_at: chrono::Utc::now(),
+        }),
+    ))
+}
+
+// =========================================================================
+// POST /admin/accounts/:account_id/freeze, /unfreeze
+// =========================================================================
+
+/// Freeze an account (admin only) - blocks debits and credits
+async fn freeze_account(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+    ValidJson(request): ValidJson<FreezeAccountRequest>,
+) -> Result<Json<AccountStatusResponse>, AppError> {
+    let handler = FreezeAccountHandler::from_state(&state);
+    let command = FreezeAccountCommand::freeze(account_id.into(), request.reason);
+    let result = handler.execute(command, &context).await?;
+
+    Ok(Json(account_status_response(result)))
+}
+
+/// Unfreeze a previously frozen account (admin only)
+async fn unfreeze_account(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<AccountStatusResponse>, AppError> {
+    let handler = FreezeAccountHandler::from_state(&state);
+    let command = FreezeAccountCommand::unfreeze(account_id.into());
+    let result = handler.execute(command, &context).await?;
+
+    Ok(Json(account_status_response(result)))
+}
+
+// =========================================================================
+// DELETE /admin/accounts/:account_id
+// =========================================================================
+
+/// Close an account (admin only) - sweeps any remaining balance to
+/// sweep_to_account_id, then blocks further debits and credits
+async fn close_account(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+    request: Option<Json<CloseAccountRequest>>,
+) -> Result<Json<CloseAccountResponse>, AppError> {
+    let Json(request) = request.unwrap_or_default();
+    request.check()?;
+    ensure_in_tenant(&state, &context, &[], request.sweep_to_account_id).await?;
+
+    let handler = CloseAccountHandler::from_state(&state);
+    let mut command = CloseAccountCommand::new(account_id.into());
+    if let Some(sweep_to) = request.sweep_to_account_id {
+        command = command.with_sweep_to(sweep_to.into());
+    }
+    if let Some(reason) = request.reason {
+        command = command.with_reason(reason);
+    }
+    let result = handler.execute(command, &context).await?;
+
+    Ok(Json(CloseAccountResponse {
+        account_id: result.account_id,
+        status: "closed".to_string(),
+        swept_amount: result.swept_amount,
+        swept_to_account_id: result.swept_to_account_id,
+        sweep_id: result.sweep_id,
+        closed_at: result.closed_at,
+    }))
+}
+
+/// Freeze state of an account with its freeze/unfreeze history (admin only)
+async fn get_account_status(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<AccountFreezeStatus>, AppError> {
+    QueryHandler::from_state(&state)
+        .get_account_status(&GetAccountStatusQuery { account_id })
+        .await
+        .map(Json)
+}
+
+/// Rebuild account_balances and ledger_entries from the event stream (admin only)
+async fn rebuild_projections(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminProjections>,
+    Query(query): Query<RebuildProjectionsQuery>,
+) -> Result<Json<RebuildReport>, AppError> {
+    tracing::warn!(
+        correlation_id = ?context.correlation_id,
+        account_id = ?query.account_id,
+        "Projection rebuild requested"
+    );
+
+    let projection = &state.projection;
+    let report = match query.account_id {
+        Some(account_id) => projection.rebuild_account(account_id).await,
+        None => projection.rebuild_all().await,
+    }
+    .map_err(|e| match e {
+        ProjectionError::AccountNotFound(id) => {
+            AppError::AccountNotFound(id.to_string())
+        }
+        e => AppError::Internal(e.to_string()),
+    })?;
+
+    Ok(Json(report))
+}
+
+/// Rebuild account_balances in a shadow table and swap it in without
+/// blocking reads (admin only)
+async fn shadow_rebuild_balances(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminProjections>,
+    Query(query): Query<ShadowRebuildQuery>,
+) -> Result<Json<ShadowRebuildReport>, AppError> {
+    tracing::warn!(
+        correlation_id = ?context.correlation_id,
+        dry_run = query.dry_run,
+        "Shadow balance rebuild requested"
+    );
+
+    let report = state
+        .projection
+        .rebuild_balances_shadow(query.dry_run)
+        .await
+        .map_err(|e| match e {
+            ProjectionError::RebuildInProgress => AppError::InvalidRequest(e.to_string()),
+            e => AppError::Internal(e.to_string()),
+        })?;
+
+    Ok(Json(report))
+}
+
+/// Projection lag behind the event store (admin only)
+async fn get_projection_status(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminProjections>,
+    Query(query): Query<ProjectionStatusQuery>,
+) -> Result<Json<ProjectionStatus>, AppError> {
+    let status = state
+        .projection
+        .projection_status(query.limit.clamp(1, 1000))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(status))
+}
+
+/// Total supply and system account balances (admin only)
+async fn get_supply(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminSupply>,
+) -> Result<Json<SupplyReport>, AppError> {
+    let report = state
+        .projection
+        .supply_report()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if !report.is_balanced {
+        tracing::error!(
+            total_debits = %report.total_debits,
+            total_credits = %report.total_credits,
+            "Ledger does not balance"
+        );
+    }
+
+    Ok(Json(report))
+}
+
+/// Minted total against MINT_SUPPLY_CAP (admin only)
+async fn get_supply_cap(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminSupply>,
+) -> Result<Json<SupplyCapStatus>, AppError> {
+    MintHandler::from_state(&state).supply_cap_status().await.map(Json)
+}
+
+/// Ledger reconciliation reports, newest first (admin only)
+async fn get_reconciliation(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminReconciliation>,
+    Query(query): Query<ReconciliationQuery>,
+) -> Result<Json<Vec<ReconciliationReport>>, AppError> {
+    if query.run {
+        jobs::reconcile_ledger(&state.pool)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+    }
+
+    let reports = jobs::recent_reconciliation_reports(&state.pool, query.limit.clamp(1, 100))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(reports))
+}
+
+/// Maintenance jobs with their latest and recent runs (admin only)
+async fn list_jobs(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminJobs>,
+    Query(query): Query<JobsQuery>,
+) -> Result<Json<JobsResponse>, AppError> {
+    let job = query.job.as_deref().map(parse_job).transpose()?;
+    let scheduler = JobScheduler::with_config(state.pool.clone(), JobSchedulerConfig::from_config(&state.config));
+
+    let jobs = scheduler
+        .job_statuses()
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+    let runs = jobs::recent_job_runs(&state.pool, job, query.limit.clamp(1, 500))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(JobsResponse { jobs, runs }))
+}
+
+/// Run a maintenance job now instead of waiting for the scheduler (admin only)
+async fn run_job(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::AdminJobs>,
+    Path(name): Path<String>,
+) -> Result<Json<JobRun>, AppError> {
+    let job = parse_job(&name)?;
+    let scheduler = JobScheduler::with_config(state.pool.clone(), JobSchedulerConfig::from_config(&state.config));
+
+    let run = scheduler
+        .run_manually(job, Some(permission.0.id))
+        .await
+        .map_err(|e| match e {
+            JobError::Disabled(_) => AppError::Domain(DomainError::BusinessRuleViolation(e.to_string())),
+            e => AppError::Internal(e.to_string()),
+        })?;
+
+    Ok(Json(run))
+}
+
+fn parse_job(name: &str) -> Result<Job, AppError> {
+    Job::from_name(name).ok_or_else(|| AppError::InvalidRequest(format!("Unknown job: {}", name)))
+}
+
+/// Transfer volume per category over a date range (admin only)
+async fn get_category_report(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminReports>,
+    Query(query): Query<CategoryReportQuery>,
+) -> Result<Json<CategoryReport>, AppError> {
+    let report = QueryHandler::from_state(&state)
+        .category_report(&query)
+        .await?;
+
+    Ok(Json(report))
+}
+
+/// In-process counters of this instance (admin only)
+async fn get_metrics(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminReports>,
+) -> Json<MetricsResponse> {
+    Json(MetricsResponse {
+        requests: state.metrics.snapshot(),
+        balance_cache: state.projection.balance_cache().map(|cache| cache.stats()),
+    })
+}
+
+/// Materialized daily statistics for dashboards (admin only)
+async fn get_daily_stats(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminReports>,
+    Query(query): Query<DailyStatsQuery>,
+) -> Result<Json<DailyStatsResponse>, AppError> {
+    let (from, to) = query.range(Utc::now().date_naive())?;
+
+    let days = jobs::daily_stats(&state.pool, from, to)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(DailyStatsResponse { from, to, days }))
+}
+
+/// List audit log entries with filters, newest first (admin only)
+async fn list_audit_logs(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAudit>,
+    Query(query): Query<AuditLogsQuery>,
+) -> Result<Json<AuditLogListResponse>, AppError> {
+    let limit = query.limit.clamp(1, 200);
+
+    let filter = AuditLogFilter {
+        action: query.action,
+        resource_type: query.resource_type,
+        resource_id: query.resource_id,
+        correlation_id: query.correlation_id,
+        from_date: query.from_date,
+        to_date: query.to_date,
+    };
+
+    // Fetch one extra row to know whether another page exists
+    let mut entries = state
+        .audit
+        .query(&filter, query.cursor, limit + 1)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let next_cursor = if entries.len() as i64 > limit {
+        entries.truncate(limit as usize);
+        entries.last().map(|e| e.sequence_number)
+    } else {
+        None
+    };
+
+    Ok(Json(AuditLogListResponse {
+        entries,
+        next_cursor,
+    }))
+}
+
+/// Verify the audit log hash chain (admin only)
+async fn verify_audit_logs(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAudit>,
+    Query(query): Query<VerifyAuditLogsQuery>,
+) -> Result<Json<ChainVerificationResult>, AppError> {
+    let result = state
+        .audit
+        .verify_hash_chain(query.limit)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    if !result.is_valid {
+        tracing::error!(
+            first_invalid_entry = ?result.first_invalid_entry,
+            "Audit log hash chain verification failed"
+        );
+    }
+
+    Ok(Json(result))
+}
+
+/// Incremental audit chain verification checkpoints, newest first (admin only)
+async fn list_audit_checkpoints(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAudit>,
+    Query(query): Query<AuditCheckpointsQuery>,
+) -> Result<Json<Vec<AuditCheckpoint>>, AppError> {
+    if query.run {
+        jobs::verify_audit_chain(&state.pool, &reqwest::Client::new(), state.config.audit_anchor.as_ref())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+    }
+
+    let checkpoints = jobs::recent_audit_checkpoints(&state.pool, query.limit.clamp(1, 100))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(checkpoints))
+}
+
+/// Audit log archives, newest first (admin only)
+async fn list_audit_archives(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAudit>,
+    Query(query): Query<ListAuditArchivesQuery>,
+) -> Result<Json<Vec<AuditArchive>>, AppError> {
+    let archives = jobs::recent_audit_archives(&state.pool, query.limit.clamp(1, 100))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(archives))
+}
+
+/// Maximum events (and, separately, audit entries) in a trace
+const TRACE_LIMIT: i64 = 500;
+
+/// Events, audit log entries and idempotency keys recorded under a
+/// correlation ID, in chronological order (admin only)
+async fn get_trace(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAudit>,
+    Path(correlation_id): Path<Uuid>,
+) -> Result<Json<Trace>, AppError> {
+    let trace = load_trace(
+        &state.event_store,
+        &state.audit,
+        &state.idempotency,
+        correlation_id,
+        TRACE_LIMIT,
+    )
+    .await
+    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(trace))
 }
 
 // =========================================================================
-// M127: GET /transfers/:transfer_id
+// Webhook Handlers
 // =========================================================================
 
-/// Get transfer details
-async fn get_transfer(
-    State(pool): State<PgPool>,
-    Path(transfer_id): Path<Uuid>,
-) -> Result<Json<TransferDetailResponse>, AppError> {
-    // Find the debit event with this transfer_id
-    let transfer: Option<(Uuid, Uuid, Decimal, String, DateTime<Utc>)> = sqlx::query_as(
-        r#"
-        SELECT 
-            le.journal_id,
-            le.account_id,
-            le.amount,
-            COALESCE(le.description, '') as description,
-            le.created_at
-        FROM ledger_entries le
-        WHERE le.journal_id = $1 AND le.entry_type = 'debit'
-        LIMIT 1
-        "#,
-    )
-    .bind(transfer_id)
-    .fetch_optional(&pool)
-    .await?;
+/// Register a webhook endpoint (admin only)
+async fn create_webhook(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminWebhooks>,
+    ApiJson(request): ApiJson<CreateWebhookRequest>,
+) -> Result<(StatusCode, Json<CreateWebhookResponse>), AppError> {
+    let secret = request.secret.unwrap_or_else(generate_secret);
+    let endpoint = state
+        .webhooks
+        .register_endpoint(&request.url, &secret, &request.event_types)
+        .await
+        .map_err(map_webhook_error)?;
+
+    let audit_entry = AuditLogBuilder::new(AuditAction::WebhookCreated)
+        .resource_type("WebhookEndpoint")
+        .resource_id(endpoint.id)
+        .after_state(&serde_json::json!({
+            "url": endpoint.url,
+            "event_types": endpoint.event_types,
+        }));
+    state.audit.record(audit_entry, &context).await;
+
+    Ok((StatusCode::CREATED, Json(CreateWebhookResponse { endpoint, secret })))
+}
 
-    let (journal_id, from_account_id, amount, description, created_at) = transfer
-        .ok_or_else(|| AppError::InvalidRequest(format!("Transfer {} not found", transfer_id)))?;
+/// List webhook endpoints (admin only)
+async fn list_webhooks(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminWebhooks>,
+) -> Result<Json<Vec<WebhookEndpoint>>, AppError> {
+    let endpoints = state
+        .webhooks
+        .list_endpoints()
+        .await
+        .map_err(map_webhook_error)?;
 
-    // Get the credit side
-    let to_account_id: Option<Uuid> = sqlx::query_scalar(
-        "SELECT account_id FROM ledger_entries WHERE journal_id = $1 AND entry_type = 'credit' LIMIT 1",
-    )
-    .bind(journal_id)
-    .fetch_optional(&pool)
-    .await?;
+    Ok(Json(endpoints))
+}
+
+/// Deactivate a webhook endpoint (admin only)
+async fn delete_webhook(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminWebhooks>,
+    Path(webhook_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    state
+        .webhooks
+        .deactivate_endpoint(webhook_id)
+        .await
+        .map_err(map_webhook_error)?;
 
-    let to_account_id = to_account_id
-        .ok_or_else(|| AppError::Internal("Invalid transfer: missing credit entry".to_string()))?;
+    let audit_entry = AuditLogBuilder::new(AuditAction::WebhookDeactivated)
+        .resource_type("WebhookEndpoint")
+        .resource_id(webhook_id)
+        .after_state(&serde_json::json!({ "is_active": false }))
+        .changed_fields(vec!["is_active".to_string()]);
+    state.audit.record(audit_entry, &context).await;
 
-    Ok(Json(TransferDetailResponse {
-        id: journal_id,
-        from_account_id,
-        to_account_id,
-        amount,
-        description,
-        created_at,
-    }))
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Recent deliveries for a webhook endpoint (admin only)
+async fn list_webhook_deliveries(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminWebhooks>,
+    Path(webhook_id): Path<Uuid>,
+    Query(query): Query<WebhookDeliveriesQuery>,
+) -> Result<Json<Vec<WebhookDelivery>>, AppError> {
+    let deliveries = state
+        .webhooks
+        .list_deliveries(webhook_id, query.limit.clamp(1, 200))
+        .await
+        .map_err(map_webhook_error)?;
+
+    Ok(Json(deliveries))
 }
 
 // =========================================================================
-// M128: POST /admin/mint
+// Transfer Limits
 // =========================================================================
 
-/// Mint new ATP (admin only)
-async fn mint(
-    State(pool): State<PgPool>,
+/// List configured transfer limits (admin only)
+async fn list_transfer_limits(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminLimits>,
+) -> Result<Json<Vec<TransferLimit>>, AppError> {
+    Ok(Json(state.limits.list().await?))
+}
+
+/// Create or replace the limits for an operation (admin only)
+async fn set_transfer_limit(
+    State(state): State<SharedState>,
     Extension(context): Extension<OperationContext>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
-    headers: axum::http::HeaderMap,
-    Json(request): Json<MintRequest>,
-) -> Result<(StatusCode, Json<MintResponse>), AppError> {
-    // Check admin permission
-    if !api_key.has_permission("admin:mint") {
-        return Err(AppError::Forbidden("admin:mint permission required".to_string()));
+    _: RequirePermission<perms::AdminLimits>,
+    Path(operation): Path<LimitOperation>,
+    ApiJson(request): ApiJson<SetTransferLimitRequest>,
+) -> Result<Json<TransferLimit>, AppError> {
+    let before = state.limits.get(operation).await?;
+    let limit = state
+        .limits
+        .upsert(operation, request.min_amount, request.max_amount, request.daily_limit)
+        .await?;
+
+    let mut audit_entry = AuditLogBuilder::new(AuditAction::LimitUpdated)
+        .resource_type("TransferLimit")
+        .after_state(&limit);
+    if let Some(before) = before {
+        audit_entry = audit_entry.before_state(&before);
     }
+    state.audit.record(audit_entry, &context).await;
 
-    let idempotency_key = headers.get("Idempotency-Key");
-    let idem_key = idempotency_key
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| Uuid::parse_str(s).ok());
+    Ok(Json(limit))
+}
 
-    let handler = MintHandler::new(pool);
+/// Remove the limits for an operation (admin only)
+async fn delete_transfer_limit(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminLimits>,
+    Path(operation): Path<LimitOperation>,
+) -> Result<StatusCode, AppError> {
+    let before = state.limits.get(operation).await?;
+    state.limits.delete(operation).await?;
 
-    let command = MintCommand::new(request.recipient_user_id, request.amount, request.reason);
+    let mut audit_entry = AuditLogBuilder::new(AuditAction::LimitRemoved).resource_type("TransferLimit");
+    if let Some(before) = before {
+        audit_entry = audit_entry.before_state(&before);
+    }
+    state.audit.record(audit_entry, &context).await;
 
-    let result = handler.execute(command, idem_key, &context).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    Ok((
-        StatusCode::CREATED,
-        Json(MintResponse {
-            mint_id: result.mint_id,
-            status: "completed".to_string(),
-            to_user_id: result.recipient_user_id,
-            amount: result.amount,
-            created_at: chrono::Utc::now(),
-        }),
-    ))
+/// Alerts raised by the ALERT_* soft caps, newest first (admin only)
+async fn list_alerts(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminLimits>,
+    Query(query): Query<ListAlertsQuery>,
+) -> Result<Json<Vec<Alert>>, AppError> {
+    Ok(Json(state.alerts.list(&query).await?))
 }
 
 // =========================================================================
-// M129: POST /admin/burn
+// Account Restrictions
 // =========================================================================
 
-/// Burn ATP (admin only) - removes ATP from circulation
-async fn burn(
-    State(pool): State<PgPool>,
+/// List restricted accounts (admin only)
+async fn list_account_restrictions(
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::AdminAccounts>,
+) -> Result<Json<Vec<AccountRestriction>>, AppError> {
+    Ok(Json(state.restrictions.list(Some(permission.0.tenant_id)).await?))
+}
+
+/// Restriction of one account (admin only)
+async fn get_account_restriction(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+) -> Result<Json<AccountRestriction>, AppError> {
+    let restriction = state
+        .restrictions
+        .get(account_id)
+        .await?
+        .ok_or(RestrictionError::NotConfigured(account_id))?;
+
+    Ok(Json(restriction))
+}
+
+/// Create or replace the restriction of an account (admin only)
+async fn set_account_restriction(
+    State(state): State<SharedState>,
     Extension(context): Extension<OperationContext>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
-    headers: axum::http::HeaderMap,
-    Json(request): Json<BurnRequest>,
-) -> Result<(StatusCode, Json<BurnResponse>), AppError> {
-    // Check admin permission
-    if !api_key.has_permission("admin:burn") {
-        return Err(AppError::Forbidden("admin:burn permission required".to_string()));
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+    ApiJson(request): ApiJson<SetAccountRestrictionRequest>,
+) -> Result<Json<AccountRestriction>, AppError> {
+    ensure_in_tenant(&state, &context, &[], request.allowed_counterparties.clone()).await?;
+    let before = state.restrictions.get(account_id).await?;
+    let restriction = state
+        .restrictions
+        .upsert(account_id, request.mode, request.allowed_counterparties, request.reason)
+        .await?;
+
+    let mut audit_entry = AuditLogBuilder::new(AuditAction::AccountRestrictionUpdated)
+        .resource_type("Account")
+        .resource_id(account_id)
+        .after_state(&restriction);
+    if let Some(before) = before {
+        audit_entry = audit_entry.before_state(&before);
     }
+    state.audit.record(audit_entry, &context).await;
 
-    let idempotency_key = headers.get("Idempotency-Key");
-    let idem_key = idempotency_key
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| Uuid::parse_str(s).ok());
+    Ok(Json(restriction))
+}
 
-    let handler = crate::handlers::BurnHandler::new(pool);
+/// Remove the restriction of an account (admin only)
+async fn delete_account_restriction(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminAccounts>,
+    Path(account_id): Path<Uuid>,
+) -> Result<StatusCode, AppError> {
+    let before = state.restrictions.get(account_id).await?;
+    state.restrictions.delete(account_id).await?;
+
+    let mut audit_entry = AuditLogBuilder::new(AuditAction::AccountRestrictionRemoved)
+        .resource_type("Account")
+        .resource_id(account_id);
+    if let Some(before) = before {
+        audit_entry = audit_entry.before_state(&before);
+    }
+    state.audit.record(audit_entry, &context).await;
 
-    let command = crate::handlers::BurnCommand::new(
-        request.from_user_id,
-        request.amount,
-        request.reason,
-    );
+    Ok(StatusCode::NO_CONTENT)
+}
 
-    let result = handler.execute(command, idem_key, &context).await?;
+fn map_webhook_error(e: WebhookError) -> AppError {
+    match e {
+        WebhookError::Database(e) => AppError::Internal(e.to_string()),
+        e => AppError::InvalidRequest(e.to_string()),
+    }
+}
 
-    Ok((
-        StatusCode::CREATED,
-        Json(BurnResponse {
-            burn_id: result.burn_id,
-            status: "completed".to_string(),
-            from_user_id: result.from_user_id,
-            amount: result.amount,
-            created_at: chrono::Utc::now(),
-        }),
-    ))
+fn account_status_response(result: FreezeAccountResult) -> AccountStatusResponse {
+    AccountStatusResponse {
+        account_id: result.account_id,
+        status: if result.frozen { "frozen" } else { "active" }.to_string(),
+        changed_at: result.changed_at,
+    }
 }
 
 // =========================================================================
@@ -679,86 +3034,166 @@ async fn burn(
 
 /// Get events (admin only)
 async fn get_events(
-    State(pool): State<PgPool>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
-    Query(query): Query<EventsQuery>,
-) -> Result<Json<EventsListResponse>, AppError> {
-    // Check admin permission
-    if !api_key.has_permission("admin:events") {
-        return Err(AppError::Forbidden("admin:events permission required".to_string()));
-    }
-
-    let limit = query.limit.min(1000);
-    let offset = query.offset;
-
-    // Build query based on filters
-    let events: Vec<(Uuid, String, Uuid, String, i64, DateTime<Utc>)> = if let Some(ref agg_type) = query.aggregate_type {
-        if let Some(agg_id) = query.aggregate_id {
-            sqlx::query_as(
-                r#"
-                SELECT id, aggregate_type, aggregate_id, event_type, version, created_at
-                FROM events
-                WHERE aggregate_type = $1 AND aggregate_id = $2
-                ORDER BY created_at DESC
-                LIMIT $3 OFFSET $4
-                "#,
-            )
-            .bind(agg_type)
-            .bind(agg_id)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&pool)
-            .await?
-        } else {
-            sqlx::query_as(
-                r#"
-                SELECT id, aggregate_type, aggregate_id, event_type, version, created_at
-                FROM events
-                WHERE aggregate_type = $1
-                ORDER BY created_at DESC
-                LIMIT $2 OFFSET $3
-                "#,
-            )
-            .bind(agg_type)
-            .bind(limit)
-            .bind(offset)
-            .fetch_all(&pool)
-            .await?
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Query(query): Query<ListEventsQuery>,
+) -> Result<Json<EventPage>, AppError> {
+    QueryHandler::from_state(&state).list_events(&query).await.map(Json)
+}
+
+/// Export events as NDJSON in global_sequence order (admin only).
+/// The body is streamed in chunks, so exports of any size run in constant memory.
+async fn export_events(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Query(query): Query<EventExportQuery>,
+) -> Result<Response, AppError> {
+    if let (Some(from), Some(to)) = (query.from_date, query.to_date) {
+        if from > to {
+            return Err(AppError::InvalidRequest(
+                "from_date must not be after to_date".to_string(),
+            ));
         }
-    } else {
-        sqlx::query_as(
-            r#"
-            SELECT id, aggregate_type, aggregate_id, event_type, version, created_at
-            FROM events
-            ORDER BY created_at DESC
-            LIMIT $1 OFFSET $2
-            "#,
-        )
-        .bind(limit)
-        .bind(offset)
-        .fetch_all(&pool)
-        .await?
+    }
+
+    let filter = EventExportFilter {
+        aggregate_type: query.aggregate_type,
+        from_date: query.from_date,
+        to_date: query.to_date,
+        after_sequence: query.after_sequence.max(0),
     };
+    let chunks = export_ndjson(state.event_store.clone(), filter).map(|chunk| chunk.map(Bytes::from));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/x-ndjson"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"events.ndjson\""),
+        ],
+        Body::from_stream(chunks),
+    )
+        .into_response())
+}
+
+/// Get a single stored event with payload and context (admin only)
+async fn get_event(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<EventDetailResponse>, AppError> {
+    let event = state
+        .event_store
+        .get_event(event_id)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?
+        .ok_or_else(|| AppError::EventNotFound(event_id.to_string()))?;
+
+    Ok(Json(event.into()))
+}
+
+/// Recompute the stored daily event digests in a range and report days
+/// whose events changed since they were digested (admin only)
+async fn get_event_integrity(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Query(query): Query<DailyStatsQuery>,
+) -> Result<Json<EventIntegrityReport>, AppError> {
+    let (from, to) = query.range(Utc::now().date_naive())?;
+
+    let report = jobs::verify_event_integrity(&state.pool, from, to)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(report))
+}
+
+/// Checkpoints and lag of the global stream subscriptions (admin only)
+async fn list_subscriptions(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+) -> Result<Json<Vec<SubscriptionStatus>>, AppError> {
+    let subscriptions = Subscription::list(&state.pool)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(subscriptions))
+}
 
-    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events")
-        .fetch_one(&pool)
+/// Events skipped during replay, most recent failure first (admin only)
+async fn list_dead_letters(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Query(query): Query<DeadLettersQuery>,
+) -> Result<Json<Vec<DeadLetterEvent>>, AppError> {
+    let dead_letters = state
+        .dead_letters
+        .list(query.include_resolved, query.limit.clamp(1, 1000))
         .await?;
 
-    let events: Vec<EventResponse> = events
-        .into_iter()
-        .map(|(id, aggregate_type, aggregate_id, event_type, version, created_at)| {
-            EventResponse {
-                id,
-                aggregate_type,
-                aggregate_id,
-                event_type,
-                version,
-                created_at,
+    Ok(Json(dead_letters))
+}
+
+/// Re-check a dead-lettered event after a fix; resolves it if it now
+/// deserializes (admin only)
+async fn reprocess_dead_letter(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<DeadLetterEvent>, AppError> {
+    let dead_letter = state
+        .dead_letters
+        .reprocess(&state.event_store, event_id)
+        .await?
+        .ok_or_else(|| AppError::EventNotFound(event_id.to_string()))?;
+
+    Ok(Json(dead_letter))
+}
+
+/// Snapshot coverage of the event store (admin only)
+async fn get_snapshot_coverage(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+) -> Result<Json<jobs::SnapshotCoverage>, AppError> {
+    let coverage = jobs::snapshot_coverage(&state.pool)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(coverage))
+}
+
+/// Run snapshot maintenance now instead of waiting for the scheduler (admin only)
+async fn run_snapshot_maintenance(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+) -> Result<Json<SnapshotMaintenanceReport>, AppError> {
+    let report = jobs::maintain_snapshots(&state.pool)
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    Ok(Json(report))
+}
+
+/// Stream newly appended events as Server-Sent Events (admin only)
+async fn stream_events(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminEvents>,
+    Query(query): Query<EventStreamQuery>,
+) -> Result<Sse<impl Stream<Item = Result<SseEvent, axum::Error>>>, AppError> {
+    let aggregate_type = query.aggregate_type;
+    let events = BroadcastStream::new(state.event_notifier.subscribe()).filter_map(move |item| {
+        match item {
+            Ok(event) => aggregate_type
+                .as_ref()
+                .is_none_or(|t| *t == event.aggregate_type)
+                .then(|| SseEvent::default().id(event.id.to_string()).json_data(&event)),
+            // Slow client: tell it how many events it missed and keep going
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped = skipped, "Event stream subscriber lagged");
+                Some(Ok(SseEvent::default().event("lagged").data(skipped.to_string())))
             }
-        })
-        .collect();
+        }
+    });
 
-    Ok(Json(EventsListResponse { events, total }))
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
 }
 
 // =========================================================================
@@ -767,108 +3202,105 @@ async fn get_events(
 
 /// Get user balance by query parameter (legacy)
 async fn get_balance_legacy(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
     Query(query): Query<BalanceQuery>,
-) -> Result<Json<BalanceResponse>, AppError> {
-    get_user_balance(State(pool), Path(query.user_id)).await
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    get_user_balance(
+        State(state),
+        permission,
+        request_user,
+        Path(query.user_id),
+        Query(BalanceAsOfQuery::default()),
+        headers,
+    )
+    .await
 }
 
 /// Get user balance by path parameter (legacy)
 async fn get_balance_by_path(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    permission: RequirePermission<perms::ReadAccounts>,
+    request_user: Option<Extension<RequestUser>>,
     Path(user_id): Path<Uuid>,
-) -> Result<Json<BalanceResponse>, AppError> {
-    get_user_balance(State(pool), Path(user_id)).await
+    headers: HeaderMap,
+) -> Result<Response, AppError> {
+    get_user_balance(
+        State(state),
+        permission,
+        request_user,
+        Path(user_id),
+        Query(BalanceAsOfQuery::default()),
+        headers,
+    )
+    .await
 }
 
 // =========================================================================
 // API Key Management Handlers
 // =========================================================================
 
-/// Generate a random API key
-fn generate_api_key() -> String {
-    use rand::Rng;
-    let mut rng = rand::thread_rng();
-    let random_bytes: [u8; 24] = rng.gen();
-    format!("sk_live_{}", hex::encode(random_bytes))
-}
-
 /// Create a new API key
 async fn create_api_key(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
     Extension(api_key): Extension<AuthenticatedApiKey>,
-    Json(request): Json<CreateApiKeyRequest>,
+    _: RequirePermission<perms::AdminApiKeys>,
+    ValidJson(mut request): ValidJson<CreateApiKeyRequest>,
 ) -> Result<(StatusCode, Json<CreateApiKeyResponse>), AppError> {
-    // Check for admin:api-keys permission
-    if !api_key.permissions.iter().any(|p| p == "admin:api-keys") {
-        return Err(AppError::Forbidden("admin:api-keys permission required".to_string()));
+    // Keys for another tenant are issued by platform administrators only
+    match request.tenant_id {
+        Some(tenant_id) if tenant_id != api_key.tenant_id => {
+            require_permission(&api_key, Permission::AdminTenants)?;
+        }
+        _ => request.tenant_id = Some(api_key.tenant_id),
     }
 
-    let id = Uuid::new_v4();
-    let raw_key = generate_api_key();
-    let key_prefix = raw_key[..8].to_string();
-    let key_hash = format!("{:x}", sha2::Sha256::digest(raw_key.as_bytes()));
-    let now = chrono::Utc::now();
-
-    sqlx::query(
-        r#"
-        INSERT INTO api_keys (id, name, key_prefix, key_hash, permissions, rate_limit_per_minute, created_at)
-        VALUES ($1, $2, $3, $4, $5, $6, $7)
-        "#
-    )
-    .bind(id)
-    .bind(&request.name)
-    .bind(&key_prefix)
-    .bind(&key_hash)
-    .bind(&request.permissions)
-    .bind(request.rate_limit_per_minute)
-    .bind(now)
-    .execute(&pool)
-    .await?;
-
-    Ok((StatusCode::CREATED, Json(CreateApiKeyResponse {
-        id,
-        name: request.name,
-        api_key: raw_key,
-        key_prefix,
-        permissions: request.permissions,
-        rate_limit_per_minute: request.rate_limit_per_minute,
-        created_at: now,
-    })))
+    let created = issue_api_key(&state.pool, request).await?;
+
+    let audit_entry = AuditLogBuilder::new(AuditAction::ApiKeyCreated)
+        .resource_type("ApiKey")
+        .resource_id(created.id)
+        .after_state(&serde_json::json!({
+            "name": created.name,
+            "key_prefix": created.key_prefix,
+            "permissions": created.permissions,
+            "rate_limit_per_minute": created.rate_limit_per_minute,
+            "rate_limit_burst": created.rate_limit_burst,
+            "rate_limit_exempt": created.rate_limit_exempt,
+            "expires_at": created.expires_at,
+            "allowed_user_ids": created.allowed_user_ids,
+            "tenant_id": created.tenant_id,
+        }));
+    state.audit.record(audit_entry, &context).await;
+
+    Ok((StatusCode::CREATED, Json(created)))
 }
 
-/// List all API keys
+/// List the API keys of the caller's tenant
 async fn list_api_keys(
-    State(pool): State<PgPool>,
+    State(state): State<SharedState>,
     Extension(api_key): Extension<AuthenticatedApiKey>,
+    _: RequirePermission<perms::AdminApiKeys>,
 ) -> Result<Json<Vec<ApiKeyResponse>>, AppError> {
-    // Check for admin:api-keys permission
-    if !api_key.permissions.iter().any(|p| p == "admin:api-keys") {
-        return Err(AppError::Forbidden("admin:api-keys permission required".to_string()));
-    }
-
-    let keys: Vec<ApiKeyResponse> = sqlx::query_as::<_, (Uuid, String, String, Vec<String>, i32, bool, DateTime<Utc>, Option<DateTime<Utc>>)>(
+    let keys: Vec<ApiKeyResponse> = sqlx::query_as!(
+        ApiKeyRow,
         r#"
-        SELECT id, name, key_prefix, permissions, rate_limit_per_minute, is_active, created_at, last_used_at
+        SELECT id, name, key_prefix, permissions, rate_limit_per_minute, rate_limit_burst,
+               rate_limit_exempt, is_active, expires_at, allowed_user_ids, tenant_id, rotated_at,
+               previous_key_expires_at, created_at, last_used_at
         FROM api_keys
+        WHERE tenant_id = $1
         ORDER BY created_at DESC
-        "#
+        "#,
+        api_key.tenant_id
     )
-    .fetch_all(&pool)
+    .fetch_all(&state.pool)
     .await?
     .into_iter()
-    .map(|(id, name, key_prefix, permissions, rate_limit_per_minute, is_active, created_at, last_used_at)| {
-        ApiKeyResponse {
-            id,
-            name,
-            key_prefix,
-            permissions,
-            rate_limit_per_minute,
-            is_active,
-            created_at,
-            last_used_at,
-        }
-    })
+    .map(|row| ApiKeyResponse::from_row(row, &state.config))
     .collect();
 
     Ok(Json(keys))
@@ -876,14 +3308,31 @@ async fn list_api_keys(
 
 /// Update an API key
 async fn update_api_key(
-    State(pool): State<PgPool>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
-    Path(key_id): Path<Uuid>,
-    Json(request): Json<UpdateApiKeyRequest>,
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminApiKeys>,
+    Path(key_id): Path<ApiKeyId>,
+    ValidJson(request): ValidJson<UpdateApiKeyRequest>,
 ) -> Result<Json<ApiKeyResponse>, AppError> {
-    // Check for admin:api-keys permission
-    if !api_key.permissions.iter().any(|p| p == "admin:api-keys") {
-        return Err(AppError::Forbidden("admin:api-keys permission required".to_string()));
+    let permissions = request
+        .permissions
+        .as_deref()
+        .map(Permission::canonicalize)
+        .transpose()?;
+
+    // The path guard has already matched the key to the caller's tenant
+    let tenant_id = state
+        .tenants
+        .tenant_of(TenantResource::ApiKey, key_id.as_uuid())
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("API key not found".to_string()))?;
+    if let Some(ref permissions) = permissions {
+        check_tenant_permissions(tenant_id, permissions)?;
+    }
+    if let Some(ref allowed_user_ids) = request.allowed_user_ids {
+        state
+            .tenants
+            .ensure_all(Some(tenant_id), TenantResource::User, allowed_user_ids.iter().copied())
+            .await?;
     }
 
     // Build dynamic update query
@@ -898,95 +3347,328 @@ async fn update_api_key(
         updates.push(format!("rate_limit_per_minute = ${}", params.len() + 2));
         params.push(rate_limit.to_string());
     }
+    if let Some(ref burst) = request.rate_limit_burst {
+        if *burst < 0 {
+            return Err(AppError::InvalidRequest("rate_limit_burst must not be negative".to_string()));
+        }
+        updates.push(format!("rate_limit_burst = ${}", params.len() + 2));
+        params.push(burst.to_string());
+    }
+    if let Some(ref exempt) = request.rate_limit_exempt {
+        updates.push(format!("rate_limit_exempt = ${}", params.len() + 2));
+        params.push(exempt.to_string());
+    }
     if let Some(ref is_active) = request.is_active {
         updates.push(format!("is_active = ${}", params.len() + 2));
         params.push(is_active.to_string());
     }
 
-    if updates.is_empty() && request.permissions.is_none() {
+    if updates.is_empty() && permissions.is_none() && request.allowed_user_ids.is_none() {
         return Err(AppError::InvalidRequest("No fields to update".to_string()));
     }
 
     // Handle permissions separately due to array type
-    if let Some(ref permissions) = request.permissions {
-        sqlx::query("UPDATE api_keys SET permissions = $2 WHERE id = $1")
-            .bind(key_id)
-            .bind(permissions)
-            .execute(&pool)
+    if let Some(ref permissions) = permissions {
+        sqlx::query!("UPDATE api_keys SET permissions = $2 WHERE id = $1", key_id as _, permissions)
+            .execute(&state.pool)
             .await?;
     }
 
     // Handle other updates
     if let Some(ref name) = request.name {
-        sqlx::query("UPDATE api_keys SET name = $2 WHERE id = $1")
-            .bind(key_id)
-            .bind(name)
-            .execute(&pool)
+        sqlx::query!("UPDATE api_keys SET name = $2 WHERE id = $1", key_id as _, name)
+            .execute(&state.pool)
             .await?;
     }
     if let Some(rate_limit) = request.rate_limit_per_minute {
-        sqlx::query("UPDATE api_keys SET rate_limit_per_minute = $2 WHERE id = $1")
-            .bind(key_id)
-            .bind(rate_limit)
-            .execute(&pool)
+        sqlx::query!(
+            "UPDATE api_keys SET rate_limit_per_minute = $2 WHERE id = $1",
+            key_id as _,
+            rate_limit
+        )
+        .execute(&state.pool)
+        .await?;
+    }
+    if let Some(burst) = request.rate_limit_burst {
+        sqlx::query!("UPDATE api_keys SET rate_limit_burst = $2 WHERE id = $1", key_id as _, burst)
+            .execute(&state.pool)
+            .await?;
+    }
+    if let Some(exempt) = request.rate_limit_exempt {
+        sqlx::query!("UPDATE api_keys SET rate_limit_exempt = $2 WHERE id = $1", key_id as _, exempt)
+            .execute(&state.pool)
             .await?;
     }
     if let Some(is_active) = request.is_active {
-        sqlx::query("UPDATE api_keys SET is_active = $2 WHERE id = $1")
-            .bind(key_id)
-            .bind(is_active)
-            .execute(&pool)
+        sqlx::query!("UPDATE api_keys SET is_active = $2 WHERE id = $1", key_id as _, is_active)
+            .execute(&state.pool)
             .await?;
     }
-
-    // Fetch updated key
-    let row: Option<(Uuid, String, String, Vec<String>, i32, bool, DateTime<Utc>, Option<DateTime<Utc>>)> = 
-        sqlx::query_as(
-            "SELECT id, name, key_prefix, permissions, rate_limit_per_minute, is_active, created_at, last_used_at FROM api_keys WHERE id = $1"
+    if let Some(allowed_user_ids) = request.allowed_user_ids {
+        let allowed_user_ids = normalize_user_scope(Some(allowed_user_ids));
+        sqlx::query!(
+            "UPDATE api_keys SET allowed_user_ids = $2 WHERE id = $1",
+            key_id as _,
+            allowed_user_ids.as_deref()
         )
-        .bind(key_id)
-        .fetch_optional(&pool)
+        .execute(&state.pool)
         .await?;
+    }
+
+    // Fetch updated key
+    let row = sqlx::query_as!(
+        ApiKeyRow,
+        r#"
+        SELECT id, name, key_prefix, permissions, rate_limit_per_minute, rate_limit_burst,
+               rate_limit_exempt, is_active, expires_at, allowed_user_ids, tenant_id, rotated_at,
+               previous_key_expires_at, created_at, last_used_at
+        FROM api_keys
+        WHERE id = $1
+        "#,
+        key_id as _
+    )
+    .fetch_optional(&state.pool)
+    .await?;
 
-    let (id, name, key_prefix, permissions, rate_limit_per_minute, is_active, created_at, last_used_at) = 
-        row.ok_or_else(|| AppError::InvalidRequest("API key not found".to_string()))?;
+    let row = row.ok_or_else(|| AppError::InvalidRequest("API key not found".to_string()))?;
 
-    Ok(Json(ApiKeyResponse {
-        id,
-        name,
-        key_prefix,
-        permissions,
-        rate_limit_per_minute,
-        is_active,
-        created_at,
-        last_used_at,
-    }))
+    Ok(Json(ApiKeyResponse::from_row(row, &state.config)))
 }
 
 /// Delete (deactivate) an API key
 async fn delete_api_key(
-    State(pool): State<PgPool>,
-    Extension(api_key): Extension<AuthenticatedApiKey>,
-    Path(key_id): Path<Uuid>,
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminApiKeys>,
+    Path(key_id): Path<ApiKeyId>,
 ) -> Result<StatusCode, AppError> {
-    // Check for admin:api-keys permission
-    if !api_key.permissions.iter().any(|p| p == "admin:api-keys") {
-        return Err(AppError::Forbidden("admin:api-keys permission required".to_string()));
-    }
-
     // Soft delete by setting is_active = false
-    let result = sqlx::query("UPDATE api_keys SET is_active = false WHERE id = $1")
-        .bind(key_id)
-        .execute(&pool)
+    let result = sqlx::query!("UPDATE api_keys SET is_active = false WHERE id = $1", key_id as _)
+        .execute(&state.pool)
         .await?;
 
     if result.rows_affected() == 0 {
         return Err(AppError::InvalidRequest("API key not found".to_string()));
     }
 
+    let audit_entry = AuditLogBuilder::new(AuditAction::ApiKeyRevoked)
+        .resource_type("ApiKey")
+        .resource_id(key_id)
+        .after_state(&serde_json::json!({ "is_active": false }))
+        .changed_fields(vec!["is_active".to_string()]);
+    state.audit.record(audit_entry, &context).await;
+
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// Issue a new secret for an API key. The previous secret stays valid for
+/// the grace period; a secret still in its grace period from an earlier
+/// rotation is invalidated immediately.
+async fn rotate_api_key(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminApiKeys>,
+    Path(key_id): Path<ApiKeyId>,
+    request: Option<Json<RotateApiKeyRequest>>,
+) -> Result<Json<RotateApiKeyResponse>, AppError> {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let grace_period_secs = request
+        .grace_period_secs
+        .unwrap_or(state.config.api_key_rotation_grace_secs);
+    if grace_period_secs < 0 {
+        return Err(AppError::InvalidRequest(
+            "grace_period_secs must not be negative".to_string(),
+        ));
+    }
+
+    let raw_key = generate_api_key();
+    let key_prefix = raw_key[..8].to_string();
+    let key_hash = hash_api_key(&raw_key);
+    let rotated_at = Utc::now();
+    let previous_key_expires_at = rotated_at + chrono::Duration::seconds(grace_period_secs);
+
+    let row = sqlx::query!(
+        r#"
+        WITH current AS (
+            SELECT id, key_prefix FROM api_keys
+            WHERE id = $1 AND is_active = TRUE
+            FOR UPDATE
+        )
+        UPDATE api_keys k
+        SET previous_key_hash = k.key_hash,
+            previous_key_expires_at = $4,
+            key_hash = $2,
+            key_prefix = $3,
+            rotated_at = $5
+        FROM current
+        WHERE k.id = current.id
+        RETURNING k.name, current.key_prefix AS previous_key_prefix
+        "#,
+        key_id as _,
+        key_hash,
+        key_prefix,
+        previous_key_expires_at,
+        rotated_at
+    )
+    .fetch_optional(&state.pool)
+    .await?;
+
+    let row = row.ok_or_else(|| AppError::InvalidRequest("Active API key not found".to_string()))?;
+    let (name, previous_key_prefix) = (row.name, row.previous_key_prefix);
+
+    let audit_entry = AuditLogBuilder::new(AuditAction::ApiKeyCreated)
+        .resource_type("ApiKey")
+        .resource_id(key_id)
+        .after_state(&serde_json::json!({
+            "key_prefix": key_prefix,
+            "rotated_from": previous_key_prefix,
+        }))
+        .changed_fields(vec!["key_prefix".to_string()]);
+    state.audit.record(audit_entry, &context).await;
+
+    let audit_entry = AuditLogBuilder::new(AuditAction::ApiKeyRevoked)
+        .resource_type("ApiKey")
+        .resource_id(key_id)
+        .before_state(&serde_json::json!({ "key_prefix": previous_key_prefix }))
+        .after_state(&serde_json::json!({
+            "key_prefix": previous_key_prefix,
+            "valid_until": previous_key_expires_at,
+        }));
+    state.audit.record(audit_entry, &context).await;
+
+    Ok(Json(RotateApiKeyResponse {
+        id: key_id.into(),
+        name,
+        api_key: raw_key,
+        key_prefix,
+        previous_key_prefix,
+        previous_key_expires_at,
+        rotated_at,
+    }))
+}
+
+/// Mint/burn caps of an API key with its usage over the last 24 hours
+async fn get_api_key_limits(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminApiKeys>,
+    Path(key_id): Path<ApiKeyId>,
+) -> Result<Json<ApiKeyLimits>, AppError> {
+    let limits = state
+        .limits
+        .api_key_limits(key_id)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("API key not found".to_string()))?;
+
+    Ok(Json(limits))
+}
+
+/// Replace the mint/burn caps of an API key; omitted caps are removed
+async fn set_api_key_limits(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminApiKeys>,
+    Path(key_id): Path<ApiKeyId>,
+    ApiJson(caps): ApiJson<ApiKeyCaps>,
+) -> Result<Json<ApiKeyLimits>, AppError> {
+    let before = state
+        .limits
+        .api_key_caps(key_id)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("API key not found".to_string()))?;
+    state.limits.set_api_key_caps(key_id, &caps).await?;
+
+    let audit_entry = AuditLogBuilder::new(AuditAction::ApiKeyLimitsUpdated)
+        .resource_type("ApiKey")
+        .resource_id(key_id)
+        .before_state(&before)
+        .after_state(&caps);
+    state.audit.record(audit_entry, &context).await;
+
+    let limits = state
+        .limits
+        .api_key_limits(key_id)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest("API key not found".to_string()))?;
+
+    Ok(Json(limits))
+}
+
+// =========================================================================
+// Tenant Management Handlers
+// =========================================================================
+
+/// Create a tenant (platform admin only)
+async fn create_tenant(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminTenants>,
+    ValidJson(request): ValidJson<CreateTenantRequest>,
+) -> Result<(StatusCode, Json<Tenant>), AppError> {
+    let tenant = state.tenants.create(request.name.trim()).await?;
+
+    let audit_entry = AuditLogBuilder::new(AuditAction::TenantCreated)
+        .resource_type("Tenant")
+        .resource_id(tenant.id)
+        .after_state(&tenant);
+    state.audit.record(audit_entry, &context).await;
+
+    Ok((StatusCode::CREATED, Json(tenant)))
+}
+
+/// All tenants, default tenant first (platform admin only)
+async fn list_tenants(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminTenants>,
+) -> Result<Json<Vec<Tenant>>, AppError> {
+    Ok(Json(state.tenants.list().await?))
+}
+
+/// A tenant with its user count and total wallet balance (platform admin only)
+async fn get_tenant(
+    State(state): State<SharedState>,
+    _: RequirePermission<perms::AdminTenants>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Json<TenantSummary>, AppError> {
+    Ok(Json(state.tenants.summary(tenant_id).await?))
+}
+
+/// Rename or (de)activate a tenant (platform admin only)
+async fn update_tenant(
+    State(state): State<SharedState>,
+    Extension(context): Extension<OperationContext>,
+    _: RequirePermission<perms::AdminTenants>,
+    Path(tenant_id): Path<Uuid>,
+    ValidJson(request): ValidJson<UpdateTenantRequest>,
+) -> Result<Json<Tenant>, AppError> {
+    if request.name.is_none() && request.is_active.is_none() {
+        return Err(AppError::InvalidRequest("No fields to update".to_string()));
+    }
+
+    let before = state.tenants.get(tenant_id).await?;
+    let update = TenantUpdate {
+        name: request.name.map(|name| name.trim().to_string()),
+        is_active: request.is_active,
+    };
+    let tenant = state.tenants.update(tenant_id, update).await?;
+
+    let mut changed_fields = Vec::new();
+    if tenant.name != before.name {
+        changed_fields.push("name".to_string());
+    }
+    if tenant.is_active != before.is_active {
+        changed_fields.push("is_active".to_string());
+    }
+    let audit_entry = AuditLogBuilder::new(AuditAction::TenantUpdated)
+        .resource_type("Tenant")
+        .resource_id(tenant_id)
+        .before_state(&before)
+        .after_state(&tenant)
+        .changed_fields(changed_fields);
+    state.audit.record(audit_entry, &context).await;
+
+    Ok(Json(tenant))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1014,15 +3696,39 @@ mod tests {
         }"#;
 
         let request: TransferRequest = serde_json::from_str(json).unwrap();
-        assert_eq!(request.amount, "100.50");
+        assert_eq!(request.amount.to_string(), "100.50");
         assert_eq!(request.memo, Some("Test payment".to_string()));
     }
 
     #[test]
-    fn test_events_query_defaults() {
-        let query: EventsQuery = serde_json::from_str("{}").unwrap();
-        assert_eq!(query.limit, 50);
-        assert_eq!(query.offset, 0);
-        assert!(query.aggregate_type.is_none());
+    fn test_daily_stats_query_range() {
+        let today: NaiveDate = "2026-03-31".parse().unwrap();
+
+        let (from, to) = DailyStatsQuery::default().range(today).unwrap();
+        assert_eq!(from, "2026-03-02".parse::<NaiveDate>().unwrap());
+        assert_eq!(to, today);
+
+        let inverted = DailyStatsQuery {
+            from: Some(today),
+            to: Some("2026-03-01".parse().unwrap()),
+        };
+        assert!(matches!(inverted.range(today), Err(AppError::InvalidRequest(_))));
+
+        let too_long = DailyStatsQuery {
+            from: Some("2025-01-01".parse().unwrap()),
+            to: None,
+        };
+        assert!(matches!(too_long.range(today), Err(AppError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_create_api_key_request_defaults() {
+        let json = r#"{"name": "svc", "permissions": ["read:users"]}"#;
+
+        let request: CreateApiKeyRequest = serde_json::from_str(json).unwrap();
+        assert_eq!(request.rate_limit_per_minute, 1000);
+        assert_eq!(request.rate_limit_burst, None);
+        assert!(!request.rate_limit_exempt);
+        assert!(request.expires_at.is_none());
     }
 }
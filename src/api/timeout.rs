@@ -0,0 +1,118 @@
+//! Latency Budgets
+//!
+//! Every API request runs under a time budget chosen by its route group, so
+//! a slow database query fails the request with 503 instead of letting
+//! requests pile up behind it. Admin maintenance routes (rebuilds, manual
+//! job runs, full verifications) get their own, longer budget.
+
+use std::time::Duration;
+
+use axum::http::Method;
+use serde::Serialize;
+
+/// Route groups with separate latency budgets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RouteGroup {
+    /// GET and HEAD requests
+    Read,
+    /// Every other method
+    Write,
+    /// Long-running admin operations
+    Maintenance,
+}
+
+impl RouteGroup {
+    /// Group of a request by method and path (relative to /api/v1)
+    pub fn of(method: &Method, path: &str) -> Self {
+        if is_maintenance_route(method, path) {
+            Self::Maintenance
+        } else if method == Method::GET || method == Method::HEAD {
+            Self::Read
+        } else {
+            Self::Write
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Maintenance => "maintenance",
+        }
+    }
+}
+
+/// Admin routes that scan whole tables and may run for minutes
+fn is_maintenance_route(method: &Method, path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    if method == Method::GET {
+        matches!(
+            path,
+            "/admin/events/integrity" | "/admin/audit-logs/verify" | "/admin/reconciliation"
+        )
+    } else if method == Method::POST {
+        matches!(
+            path,
+            "/admin/projections/rebuild" | "/admin/projections/shadow-rebuild" | "/admin/snapshots/maintain"
+        ) || (path.starts_with("/admin/jobs/") && path.ends_with("/run"))
+    } else {
+        false
+    }
+}
+
+/// Time budget per route group (REQUEST_TIMEOUT_*_MS)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBudgets {
+    pub read: Duration,
+    pub write: Duration,
+    pub maintenance: Duration,
+}
+
+impl Default for LatencyBudgets {
+    fn default() -> Self {
+        Self {
+            read: Duration::from_secs(2),
+            write: Duration::from_secs(10),
+            maintenance: Duration::from_secs(300),
+        }
+    }
+}
+
+impl LatencyBudgets {
+    pub fn budget(&self, group: RouteGroup) -> Duration {
+        match group {
+            RouteGroup::Read => self.read,
+            RouteGroup::Write => self.write,
+            RouteGroup::Maintenance => self.maintenance,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_group_of_request() {
+        assert_eq!(RouteGroup::of(&Method::GET, "/users/abc/balance"), RouteGroup::Read);
+        assert_eq!(RouteGroup::of(&Method::HEAD, "/users/abc/balance"), RouteGroup::Read);
+        assert_eq!(RouteGroup::of(&Method::POST, "/transfers"), RouteGroup::Write);
+        assert_eq!(RouteGroup::of(&Method::DELETE, "/admin/webhooks/abc"), RouteGroup::Write);
+
+        assert_eq!(RouteGroup::of(&Method::GET, "/admin/events/integrity"), RouteGroup::Maintenance);
+        assert_eq!(RouteGroup::of(&Method::POST, "/admin/projections/rebuild/"), RouteGroup::Maintenance);
+        assert_eq!(RouteGroup::of(&Method::POST, "/admin/jobs/daily_stats/run"), RouteGroup::Maintenance);
+        // Only the listed method is long-running
+        assert_eq!(RouteGroup::of(&Method::GET, "/admin/jobs"), RouteGroup::Read);
+        assert_eq!(RouteGroup::of(&Method::GET, "/admin/projections/status"), RouteGroup::Read);
+    }
+
+    #[test]
+    fn test_default_budgets() {
+        let budgets = LatencyBudgets::default();
+        assert_eq!(budgets.budget(RouteGroup::Read), Duration::from_secs(2));
+        assert_eq!(budgets.budget(RouteGroup::Write), Duration::from_secs(10));
+        assert_eq!(budgets.budget(RouteGroup::Maintenance), Duration::from_secs(300));
+    }
+}
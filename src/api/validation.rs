@@ -0,0 +1,308 @@
+//! Request field parsing and validation
+//!
+//! Amounts arrive as decimal strings. They are parsed while the JSON body is
+//! deserialized, but a bad amount does not reject the whole body: the route
+//! turns it into a field error so clients get the reason (`not_positive`,
+//! `too_many_decimals`, ...) and the field it applies to.
+//!
+//! Other fields are checked by the request type's `Validate` impl when the
+//! body is extracted with `ValidJson`. Every invalid field is reported at
+//! once in a 422 response, before any handler runs.
+//!
+//! Both extractors report bodies that cannot be read (malformed JSON, wrong
+//! content type, too large) in the standard error envelope.
+
+use std::fmt;
+use std::ops::RangeInclusive;
+
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::permission::Permission;
+use crate::domain::{Amount, AmountError};
+use crate::error::{AppError, FieldError};
+
+/// Longest transfer or hold memo
+pub const MAX_MEMO_LENGTH: usize = 500;
+
+/// Longest reason of a mint, burn, freeze, reversal or rejection
+pub const MAX_REASON_LENGTH: usize = 500;
+
+/// Length of users.username
+const USERNAME_LENGTH: RangeInclusive<usize> = 3..=50;
+
+/// Length of users.email
+const MAX_EMAIL_LENGTH: usize = 100;
+
+/// Length of users.display_name
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+
+/// Length of api_keys.name
+const MAX_API_KEY_NAME_LENGTH: usize = 100;
+
+/// Length of tenants.name
+const MAX_TENANT_NAME_LENGTH: usize = 100;
+
+/// Request body whose fields can be checked before it reaches a handler
+pub trait Validate {
+    /// Record every invalid field in `errors`
+    fn validate(&self, errors: &mut FieldErrors);
+
+    /// 422 listing every invalid field, for bodies not extracted with
+    /// `ValidJson` (e.g. optional bodies)
+    fn check(&self) -> Result<(), AppError> {
+        let mut errors = FieldErrors::default();
+        self.validate(&mut errors);
+        errors.into_result()
+    }
+}
+
+/// Field errors collected while validating one request body
+#[derive(Debug, Default)]
+pub struct FieldErrors(Vec<FieldError>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: &str, code: &str, message: impl Into<String>) {
+        self.0.push(FieldError::new(field, code, message));
+    }
+
+    /// 3-50 ASCII letters, digits and underscores (users.valid_username)
+    pub fn username(&mut self, field: &str, value: &str) {
+        let length = value.chars().count();
+        if length < *USERNAME_LENGTH.start() {
+            self.add(field, "too_short", format!("must be at least {} characters", USERNAME_LENGTH.start()));
+        } else if length > *USERNAME_LENGTH.end() {
+            self.add(field, "too_long", format!("must be at most {} characters", USERNAME_LENGTH.end()));
+        } else if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            self.add(field, "invalid_format", "may only contain letters, digits and '_'");
+        }
+    }
+
+    /// local@domain.tld, at most 100 characters (users.valid_email)
+    pub fn email(&mut self, field: &str, value: &str) {
+        if value.chars().count() > MAX_EMAIL_LENGTH {
+            self.add(field, "too_long", format!("must be at most {} characters", MAX_EMAIL_LENGTH));
+        } else if !is_email(value) {
+            self.add(field, "invalid_format", "must be an email address");
+        }
+    }
+
+    pub fn display_name(&mut self, field: &str, value: Option<&str>) {
+        self.max_length(field, value, MAX_DISPLAY_NAME_LENGTH);
+    }
+
+    pub fn memo(&mut self, field: &str, value: Option<&str>) {
+        self.max_length(field, value, MAX_MEMO_LENGTH);
+    }
+
+    pub fn reason(&mut self, field: &str, value: Option<&str>) {
+        self.max_length(field, value, MAX_REASON_LENGTH);
+    }
+
+    /// Non-blank and at most 100 characters
+    pub fn api_key_name(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "blank", "must not be blank");
+        } else {
+            self.max_length(field, Some(value), MAX_API_KEY_NAME_LENGTH);
+        }
+    }
+
+    /// Non-blank and at most 100 characters (tenants.name)
+    pub fn tenant_name(&mut self, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            self.add(field, "blank", "must not be blank");
+        } else {
+            self.max_length(field, Some(value), MAX_TENANT_NAME_LENGTH);
+        }
+    }
+
+    /// Every entry names a Permission (legacy spellings included)
+    pub fn permissions(&mut self, field: &str, values: &[String]) {
+        for (index, value) in values.iter().enumerate() {
+            if value.parse::<Permission>().is_err() {
+                self.add(
+                    &format!("{}[{}]", field, index),
+                    "unknown_permission",
+                    format!("unknown permission '{}'", value),
+                );
+            }
+        }
+    }
+
+    pub fn max_length(&mut self, field: &str, value: Option<&str>, max: usize) {
+        if value.is_some_and(|v| v.chars().count() > max) {
+            self.add(field, "too_long", format!("must be at most {} characters", max));
+        }
+    }
+
+    /// 422 listing every invalid field, if there are any
+    pub fn into_result(self) -> Result<(), AppError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(AppError::Validation(self.0))
+        }
+    }
+}
+
+/// Same shape as users.valid_email: `[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}`
+fn is_email(value: &str) -> bool {
+    let Some((local, domain)) = value.split_once('@') else {
+        return false;
+    };
+    let Some((host, tld)) = domain.rsplit_once('.') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && local.chars().all(|c| c.is_ascii_alphanumeric() || "._%+-".contains(c))
+        && !host.is_empty()
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || ".-".contains(c))
+        && tld.len() >= 2
+        && tld.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+/// `Json` extractor whose rejections are `AppError`s
+#[derive(Debug, Clone, Default, FromRequest)]
+#[from_request(via(axum::Json), rejection(AppError))]
+pub struct ApiJson<T>(pub T);
+
+/// JSON body extractor that also runs the body's `Validate` impl.
+/// Unreadable bodies are rejected as by `ApiJson`; invalid fields with 422.
+#[derive(Debug, Clone)]
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let ApiJson(value) = ApiJson::<T>::from_request(req, state).await?;
+        value.check()?;
+
+        Ok(Self(value))
+    }
+}
+
+/// Amount field of a request body, as sent and as parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmountInput {
+    raw: String,
+    parsed: Result<Amount, AmountError>,
+}
+
+impl AmountInput {
+    /// The parsed amount, or a 400 naming `field` and why it is invalid
+    pub fn into_amount(self, field: &str) -> Result<Amount, AppError> {
+        self.parsed
+            .map_err(|e| AppError::InvalidFields(vec![FieldError::new(field, e.code(), e.to_string())]))
+    }
+}
+
+impl From<String> for AmountInput {
+    fn from(raw: String) -> Self {
+        let parsed = raw.parse();
+        Self { raw, parsed }
+    }
+}
+
+impl From<&str> for AmountInput {
+    fn from(raw: &str) -> Self {
+        Self::from(raw.to_string())
+    }
+}
+
+impl fmt::Display for AmountInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for AmountInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(Self::from)
+    }
+}
+
+impl Serialize for AmountInput {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+
+    #[test]
+    fn test_amount_input() {
+        let valid: AmountInput = serde_json::from_str("\"12.50\"").unwrap();
+        assert_eq!(valid.clone().into_amount("amount").unwrap().value(), Decimal::new(1250, 2));
+        assert_eq!(serde_json::to_string(&valid).unwrap(), "\"12.50\"");
+
+        // An invalid amount still deserializes and is reported per field
+        let invalid: AmountInput = serde_json::from_str("\"0.000000001\"").unwrap();
+        match invalid.into_amount("amount") {
+            Err(AppError::InvalidFields(fields)) => {
+                assert_eq!(fields[0].field, "amount");
+                assert_eq!(fields[0].code, "too_many_decimals");
+            }
+            other => panic!("expected InvalidFields, got {:?}", other),
+        }
+
+        assert_eq!(AmountInput::from("-1").into_amount("amount").unwrap_err().to_string(), "Invalid request fields");
+        assert!(serde_json::from_str::<AmountInput>("12").is_err());
+    }
+
+    fn codes(check: impl FnOnce(&mut FieldErrors)) -> Vec<String> {
+        let mut errors = FieldErrors::default();
+        check(&mut errors);
+        errors.0.into_iter().map(|e| format!("{}:{}", e.field, e.code)).collect()
+    }
+
+    #[test]
+    fn test_field_errors() {
+        assert!(codes(|e| e.username("username", "alice_01")).is_empty());
+        assert_eq!(codes(|e| e.username("username", "al")), ["username:too_short"]);
+        assert_eq!(codes(|e| e.username("username", &"a".repeat(51))), ["username:too_long"]);
+        assert_eq!(codes(|e| e.username("username", "alice!")), ["username:invalid_format"]);
+
+        assert!(codes(|e| e.email("email", "a.b+c@example.co.jp")).is_empty());
+        for bad in ["alice", "alice@", "@example.com", "alice@example", "alice@example.c", "a b@example.com"] {
+            assert_eq!(codes(|e| e.email("email", bad)), ["email:invalid_format"], "{}", bad);
+        }
+
+        assert!(codes(|e| e.memo("memo", None)).is_empty());
+        assert_eq!(codes(|e| e.memo("memo", Some(&"x".repeat(MAX_MEMO_LENGTH + 1)))), ["memo:too_long"]);
+        assert_eq!(codes(|e| e.api_key_name("name", " ")), ["name:blank"]);
+
+        let permissions = ["read:users".to_string(), "mint".to_string(), "root".to_string()];
+        assert_eq!(codes(|e| e.permissions("permissions", &permissions)), ["permissions[2]:unknown_permission"]);
+
+        assert!(FieldErrors::default().into_result().is_ok());
+    }
+
+    async fn extract(content_type: &str, body: &str) -> Result<ApiJson<serde_json::Value>, AppError> {
+        let request = Request::builder()
+            .header("content-type", content_type)
+            .body(axum::body::Body::from(body.to_string()))
+            .unwrap();
+        ApiJson::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_api_json_rejections() {
+        assert!(extract("application/json", "{\"a\": 1}").await.is_ok());
+        assert!(matches!(extract("application/json", "{\"a\":").await, Err(AppError::MalformedJson(_))));
+        assert!(matches!(extract("text/plain", "{}").await, Err(AppError::UnsupportedMediaType(_))));
+    }
+}
@@ -0,0 +1,147 @@
+//! Audit Chain Anchoring
+//!
+//! Publishes the verified head of the audit log hash chain outside the
+//! database (AUDIT_ANCHOR), so rewriting the chain together with its
+//! checkpoints is still detectable against the published hashes.
+//!
+//! Targets:
+//! - `file:/path/to/anchors.jsonl` appends one JSON line per anchor
+//! - `https://...` (or `http://...`) POSTs the anchor as JSON
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Timeout of a webhook anchor request
+const ANCHOR_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where chain heads are published
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditAnchor {
+    File(PathBuf),
+    Webhook(String),
+}
+
+/// Published chain head
+#[derive(Debug, Clone, Serialize)]
+pub struct ChainAnchor {
+    pub sequence_number: i64,
+    pub chain_head_hash: String,
+    pub verified_at: DateTime<Utc>,
+}
+
+impl AuditAnchor {
+    /// Publish `anchor` to this target
+    pub async fn publish(&self, http: &reqwest::Client, anchor: &ChainAnchor) -> Result<(), AnchorError> {
+        match self {
+            AuditAnchor::File(path) => {
+                let mut line = serde_json::to_vec(anchor)?;
+                line.push(b'\n');
+
+                let mut file = tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?;
+                file.write_all(&line).await?;
+                file.sync_data().await?;
+            }
+            AuditAnchor::Webhook(url) => {
+                http.post(url)
+                    .timeout(ANCHOR_REQUEST_TIMEOUT)
+                    .json(anchor)
+                    .send()
+                    .await?
+                    .error_for_status()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AuditAnchor {
+    type Err = AnchorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(path) = s.strip_prefix("file:") {
+            if path.is_empty() {
+                return Err(AnchorError::InvalidTarget(s.to_string()));
+            }
+            Ok(AuditAnchor::File(PathBuf::from(path)))
+        } else if s.starts_with("https://") || s.starts_with("http://") {
+            Ok(AuditAnchor::Webhook(s.to_string()))
+        } else {
+            Err(AnchorError::InvalidTarget(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for AuditAnchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditAnchor::File(path) => write!(f, "file:{}", path.display()),
+            AuditAnchor::Webhook(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// Anchor errors
+#[derive(Debug, thiserror::Error)]
+pub enum AnchorError {
+    #[error("Invalid audit anchor target: {0}")]
+    InvalidTarget(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_anchor_target() {
+        assert_eq!(
+            "file:/var/lib/atp/anchors.jsonl".parse::<AuditAnchor>().unwrap(),
+            AuditAnchor::File(PathBuf::from("/var/lib/atp/anchors.jsonl"))
+        );
+        let webhook: AuditAnchor = " https://anchor.example.com/heads ".parse().unwrap();
+        assert_eq!(webhook.to_string(), "https://anchor.example.com/heads");
+
+        assert!("file:".parse::<AuditAnchor>().is_err());
+        assert!("s3://bucket/key".parse::<AuditAnchor>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_file_anchor_appends_lines() {
+        let path = std::env::temp_dir().join(format!("audit-anchor-{}.jsonl", uuid::Uuid::new_v4()));
+        let target = AuditAnchor::File(path.clone());
+        let http = reqwest::Client::new();
+
+        for sequence_number in [1, 2] {
+            let anchor = ChainAnchor {
+                sequence_number,
+                chain_head_hash: "ab".repeat(32),
+                verified_at: Utc::now(),
+            };
+            target.publish(&http, &anchor).await.unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        let lines: Vec<serde_json::Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["sequence_number"], 2);
+    }
+}
@@ -11,6 +11,12 @@ use uuid::Uuid;
 
 use crate::domain::OperationContext;
 
+pub mod anchor;
+pub mod trace;
+
+pub use anchor::{AnchorError, AuditAnchor, ChainAnchor};
+pub use trace::{load_trace, Trace, TraceEntry, TraceError, TraceRecord};
+
 // =========================================================================
 // M141: AuditLogService
 // =========================================================================
@@ -35,17 +41,83 @@ pub struct AuditLogEntry {
     pub created_at: DateTime<Utc>,
 }
 
+/// Row of `SELECT ... FROM audit_logs` (client_ip read as text)
+#[derive(Debug, sqlx::FromRow)]
+struct AuditLogRow {
+    id: Uuid,
+    sequence_number: i64,
+    api_key_id: Option<Uuid>,
+    request_user_id: Option<Uuid>,
+    correlation_id: Option<Uuid>,
+    action: String,
+    resource_type: Option<String>,
+    resource_id: Option<Uuid>,
+    before_state: Option<serde_json::Value>,
+    after_state: Option<serde_json::Value>,
+    changed_fields: Option<Vec<String>>,
+    client_ip: Option<String>,
+    previous_hash: String,
+    current_hash: String,
+    created_at: DateTime<Utc>,
+}
+
+impl From<AuditLogRow> for AuditLogEntry {
+    fn from(row: AuditLogRow) -> Self {
+        Self {
+            id: row.id,
+            sequence_number: row.sequence_number,
+            api_key_id: row.api_key_id,
+            request_user_id: row.request_user_id,
+            correlation_id: row.correlation_id,
+            action: row.action,
+            resource_type: row.resource_type,
+            resource_id: row.resource_id,
+            before_state: row.before_state,
+            after_state: row.after_state,
+            changed_fields: row.changed_fields,
+            client_ip: row.client_ip.and_then(|s| s.parse().ok()),
+            previous_hash: row.previous_hash,
+            current_hash: row.current_hash,
+            created_at: row.created_at,
+        }
+    }
+}
+
 /// Audit action types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AuditAction {
     UserCreated,
     UserUpdated,
     UserDeactivated,
+    UserReactivated,
+    UserAnonymized,
+    UserRenamed,
     TransferExecuted,
+    TransferReversed,
+    TransferApprovalRequested,
+    TransferApproved,
+    TransferRejected,
+    TransferExpired,
     MintExecuted,
     BurnExecuted,
+    AccountCreated,
+    AccountFrozen,
+    AccountClosed,
+    AccountUnfrozen,
+    AccountRestrictionUpdated,
+    AccountRestrictionRemoved,
+    HoldPlaced,
+    HoldCaptured,
+    HoldReleased,
     ApiKeyCreated,
     ApiKeyRevoked,
+    ApiKeyLimitsUpdated,
+    WebhookCreated,
+    WebhookDeactivated,
+    LimitUpdated,
+    LimitRemoved,
+    TenantCreated,
+    TenantUpdated,
     LoginAttempt,
     PermissionDenied,
 }
@@ -56,11 +128,35 @@ impl AuditAction {
             AuditAction::UserCreated => "user.created",
             AuditAction::UserUpdated => "user.updated",
             AuditAction::UserDeactivated => "user.deactivated",
+            AuditAction::UserReactivated => "user.reactivated",
+            AuditAction::UserAnonymized => "user.anonymized",
+            AuditAction::UserRenamed => "user.renamed",
             AuditAction::TransferExecuted => "transfer.executed",
+            AuditAction::TransferReversed => "transfer.reversed",
+            AuditAction::TransferApprovalRequested => "transfer.approval_requested",
+            AuditAction::TransferApproved => "transfer.approved",
+            AuditAction::TransferRejected => "transfer.rejected",
+            AuditAction::TransferExpired => "transfer.expired",
             AuditAction::MintExecuted => "mint.executed",
             AuditAction::BurnExecuted => "burn.executed",
+            AuditAction::AccountCreated => "account.created",
+            AuditAction::AccountFrozen => "account.frozen",
+            AuditAction::AccountClosed => "account.closed",
+            AuditAction::AccountUnfrozen => "account.unfrozen",
+            AuditAction::AccountRestrictionUpdated => "account.restriction_updated",
+            AuditAction::AccountRestrictionRemoved => "account.restriction_removed",
+            AuditAction::HoldPlaced => "hold.placed",
+            AuditAction::HoldCaptured => "hold.captured",
+            AuditAction::HoldReleased => "hold.released",
             AuditAction::ApiKeyCreated => "api_key.created",
             AuditAction::ApiKeyRevoked => "api_key.revoked",
+            AuditAction::ApiKeyLimitsUpdated => "api_key.limits_updated",
+            AuditAction::WebhookCreated => "webhook.created",
+            AuditAction::WebhookDeactivated => "webhook.deactivated",
+            AuditAction::LimitUpdated => "limit.updated",
+            AuditAction::LimitRemoved => "limit.removed",
+            AuditAction::TenantCreated => "tenant.created",
+            AuditAction::TenantUpdated => "tenant.updated",
             AuditAction::LoginAttempt => "auth.login_attempt",
             AuditAction::PermissionDenied => "auth.permission_denied",
         }
@@ -73,6 +169,19 @@ impl std::fmt::Display for AuditAction {
     }
 }
 
+/// Filters for querying audit logs
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub action: Option<String>,
+    pub resource_type: Option<String>,
+    pub resource_id: Option<Uuid>,
+    pub correlation_id: Option<Uuid>,
+    /// Inclusive lower bound on created_at
+    pub from_date: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on created_at
+    pub to_date: Option<DateTime<Utc>>,
+}
+
 /// Builder for creating audit log entries
 #[derive(Debug, Clone)]
 pub struct AuditLogBuilder {
@@ -115,9 +224,9 @@ impl AuditLogBuilder {
         self
     }
 
-    /// Set the resource ID
-    pub fn resource_id(mut self, resource_id: Uuid) -> Self {
-        self.resource_id = Some(resource_id);
+    /// Set the resource ID (a bare or typed UUID)
+    pub fn resource_id(mut self, resource_id: impl Into<Uuid>) -> Self {
+        self.resource_id = Some(resource_id.into());
         self
     }
 
@@ -169,38 +278,55 @@ impl AuditLogService {
         let changed_fields_array: Option<Vec<String>> = builder.changed_fields;
 
         // Note: sequence_number, previous_hash, and current_hash are set by the DB trigger
-        let result: (Uuid,) = sqlx::query_as(
+        let audit_id = sqlx::query_scalar!(
             r#"
             INSERT INTO audit_logs (
                 id, api_key_id, request_user_id, correlation_id,
                 action, resource_type, resource_id,
                 before_state, after_state, changed_fields, client_ip
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11::text::inet)
             RETURNING id
             "#,
+            id,
+            context.api_key_id,
+            context.request_user_id,
+            context.correlation_id,
+            builder.action,
+            builder.resource_type,
+            builder.resource_id,
+            builder.before_state,
+            builder.after_state,
+            changed_fields_array.as_deref(),
+            context.client_ip.map(|ip| ip.to_string())
         )
-        .bind(id)
-        .bind(context.api_key_id)
-        .bind(context.request_user_id)
-        .bind(context.correlation_id)
-        .bind(&builder.action)
-        .bind(&builder.resource_type)
-        .bind(builder.resource_id)
-        .bind(&builder.before_state)
-        .bind(&builder.after_state)
-        .bind(&changed_fields_array)
-        .bind(context.client_ip.map(|ip| ip.to_string()))
         .fetch_one(&self.pool)
         .await?;
 
         tracing::debug!(
-            audit_id = %result.0,
+            audit_id = %audit_id,
             action = %builder.action,
             "Audit log entry created"
         );
 
-        Ok(result.0)
+        Ok(audit_id)
+    }
+
+    /// Write an audit log entry from a command handler.
+    /// The command has already been committed, so failures are logged, not propagated.
+    pub async fn record(&self, builder: AuditLogBuilder, context: &OperationContext) {
+        let action = builder.action.clone();
+        let resource_id = builder.resource_id;
+
+        if let Err(e) = self.log(builder, context).await {
+            tracing::error!(
+                action = %action,
+                resource_id = ?resource_id,
+                correlation_id = ?context.correlation_id,
+                "Failed to write audit log: {}",
+                e
+            );
+        }
     }
 
     // =========================================================================
@@ -210,182 +336,173 @@ impl AuditLogService {
     /// Verify the integrity of the audit log hash chain
     /// Returns Ok(true) if chain is valid, Ok(false) if tampered, Err on DB error
     pub async fn verify_hash_chain(&self, limit: Option<i64>) -> Result<ChainVerificationResult, AuditLogError> {
-        let limit = limit.unwrap_or(1000);
+        let start = self.chain_start().await?;
+        let (result, _) = self.verify_hash_chain_after(&start, limit.unwrap_or(1000)).await?;
+        Ok(result)
+    }
+
+    /// Position before the oldest entry still in audit_logs: the end of the
+    /// newest archive, or the genesis position before any archival
+    pub async fn chain_start(&self) -> Result<ChainPosition, AuditLogError> {
+        let anchor = sqlx::query!("SELECT last_sequence, last_hash FROM audit_archives ORDER BY last_sequence DESC LIMIT 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(anchor
+            .map(|archive| ChainPosition {
+                sequence_number: archive.last_sequence,
+                hash: archive.last_hash,
+            })
+            .unwrap_or_else(ChainPosition::genesis))
+    }
 
-        let entries: Vec<(Uuid, i64, String, String, String, Option<Uuid>, Option<serde_json::Value>, Option<serde_json::Value>)> = sqlx::query_as(
+    /// Verify up to `limit` entries following `from`. Also returns the last
+    /// verified position, which is `from` when there was nothing new or the
+    /// first new entry is invalid.
+    pub async fn verify_hash_chain_after(
+        &self,
+        from: &ChainPosition,
+        limit: i64,
+    ) -> Result<(ChainVerificationResult, ChainPosition), AuditLogError> {
+        let rows = self.chain_rows(from.sequence_number, limit).await?;
+        let (result, head) = verify_chain_rows(&rows, &from.hash);
+        Ok((result, head.unwrap_or_else(|| from.clone())))
+    }
+
+    /// Hash inputs of the entries after `after_sequence`, in chain order
+    async fn chain_rows(&self, after_sequence: i64, limit: i64) -> Result<Vec<AuditChainRow>, AuditLogError> {
+        // JSONB states are read back as text so the input matches calculate_audit_hash()
+        let rows = sqlx::query_as!(
+            AuditChainRow,
             r#"
-            SELECT id, sequence_number, action, previous_hash, current_hash, 
-                   request_user_id, before_state, after_state
+            SELECT id, sequence_number, action, previous_hash, current_hash,
+                   request_user_id, resource_type, resource_id,
+                   before_state::text AS "before_state?", after_state::text AS "after_state?"
             FROM audit_logs
+            WHERE sequence_number > $1
             ORDER BY sequence_number ASC
-            LIMIT $1
+            LIMIT $2
             "#,
+            after_sequence,
+            limit
         )
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        if entries.is_empty() {
-            return Ok(ChainVerificationResult {
-                is_valid: true,
-                entries_checked: 0,
-                first_invalid_entry: None,
-                expected_hash: None,
-                actual_hash: None,
-            });
-        }
-
-        let mut previous_hash = "0000000000000000000000000000000000000000000000000000000000000000".to_string();
-
-        for (id, seq, action, prev_hash, current_hash, req_user_id, before_state, after_state) in &entries {
-            // Verify chain linkage
-            if prev_hash != &previous_hash {
-                return Ok(ChainVerificationResult {
-                    is_valid: false,
-                    entries_checked: *seq as u64,
-                    first_invalid_entry: Some(*id),
-                    expected_hash: Some(previous_hash),
-                    actual_hash: Some(prev_hash.clone()),
-                });
-            }
-
-            // Recalculate hash
-            let hash_input = format!(
-                "{}{}{}{}{}{}{}",
-                id,
-                seq,
-                action,
-                req_user_id.map(|u| u.to_string()).unwrap_or_default(),
-                before_state.as_ref().map(|v| v.to_string()).unwrap_or_default(),
-                after_state.as_ref().map(|v| v.to_string()).unwrap_or_default(),
-                prev_hash
-            );
-
-            let calculated_hash = sha256_hex(&hash_input);
-
-            if &calculated_hash != current_hash {
-                return Ok(ChainVerificationResult {
-                    is_valid: false,
-                    entries_checked: *seq as u64,
-                    first_invalid_entry: Some(*id),
-                    expected_hash: Some(calculated_hash),
-                    actual_hash: Some(current_hash.clone()),
-                });
-            }
-
-            previous_hash = current_hash.clone();
-        }
-
-        Ok(ChainVerificationResult {
-            is_valid: true,
-            entries_checked: entries.len() as u64,
-            first_invalid_entry: None,
-            expected_hash: None,
-            actual_hash: None,
-        })
+        Ok(rows)
     }
 
     /// Get recent audit logs
     pub async fn get_recent(&self, limit: i64) -> Result<Vec<AuditLogEntry>, AuditLogError> {
-        let entries: Vec<(
-            Uuid, i64, Option<Uuid>, Option<Uuid>, Option<Uuid>,
-            String, Option<String>, Option<Uuid>,
-            Option<serde_json::Value>, Option<serde_json::Value>, Option<Vec<String>>,
-            Option<String>, String, String, DateTime<Utc>
-        )> = sqlx::query_as(
+        let entries: Vec<AuditLogRow> = sqlx::query_as!(
+            AuditLogRow,
             r#"
             SELECT id, sequence_number, api_key_id, request_user_id, correlation_id,
                    action, resource_type, resource_id,
                    before_state, after_state, changed_fields,
-                   client_ip::text, previous_hash, current_hash, created_at
+                   client_ip::text AS "client_ip?", previous_hash, current_hash, created_at
             FROM audit_logs
             ORDER BY sequence_number DESC
             LIMIT $1
             "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries.into_iter().map(AuditLogEntry::from).collect())
+    }
+
+    /// Query audit logs with filters, newest first.
+    /// Keyset pagination: pass the last seen sequence_number as `before_sequence`.
+    pub async fn query(
+        &self,
+        filter: &AuditLogFilter,
+        before_sequence: Option<i64>,
+        limit: i64,
+    ) -> Result<Vec<AuditLogEntry>, AuditLogError> {
+        let entries: Vec<AuditLogRow> = sqlx::query_as!(
+            AuditLogRow,
+            r#"
+            SELECT id, sequence_number, api_key_id, request_user_id, correlation_id,
+                   action, resource_type, resource_id,
+                   before_state, after_state, changed_fields,
+                   client_ip::text AS "client_ip?", previous_hash, current_hash, created_at
+            FROM audit_logs
+            WHERE ($1::varchar IS NULL OR action = $1)
+              AND ($2::varchar IS NULL OR resource_type = $2)
+              AND ($3::uuid IS NULL OR resource_id = $3)
+              AND ($4::uuid IS NULL OR correlation_id = $4)
+              AND ($5::timestamptz IS NULL OR created_at >= $5)
+              AND ($6::timestamptz IS NULL OR created_at < $6)
+              AND ($7::bigint IS NULL OR sequence_number < $7)
+            ORDER BY sequence_number DESC
+            LIMIT $8
+            "#,
+            filter.action,
+            filter.resource_type,
+            filter.resource_id,
+            filter.correlation_id,
+            filter.from_date,
+            filter.to_date,
+            before_sequence,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries.into_iter().map(AuditLogEntry::from).collect())
+    }
+
+    /// Entries after `after_sequence`, oldest first
+    pub async fn entries_after(&self, after_sequence: i64, limit: i64) -> Result<Vec<AuditLogEntry>, AuditLogError> {
+        let entries: Vec<AuditLogRow> = sqlx::query_as!(
+            AuditLogRow,
+            r#"
+            SELECT id, sequence_number, api_key_id, request_user_id, correlation_id,
+                   action, resource_type, resource_id,
+                   before_state, after_state, changed_fields,
+                   client_ip::text AS "client_ip?", previous_hash, current_hash, created_at
+            FROM audit_logs
+            WHERE sequence_number > $1
+            ORDER BY sequence_number ASC
+            LIMIT $2
+            "#,
+            after_sequence,
+            limit
         )
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(entries.into_iter().map(|(
-            id, sequence_number, api_key_id, request_user_id, correlation_id,
-            action, resource_type, resource_id,
-            before_state, after_state, changed_fields,
-            client_ip, previous_hash, current_hash, created_at
-        )| {
-            AuditLogEntry {
-                id,
-                sequence_number,
-                api_key_id,
-                request_user_id,
-                correlation_id,
-                action,
-                resource_type,
-                resource_id,
-                before_state,
-                after_state,
-                changed_fields,
-                client_ip: client_ip.and_then(|s| s.parse().ok()),
-                previous_hash,
-                current_hash,
-                created_at,
-            }
-        }).collect())
+        Ok(entries.into_iter().map(AuditLogEntry::from).collect())
     }
 
     /// Get audit logs for a specific user
     pub async fn get_by_user(&self, user_id: Uuid, limit: i64) -> Result<Vec<AuditLogEntry>, AuditLogError> {
-        let entries: Vec<(
-            Uuid, i64, Option<Uuid>, Option<Uuid>, Option<Uuid>,
-            String, Option<String>, Option<Uuid>,
-            Option<serde_json::Value>, Option<serde_json::Value>, Option<Vec<String>>,
-            Option<String>, String, String, DateTime<Utc>
-        )> = sqlx::query_as(
+        let entries: Vec<AuditLogRow> = sqlx::query_as!(
+            AuditLogRow,
             r#"
             SELECT id, sequence_number, api_key_id, request_user_id, correlation_id,
                    action, resource_type, resource_id,
                    before_state, after_state, changed_fields,
-                   client_ip::text, previous_hash, current_hash, created_at
+                   client_ip::text AS "client_ip?", previous_hash, current_hash, created_at
             FROM audit_logs
             WHERE request_user_id = $1
             ORDER BY sequence_number DESC
             LIMIT $2
             "#,
+            user_id,
+            limit
         )
-        .bind(user_id)
-        .bind(limit)
         .fetch_all(&self.pool)
         .await?;
 
-        Ok(entries.into_iter().map(|(
-            id, sequence_number, api_key_id, request_user_id, correlation_id,
-            action, resource_type, resource_id,
-            before_state, after_state, changed_fields,
-            client_ip, previous_hash, current_hash, created_at
-        )| {
-            AuditLogEntry {
-                id,
-                sequence_number,
-                api_key_id,
-                request_user_id,
-                correlation_id,
-                action,
-                resource_type,
-                resource_id,
-                before_state,
-                after_state,
-                changed_fields,
-                client_ip: client_ip.and_then(|s| s.parse().ok()),
-                previous_hash,
-                current_hash,
-                created_at,
-            }
-        }).collect())
+        Ok(entries.into_iter().map(AuditLogEntry::from).collect())
     }
 }
 
 /// Result of hash chain verification
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct ChainVerificationResult {
     pub is_valid: bool,
     pub entries_checked: u64,
@@ -394,6 +511,123 @@ pub struct ChainVerificationResult {
     pub actual_hash: Option<String>,
 }
 
+/// previous_hash of the first audit log entry
+const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Verified entry of the hash chain, where incremental verification resumes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ChainPosition {
+    pub sequence_number: i64,
+    pub hash: String,
+}
+
+impl ChainPosition {
+    /// Position before the first entry
+    pub fn genesis() -> Self {
+        Self {
+            sequence_number: 0,
+            hash: GENESIS_HASH.to_string(),
+        }
+    }
+}
+
+/// Hash inputs of one entry (JSONB states as text)
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct AuditChainRow {
+    id: Uuid,
+    sequence_number: i64,
+    action: String,
+    previous_hash: String,
+    current_hash: String,
+    request_user_id: Option<Uuid>,
+    resource_type: Option<String>,
+    resource_id: Option<Uuid>,
+    before_state: Option<String>,
+    after_state: Option<String>,
+}
+
+/// Check linkage and hashes of consecutive entries starting after
+/// `start_hash`; returns the last entry verified before any mismatch
+fn verify_chain_rows(rows: &[AuditChainRow], start_hash: &str) -> (ChainVerificationResult, Option<ChainPosition>) {
+    let mut previous_hash = start_hash.to_string();
+    let mut head = None;
+
+    for row in rows {
+        let AuditChainRow {
+            id,
+            sequence_number: seq,
+            action,
+            previous_hash: prev_hash,
+            current_hash,
+            request_user_id: req_user_id,
+            resource_type,
+            resource_id,
+            before_state,
+            after_state,
+        } = row;
+
+        // Verify chain linkage
+        if prev_hash != &previous_hash {
+            return (
+                ChainVerificationResult {
+                    is_valid: false,
+                    entries_checked: *seq as u64,
+                    first_invalid_entry: Some(*id),
+                    expected_hash: Some(previous_hash),
+                    actual_hash: Some(prev_hash.clone()),
+                },
+                head,
+            );
+        }
+
+        // Recalculate hash (same field order as the hash_audit_log trigger)
+        let hash_input = format!(
+            "{}{}{}{}{}{}{}{}{}",
+            id,
+            seq,
+            action,
+            req_user_id.map(|u| u.to_string()).unwrap_or_default(),
+            resource_type.as_deref().unwrap_or_default(),
+            resource_id.map(|r| r.to_string()).unwrap_or_default(),
+            before_state.as_deref().unwrap_or_default(),
+            after_state.as_deref().unwrap_or_default(),
+            prev_hash
+        );
+
+        let calculated_hash = sha256_hex(&hash_input);
+
+        if &calculated_hash != current_hash {
+            return (
+                ChainVerificationResult {
+                    is_valid: false,
+                    entries_checked: *seq as u64,
+                    first_invalid_entry: Some(*id),
+                    expected_hash: Some(calculated_hash),
+                    actual_hash: Some(current_hash.clone()),
+                },
+                head,
+            );
+        }
+
+        previous_hash = current_hash.clone();
+        head = Some(ChainPosition {
+            sequence_number: *seq,
+            hash: current_hash.clone(),
+        });
+    }
+
+    (
+        ChainVerificationResult {
+            is_valid: true,
+            entries_checked: rows.len() as u64,
+            first_invalid_entry: None,
+            expected_hash: None,
+            actual_hash: None,
+        },
+        head,
+    )
+}
+
 /// Calculate SHA-256 hash and return as hex string
 fn sha256_hex(input: &str) -> String {
     use sha2::{Digest, Sha256};
@@ -425,6 +659,8 @@ mod tests {
         assert_eq!(AuditAction::UserCreated.as_str(), "user.created");
         assert_eq!(AuditAction::TransferExecuted.as_str(), "transfer.executed");
         assert_eq!(AuditAction::PermissionDenied.as_str(), "auth.permission_denied");
+        assert_eq!(AuditAction::HoldCaptured.as_str(), "hold.captured");
+        assert_eq!(AuditAction::TenantCreated.as_str(), "tenant.created");
     }
 
     #[test]
@@ -445,6 +681,49 @@ mod tests {
         assert_eq!(hash.len(), 64); // SHA-256 produces 64 hex characters
     }
 
+    /// Chain entry hashed like the hash_audit_log trigger
+    fn chain_row(seq: i64, previous_hash: &str) -> AuditChainRow {
+        let id = Uuid::new_v4();
+        let hash = sha256_hex(&format!("{}{}user.created{}", id, seq, previous_hash));
+        AuditChainRow {
+            id,
+            sequence_number: seq,
+            action: "user.created".to_string(),
+            previous_hash: previous_hash.to_string(),
+            current_hash: hash,
+            request_user_id: None,
+            resource_type: None,
+            resource_id: None,
+            before_state: None,
+            after_state: None,
+        }
+    }
+
+    #[test]
+    fn test_verify_chain_rows() {
+        let first = chain_row(1, GENESIS_HASH);
+        let second = chain_row(2, &first.current_hash);
+        let rows = vec![first, second.clone()];
+
+        let (result, head) = verify_chain_rows(&rows, GENESIS_HASH);
+        assert!(result.is_valid);
+        assert_eq!(result.entries_checked, 2);
+        assert_eq!(head, Some(ChainPosition { sequence_number: 2, hash: second.current_hash.clone() }));
+
+        // Resuming from the first entry only checks the rest
+        let (result, head) = verify_chain_rows(&rows[1..], &rows[0].current_hash);
+        assert!(result.is_valid);
+        assert_eq!(head.unwrap().sequence_number, 2);
+
+        // A tampered entry stops verification at the entry before it
+        let mut tampered = rows.clone();
+        tampered[1].action = "user.deactivated".to_string();
+        let (result, head) = verify_chain_rows(&tampered, GENESIS_HASH);
+        assert!(!result.is_valid);
+        assert_eq!(result.first_invalid_entry, Some(tampered[1].id));
+        assert_eq!(head.unwrap().sequence_number, 1);
+    }
+
     #[test]
     fn test_chain_verification_result() {
         let result = ChainVerificationResult {
@@ -0,0 +1,251 @@
+//! Correlation Trace
+//!
+//! Everything recorded under one correlation ID (the X-Correlation-ID of a
+//! request) merged into a single timeline: events (matched on
+//! context.correlation_id), audit log entries, and the idempotency keys that
+//! produced those events. Used by support to reconstruct what a request did.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{AuditLogEntry, AuditLogError, AuditLogFilter, AuditLogService};
+use crate::event_store::{EventStore, EventStoreError, StoredEvent};
+use crate::idempotency::{IdempotencyError, IdempotencyKey, IdempotencyRepository, IdempotencyStatus};
+
+/// Timeline of one correlation ID
+#[derive(Debug, Clone, Serialize)]
+pub struct Trace {
+    pub correlation_id: Uuid,
+    pub entries: Vec<TraceEntry>,
+}
+
+/// One timeline entry
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceEntry {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub record: TraceRecord,
+}
+
+/// What was recorded
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "data", rename_all = "snake_case")]
+pub enum TraceRecord {
+    IdempotencyKey(TracedIdempotencyKey),
+    Event(TracedEvent),
+    AuditLog(AuditLogEntry),
+}
+
+/// Idempotency key claimed by the request
+#[derive(Debug, Clone, Serialize)]
+pub struct TracedIdempotencyKey {
+    pub key: Uuid,
+    pub status: IdempotencyStatus,
+    pub event_id: Option<Uuid>,
+    pub response_status: Option<i32>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Event appended by the request
+#[derive(Debug, Clone, Serialize)]
+pub struct TracedEvent {
+    pub event_id: Uuid,
+    pub global_sequence: i64,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub version: i64,
+    pub event_type: String,
+    pub event_data: serde_json::Value,
+    pub idempotency_key: Option<Uuid>,
+}
+
+impl From<IdempotencyKey> for TraceEntry {
+    fn from(key: IdempotencyKey) -> Self {
+        Self {
+            at: key.created_at,
+            record: TraceRecord::IdempotencyKey(TracedIdempotencyKey {
+                key: key.key,
+                status: key.status,
+                event_id: key.event_id,
+                response_status: key.response_status,
+                expires_at: key.expires_at,
+            }),
+        }
+    }
+}
+
+impl From<StoredEvent> for TraceEntry {
+    fn from(event: StoredEvent) -> Self {
+        Self {
+            at: event.created_at,
+            record: TraceRecord::Event(TracedEvent {
+                event_id: event.id,
+                global_sequence: event.global_sequence,
+                aggregate_type: event.aggregate_type,
+                aggregate_id: event.aggregate_id,
+                version: event.version,
+                event_type: event.event_type,
+                event_data: event.event_data,
+                idempotency_key: event.idempotency_key,
+            }),
+        }
+    }
+}
+
+impl From<AuditLogEntry> for TraceEntry {
+    fn from(entry: AuditLogEntry) -> Self {
+        Self {
+            at: entry.created_at,
+            record: TraceRecord::AuditLog(entry),
+        }
+    }
+}
+
+impl TraceEntry {
+    /// Chronological order; on equal timestamps the key is claimed before
+    /// events are appended, and audit entries are written last
+    fn sort_key(&self) -> (DateTime<Utc>, u8, i64) {
+        match &self.record {
+            TraceRecord::IdempotencyKey(_) => (self.at, 0, 0),
+            TraceRecord::Event(event) => (self.at, 1, event.global_sequence),
+            TraceRecord::AuditLog(entry) => (self.at, 2, entry.sequence_number),
+        }
+    }
+}
+
+/// Merge records into one chronological timeline
+fn merge_entries(
+    keys: Vec<IdempotencyKey>,
+    events: Vec<StoredEvent>,
+    audit_logs: Vec<AuditLogEntry>,
+) -> Vec<TraceEntry> {
+    let mut entries: Vec<TraceEntry> = keys
+        .into_iter()
+        .map(TraceEntry::from)
+        .chain(events.into_iter().map(TraceEntry::from))
+        .chain(audit_logs.into_iter().map(TraceEntry::from))
+        .collect();
+    entries.sort_by_key(TraceEntry::sort_key);
+    entries
+}
+
+/// Load the trace of a correlation ID. `limit` caps events and audit
+/// entries separately.
+pub async fn load_trace(
+    event_store: &EventStore,
+    audit: &AuditLogService,
+    idempotency: &IdempotencyRepository,
+    correlation_id: Uuid,
+    limit: i64,
+) -> Result<Trace, TraceError> {
+    let events = event_store.get_events_by_correlation(correlation_id, limit).await?;
+
+    let filter = AuditLogFilter {
+        correlation_id: Some(correlation_id),
+        ..Default::default()
+    };
+    let audit_logs = audit.query(&filter, None, limit).await?;
+
+    let event_ids: Vec<Uuid> = events.iter().map(|e| e.id).collect();
+    let idempotency_keys = if event_ids.is_empty() {
+        Vec::new()
+    } else {
+        idempotency.get_linked(&event_ids).await?
+    };
+
+    Ok(Trace {
+        correlation_id,
+        entries: merge_entries(idempotency_keys, events, audit_logs),
+    })
+}
+
+/// Trace loading errors
+#[derive(Debug, thiserror::Error)]
+pub enum TraceError {
+    #[error("Event store error: {0}")]
+    EventStore(#[from] EventStoreError),
+
+    #[error("Audit log error: {0}")]
+    Audit(#[from] AuditLogError),
+
+    #[error("Idempotency error: {0}")]
+    Idempotency(#[from] IdempotencyError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_merge_entries_is_chronological() {
+        let at = Utc::now();
+        let event_id = Uuid::new_v4();
+        let key = Uuid::new_v4();
+
+        let idempotency_key = IdempotencyKey {
+            key,
+            api_key_id: None,
+            tenant_id: None,
+            request_hash: "hash".to_string(),
+            event_id: Some(event_id),
+            response_status: Some(200),
+            response_body: None,
+            status: IdempotencyStatus::Completed,
+            processing_started_at: Some(at),
+            created_at: at,
+            expires_at: at + Duration::hours(24),
+        };
+        let event = |id: Uuid, global_sequence: i64| StoredEvent {
+            id,
+            global_sequence,
+            aggregate_type: "Account".to_string(),
+            aggregate_id: Uuid::new_v4(),
+            version: 1,
+            event_type: "MoneyDebited".to_string(),
+            event_data: serde_json::json!({}),
+            context: serde_json::json!({}),
+            idempotency_key: Some(key),
+            created_at: at,
+        };
+        let audit_log = AuditLogEntry {
+            id: Uuid::new_v4(),
+            sequence_number: 7,
+            api_key_id: None,
+            request_user_id: None,
+            correlation_id: None,
+            action: "transfer.executed".to_string(),
+            resource_type: Some("Transfer".to_string()),
+            resource_id: None,
+            before_state: None,
+            after_state: None,
+            changed_fields: None,
+            client_ip: None,
+            previous_hash: String::new(),
+            current_hash: String::new(),
+            created_at: at + Duration::milliseconds(5),
+        };
+
+        let entries = merge_entries(
+            vec![idempotency_key],
+            vec![event(Uuid::new_v4(), 11), event(event_id, 10)],
+            vec![audit_log],
+        );
+
+        let kinds: Vec<&str> = entries
+            .iter()
+            .map(|e| match &e.record {
+                TraceRecord::IdempotencyKey(_) => "idempotency_key",
+                TraceRecord::Event(_) => "event",
+                TraceRecord::AuditLog(_) => "audit_log",
+            })
+            .collect();
+        assert_eq!(kinds, ["idempotency_key", "event", "event", "audit_log"]);
+        assert!(matches!(&entries[1].record, TraceRecord::Event(e) if e.event_id == event_id));
+
+        let json = serde_json::to_value(&entries[0]).unwrap();
+        assert_eq!(json["kind"], "idempotency_key");
+        assert_eq!(json["data"]["key"], key.to_string());
+    }
+}
@@ -0,0 +1,89 @@
+//! Load Simulator
+//!
+//! Run against a running instance:
+//!   cargo run --features simulator --bin simulate -- --url http://localhost:3000 --api-key KEY
+//! or in process against the handlers (uses DATABASE_URL):
+//!   cargo run --features simulator --bin simulate -- --users 50 --operations 200
+
+use clap::Parser;
+use rust_decimal::Decimal;
+use sqlx::postgres::PgPoolOptions;
+
+use finance_atp::simulator::{self, SimulationConfig, Target};
+use finance_atp::{db, AppState, Config};
+
+#[derive(Debug, Parser)]
+#[command(name = "simulate", about = "Drive concurrent simulated users against the ledger")]
+struct Args {
+    /// Base URL of a running instance (handlers are called in process if omitted)
+    #[arg(long, requires = "api_key")]
+    url: Option<String>,
+    /// API key for --url
+    #[arg(long)]
+    api_key: Option<String>,
+    /// Concurrent simulated users
+    #[arg(long, default_value_t = 10)]
+    users: usize,
+    /// Operations per user
+    #[arg(long, default_value_t = 100)]
+    operations: usize,
+    /// Share of operations that are mints
+    #[arg(long, default_value_t = 0.05)]
+    mint_ratio: f64,
+    /// Balance minted to every user before the run
+    #[arg(long, default_value = "1000")]
+    initial_balance: Decimal,
+    /// Upper bound of a random operation amount
+    #[arg(long, default_value = "50")]
+    max_amount: Decimal,
+    /// RNG seed for reproducible runs
+    #[arg(long)]
+    seed: Option<u64>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenvy::dotenv().ok();
+    let args = Args::parse();
+
+    let config = SimulationConfig {
+        users: args.users,
+        operations_per_user: args.operations,
+        mint_ratio: args.mint_ratio,
+        initial_balance: args.initial_balance,
+        max_amount: args.max_amount,
+        seed: args.seed,
+    };
+
+    let report = match (args.url, args.api_key) {
+        (Some(url), Some(api_key)) => simulator::run(&Target::http(url, api_key), &config).await?,
+        _ => {
+            let app_config = Config::from_env()?;
+            let pool = PgPoolOptions::new()
+                .max_connections(app_config.database_max_connections)
+                .connect(&app_config.database_url)
+                .await?;
+            let system_accounts = db::resolve_system_accounts(&pool, app_config.system_users)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("System accounts missing; run `finance_atp seed-system-accounts`"))?;
+            let target = Target::Handlers(AppState::new(pool.clone(), app_config, system_accounts).shared());
+            let report = simulator::run(&target, &config).await;
+            pool.close().await;
+            report?
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    println!(
+        "conflict rate: {:.4}, retry rate: {}",
+        report.conflict_rate(),
+        report
+            .retry_rate()
+            .map_or_else(|| "n/a".to_string(), |rate| format!("{:.4}", rate))
+    );
+
+    if !report.consistency.is_consistent() {
+        anyhow::bail!("ledger is inconsistent after the simulation");
+    }
+    Ok(())
+}
@@ -3,6 +3,21 @@
 //! Loads configuration from environment variables.
 
 use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::alerts::AlertRules;
+use crate::api::rate_limit::RateLimitBackend;
+use crate::api::cors::CorsConfig;
+use crate::api::timeout::LatencyBudgets;
+use crate::audit::AuditAnchor;
+use crate::domain::TransferCategories;
+use crate::event_store::{IsolationLevel, PoisonEventPolicy};
+use crate::handlers::BurnConsentPolicy;
 
 /// Application configuration
 #[derive(Debug, Clone)]
@@ -24,6 +39,154 @@ pub struct Config {
     
     /// Rate limit: requests per minute per API key
     pub rate_limit_per_minute: i32,
+
+    /// Rate limit: extra requests per window allowed above the per-minute limit
+    pub rate_limit_burst: i32,
+
+    /// Rate limit per request user within an API key (None = not limited per user)
+    pub user_rate_limit_per_minute: Option<i32>,
+
+    /// Extra requests per window a request user may burst to
+    pub user_rate_limit_burst: i32,
+
+    /// Where rate limit buckets are kept
+    pub rate_limit_backend: RateLimitBackend,
+
+    /// Redis connection URL (required by the redis rate limit backend)
+    pub redis_url: Option<String>,
+
+    /// Transaction isolation level for event appends
+    pub event_store_isolation: IsolationLevel,
+
+    /// Handling of events that fail to deserialize during replay
+    pub event_poison_policy: PoisonEventPolicy,
+
+    /// Seconds the previous secret of a rotated API key stays valid
+    pub api_key_rotation_grace_secs: i64,
+
+    /// Accept X-API-Key authentication (disable to require bearer tokens)
+    pub api_key_auth_enabled: bool,
+
+    /// Shared secret for HS256/384/512 bearer tokens
+    pub jwt_secret: Option<String>,
+
+    /// JWKS endpoint for asymmetrically signed bearer tokens
+    pub jwt_jwks_url: Option<String>,
+
+    /// Required `iss` claim of bearer tokens
+    pub jwt_issuer: Option<String>,
+
+    /// Required `aud` claim of bearer tokens
+    pub jwt_audience: Option<String>,
+
+    /// Log output format
+    pub log_format: LogFormat,
+
+    /// Apply pending embedded migrations on startup
+    pub auto_migrate: bool,
+
+    /// HMAC secret for transfer receipt signatures (receipts disabled if unset)
+    pub receipt_signing_secret: Option<String>,
+
+    /// Days after deactivation before users are anonymized (job disabled if unset)
+    pub user_retention_days: Option<u32>,
+
+    /// Transfers above this amount need a second API key to approve them
+    /// (approval disabled if unset)
+    pub transfer_approval_threshold: Option<Decimal>,
+
+    /// How long a transfer waits for approval before it expires
+    pub pending_transfer_ttl: Duration,
+
+    /// Most ATP that may be minted in total (SYSTEM_MINT liability; uncapped if unset)
+    pub mint_supply_cap: Option<Decimal>,
+
+    /// Whether burns need the wallet owner as request user (or a forced burn)
+    pub burn_consent_policy: BurnConsentPolicy,
+
+    /// SYSTEM_MINT and SYSTEM_BURN users (defaults match the database seed)
+    pub system_users: SystemUserIds,
+
+    /// Account that receives the balance of users deactivated with a sweep
+    /// (sweeping disabled if unset)
+    pub recovery_account_id: Option<Uuid>,
+
+    /// Categories transfers may be tagged with
+    pub transfer_categories: TransferCategories,
+
+    /// Soft caps that raise alerts on unusual activity (ALERT_*)
+    pub alert_rules: AlertRules,
+
+    /// Where verified audit chain heads are published (anchoring disabled if unset)
+    pub audit_anchor: Option<AuditAnchor>,
+
+    /// Days before audit log entries are archived and deleted (job disabled if unset)
+    pub audit_archive_after_days: Option<u32>,
+
+    /// Directory audit log archives are written to
+    pub audit_archive_dir: PathBuf,
+
+    /// Months emptied audit_logs partitions are kept before being dropped
+    /// (kept forever if unset)
+    pub audit_log_partition_retention_months: Option<u32>,
+
+    /// Balances kept in the in-process balance cache (cache disabled if unset)
+    pub balance_cache_capacity: Option<u64>,
+
+    /// Longest time a cached balance is served without invalidation
+    pub balance_cache_ttl_secs: u64,
+
+    /// Largest request body accepted by the API
+    pub max_body_bytes: usize,
+
+    /// Cross-origin browser access (disabled if CORS_ALLOWED_ORIGINS is unset)
+    pub cors: Option<CorsConfig>,
+
+    /// Time budget per route group before a request fails with 503
+    pub latency_budgets: LatencyBudgets,
+}
+
+/// IDs of the users owning the SYSTEM_MINT and SYSTEM_BURN accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemUserIds {
+    pub mint: Uuid,
+    pub burn: Uuid,
+}
+
+impl SystemUserIds {
+    /// The users seeded by migration 004
+    pub const SEEDED: SystemUserIds = SystemUserIds {
+        mint: Uuid::from_u128(1),
+        burn: Uuid::from_u128(2),
+    };
+}
+
+impl Default for SystemUserIds {
+    fn default() -> Self {
+        Self::SEEDED
+    }
+}
+
+/// Log output format
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line, including the fields of the current spans
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" | "pretty" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(ConfigError::InvalidValue("LOG_FORMAT")),
+        }
+    }
 }
 
 impl Config {
@@ -51,6 +214,231 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::InvalidValue("RATE_LIMIT_PER_MINUTE"))?;
 
+        let rate_limit_burst = env::var("RATE_LIMIT_BURST")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<i32>()
+            .ok()
+            .filter(|burst| *burst >= 0)
+            .ok_or(ConfigError::InvalidValue("RATE_LIMIT_BURST"))?;
+
+        let user_rate_limit_per_minute = env::var("USER_RATE_LIMIT_PER_MINUTE")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<i32>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or(ConfigError::InvalidValue("USER_RATE_LIMIT_PER_MINUTE"))
+            })
+            .transpose()?;
+
+        let user_rate_limit_burst = env::var("USER_RATE_LIMIT_BURST")
+            .unwrap_or_else(|_| "0".to_string())
+            .parse::<i32>()
+            .ok()
+            .filter(|burst| *burst >= 0)
+            .ok_or(ConfigError::InvalidValue("USER_RATE_LIMIT_BURST"))?;
+
+        let rate_limit_backend = env::var("RATE_LIMIT_BACKEND")
+            .unwrap_or_else(|_| "postgres".to_string())
+            .parse()?;
+
+        let redis_url = env::var("REDIS_URL").ok().filter(|s| !s.is_empty());
+        #[cfg(feature = "redis")]
+        if rate_limit_backend == RateLimitBackend::Redis && redis_url.is_none() {
+            return Err(ConfigError::MissingEnv("REDIS_URL"));
+        }
+
+        let event_store_isolation = env::var("EVENT_STORE_ISOLATION")
+            .unwrap_or_else(|_| "read_committed".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("EVENT_STORE_ISOLATION"))?;
+
+        let event_poison_policy = env::var("EVENT_POISON_POLICY")
+            .unwrap_or_else(|_| "fail".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("EVENT_POISON_POLICY"))?;
+
+        let api_key_rotation_grace_secs = env::var("API_KEY_ROTATION_GRACE_SECS")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("API_KEY_ROTATION_GRACE_SECS"))?;
+
+        let api_key_auth_enabled = env::var("API_KEY_AUTH_ENABLED")
+            .unwrap_or_else(|_| "true".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("API_KEY_AUTH_ENABLED"))?;
+
+        let jwt_secret = env::var("JWT_SECRET").ok().filter(|s| !s.is_empty());
+        let jwt_jwks_url = env::var("JWT_JWKS_URL").ok().filter(|s| !s.is_empty());
+        let jwt_issuer = env::var("JWT_ISSUER").ok().filter(|s| !s.is_empty());
+        let jwt_audience = env::var("JWT_AUDIENCE").ok().filter(|s| !s.is_empty());
+
+        let log_format = env::var("LOG_FORMAT")
+            .unwrap_or_else(|_| "text".to_string())
+            .parse()?;
+
+        let auto_migrate = env::var("AUTO_MIGRATE")
+            .unwrap_or_else(|_| "false".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("AUTO_MIGRATE"))?;
+
+        let receipt_signing_secret = env::var("RECEIPT_SIGNING_SECRET").ok().filter(|s| !s.is_empty());
+
+        let user_retention_days = env::var("USER_RETENTION_DAYS")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|_| ConfigError::InvalidValue("USER_RETENTION_DAYS"))?;
+
+        let transfer_approval_threshold = env::var("TRANSFER_APPROVAL_THRESHOLD")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<Decimal>().ok().filter(|threshold| *threshold > Decimal::ZERO))
+            .map(|threshold| threshold.ok_or(ConfigError::InvalidValue("TRANSFER_APPROVAL_THRESHOLD")))
+            .transpose()?;
+
+        let pending_transfer_ttl = env::var("PENDING_TRANSFER_TTL_HOURS")
+            .unwrap_or_else(|_| "72".to_string())
+            .parse::<u64>()
+            .ok()
+            .filter(|hours| *hours > 0)
+            .map(|hours| Duration::from_secs(hours * 3600))
+            .ok_or(ConfigError::InvalidValue("PENDING_TRANSFER_TTL_HOURS"))?;
+
+        let mint_supply_cap = env::var("MINT_SUPPLY_CAP")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<Decimal>().ok().filter(|cap| *cap > Decimal::ZERO))
+            .map(|cap| cap.ok_or(ConfigError::InvalidValue("MINT_SUPPLY_CAP")))
+            .transpose()?;
+
+        let burn_consent_policy = env::var("BURN_CONSENT_POLICY")
+            .unwrap_or_else(|_| "open".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue("BURN_CONSENT_POLICY"))?;
+
+        let system_users = SystemUserIds {
+            mint: env::var("SYSTEM_MINT_USER_ID")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<Uuid>())
+                .transpose()
+                .map_err(|_| ConfigError::InvalidValue("SYSTEM_MINT_USER_ID"))?
+                .unwrap_or(SystemUserIds::SEEDED.mint),
+            burn: env::var("SYSTEM_BURN_USER_ID")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<Uuid>())
+                .transpose()
+                .map_err(|_| ConfigError::InvalidValue("SYSTEM_BURN_USER_ID"))?
+                .unwrap_or(SystemUserIds::SEEDED.burn),
+        };
+        if system_users.mint == system_users.burn {
+            return Err(ConfigError::InvalidValue("SYSTEM_BURN_USER_ID"));
+        }
+
+        let recovery_account_id = env::var("RECOVERY_ACCOUNT_ID")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<Uuid>())
+            .transpose()
+            .map_err(|_| ConfigError::InvalidValue("RECOVERY_ACCOUNT_ID"))?;
+
+        let transfer_categories = env::var("TRANSFER_CATEGORIES")
+            .ok()
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|_| ConfigError::InvalidValue("TRANSFER_CATEGORIES"))?
+            .unwrap_or_default();
+
+        let alert_rules = AlertRules {
+            transfer_amount: env::var("ALERT_TRANSFER_AMOUNT")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<Decimal>().ok().filter(|amount| *amount > Decimal::ZERO))
+                .map(|amount| amount.ok_or(ConfigError::InvalidValue("ALERT_TRANSFER_AMOUNT")))
+                .transpose()?,
+            transfers_per_minute: env::var("ALERT_TRANSFERS_PER_MINUTE")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse::<i64>().ok().filter(|count| *count > 0))
+                .map(|count| count.ok_or(ConfigError::InvalidValue("ALERT_TRANSFERS_PER_MINUTE")))
+                .transpose()?,
+            mint_hours: env::var("ALERT_MINT_HOURS")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse())
+                .transpose()
+                .map_err(|_| ConfigError::InvalidValue("ALERT_MINT_HOURS"))?,
+        };
+
+        let audit_anchor = env::var("AUDIT_ANCHOR")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|_| ConfigError::InvalidValue("AUDIT_ANCHOR"))?;
+
+        let audit_archive_after_days = env::var("AUDIT_ARCHIVE_AFTER_DAYS")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u32>().ok().filter(|days| *days > 0))
+            .map(|days| days.ok_or(ConfigError::InvalidValue("AUDIT_ARCHIVE_AFTER_DAYS")))
+            .transpose()?;
+
+        let audit_archive_dir = env::var("AUDIT_ARCHIVE_DIR")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("audit-archives"));
+
+        let audit_log_partition_retention_months = env::var("AUDIT_LOG_PARTITION_RETENTION_MONTHS")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse())
+            .transpose()
+            .map_err(|_| ConfigError::InvalidValue("AUDIT_LOG_PARTITION_RETENTION_MONTHS"))?;
+
+        let balance_cache_capacity = env::var("BALANCE_CACHE_CAPACITY")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<u64>().ok().filter(|capacity| *capacity > 0))
+            .map(|capacity| capacity.ok_or(ConfigError::InvalidValue("BALANCE_CACHE_CAPACITY")))
+            .transpose()?;
+
+        let balance_cache_ttl_secs = env::var("BALANCE_CACHE_TTL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse::<u64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .ok_or(ConfigError::InvalidValue("BALANCE_CACHE_TTL_SECS"))?;
+
+        let max_body_bytes = env::var("MAX_BODY_BYTES")
+            .unwrap_or_else(|_| "1048576".to_string())
+            .parse::<usize>()
+            .ok()
+            .filter(|bytes| *bytes > 0)
+            .ok_or(ConfigError::InvalidValue("MAX_BODY_BYTES"))?;
+
+        let latency_budgets = LatencyBudgets {
+            read: timeout_from_env("REQUEST_TIMEOUT_READ_MS", "2000")?,
+            write: timeout_from_env("REQUEST_TIMEOUT_WRITE_MS", "10000")?,
+            maintenance: timeout_from_env("REQUEST_TIMEOUT_MAINTENANCE_MS", "300000")?,
+        };
+
+        let cors_max_age_secs = env::var("CORS_MAX_AGE_SECS")
+            .unwrap_or_else(|_| "600".to_string())
+            .parse::<u64>()
+            .map_err(|_| ConfigError::InvalidValue("CORS_MAX_AGE_SECS"))?;
+
+        let cors = env::var("CORS_ALLOWED_ORIGINS")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|origins| CorsConfig::parse(&origins, Duration::from_secs(cors_max_age_secs)))
+            .transpose()?;
+
         Ok(Self {
             database_url,
             database_max_connections,
@@ -58,6 +446,40 @@ impl Config {
             port,
             environment,
             rate_limit_per_minute,
+            rate_limit_burst,
+            user_rate_limit_per_minute,
+            user_rate_limit_burst,
+            rate_limit_backend,
+            redis_url,
+            event_store_isolation,
+            event_poison_policy,
+            api_key_rotation_grace_secs,
+            api_key_auth_enabled,
+            jwt_secret,
+            jwt_jwks_url,
+            jwt_issuer,
+            jwt_audience,
+            log_format,
+            auto_migrate,
+            receipt_signing_secret,
+            user_retention_days,
+            transfer_approval_threshold,
+            pending_transfer_ttl,
+            mint_supply_cap,
+            burn_consent_policy,
+            system_users,
+            recovery_account_id,
+            transfer_categories,
+            alert_rules,
+            audit_anchor,
+            audit_archive_after_days,
+            audit_archive_dir,
+            audit_log_partition_retention_months,
+            balance_cache_capacity,
+            balance_cache_ttl_secs,
+            max_body_bytes,
+            cors,
+            latency_budgets,
         })
     }
 
@@ -67,6 +489,17 @@ impl Config {
     }
 }
 
+/// Parse a positive request timeout in milliseconds
+fn timeout_from_env(name: &'static str, default: &str) -> Result<Duration, ConfigError> {
+    env::var(name)
+        .unwrap_or_else(|_| default.to_string())
+        .parse::<u64>()
+        .ok()
+        .filter(|ms| *ms > 0)
+        .map(Duration::from_millis)
+        .ok_or(ConfigError::InvalidValue(name))
+}
+
 /// Configuration error types
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
@@ -2,11 +2,37 @@
 //!
 //! Database connection and migration utilities.
 
+use sqlx::migrate::{Migrate, MigrateError, Migrator};
 use sqlx::PgPool;
+use uuid::Uuid;
 
-/// Run database migrations
-/// Note: We use raw SQL files in migrations/ directory
-/// This function can be used to verify database connectivity
+use crate::config::SystemUserIds;
+use crate::domain::AccountType;
+
+/// Migrations embedded from the migrations/ directory, tracked in the
+/// _sqlx_migrations table
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+/// Migration errors
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error(
+        "Database schema was created without migration tracking; \
+         run `finance_atp migrate --baseline <version>` to record the migrations already applied"
+    )]
+    NotBaselined,
+
+    #[error("Unknown migration version: {0}")]
+    UnknownVersion(i64),
+
+    #[error(transparent)]
+    Migrate(#[from] MigrateError),
+
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Verify database connectivity
 pub async fn verify_connection(pool: &PgPool) -> Result<(), sqlx::Error> {
     // Simple connectivity check
     sqlx::query("SELECT 1")
@@ -16,6 +42,93 @@ pub async fn verify_connection(pool: &PgPool) -> Result<(), sqlx::Error> {
     Ok(())
 }
 
+/// Apply pending embedded migrations. Returns the versions applied.
+pub async fn run_migrations(pool: &PgPool) -> Result<Vec<i64>, MigrationError> {
+    // Databases set up with psql have the schema but no tracking table;
+    // re-running 001 on them would fail halfway
+    if !table_exists(pool, "_sqlx_migrations").await? && table_exists(pool, "api_keys").await? {
+        return Err(MigrationError::NotBaselined);
+    }
+
+    let applied = applied_versions(pool).await?;
+    let pending: Vec<i64> = MIGRATOR
+        .iter()
+        .map(|m| m.version)
+        .filter(|v| !applied.contains(v))
+        .collect();
+
+    MIGRATOR.run(pool).await?;
+
+    for version in &pending {
+        tracing::info!(version, "Applied migration");
+    }
+
+    Ok(pending)
+}
+
+/// Record every embedded migration up to `version` as applied without
+/// running it (for databases migrated manually with psql). Returns the
+/// versions recorded.
+pub async fn baseline_migrations(pool: &PgPool, version: i64) -> Result<Vec<i64>, MigrationError> {
+    if !MIGRATOR.iter().any(|m| m.version == version) {
+        return Err(MigrationError::UnknownVersion(version));
+    }
+
+    let mut conn = pool.acquire().await?;
+    conn.ensure_migrations_table().await?;
+
+    let applied = applied_versions(pool).await?;
+    let mut recorded = Vec::new();
+
+    for migration in MIGRATOR.iter().filter(|m| m.version <= version) {
+        if applied.contains(&migration.version) {
+            continue;
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO _sqlx_migrations (version, description, success, checksum, execution_time)
+            VALUES ($1, $2, TRUE, $3, 0)
+            "#,
+        )
+        .bind(migration.version)
+        .bind(&*migration.description)
+        .bind(&*migration.checksum)
+        .execute(&mut *conn)
+        .await?;
+
+        recorded.push(migration.version);
+    }
+
+    tracing::info!(version, recorded = recorded.len(), "Baselined migrations");
+    Ok(recorded)
+}
+
+/// Versions recorded in _sqlx_migrations (empty before the first run)
+async fn applied_versions(pool: &PgPool) -> Result<Vec<i64>, sqlx::Error> {
+    if !table_exists(pool, "_sqlx_migrations").await? {
+        return Ok(Vec::new());
+    }
+
+    sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success ORDER BY version")
+        .fetch_all(pool)
+        .await
+}
+
+async fn table_exists(pool: &PgPool, table: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM information_schema.tables
+            WHERE table_schema = 'public' AND table_name = $1
+        )
+        "#,
+    )
+    .bind(table)
+    .fetch_one(pool)
+    .await
+}
+
 /// Check if required tables exist
 pub async fn check_schema(pool: &PgPool) -> Result<bool, sqlx::Error> {
     let required_tables = vec![
@@ -51,31 +164,44 @@ pub async fn check_schema(pool: &PgPool) -> Result<bool, sqlx::Error> {
         }
     }
 
-    // Check for required system accounts
-    if !check_system_accounts(pool).await? {
-        return Ok(false);
-    }
-
     Ok(true)
 }
 
-/// System user IDs
-const SYSTEM_MINT_USER_ID: &str = "00000000-0000-0000-0000-000000000001";
-const SYSTEM_BURN_USER_ID: &str = "00000000-0000-0000-0000-000000000002";
+/// SYSTEM_MINT and SYSTEM_BURN users and their accounts, resolved once at
+/// startup and shared through AppState
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemAccounts {
+    pub mint_user_id: Uuid,
+    pub mint_account_id: Uuid,
+    pub burn_user_id: Uuid,
+    pub burn_account_id: Uuid,
+}
 
-/// Check if required system accounts exist
-async fn check_system_accounts(pool: &PgPool) -> Result<bool, sqlx::Error> {
-    let system_users = vec![
-        (SYSTEM_MINT_USER_ID, "SYSTEM_MINT"),
-        (SYSTEM_BURN_USER_ID, "SYSTEM_BURN"),
-    ];
+impl SystemAccounts {
+    /// IDs of the system users
+    pub fn users(&self) -> SystemUserIds {
+        SystemUserIds {
+            mint: self.mint_user_id,
+            burn: self.burn_user_id,
+        }
+    }
+}
+
+/// Look up the accounts of the configured system users. Returns None (and
+/// logs which one) if a user or its account does not exist.
+pub async fn resolve_system_accounts(
+    pool: &PgPool,
+    users: SystemUserIds,
+) -> Result<Option<SystemAccounts>, sqlx::Error> {
+    let mut account_ids = [Uuid::nil(); 2];
 
-    for (user_id_str, name) in system_users {
-        let user_id: uuid::Uuid = user_id_str.parse().expect("Invalid system user ID");
-        
+    for (account_id, (user_id, name)) in account_ids
+        .iter_mut()
+        .zip([(users.mint, "SYSTEM_MINT"), (users.burn, "SYSTEM_BURN")])
+    {
         // Check if user exists
         let user_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT 1 FROM users WHERE id = $1)"
+            "SELECT EXISTS (SELECT 1 FROM users WHERE id = $1 AND is_system)"
         )
         .bind(user_id)
         .fetch_one(pool)
@@ -86,26 +212,149 @@ async fn check_system_accounts(pool: &PgPool) -> Result<bool, sqlx::Error> {
                 "Required system user '{}' ({}) does not exist. Please run database seed.",
                 name, user_id
             );
-            return Ok(false);
+            return Ok(None);
         }
 
         // Check if account exists
-        let account_exists: bool = sqlx::query_scalar(
-            "SELECT EXISTS (SELECT 1 FROM accounts WHERE user_id = $1)"
+        let id: Option<Uuid> = sqlx::query_scalar(
+            "SELECT id FROM accounts WHERE user_id = $1 AND name IS NULL ORDER BY created_at LIMIT 1"
         )
         .bind(user_id)
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await?;
 
-        if !account_exists {
+        let Some(id) = id else {
             tracing::error!(
                 "Required system account for '{}' ({}) does not exist. Please run database seed.",
                 name, user_id
             );
-            return Ok(false);
-        }
+            return Ok(None);
+        };
+        *account_id = id;
     }
 
-    tracing::info!("System accounts verified: SYSTEM_MINT, SYSTEM_BURN");
-    Ok(true)
+    let [mint_account_id, burn_account_id] = account_ids;
+    tracing::info!(%mint_account_id, %burn_account_id, "System accounts resolved: SYSTEM_MINT, SYSTEM_BURN");
+
+    Ok(Some(SystemAccounts {
+        mint_user_id: users.mint,
+        mint_account_id,
+        burn_user_id: users.burn,
+        burn_account_id,
+    }))
+}
+
+/// System users and their accounts (same rows as migrations 004 and 005):
+/// (user_id, username, email, display_name, account_type)
+const SYSTEM_ACCOUNTS: [(Uuid, &str, &str, &str, AccountType); 4] = [
+    (SystemUserIds::SEEDED.mint, "SYSTEM_MINT", "mint@system.internal", "ATP Mint Source", AccountType::MintSource),
+    (SystemUserIds::SEEDED.burn, "SYSTEM_BURN", "burn@system.internal", "ATP Burn Sink", AccountType::MintSource),
+    (Uuid::from_u128(3), "SYSTEM_FEE", "fee@system.internal", "Fee Income", AccountType::FeeIncome),
+    (Uuid::from_u128(4), "SYSTEM_RESERVE", "reserve@system.internal", "System Reserve", AccountType::SystemReserve),
+];
+
+/// Create any missing system users, accounts and balance rows.
+/// Existing rows are left untouched. Returns the number of rows created.
+pub async fn seed_system_accounts(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    sync_account_types(pool).await?;
+
+    let mut tx = pool.begin().await?;
+    let mut created = 0;
+
+    for (user_id, username, email, display_name, account_type) in SYSTEM_ACCOUNTS {
+        created += sqlx::query(
+            r#"
+            INSERT INTO users (id, username, email, display_name, is_system, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, TRUE, NOW(), NOW())
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(username)
+        .bind(email)
+        .bind(display_name)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        created += sqlx::query(
+            r#"
+            INSERT INTO accounts (user_id, account_type)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id, account_type) WHERE name IS NULL DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(account_type.as_str())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        created += sqlx::query(
+            r#"
+            INSERT INTO account_balances (account_id, balance, last_event_id, last_event_version)
+            SELECT id, 0, '00000000-0000-0000-0000-000000000000', 0
+            FROM accounts
+            WHERE user_id = $1 AND account_type = $2 AND name IS NULL
+            ON CONFLICT (account_id) DO NOTHING
+            "#,
+        )
+        .bind(user_id)
+        .bind(account_type.as_str())
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    }
+
+    tx.commit().await?;
+
+    if created > 0 {
+        tracing::info!(created, "Seeded system accounts");
+    }
+
+    Ok(created)
+}
+
+/// Upsert every AccountType into the account_types registry and deactivate
+/// codes the application no longer knows. Returns the unknown codes.
+pub async fn sync_account_types(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for account_type in AccountType::ALL {
+        sqlx::query(
+            r#"
+            INSERT INTO account_types (code, name, is_debit_normal, is_system_only, is_active)
+            VALUES ($1, $2, $3, $4, TRUE)
+            ON CONFLICT (code) DO UPDATE
+            SET name = $2, is_debit_normal = $3, is_system_only = $4, is_active = TRUE
+            "#,
+        )
+        .bind(account_type.as_str())
+        .bind(account_type.display_name())
+        .bind(account_type.is_debit_normal())
+        .bind(account_type.is_system_only())
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let known: Vec<&str> = AccountType::ALL.iter().map(|t| t.as_str()).collect();
+    let unknown: Vec<String> = sqlx::query_scalar(
+        r#"
+        UPDATE account_types
+        SET is_active = FALSE
+        WHERE code <> ALL($1) AND is_active
+        RETURNING code
+        "#,
+    )
+    .bind(&known)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    for code in &unknown {
+        tracing::warn!(code = %code, "Deactivated account type not known to this build");
+    }
+
+    Ok(unknown)
 }
@@ -0,0 +1,120 @@
+//! Account Types
+//!
+//! The closed set of account types, mirrored into the `account_types`
+//! registry table on startup. Operation rules that depend on the type
+//! (which accounts may go negative, which are reserved for system users)
+//! live here rather than in string comparisons across handlers.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use super::error::DomainError;
+
+/// Type of an account
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountType {
+    /// Spendable balance of a regular user
+    #[default]
+    UserWallet,
+    /// Issuing side of mints and receiving side of burns (liability)
+    MintSource,
+    /// Collected fees
+    FeeIncome,
+    /// Operator-held reserve
+    SystemReserve,
+}
+
+impl AccountType {
+    /// Every account type, in registry order
+    pub const ALL: [AccountType; 4] = [
+        AccountType::UserWallet,
+        AccountType::MintSource,
+        AccountType::FeeIncome,
+        AccountType::SystemReserve,
+    ];
+
+    /// Code stored in accounts.account_type / account_types.code
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountType::UserWallet => "user_wallet",
+            AccountType::MintSource => "mint_source",
+            AccountType::FeeIncome => "fee_income",
+            AccountType::SystemReserve => "system_reserve",
+        }
+    }
+
+    /// Display name (account_types.name)
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            AccountType::UserWallet => "User Wallet",
+            AccountType::MintSource => "ATP Mint Source",
+            AccountType::FeeIncome => "Fee Income",
+            AccountType::SystemReserve => "System Reserve",
+        }
+    }
+
+    /// TRUE if a debit increases the balance (asset accounts)
+    pub fn is_debit_normal(&self) -> bool {
+        matches!(self, AccountType::UserWallet | AccountType::SystemReserve)
+    }
+
+    /// Only system users may own accounts of this type
+    pub fn is_system_only(&self) -> bool {
+        !matches!(self, AccountType::UserWallet)
+    }
+
+    /// Debits may take the balance below zero (the mint source carries the
+    /// negative of the circulating supply)
+    pub fn may_go_negative(&self) -> bool {
+        matches!(self, AccountType::MintSource)
+    }
+}
+
+impl fmt::Display for AccountType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AccountType {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AccountType::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| DomainError::UnknownAccountType(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_type_round_trip() {
+        for account_type in AccountType::ALL {
+            assert_eq!(account_type.as_str().parse::<AccountType>().unwrap(), account_type);
+            assert_eq!(
+                serde_json::to_value(account_type).unwrap(),
+                serde_json::json!(account_type.as_str())
+            );
+        }
+        assert!(matches!(
+            "savings".parse::<AccountType>(),
+            Err(DomainError::UnknownAccountType(_))
+        ));
+    }
+
+    #[test]
+    fn test_account_type_rules() {
+        assert!(AccountType::MintSource.may_go_negative());
+        assert!(!AccountType::UserWallet.may_go_negative());
+        assert!(!AccountType::SystemReserve.may_go_negative());
+        assert!(!AccountType::UserWallet.is_system_only());
+        assert!(AccountType::FeeIncome.is_system_only());
+    }
+}
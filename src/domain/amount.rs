@@ -10,6 +10,9 @@ use std::fmt;
 use std::ops::Add;
 use std::str::FromStr;
 
+use super::account_type::AccountType;
+use super::error::DomainError;
+
 /// Maximum allowed balance (1 trillion ATP)
 const MAX_AMOUNT: &str = "1000000000000";
 
@@ -51,6 +54,18 @@ pub enum AmountError {
     ParseError(String),
 }
 
+impl AmountError {
+    /// Machine-readable code reported for an invalid amount field
+    pub fn code(&self) -> &'static str {
+        match self {
+            AmountError::NotPositive(_) => "not_positive",
+            AmountError::TooManyDecimals(_) => "too_many_decimals",
+            AmountError::Overflow => "too_large",
+            AmountError::ParseError(_) => "invalid_format",
+        }
+    }
+}
+
 impl Amount {
     /// Create a new Amount with validation.
     /// 
@@ -179,6 +194,24 @@ impl Balance {
         Self(value)
     }
 
+    /// Balance of an account of the given type, enforcing the negative-balance
+    /// policy: only types where `may_go_negative()` accept values below zero.
+    /// Used when loading balances from projections, where a negative user
+    /// wallet means the projection is corrupt.
+    pub fn for_account_type(value: Decimal, account_type: AccountType) -> Result<Self, DomainError> {
+        if value < Decimal::ZERO {
+            if account_type.may_go_negative() {
+                return Ok(Self(value));
+            }
+            return Err(DomainError::InvariantViolation(format!(
+                "{} balance must not be negative (found {})",
+                account_type, value
+            )));
+        }
+
+        Self::new(value).map_err(|e| DomainError::InvariantViolation(e.to_string()))
+    }
+
     /// Get the underlying value
     pub fn value(&self) -> Decimal {
         self.0
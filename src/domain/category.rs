@@ -0,0 +1,99 @@
+//! Transfer Categories
+//!
+//! Optional classification of a transfer ("reward", "purchase", "refund", ...)
+//! for finance reporting. Categories are checked against the taxonomy set by
+//! TRANSFER_CATEGORIES, stored on the TransferInitiated and money events and
+//! copied to both ledger entries of the transfer.
+
+use std::str::FromStr;
+
+use crate::error::AppError;
+
+/// Taxonomy used when TRANSFER_CATEGORIES is not set
+pub const DEFAULT_TRANSFER_CATEGORIES: [&str; 3] = ["reward", "purchase", "refund"];
+
+/// Longest category name (ledger_entries.category is VARCHAR(50))
+const MAX_CATEGORY_LEN: usize = 50;
+
+/// Configured set of transfer categories
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferCategories(Vec<String>);
+
+impl TransferCategories {
+    /// Accepted categories, in configuration order
+    pub fn as_slice(&self) -> &[String] {
+        &self.0
+    }
+
+    /// Canonical (trimmed, lowercase) form of `category`; fails unless the
+    /// taxonomy contains it
+    pub fn resolve(&self, category: &str) -> Result<String, AppError> {
+        let canonical = category.trim().to_ascii_lowercase();
+        if self.0.contains(&canonical) {
+            Ok(canonical)
+        } else {
+            Err(AppError::InvalidRequest(format!(
+                "Unknown transfer category '{}' (expected one of: {})",
+                category.trim(),
+                self.0.join(", ")
+            )))
+        }
+    }
+}
+
+impl Default for TransferCategories {
+    fn default() -> Self {
+        Self(DEFAULT_TRANSFER_CATEGORIES.iter().map(|c| c.to_string()).collect())
+    }
+}
+
+/// Error for a TRANSFER_CATEGORIES entry that is not a valid category name
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid transfer category: {0:?}")]
+pub struct InvalidCategory(pub String);
+
+impl FromStr for TransferCategories {
+    type Err = InvalidCategory;
+
+    /// Parse a comma-separated list; names are lowercase letters, digits,
+    /// '_' and '-'
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut categories: Vec<String> = Vec::new();
+        for raw in s.split(',').map(str::trim).filter(|c| !c.is_empty()) {
+            let category = raw.to_ascii_lowercase();
+            let valid = category.len() <= MAX_CATEGORY_LEN
+                && category
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if !valid {
+                return Err(InvalidCategory(raw.to_string()));
+            }
+            if !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+        Ok(Self(categories))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_taxonomy() {
+        let categories: TransferCategories = " Reward, purchase,,refund,reward ".parse().unwrap();
+        assert_eq!(categories.as_slice(), ["reward", "purchase", "refund"]);
+        assert_eq!(categories, TransferCategories::default());
+
+        assert!("reward,gift card".parse::<TransferCategories>().is_err());
+        assert!("".parse::<TransferCategories>().unwrap().as_slice().is_empty());
+    }
+
+    #[test]
+    fn test_resolve_category() {
+        let categories = TransferCategories::default();
+        assert_eq!(categories.resolve(" Refund ").unwrap(), "refund");
+        assert!(matches!(categories.resolve("salary"), Err(AppError::InvalidRequest(_))));
+    }
+}
@@ -24,6 +24,11 @@ pub struct OperationContext {
     /// Client IP address
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_ip: Option<IpAddr>,
+
+    /// Tenant of the API key (None for internal callers such as the CLI
+    /// and background jobs, which are not tenant-scoped)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tenant_id: Option<Uuid>,
 }
 
 impl OperationContext {
@@ -34,18 +39,19 @@ impl OperationContext {
             request_user_id: None,
             correlation_id: None,
             client_ip: None,
+            tenant_id: None,
         }
     }
 
     /// Create context with API key
-    pub fn with_api_key(mut self, api_key_id: Uuid) -> Self {
-        self.api_key_id = Some(api_key_id);
+    pub fn with_api_key(mut self, api_key_id: impl Into<Uuid>) -> Self {
+        self.api_key_id = Some(api_key_id.into());
         self
     }
 
     /// Create context with request user ID
-    pub fn with_request_user(mut self, user_id: Uuid) -> Self {
-        self.request_user_id = Some(user_id);
+    pub fn with_request_user(mut self, user_id: impl Into<Uuid>) -> Self {
+        self.request_user_id = Some(user_id.into());
         self
     }
 
@@ -61,6 +67,12 @@ impl OperationContext {
         self
     }
 
+    /// Create context scoped to a tenant
+    pub fn with_tenant(mut self, tenant_id: Uuid) -> Self {
+        self.tenant_id = Some(tenant_id);
+        self
+    }
+
     /// Generate a new correlation ID if not present
     pub fn ensure_correlation_id(&mut self) -> Uuid {
         *self.correlation_id.get_or_insert_with(Uuid::new_v4)
@@ -82,15 +94,18 @@ mod tests {
         let api_key_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
         let correlation_id = Uuid::new_v4();
+        let tenant_id = Uuid::new_v4();
 
         let context = OperationContext::new()
             .with_api_key(api_key_id)
             .with_request_user(user_id)
-            .with_correlation_id(correlation_id);
+            .with_correlation_id(correlation_id)
+            .with_tenant(tenant_id);
 
         assert_eq!(context.api_key_id, Some(api_key_id));
         assert_eq!(context.request_user_id, Some(user_id));
         assert_eq!(context.correlation_id, Some(correlation_id));
+        assert_eq!(context.tenant_id, Some(tenant_id));
     }
 
     #[test]
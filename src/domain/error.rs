@@ -56,6 +56,14 @@ pub enum DomainError {
     /// Duplicate operation (idempotency)
     #[error("Duplicate operation: {key}")]
     DuplicateOperation { key: String },
+
+    /// Account type code not in the AccountType registry
+    #[error("Unknown account type: {0}")]
+    UnknownAccountType(String),
+
+    /// Stored state breaks a domain invariant (e.g. negative user wallet)
+    #[error("Invariant violation: {0}")]
+    InvariantViolation(String),
 }
 
 impl DomainError {
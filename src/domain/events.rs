@@ -8,6 +8,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::AccountType;
+
 /// Account-related events
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -16,7 +18,10 @@ pub enum AccountEvent {
     AccountCreated {
         account_id: Uuid,
         user_id: Uuid,
-        account_type: String,
+        account_type: AccountType,
+        /// Name of an additional (non-primary) account
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
         created_at: DateTime<Utc>,
     },
 
@@ -27,6 +32,9 @@ pub enum AccountEvent {
         transfer_id: Uuid,
         description: String,
         credited_at: DateTime<Utc>,
+        /// Category of the transfer, for reporting
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
     },
 
     /// Money was debited from the account (balance decreased)
@@ -36,6 +44,9 @@ pub enum AccountEvent {
         transfer_id: Uuid,
         description: String,
         debited_at: DateTime<Utc>,
+        /// Category of the transfer, for reporting
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
     },
 
     /// Account was frozen
@@ -50,6 +61,44 @@ pub enum AccountEvent {
         account_id: Uuid,
         unfrozen_at: DateTime<Utc>,
     },
+
+    /// Funds were reserved (reduces available balance, not ledger balance)
+    BalanceHeld {
+        account_id: Uuid,
+        hold_id: Uuid,
+        amount: Decimal,
+        to_account_id: Uuid,
+        description: String,
+        held_at: DateTime<Utc>,
+    },
+
+    /// Held funds were captured (balance decreased)
+    HoldCaptured {
+        account_id: Uuid,
+        hold_id: Uuid,
+        amount: Decimal,
+        transfer_id: Uuid,
+        captured_at: DateTime<Utc>,
+    },
+
+    /// Held funds were released back to the available balance
+    HoldReleased {
+        account_id: Uuid,
+        hold_id: Uuid,
+        released_at: DateTime<Utc>,
+    },
+
+    /// Account was closed with a zero balance; it takes no further debits
+    /// or credits
+    AccountClosed {
+        account_id: Uuid,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reason: Option<String>,
+        /// Account the remaining balance was swept to before closing
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        swept_to_account_id: Option<Uuid>,
+        closed_at: DateTime<Utc>,
+    },
 }
 
 impl AccountEvent {
@@ -61,6 +110,10 @@ impl AccountEvent {
             AccountEvent::MoneyDebited { .. } => "MoneyDebited",
             AccountEvent::AccountFrozen { .. } => "AccountFrozen",
             AccountEvent::AccountUnfrozen { .. } => "AccountUnfrozen",
+            AccountEvent::BalanceHeld { .. } => "BalanceHeld",
+            AccountEvent::HoldCaptured { .. } => "HoldCaptured",
+            AccountEvent::HoldReleased { .. } => "HoldReleased",
+            AccountEvent::AccountClosed { .. } => "AccountClosed",
         }
     }
 
@@ -72,7 +125,42 @@ impl AccountEvent {
             AccountEvent::MoneyDebited { account_id, .. } => *account_id,
             AccountEvent::AccountFrozen { account_id, .. } => *account_id,
             AccountEvent::AccountUnfrozen { account_id, .. } => *account_id,
+            AccountEvent::BalanceHeld { account_id, .. } => *account_id,
+            AccountEvent::HoldCaptured { account_id, .. } => *account_id,
+            AccountEvent::HoldReleased { account_id, .. } => *account_id,
+            AccountEvent::AccountClosed { account_id, .. } => *account_id,
+        }
+    }
+
+    /// Description (memo) carried by the event, if any
+    pub fn description(&self) -> Option<&str> {
+        match self {
+            AccountEvent::MoneyCredited { description, .. }
+            | AccountEvent::MoneyDebited { description, .. }
+            | AccountEvent::BalanceHeld { description, .. } => Some(description),
+            _ => None,
+        }
+    }
+
+    /// Transfer category carried by a debit or credit, if any
+    pub fn category(&self) -> Option<&str> {
+        match self {
+            AccountEvent::MoneyCredited { category, .. } | AccountEvent::MoneyDebited { category, .. } => {
+                category.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Tag a debit or credit with a transfer category (other events are
+    /// returned unchanged)
+    pub fn with_category(mut self, new_category: Option<String>) -> Self {
+        if let AccountEvent::MoneyCredited { category, .. } | AccountEvent::MoneyDebited { category, .. } =
+            &mut self
+        {
+            *category = new_category;
         }
+        self
     }
 }
 
@@ -91,6 +179,12 @@ pub enum TransferEvent {
         memo: Option<String>,
         initiated_by: Uuid,
         initiated_at: DateTime<Utc>,
+        /// Original transfer when this transfer is a reversal
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        reversal_of: Option<Uuid>,
+        /// Reporting category from the configured taxonomy
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        category: Option<String>,
     },
 
     /// Transfer was completed successfully
@@ -105,6 +199,42 @@ pub enum TransferEvent {
         reason: TransferFailureReason,
         failed_at: DateTime<Utc>,
     },
+
+    /// Completed transfer was reversed by a compensating transfer
+    TransferReversed {
+        transfer_id: Uuid,
+        reversal_transfer_id: Uuid,
+        reason: Option<String>,
+        reversed_by: Uuid,
+        reversed_at: DateTime<Utc>,
+    },
+
+    /// Transfer exceeded the approval threshold and awaits a second API key
+    TransferApprovalRequested {
+        transfer_id: Uuid,
+        threshold: Decimal,
+        /// API key that initiated the transfer (maker)
+        requested_by_api_key_id: Option<Uuid>,
+        requested_at: DateTime<Utc>,
+        /// When the transfer fails as expired unless decided before
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<DateTime<Utc>>,
+    },
+
+    /// Pending transfer was approved (checker) and is executed next
+    TransferApproved {
+        transfer_id: Uuid,
+        approved_by_api_key_id: Uuid,
+        approved_at: DateTime<Utc>,
+    },
+
+    /// Pending transfer was rejected and will not be executed
+    TransferRejected {
+        transfer_id: Uuid,
+        rejected_by_api_key_id: Uuid,
+        reason: Option<String>,
+        rejected_at: DateTime<Utc>,
+    },
 }
 
 impl TransferEvent {
@@ -114,6 +244,10 @@ impl TransferEvent {
             TransferEvent::TransferInitiated { .. } => "TransferInitiated",
             TransferEvent::TransferCompleted { .. } => "TransferCompleted",
             TransferEvent::TransferFailed { .. } => "TransferFailed",
+            TransferEvent::TransferReversed { .. } => "TransferReversed",
+            TransferEvent::TransferApprovalRequested { .. } => "TransferApprovalRequested",
+            TransferEvent::TransferApproved { .. } => "TransferApproved",
+            TransferEvent::TransferRejected { .. } => "TransferRejected",
         }
     }
 
@@ -123,6 +257,10 @@ impl TransferEvent {
             TransferEvent::TransferInitiated { transfer_id, .. } => *transfer_id,
             TransferEvent::TransferCompleted { transfer_id, .. } => *transfer_id,
             TransferEvent::TransferFailed { transfer_id, .. } => *transfer_id,
+            TransferEvent::TransferReversed { transfer_id, .. } => *transfer_id,
+            TransferEvent::TransferApprovalRequested { transfer_id, .. } => *transfer_id,
+            TransferEvent::TransferApproved { transfer_id, .. } => *transfer_id,
+            TransferEvent::TransferRejected { transfer_id, .. } => *transfer_id,
         }
     }
 }
@@ -137,6 +275,9 @@ pub enum TransferFailureReason {
     /// Sender's account is frozen
     AccountFrozen,
 
+    /// Sender's or recipient's account is closed
+    AccountClosed,
+
     /// Account not found
     AccountNotFound,
 
@@ -152,24 +293,53 @@ pub enum TransferFailureReason {
     /// Request user doesn't match account owner
     UnauthorizedTransfer,
 
+    /// Blocked by an account restriction (receive-only, send-only, allowlist)
+    TransferBlocked,
+
     /// Concurrent modification detected
     ConcurrencyConflict,
 
+    /// Pending transfer was neither approved nor rejected in time
+    Expired,
+
     /// Internal system error
     InternalError,
 }
 
+impl TransferFailureReason {
+    /// Serialized (snake_case) name, as stored in the failed_transfers projection
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferFailureReason::InsufficientBalance => "insufficient_balance",
+            TransferFailureReason::AccountFrozen => "account_frozen",
+            TransferFailureReason::AccountClosed => "account_closed",
+            TransferFailureReason::AccountNotFound => "account_not_found",
+            TransferFailureReason::SameAccount => "same_account",
+            TransferFailureReason::AmountTooSmall => "amount_too_small",
+            TransferFailureReason::AmountTooLarge => "amount_too_large",
+            TransferFailureReason::UnauthorizedTransfer => "unauthorized_transfer",
+            TransferFailureReason::TransferBlocked => "transfer_blocked",
+            TransferFailureReason::ConcurrencyConflict => "concurrency_conflict",
+            TransferFailureReason::Expired => "expired",
+            TransferFailureReason::InternalError => "internal_error",
+        }
+    }
+}
+
 impl std::fmt::Display for TransferFailureReason {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TransferFailureReason::InsufficientBalance => write!(f, "Insufficient balance"),
             TransferFailureReason::AccountFrozen => write!(f, "Account is frozen"),
+            TransferFailureReason::AccountClosed => write!(f, "Account is closed"),
             TransferFailureReason::AccountNotFound => write!(f, "Account not found"),
             TransferFailureReason::SameAccount => write!(f, "Cannot transfer to same account"),
             TransferFailureReason::AmountTooSmall => write!(f, "Amount is too small"),
             TransferFailureReason::AmountTooLarge => write!(f, "Amount is too large"),
             TransferFailureReason::UnauthorizedTransfer => write!(f, "Unauthorized transfer"),
+            TransferFailureReason::TransferBlocked => write!(f, "Blocked by account restriction"),
             TransferFailureReason::ConcurrencyConflict => write!(f, "Concurrency conflict"),
+            TransferFailureReason::Expired => write!(f, "Approval expired"),
             TransferFailureReason::InternalError => write!(f, "Internal error"),
         }
     }
@@ -207,6 +377,21 @@ pub enum UserEvent {
         user_id: Uuid,
         reactivated_at: DateTime<Utc>,
     },
+
+    /// Personal data of a deactivated user was scrubbed
+    UserAnonymized {
+        user_id: Uuid,
+        reason: Option<String>,
+        anonymized_at: DateTime<Utc>,
+    },
+
+    /// Username was changed by an admin
+    UserRenamed {
+        user_id: Uuid,
+        old_username: String,
+        new_username: String,
+        renamed_at: DateTime<Utc>,
+    },
 }
 
 /// Changes made to a user profile
@@ -227,6 +412,8 @@ impl UserEvent {
             UserEvent::UserUpdated { .. } => "UserUpdated",
             UserEvent::UserDeactivated { .. } => "UserDeactivated",
             UserEvent::UserReactivated { .. } => "UserReactivated",
+            UserEvent::UserAnonymized { .. } => "UserAnonymized",
+            UserEvent::UserRenamed { .. } => "UserRenamed",
         }
     }
 
@@ -237,6 +424,8 @@ impl UserEvent {
             UserEvent::UserUpdated { user_id, .. } => *user_id,
             UserEvent::UserDeactivated { user_id, .. } => *user_id,
             UserEvent::UserReactivated { user_id, .. } => *user_id,
+            UserEvent::UserAnonymized { user_id, .. } => *user_id,
+            UserEvent::UserRenamed { user_id, .. } => *user_id,
         }
     }
 }
@@ -265,13 +454,19 @@ mod tests {
             transfer_id: Uuid::new_v4(),
             description: "Test credit".to_string(),
             credited_at: Utc::now(),
+            category: None,
         };
 
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("MoneyCredited"));
+        assert!(!json.contains("category"));
         
         let deserialized: AccountEvent = serde_json::from_str(&json).unwrap();
         assert_eq!(event.event_type(), deserialized.event_type());
+
+        let tagged = event.with_category(Some("reward".to_string()));
+        let deserialized: AccountEvent = serde_json::from_str(&serde_json::to_string(&tagged).unwrap()).unwrap();
+        assert_eq!(deserialized.category(), Some("reward"));
     }
 
     #[test]
@@ -282,5 +477,6 @@ mod tests {
         
         let deserialized: TransferFailureReason = serde_json::from_str(&json).unwrap();
         assert_eq!(reason, deserialized);
+        assert_eq!(json, format!(r#""{}""#, reason.as_str()));
     }
 }
@@ -0,0 +1,102 @@
+//! Typed identifiers
+//!
+//! Users, accounts, transfers and API keys are all identified by UUIDs.
+//! Wrapping each in its own type keeps a user ID from being passed where an
+//! account ID is expected. The wrappers serialize and bind to SQL exactly
+//! like the bare UUID, so stored data and the wire format are unchanged.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type,
+        )]
+        #[serde(transparent)]
+        #[sqlx(transparent)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            pub const fn from_uuid(id: Uuid) -> Self {
+                Self(id)
+            }
+
+            /// Fresh random (v4) ID
+            pub fn new_v4() -> Self {
+                Self(Uuid::new_v4())
+            }
+
+            pub const fn as_uuid(&self) -> Uuid {
+                self.0
+            }
+        }
+
+        impl From<Uuid> for $name {
+            fn from(id: Uuid) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<$name> for Uuid {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = uuid::Error;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Uuid::parse_str(s).map(Self)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// ID of a user (users.id, accounts.user_id)
+    UserId
+);
+
+id_type!(
+    /// ID of an account (accounts.id); a user may own several
+    AccountId
+);
+
+id_type!(
+    /// ID of a transfer, also the journal ID of its ledger entries
+    TransferId
+);
+
+id_type!(
+    /// ID of an API key (api_keys.id)
+    ApiKeyId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ids_are_transparent() {
+        let uuid = Uuid::new_v4();
+        let user_id = UserId::from(uuid);
+
+        assert_eq!(serde_json::to_value(user_id).unwrap(), serde_json::json!(uuid.to_string()));
+        assert_eq!(serde_json::from_value::<UserId>(serde_json::json!(uuid)).unwrap(), user_id);
+        assert_eq!(user_id.to_string().parse::<UserId>().unwrap(), user_id);
+        assert_eq!(Uuid::from(user_id), uuid);
+        assert!("not-a-uuid".parse::<AccountId>().is_err());
+    }
+}
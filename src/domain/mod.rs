@@ -2,12 +2,18 @@
 //!
 //! Core domain types and business logic.
 
+pub mod account_type;
 pub mod amount;
+pub mod category;
 pub mod context;
 pub mod error;
 pub mod events;
+pub mod ids;
 
+pub use account_type::AccountType;
 pub use amount::{Amount, AmountError, Balance};
+pub use category::{TransferCategories, DEFAULT_TRANSFER_CATEGORIES};
 pub use context::OperationContext;
 pub use error::DomainError;
 pub use events::{AccountEvent, TransferEvent, UserEvent, UserChanges, TransferFailureReason};
+pub use ids::{AccountId, ApiKeyId, TransferId, UserId};
@@ -2,6 +2,7 @@
 //!
 //! Centralized error types and HTTP response conversion.
 
+use axum::extract::rejection::JsonRejection;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -17,12 +18,44 @@ pub enum AppError {
     #[error("Invalid request: {0}")]
     InvalidRequest(String),
 
+    /// Request fields that could not be parsed (e.g. a malformed amount)
+    #[error("Invalid request fields")]
+    InvalidFields(Vec<FieldError>),
+
+    /// Request fields that parsed but break a rule (length, format, ...)
+    #[error("Validation failed")]
+    Validation(Vec<FieldError>),
+
+    /// Request body is not valid JSON
+    #[error("Malformed JSON body")]
+    MalformedJson(String),
+
+    /// Request body is JSON but does not match the expected shape
+    #[error("Invalid JSON body")]
+    InvalidJsonBody(String),
+
+    #[error("Unsupported media type")]
+    UnsupportedMediaType(String),
+
+    /// Request body exceeds MAX_BODY_BYTES
+    #[error("Request body too large")]
+    PayloadTooLarge,
+
     #[error("Insufficient balance")]
     InsufficientBalance,
 
     #[error("Account is frozen")]
     AccountFrozen,
 
+    #[error("Account is closed: {0}")]
+    AccountClosed(String),
+
+    #[error("Amount too small: {0}")]
+    AmountTooSmall(String),
+
+    #[error("Amount too large: {0}")]
+    AmountTooLarge(String),
+
     #[error("Invalid API key")]
     InvalidApiKey,
 
@@ -41,12 +74,32 @@ pub enum AppError {
     #[error("Account not found: {0}")]
     AccountNotFound(String),
 
+    #[error("Transfer blocked: {0}")]
+    TransferBlocked(String),
+
+    #[error("Hold not found: {0}")]
+    HoldNotFound(String),
+
+    #[error("Event not found: {0}")]
+    EventNotFound(String),
+
     #[error("Idempotency conflict: same key with different request")]
     IdempotencyConflict,
 
     #[error("Version conflict: concurrent modification detected")]
     VersionConflict,
 
+    #[error("Balance assertion failed: {0}")]
+    BalanceAssertionFailed(String),
+
+    /// Email already belongs to another user
+    #[error("Email already in use: {0}")]
+    EmailTaken(String),
+
+    /// Username already belongs to another user
+    #[error("Username already in use: {0}")]
+    UsernameTaken(String),
+
     #[error("Rate limit exceeded")]
     RateLimitExceeded,
 
@@ -75,6 +128,44 @@ pub struct ErrorResponse {
     pub error_code: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<String>,
+    /// Per-field errors (InvalidFields and Validation only)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldError>,
+}
+
+/// Error in one field of a request body
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+    /// Request body field, e.g. "amount"
+    pub field: String,
+    /// Machine-readable reason, e.g. "too_many_decimals"
+    pub code: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code: code.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Rejections of axum's `Json` extractor, in the standard error envelope
+impl From<JsonRejection> for AppError {
+    fn from(rejection: JsonRejection) -> Self {
+        match rejection {
+            JsonRejection::JsonDataError(e) => AppError::InvalidJsonBody(e.body_text()),
+            JsonRejection::JsonSyntaxError(e) => AppError::MalformedJson(e.body_text()),
+            JsonRejection::MissingJsonContentType(e) => AppError::UnsupportedMediaType(e.body_text()),
+            JsonRejection::BytesRejection(e) if e.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+                AppError::PayloadTooLarge
+            }
+            other => AppError::InvalidRequest(other.body_text()),
+        }
+    }
 }
 
 impl IntoResponse for AppError {
@@ -84,12 +175,30 @@ impl IntoResponse for AppError {
             AppError::InvalidRequest(msg) => {
                 (StatusCode::BAD_REQUEST, "invalid_request", Some(msg.clone()))
             }
+            AppError::InvalidFields(_) => {
+                (StatusCode::BAD_REQUEST, "invalid_fields", None)
+            }
+            AppError::MalformedJson(msg) => {
+                (StatusCode::BAD_REQUEST, "malformed_json", Some(msg.clone()))
+            }
             AppError::InsufficientBalance => {
                 (StatusCode::BAD_REQUEST, "insufficient_balance", None)
             }
             AppError::AccountFrozen => {
                 (StatusCode::BAD_REQUEST, "account_frozen", None)
             }
+            AppError::AccountClosed(id) => {
+                (StatusCode::BAD_REQUEST, "account_closed", Some(id.clone()))
+            }
+            AppError::TransferBlocked(msg) => {
+                (StatusCode::BAD_REQUEST, "transfer_blocked", Some(msg.clone()))
+            }
+            AppError::AmountTooSmall(msg) => {
+                (StatusCode::BAD_REQUEST, "amount_too_small", Some(msg.clone()))
+            }
+            AppError::AmountTooLarge(msg) => {
+                (StatusCode::BAD_REQUEST, "amount_too_large", Some(msg.clone()))
+            }
 
             // 401 Unauthorized
             AppError::InvalidApiKey => {
@@ -114,6 +223,12 @@ impl IntoResponse for AppError {
             AppError::AccountNotFound(id) => {
                 (StatusCode::NOT_FOUND, "account_not_found", Some(id.clone()))
             }
+            AppError::HoldNotFound(id) => {
+                (StatusCode::NOT_FOUND, "hold_not_found", Some(id.clone()))
+            }
+            AppError::EventNotFound(id) => {
+                (StatusCode::NOT_FOUND, "event_not_found", Some(id.clone()))
+            }
 
             // 409 Conflict
             AppError::IdempotencyConflict => {
@@ -122,6 +237,33 @@ impl IntoResponse for AppError {
             AppError::VersionConflict => {
                 (StatusCode::CONFLICT, "version_conflict", None)
             }
+            AppError::BalanceAssertionFailed(detail) => {
+                (StatusCode::CONFLICT, "balance_assertion_failed", Some(detail.clone()))
+            }
+            AppError::EmailTaken(email) => {
+                (StatusCode::CONFLICT, "email_taken", Some(email.clone()))
+            }
+            AppError::UsernameTaken(username) => {
+                (StatusCode::CONFLICT, "username_taken", Some(username.clone()))
+            }
+
+            // 413 Payload Too Large
+            AppError::PayloadTooLarge => {
+                (StatusCode::PAYLOAD_TOO_LARGE, "payload_too_large", None)
+            }
+
+            // 415 Unsupported Media Type
+            AppError::UnsupportedMediaType(msg) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, "unsupported_media_type", Some(msg.clone()))
+            }
+
+            // 422 Unprocessable Entity
+            AppError::Validation(_) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "validation_failed", None)
+            }
+            AppError::InvalidJsonBody(msg) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, "invalid_json_body", Some(msg.clone()))
+            }
 
             // 429 Too Many Requests
             AppError::RateLimitExceeded => {
@@ -170,6 +312,14 @@ impl IntoResponse for AppError {
                     DomainError::DuplicateOperation { key } => {
                         (StatusCode::CONFLICT, "duplicate_operation", Some(key.clone()))
                     }
+                    DomainError::UnknownAccountType(code) => {
+                        tracing::error!("Unknown account type: {}", code);
+                        (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", None)
+                    }
+                    DomainError::InvariantViolation(msg) => {
+                        tracing::error!("Invariant violation: {}", msg);
+                        (StatusCode::INTERNAL_SERVER_ERROR, "internal_error", None)
+                    }
                 }
             }
 
@@ -188,10 +338,16 @@ impl IntoResponse for AppError {
             }
         };
 
+        let error = self.to_string();
+        let fields = match self {
+            AppError::InvalidFields(fields) | AppError::Validation(fields) => fields,
+            _ => Vec::new(),
+        };
         let body = ErrorResponse {
-            error: self.to_string(),
+            error,
             error_code: error_code.to_string(),
             details,
+            fields,
         };
 
         (status, Json(body)).into_response()
@@ -0,0 +1,216 @@
+//! Dead-letter Events
+//!
+//! Handling of poison events: stored events whose data no longer
+//! deserializes into the aggregate's event type. By default replay fails
+//! (`PoisonEventPolicy::Fail`); with `Skip` the event is left out of the
+//! replay and recorded in dead_letter_events (migration 025) so the rest of
+//! the aggregate stays readable. An aggregate loaded without some of its
+//! events lags behind its stored version, so appends to it fail with a
+//! concurrency conflict and no snapshot is taken until the dead letter is
+//! resolved.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{EventStore, EventStoreError, StoredEvent};
+use crate::domain::{AccountEvent, TransferEvent, UserEvent};
+
+/// What to do with an event that fails to deserialize during replay
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PoisonEventPolicy {
+    /// Fail the whole load
+    #[default]
+    Fail,
+    /// Skip the event, log it and record it as a dead letter
+    Skip,
+}
+
+impl FromStr for PoisonEventPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "fail" => Ok(PoisonEventPolicy::Fail),
+            "skip" => Ok(PoisonEventPolicy::Skip),
+            _ => Err(format!("unknown poison event policy: {}", s)),
+        }
+    }
+}
+
+/// A recorded poison event
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DeadLetterEvent {
+    pub event_id: Uuid,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub version: i64,
+    pub event_type: String,
+    pub error: String,
+    pub failure_count: i32,
+    pub first_failed_at: DateTime<Utc>,
+    pub last_failed_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Check that event data deserializes into the event type of its aggregate
+fn check_event_data(aggregate_type: &str, event_data: &serde_json::Value) -> Result<(), String> {
+    let result = match aggregate_type {
+        "Account" => serde_json::from_value::<AccountEvent>(event_data.clone()).map(drop),
+        "User" => serde_json::from_value::<UserEvent>(event_data.clone()).map(drop),
+        "Transfer" => serde_json::from_value::<TransferEvent>(event_data.clone()).map(drop),
+        other => return Err(format!("unknown aggregate type: {}", other)),
+    };
+    result.map_err(|e| e.to_string())
+}
+
+/// Record (or re-record) a poison event
+pub(super) async fn record_dead_letter(
+    pool: &PgPool,
+    event: &StoredEvent,
+    error: &str,
+) -> Result<(), EventStoreError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO dead_letter_events (event_id, aggregate_type, aggregate_id, version, event_type, error)
+        VALUES ($1, $2, $3, $4, $5, $6)
+        ON CONFLICT (event_id) DO UPDATE
+        SET error = EXCLUDED.error,
+            failure_count = dead_letter_events.failure_count + 1,
+            last_failed_at = NOW(),
+            resolved_at = NULL
+        "#,
+        event.id,
+        event.aggregate_type,
+        event.aggregate_id,
+        event.version,
+        event.event_type,
+        error
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Inspect and re-process dead-lettered events
+#[derive(Debug, Clone)]
+pub struct DeadLetterRepository {
+    pool: PgPool,
+}
+
+impl DeadLetterRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Dead letters, most recent failure first
+    pub async fn list(
+        &self,
+        include_resolved: bool,
+        limit: i64,
+    ) -> Result<Vec<DeadLetterEvent>, EventStoreError> {
+        let dead_letters: Vec<DeadLetterEvent> = sqlx::query_as!(
+            DeadLetterEvent,
+            r#"
+            SELECT event_id, aggregate_type, aggregate_id, version, event_type, error,
+                   failure_count, first_failed_at, last_failed_at, resolved_at
+            FROM dead_letter_events
+            WHERE $1 OR resolved_at IS NULL
+            ORDER BY last_failed_at DESC
+            LIMIT $2
+            "#,
+            include_resolved,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(dead_letters)
+    }
+
+    pub async fn get(&self, event_id: Uuid) -> Result<Option<DeadLetterEvent>, EventStoreError> {
+        let dead_letter: Option<DeadLetterEvent> = sqlx::query_as!(
+            DeadLetterEvent,
+            r#"
+            SELECT event_id, aggregate_type, aggregate_id, version, event_type, error,
+                   failure_count, first_failed_at, last_failed_at, resolved_at
+            FROM dead_letter_events
+            WHERE event_id = $1
+            "#,
+            event_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(dead_letter)
+    }
+
+    /// Re-check a dead letter against the current event types (e.g. after
+    /// deploying a fix). Marks it resolved if the event now deserializes,
+    /// otherwise records the new failure. Returns the updated dead letter,
+    /// or None if the event was never dead-lettered.
+    pub async fn reprocess(
+        &self,
+        event_store: &EventStore,
+        event_id: Uuid,
+    ) -> Result<Option<DeadLetterEvent>, EventStoreError> {
+        if self.get(event_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let event = event_store
+            .get_event(event_id)
+            .await?
+            .ok_or_else(|| EventStoreError::InvalidEventData(format!("event {} no longer exists", event_id)))?;
+
+        match check_event_data(&event.aggregate_type, &event.event_data) {
+            Ok(()) => {
+                sqlx::query!(
+                    "UPDATE dead_letter_events SET resolved_at = NOW() WHERE event_id = $1",
+                    event_id
+                )
+                .execute(&self.pool)
+                .await?;
+                tracing::info!(event_id = %event_id, aggregate_id = %event.aggregate_id, "Dead-letter event resolved");
+            }
+            Err(error) => {
+                tracing::warn!(event_id = %event_id, error = %error, "Dead-letter event still fails to deserialize");
+                record_dead_letter(&self.pool, &event, &error).await?;
+            }
+        }
+
+        self.get(event_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poison_event_policy_from_str() {
+        assert_eq!("fail".parse(), Ok(PoisonEventPolicy::Fail));
+        assert_eq!(" SKIP ".parse(), Ok(PoisonEventPolicy::Skip));
+        assert!("ignore".parse::<PoisonEventPolicy>().is_err());
+        assert_eq!(PoisonEventPolicy::default(), PoisonEventPolicy::Fail);
+    }
+
+    #[test]
+    fn test_check_event_data() {
+        let event = AccountEvent::AccountFrozen {
+            account_id: Uuid::new_v4(),
+            reason: "test".to_string(),
+            frozen_at: Utc::now(),
+        };
+        let data = serde_json::to_value(&event).unwrap();
+
+        assert!(check_event_data("Account", &data).is_ok());
+        assert!(check_event_data("User", &data).is_err());
+        assert!(check_event_data("Account", &serde_json::json!({"type": "Renamed"})).is_err());
+        assert!(check_event_data("Ledger", &data).is_err());
+    }
+}
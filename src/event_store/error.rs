@@ -4,6 +4,8 @@
 
 use uuid::Uuid;
 
+use crate::error::AppError;
+
 /// Errors that can occur in the event store
 #[derive(Debug, thiserror::Error)]
 pub enum EventStoreError {
@@ -19,13 +21,18 @@ pub enum EventStoreError {
     #[error("Aggregate not found: {0}")]
     AggregateNotFound(Uuid),
 
-    /// Idempotency key already exists
-    #[error("Idempotency key already exists: {0}")]
-    IdempotencyKeyExists(Uuid),
+    /// Idempotency key reused with a different command payload
+    #[error("Idempotency key {0} was already used with a different request")]
+    IdempotencyConflict(Uuid),
+
+    /// The append transaction lost a race with a concurrent transaction
+    /// (serialization failure or deadlock) and was rolled back
+    #[error("Transaction aborted by a concurrent append: {0}")]
+    SerializationFailure(#[source] sqlx::Error),
 
     /// Database error
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(#[source] sqlx::Error),
 
     /// Serialization error
     #[error("Serialization error: {0}")]
@@ -40,17 +47,54 @@ pub enum EventStoreError {
     InvalidEventData(String),
 }
 
+/// SQLSTATE serialization_failure
+const SERIALIZATION_FAILURE: &str = "40001";
+
+/// SQLSTATE deadlock_detected
+const DEADLOCK_DETECTED: &str = "40P01";
+
+impl From<sqlx::Error> for EventStoreError {
+    fn from(e: sqlx::Error) -> Self {
+        let code = e.as_database_error().and_then(|db| db.code());
+        match code.as_deref() {
+            Some(SERIALIZATION_FAILURE | DEADLOCK_DETECTED) => {
+                EventStoreError::SerializationFailure(e)
+            }
+            _ => EventStoreError::Database(e),
+        }
+    }
+}
+
 impl EventStoreError {
-    /// Check if this error is a concurrency conflict
+    /// Check if this error is a concurrency conflict: a stale expected
+    /// version or a transaction aborted by a concurrent one. Both are fixed
+    /// by reloading the aggregates and retrying.
     pub fn is_concurrency_conflict(&self) -> bool {
-        matches!(self, EventStoreError::ConcurrencyConflict { .. })
+        matches!(
+            self,
+            EventStoreError::ConcurrencyConflict { .. } | EventStoreError::SerializationFailure(_)
+        )
     }
 
     /// Check if this error is retryable
     pub fn is_retryable(&self) -> bool {
         matches!(
             self,
-            EventStoreError::ConcurrencyConflict { .. } | EventStoreError::Database(_)
+            EventStoreError::ConcurrencyConflict { .. }
+                | EventStoreError::SerializationFailure(_)
+                | EventStoreError::Database(_)
         )
     }
 }
+
+impl From<EventStoreError> for AppError {
+    fn from(e: EventStoreError) -> Self {
+        match e {
+            EventStoreError::ConcurrencyConflict { .. }
+            | EventStoreError::SerializationFailure(_)
+            | EventStoreError::MaxRetriesExceeded => AppError::VersionConflict,
+            EventStoreError::IdempotencyConflict(_) => AppError::IdempotencyConflict,
+            e => AppError::Internal(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Event Export
+//!
+//! Streams the global event stream as NDJSON (one stored event per line) for
+//! loading into external analytics stores. Events are read in pages of
+//! global_sequence order by a background task that feeds a bounded channel,
+//! so a slow client pauses the reads instead of buffering the whole table.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use super::{EventStore, EventStoreError, StoredEvent};
+
+/// Events read per page (one NDJSON chunk)
+const EXPORT_BATCH_SIZE: i64 = 500;
+
+/// Chunks buffered ahead of the client
+const EXPORT_CHANNEL_CAPACITY: usize = 2;
+
+/// Which events to export
+#[derive(Debug, Clone, Default)]
+pub struct EventExportFilter {
+    pub aggregate_type: Option<String>,
+    pub from_date: Option<DateTime<Utc>>,
+    pub to_date: Option<DateTime<Utc>>,
+    /// Resume after this global_sequence (0 = from the beginning)
+    pub after_sequence: i64,
+}
+
+/// One NDJSON line
+#[derive(Debug, Serialize)]
+struct ExportedEvent<'a> {
+    id: Uuid,
+    global_sequence: i64,
+    aggregate_type: &'a str,
+    aggregate_id: Uuid,
+    version: i64,
+    event_type: &'a str,
+    event_data: &'a serde_json::Value,
+    context: &'a serde_json::Value,
+    idempotency_key: Option<Uuid>,
+    created_at: DateTime<Utc>,
+}
+
+impl<'a> From<&'a StoredEvent> for ExportedEvent<'a> {
+    fn from(event: &'a StoredEvent) -> Self {
+        Self {
+            id: event.id,
+            global_sequence: event.global_sequence,
+            aggregate_type: &event.aggregate_type,
+            aggregate_id: event.aggregate_id,
+            version: event.version,
+            event_type: &event.event_type,
+            event_data: &event.event_data,
+            context: &event.context,
+            idempotency_key: event.idempotency_key,
+            created_at: event.created_at,
+        }
+    }
+}
+
+/// Stream matching events as NDJSON chunks in global_sequence order.
+/// A read error is sent as the last item, which aborts the response so the
+/// client sees a truncated export rather than a silently short one.
+pub fn export_ndjson(
+    event_store: EventStore,
+    filter: EventExportFilter,
+) -> ReceiverStream<Result<Vec<u8>, EventStoreError>> {
+    let (tx, rx) = mpsc::channel(EXPORT_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut after_sequence = filter.after_sequence;
+
+        loop {
+            let events = match event_store
+                .read_filtered(after_sequence, &filter, EXPORT_BATCH_SIZE)
+                .await
+            {
+                Ok(events) => events,
+                Err(e) => {
+                    tracing::error!(error = %e, after_sequence, "Event export failed");
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+
+            let Some(last) = events.last() else {
+                return;
+            };
+            after_sequence = last.global_sequence;
+
+            let mut chunk = Vec::new();
+            for event in &events {
+                if let Err(e) = serde_json::to_writer(&mut chunk, &ExportedEvent::from(event)) {
+                    let _ = tx.send(Err(e.into())).await;
+                    return;
+                }
+                chunk.push(b'\n');
+            }
+
+            // Client went away
+            if tx.send(Ok(chunk)).await.is_err() {
+                return;
+            }
+
+            if (events.len() as i64) < EXPORT_BATCH_SIZE {
+                return;
+            }
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
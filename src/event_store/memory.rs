@@ -0,0 +1,438 @@
+//! In-Memory Event Store
+//!
+//! [`EventStoreTrait`] implementation keeping events and snapshots in process
+//! memory, for embedding the domain in services without PostgreSQL and for
+//! handler tests. Version checks, idempotency keys and snapshots behave like
+//! the PG-backed [`super::EventStore`]; nothing survives the process.
+
+use chrono::Utc;
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::aggregate::Aggregate;
+use crate::domain::OperationContext;
+use crate::idempotency::IdempotencyScope;
+
+use super::{
+    AggregateOperation, BoxFuture, EventStoreError, EventStoreTrait, IdempotencyRequest, PendingAppend, StoredEvent,
+};
+
+/// Event store backed by a shared in-memory event list; clones share it
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryEventStore {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+#[derive(Debug, Default)]
+struct MemoryState {
+    /// Events in global stream order
+    events: Vec<StoredEvent>,
+    /// (scope, idempotency key) -> (command hash, first event ID)
+    idempotency_keys: HashMap<(IdempotencyScope, Uuid), (String, Uuid)>,
+    /// (scope, idempotency key) -> stored command result
+    results: HashMap<(IdempotencyScope, Uuid), serde_json::Value>,
+    /// (aggregate type, aggregate ID) -> (version, state)
+    snapshots: HashMap<(&'static str, Uuid), (i64, serde_json::Value)>,
+}
+
+impl MemoryState {
+    fn current_version(&self, aggregate_id: Uuid) -> i64 {
+        self.events
+            .iter()
+            .filter(|e| e.aggregate_id == aggregate_id)
+            .map(|e| e.version)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl InMemoryEventStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// All events appended so far, in stream order
+    pub fn events(&self) -> Vec<StoredEvent> {
+        self.lock().events.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Append all operations or none of them
+    fn try_append(
+        &self,
+        operations: &[AggregateOperation],
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> Result<Vec<Uuid>, EventStoreError> {
+        self.try_append_or_replay(operations, idempotency, context)
+            .map(|(event_ids, _)| event_ids)
+    }
+
+    /// [`Self::try_append`], also returning whether the idempotency key was
+    /// already completed (the returned event ID is then the recorded one)
+    fn try_append_or_replay(
+        &self,
+        operations: &[AggregateOperation],
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> Result<(Vec<Uuid>, bool), EventStoreError> {
+        let context_json = serde_json::to_value(context)?;
+        let mut state = self.lock();
+
+        if let Some(idempotency) = idempotency {
+            if let Some((command_hash, event_id)) = state.idempotency_keys.get(&(idempotency.scope, idempotency.key)) {
+                if command_hash != &idempotency.command_hash {
+                    return Err(EventStoreError::IdempotencyConflict(idempotency.key));
+                }
+                return Ok((vec![*event_id], true));
+            }
+        }
+
+        // Check every expected version first; operations may target the
+        // same aggregate more than once
+        let mut versions: HashMap<Uuid, i64> = HashMap::new();
+        for op in operations {
+            let current = *versions
+                .entry(op.aggregate_id)
+                .or_insert_with(|| state.current_version(op.aggregate_id));
+            if op.expected_version != current {
+                return Err(EventStoreError::ConcurrencyConflict {
+                    aggregate_id: op.aggregate_id,
+                    expected: op.expected_version,
+                    actual: current,
+                });
+            }
+            versions.insert(op.aggregate_id, current + 1);
+        }
+
+        let created_at = Utc::now();
+        let mut event_ids = Vec::with_capacity(operations.len());
+        for (idx, op) in operations.iter().enumerate() {
+            let id = Uuid::new_v4();
+            let global_sequence = state.events.len() as i64 + 1;
+            state.events.push(StoredEvent {
+                id,
+                global_sequence,
+                aggregate_type: op.aggregate_type.clone(),
+                aggregate_id: op.aggregate_id,
+                version: op.expected_version + 1,
+                event_type: op.event_type.clone(),
+                event_data: op.event_data.clone(),
+                context: context_json.clone(),
+                idempotency_key: idempotency.filter(|_| idx == 0).map(|i| i.key),
+                created_at,
+            });
+            event_ids.push(id);
+        }
+
+        if let (Some(idempotency), Some(&first)) = (idempotency, event_ids.first()) {
+            state
+                .idempotency_keys
+                .insert((idempotency.scope, idempotency.key), (idempotency.command_hash.clone(), first));
+        }
+
+        Ok((event_ids, false))
+    }
+}
+
+impl EventStoreTrait for InMemoryEventStore {
+    /// No transaction: nothing is rolled back if `project` fails
+    type Tx = ();
+
+    async fn append_atomic(
+        &self,
+        operations: Vec<AggregateOperation>,
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> Result<Vec<Uuid>, EventStoreError> {
+        self.try_append(&operations, idempotency, context)
+    }
+
+    fn append_with_retry<'a, T, E, F, Fut>(
+        &'a self,
+        idempotency: Option<&'a IdempotencyRequest>,
+        context: &'a OperationContext,
+        mut build: F,
+    ) -> BoxFuture<'a, Result<(Vec<Uuid>, T), E>>
+    where
+        T: Send + 'a,
+        F: FnMut() -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>> + Send + 'a,
+        E: From<EventStoreError> + Send + 'a,
+    {
+        const MAX_ATTEMPTS: u32 = 3;
+
+        Box::pin(async move {
+            for _ in 0..MAX_ATTEMPTS {
+                let (operations, value) = build().await?;
+                match self.try_append(&operations, idempotency, context) {
+                    Ok(event_ids) => return Ok((event_ids, value)),
+                    Err(e) if e.is_concurrency_conflict() => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            Err(EventStoreError::MaxRetriesExceeded.into())
+        })
+    }
+
+    fn append_with_retry_in_tx<'a, T, E, F, Fut, P, PFut>(
+        &'a self,
+        idempotency: Option<&'a IdempotencyRequest>,
+        context: &'a OperationContext,
+        mut build: F,
+        mut project: P,
+    ) -> BoxFuture<'a, Result<(Vec<Uuid>, T), E>>
+    where
+        T: Send + 'a,
+        F: FnMut() -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>> + Send + 'a,
+        P: FnMut(PendingAppend<T, Self::Tx>) -> PFut + Send + 'a,
+        PFut: Future<Output = Result<PendingAppend<T, Self::Tx>, E>> + Send + 'a,
+        E: From<EventStoreError> + Send + 'a,
+    {
+        const MAX_ATTEMPTS: u32 = 3;
+
+        Box::pin(async move {
+            for _ in 0..MAX_ATTEMPTS {
+                let (operations, value) = build().await?;
+                match self.try_append_or_replay(&operations, idempotency, context) {
+                    // Projected by the call that completed the key
+                    Ok((event_ids, true)) => return Ok((event_ids, value)),
+                    Ok((event_ids, false)) => {
+                        let pending = project(PendingAppend { tx: (), event_ids, value }).await?;
+                        return Ok((pending.event_ids, pending.value));
+                    }
+                    Err(e) if e.is_concurrency_conflict() => continue,
+                    Err(e) => return Err(e.into()),
+                }
+            }
+
+            Err(EventStoreError::MaxRetriesExceeded.into())
+        })
+    }
+
+    async fn store_result_in_tx<R: Serialize + Sync>(
+        &self,
+        _tx: &mut Self::Tx,
+        idempotency: &IdempotencyRequest,
+        result: &R,
+    ) -> Result<(), EventStoreError> {
+        let result = serde_json::to_value(result)?;
+        self.lock().results.insert((idempotency.scope, idempotency.key), result);
+        Ok(())
+    }
+
+    async fn stored_result<R: DeserializeOwned + Send>(
+        &self,
+        idempotency: &IdempotencyRequest,
+    ) -> Result<Option<R>, EventStoreError> {
+        let key = (idempotency.scope, idempotency.key);
+        let state = self.lock();
+        let Some((command_hash, _)) = state.idempotency_keys.get(&key) else {
+            return Ok(None);
+        };
+        if command_hash != &idempotency.command_hash {
+            return Err(EventStoreError::IdempotencyConflict(idempotency.key));
+        }
+
+        Ok(state.results.get(&key).cloned().map(serde_json::from_value).transpose()?)
+    }
+
+    async fn load_aggregate<A>(&self, aggregate_id: Uuid) -> Result<Option<A>, EventStoreError>
+    where
+        A: Aggregate + DeserializeOwned + Default + Serialize + Send,
+        A::Event: DeserializeOwned + Send,
+    {
+        let (snapshot, mut events) = {
+            let state = self.lock();
+            let snapshot = state.snapshots.get(&(A::aggregate_type(), aggregate_id)).cloned();
+            let from_version = snapshot.as_ref().map_or(0, |(version, _)| *version);
+            let events: Vec<StoredEvent> = state
+                .events
+                .iter()
+                .filter(|e| e.aggregate_id == aggregate_id && e.version > from_version)
+                .cloned()
+                .collect();
+            (snapshot, events)
+        };
+        if snapshot.is_none() && events.is_empty() {
+            return Ok(None);
+        }
+        events.sort_by_key(|e| e.version);
+
+        let mut aggregate = match snapshot {
+            Some((_, state)) => serde_json::from_value(state)?,
+            None => A::default(),
+        };
+        for stored in events {
+            let event: A::Event = serde_json::from_value(stored.event_data).map_err(|e| {
+                EventStoreError::InvalidEventData(format!(
+                    "event {} ({} v{}): {}",
+                    stored.id, stored.event_type, stored.version, e
+                ))
+            })?;
+            aggregate = aggregate.apply(event);
+        }
+
+        Ok(Some(aggregate))
+    }
+
+    async fn save_snapshot<A>(&self, aggregate: &A) -> Result<bool, EventStoreError>
+    where
+        A: Aggregate + Serialize + Sync,
+    {
+        let state = serde_json::to_value(aggregate)?;
+        let mut store = self.lock();
+
+        // An existing snapshot at the same or a later version is kept
+        let key = (A::aggregate_type(), aggregate.id());
+        if store
+            .snapshots
+            .get(&key)
+            .is_some_and(|(version, _)| *version >= aggregate.version())
+        {
+            return Ok(false);
+        }
+        store.snapshots.insert(key, (aggregate.version(), state));
+
+        Ok(true)
+    }
+
+    async fn current_version(&self, aggregate_id: Uuid) -> Result<i64, EventStoreError> {
+        Ok(self.lock().current_version(aggregate_id))
+    }
+
+    async fn get_event(&self, event_id: Uuid) -> Result<Option<StoredEvent>, EventStoreError> {
+        Ok(self.lock().events.iter().find(|e| e.id == event_id).cloned())
+    }
+
+    async fn get_events(&self, aggregate_id: Uuid) -> Result<Vec<StoredEvent>, EventStoreError> {
+        let mut events: Vec<StoredEvent> = self
+            .lock()
+            .events
+            .iter()
+            .filter(|e| e.aggregate_id == aggregate_id)
+            .cloned()
+            .collect();
+        events.sort_by_key(|e| e.version);
+        Ok(events)
+    }
+
+    async fn head_sequence(&self) -> Result<i64, EventStoreError> {
+        Ok(self.lock().events.len() as i64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregate::Account;
+    use crate::domain::{AccountType, Amount};
+    use rust_decimal::Decimal;
+
+    fn open_account(store: &InMemoryEventStore) -> Account {
+        let (account, event) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        let op = AggregateOperation::new("Account", account.id(), 0, event.event_type(), &event).unwrap();
+        store.try_append(&[op], None, &OperationContext::new()).unwrap();
+        account
+    }
+
+    #[tokio::test]
+    async fn test_append_and_load_aggregate() {
+        let store = InMemoryEventStore::new();
+        let account = open_account(&store);
+
+        let loaded: Account = store.load_aggregate(account.id()).await.unwrap().unwrap();
+        assert_eq!(loaded.id(), account.id());
+        assert_eq!(loaded.version(), 1);
+        assert!(store.load_aggregate::<Account>(Uuid::new_v4()).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stale_version_appends_nothing() {
+        let store = InMemoryEventStore::new();
+        let account = open_account(&store);
+        let event = account
+            .credit(&Amount::new(Decimal::ONE).unwrap(), Uuid::new_v4(), "Test".to_string())
+            .unwrap();
+        let op =
+            |version| AggregateOperation::new("Account", account.id(), version, event.event_type(), &event).unwrap();
+
+        let result = store
+            .append_atomic(vec![op(1), op(1)], None, &OperationContext::new())
+            .await;
+        assert!(matches!(
+            result,
+            Err(EventStoreError::ConcurrencyConflict {
+                expected: 1,
+                actual: 2,
+                ..
+            })
+        ));
+        assert_eq!(store.events().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_load_aggregate_from_snapshot() {
+        let store = InMemoryEventStore::new();
+        let account = open_account(&store);
+        let amount = Amount::new(Decimal::from(5)).unwrap();
+
+        let credited = account.credit(&amount, Uuid::new_v4(), "Test".to_string()).unwrap();
+        let op = AggregateOperation::new("Account", account.id(), 1, credited.event_type(), &credited).unwrap();
+        store
+            .append_atomic(vec![op], None, &OperationContext::new())
+            .await
+            .unwrap();
+        let account = account.apply(credited);
+
+        assert!(store.save_snapshot(&account).await.unwrap());
+        assert!(!store.save_snapshot(&account).await.unwrap());
+
+        // Events after the snapshot are replayed on top of it
+        let credited = account.credit(&amount, Uuid::new_v4(), "Test".to_string()).unwrap();
+        let op = AggregateOperation::new("Account", account.id(), 2, credited.event_type(), &credited).unwrap();
+        store
+            .append_atomic(vec![op], None, &OperationContext::new())
+            .await
+            .unwrap();
+
+        let loaded: Account = store.load_aggregate(account.id()).await.unwrap().unwrap();
+        assert_eq!(loaded.version(), 3);
+        assert_eq!(loaded.balance().value(), Decimal::from(10));
+        assert_eq!(store.get_events(account.id()).await.unwrap().len(), 3);
+        assert_eq!(store.head_sequence().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_append_replays_first_event() {
+        let store = InMemoryEventStore::new();
+        let key = Uuid::new_v4();
+        let context = OperationContext::new();
+        let request = IdempotencyRequest::for_command(key, &"create", &context).unwrap();
+
+        let (account, event) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        let op = || AggregateOperation::new("Account", account.id(), 0, event.event_type(), &event).unwrap();
+        let first = store.append_atomic(vec![op()], Some(&request), &context).await.unwrap();
+        let replay = store.append_atomic(vec![op()], Some(&request), &context).await.unwrap();
+        assert_eq!(first, replay);
+        assert_eq!(store.events().len(), 1);
+
+        let other = IdempotencyRequest::for_command(key, &"other", &context).unwrap();
+        let result = store.append_atomic(vec![op()], Some(&other), &context).await;
+        assert!(matches!(result, Err(EventStoreError::IdempotencyConflict(k)) if k == key));
+
+        // The same key sent by another API key is a new request, not a replay
+        let foreign_context = OperationContext::new().with_api_key(Uuid::new_v4());
+        let foreign = IdempotencyRequest::for_command(key, &"create", &foreign_context).unwrap();
+        let result = store.append_atomic(vec![op()], Some(&foreign), &foreign_context).await;
+        assert!(matches!(result, Err(EventStoreError::ConcurrencyConflict { .. })));
+    }
+}
@@ -3,8 +3,22 @@
 //! Persistence layer for Event Sourcing.
 //! Handles storing and retrieving events from PostgreSQL.
 
+mod dead_letter;
 mod error;
+mod export;
+mod memory;
+mod notifications;
 mod repository;
+mod subscription;
+mod traits;
 
+pub use dead_letter::{DeadLetterEvent, DeadLetterRepository, PoisonEventPolicy};
 pub use error::EventStoreError;
-pub use repository::{EventStore, AggregateOperation, StoredEvent};
+pub use export::{export_ndjson, EventExportFilter};
+pub use memory::InMemoryEventStore;
+pub use notifications::{EventNotification, EventNotifier, EVENTS_CHANNEL};
+pub use repository::{
+    AggregateOperation, EventStore, IdempotencyRequest, IsolationLevel, PendingAppend, StoredEvent,
+};
+pub use subscription::{Subscription, SubscriptionStatus};
+pub use traits::{BoxFuture, EventStoreTrait};
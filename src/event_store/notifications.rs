@@ -0,0 +1,110 @@
+//! Event Notifications
+//!
+//! Fan-out of newly committed events published by the notify_events_appended
+//! trigger (migration 012). A single LISTEN connection feeds a broadcast
+//! channel that any number of in-process subscribers can read from.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use sqlx::PgPool;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// NOTIFY channel used by the events trigger
+pub const EVENTS_CHANNEL: &str = "events";
+
+/// Buffered notifications per subscriber before it starts lagging
+const BROADCAST_CAPACITY: usize = 1024;
+
+/// Metadata of an appended event (event_data is not included)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventNotification {
+    pub id: Uuid,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Broadcasts event notifications to in-process subscribers
+#[derive(Debug, Clone)]
+pub struct EventNotifier {
+    sender: broadcast::Sender<EventNotification>,
+}
+
+impl Default for EventNotifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EventNotifier {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self { sender }
+    }
+
+    /// Receive notifications published after this call
+    pub fn subscribe(&self) -> broadcast::Receiver<EventNotification> {
+        self.sender.subscribe()
+    }
+
+    /// Start the LISTEN loop in the background.
+    /// The listener reconnects on connection loss; notifications sent while
+    /// disconnected are lost.
+    pub fn listen(&self, pool: PgPool) -> tokio::task::JoinHandle<()> {
+        let sender = self.sender.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = forward_notifications(&pool, &sender).await {
+                    tracing::error!(error = %e, "Event notification listener failed, retrying");
+                }
+                tokio::time::sleep(Duration::from_secs(5)).await;
+            }
+        })
+    }
+}
+
+/// LISTEN on the events channel and forward parsed payloads
+async fn forward_notifications(
+    pool: &PgPool,
+    sender: &broadcast::Sender<EventNotification>,
+) -> Result<(), sqlx::Error> {
+    let mut listener = PgListener::connect_with(pool).await?;
+    listener.listen(EVENTS_CHANNEL).await?;
+    tracing::info!(channel = EVENTS_CHANNEL, "Listening for event notifications");
+
+    loop {
+        let notification = listener.recv().await?;
+        match serde_json::from_str::<EventNotification>(notification.payload()) {
+            // No receivers is not an error: nobody is streaming right now
+            Ok(event) => {
+                let _ = sender.send(event);
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, payload = notification.payload(), "Invalid event notification");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_notification_from_trigger_payload() {
+        // Shape produced by json_build_object() in notify_event_appended()
+        let payload = r#"{"id" : "8a3f4c3e-2a7d-4c1b-9a4f-3d2b1c0e9f8a", "aggregate_type" : "Account", "aggregate_id" : "247bcf70-0000-4000-8000-000000000000", "event_type" : "MoneyCredited", "version" : 3, "created_at" : "2026-10-16T11:46:24.127331+00:00"}"#;
+
+        let event: EventNotification = serde_json::from_str(payload).unwrap();
+
+        assert_eq!(event.aggregate_type, "Account");
+        assert_eq!(event.event_type, "MoneyCredited");
+        assert_eq!(event.version, 3);
+    }
+}
@@ -6,18 +6,26 @@
 use chrono::{DateTime, Utc};
 use serde::{de::DeserializeOwned, Serialize};
 use sqlx::{PgPool, Postgres, Transaction};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use uuid::Uuid;
 
 use crate::aggregate::Aggregate;
 use crate::domain::OperationContext;
+use crate::idempotency::IdempotencyScope;
+use crate::tenants::DEFAULT_TENANT_ID;
 
-use super::EventStoreError;
+use super::dead_letter::record_dead_letter;
+use super::{EventExportFilter, EventStoreError, PoisonEventPolicy};
 
 /// Stored event from the database
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StoredEvent {
     pub id: Uuid,
+    /// Position in the global event stream
+    pub global_sequence: i64,
     pub aggregate_type: String,
     pub aggregate_id: Uuid,
     pub version: i64,
@@ -28,6 +36,21 @@ pub struct StoredEvent {
     pub created_at: DateTime<Utc>,
 }
 
+/// Advisory lock key appends hold shared from taking their global_sequence
+/// values to commit. Readers of the global stream take it exclusively to wait
+/// out appends in flight before reading past a gap (see
+/// [`EventStore::stable_sequence`]).
+const EVENT_APPEND_LOCK_KEY: i64 = 0x6576_656e_7473; // "events"
+
+/// Events appended by one attempt of [`EventStore::append_with_retry_in_tx`],
+/// in their still-open transaction
+pub struct PendingAppend<T, Tx = Transaction<'static, Postgres>> {
+    pub tx: Tx,
+    pub event_ids: Vec<Uuid>,
+    /// Value returned by the attempt's `build`
+    pub value: T,
+}
+
 /// Operation to be performed on an aggregate
 #[derive(Debug)]
 pub struct AggregateOperation {
@@ -58,53 +81,232 @@ impl AggregateOperation {
     }
 }
 
+/// Idempotency key supplied with a command, the caller it belongs to, plus a
+/// hash of the command payload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyRequest {
+    pub key: Uuid,
+    pub scope: IdempotencyScope,
+    pub command_hash: String,
+}
+
+impl IdempotencyRequest {
+    /// Build from a key, the command it accompanies and the caller's context
+    pub fn for_command<C: Serialize>(
+        key: Uuid,
+        command: &C,
+        context: &OperationContext,
+    ) -> Result<Self, EventStoreError> {
+        use sha2::{Digest, Sha256};
+        let payload = serde_json::to_vec(command)?;
+        Ok(Self {
+            key,
+            scope: IdempotencyScope::from_context(context),
+            command_hash: hex::encode(Sha256::digest(&payload)),
+        })
+    }
+}
+
+/// Constraints of event_versions that reject a stale or skipped version
+const EVENT_VERSION_CONSTRAINTS: [&str; 2] = ["event_versions_pkey", "event_versions_previous_fkey"];
+
+/// Check whether an insert into events failed because its version was
+/// already taken or does not follow the aggregate's current version
+fn is_version_violation(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|db| db.constraint())
+        .is_some_and(|constraint| EVENT_VERSION_CONSTRAINTS.contains(&constraint))
+}
+
+/// Transaction isolation level used for appends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Sufficient on its own: event_versions constraints reject a stale or
+    /// concurrently claimed version at any isolation level
+    #[default]
+    ReadCommitted,
+    RepeatableRead,
+    Serializable,
+}
+
+impl IsolationLevel {
+    pub fn as_sql(&self) -> &'static str {
+        match self {
+            IsolationLevel::ReadCommitted => "READ COMMITTED",
+            IsolationLevel::RepeatableRead => "REPEATABLE READ",
+            IsolationLevel::Serializable => "SERIALIZABLE",
+        }
+    }
+}
+
+impl std::str::FromStr for IsolationLevel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().replace(['-', ' '], "_").as_str() {
+            "read_committed" => Ok(IsolationLevel::ReadCommitted),
+            "repeatable_read" => Ok(IsolationLevel::RepeatableRead),
+            "serializable" => Ok(IsolationLevel::Serializable),
+            _ => Err(format!("unknown isolation level: {}", s)),
+        }
+    }
+}
+
 /// Event Store for persisting and retrieving events
 #[derive(Debug, Clone)]
 pub struct EventStore {
     pool: PgPool,
+    isolation: IsolationLevel,
+    poison_policy: PoisonEventPolicy,
+    /// Retries after concurrency conflicts, shared between clones
+    conflict_retries: Arc<AtomicU64>,
 }
 
 impl EventStore {
     /// Create a new EventStore with a database pool
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            isolation: IsolationLevel::default(),
+            poison_policy: PoisonEventPolicy::default(),
+            conflict_retries: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Use the given isolation level for append transactions
+    pub fn with_isolation(mut self, isolation: IsolationLevel) -> Self {
+        self.isolation = isolation;
+        self
+    }
+
+    pub fn isolation(&self) -> IsolationLevel {
+        self.isolation
+    }
+
+    /// Use the given policy for events that fail to deserialize during replay
+    pub fn with_poison_policy(mut self, policy: PoisonEventPolicy) -> Self {
+        self.poison_policy = policy;
+        self
+    }
+
+    /// Retries `append_with_retry` made after concurrency conflicts since
+    /// this store (or the store it was cloned from) was created
+    pub fn conflict_retries(&self) -> u64 {
+        self.conflict_retries.load(Ordering::Relaxed)
     }
 
     // =========================================================================
-    // M078: append_atomic with retry
+    // M078: append_atomic / append_with_retry
     // =========================================================================
 
-    /// Atomically append events across multiple aggregates with retry
+    /// Atomically append events across multiple aggregates (single attempt).
+    ///
+    /// Fails with `ConcurrencyConflict` if any aggregate moved past its
+    /// expected version. Retrying the same operations cannot succeed, so
+    /// callers that want retries should use [`EventStore::append_with_retry`].
     pub async fn append_atomic(
         &self,
         operations: Vec<AggregateOperation>,
-        idempotency_key: Option<Uuid>,
+        idempotency: Option<&IdempotencyRequest>,
         context: &OperationContext,
     ) -> Result<Vec<Uuid>, EventStoreError> {
-        const MAX_RETRIES: u32 = 3;
+        self.try_append_atomic(&operations, idempotency, context).await
+    }
+
+    /// Build operations from fresh state and append them, retrying on
+    /// concurrency conflicts.
+    ///
+    /// `build` runs before every attempt and must (re)load the aggregates it
+    /// touches, so a retry uses their current versions and re-runs domain
+    /// checks such as balance validation. Besides the operations it returns a
+    /// value (e.g. the loaded aggregates and generated events) that is handed
+    /// back with the event IDs of the successful attempt. Errors from `build`
+    /// are returned immediately; exhausting the attempts yields
+    /// `MaxRetriesExceeded`.
+    pub async fn append_with_retry<T, E, F, Fut>(
+        &self,
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+        build: F,
+    ) -> Result<(Vec<Uuid>, T), E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>>,
+        E: From<EventStoreError>,
+    {
+        self.append_with_retry_in_tx(idempotency, context, build, |pending| {
+            std::future::ready(Ok(pending))
+        })
+        .await
+    }
+
+    /// Like [`EventStore::append_with_retry`], but runs `project` in the
+    /// append transaction before it commits, so read models updated there
+    /// (e.g. `ProjectionService::apply_transfer_in_tx`) commit together with
+    /// the events. A crash between the two can no longer leave stale
+    /// balances. Errors from `project` roll the attempt back and are returned
+    /// immediately. `project` is skipped when the idempotency key replays an
+    /// earlier call, whose events were projected when they were appended.
+    pub async fn append_with_retry_in_tx<T, E, F, Fut, P, PFut>(
+        &self,
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+        mut build: F,
+        mut project: P,
+    ) -> Result<(Vec<Uuid>, T), E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>>,
+        P: FnMut(PendingAppend<T>) -> PFut,
+        PFut: Future<Output = Result<PendingAppend<T>, E>>,
+        E: From<EventStoreError>,
+    {
+        const MAX_ATTEMPTS: u32 = 3;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let (operations, value) = build().await?;
 
-        for attempt in 0..MAX_RETRIES {
-            match self
-                .try_append_atomic(&operations, idempotency_key, context)
+            let tx = self.begin().await?;
+            let conflict = match self
+                .append_or_replay_in_tx(tx, &operations, idempotency, context)
                 .await
             {
-                Ok(ids) => return Ok(ids),
-                Err(EventStoreError::ConcurrencyConflict { .. }) if attempt < MAX_RETRIES - 1 => {
-                    // Exponential backoff before retry
-                    let delay = Duration::from_millis(50 * (attempt as u64 + 1));
-                    tokio::time::sleep(delay).await;
-                    tracing::warn!(
-                        "Concurrency conflict, retrying (attempt {}/{})",
-                        attempt + 1,
-                        MAX_RETRIES
-                    );
-                    continue;
+                // Idempotent replay: the events were appended and projected
+                // by the original call, so there is nothing to project
+                Ok((tx, event_ids, true)) => {
+                    match tx.commit().await.map_err(EventStoreError::from) {
+                        Ok(()) => return Ok((event_ids, value)),
+                        Err(e) => e,
+                    }
                 }
-                Err(e) => return Err(e),
+                Ok((tx, event_ids, false)) => {
+                    let pending = project(PendingAppend { tx, event_ids, value }).await?;
+                    match pending.tx.commit().await.map_err(EventStoreError::from) {
+                        Ok(()) => return Ok((pending.event_ids, pending.value)),
+                        Err(e) => e,
+                    }
+                }
+                Err(e) => e,
+            };
+
+            if !conflict.is_concurrency_conflict() {
+                return Err(conflict.into());
             }
+            if attempt == MAX_ATTEMPTS {
+                break;
+            }
+            self.conflict_retries.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                error = %conflict,
+                "Concurrency conflict, reloading and retrying (attempt {}/{})",
+                attempt,
+                MAX_ATTEMPTS
+            );
+            // Linear backoff before rebuilding from fresh state
+            tokio::time::sleep(Duration::from_millis(50 * attempt as u64)).await;
         }
 
-        Err(EventStoreError::MaxRetriesExceeded)
+        Err(EventStoreError::MaxRetriesExceeded.into())
     }
 
     // =========================================================================
@@ -115,132 +317,223 @@ impl EventStore {
     async fn try_append_atomic(
         &self,
         operations: &[AggregateOperation],
-        idempotency_key: Option<Uuid>,
+        idempotency: Option<&IdempotencyRequest>,
         context: &OperationContext,
     ) -> Result<Vec<Uuid>, EventStoreError> {
-        let context_json = serde_json::to_value(context)?;
+        let tx = self.begin().await?;
+        let (tx, event_ids) = self
+            .append_atomic_in_tx(tx, operations, idempotency, context)
+            .await?;
+        tx.commit().await?;
+
+        Ok(event_ids)
+    }
 
-        // Start transaction with SERIALIZABLE isolation
+    /// Begin an append transaction at the configured isolation level.
+    /// Serialization failures surface as SerializationFailure and are
+    /// retried like version conflicts.
+    pub async fn begin(&self) -> Result<Transaction<'static, Postgres>, EventStoreError> {
         let mut tx = self.pool.begin().await?;
+        sqlx::query(&format!(
+            "SET TRANSACTION ISOLATION LEVEL {}",
+            self.isolation.as_sql()
+        ))
+        .execute(&mut *tx)
+        .await?;
+
+        Ok(tx)
+    }
+
+    /// Append events inside a transaction from [`EventStore::begin`] without
+    /// committing it, so the caller can update read models in the same
+    /// transaction and commit once.
+    ///
+    /// The transaction is taken by value and handed back on success: after a
+    /// version conflict it is aborted, so it is rolled back before the
+    /// current version is read.
+    pub async fn append_atomic_in_tx(
+        &self,
+        tx: Transaction<'static, Postgres>,
+        operations: &[AggregateOperation],
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> Result<(Transaction<'static, Postgres>, Vec<Uuid>), EventStoreError> {
+        let (tx, event_ids, _replayed) = self
+            .append_or_replay_in_tx(tx, operations, idempotency, context)
+            .await?;
+        Ok((tx, event_ids))
+    }
+
+    /// [`EventStore::append_atomic_in_tx`], also telling whether the
+    /// idempotency key was already completed (nothing was appended and the
+    /// only event ID is the one the original call recorded)
+    async fn append_or_replay_in_tx(
+        &self,
+        mut tx: Transaction<'static, Postgres>,
+        operations: &[AggregateOperation],
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> Result<(Transaction<'static, Postgres>, Vec<Uuid>, bool), EventStoreError> {
+        let idempotency_key = idempotency.map(|i| i.key);
+        let context_json = serde_json::to_value(context)?;
 
         // Check idempotency key if provided
-        if let Some(key) = idempotency_key {
-            if let Some(existing) = self.check_idempotency_key(&mut tx, key).await? {
+        if let Some(idempotency) = idempotency {
+            if let Some(existing) = self.check_idempotency_key(&mut tx, idempotency).await? {
                 // Already processed, return existing event ID
-                return Ok(vec![existing]);
+                return Ok((tx, vec![existing], true));
             }
         }
 
+        // Appends run concurrently, so sequence N+1 may commit before N.
+        // Holding the lock shared until commit lets readers wait for N
+        // instead of skipping it.
+        sqlx::query!("SELECT pg_advisory_xact_lock_shared($1)", EVENT_APPEND_LOCK_KEY)
+            .execute(&mut *tx)
+            .await?;
+
         let mut event_ids = Vec::with_capacity(operations.len());
 
         for (idx, op) in operations.iter().enumerate() {
-            // M079: Verify expected version (optimistic locking)
-            let current_version = self
-                .get_current_version(&mut tx, op.aggregate_id)
-                .await?;
-
-            if current_version != op.expected_version {
-                return Err(EventStoreError::ConcurrencyConflict {
-                    aggregate_id: op.aggregate_id,
-                    expected: op.expected_version,
-                    actual: current_version,
-                });
-            }
-
-            // Insert event
+            // M079: Optimistic locking. The insert claims (aggregate_id, version)
+            // in event_versions (migration 015); a stale or skipped
+            // expected_version violates its constraints.
             let new_version = op.expected_version + 1;
             let idem_key = if idx == 0 { idempotency_key } else { None };
 
-            let event_id: Uuid = sqlx::query_scalar(
+            let inserted: Result<Uuid, sqlx::Error> = sqlx::query_scalar!(
                 r#"
                 INSERT INTO events (
-                    aggregate_type, aggregate_id, version, 
-                    event_type, event_data, context, idempotency_key
+                    aggregate_type, aggregate_id, version,
+                    event_type, event_data, context, idempotency_key, tenant_id
+                )
+                VALUES (
+                    $1, $2, $3, $4, $5, $6, $7,
+                    COALESCE(
+                        $8,
+                        (SELECT tenant_id FROM accounts WHERE id = $2),
+                        (SELECT tenant_id FROM users WHERE id = $2),
+                        $9
+                    )
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
                 RETURNING id
                 "#,
+                op.aggregate_type,
+                op.aggregate_id,
+                new_version,
+                op.event_type,
+                op.event_data,
+                context_json,
+                idem_key,
+                context.tenant_id,
+                DEFAULT_TENANT_ID,
             )
-            .bind(&op.aggregate_type)
-            .bind(op.aggregate_id)
-            .bind(new_version)
-            .bind(&op.event_type)
-            .bind(&op.event_data)
-            .bind(&context_json)
-            .bind(idem_key)
             .fetch_one(&mut *tx)
-            .await?;
+            .await;
+
+            let event_id = match inserted {
+                Ok(id) => id,
+                Err(e) if is_version_violation(&e) => {
+                    // The transaction is aborted; read the actual version outside it
+                    drop(tx);
+                    return Err(EventStoreError::ConcurrencyConflict {
+                        aggregate_id: op.aggregate_id,
+                        expected: op.expected_version,
+                        actual: self.get_current_version(op.aggregate_id).await?,
+                    });
+                }
+                Err(e) => return Err(e.into()),
+            };
 
             event_ids.push(event_id);
         }
 
         // Mark idempotency key as completed
-        if let Some(key) = idempotency_key {
-            self.complete_idempotency_key(&mut tx, key, event_ids[0])
+        if let Some(idempotency) = idempotency {
+            self.complete_idempotency_key(&mut tx, idempotency, event_ids[0])
                 .await?;
         }
 
-        // Commit transaction
-        tx.commit().await?;
-
-        Ok(event_ids)
+        Ok((tx, event_ids, false))
     }
 
-    /// Get current version of an aggregate
-    async fn get_current_version(
-        &self,
-        tx: &mut Transaction<'_, Postgres>,
-        aggregate_id: Uuid,
-    ) -> Result<i64, EventStoreError> {
-        let result: Option<i64> = sqlx::query_scalar(
-            r#"
-            SELECT MAX(version) FROM events WHERE aggregate_id = $1
-            "#,
+    /// Get current version of an aggregate (0 when it has no events)
+    pub(super) async fn get_current_version(&self, aggregate_id: Uuid) -> Result<i64, EventStoreError> {
+        let result: Option<i64> = sqlx::query_scalar!(
+            "SELECT MAX(version) FROM event_versions WHERE aggregate_id = $1",
+            aggregate_id
         )
-        .bind(aggregate_id)
-        .fetch_optional(&mut **tx)
-        .await?
-        .flatten();
+        .fetch_one(&self.pool)
+        .await?;
 
         Ok(result.unwrap_or(0))
     }
 
-    /// Check if idempotency key exists and return event ID if completed
+    /// Check if idempotency key exists and return event ID if completed.
+    /// Fails with IdempotencyConflict if the key was used for a different command.
     async fn check_idempotency_key(
         &self,
         tx: &mut Transaction<'_, Postgres>,
-        key: Uuid,
+        idempotency: &IdempotencyRequest,
     ) -> Result<Option<Uuid>, EventStoreError> {
-        let result: Option<(String, Option<Uuid>)> = sqlx::query_as(
+        let key = idempotency.key;
+        let scope = idempotency.scope;
+        let result = sqlx::query!(
             r#"
-            SELECT processing_status, event_id 
-            FROM idempotency_keys 
-            WHERE key = $1
+            SELECT processing_status, event_id, command_hash
+            FROM idempotency_keys
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
             "#,
+            key,
+            scope.api_key_id,
+            scope.tenant_id
         )
-        .bind(key)
         .fetch_optional(&mut **tx)
         .await?;
 
+        if let Some(command_hash) = result.as_ref().and_then(|row| row.command_hash.as_ref()) {
+            if command_hash != &idempotency.command_hash {
+                return Err(EventStoreError::IdempotencyConflict(key));
+            }
+        }
+
         match result {
-            Some((status, event_id)) if status == "completed" => Ok(event_id),
-            Some((status, _)) if status == "processing" => {
-                // Another request is processing, treat as conflict
-                Err(EventStoreError::IdempotencyKeyExists(key))
+            Some(row) if row.processing_status == "completed" => Ok(row.event_id),
+            // Keys registered here are inserted and completed in the same transaction,
+            // so a committed 'processing' row was claimed by the idempotency middleware
+            // for this request (concurrent duplicates are rejected there)
+            Some(_) => {
+                // Processing, failed or pending: record which command owns the key
+                sqlx::query!(
+                    r#"
+                    UPDATE idempotency_keys SET command_hash = $4
+                    WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+                    "#,
+                    key,
+                    scope.api_key_id,
+                    scope.tenant_id,
+                    idempotency.command_hash
+                )
+                .execute(&mut **tx)
+                .await?;
+                Ok(None)
             }
-            Some(_) => Ok(None), // Failed or pending, can retry
             None => {
-                // Register new idempotency key with hash of the key itself
-                // (Full request body hashing should be done at API layer)
-                let request_hash = format!("{:x}", md5::compute(key.as_bytes()));
-                sqlx::query(
+                // Register new idempotency key (no HTTP request hash available,
+                // so the command hash doubles as request_hash)
+                sqlx::query!(
                     r#"
-                    INSERT INTO idempotency_keys (key, request_hash, processing_status, processing_started_at)
-                    VALUES ($1, $2, 'processing', NOW())
+                    INSERT INTO idempotency_keys (
+                        key, api_key_id, tenant_id, request_hash, command_hash,
+                        processing_status, processing_started_at
+                    )
+                    VALUES ($1, $2, $3, $4, $4, 'processing', NOW())
                     "#,
+                    key,
+                    scope.api_key_id,
+                    scope.tenant_id,
+                    idempotency.command_hash
                 )
-                .bind(key)
-                .bind(&request_hash)
                 .execute(&mut **tx)
                 .await?;
                 Ok(None)
@@ -252,24 +545,82 @@ impl EventStore {
     async fn complete_idempotency_key(
         &self,
         tx: &mut Transaction<'_, Postgres>,
-        key: Uuid,
+        idempotency: &IdempotencyRequest,
         event_id: Uuid,
     ) -> Result<(), EventStoreError> {
-        sqlx::query(
+        sqlx::query!(
             r#"
-            UPDATE idempotency_keys 
-            SET processing_status = 'completed', event_id = $2
-            WHERE key = $1
+            UPDATE idempotency_keys
+            SET processing_status = 'completed', event_id = $4
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
             "#,
+            idempotency.key,
+            idempotency.scope.api_key_id,
+            idempotency.scope.tenant_id,
+            event_id
         )
-        .bind(key)
-        .bind(event_id)
         .execute(&mut **tx)
         .await?;
 
         Ok(())
     }
 
+    /// Store the result of the command whose events were just appended
+    /// with `idempotency`, in the same transaction, for
+    /// [`EventStore::stored_result`]
+    pub async fn store_result_in_tx<R: Serialize>(
+        &self,
+        tx: &mut Transaction<'_, Postgres>,
+        idempotency: &IdempotencyRequest,
+        result: &R,
+    ) -> Result<(), EventStoreError> {
+        sqlx::query!(
+            r#"
+            UPDATE idempotency_keys SET command_result = $4
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+            "#,
+            idempotency.key,
+            idempotency.scope.api_key_id,
+            idempotency.scope.tenant_id,
+            serde_json::to_value(result)?
+        )
+        .execute(&mut **tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Result stored by an earlier call that completed the idempotency key,
+    /// if any. Fails with IdempotencyConflict if that call was a different
+    /// command.
+    pub async fn stored_result<R: DeserializeOwned>(
+        &self,
+        idempotency: &IdempotencyRequest,
+    ) -> Result<Option<R>, EventStoreError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT command_hash, command_result
+            FROM idempotency_keys
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+              AND processing_status = 'completed'
+            "#,
+            idempotency.key,
+            idempotency.scope.api_key_id,
+            idempotency.scope.tenant_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        if row.command_hash.is_some_and(|hash| hash != idempotency.command_hash) {
+            return Err(EventStoreError::IdempotencyConflict(idempotency.key));
+        }
+
+        Ok(row.command_result.map(serde_json::from_value).transpose()?)
+    }
+
     // =========================================================================
     // M081 & M082: load_aggregate with snapshot support
     // =========================================================================
@@ -287,33 +638,19 @@ impl EventStore {
         let (from_version, initial_state) = self.load_snapshot::<A>(aggregate_id).await?;
 
         // 2. Load events after snapshot version
-        let events: Vec<StoredEvent> = sqlx::query_as::<_, (Uuid, String, Uuid, i64, String, serde_json::Value, serde_json::Value, Option<Uuid>, DateTime<Utc>)>(
+        let events: Vec<StoredEvent> = sqlx::query_as!(
+            StoredEvent,
             r#"
-            SELECT id, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
             FROM events
             WHERE aggregate_id = $1 AND version > $2
             ORDER BY version ASC
             "#,
+            aggregate_id,
+            from_version
         )
-        .bind(aggregate_id)
-        .bind(from_version)
         .fetch_all(&self.pool)
-        .await?
-        .into_iter()
-        .map(|(id, agg_type, agg_id, version, event_type, event_data, context, idem_key, created_at)| {
-            StoredEvent {
-                id,
-                aggregate_type: agg_type,
-                aggregate_id: agg_id,
-                version,
-                event_type,
-                event_data,
-                context,
-                idempotency_key: idem_key,
-                created_at,
-            }
-        })
-        .collect();
+        .await?;
 
         // If no snapshot and no events, aggregate doesn't exist
         if initial_state.is_none() && events.is_empty() {
@@ -323,13 +660,104 @@ impl EventStore {
         // 3. Replay events on initial state
         let mut aggregate = initial_state.unwrap_or_default();
         for stored_event in events {
-            let event: A::Event = serde_json::from_value(stored_event.event_data)?;
-            aggregate = aggregate.apply(event);
+            if let Some(event) = self.decode_event::<A::Event>(stored_event).await? {
+                aggregate = aggregate.apply(event);
+            }
         }
 
         Ok(Some(aggregate))
     }
 
+    /// Load an aggregate as it was at a point in time, replaying only events
+    /// created at or before `at`. The snapshot is used when it predates `at`.
+    pub async fn load_aggregate_at<A>(
+        &self,
+        aggregate_id: Uuid,
+        at: DateTime<Utc>,
+    ) -> Result<Option<A>, EventStoreError>
+    where
+        A: Aggregate + DeserializeOwned + Default + Serialize,
+        A::Event: DeserializeOwned,
+    {
+        // 1. Use the snapshot only if its last event is not after `at`
+        let (mut from_version, mut initial_state) = self.load_snapshot::<A>(aggregate_id).await?;
+        if initial_state.is_some() {
+            let snapshot_event_at: Option<DateTime<Utc>> = sqlx::query_scalar!(
+                "SELECT created_at FROM events WHERE aggregate_id = $1 AND version = $2",
+                aggregate_id,
+                from_version
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if snapshot_event_at.is_none_or(|created_at| created_at > at) {
+                from_version = 0;
+                initial_state = None;
+            }
+        }
+
+        // 2. Load events after the snapshot up to `at`
+        let events: Vec<StoredEvent> = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            FROM events
+            WHERE aggregate_id = $1 AND version > $2 AND created_at <= $3
+            ORDER BY version ASC
+            "#,
+            aggregate_id,
+            from_version,
+            at
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // Aggregate did not exist yet at `at`
+        if initial_state.is_none() && events.is_empty() {
+            return Ok(None);
+        }
+
+        // 3. Replay events on initial state
+        let mut aggregate = initial_state.unwrap_or_default();
+        for stored_event in events {
+            if let Some(event) = self.decode_event::<A::Event>(stored_event).await? {
+                aggregate = aggregate.apply(event);
+            }
+        }
+
+        Ok(Some(aggregate))
+    }
+
+    /// Deserialize a stored event for replay. Under `PoisonEventPolicy::Skip`
+    /// an event that fails is dead-lettered and None is returned.
+    async fn decode_event<E: DeserializeOwned>(
+        &self,
+        stored_event: StoredEvent,
+    ) -> Result<Option<E>, EventStoreError> {
+        let error = match serde_json::from_value(stored_event.event_data.clone()) {
+            Ok(event) => return Ok(Some(event)),
+            Err(e) => e,
+        };
+
+        match self.poison_policy {
+            PoisonEventPolicy::Fail => Err(EventStoreError::InvalidEventData(format!(
+                "event {} ({} v{}): {}",
+                stored_event.id, stored_event.event_type, stored_event.version, error
+            ))),
+            PoisonEventPolicy::Skip => {
+                tracing::error!(
+                    event_id = %stored_event.id,
+                    aggregate_id = %stored_event.aggregate_id,
+                    version = stored_event.version,
+                    error = %error,
+                    "Skipping undeserializable event during replay"
+                );
+                record_dead_letter(&self.pool, &stored_event, &error.to_string()).await?;
+                Ok(None)
+            }
+        }
+    }
+
     /// Load snapshot for an aggregate
     async fn load_snapshot<A>(
         &self,
@@ -338,22 +766,22 @@ impl EventStore {
     where
         A: Aggregate + DeserializeOwned,
     {
-        let result: Option<(i64, serde_json::Value)> = sqlx::query_as(
+        let result = sqlx::query!(
             r#"
-            SELECT version, state 
+            SELECT version, state
             FROM event_snapshots
             WHERE aggregate_type = $1 AND aggregate_id = $2
             "#,
+            A::aggregate_type(),
+            aggregate_id
         )
-        .bind(A::aggregate_type())
-        .bind(aggregate_id)
         .fetch_optional(&self.pool)
         .await?;
 
         match result {
-            Some((version, state)) => {
-                let aggregate: A = serde_json::from_value(state)?;
-                Ok((version, Some(aggregate)))
+            Some(snapshot) => {
+                let aggregate: A = serde_json::from_value(snapshot.state)?;
+                Ok((snapshot.version, Some(aggregate)))
             }
             None => Ok((0, None)),
         }
@@ -375,23 +803,42 @@ impl EventStore {
             return Ok(false);
         }
 
+        self.save_snapshot(aggregate).await
+    }
+
+    /// Save a snapshot of the aggregate at its current version.
+    /// An existing snapshot at the same or a later version is kept, and
+    /// aggregates with unresolved dead-letter events (replayed without them)
+    /// are not snapshotted; returns whether the snapshot was written.
+    pub async fn save_snapshot<A>(&self, aggregate: &A) -> Result<bool, EventStoreError>
+    where
+        A: Aggregate + Serialize,
+    {
         let state = serde_json::to_value(aggregate)?;
 
-        sqlx::query(
+        let result = sqlx::query!(
             r#"
             INSERT INTO event_snapshots (aggregate_type, aggregate_id, version, state)
-            VALUES ($1, $2, $3, $4)
-            ON CONFLICT (aggregate_type, aggregate_id) 
+            SELECT $1, $2, $3, $4
+            WHERE NOT EXISTS (
+                SELECT 1 FROM dead_letter_events WHERE aggregate_id = $2 AND resolved_at IS NULL
+            )
+            ON CONFLICT (aggregate_type, aggregate_id)
             DO UPDATE SET version = $3, state = $4, created_at = NOW()
+            WHERE event_snapshots.version < $3
             "#,
+            A::aggregate_type(),
+            aggregate.id(),
+            aggregate.version(),
+            state
         )
-        .bind(A::aggregate_type())
-        .bind(aggregate.id())
-        .bind(aggregate.version())
-        .bind(state)
         .execute(&self.pool)
         .await?;
 
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
         tracing::info!(
             "Snapshot saved for {} aggregate {} at version {}",
             A::aggregate_type(),
@@ -402,37 +849,186 @@ impl EventStore {
         Ok(true)
     }
 
+    // =========================================================================
+    // Global stream
+    // =========================================================================
+
+    /// Read the global event stream in order, starting after `after_sequence`.
+    ///
+    /// A gap in the returned sequences may belong to an append still in
+    /// flight; the stream is then only read up to [`EventStore::stable_sequence`],
+    /// so no event is ever delivered after a later one.
+    pub async fn read_all(
+        &self,
+        after_sequence: i64,
+        limit: i64,
+    ) -> Result<Vec<StoredEvent>, EventStoreError> {
+        let events = self.read_range(after_sequence, i64::MAX, limit).await?;
+        let contiguous = events
+            .iter()
+            .zip(after_sequence + 1..)
+            .all(|(event, expected)| event.global_sequence == expected);
+        if contiguous {
+            return Ok(events);
+        }
+
+        let stable = self.stable_sequence().await?;
+        self.read_range(after_sequence, stable, limit).await
+    }
+
+    /// Events with `after_sequence < global_sequence <= until_sequence`, in order
+    async fn read_range(
+        &self,
+        after_sequence: i64,
+        until_sequence: i64,
+        limit: i64,
+    ) -> Result<Vec<StoredEvent>, EventStoreError> {
+        let events = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            FROM events
+            WHERE global_sequence > $1 AND global_sequence <= $2
+            ORDER BY global_sequence ASC
+            LIMIT $3
+            "#,
+            after_sequence,
+            until_sequence,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Highest global_sequence up to which the stream is final: every lower
+    /// sequence is committed or belonged to a rolled-back append and stays a
+    /// gap. Waits for the appends in flight; appends starting meanwhile wait
+    /// in turn and then take higher sequences.
+    pub async fn stable_sequence(&self) -> Result<i64, EventStoreError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", EVENT_APPEND_LOCK_KEY)
+            .execute(&mut *tx)
+            .await?;
+        let sequence = sqlx::query!(
+            r#"SELECT last_value AS "last_value!", is_called AS "is_called!" FROM events_global_sequence_seq"#
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        Ok(if sequence.is_called {
+            sequence.last_value
+        } else {
+            sequence.last_value - 1
+        })
+    }
+
+    /// Read the global event stream after `after_sequence`, restricted to an
+    /// aggregate type and created_at range (used by the NDJSON export).
+    /// Matching events are not contiguous, so this always stops at
+    /// [`EventStore::stable_sequence`].
+    pub async fn read_filtered(
+        &self,
+        after_sequence: i64,
+        filter: &EventExportFilter,
+        limit: i64,
+    ) -> Result<Vec<StoredEvent>, EventStoreError> {
+        let stable = self.stable_sequence().await?;
+        let events = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            FROM events
+            WHERE global_sequence > $1 AND global_sequence <= $2
+              AND ($3::text IS NULL OR aggregate_type = $3)
+              AND ($4::timestamptz IS NULL OR created_at >= $4)
+              AND ($5::timestamptz IS NULL OR created_at < $5)
+            ORDER BY global_sequence ASC
+            LIMIT $6
+            "#,
+            after_sequence,
+            stable,
+            filter.aggregate_type,
+            filter.from_date,
+            filter.to_date,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Sequence of the most recently appended event (0 when empty)
+    pub async fn head_sequence(&self) -> Result<i64, EventStoreError> {
+        let head: Option<i64> = sqlx::query_scalar!("SELECT MAX(global_sequence) FROM events")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(head.unwrap_or(0))
+    }
+
+    /// Get a single event by ID (for debugging/auditing)
+    pub async fn get_event(&self, event_id: Uuid) -> Result<Option<StoredEvent>, EventStoreError> {
+        let event = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            FROM events
+            WHERE id = $1
+            "#,
+            event_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(event)
+    }
+
     /// Get all events for an aggregate (for debugging/auditing)
     pub async fn get_events(
         &self,
         aggregate_id: Uuid,
     ) -> Result<Vec<StoredEvent>, EventStoreError> {
-        let events: Vec<StoredEvent> = sqlx::query_as::<_, (Uuid, String, Uuid, i64, String, serde_json::Value, serde_json::Value, Option<Uuid>, DateTime<Utc>)>(
+        let events: Vec<StoredEvent> = sqlx::query_as!(
+            StoredEvent,
             r#"
-            SELECT id, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
             FROM events
             WHERE aggregate_id = $1
             ORDER BY version ASC
             "#,
+            aggregate_id
         )
-        .bind(aggregate_id)
         .fetch_all(&self.pool)
-        .await?
-        .into_iter()
-        .map(|(id, agg_type, agg_id, version, event_type, event_data, context, idem_key, created_at)| {
-            StoredEvent {
-                id,
-                aggregate_type: agg_type,
-                aggregate_id: agg_id,
-                version,
-                event_type,
-                event_data,
-                context,
-                idempotency_key: idem_key,
-                created_at,
-            }
-        })
-        .collect();
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Events appended under a correlation ID (context.correlation_id),
+    /// in stream order
+    pub async fn get_events_by_correlation(
+        &self,
+        correlation_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<StoredEvent>, EventStoreError> {
+        let events: Vec<StoredEvent> = sqlx::query_as!(
+            StoredEvent,
+            r#"
+            SELECT id, global_sequence, aggregate_type, aggregate_id, version, event_type, event_data, context, idempotency_key, created_at
+            FROM events
+            WHERE context->>'correlation_id' = $1
+            ORDER BY global_sequence ASC
+            LIMIT $2
+            "#,
+            correlation_id.to_string(),
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
         Ok(events)
     }
@@ -446,15 +1042,25 @@ impl EventStore {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_isolation_level_from_str() {
+        assert_eq!("serializable".parse(), Ok(IsolationLevel::Serializable));
+        assert_eq!("REPEATABLE READ".parse(), Ok(IsolationLevel::RepeatableRead));
+        assert_eq!("read-committed".parse(), Ok(IsolationLevel::ReadCommitted));
+        assert!("snapshot".parse::<IsolationLevel>().is_err());
+        assert_eq!(IsolationLevel::default().as_sql(), "READ COMMITTED");
+    }
+
     #[test]
     fn test_aggregate_operation_new() {
-        use crate::domain::AccountEvent;
+        use crate::domain::{AccountEvent, AccountType};
         use chrono::Utc;
 
         let event = AccountEvent::AccountCreated {
             account_id: Uuid::new_v4(),
             user_id: Uuid::new_v4(),
-            account_type: "user_wallet".to_string(),
+            account_type: AccountType::UserWallet,
+            name: None,
             created_at: Utc::now(),
         };
 
@@ -485,4 +1091,20 @@ mod tests {
         let not_found = EventStoreError::AggregateNotFound(Uuid::new_v4());
         assert!(!not_found.is_retryable());
     }
+
+    #[test]
+    fn test_idempotency_request_for_command() {
+        let key = Uuid::new_v4();
+        let context = OperationContext::new().with_api_key(Uuid::new_v4());
+        let first = IdempotencyRequest::for_command(key, &serde_json::json!({ "amount": "100" }), &context).unwrap();
+        let same = IdempotencyRequest::for_command(key, &serde_json::json!({ "amount": "100" }), &context).unwrap();
+        let different =
+            IdempotencyRequest::for_command(key, &serde_json::json!({ "amount": "200" }), &context).unwrap();
+
+        assert_eq!(first.key, key);
+        assert_eq!(first.scope.api_key_id, context.api_key_id);
+        assert_eq!(first.command_hash.len(), 64);
+        assert_eq!(first, same);
+        assert_ne!(first.command_hash, different.command_hash);
+    }
 }
@@ -0,0 +1,149 @@
+//! Catch-up Subscriptions
+//!
+//! Lets internal consumers process the global event stream in order and
+//! resume where they left off. Each subscription stores the global_sequence
+//! of its last processed event in the subscriptions table (migration 014).
+//!
+//! Delivery is at-least-once: events processed after the last checkpoint are
+//! replayed after a crash, so handlers must be idempotent.
+
+use std::future::Future;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+
+use super::{EventStore, EventStoreError, StoredEvent};
+
+/// Checkpoint and lag of a subscription
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriptionStatus {
+    pub name: String,
+    pub last_sequence: i64,
+    /// Distance to the head of the stream (sequences may have gaps)
+    pub lag: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Named consumer of the global event stream
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    name: String,
+    position: i64,
+    event_store: EventStore,
+    pool: PgPool,
+}
+
+impl Subscription {
+    /// Load (or register) a subscription; new subscriptions start at the
+    /// beginning of the stream
+    pub async fn open(pool: PgPool, name: &str) -> Result<Self, EventStoreError> {
+        let position: i64 = sqlx::query_scalar!(
+            r#"
+            INSERT INTO subscriptions (name)
+            VALUES ($1)
+            ON CONFLICT (name) DO UPDATE SET name = EXCLUDED.name
+            RETURNING last_sequence
+            "#,
+            name
+        )
+        .fetch_one(&pool)
+        .await?;
+
+        Ok(Self {
+            name: name.to_string(),
+            position,
+            event_store: EventStore::new(pool.clone()),
+            pool,
+        })
+    }
+
+    /// Status of all registered subscriptions
+    pub async fn list(pool: &PgPool) -> Result<Vec<SubscriptionStatus>, EventStoreError> {
+        let head = EventStore::new(pool.clone()).head_sequence().await?;
+
+        let rows = sqlx::query!("SELECT name, last_sequence, updated_at FROM subscriptions ORDER BY name")
+            .fetch_all(pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SubscriptionStatus {
+                name: row.name,
+                last_sequence: row.last_sequence,
+                lag: (head - row.last_sequence).max(0),
+                updated_at: row.updated_at,
+            })
+            .collect())
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// global_sequence of the last processed event
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /// Next events after the current position (does not advance it)
+    pub async fn fetch(&self, limit: i64) -> Result<Vec<StoredEvent>, EventStoreError> {
+        self.event_store.read_all(self.position, limit).await
+    }
+
+    /// Record that every event up to `sequence` has been processed
+    pub async fn checkpoint(&mut self, sequence: i64) -> Result<(), EventStoreError> {
+        if sequence <= self.position {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE subscriptions
+            SET last_sequence = $2, updated_at = NOW()
+            WHERE name = $1
+            "#,
+            self.name,
+            sequence
+        )
+        .execute(&self.pool)
+        .await?;
+
+        self.position = sequence;
+        Ok(())
+    }
+
+    /// Process all available events in order, checkpointing after each batch.
+    /// On a handler error the checkpoint is moved to the last successful event
+    /// and the error is returned. Returns the number of events processed.
+    pub async fn catch_up<F, Fut, E>(&mut self, batch_size: i64, mut handler: F) -> Result<u64, E>
+    where
+        F: FnMut(StoredEvent) -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: From<EventStoreError>,
+    {
+        let mut processed = 0;
+
+        loop {
+            let batch = self.fetch(batch_size).await?;
+            let batch_len = batch.len() as i64;
+            let mut last_sequence = self.position;
+
+            for event in batch {
+                let sequence = event.global_sequence;
+                if let Err(e) = handler(event).await {
+                    self.checkpoint(last_sequence).await?;
+                    return Err(e);
+                }
+                last_sequence = sequence;
+                processed += 1;
+            }
+
+            self.checkpoint(last_sequence).await?;
+
+            if batch_len < batch_size {
+                return Ok(processed);
+            }
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! Event Store Trait
+//!
+//! The event store operations command handlers depend on, so handlers can
+//! run against the PG-backed [`EventStore`] or against
+//! [`super::InMemoryEventStore`] (tests, embedding without PostgreSQL).
+
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+use std::pin::Pin;
+use uuid::Uuid;
+
+use crate::aggregate::Aggregate;
+use crate::domain::OperationContext;
+
+use sqlx::{Postgres, Transaction};
+
+use super::{AggregateOperation, EventStore, EventStoreError, IdempotencyRequest, PendingAppend, StoredEvent};
+
+/// Boxed future returned by [`EventStoreTrait::append_with_retry`]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Event persistence used by command handlers
+pub trait EventStoreTrait: Send + Sync {
+    /// Transaction the `project` step of [`Self::append_with_retry_in_tx`]
+    /// runs in, together with the appended events
+    type Tx: Send;
+
+    /// Atomically append events across aggregates (single attempt)
+    fn append_atomic(
+        &self,
+        operations: Vec<AggregateOperation>,
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> impl Future<Output = Result<Vec<Uuid>, EventStoreError>> + Send;
+
+    /// Build operations from fresh state and append them, retrying on
+    /// concurrency conflicts (see [`EventStore::append_with_retry`]).
+    ///
+    /// Boxed: an opaque future over the caller's `build` closure cannot be
+    /// proven `Send` inside generic handlers.
+    fn append_with_retry<'a, T, E, F, Fut>(
+        &'a self,
+        idempotency: Option<&'a IdempotencyRequest>,
+        context: &'a OperationContext,
+        build: F,
+    ) -> BoxFuture<'a, Result<(Vec<Uuid>, T), E>>
+    where
+        T: Send + 'a,
+        F: FnMut() -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>> + Send + 'a,
+        E: From<EventStoreError> + Send + 'a;
+
+    /// Like [`Self::append_with_retry`], running `project` in the append's
+    /// transaction (see [`EventStore::append_with_retry_in_tx`]). `project`
+    /// is skipped on an idempotent replay.
+    fn append_with_retry_in_tx<'a, T, E, F, Fut, P, PFut>(
+        &'a self,
+        idempotency: Option<&'a IdempotencyRequest>,
+        context: &'a OperationContext,
+        build: F,
+        project: P,
+    ) -> BoxFuture<'a, Result<(Vec<Uuid>, T), E>>
+    where
+        T: Send + 'a,
+        F: FnMut() -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>> + Send + 'a,
+        P: FnMut(PendingAppend<T, Self::Tx>) -> PFut + Send + 'a,
+        PFut: Future<Output = Result<PendingAppend<T, Self::Tx>, E>> + Send + 'a,
+        E: From<EventStoreError> + Send + 'a;
+
+    /// Store the command result for replays of the idempotency key, in the
+    /// transaction that completes it
+    fn store_result_in_tx<R: Serialize + Sync>(
+        &self,
+        tx: &mut Self::Tx,
+        idempotency: &IdempotencyRequest,
+        result: &R,
+    ) -> impl Future<Output = Result<(), EventStoreError>> + Send;
+
+    /// Result stored by an earlier call that completed the idempotency key
+    fn stored_result<R: DeserializeOwned + Send>(
+        &self,
+        idempotency: &IdempotencyRequest,
+    ) -> impl Future<Output = Result<Option<R>, EventStoreError>> + Send;
+
+    /// Load an aggregate by replaying its events
+    fn load_aggregate<A>(&self, aggregate_id: Uuid) -> impl Future<Output = Result<Option<A>, EventStoreError>> + Send
+    where
+        A: Aggregate + DeserializeOwned + Default + Serialize + Send,
+        A::Event: DeserializeOwned + Send;
+
+    /// Save a snapshot of the aggregate at its current version; returns
+    /// whether it was written
+    fn save_snapshot<A>(&self, aggregate: &A) -> impl Future<Output = Result<bool, EventStoreError>> + Send
+    where
+        A: Aggregate + Serialize + Sync;
+
+    /// Save a snapshot if the aggregate version warrants it
+    fn save_snapshot_if_needed<A>(&self, aggregate: &A) -> impl Future<Output = Result<bool, EventStoreError>> + Send
+    where
+        A: Aggregate + Serialize + Sync,
+    {
+        async move {
+            if !aggregate.should_snapshot() {
+                return Ok(false);
+            }
+            self.save_snapshot(aggregate).await
+        }
+    }
+
+    /// Version of an aggregate's latest event (0 when it has none)
+    fn current_version(&self, aggregate_id: Uuid) -> impl Future<Output = Result<i64, EventStoreError>> + Send;
+
+    /// Get a single event by ID
+    fn get_event(&self, event_id: Uuid) -> impl Future<Output = Result<Option<StoredEvent>, EventStoreError>> + Send;
+
+    /// All events of an aggregate, oldest first
+    fn get_events(&self, aggregate_id: Uuid) -> impl Future<Output = Result<Vec<StoredEvent>, EventStoreError>> + Send;
+
+    /// Sequence of the latest event in the global stream (0 when empty)
+    fn head_sequence(&self) -> impl Future<Output = Result<i64, EventStoreError>> + Send;
+}
+
+impl EventStoreTrait for EventStore {
+    type Tx = Transaction<'static, Postgres>;
+
+    fn append_atomic(
+        &self,
+        operations: Vec<AggregateOperation>,
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> impl Future<Output = Result<Vec<Uuid>, EventStoreError>> + Send {
+        EventStore::append_atomic(self, operations, idempotency, context)
+    }
+
+    fn append_with_retry<'a, T, E, F, Fut>(
+        &'a self,
+        idempotency: Option<&'a IdempotencyRequest>,
+        context: &'a OperationContext,
+        build: F,
+    ) -> BoxFuture<'a, Result<(Vec<Uuid>, T), E>>
+    where
+        T: Send + 'a,
+        F: FnMut() -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>> + Send + 'a,
+        E: From<EventStoreError> + Send + 'a,
+    {
+        Box::pin(EventStore::append_with_retry(self, idempotency, context, build))
+    }
+
+    fn append_with_retry_in_tx<'a, T, E, F, Fut, P, PFut>(
+        &'a self,
+        idempotency: Option<&'a IdempotencyRequest>,
+        context: &'a OperationContext,
+        build: F,
+        project: P,
+    ) -> BoxFuture<'a, Result<(Vec<Uuid>, T), E>>
+    where
+        T: Send + 'a,
+        F: FnMut() -> Fut + Send + 'a,
+        Fut: Future<Output = Result<(Vec<AggregateOperation>, T), E>> + Send + 'a,
+        P: FnMut(PendingAppend<T, Self::Tx>) -> PFut + Send + 'a,
+        PFut: Future<Output = Result<PendingAppend<T, Self::Tx>, E>> + Send + 'a,
+        E: From<EventStoreError> + Send + 'a,
+    {
+        Box::pin(EventStore::append_with_retry_in_tx(self, idempotency, context, build, project))
+    }
+
+    fn store_result_in_tx<R: Serialize + Sync>(
+        &self,
+        tx: &mut Self::Tx,
+        idempotency: &IdempotencyRequest,
+        result: &R,
+    ) -> impl Future<Output = Result<(), EventStoreError>> + Send {
+        EventStore::store_result_in_tx(self, tx, idempotency, result)
+    }
+
+    fn stored_result<R: DeserializeOwned + Send>(
+        &self,
+        idempotency: &IdempotencyRequest,
+    ) -> impl Future<Output = Result<Option<R>, EventStoreError>> + Send {
+        EventStore::stored_result(self, idempotency)
+    }
+
+    fn load_aggregate<A>(&self, aggregate_id: Uuid) -> impl Future<Output = Result<Option<A>, EventStoreError>> + Send
+    where
+        A: Aggregate + DeserializeOwned + Default + Serialize + Send,
+        A::Event: DeserializeOwned + Send,
+    {
+        EventStore::load_aggregate(self, aggregate_id)
+    }
+
+    fn save_snapshot<A>(&self, aggregate: &A) -> impl Future<Output = Result<bool, EventStoreError>> + Send
+    where
+        A: Aggregate + Serialize + Sync,
+    {
+        EventStore::save_snapshot(self, aggregate)
+    }
+
+    fn current_version(&self, aggregate_id: Uuid) -> impl Future<Output = Result<i64, EventStoreError>> + Send {
+        EventStore::get_current_version(self, aggregate_id)
+    }
+
+    fn get_event(&self, event_id: Uuid) -> impl Future<Output = Result<Option<StoredEvent>, EventStoreError>> + Send {
+        EventStore::get_event(self, event_id)
+    }
+
+    fn get_events(&self, aggregate_id: Uuid) -> impl Future<Output = Result<Vec<StoredEvent>, EventStoreError>> + Send {
+        EventStore::get_events(self, aggregate_id)
+    }
+
+    fn head_sequence(&self) -> impl Future<Output = Result<i64, EventStoreError>> + Send {
+        EventStore::head_sequence(self)
+    }
+}
@@ -0,0 +1,196 @@
+//! Create Account Handler
+//!
+//! Opens additional named wallet accounts (sub-wallets such as "savings" or
+//! "escrow") for an existing user. The wallet created with the user stays the
+//! primary account that user_id-based operations resolve to.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{OperationContext, UserId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore};
+use crate::state::AppState;
+
+/// Longest account name (accounts.name)
+const MAX_ACCOUNT_NAME_LENGTH: usize = 50;
+
+// =========================================================================
+// CreateAccountCommand
+// =========================================================================
+
+/// Command to open a named account for a user
+#[derive(Debug, Clone)]
+pub struct CreateAccountCommand {
+    pub user_id: UserId,
+    pub name: String,
+}
+
+/// Result of a successful account creation
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateAccountResult {
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub account_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+// =========================================================================
+// CreateAccountHandler
+// =========================================================================
+
+/// Handler for named account creation
+pub struct CreateAccountHandler {
+    event_store: EventStore,
+    audit: AuditLogService,
+    pool: PgPool,
+}
+
+impl CreateAccountHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            audit: state.audit.clone(),
+            pool: state.pool.clone(),
+        }
+    }
+
+    /// Execute the create account command
+    pub async fn execute(
+        &self,
+        command: CreateAccountCommand,
+        context: &OperationContext,
+    ) -> Result<CreateAccountResult, AppError> {
+        let name = validate_account_name(&command.name)?;
+
+        let user: Option<(bool, bool)> =
+            sqlx::query_as("SELECT is_active, is_system FROM users WHERE id = $1 AND deleted_at IS NULL")
+                .bind(command.user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        match user {
+            None | Some((_, true)) => return Err(AppError::UserNotFound(command.user_id.to_string())),
+            Some((false, _)) => {
+                return Err(AppError::InvalidRequest("User is deactivated".to_string()));
+            }
+            Some((true, false)) => {}
+        }
+
+        let taken: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM accounts WHERE user_id = $1 AND name = $2)")
+            .bind(command.user_id)
+            .bind(&name)
+            .fetch_one(&self.pool)
+            .await?;
+        if taken {
+            return Err(AppError::InvalidRequest(format!("Account name already in use: {}", name)));
+        }
+
+        let account_id = Uuid::new_v4();
+        let (account, event) = Account::create_named(account_id, command.user_id.into(), name.clone());
+        let operation = AggregateOperation::new("Account", account.id(), 0, event.event_type(), &event)
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Event and read models commit together
+        let tx = self.event_store.begin().await?;
+        let (mut tx, event_ids) = self
+            .event_store
+            .append_atomic_in_tx(tx, &[operation], None, context)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO accounts (id, user_id, account_type, name)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(account_id)
+        .bind(command.user_id)
+        .bind(account.account_type().as_str())
+        .bind(&name)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_balances (account_id, balance, last_event_id, last_event_version)
+            VALUES ($1, 0, $2, 1)
+            "#,
+        )
+        .bind(account_id)
+        .bind(event_ids[0])
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::AccountCreated)
+            .resource_type("Account")
+            .resource_id(account_id)
+            .after_state(&json!({
+                "user_id": command.user_id,
+                "name": name,
+                "account_type": account.account_type(),
+            }));
+        self.audit.record(audit_entry, context).await;
+
+        Ok(CreateAccountResult {
+            account_id,
+            user_id: command.user_id.into(),
+            name,
+            account_type: account.account_type().as_str().to_string(),
+            created_at: account.created_at().unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+/// Normalized account name: lowercase letters, digits, '_' and '-',
+/// starting with a letter or digit
+fn validate_account_name(name: &str) -> Result<String, AppError> {
+    let name = name.trim().to_lowercase();
+    let valid = !name.is_empty()
+        && name.len() <= MAX_ACCOUNT_NAME_LENGTH
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-');
+
+    if !valid {
+        return Err(AppError::InvalidRequest(format!(
+            "Invalid account name: {:?} (1-{} characters of a-z, 0-9, '_' and '-')",
+            name, MAX_ACCOUNT_NAME_LENGTH
+        )));
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_account_name() {
+        assert_eq!(validate_account_name(" Savings ").unwrap(), "savings");
+        assert_eq!(validate_account_name("escrow-2024_q1").unwrap(), "escrow-2024_q1");
+
+        assert!(validate_account_name("").is_err());
+        assert!(validate_account_name("_hidden").is_err());
+        assert!(validate_account_name("rainy day").is_err());
+        assert!(validate_account_name("ñ").is_err());
+        assert!(validate_account_name(&"a".repeat(51)).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Anonymize User Handler
+//!
+//! Scrubs personal data (email, display name) of a deactivated user from the
+//! users projection and records a UserAnonymized event. Financial events,
+//! accounts and ledger entries are left intact.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{anonymized_email, Aggregate, User};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{OperationContext, UserId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+// =========================================================================
+// AnonymizeUserCommand
+// =========================================================================
+
+/// Command to anonymize a deactivated user
+#[derive(Debug, Clone)]
+pub struct AnonymizeUserCommand {
+    pub user_id: UserId,
+    pub reason: Option<String>,
+}
+
+impl AnonymizeUserCommand {
+    pub fn new(user_id: UserId) -> Self {
+        Self {
+            user_id,
+            reason: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: String) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+/// Result of a successful user anonymization
+#[derive(Debug, Clone)]
+pub struct AnonymizeUserResult {
+    pub user_id: Uuid,
+    pub anonymized_at: DateTime<Utc>,
+}
+
+// =========================================================================
+// AnonymizeUserHandler
+// =========================================================================
+
+/// Handler for user anonymization
+pub struct AnonymizeUserHandler {
+    event_store: EventStore,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    pool: PgPool,
+}
+
+impl AnonymizeUserHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            pool: state.pool.clone(),
+        }
+    }
+
+    /// Execute the anonymize user command
+    pub async fn execute(
+        &self,
+        command: AnonymizeUserCommand,
+        context: &OperationContext,
+    ) -> Result<AnonymizeUserResult, AppError> {
+        // Load user aggregate from event store
+        let user: User = self
+            .event_store
+            .load_aggregate(command.user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(command.user_id.to_string()))?;
+
+        // Check if user is system user
+        let is_system: Option<bool> = sqlx::query_scalar("SELECT is_system FROM users WHERE id = $1")
+            .bind(command.user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if is_system == Some(true) {
+            return Err(AppError::Forbidden("Cannot anonymize system user".to_string()));
+        }
+
+        // Generate anonymize event (fails unless the user is deactivated)
+        let event = user.anonymize(command.reason.clone())?;
+        let anonymized_at = match &event {
+            crate::domain::UserEvent::UserAnonymized { anonymized_at, .. } => *anonymized_at,
+            _ => Utc::now(),
+        };
+
+        // Prepare operation
+        let operation = AggregateOperation::new(
+            "User",
+            user.id(),
+            user.version(),
+            event.event_type(),
+            &event,
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Persist event
+        self.event_store
+            .append_atomic(vec![operation], None, context)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Sync users table (projection)
+        sqlx::query(
+            r#"
+            UPDATE users
+            SET email = $2, display_name = NULL, anonymized_at = $3, updated_at = $3
+            WHERE id = $1
+            "#,
+        )
+        .bind(command.user_id)
+        .bind(anonymized_email(command.user_id.into()))
+        .bind(anonymized_at)
+        .execute(&self.pool)
+        .await?;
+
+        // Snapshots taken before anonymization still hold the old profile
+        sqlx::query("DELETE FROM event_snapshots WHERE aggregate_id = $1")
+            .bind(command.user_id)
+            .execute(&self.pool)
+            .await?;
+
+        // Record audit log entry (no personal data in before/after state)
+        let audit_entry = AuditLogBuilder::new(AuditAction::UserAnonymized)
+            .resource_type("User")
+            .resource_id(command.user_id)
+            .before_state(&json!({ "status": user.status() }))
+            .after_state(&json!({
+                "status": user.apply(event).status(),
+                "reason": command.reason,
+            }))
+            .changed_fields(vec![
+                "status".to_string(),
+                "email".to_string(),
+                "display_name".to_string(),
+            ]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::UserAnonymized,
+                json!({ "user_id": command.user_id, "anonymized_at": anonymized_at }),
+            )
+            .await;
+
+        Ok(AnonymizeUserResult {
+            user_id: command.user_id.into(),
+            anonymized_at,
+        })
+    }
+}
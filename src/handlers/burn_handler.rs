@@ -2,62 +2,182 @@
 //!
 //! Handles ATP burning (removal from circulation) to SYSTEM_BURN account.
 
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::aggregate::{Account, Aggregate};
-use crate::domain::{Amount, OperationContext};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::db::SystemAccounts;
+use crate::domain::{AccountEvent, Amount, OperationContext, UserId};
 use crate::error::AppError;
-use crate::event_store::{AggregateOperation, EventStore};
-use crate::projection::ProjectionService;
+use crate::event_store::{AggregateOperation, EventStore, EventStoreTrait, IdempotencyRequest, PendingAppend};
+use crate::limits::{LimitOperation, LimitService, LimitTrait};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService, ProjectionTrait};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
 
-/// System burn user ID (must match database seed)
-const SYSTEM_BURN_USER_ID: &str = "00000000-0000-0000-0000-000000000002";
+use super::replay::replayed_result;
 
 /// Command to burn ATP
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct BurnCommand {
     /// User ID to burn ATP from
-    pub from_user_id: Uuid,
+    pub from_user_id: UserId,
     /// Amount to burn
-    pub amount: String,
+    pub amount: Amount,
     /// Reason for burning
     pub reason: String,
+    /// Why the burn overrides the wallet owner's consent (forced burns only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub force_reason: Option<String>,
 }
 
 impl BurnCommand {
-    pub fn new(from_user_id: Uuid, amount: String, reason: String) -> Self {
+    pub fn new(from_user_id: UserId, amount: Amount, reason: String) -> Self {
         Self {
             from_user_id,
             amount,
             reason,
+            force_reason: None,
         }
     }
+
+    /// Burn without the wallet owner's consent, for the given reason
+    pub fn forced(mut self, force_reason: String) -> Self {
+        self.force_reason = Some(force_reason);
+        self
+    }
+}
+
+/// Whose consent a burn from a user's wallet needs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BurnConsentPolicy {
+    /// Any key with admin:burn may burn from any wallet
+    #[default]
+    Open,
+    /// The request user must own the wallet, unless the burn is forced
+    /// with a reason
+    Consent,
+}
+
+impl FromStr for BurnConsentPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "open" => Ok(BurnConsentPolicy::Open),
+            "consent" => Ok(BurnConsentPolicy::Consent),
+            _ => Err(format!("unknown burn consent policy: {}", s)),
+        }
+    }
+}
+
+/// Check that the burn is allowed under `policy`
+fn check_consent(
+    policy: BurnConsentPolicy,
+    command: &BurnCommand,
+    context: &OperationContext,
+) -> Result<(), AppError> {
+    if command.force_reason.as_deref().is_some_and(|r| r.trim().is_empty()) {
+        return Err(AppError::InvalidRequest("force_reason must not be empty".to_string()));
+    }
+
+    let consented = context.request_user_id == Some(command.from_user_id.into());
+    match policy {
+        BurnConsentPolicy::Consent if !consented && command.force_reason.is_none() => Err(AppError::Forbidden(
+            "Burning requires X-Request-User-Id of the wallet owner, or force with a force_reason".to_string(),
+        )),
+        _ => Ok(()),
+    }
 }
 
 /// Result of a successful burn
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BurnResult {
     pub burn_id: Uuid,
     pub from_user_id: Uuid,
     pub amount: rust_decimal::Decimal,
+    /// User's wallet version after the burn
+    pub from_account_version: Option<i64>,
+    /// Events appended by the call
+    #[serde(default)]
+    pub event_ids: Vec<Uuid>,
+}
+
+/// Accounts and events of one append attempt
+struct PreparedBurn {
+    from_account: Account,
+    burn_account: Account,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
 }
 
 /// Handler for ATP burning
-pub struct BurnHandler {
-    event_store: EventStore,
-    projection: ProjectionService,
-    pool: PgPool,
+pub struct BurnHandler<E = EventStore, P = ProjectionService, L = LimitService> {
+    event_store: E,
+    projection: P,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    limits: L,
+    consent: BurnConsentPolicy,
+    system_accounts: SystemAccounts,
 }
 
 impl BurnHandler {
-    pub fn new(pool: PgPool) -> Self {
-        Self {
-            event_store: EventStore::new(pool.clone()),
-            projection: ProjectionService::new(pool.clone()),
+    pub fn new(pool: PgPool, system_accounts: SystemAccounts) -> Self {
+        Self::with_services(
+            EventStore::new(pool.clone()),
+            ProjectionService::new(pool.clone()),
+            LimitService::new(pool.clone()).with_system_users(system_accounts.users()),
+            system_accounts,
             pool,
+        )
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            limits: state.limits.clone(),
+            consent: state.config.burn_consent_policy,
+            system_accounts: state.system_accounts,
         }
     }
+}
+
+impl<E: EventStoreTrait, P: ProjectionTrait<Tx = E::Tx>, L: LimitTrait> BurnHandler<E, P, L> {
+    /// Use the given event store, projections and limits (e.g. in-memory
+    /// ones in tests); the audit log and webhooks still use `pool`
+    pub fn with_services(
+        event_store: E,
+        projection: P,
+        limits: L,
+        system_accounts: SystemAccounts,
+        pool: PgPool,
+    ) -> Self {
+        Self {
+            event_store,
+            projection,
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool),
+            limits,
+            consent: BurnConsentPolicy::default(),
+            system_accounts,
+        }
+    }
+
+    /// Require the wallet owner's consent under `policy`
+    pub fn with_consent_policy(mut self, policy: BurnConsentPolicy) -> Self {
+        self.consent = policy;
+        self
+    }
 
     /// Execute the burn command
     pub async fn execute(
@@ -66,65 +186,98 @@ impl BurnHandler {
         idempotency_key: Option<Uuid>,
         context: &OperationContext,
     ) -> Result<BurnResult, AppError> {
-        // Parse and validate amount
-        let amount: Amount = command
-            .amount
-            .parse()
-            .map_err(|e| AppError::InvalidRequest(format!("Invalid amount: {}", e)))?;
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyRequest::for_command(key, &command, context))
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        // Get SYSTEM_BURN account
-        let system_burn_user_id: Uuid = SYSTEM_BURN_USER_ID
-            .parse()
-            .expect("Invalid SYSTEM_BURN_USER_ID");
+        // Consent is required even when replaying
+        check_consent(self.consent, &command, context)?;
 
-        let burn_account_id = self.get_system_account_id(system_burn_user_id).await?;
+        // A completed key returns the original result, before the limit and
+        // balance checks that may no longer pass
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
+        }
 
-        // Get user's wallet account
-        let from_account_id = self.get_wallet_account_id(command.from_user_id).await?;
+        let amount = command.amount.clone();
 
-        // Load user's account (use event sourcing if available, fallback to DB)
-        let from_account = self.load_account_with_fallback(from_account_id).await?;
+        // Enforce configured burn limits and the API key's burn caps
+        self.limits
+            .check(LimitOperation::Burn, command.from_user_id.into(), amount.value())
+            .await?;
+        self.limits
+            .check_api_key(LimitOperation::Burn, context.api_key_id, amount.value())
+            .await?;
 
-        // Load SYSTEM_BURN account from DB (bypasses event sourcing for system accounts)
-        let burn_account = self.load_system_account(burn_account_id).await?;
+        // Get SYSTEM_BURN account
+        let burn_account_id = self.system_accounts.burn_account_id;
+
+        // Get user's wallet account
+        let from_account_id = self.get_wallet_account_id(command.from_user_id).await?;
 
         // Generate burn ID
         let burn_id = Uuid::new_v4();
-
-        // Generate debit event from user
-        let debit_description = format!("Burn: {}", command.reason);
-        let debit_event = from_account.debit(&amount, burn_id, debit_description)?;
-
-        // Generate credit event to SYSTEM_BURN
-        let credit_description = format!("Burned from user: {}", command.reason);
-        let credit_event = burn_account.credit(&amount, burn_id, credit_description)?;
-
-        // Prepare atomic operations
-        let operations = vec![
-            AggregateOperation::new(
-                "Account",
-                from_account_id,
-                from_account.version(),
-                debit_event.event_type(),
-                &debit_event,
-            )
-            .map_err(|e| AppError::Internal(e.to_string()))?,
-            AggregateOperation::new(
-                "Account",
-                burn_account_id,
-                burn_account.version(),
-                credit_event.event_type(),
-                &credit_event,
+        let burn_result = |event_ids: &[Uuid], prepared: &PreparedBurn| BurnResult {
+            burn_id,
+            from_user_id: command.from_user_id.into(),
+            amount: amount.value(),
+            from_account_version: Some(prepared.from_account.version() + 1),
+            event_ids: event_ids.to_vec(),
+        };
+
+        // Persist events atomically, reloading both accounts on each attempt;
+        // the result is stored with them for replays of the idempotency key
+        let idempotency_request = idempotency.as_ref();
+        let (event_ids, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                || {
+                    self.prepare_operations(
+                        from_account_id,
+                        burn_account_id,
+                        burn_id,
+                        &amount,
+                        &command.reason,
+                    )
+                },
+                |mut pending: PendingAppend<PreparedBurn, E::Tx>| async move {
+                    if let Some(idempotency) = idempotency_request {
+                        let result = burn_result(&pending.event_ids, &pending.value);
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, &result)
+                            .await?;
+                    }
+                    Ok::<_, AppError>(pending)
+                },
             )
-            .map_err(|e| AppError::Internal(e.to_string()))?,
-        ];
+            .await?;
 
-        // Persist events atomically
-        let event_ids = self
-            .event_store
-            .append_atomic(operations, idempotency_key, context)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+        // Check for idempotency early return (only 1 event ID returned for 2 operations means cached)
+        if let Some(idempotency) = idempotency.as_ref().filter(|_| event_ids.len() == 1) {
+            // A concurrent call with the same key completed first: return its
+            // result (skip projection update)
+            return replayed_result(&self.event_store, idempotency, event_ids[0], |burn_id| BurnResult {
+                burn_id,
+                from_user_id: command.from_user_id.into(),
+                amount: amount.value(),
+                from_account_version: None,
+                event_ids: Vec::new(),
+            })
+            .await;
+        }
+
+        let result = burn_result(&event_ids, &prepared);
+        let PreparedBurn {
+            from_account,
+            burn_account,
+            debit_event,
+            credit_event,
+        } = prepared;
 
         // Update projections
         self.projection
@@ -134,11 +287,18 @@ impl BurnHandler {
                 from_account_id,
                 burn_account_id,
                 &amount,
-                from_account.version() + 1,
+                LedgerDescriptions::from_events(&debit_event, &credit_event).journal(
+                    JournalType::Burn,
+                    Some(&command.reason),
+                    context.request_user_id,
+                ),
+                LegVersions::after(&from_account, &burn_account),
             )
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
 
+        let balance_before = from_account.balance().value();
+
         // Apply events to get updated accounts
         let from_account = from_account.apply(debit_event);
         let burn_account = burn_account.apply(credit_event);
@@ -153,76 +313,110 @@ impl BurnHandler {
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        Ok(BurnResult {
-            burn_id,
-            from_user_id: command.from_user_id,
-            amount: amount.value(),
-        })
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::BurnExecuted)
+            .resource_type("Account")
+            .resource_id(from_account_id)
+            .before_state(&json!({ "balance": balance_before }))
+            .after_state(&json!({
+                "burn_id": burn_id,
+                "from_user_id": command.from_user_id,
+                "amount": amount.value(),
+                "reason": command.reason,
+                "forced": command.force_reason.is_some(),
+                "force_reason": command.force_reason,
+                "balance": from_account.balance().value(),
+            }))
+            .changed_fields(vec!["balance".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::BurnExecuted,
+                json!({
+                    "burn_id": burn_id,
+                    "from_user_id": command.from_user_id,
+                    "amount": amount.value(),
+                    "reason": command.reason,
+                }),
+            )
+            .await;
+
+        Ok(result)
     }
 
-    async fn get_system_account_id(&self, user_id: Uuid) -> Result<Uuid, AppError> {
-        let account_id: Option<Uuid> = sqlx::query_scalar(
-            r#"
-            SELECT id FROM accounts 
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+    /// Load the user's and SYSTEM_BURN accounts and build the burn's operations
+    async fn prepare_operations(
+        &self,
+        from_account_id: Uuid,
+        burn_account_id: Uuid,
+        burn_id: Uuid,
+        amount: &Amount,
+        reason: &str,
+    ) -> Result<(Vec<AggregateOperation>, PreparedBurn), AppError> {
+        // Load user's account (use event sourcing if available, fallback to DB)
+        let from_account = self.load_account_with_fallback(from_account_id).await?;
 
-        account_id.ok_or_else(|| AppError::Internal("System account not found".to_string()))
-    }
+        // Load SYSTEM_BURN account from DB (bypasses event sourcing for system accounts)
+        let burn_account = self.load_system_account(burn_account_id).await?;
 
-    async fn get_wallet_account_id(&self, user_id: Uuid) -> Result<Uuid, AppError> {
-        let account_id: Option<Uuid> = sqlx::query_scalar(
-            r#"
-            SELECT id FROM accounts 
-            WHERE user_id = $1 AND account_type = 'user_wallet'
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        // Generate debit event from user
+        let debit_description = format!("Burn: {}", reason);
+        let debit_event = from_account.debit(amount, burn_id, debit_description)?;
+
+        // Generate credit event to SYSTEM_BURN
+        let credit_description = format!("Burned from user: {}", reason);
+        let credit_event = burn_account.credit(amount, burn_id, credit_description)?;
+
+        let operations = vec![
+            AggregateOperation::new(
+                "Account",
+                from_account_id,
+                from_account.version(),
+                debit_event.event_type(),
+                &debit_event,
+            )?,
+            AggregateOperation::new(
+                "Account",
+                burn_account_id,
+                burn_account.version(),
+                credit_event.event_type(),
+                &credit_event,
+            )?,
+        ];
 
-        account_id.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+        Ok((
+            operations,
+            PreparedBurn {
+                from_account,
+                burn_account,
+                debit_event,
+                credit_event,
+            },
+        ))
     }
 
-    /// Load system account directly from DB (bypasses event sourcing)
-    async fn load_system_account(&self, account_id: Uuid) -> Result<Account, AppError> {
-        // Get account info from DB
-        let account_info: Option<(Uuid, Uuid, String, bool)> = sqlx::query_as(
-            r#"
-            SELECT id, user_id, account_type, is_active
-            FROM accounts
-            WHERE id = $1
-            "#,
-        )
-        .bind(account_id)
-        .fetch_optional(&self.pool)
-        .await?;
+    async fn get_wallet_account_id(&self, user_id: UserId) -> Result<Uuid, AppError> {
+        self.projection
+            .wallet_account_id(user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+    }
 
-        let (id, user_id, account_type, _is_active) = account_info
+    /// Load a system account from the projections (bypasses event sourcing)
+    async fn load_system_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        let record = self
+            .projection
+            .account_record(account_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
             .ok_or_else(|| AppError::Internal("System account not found".to_string()))?;
 
-        // Get current balance from projection
-        let balance: Option<rust_decimal::Decimal> = sqlx::query_scalar(
-            "SELECT balance FROM account_balances WHERE account_id = $1"
-        )
-        .bind(account_id)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        // Get current version from events
-        let version: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(MAX(version), 0) FROM events WHERE aggregate_id = $1"
-        )
-        .bind(account_id)
-        .fetch_one(&self.pool)
-        .await?;
+        // Current version from events
+        let version = self.event_store.current_version(account_id).await?;
 
-        // Construct Account from DB state
-        Ok(Account::from_db_state(id, user_id, account_type, balance.unwrap_or_default(), version))
+        Ok(Account::from_db_state(record.id, record.user_id, &record.account_type, record.balance, version)?)
     }
 
     /// Load account with event sourcing, fallback to DB if no events exist
@@ -247,18 +441,41 @@ mod tests {
     #[test]
     fn test_burn_command() {
         let cmd = BurnCommand::new(
-            Uuid::new_v4(),
-            "100.00".to_string(),
+            UserId::new_v4(),
+            "100.00".parse().unwrap(),
             "Refund processing".to_string(),
         );
 
-        assert_eq!(cmd.amount, "100.00");
+        assert_eq!(cmd.amount, "100.00".parse().unwrap());
         assert_eq!(cmd.reason, "Refund processing");
+        assert_eq!(cmd.force_reason, None);
     }
 
     #[test]
-    fn test_system_burn_user_id() {
-        let id: Uuid = SYSTEM_BURN_USER_ID.parse().unwrap();
-        assert_eq!(id.to_string(), "00000000-0000-0000-0000-000000000002");
+    fn test_check_consent() {
+        let owner = UserId::new_v4();
+        let command = BurnCommand::new(owner, "1".parse().unwrap(), "Refund".to_string());
+        let as_owner = OperationContext::new().with_request_user(owner);
+        let as_other = OperationContext::new().with_request_user(Uuid::new_v4());
+
+        assert!(check_consent(BurnConsentPolicy::Open, &command, &as_other).is_ok());
+        assert!(check_consent(BurnConsentPolicy::Consent, &command, &as_owner).is_ok());
+        assert!(matches!(
+            check_consent(BurnConsentPolicy::Consent, &command, &as_other),
+            Err(AppError::Forbidden(_))
+        ));
+        assert!(matches!(
+            check_consent(BurnConsentPolicy::Consent, &command, &OperationContext::new()),
+            Err(AppError::Forbidden(_))
+        ));
+
+        let forced = command.forced("Court order".to_string());
+        assert!(check_consent(BurnConsentPolicy::Consent, &forced, &as_other).is_ok());
+        let blank = forced.clone().forced(" ".to_string());
+        assert!(matches!(
+            check_consent(BurnConsentPolicy::Open, &blank, &as_owner),
+            Err(AppError::InvalidRequest(_))
+        ));
+        assert_eq!("consent".parse(), Ok(BurnConsentPolicy::Consent));
     }
 }
@@ -0,0 +1,382 @@
+//! Close Account Handler
+//!
+//! Closes a user's wallet account, optionally sweeping its remaining balance
+//! to another account first. A closed account takes no further debits,
+//! credits or holds.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountEvent, AccountId, AccountType, Amount, OperationContext};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore, PendingAppend};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+// =========================================================================
+// CloseAccountCommand
+// =========================================================================
+
+/// Command to close an account
+#[derive(Debug, Clone)]
+pub struct CloseAccountCommand {
+    pub account_id: AccountId,
+    /// Account that receives the remaining balance (required unless the
+    /// balance is already zero)
+    pub sweep_to: Option<AccountId>,
+    /// Reason for closing
+    pub reason: Option<String>,
+}
+
+impl CloseAccountCommand {
+    pub fn new(account_id: AccountId) -> Self {
+        Self {
+            account_id,
+            sweep_to: None,
+            reason: None,
+        }
+    }
+
+    pub fn with_sweep_to(mut self, sweep_to: AccountId) -> Self {
+        self.sweep_to = Some(sweep_to);
+        self
+    }
+
+    pub fn with_reason(mut self, reason: String) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+/// Result of a successful closure
+#[derive(Debug, Clone)]
+pub struct CloseAccountResult {
+    pub account_id: Uuid,
+    /// Balance moved out before closing (zero when nothing was swept)
+    pub swept_amount: Decimal,
+    pub swept_to_account_id: Option<Uuid>,
+    /// Journal ID of the sweep, if any
+    pub sweep_id: Option<Uuid>,
+    pub closed_at: DateTime<Utc>,
+    /// Events appended by the call
+    pub event_ids: Vec<Uuid>,
+}
+
+/// Sweep of the remaining balance built by one append attempt
+struct PreparedSweep {
+    amount: Amount,
+    to_account: Account,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
+}
+
+/// Accounts and events of one append attempt
+struct PreparedClosure {
+    account: Account,
+    sweep: Option<PreparedSweep>,
+    closed_event: AccountEvent,
+}
+
+// =========================================================================
+// CloseAccountHandler
+// =========================================================================
+
+/// Handler for account closure
+pub struct CloseAccountHandler {
+    event_store: EventStore,
+    projection: ProjectionService,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+}
+
+impl CloseAccountHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            projection: ProjectionService::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool),
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+        }
+    }
+
+    /// Execute the close command
+    pub async fn execute(
+        &self,
+        command: CloseAccountCommand,
+        context: &OperationContext,
+    ) -> Result<CloseAccountResult, AppError> {
+        if command.sweep_to == Some(command.account_id) {
+            return Err(AppError::InvalidRequest(
+                "Cannot sweep an account's balance to itself".to_string(),
+            ));
+        }
+
+        let sweep_id = Uuid::new_v4();
+
+        // Persist events atomically, reloading both accounts on each attempt;
+        // the sweep is projected and the accounts row marked closed in the
+        // same transaction
+        let command_ref = &command;
+        let (event_ids, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                None,
+                context,
+                || self.prepare_operations(command_ref, sweep_id),
+                |mut pending: PendingAppend<PreparedClosure>| async move {
+                    if let Some(sweep) = &pending.value.sweep {
+                        let account = &pending.value.account;
+                        self.projection
+                            .apply_transfer_in_tx(
+                                &mut pending.tx,
+                                sweep_id,
+                                pending.event_ids[0],
+                                account.id(),
+                                sweep.to_account.id(),
+                                &sweep.amount,
+                                LedgerDescriptions::from_events(&sweep.debit_event, &sweep.credit_event)
+                                    .journal(
+                                        JournalType::Sweep,
+                                        command_ref.reason.as_deref(),
+                                        context.request_user_id,
+                                    ),
+                                LegVersions::after(account, &sweep.to_account),
+                            )
+                            .await
+                            .map_err(|e| AppError::Internal(e.to_string()))?;
+                    }
+                    let closed_at = match &pending.value.closed_event {
+                        AccountEvent::AccountClosed { closed_at, .. } => *closed_at,
+                        _ => Utc::now(),
+                    };
+                    sqlx::query(
+                        r#"
+                        UPDATE accounts
+                        SET is_active = FALSE, closed_at = $2
+                        WHERE id = $1
+                        "#,
+                    )
+                    .bind(pending.value.account.id())
+                    .bind(closed_at)
+                    .execute(&mut *pending.tx)
+                    .await?;
+                    Ok::<_, AppError>(pending)
+                },
+            )
+            .await?;
+
+        let PreparedClosure {
+            account,
+            sweep,
+            closed_event,
+        } = prepared;
+
+        let closed_at = match &closed_event {
+            AccountEvent::AccountClosed { closed_at, .. } => *closed_at,
+            _ => Utc::now(),
+        };
+        let balance_before = account.balance().value();
+
+        let (account, swept_amount, swept_to_account_id) = match sweep {
+            Some(PreparedSweep {
+                amount,
+                to_account,
+                debit_event,
+                credit_event,
+            }) => {
+                let to_account = to_account.apply(credit_event);
+                self.event_store
+                    .save_snapshot_if_needed(&to_account)
+                    .await
+                    .map_err(|e| AppError::Internal(e.to_string()))?;
+
+                (account.apply(debit_event), amount.value(), Some(to_account.id()))
+            }
+            None => (account, Decimal::ZERO, None),
+        };
+
+        // Apply the closure and save snapshot if needed
+        let account = account.apply(closed_event);
+        self.event_store
+            .save_snapshot_if_needed(&account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let sweep_id = swept_to_account_id.map(|_| sweep_id);
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::AccountClosed)
+            .resource_type("Account")
+            .resource_id(command.account_id)
+            .before_state(&json!({
+                "status": "active",
+                "balance": balance_before,
+            }))
+            .after_state(&json!({
+                "status": account.status(),
+                "balance": account.balance().value(),
+                "reason": command.reason,
+                "swept_amount": swept_amount,
+                "swept_to_account_id": swept_to_account_id,
+                "sweep_id": sweep_id,
+            }))
+            .changed_fields(vec!["status".to_string(), "balance".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::AccountClosed,
+                json!({
+                    "account_id": command.account_id,
+                    "user_id": account.user_id(),
+                    "swept_amount": swept_amount,
+                    "swept_to_account_id": swept_to_account_id,
+                    "reason": command.reason,
+                    "closed_at": closed_at,
+                }),
+            )
+            .await;
+
+        Ok(CloseAccountResult {
+            account_id: command.account_id.into(),
+            swept_amount,
+            swept_to_account_id,
+            sweep_id,
+            closed_at,
+            event_ids,
+        })
+    }
+
+    /// Load the account (and sweep target) and build the closure's operations
+    async fn prepare_operations(
+        &self,
+        command: &CloseAccountCommand,
+        sweep_id: Uuid,
+    ) -> Result<(Vec<AggregateOperation>, PreparedClosure), AppError> {
+        let account = self.load_account(command.account_id.into()).await?;
+
+        // System accounts back minting and burning and are never closed
+        if account.account_type() != AccountType::UserWallet {
+            return Err(AppError::Forbidden(
+                "Only user wallet accounts can be closed".to_string(),
+            ));
+        }
+
+        let balance = account.balance().value();
+        let mut operations = Vec::with_capacity(3);
+
+        let sweep = if balance > Decimal::ZERO {
+            let sweep_to = command.sweep_to.ok_or_else(|| {
+                AppError::InvalidRequest(format!(
+                    "Account balance must be zero to close (balance is {}); set sweep_to_account_id to move it",
+                    balance
+                ))
+            })?;
+            let to_account = self.load_account(sweep_to.into()).await?;
+
+            let amount = Amount::new(balance)
+                .map_err(|e| AppError::Internal(format!("Invalid sweep amount: {}", e)))?;
+            let description = format!("Sweep on closure of account {}", account.id());
+            let debit_event = account.debit(&amount, sweep_id, description.clone())?;
+            let credit_event = to_account.credit(&amount, sweep_id, description)?;
+
+            // Debit first so event_ids[0] is the sweep's debit event
+            operations.push(AggregateOperation::new(
+                "Account",
+                account.id(),
+                account.version(),
+                debit_event.event_type(),
+                &debit_event,
+            )?);
+            operations.push(AggregateOperation::new(
+                "Account",
+                to_account.id(),
+                to_account.version(),
+                credit_event.event_type(),
+                &credit_event,
+            )?);
+
+            Some(PreparedSweep {
+                amount,
+                to_account,
+                debit_event,
+                credit_event,
+            })
+        } else {
+            None
+        };
+
+        // Closure is checked against the account as it stands after the sweep
+        let (closed_event, close_version) = match &sweep {
+            Some(sweep) => (
+                account
+                    .clone()
+                    .apply(sweep.debit_event.clone())
+                    .close(command.reason.clone(), Some(sweep.to_account.id()))?,
+                account.version() + 1,
+            ),
+            None => (account.close(command.reason.clone(), None)?, account.version()),
+        };
+        operations.push(AggregateOperation::new(
+            "Account",
+            account.id(),
+            close_version,
+            closed_event.event_type(),
+            &closed_event,
+        )?);
+
+        Ok((
+            operations,
+            PreparedClosure {
+                account,
+                sweep,
+                closed_event,
+            },
+        ))
+    }
+
+    async fn load_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        self.event_store
+            .load_aggregate(account_id)
+            .await?
+            .ok_or_else(|| AppError::AccountNotFound(account_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_close_account_command() {
+        let account_id = AccountId::new_v4();
+        let sweep_to = AccountId::new_v4();
+
+        let cmd = CloseAccountCommand::new(account_id);
+        assert_eq!(cmd.account_id, account_id);
+        assert!(cmd.sweep_to.is_none());
+        assert!(cmd.reason.is_none());
+
+        let cmd = cmd
+            .with_sweep_to(sweep_to)
+            .with_reason("Customer request".to_string());
+        assert_eq!(cmd.sweep_to, Some(sweep_to));
+        assert_eq!(cmd.reason, Some("Customer request".to_string()));
+    }
+}
@@ -6,6 +6,8 @@ use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::{AccountId, Amount, UserId};
+
 // =========================================================================
 // M097: CreateUserCommand
 // =========================================================================
@@ -13,14 +15,14 @@ use uuid::Uuid;
 /// Command to create a new user
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateUserCommand {
-    pub user_id: Uuid,
+    pub user_id: UserId,
     pub username: String,
     pub email: String,
     pub display_name: Option<String>,
 }
 
 impl CreateUserCommand {
-    pub fn new(user_id: Uuid, username: String, email: String) -> Self {
+    pub fn new(user_id: UserId, username: String, email: String) -> Self {
         Self {
             user_id,
             username,
@@ -43,22 +45,34 @@ impl CreateUserCommand {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferCommand {
     /// User ID of the sender (resolved to account internally)
-    pub from_user_id: Uuid,
+    pub from_user_id: UserId,
     /// User ID of the recipient (resolved to account internally)
-    pub to_user_id: Uuid,
-    /// Amount to transfer (as string for precise decimal)
-    pub amount: String,
+    pub to_user_id: UserId,
+    /// Amount to transfer
+    pub amount: Amount,
     /// Optional memo
     pub memo: Option<String>,
+    /// Optional reporting category (must be in TRANSFER_CATEGORIES)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// Sender's account to debit (the primary wallet if omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_account_id: Option<AccountId>,
+    /// Recipient's account to credit (the primary wallet if omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_account_id: Option<AccountId>,
 }
 
 impl TransferCommand {
-    pub fn new(from_user_id: Uuid, to_user_id: Uuid, amount: String) -> Self {
+    pub fn new(from_user_id: UserId, to_user_id: UserId, amount: Amount) -> Self {
         Self {
             from_user_id,
             to_user_id,
             amount,
             memo: None,
+            category: None,
+            from_account_id: None,
+            to_account_id: None,
         }
     }
 
@@ -66,6 +80,18 @@ impl TransferCommand {
         self.memo = Some(memo);
         self
     }
+
+    pub fn with_category(mut self, category: String) -> Self {
+        self.category = Some(category);
+        self
+    }
+
+    /// Target specific accounts of the sender and recipient
+    pub fn with_accounts(mut self, from_account_id: Option<AccountId>, to_account_id: Option<AccountId>) -> Self {
+        self.from_account_id = from_account_id;
+        self.to_account_id = to_account_id;
+        self
+    }
 }
 
 // =========================================================================
@@ -76,15 +102,15 @@ impl TransferCommand {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MintCommand {
     /// User ID to receive minted ATP
-    pub recipient_user_id: Uuid,
-    /// Amount to mint (as string for precise decimal)
-    pub amount: String,
+    pub recipient_user_id: UserId,
+    /// Amount to mint
+    pub amount: Amount,
     /// Reason for minting
     pub reason: String,
 }
 
 impl MintCommand {
-    pub fn new(recipient_user_id: Uuid, amount: String, reason: String) -> Self {
+    pub fn new(recipient_user_id: UserId, amount: Amount, reason: String) -> Self {
         Self {
             recipient_user_id,
             amount,
@@ -101,6 +127,27 @@ pub struct TransferResult {
     pub to_user_id: Uuid,
     pub amount: Decimal,
     pub status: String,
+    /// Sender's wallet version after the transfer (None until money moves)
+    pub from_account_version: Option<i64>,
+    /// Recipient's wallet version after the transfer (None until money moves)
+    pub to_account_version: Option<i64>,
+    /// Events appended by the call
+    #[serde(default)]
+    pub event_ids: Vec<Uuid>,
+}
+
+/// Would-be result of a transfer that passed all checks (nothing persisted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferQuote {
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: Decimal,
+    /// Fee charged on top of the amount (transfers are currently free)
+    pub fee: Decimal,
+    pub from_balance_after: Decimal,
+    pub to_balance_after: Decimal,
+    /// "completed", or "pending_approval" above the approval threshold
+    pub status: String,
 }
 
 /// Result of a successful mint
@@ -109,6 +156,11 @@ pub struct MintResult {
     pub mint_id: Uuid,
     pub recipient_user_id: Uuid,
     pub amount: Decimal,
+    /// Recipient's wallet version after the mint
+    pub recipient_account_version: Option<i64>,
+    /// Events appended by the call
+    #[serde(default)]
+    pub event_ids: Vec<Uuid>,
 }
 
 /// Result of a successful user creation
@@ -1,15 +1,28 @@
 //! Deactivate User Handler
 //!
-//! Handles user deactivation (soft delete) with event sourcing.
+//! Handles user deactivation (soft delete) with event sourcing, optionally
+//! sweeping the user's remaining balance to the recovery account.
 
 use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::aggregate::{Aggregate, User};
-use crate::domain::OperationContext;
+use crate::aggregate::{Account, Aggregate, User};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountEvent, Amount, OperationContext, UserEvent, UserId};
 use crate::error::AppError;
-use crate::event_store::{AggregateOperation, EventStore};
+use crate::event_store::{AggregateOperation, EventStore, PendingAppend};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+/// Category of the ledger entries of a deactivation sweep
+const RECOVERY_SWEEP_CATEGORY: &str = "recovery_sweep";
+
+/// Memo of a deactivation sweep's journal
+const RECOVERY_SWEEP_MEMO: &str = "Balance swept on user deactivation";
 
 // =========================================================================
 // DeactivateUserCommand
@@ -18,15 +31,18 @@ use crate::event_store::{AggregateOperation, EventStore};
 /// Command to deactivate a user
 #[derive(Debug, Clone)]
 pub struct DeactivateUserCommand {
-    pub user_id: Uuid,
+    pub user_id: UserId,
     pub reason: Option<String>,
+    /// Move the user's available balance to the recovery account first
+    pub sweep_balance: bool,
 }
 
 impl DeactivateUserCommand {
-    pub fn new(user_id: Uuid) -> Self {
+    pub fn new(user_id: UserId) -> Self {
         Self {
             user_id,
             reason: None,
+            sweep_balance: false,
         }
     }
 
@@ -34,6 +50,12 @@ impl DeactivateUserCommand {
         self.reason = Some(reason);
         self
     }
+
+    /// Sweep the user's wallets to the recovery account in the same append
+    pub fn with_sweep(mut self) -> Self {
+        self.sweep_balance = true;
+        self
+    }
 }
 
 /// Result of a successful user deactivation
@@ -41,6 +63,31 @@ impl DeactivateUserCommand {
 pub struct DeactivateUserResult {
     pub user_id: Uuid,
     pub deactivated_at: DateTime<Utc>,
+    /// Total moved to the recovery account (zero without a sweep)
+    pub swept_amount: Decimal,
+    /// Recovery account, if anything was swept
+    pub swept_to_account_id: Option<Uuid>,
+}
+
+/// One wallet's sweep to the recovery account
+struct PreparedSweep {
+    sweep_id: Uuid,
+    amount: Amount,
+    /// Wallet after the debit
+    from_account: Account,
+    to_account_id: Uuid,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
+    versions: LegVersions,
+}
+
+/// Aggregates and events of one append attempt
+struct PreparedDeactivation {
+    user: User,
+    event: UserEvent,
+    sweeps: Vec<PreparedSweep>,
+    /// Recovery account after all credits
+    recovery_account: Option<Account>,
 }
 
 // =========================================================================
@@ -50,6 +97,10 @@ pub struct DeactivateUserResult {
 /// Handler for user deactivation
 pub struct DeactivateUserHandler {
     event_store: EventStore,
+    projection: ProjectionService,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    recovery_account_id: Option<Uuid>,
     pool: PgPool,
 }
 
@@ -57,24 +108,38 @@ impl DeactivateUserHandler {
     pub fn new(pool: PgPool) -> Self {
         Self {
             event_store: EventStore::new(pool.clone()),
+            projection: ProjectionService::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            recovery_account_id: None,
             pool,
         }
     }
 
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            recovery_account_id: state.config.recovery_account_id,
+            pool: state.pool.clone(),
+        }
+    }
+
+    /// Account that receives swept balances
+    pub fn with_recovery_account(mut self, account_id: Uuid) -> Self {
+        self.recovery_account_id = Some(account_id);
+        self
+    }
+
     /// Execute the deactivate user command
     pub async fn execute(
         &self,
         command: DeactivateUserCommand,
         context: &OperationContext,
     ) -> Result<DeactivateUserResult, AppError> {
-        // Load user aggregate from event store
-        let user: User = self
-            .event_store
-            .load_aggregate(command.user_id)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?
-            .ok_or_else(|| AppError::UserNotFound(command.user_id.to_string()))?;
-
         // Check if user is system user
         let is_system: Option<bool> = sqlx::query_scalar("SELECT is_system FROM users WHERE id = $1")
             .bind(command.user_id)
@@ -85,39 +150,258 @@ impl DeactivateUserHandler {
             return Err(AppError::Forbidden("Cannot deactivate system user".to_string()));
         }
 
-        // Generate deactivate event
-        let event = user.deactivate(command.reason)?;
-        let deactivated_at = match &event {
-            crate::domain::UserEvent::UserDeactivated { deactivated_at, .. } => *deactivated_at,
-            _ => Utc::now(),
-        };
+        // Persist the sweeps and the deactivation atomically, reloading the
+        // user and accounts on each attempt; the sweeps are projected and the
+        // users row synced in the same transaction
+        let command_ref = &command;
+        let (_, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                None,
+                context,
+                || self.prepare_operations(command_ref),
+                |mut pending: PendingAppend<PreparedDeactivation>| async move {
+                    // Each sweep's debit is at an even index
+                    let debit_event_ids: Vec<Uuid> = pending.event_ids.iter().step_by(2).copied().collect();
+                    for (sweep, debit_event_id) in pending.value.sweeps.iter().zip(debit_event_ids) {
+                        self.projection
+                            .apply_transfer_in_tx(
+                                &mut pending.tx,
+                                sweep.sweep_id,
+                                debit_event_id,
+                                sweep.from_account.id(),
+                                sweep.to_account_id,
+                                &sweep.amount,
+                                LedgerDescriptions::from_events(&sweep.debit_event, &sweep.credit_event)
+                                    .journal(JournalType::Sweep, Some(RECOVERY_SWEEP_MEMO), context.request_user_id),
+                                sweep.versions,
+                            )
+                            .await
+                            .map_err(|e| AppError::Internal(e.to_string()))?;
+                    }
+
+                    sqlx::query(
+                        "UPDATE users SET is_active = false, deactivated_at = $2, updated_at = $2 WHERE id = $1",
+                    )
+                    .bind(command_ref.user_id)
+                    .bind(deactivation_time(&pending.value.event))
+                    .execute(&mut *pending.tx)
+                    .await?;
+                    Ok::<_, AppError>(pending)
+                },
+            )
+            .await?;
+        let PreparedDeactivation {
+            user,
+            event,
+            sweeps,
+            recovery_account,
+        } = prepared;
+
+        let deactivated_at = deactivation_time(&event);
+
+        for sweep in &sweeps {
+            self.event_store
+                .save_snapshot_if_needed(&sweep.from_account)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+        }
+        if let Some(recovery_account) = &recovery_account {
+            self.event_store
+                .save_snapshot_if_needed(recovery_account)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+        }
+
+        let swept_amount: Decimal = sweeps.iter().map(|sweep| sweep.amount.value()).sum();
+        let swept_to_account_id = sweeps.first().map(|sweep| sweep.to_account_id);
+        let swept: Vec<_> = sweeps
+            .iter()
+            .map(|sweep| {
+                json!({
+                    "sweep_id": sweep.sweep_id,
+                    "account_id": sweep.from_account.id(),
+                    "amount": sweep.amount.value(),
+                })
+            })
+            .collect();
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::UserDeactivated)
+            .resource_type("User")
+            .resource_id(command.user_id)
+            .before_state(&json!({ "status": user.status() }))
+            .after_state(&json!({
+                "status": user.apply(event).status(),
+                "reason": command.reason,
+                "swept_amount": swept_amount,
+                "swept_to_account_id": swept_to_account_id,
+                "sweeps": swept,
+            }))
+            .changed_fields(vec!["status".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::UserDeactivated,
+                json!({
+                    "user_id": command.user_id,
+                    "deactivated_at": deactivated_at,
+                    "swept_amount": swept_amount,
+                    "swept_to_account_id": swept_to_account_id,
+                }),
+            )
+            .await;
+
+        Ok(DeactivateUserResult {
+            user_id: command.user_id.into(),
+            deactivated_at,
+            swept_amount,
+            swept_to_account_id,
+        })
+    }
+
+    /// Load the user (and, when sweeping, their wallets and the recovery
+    /// account) and build the deactivation's operations
+    async fn prepare_operations(
+        &self,
+        command: &DeactivateUserCommand,
+    ) -> Result<(Vec<AggregateOperation>, PreparedDeactivation), AppError> {
+        let user: User = self
+            .event_store
+            .load_aggregate(command.user_id.into())
+            .await?
+            .ok_or_else(|| AppError::UserNotFound(command.user_id.to_string()))?;
+
+        let event = user.deactivate(command.reason.clone())?;
+
+        let mut operations = Vec::new();
+        let mut sweeps = Vec::new();
+        let mut recovery_account = None;
+
+        if command.sweep_balance {
+            let recovery_account_id = self.recovery_account_id.ok_or_else(|| {
+                AppError::InvalidRequest("Balance sweep is not configured (RECOVERY_ACCOUNT_ID)".to_string())
+            })?;
+
+            let account_ids: Vec<Uuid> = sqlx::query_scalar(
+                r#"
+                SELECT id FROM accounts
+                WHERE user_id = $1 AND account_type = 'user_wallet' AND is_active = TRUE
+                ORDER BY created_at, id
+                "#,
+            )
+            .bind(command.user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+            if account_ids.contains(&recovery_account_id) {
+                return Err(AppError::InvalidRequest(
+                    "Recovery account belongs to the user being deactivated".to_string(),
+                ));
+            }
+
+            let mut recovery: Account = self
+                .event_store
+                .load_aggregate(recovery_account_id)
+                .await?
+                .ok_or_else(|| AppError::AccountNotFound(recovery_account_id.to_string()))?;
+
+            for account_id in account_ids {
+                let Some(account) = self.event_store.load_aggregate::<Account>(account_id).await? else {
+                    continue;
+                };
+
+                // Held funds stay put until captured or released
+                let available = account.available_balance();
+                if available <= Decimal::ZERO {
+                    continue;
+                }
+                let amount = Amount::new(available)
+                    .map_err(|e| AppError::Internal(format!("Invalid sweep amount: {}", e)))?;
 
-        // Prepare operation
-        let operation = AggregateOperation::new(
+                let sweep_id = Uuid::new_v4();
+                let debit_event = account
+                    .debit(&amount, sweep_id, RECOVERY_SWEEP_MEMO.to_string())?
+                    .with_category(Some(RECOVERY_SWEEP_CATEGORY.to_string()));
+                let credit_event = recovery
+                    .credit(&amount, sweep_id, RECOVERY_SWEEP_MEMO.to_string())?
+                    .with_category(Some(RECOVERY_SWEEP_CATEGORY.to_string()));
+
+                operations.push(AggregateOperation::new(
+                    "Account",
+                    account.id(),
+                    account.version(),
+                    debit_event.event_type(),
+                    &debit_event,
+                )?);
+                operations.push(AggregateOperation::new(
+                    "Account",
+                    recovery.id(),
+                    recovery.version(),
+                    credit_event.event_type(),
+                    &credit_event,
+                )?);
+
+                let versions = LegVersions::after(&account, &recovery);
+                recovery = recovery.apply(credit_event.clone());
+                sweeps.push(PreparedSweep {
+                    sweep_id,
+                    amount,
+                    from_account: account.apply(debit_event.clone()),
+                    to_account_id: recovery_account_id,
+                    debit_event,
+                    credit_event,
+                    versions,
+                });
+            }
+
+            recovery_account = Some(recovery).filter(|_| !sweeps.is_empty());
+        }
+
+        // The deactivation comes last so sweeps keep their debit at even indexes
+        operations.push(AggregateOperation::new(
             "User",
             user.id(),
             user.version(),
             event.event_type(),
             &event,
-        )
-        .map_err(|e| AppError::Internal(e.to_string()))?;
+        )?);
 
-        // Persist event
-        self.event_store
-            .append_atomic(vec![operation], None, context)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+        Ok((
+            operations,
+            PreparedDeactivation {
+                user,
+                event,
+                sweeps,
+                recovery_account,
+            },
+        ))
+    }
+}
 
-        // Sync users table (projection)
-        sqlx::query("UPDATE users SET is_active = false, updated_at = $2 WHERE id = $1")
-            .bind(command.user_id)
-            .bind(deactivated_at)
-            .execute(&self.pool)
-            .await?;
+/// When the user was deactivated, from their UserDeactivated event
+fn deactivation_time(event: &UserEvent) -> DateTime<Utc> {
+    match event {
+        UserEvent::UserDeactivated { deactivated_at, .. } => *deactivated_at,
+        _ => Utc::now(),
+    }
+}
 
-        Ok(DeactivateUserResult {
-            user_id: command.user_id,
-            deactivated_at,
-        })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deactivate_user_command() {
+        let user_id = UserId::new_v4();
+
+        let cmd = DeactivateUserCommand::new(user_id);
+        assert_eq!(cmd.user_id, user_id);
+        assert!(!cmd.sweep_balance);
+
+        let cmd = cmd.with_reason("Closed by request".to_string()).with_sweep();
+        assert!(cmd.sweep_balance);
+        assert_eq!(cmd.reason, Some("Closed by request".to_string()));
     }
 }
@@ -0,0 +1,212 @@
+//! Freeze Account Handler
+//!
+//! Handles freezing and unfreezing accounts with event sourcing and audit logging.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountEvent, AccountId, OperationContext};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore, EventStoreTrait};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+// =========================================================================
+// FreezeAccountCommand
+// =========================================================================
+
+/// Command to freeze or unfreeze an account
+#[derive(Debug, Clone)]
+pub struct FreezeAccountCommand {
+    pub account_id: AccountId,
+    /// true to freeze, false to unfreeze
+    pub freeze: bool,
+    /// Reason for freezing (required when freezing)
+    pub reason: Option<String>,
+}
+
+impl FreezeAccountCommand {
+    /// Create a freeze command
+    pub fn freeze(account_id: AccountId, reason: String) -> Self {
+        Self {
+            account_id,
+            freeze: true,
+            reason: Some(reason),
+        }
+    }
+
+    /// Create an unfreeze command
+    pub fn unfreeze(account_id: AccountId) -> Self {
+        Self {
+            account_id,
+            freeze: false,
+            reason: None,
+        }
+    }
+}
+
+/// Result of a successful freeze/unfreeze
+#[derive(Debug, Clone)]
+pub struct FreezeAccountResult {
+    pub account_id: Uuid,
+    pub frozen: bool,
+    pub changed_at: DateTime<Utc>,
+}
+
+// =========================================================================
+// FreezeAccountHandler
+// =========================================================================
+
+/// Handler for account freeze/unfreeze
+pub struct FreezeAccountHandler<E = EventStore> {
+    event_store: E,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+}
+
+impl FreezeAccountHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_event_store(EventStore::new(pool.clone()), pool)
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+        }
+    }
+}
+
+impl<E: EventStoreTrait> FreezeAccountHandler<E> {
+    /// Use the given event store (e.g. an in-memory one in tests); the audit
+    /// log and webhooks still use `pool`
+    pub fn with_event_store(event_store: E, pool: PgPool) -> Self {
+        Self {
+            event_store,
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool),
+        }
+    }
+
+    /// Execute the freeze/unfreeze command
+    pub async fn execute(
+        &self,
+        command: FreezeAccountCommand,
+        context: &OperationContext,
+    ) -> Result<FreezeAccountResult, AppError> {
+        // Load account aggregate from event store
+        let account: Account = self
+            .event_store
+            .load_aggregate(command.account_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::AccountNotFound(command.account_id.to_string()))?;
+
+        let before_status = account.status().clone();
+
+        // Generate event
+        let event = if command.freeze {
+            let reason = command
+                .reason
+                .clone()
+                .filter(|r| !r.trim().is_empty())
+                .ok_or_else(|| AppError::InvalidRequest("Freeze reason is required".to_string()))?;
+            account.freeze(reason)?
+        } else {
+            account.unfreeze()?
+        };
+
+        let changed_at = match &event {
+            AccountEvent::AccountFrozen { frozen_at, .. } => *frozen_at,
+            AccountEvent::AccountUnfrozen { unfrozen_at, .. } => *unfrozen_at,
+            _ => Utc::now(),
+        };
+
+        // Prepare operation
+        let operation = AggregateOperation::new(
+            "Account",
+            account.id(),
+            account.version(),
+            event.event_type(),
+            &event,
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Persist event
+        self.event_store
+            .append_atomic(vec![operation], None, context)
+            .await?;
+
+        // Apply event and save snapshot if needed
+        let account = account.apply(event);
+        self.event_store
+            .save_snapshot_if_needed(&account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Record audit log entry
+        let action = if command.freeze {
+            AuditAction::AccountFrozen
+        } else {
+            AuditAction::AccountUnfrozen
+        };
+        let audit_entry = AuditLogBuilder::new(action)
+            .resource_type("Account")
+            .resource_id(command.account_id)
+            .before_state(&json!({ "status": before_status }))
+            .after_state(&json!({
+                "status": account.status(),
+                "reason": command.reason,
+            }))
+            .changed_fields(vec!["status".to_string()]);
+
+        self.audit.record(audit_entry, context).await;
+
+        let webhook_event = if command.freeze {
+            WebhookEventType::AccountFrozen
+        } else {
+            WebhookEventType::AccountUnfrozen
+        };
+        self.webhooks
+            .notify(
+                webhook_event,
+                json!({
+                    "account_id": command.account_id,
+                    "reason": command.reason,
+                    "changed_at": changed_at,
+                }),
+            )
+            .await;
+
+        Ok(FreezeAccountResult {
+            account_id: command.account_id.into(),
+            frozen: account.is_frozen(),
+            changed_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_freeze_account_command() {
+        let account_id = AccountId::new_v4();
+
+        let cmd = FreezeAccountCommand::freeze(account_id, "Suspicious activity".to_string());
+        assert_eq!(cmd.account_id, account_id);
+        assert!(cmd.freeze);
+        assert_eq!(cmd.reason, Some("Suspicious activity".to_string()));
+
+        let cmd = FreezeAccountCommand::unfreeze(account_id);
+        assert!(!cmd.freeze);
+        assert!(cmd.reason.is_none());
+    }
+}
@@ -0,0 +1,657 @@
+//! Hold Handler
+//!
+//! Handles two-phase payments: funds are held on the payer's account,
+//! then either captured (moved to the payee) or released.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountEvent, Amount, OperationContext, UserId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore, EventStoreTrait, IdempotencyRequest, PendingAppend};
+use crate::projection::{
+    AccountHold, JournalType, LedgerDescriptions, LegVersions, ProjectionService, ProjectionTrait,
+};
+use crate::state::AppState;
+
+// =========================================================================
+// Commands / Results
+// =========================================================================
+
+/// Command to place a hold on the sender's balance
+#[derive(Debug, Clone, Serialize)]
+pub struct HoldCommand {
+    /// Payer (funds are held on this user's wallet)
+    pub from_user_id: UserId,
+    /// Payee (receives the funds on capture)
+    pub to_user_id: UserId,
+    /// Amount to hold
+    pub amount: Amount,
+    /// Optional memo
+    pub memo: Option<String>,
+}
+
+impl HoldCommand {
+    pub fn new(from_user_id: UserId, to_user_id: UserId, amount: Amount) -> Self {
+        Self {
+            from_user_id,
+            to_user_id,
+            amount,
+            memo: None,
+        }
+    }
+
+    pub fn with_memo(mut self, memo: String) -> Self {
+        self.memo = Some(memo);
+        self
+    }
+}
+
+/// Result of a hold, capture or release
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HoldResult {
+    pub hold_id: Uuid,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: Decimal,
+    /// held, captured or released
+    pub status: String,
+    /// Ledger journal ID (only set on capture)
+    pub transfer_id: Option<Uuid>,
+}
+
+/// Payer's account and the BalanceHeld event built by one append attempt
+struct PreparedHold {
+    from_account: Account,
+    event: AccountEvent,
+    /// Set once the attempt's events were appended and projected; stays
+    /// false when the append replayed an earlier call with the same key
+    projected: bool,
+}
+
+/// Capture or release of a hold, hashed for its idempotency key
+#[derive(Serialize)]
+struct SettleHoldCommand {
+    hold_id: Uuid,
+    action: &'static str,
+}
+
+impl SettleHoldCommand {
+    fn capture(hold_id: Uuid) -> Self {
+        Self { hold_id, action: "capture" }
+    }
+
+    fn release(hold_id: Uuid) -> Self {
+        Self { hold_id, action: "release" }
+    }
+}
+
+/// Accounts and events built by one capture attempt
+struct PreparedCapture {
+    from_account: Account,
+    to_account: Account,
+    capture_event: AccountEvent,
+    credit_event: AccountEvent,
+    /// Set once the attempt's events were appended and projected
+    projected: bool,
+}
+
+/// Payer's account and the HoldReleased event built by one release attempt
+struct PreparedRelease {
+    from_account: Account,
+    event: AccountEvent,
+    /// Set once the attempt's events were appended and projected
+    projected: bool,
+}
+
+/// Only a held hold can be captured or released
+fn ensure_held(hold: &AccountHold) -> Result<(), AppError> {
+    if hold.status != "held" {
+        return Err(AppError::InvalidRequest(format!("Hold is already {}", hold.status)));
+    }
+    Ok(())
+}
+
+/// Result reporting the hold in `status`
+fn hold_result(hold: &AccountHold, status: &str, transfer_id: Option<Uuid>) -> HoldResult {
+    HoldResult {
+        hold_id: hold.id,
+        from_user_id: hold.from_user_id,
+        to_user_id: hold.to_user_id,
+        amount: hold.amount,
+        status: status.to_string(),
+        transfer_id,
+    }
+}
+
+// =========================================================================
+// HoldHandler
+// =========================================================================
+
+/// Handler for balance holds
+pub struct HoldHandler<E = EventStore, P = ProjectionService> {
+    event_store: E,
+    projection: P,
+    audit: AuditLogService,
+}
+
+impl HoldHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_services(EventStore::new(pool.clone()), ProjectionService::new(pool.clone()), pool)
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+        }
+    }
+}
+
+impl<E: EventStoreTrait, P: ProjectionTrait<Tx = E::Tx>> HoldHandler<E, P> {
+    /// Use the given event store and projections (e.g. in-memory ones in
+    /// tests); the audit log still uses `pool`
+    pub fn with_services(event_store: E, projection: P, pool: PgPool) -> Self {
+        Self {
+            event_store,
+            projection,
+            audit: AuditLogService::new(pool),
+        }
+    }
+
+    /// Place a hold on the payer's wallet
+    pub async fn hold(
+        &self,
+        command: HoldCommand,
+        idempotency_key: Option<Uuid>,
+        context: &OperationContext,
+    ) -> Result<HoldResult, AppError> {
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyRequest::for_command(key, &command, context))
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Only the payer can hold their own funds
+        match context.request_user_id {
+            Some(request_user_id) if UserId::from(request_user_id) == command.from_user_id => {}
+            Some(_) => return Err(AppError::UnauthorizedTransfer),
+            None => return Err(AppError::MissingHeader("X-Request-User-Id".to_string())),
+        }
+
+        if command.from_user_id == command.to_user_id {
+            return Err(AppError::InvalidRequest(
+                "Cannot hold funds for the same account".to_string(),
+            ));
+        }
+
+        // A completed key returns the original result instead of placing a
+        // second hold
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
+        }
+
+        let amount = command.amount.clone();
+
+        let from_account_id = self.get_wallet_account_id(command.from_user_id).await?;
+        let to_account_id = self.get_wallet_account_id(command.to_user_id).await?;
+
+        let hold_id = Uuid::new_v4();
+        let description = command.memo.clone().unwrap_or_else(|| "Hold".to_string());
+        let result = HoldResult {
+            hold_id,
+            from_user_id: command.from_user_id.into(),
+            to_user_id: command.to_user_id.into(),
+            amount: amount.value(),
+            status: "held".to_string(),
+            transfer_id: None,
+        };
+
+        // Reload the payer's account on each attempt so the available balance
+        // check runs against the latest state. The hold row and the result
+        // for replays of the idempotency key commit with the event.
+        let (amount_ref, description_ref, result_ref) = (&amount, &description, &result);
+        let idempotency_request = idempotency.as_ref();
+        let (event_ids, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                || async move {
+                    let from_account = self.load_account(from_account_id).await?;
+                    let event =
+                        from_account.hold(amount_ref, hold_id, to_account_id, description_ref.clone())?;
+                    let operation = AggregateOperation::new(
+                        "Account",
+                        from_account_id,
+                        from_account.version(),
+                        event.event_type(),
+                        &event,
+                    )?;
+                    let prepared = PreparedHold {
+                        from_account,
+                        event,
+                        projected: false,
+                    };
+                    Ok::<_, AppError>((vec![operation], prepared))
+                },
+                |mut pending: PendingAppend<PreparedHold, E::Tx>| async move {
+                    self.projection
+                        .apply_hold_in_tx(
+                            &mut pending.tx,
+                            hold_id,
+                            from_account_id,
+                            to_account_id,
+                            amount_ref,
+                            description_ref,
+                        )
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    if let Some(idempotency) = idempotency_request {
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, result_ref)
+                            .await?;
+                    }
+                    pending.value.projected = true;
+                    Ok(pending)
+                },
+            )
+            .await?;
+
+        // A concurrent call with the same key completed first: return its result
+        if let Some(idempotency) = idempotency_request.filter(|_| !prepared.projected) {
+            return self.replayed_hold(idempotency, event_ids[0], &command).await;
+        }
+
+        let PreparedHold { from_account, event, .. } = prepared;
+        let available_before = from_account.available_balance();
+        let from_account = from_account.apply(event);
+        self.event_store
+            .save_snapshot_if_needed(&from_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::HoldPlaced)
+            .resource_type("Hold")
+            .resource_id(hold_id)
+            .before_state(&json!({ "available_balance": available_before }))
+            .after_state(&json!({
+                "status": "held",
+                "account_id": from_account_id,
+                "to_account_id": to_account_id,
+                "amount": amount.value(),
+                "available_balance": from_account.available_balance(),
+            }))
+            .changed_fields(vec!["status".to_string(), "available_balance".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        Ok(result)
+    }
+
+    /// Result of the hold that completed `idempotency`, whose append returned
+    /// the recorded `event_id` instead of a new event. Keys completed before
+    /// hold results were stored are answered from their BalanceHeld event.
+    async fn replayed_hold(
+        &self,
+        idempotency: &IdempotencyRequest,
+        event_id: Uuid,
+        command: &HoldCommand,
+    ) -> Result<HoldResult, AppError> {
+        if let Some(result) = self.event_store.stored_result(idempotency).await? {
+            return Ok(result);
+        }
+
+        let hold_id = self
+            .event_store
+            .get_event(event_id)
+            .await?
+            .filter(|event| event.event_type == "BalanceHeld")
+            .and_then(|event| event.event_data.get("hold_id")?.as_str()?.parse().ok())
+            .ok_or(AppError::IdempotencyConflict)?;
+
+        Ok(HoldResult {
+            hold_id,
+            from_user_id: command.from_user_id.into(),
+            to_user_id: command.to_user_id.into(),
+            amount: command.amount.value(),
+            status: "held".to_string(),
+            transfer_id: None,
+        })
+    }
+
+    /// Capture a hold: debit the payer and credit the payee
+    pub async fn capture(
+        &self,
+        hold_id: Uuid,
+        idempotency_key: Option<Uuid>,
+        context: &OperationContext,
+    ) -> Result<HoldResult, AppError> {
+        let idempotency = idempotency_key
+            .map(|key| {
+                IdempotencyRequest::for_command(key, &SettleHoldCommand::capture(hold_id), context)
+            })
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let hold = self.get_hold(hold_id, context).await?;
+
+        // A completed key returns the original result; checked before the
+        // status so a retry of a capture that went through still succeeds
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
+        }
+        ensure_held(&hold)?;
+
+        let transfer_id = Uuid::new_v4();
+        let amount = Amount::new(hold.amount).map_err(|e| AppError::Internal(e.to_string()))?;
+        let result = hold_result(&hold, "captured", Some(transfer_id));
+
+        // Reload both accounts on each attempt; a concurrent release makes the
+        // retry fail with the hold no longer active. The ledger, the hold's
+        // status and the result for replays of the key commit with the events.
+        let (hold_ref, amount_ref, result_ref) = (&hold, &amount, &result);
+        let idempotency_request = idempotency.as_ref();
+        let (event_ids, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                || async move {
+                    let from_account = self.load_account(hold_ref.account_id).await?;
+                    let to_account = self.load_account(hold_ref.to_account_id).await?;
+
+                    let capture_event = from_account.capture_hold(hold_id, transfer_id)?;
+                    let credit_event =
+                        to_account.credit(amount_ref, transfer_id, "Hold capture".to_string())?;
+
+                    let operations = vec![
+                        AggregateOperation::new(
+                            "Account",
+                            hold_ref.account_id,
+                            from_account.version(),
+                            capture_event.event_type(),
+                            &capture_event,
+                        )?,
+                        AggregateOperation::new(
+                            "Account",
+                            hold_ref.to_account_id,
+                            to_account.version(),
+                            credit_event.event_type(),
+                            &credit_event,
+                        )?,
+                    ];
+                    let prepared = PreparedCapture {
+                        from_account,
+                        to_account,
+                        capture_event,
+                        credit_event,
+                        projected: false,
+                    };
+                    Ok::<_, AppError>((operations, prepared))
+                },
+                |mut pending: PendingAppend<PreparedCapture, E::Tx>| async move {
+                    let prepared = &pending.value;
+                    self.projection
+                        .apply_hold_capture_in_tx(
+                            &mut pending.tx,
+                            hold_id,
+                            transfer_id,
+                            pending.event_ids[0],
+                            hold_ref.account_id,
+                            hold_ref.to_account_id,
+                            amount_ref,
+                            LedgerDescriptions::from_events(
+                                &prepared.capture_event,
+                                &prepared.credit_event,
+                            )
+                            .journal(JournalType::HoldCapture, None, context.request_user_id),
+                            LegVersions::after(&prepared.from_account, &prepared.to_account),
+                        )
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    if let Some(idempotency) = idempotency_request {
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, result_ref)
+                            .await?;
+                    }
+                    pending.value.projected = true;
+                    Ok(pending)
+                },
+            )
+            .await?;
+
+        // A concurrent call with the same key completed first: return its result
+        if let Some(idempotency) = idempotency_request.filter(|_| !prepared.projected) {
+            return self
+                .replayed_settlement(idempotency, event_ids[0], &hold, "captured")
+                .await;
+        }
+
+        let PreparedCapture {
+            from_account,
+            to_account,
+            capture_event,
+            credit_event,
+            ..
+        } = prepared;
+        let from_account = from_account.apply(capture_event);
+        let to_account = to_account.apply(credit_event);
+        self.event_store
+            .save_snapshot_if_needed(&from_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        self.event_store
+            .save_snapshot_if_needed(&to_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::HoldCaptured)
+            .resource_type("Hold")
+            .resource_id(hold_id)
+            .before_state(&json!({ "status": "held" }))
+            .after_state(&json!({
+                "status": "captured",
+                "transfer_id": transfer_id,
+                "amount": hold.amount,
+            }))
+            .changed_fields(vec!["status".to_string(), "transfer_id".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        Ok(result)
+    }
+
+    /// Release a hold: return the funds to the payer's available balance
+    pub async fn release(
+        &self,
+        hold_id: Uuid,
+        idempotency_key: Option<Uuid>,
+        context: &OperationContext,
+    ) -> Result<HoldResult, AppError> {
+        let idempotency = idempotency_key
+            .map(|key| {
+                IdempotencyRequest::for_command(key, &SettleHoldCommand::release(hold_id), context)
+            })
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let hold = self.get_hold(hold_id, context).await?;
+
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
+        }
+        ensure_held(&hold)?;
+
+        let result = hold_result(&hold, "released", None);
+
+        let (account_id, result_ref) = (hold.account_id, &result);
+        let idempotency_request = idempotency.as_ref();
+        let (event_ids, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                || async move {
+                    let from_account = self.load_account(account_id).await?;
+                    let event = from_account.release_hold(hold_id)?;
+                    let operation = AggregateOperation::new(
+                        "Account",
+                        account_id,
+                        from_account.version(),
+                        event.event_type(),
+                        &event,
+                    )?;
+                    let prepared = PreparedRelease {
+                        from_account,
+                        event,
+                        projected: false,
+                    };
+                    Ok::<_, AppError>((vec![operation], prepared))
+                },
+                |mut pending: PendingAppend<PreparedRelease, E::Tx>| async move {
+                    self.projection
+                        .apply_hold_release_in_tx(&mut pending.tx, hold_id)
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    if let Some(idempotency) = idempotency_request {
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, result_ref)
+                            .await?;
+                    }
+                    pending.value.projected = true;
+                    Ok(pending)
+                },
+            )
+            .await?;
+
+        if let Some(idempotency) = idempotency_request.filter(|_| !prepared.projected) {
+            return self
+                .replayed_settlement(idempotency, event_ids[0], &hold, "released")
+                .await;
+        }
+
+        let PreparedRelease { from_account, event, .. } = prepared;
+        let from_account = from_account.apply(event);
+        self.event_store
+            .save_snapshot_if_needed(&from_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::HoldReleased)
+            .resource_type("Hold")
+            .resource_id(hold_id)
+            .before_state(&json!({ "status": "held" }))
+            .after_state(&json!({ "status": "released", "amount": hold.amount }))
+            .changed_fields(vec!["status".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        Ok(result)
+    }
+
+    /// Result of the capture or release that completed `idempotency`, whose
+    /// append returned the recorded `event_id` instead of a new event. A
+    /// capture's transfer ID is read back from its HoldCaptured event when
+    /// no result was stored.
+    async fn replayed_settlement(
+        &self,
+        idempotency: &IdempotencyRequest,
+        event_id: Uuid,
+        hold: &AccountHold,
+        status: &str,
+    ) -> Result<HoldResult, AppError> {
+        if let Some(result) = self.event_store.stored_result(idempotency).await? {
+            return Ok(result);
+        }
+
+        let event = self
+            .event_store
+            .get_event(event_id)
+            .await?
+            .filter(|event| {
+                event.event_data.get("hold_id").and_then(|id| id.as_str())
+                    == Some(hold.id.to_string().as_str())
+            })
+            .ok_or(AppError::IdempotencyConflict)?;
+
+        let transfer_id = match (status, event.event_type.as_str()) {
+            ("captured", "HoldCaptured") => Some(
+                event
+                    .event_data
+                    .get("transfer_id")
+                    .and_then(|id| id.as_str()?.parse().ok())
+                    .ok_or(AppError::IdempotencyConflict)?,
+            ),
+            ("released", "HoldReleased") => None,
+            _ => return Err(AppError::IdempotencyConflict),
+        };
+
+        Ok(hold_result(hold, status, transfer_id))
+    }
+
+    /// Load a hold and check that the request user is its payee
+    async fn get_hold(
+        &self,
+        hold_id: Uuid,
+        context: &OperationContext,
+    ) -> Result<AccountHold, AppError> {
+        let request_user_id = context
+            .request_user_id
+            .ok_or_else(|| AppError::MissingHeader("X-Request-User-Id".to_string()))?;
+
+        let hold = self
+            .projection
+            .get_hold(hold_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::HoldNotFound(hold_id.to_string()))?;
+
+        if request_user_id != hold.to_user_id {
+            return Err(AppError::Forbidden(
+                "Only the payee can capture or release a hold".to_string(),
+            ));
+        }
+
+        Ok(hold)
+    }
+
+    async fn load_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        self.event_store
+            .load_aggregate(account_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::AccountNotFound(account_id.to_string()))
+    }
+
+    async fn get_wallet_account_id(&self, user_id: UserId) -> Result<Uuid, AppError> {
+        self.projection
+            .wallet_account_id(user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hold_command() {
+        let cmd = HoldCommand::new(UserId::new_v4(), UserId::new_v4(), "25.00".parse().unwrap())
+            .with_memo("Order #42".to_string());
+
+        assert_eq!(cmd.amount, "25.00".parse().unwrap());
+        assert_eq!(cmd.memo, Some("Order #42".to_string()));
+    }
+}
@@ -2,40 +2,132 @@
 //!
 //! Handles ATP minting (creation) from SYSTEM_MINT account.
 
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::aggregate::{Account, Aggregate};
-use crate::domain::{Amount, OperationContext};
+use crate::alerts::{AlertRules, AlertService};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::db::SystemAccounts;
+use crate::domain::{AccountEvent, Amount, DomainError, OperationContext, UserId};
 use crate::error::AppError;
-use crate::event_store::{AggregateOperation, EventStore};
-use crate::projection::ProjectionService;
+use crate::event_store::{AggregateOperation, EventStore, EventStoreTrait, IdempotencyRequest, PendingAppend};
+use crate::limits::{LimitOperation, LimitService, LimitTrait};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService, ProjectionTrait};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
 
+use super::replay::replayed_result;
 use super::{MintCommand, MintResult};
 
-/// System user IDs (must match database seed)
-const SYSTEM_MINT_USER_ID: &str = "00000000-0000-0000-0000-000000000001";
-
 // =========================================================================
 // M109: MintHandler
 // =========================================================================
 
+/// Accounts and events of one append attempt
+struct PreparedMint {
+    mint_account: Account,
+    recipient_account: Account,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
+}
+
+/// Minted supply against the configured cap
+#[derive(Debug, Clone, Serialize)]
+pub struct SupplyCapStatus {
+    /// None when minting is uncapped
+    pub cap: Option<Decimal>,
+    /// SYSTEM_MINT liability (total ever minted)
+    pub minted_to_date: Decimal,
+    /// How much more may be minted (None when uncapped)
+    pub remaining: Option<Decimal>,
+    pub system_mint_account_id: Uuid,
+}
+
 /// Handler for ATP minting
-pub struct MintHandler {
-    event_store: EventStore,
-    projection: ProjectionService,
-    pool: PgPool,
+pub struct MintHandler<E = EventStore, P = ProjectionService, L = LimitService> {
+    event_store: E,
+    projection: P,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    alerts: AlertService,
+    limits: L,
+    /// Most ATP that may be minted in total
+    supply_cap: Option<Decimal>,
+    system_accounts: SystemAccounts,
 }
 
 impl MintHandler {
-    pub fn new(pool: PgPool) -> Self {
-        Self {
-            event_store: EventStore::new(pool.clone()),
-            projection: ProjectionService::new(pool.clone()),
+    pub fn new(pool: PgPool, system_accounts: SystemAccounts) -> Self {
+        Self::with_services(
+            EventStore::new(pool.clone()),
+            ProjectionService::new(pool.clone()),
+            LimitService::new(pool.clone()).with_system_users(system_accounts.users()),
+            system_accounts,
             pool,
+        )
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            alerts: state.alerts.clone(),
+            limits: state.limits.clone(),
+            supply_cap: state.config.mint_supply_cap,
+            system_accounts: state.system_accounts,
+        }
+    }
+}
+
+impl<E: EventStoreTrait, P: ProjectionTrait<Tx = E::Tx>, L: LimitTrait> MintHandler<E, P, L> {
+    /// Use the given event store, projections and limits (e.g. in-memory
+    /// ones in tests); the audit log, webhooks and alerts still use `pool`
+    pub fn with_services(
+        event_store: E,
+        projection: P,
+        limits: L,
+        system_accounts: SystemAccounts,
+        pool: PgPool,
+    ) -> Self {
+        Self {
+            event_store,
+            projection,
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            alerts: AlertService::new(pool, AlertRules::default()),
+            limits,
+            supply_cap: None,
+            system_accounts,
         }
     }
 
+    /// Refuse mints that would take the minted total above `cap`
+    pub fn with_supply_cap(mut self, cap: Option<Decimal>) -> Self {
+        self.supply_cap = cap;
+        self
+    }
+
+    /// Report the minted supply against the cap
+    pub async fn supply_cap_status(&self) -> Result<SupplyCapStatus, AppError> {
+        let system_mint_account_id = self.system_accounts.mint_account_id;
+        let mint_account = self.load_system_account(system_mint_account_id).await?;
+        let minted_to_date = minted_to_date(&mint_account);
+
+        Ok(SupplyCapStatus {
+            cap: self.supply_cap,
+            minted_to_date,
+            remaining: self.supply_cap.map(|cap| (cap - minted_to_date).max(Decimal::ZERO)),
+            system_mint_account_id,
+        })
+    }
+
     /// Execute the mint command
     pub async fn execute(
         &self,
@@ -43,88 +135,96 @@ impl MintHandler {
         idempotency_key: Option<Uuid>,
         context: &OperationContext,
     ) -> Result<MintResult, AppError> {
-        // Parse and validate amount
-        let amount: Amount = command
-            .amount
-            .parse()
-            .map_err(|e| AppError::InvalidRequest(format!("Invalid amount: {}", e)))?;
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyRequest::for_command(key, &command, context))
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        // M110: Get SYSTEM_MINT account
-        let system_mint_user_id: Uuid = SYSTEM_MINT_USER_ID
-            .parse()
-            .expect("Invalid SYSTEM_MINT_USER_ID");
+        // A completed key returns the original result, before any check
+        // that may no longer pass
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
+        }
 
-        let mint_account_id = self.get_system_account_id(system_mint_user_id).await?;
+        let amount = command.amount.clone();
 
-        // Get recipient's wallet account
-        let recipient_account_id = self.get_wallet_account_id(command.recipient_user_id).await?;
+        // Enforce configured mint limits and the API key's mint caps
+        self.limits
+            .check(LimitOperation::Mint, command.recipient_user_id.into(), amount.value())
+            .await?;
+        self.limits
+            .check_api_key(LimitOperation::Mint, context.api_key_id, amount.value())
+            .await?;
 
-        // Load SYSTEM_MINT account from DB (bypasses event sourcing for system accounts)
-        let mint_account = self.load_system_account(mint_account_id).await?;
+        // M110: Get SYSTEM_MINT account
+        let mint_account_id = self.system_accounts.mint_account_id;
 
-        // Load recipient's account (use event sourcing if available, fallback to DB)
-        let recipient_account = self.load_account_with_fallback(recipient_account_id).await?;
+        // Get recipient's wallet account
+        let recipient_account_id = self.get_wallet_account_id(command.recipient_user_id).await?;
 
         // Generate mint ID
         let mint_id = Uuid::new_v4();
-
-        // For minting, SYSTEM_MINT is debited (creates liability)
-        // and recipient is credited
-        let debit_description = format!("Mint: {}", command.reason);
-        let credit_description = format!("Received from mint: {}", command.reason);
-
-        // Note: SYSTEM_MINT can go negative (it's a liability account)
-        // We bypass the normal debit check by directly creating the event
-        let debit_event = crate::domain::AccountEvent::MoneyDebited {
-            account_id: mint_account_id,
+        let mint_result = |event_ids: &[Uuid], prepared: &PreparedMint| MintResult {
+            mint_id,
+            recipient_user_id: command.recipient_user_id.into(),
             amount: amount.value(),
-            transfer_id: mint_id,
-            description: debit_description,
-            debited_at: chrono::Utc::now(),
+            recipient_account_version: Some(prepared.recipient_account.version() + 1),
+            event_ids: event_ids.to_vec(),
         };
 
-        let credit_event = recipient_account
-            .credit(&amount, mint_id, credit_description)?;
-
-        // Prepare atomic operations
-        let operations = vec![
-            AggregateOperation::new(
-                "Account",
-                mint_account_id,
-                mint_account.version(),
-                "MoneyDebited",
-                &debit_event,
-            )
-            .map_err(|e| AppError::Internal(e.to_string()))?,
-            AggregateOperation::new(
-                "Account",
-                recipient_account_id,
-                recipient_account.version(),
-                credit_event.event_type(),
-                &credit_event,
-            )
-            .map_err(|e| AppError::Internal(e.to_string()))?,
-        ];
-
-        // Persist events atomically
-        let event_ids = self
+        // Persist events atomically, reloading both accounts on each attempt;
+        // the result is stored with them for replays of the idempotency key
+        let idempotency_request = idempotency.as_ref();
+        let (event_ids, prepared) = self
             .event_store
-            .append_atomic(operations, idempotency_key, context)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                || {
+                    self.prepare_operations(
+                        mint_account_id,
+                        recipient_account_id,
+                        mint_id,
+                        &amount,
+                        &command.reason,
+                    )
+                },
+                |mut pending: PendingAppend<PreparedMint, E::Tx>| async move {
+                    if let Some(idempotency) = idempotency_request {
+                        let result = mint_result(&pending.event_ids, &pending.value);
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, &result)
+                            .await?;
+                    }
+                    Ok::<_, AppError>(pending)
+                },
+            )
+            .await?;
 
         // Check for idempotency early return (only 1 event ID returned for 2 operations means cached)
-        if event_ids.len() == 1 && idempotency_key.is_some() {
-            // This was an idempotent request - return cached result (skip projection update)
-            // Note: The original mint_id is not stored, so we generate a new one for the response
-            // In production, you'd want to store and retrieve the original response
-            return Ok(MintResult {
-                mint_id: event_ids[0], // Use the cached event ID as mint_id
-                recipient_user_id: command.recipient_user_id,
+        if let Some(idempotency) = idempotency.as_ref().filter(|_| event_ids.len() == 1) {
+            // A concurrent call with the same key completed first: return its
+            // result (skip projection update)
+            return replayed_result(&self.event_store, idempotency, event_ids[0], |mint_id| MintResult {
+                mint_id,
+                recipient_user_id: command.recipient_user_id.into(),
                 amount: amount.value(),
-            });
+                recipient_account_version: None,
+                event_ids: Vec::new(),
+            })
+            .await;
         }
 
+        let result = mint_result(&event_ids, &prepared);
+        let PreparedMint {
+            mint_account,
+            recipient_account,
+            debit_event,
+            credit_event,
+        } = prepared;
+
         // Update projections (only for new requests)
         self.projection
             .apply_mint(
@@ -133,11 +233,18 @@ impl MintHandler {
                 mint_account_id,
                 recipient_account_id,
                 &amount,
-                mint_account.version() + 1,
+                LedgerDescriptions::from_events(&debit_event, &credit_event).journal(
+                    JournalType::Mint,
+                    Some(&command.reason),
+                    context.request_user_id,
+                ),
+                LegVersions::after(&mint_account, &recipient_account),
             )
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
 
+        let recipient_balance_before = recipient_account.balance().value();
+
         // Apply events to get updated accounts
         let mint_account = mint_account.apply(debit_event);
         let recipient_account = recipient_account.apply(credit_event);
@@ -152,76 +259,118 @@ impl MintHandler {
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        Ok(MintResult {
-            mint_id,
-            recipient_user_id: command.recipient_user_id,
-            amount: amount.value(),
-        })
-    }
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::MintExecuted)
+            .resource_type("Account")
+            .resource_id(recipient_account_id)
+            .before_state(&json!({ "balance": recipient_balance_before }))
+            .after_state(&json!({
+                "mint_id": mint_id,
+                "recipient_user_id": command.recipient_user_id,
+                "amount": amount.value(),
+                "reason": command.reason,
+                "balance": recipient_account.balance().value(),
+            }))
+            .changed_fields(vec!["balance".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::MintExecuted,
+                json!({
+                    "mint_id": mint_id,
+                    "recipient_user_id": command.recipient_user_id,
+                    "amount": amount.value(),
+                    "reason": command.reason,
+                }),
+            )
+            .await;
 
-    async fn get_system_account_id(&self, user_id: Uuid) -> Result<Uuid, AppError> {
-        let account_id: Option<Uuid> = sqlx::query_scalar(
-            r#"
-            SELECT id FROM accounts 
-            WHERE user_id = $1
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        self.alerts
+            .check_mint(command.recipient_user_id.into(), mint_id, amount.value())
+            .await;
 
-        account_id.ok_or_else(|| AppError::Internal("System account not found".to_string()))
+        Ok(result)
     }
 
-    async fn get_wallet_account_id(&self, user_id: Uuid) -> Result<Uuid, AppError> {
-        let account_id: Option<Uuid> = sqlx::query_scalar(
-            r#"
-            SELECT id FROM accounts 
-            WHERE user_id = $1 AND account_type = 'user_wallet'
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+    /// Load the SYSTEM_MINT and recipient accounts and build the mint's operations
+    async fn prepare_operations(
+        &self,
+        mint_account_id: Uuid,
+        recipient_account_id: Uuid,
+        mint_id: Uuid,
+        amount: &Amount,
+        reason: &str,
+    ) -> Result<(Vec<AggregateOperation>, PreparedMint), AppError> {
+        // Load SYSTEM_MINT account from DB (bypasses event sourcing for system accounts)
+        let mint_account = self.load_system_account(mint_account_id).await?;
+
+        // Load recipient's account (use event sourcing if available, fallback to DB)
+        let recipient_account = self.load_account_with_fallback(recipient_account_id).await?;
+
+        // For minting, SYSTEM_MINT is debited (creates liability)
+        // and recipient is credited
+        let debit_description = format!("Mint: {}", reason);
+        let credit_description = format!("Received from mint: {}", reason);
+
+        // Checked against the freshly loaded liability on every attempt; a
+        // concurrent mint makes the append conflict and re-run this check
+        check_supply_cap(self.supply_cap, minted_to_date(&mint_account), amount.value())?;
+
+        // SYSTEM_MINT is a mint_source account, which may go negative (liability)
+        let debit_event = mint_account.debit(amount, mint_id, debit_description)?;
+
+        let credit_event = recipient_account.credit(amount, mint_id, credit_description)?;
 
-        account_id.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+        let operations = vec![
+            AggregateOperation::new(
+                "Account",
+                mint_account_id,
+                mint_account.version(),
+                "MoneyDebited",
+                &debit_event,
+            )?,
+            AggregateOperation::new(
+                "Account",
+                recipient_account_id,
+                recipient_account.version(),
+                credit_event.event_type(),
+                &credit_event,
+            )?,
+        ];
+
+        Ok((
+            operations,
+            PreparedMint {
+                mint_account,
+                recipient_account,
+                debit_event,
+                credit_event,
+            },
+        ))
     }
 
-    /// Load system account directly from DB (bypasses event sourcing)
-    async fn load_system_account(&self, account_id: Uuid) -> Result<Account, AppError> {
-        // Get account info from DB
-        let account_info: Option<(Uuid, Uuid, String, bool)> = sqlx::query_as(
-            r#"
-            SELECT id, user_id, account_type, is_active
-            FROM accounts
-            WHERE id = $1
-            "#,
-        )
-        .bind(account_id)
-        .fetch_optional(&self.pool)
-        .await?;
+    async fn get_wallet_account_id(&self, user_id: UserId) -> Result<Uuid, AppError> {
+        self.projection
+            .wallet_account_id(user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+    }
 
-        let (id, user_id, account_type, _is_active) = account_info
+    /// Load a system account from the projections (bypasses event sourcing)
+    async fn load_system_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        let record = self
+            .projection
+            .account_record(account_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
             .ok_or_else(|| AppError::Internal("SYSTEM_MINT account not found".to_string()))?;
 
-        // Get current balance from projection
-        let balance: Option<rust_decimal::Decimal> = sqlx::query_scalar(
-            "SELECT balance FROM account_balances WHERE account_id = $1"
-        )
-        .bind(account_id)
-        .fetch_optional(&self.pool)
-        .await?;
+        // Current version from events
+        let version = self.event_store.current_version(account_id).await?;
 
-        // Get current version from events
-        let version: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(MAX(version), 0) FROM events WHERE aggregate_id = $1"
-        )
-        .bind(account_id)
-        .fetch_one(&self.pool)
-        .await?;
-
-        // Construct Account from DB state
-        Ok(Account::from_db_state(id, user_id, account_type, balance.unwrap_or_default(), version))
+        Ok(Account::from_db_state(record.id, record.user_id, &record.account_type, record.balance, version)?)
     }
 
     /// Load account with event sourcing, fallback to DB if no events exist
@@ -239,6 +388,26 @@ impl MintHandler {
     }
 }
 
+/// Total ever minted: SYSTEM_MINT is only debited by mints, so its liability
+/// is the negative of its balance
+fn minted_to_date(mint_account: &Account) -> Decimal {
+    -mint_account.balance().value()
+}
+
+/// Reject a mint that would take the minted total above the cap
+fn check_supply_cap(cap: Option<Decimal>, minted: Decimal, amount: Decimal) -> Result<(), DomainError> {
+    match cap {
+        Some(cap) if minted + amount > cap => Err(DomainError::BusinessRuleViolation(format!(
+            "Mint of {} exceeds the supply cap of {} ({} minted, {} remaining)",
+            amount,
+            cap,
+            minted,
+            (cap - minted).max(Decimal::ZERO)
+        ))),
+        _ => Ok(()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -246,18 +415,23 @@ mod tests {
     #[test]
     fn test_mint_command() {
         let cmd = MintCommand::new(
-            Uuid::new_v4(),
-            "1000.00".to_string(),
+            UserId::new_v4(),
+            "1000.00".parse().unwrap(),
             "Initial balance".to_string(),
         );
 
-        assert_eq!(cmd.amount, "1000.00");
+        assert_eq!(cmd.amount, "1000.00".parse().unwrap());
         assert_eq!(cmd.reason, "Initial balance");
     }
 
     #[test]
-    fn test_system_mint_user_id() {
-        let id: Uuid = SYSTEM_MINT_USER_ID.parse().unwrap();
-        assert_eq!(id.to_string(), "00000000-0000-0000-0000-000000000001");
+    fn test_check_supply_cap() {
+        let cap = Some(Decimal::new(1000, 0));
+        assert!(check_supply_cap(cap, Decimal::new(900, 0), Decimal::new(100, 0)).is_ok());
+        assert!(matches!(
+            check_supply_cap(cap, Decimal::new(900, 0), Decimal::new(10001, 2)),
+            Err(DomainError::BusinessRuleViolation(_))
+        ));
+        assert!(check_supply_cap(None, Decimal::new(900, 0), Decimal::new(1_000_000, 0)).is_ok());
     }
 }
@@ -6,10 +6,20 @@
 mod commands;
 mod user_handler;
 mod transfer_handler;
+mod transfer_approval_handler;
 mod mint_handler;
 mod burn_handler;
 mod update_user_handler;
 mod deactivate_user_handler;
+mod reactivate_user_handler;
+mod anonymize_user_handler;
+mod rename_user_handler;
+mod freeze_account_handler;
+mod close_account_handler;
+mod account_handler;
+mod hold_handler;
+mod reversal_handler;
+mod replay;
 
 #[cfg(test)]
 mod tests;
@@ -17,8 +27,16 @@ mod tests;
 pub use commands::*;
 pub use user_handler::CreateUserHandler;
 pub use transfer_handler::TransferHandler;
-pub use mint_handler::MintHandler;
-pub use burn_handler::{BurnHandler, BurnCommand, BurnResult};
+pub use transfer_approval_handler::TransferApprovalHandler;
+pub use mint_handler::{MintHandler, SupplyCapStatus};
+pub use burn_handler::{BurnHandler, BurnCommand, BurnConsentPolicy, BurnResult};
 pub use update_user_handler::{UpdateUserHandler, UpdateUserCommand, UpdateUserResult};
 pub use deactivate_user_handler::{DeactivateUserHandler, DeactivateUserCommand, DeactivateUserResult};
-
+pub use reactivate_user_handler::{ReactivateUserHandler, ReactivateUserCommand, ReactivateUserResult};
+pub use anonymize_user_handler::{AnonymizeUserHandler, AnonymizeUserCommand, AnonymizeUserResult};
+pub use rename_user_handler::{RenameUserHandler, RenameUserCommand, RenameUserResult};
+pub use freeze_account_handler::{FreezeAccountHandler, FreezeAccountCommand, FreezeAccountResult};
+pub use close_account_handler::{CloseAccountHandler, CloseAccountCommand, CloseAccountResult};
+pub use account_handler::{CreateAccountHandler, CreateAccountCommand, CreateAccountResult};
+pub use hold_handler::{HoldHandler, HoldCommand, HoldResult};
+pub use reversal_handler::{ReverseTransferHandler, ReverseTransferCommand, ReverseTransferResult};
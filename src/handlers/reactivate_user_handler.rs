@@ -0,0 +1,138 @@
+//! Reactivate User Handler
+//!
+//! Handles reactivation of a previously deactivated user with event sourcing.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Aggregate, User};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{OperationContext, UserId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+// =========================================================================
+// ReactivateUserCommand
+// =========================================================================
+
+/// Command to reactivate a user
+#[derive(Debug, Clone)]
+pub struct ReactivateUserCommand {
+    pub user_id: UserId,
+}
+
+impl ReactivateUserCommand {
+    pub fn new(user_id: UserId) -> Self {
+        Self { user_id }
+    }
+}
+
+/// Result of a successful user reactivation
+#[derive(Debug, Clone)]
+pub struct ReactivateUserResult {
+    pub user_id: Uuid,
+    pub reactivated_at: DateTime<Utc>,
+}
+
+// =========================================================================
+// ReactivateUserHandler
+// =========================================================================
+
+/// Handler for user reactivation
+pub struct ReactivateUserHandler {
+    event_store: EventStore,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    pool: PgPool,
+}
+
+impl ReactivateUserHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            pool: state.pool.clone(),
+        }
+    }
+
+    /// Execute the reactivate user command
+    pub async fn execute(
+        &self,
+        command: ReactivateUserCommand,
+        context: &OperationContext,
+    ) -> Result<ReactivateUserResult, AppError> {
+        // Load user aggregate from event store
+        let user: User = self
+            .event_store
+            .load_aggregate(command.user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(command.user_id.to_string()))?;
+
+        // Generate reactivate event
+        let event = user.reactivate()?;
+        let reactivated_at = match &event {
+            crate::domain::UserEvent::UserReactivated { reactivated_at, .. } => *reactivated_at,
+            _ => Utc::now(),
+        };
+
+        // Prepare operation
+        let operation = AggregateOperation::new(
+            "User",
+            user.id(),
+            user.version(),
+            event.event_type(),
+            &event,
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Persist event
+        self.event_store
+            .append_atomic(vec![operation], None, context)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Sync users table (projection)
+        sqlx::query("UPDATE users SET is_active = true, deactivated_at = NULL, updated_at = $2 WHERE id = $1")
+            .bind(command.user_id)
+            .bind(reactivated_at)
+            .execute(&self.pool)
+            .await?;
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::UserReactivated)
+            .resource_type("User")
+            .resource_id(command.user_id)
+            .before_state(&json!({ "status": user.status() }))
+            .after_state(&json!({ "status": user.apply(event).status() }))
+            .changed_fields(vec!["status".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::UserReactivated,
+                json!({ "user_id": command.user_id, "reactivated_at": reactivated_at }),
+            )
+            .await;
+
+        Ok(ReactivateUserResult {
+            user_id: command.user_id.into(),
+            reactivated_at,
+        })
+    }
+}
@@ -0,0 +1,200 @@
+//! Rename User Handler
+//!
+//! Usernames cannot be changed through profile updates; admins rename users
+//! here. Records a UserRenamed event and syncs the users projection in the
+//! same transaction, so a username claimed concurrently by another user
+//! leaves no event behind.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Aggregate, User};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{OperationContext, UserId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+// =========================================================================
+// RenameUserCommand
+// =========================================================================
+
+/// Command to change a user's username
+#[derive(Debug, Clone)]
+pub struct RenameUserCommand {
+    pub user_id: UserId,
+    pub new_username: String,
+}
+
+impl RenameUserCommand {
+    pub fn new(user_id: UserId, new_username: String) -> Self {
+        Self { user_id, new_username }
+    }
+}
+
+/// Result of a successful rename
+#[derive(Debug, Clone)]
+pub struct RenameUserResult {
+    pub user_id: Uuid,
+    pub old_username: String,
+    pub new_username: String,
+    pub renamed_at: DateTime<Utc>,
+}
+
+/// Unique constraint on (users.tenant_id, users.username)
+const USERNAME_UNIQUE_CONSTRAINT: &str = "users_tenant_username_key";
+
+/// Check whether a users write failed because the username belongs to another user
+fn is_username_taken(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|db| db.constraint())
+        .is_some_and(|constraint| constraint == USERNAME_UNIQUE_CONSTRAINT)
+}
+
+// =========================================================================
+// RenameUserHandler
+// =========================================================================
+
+/// Handler for admin renames
+pub struct RenameUserHandler {
+    event_store: EventStore,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    pool: PgPool,
+}
+
+impl RenameUserHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            pool: state.pool.clone(),
+        }
+    }
+
+    /// Execute the rename user command
+    pub async fn execute(
+        &self,
+        command: RenameUserCommand,
+        context: &OperationContext,
+    ) -> Result<RenameUserResult, AppError> {
+        // Load user aggregate from event store
+        let user: User = self
+            .event_store
+            .load_aggregate(command.user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(command.user_id.to_string()))?;
+
+        // System users are referenced by name in seeds and reports
+        let is_system: Option<bool> = sqlx::query_scalar("SELECT is_system FROM users WHERE id = $1")
+            .bind(command.user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        if is_system == Some(true) {
+            return Err(AppError::Forbidden("Cannot rename system user".to_string()));
+        }
+
+        let taken: bool = sqlx::query_scalar(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM users u
+                JOIN users renamed ON renamed.id = $2 AND renamed.tenant_id = u.tenant_id
+                WHERE u.username = $1 AND u.id <> $2
+            )
+            "#,
+        )
+        .bind(&command.new_username)
+        .bind(command.user_id)
+        .fetch_one(&self.pool)
+        .await?;
+        if taken {
+            return Err(AppError::UsernameTaken(command.new_username));
+        }
+
+        // Generate rename event
+        let event = user.rename(command.new_username.clone())?;
+        let renamed_at = match &event {
+            crate::domain::UserEvent::UserRenamed { renamed_at, .. } => *renamed_at,
+            _ => Utc::now(),
+        };
+
+        // Prepare operation
+        let operation = AggregateOperation::new(
+            "User",
+            user.id(),
+            user.version(),
+            event.event_type(),
+            &event,
+        )
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        let tx = self.event_store.begin().await?;
+        let (mut tx, _event_ids) = self
+            .event_store
+            .append_atomic_in_tx(tx, &[operation], None, context)
+            .await?;
+
+        // Sync users table (projection)
+        sqlx::query("UPDATE users SET username = $2, updated_at = $3 WHERE id = $1")
+            .bind(command.user_id)
+            .bind(&command.new_username)
+            .bind(renamed_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                if is_username_taken(&e) {
+                    AppError::UsernameTaken(command.new_username.clone())
+                } else {
+                    AppError::Database(e)
+                }
+            })?;
+
+        tx.commit().await?;
+
+        let old_username = user.username().to_string();
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::UserRenamed)
+            .resource_type("User")
+            .resource_id(command.user_id)
+            .before_state(&json!({ "username": old_username }))
+            .after_state(&json!({ "username": command.new_username }))
+            .changed_fields(vec!["username".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::UserRenamed,
+                json!({
+                    "user_id": command.user_id,
+                    "old_username": old_username,
+                    "new_username": command.new_username,
+                    "renamed_at": renamed_at,
+                }),
+            )
+            .await;
+
+        Ok(RenameUserResult {
+            user_id: command.user_id.into(),
+            old_username,
+            new_username: command.new_username,
+            renamed_at,
+        })
+    }
+}
@@ -0,0 +1,40 @@
+//! Idempotent replay
+//!
+//! A command retried with a completed idempotency key returns the result
+//! stored with its events (`EventStoreTrait::store_result_in_tx`), so the caller
+//! sees exactly what the first call returned.
+
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::error::AppError;
+use crate::event_store::{EventStoreTrait, IdempotencyRequest};
+
+/// Result of the call that completed `idempotency`, whose append returned
+/// the recorded `event_id` instead of new events.
+///
+/// Keys completed before results were stored fall back to `legacy`, given
+/// the transfer, mint or burn ID recorded in that event.
+pub(super) async fn replayed_result<R: DeserializeOwned + Send>(
+    event_store: &impl EventStoreTrait,
+    idempotency: &IdempotencyRequest,
+    event_id: Uuid,
+    legacy: impl FnOnce(Uuid) -> R,
+) -> Result<R, AppError> {
+    if let Some(result) = event_store.stored_result(idempotency).await? {
+        return Ok(result);
+    }
+
+    let journal_id = event_store
+        .get_event(event_id)
+        .await?
+        .and_then(|event| event.event_data.get("transfer_id")?.as_str()?.parse().ok())
+        .ok_or_else(|| {
+            AppError::Internal(format!(
+                "No stored result for idempotency key {}",
+                idempotency.key
+            ))
+        })?;
+
+    Ok(legacy(journal_id))
+}
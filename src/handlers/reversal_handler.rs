@@ -0,0 +1,405 @@
+//! Reversal Handler
+//!
+//! Reverses a completed transfer with a compensating transfer from the
+//! original recipient back to the original sender.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate, Transfer};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountEvent, Amount, OperationContext, TransferId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore, EventStoreTrait, IdempotencyRequest, PendingAppend};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService, ProjectionTrait};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+/// Command to reverse a completed transfer
+#[derive(Debug, Clone, Serialize)]
+pub struct ReverseTransferCommand {
+    /// Transfer to reverse
+    pub transfer_id: TransferId,
+    /// Reason for the reversal
+    pub reason: Option<String>,
+}
+
+impl ReverseTransferCommand {
+    pub fn new(transfer_id: TransferId) -> Self {
+        Self {
+            transfer_id,
+            reason: None,
+        }
+    }
+
+    pub fn with_reason(mut self, reason: String) -> Self {
+        self.reason = Some(reason);
+        self
+    }
+}
+
+/// Result of a successful reversal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReverseTransferResult {
+    pub reversal_id: Uuid,
+    pub original_transfer_id: Uuid,
+    /// Original recipient, debited by the reversal
+    pub from_user_id: Uuid,
+    /// Original sender, credited by the reversal
+    pub to_user_id: Uuid,
+    pub amount: rust_decimal::Decimal,
+}
+
+/// Loaded aggregates and events of one append attempt
+struct PreparedReversal {
+    original: Transfer,
+    amount: Amount,
+    from_account: Account,
+    to_account: Account,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
+    /// Set once the attempt's events were projected (false on a replay)
+    projected: bool,
+}
+
+/// Handler for transfer reversals
+pub struct ReverseTransferHandler<E = EventStore, P = ProjectionService> {
+    event_store: E,
+    projection: P,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+}
+
+impl ReverseTransferHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self::with_services(EventStore::new(pool.clone()), ProjectionService::new(pool.clone()), pool)
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+        }
+    }
+}
+
+impl<E: EventStoreTrait, P: ProjectionTrait<Tx = E::Tx>> ReverseTransferHandler<E, P> {
+    /// Use the given event store and projections (e.g. in-memory ones in
+    /// tests); the audit log and webhooks still use `pool`
+    pub fn with_services(event_store: E, projection: P, pool: PgPool) -> Self {
+        Self {
+            event_store,
+            projection,
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool),
+        }
+    }
+
+    /// Execute the reversal command
+    pub async fn execute(
+        &self,
+        command: ReverseTransferCommand,
+        idempotency_key: Option<Uuid>,
+        context: &OperationContext,
+    ) -> Result<ReverseTransferResult, AppError> {
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyRequest::for_command(key, &command, context))
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // A completed key returns the original result instead of failing as
+        // already reversed
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
+        }
+
+        let reversal_id = Uuid::new_v4();
+        let reversed_by = context.request_user_id.unwrap_or_else(Uuid::nil);
+
+        // Persist events atomically, reloading the original transfer and both
+        // accounts on each attempt. The projection and the result for replays
+        // of the idempotency key commit with the events.
+        let (command_ref, idempotency_request) = (&command, idempotency.as_ref());
+        let (event_ids, prepared) = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                move || self.prepare_operations(command_ref, reversal_id, reversed_by),
+                move |mut pending: PendingAppend<PreparedReversal, E::Tx>| async move {
+                    let prepared = &pending.value;
+                    self.projection
+                        .apply_transfer_in_tx(
+                            &mut pending.tx,
+                            reversal_id,
+                            pending.event_ids[0],
+                            prepared.from_account.id(),
+                            prepared.to_account.id(),
+                            &prepared.amount,
+                            LedgerDescriptions::from_events(&prepared.debit_event, &prepared.credit_event)
+                                .journal(
+                                    JournalType::Reversal,
+                                    command_ref.reason.as_deref(),
+                                    context.request_user_id,
+                                ),
+                            LegVersions::after(&prepared.from_account, &prepared.to_account),
+                        )
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    if let Some(idempotency) = idempotency_request {
+                        let result = ReverseTransferResult {
+                            reversal_id,
+                            original_transfer_id: prepared.original.id(),
+                            from_user_id: prepared.from_account.user_id(),
+                            to_user_id: prepared.to_account.user_id(),
+                            amount: prepared.amount.value(),
+                        };
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, &result)
+                            .await?;
+                    }
+                    pending.value.projected = true;
+                    Ok(pending)
+                },
+            )
+            .await?;
+
+        // A concurrent call with the same key completed first: return its result
+        if let Some(idempotency) = idempotency_request.filter(|_| !prepared.projected) {
+            return self.replayed_reversal(idempotency, event_ids[0], &command).await;
+        }
+
+        let PreparedReversal {
+            original,
+            amount,
+            from_account,
+            to_account,
+            debit_event,
+            credit_event,
+            ..
+        } = prepared;
+
+        let from_balance_before = from_account.balance().value();
+        let to_balance_before = to_account.balance().value();
+
+        // Apply events to get updated accounts
+        let from_account = from_account.apply(debit_event);
+        let to_account = to_account.apply(credit_event);
+
+        // Save snapshots if needed
+        self.event_store
+            .save_snapshot_if_needed(&from_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        self.event_store
+            .save_snapshot_if_needed(&to_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::TransferReversed)
+            .resource_type("Transfer")
+            .resource_id(original.id())
+            .before_state(&json!({
+                "status": original.status().as_str(),
+                "from_balance": from_balance_before,
+                "to_balance": to_balance_before,
+            }))
+            .after_state(&json!({
+                "status": "reversed",
+                "reversal_id": reversal_id,
+                "reason": command.reason,
+                "amount": amount.value(),
+                "from_balance": from_account.balance().value(),
+                "to_balance": to_account.balance().value(),
+            }))
+            .changed_fields(vec![
+                "status".to_string(),
+                "from_balance".to_string(),
+                "to_balance".to_string(),
+            ]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::TransferReversed,
+                json!({
+                    "transfer_id": original.id(),
+                    "reversal_id": reversal_id,
+                    "from_user_id": from_account.user_id(),
+                    "to_user_id": to_account.user_id(),
+                    "amount": amount.value(),
+                    "reason": command.reason,
+                }),
+            )
+            .await;
+
+        Ok(ReverseTransferResult {
+            reversal_id,
+            original_transfer_id: original.id(),
+            from_user_id: from_account.user_id(),
+            to_user_id: to_account.user_id(),
+            amount: amount.value(),
+        })
+    }
+
+    /// Load the original transfer and both accounts and build the reversal's operations
+    async fn prepare_operations(
+        &self,
+        command: &ReverseTransferCommand,
+        reversal_id: Uuid,
+        reversed_by: Uuid,
+    ) -> Result<(Vec<AggregateOperation>, PreparedReversal), AppError> {
+        // Mints, burns and pre-saga transfers have no Transfer aggregate and cannot be reversed
+        let original: Transfer = self
+            .event_store
+            .load_aggregate(command.transfer_id.into())
+            .await?
+            .ok_or_else(|| {
+                AppError::InvalidRequest(format!("Transfer {} not found", command.transfer_id))
+            })?;
+
+        // Rejects pending, failed and already reversed transfers
+        let reversed_event = original.reverse(reversal_id, command.reason.clone(), reversed_by)?;
+
+        let amount = Amount::new(original.amount())
+            .map_err(|e| AppError::Internal(format!("Invalid transfer amount: {}", e)))?;
+
+        // Money flows back from the original recipient to the original sender
+        let from_account: Account = self.load_account(original.to_account_id()).await?;
+        let to_account: Account = self.load_account(original.from_account_id()).await?;
+
+        let (reversal, initiated_event) = Transfer::initiate_reversal(
+            reversal_id,
+            &original,
+            &from_account,
+            &to_account,
+            reversed_by,
+        );
+
+        let description = format!("Reversal of transfer {}", original.id());
+        let debit_event = from_account.debit(&amount, reversal_id, description.clone())?;
+        let credit_event = to_account.credit(&amount, reversal_id, description)?;
+        let completed_event = reversal.complete()?;
+
+        // Account events come first so event_ids[0] stays the debit event.
+        // TransferReversed is appended at the original's current version, so a
+        // concurrent second reversal conflicts and, once reloaded, is rejected
+        // as already reversed.
+        let operations = vec![
+            AggregateOperation::new(
+                "Account",
+                from_account.id(),
+                from_account.version(),
+                debit_event.event_type(),
+                &debit_event,
+            )?,
+            AggregateOperation::new(
+                "Account",
+                to_account.id(),
+                to_account.version(),
+                credit_event.event_type(),
+                &credit_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                reversal_id,
+                0,
+                initiated_event.event_type(),
+                &initiated_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                reversal_id,
+                reversal.version(),
+                completed_event.event_type(),
+                &completed_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                original.id(),
+                original.version(),
+                reversed_event.event_type(),
+                &reversed_event,
+            )?,
+        ];
+
+        Ok((
+            operations,
+            PreparedReversal {
+                original,
+                amount,
+                from_account,
+                to_account,
+                debit_event,
+                credit_event,
+                projected: false,
+            },
+        ))
+    }
+
+    /// Result of the reversal that completed `idempotency`, whose append
+    /// returned the recorded `event_id` instead of new events. Keys completed
+    /// before reversal results were stored are answered from the reversal's
+    /// debit event and the original transfer.
+    async fn replayed_reversal(
+        &self,
+        idempotency: &IdempotencyRequest,
+        event_id: Uuid,
+        command: &ReverseTransferCommand,
+    ) -> Result<ReverseTransferResult, AppError> {
+        if let Some(result) = self.event_store.stored_result(idempotency).await? {
+            return Ok(result);
+        }
+
+        let reversal_id = self
+            .event_store
+            .get_event(event_id)
+            .await?
+            .and_then(|event| event.event_data.get("transfer_id")?.as_str()?.parse().ok())
+            .ok_or(AppError::IdempotencyConflict)?;
+        let original: Transfer = self
+            .event_store
+            .load_aggregate(command.transfer_id.into())
+            .await?
+            .ok_or(AppError::IdempotencyConflict)?;
+        let from_account = self.load_account(original.to_account_id()).await?;
+        let to_account = self.load_account(original.from_account_id()).await?;
+
+        Ok(ReverseTransferResult {
+            reversal_id,
+            original_transfer_id: original.id(),
+            from_user_id: from_account.user_id(),
+            to_user_id: to_account.user_id(),
+            amount: original.amount(),
+        })
+    }
+
+    async fn load_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        self.event_store
+            .load_aggregate(account_id)
+            .await?
+            .ok_or_else(|| AppError::AccountNotFound(account_id.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reverse_transfer_command() {
+        let transfer_id = TransferId::new_v4();
+        let cmd = ReverseTransferCommand::new(transfer_id).with_reason("Chargeback".to_string());
+
+        assert_eq!(cmd.transfer_id, transfer_id);
+        assert_eq!(cmd.reason, Some("Chargeback".to_string()));
+    }
+}
@@ -1,17 +1,15 @@
 //! Integration tests for handlers
 //!
-//! These tests require a database connection.
-//! Run with: cargo test --features integration_tests
+//! Handlers built on the in-memory event store and projection run without a
+//! database; the rest cover commands and aggregates.
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::aggregate::{Account, Aggregate};
-    use crate::domain::{Amount, Balance, OperationContext};
+    use crate::domain::{AccountId, AccountType, Amount, UserId};
     use crate::error::AppError;
-    use crate::handlers::{
-        CreateUserCommand, CreateUserHandler, MintCommand, MintHandler, TransferCommand,
-        TransferHandler,
-    };
+    use crate::handlers::{CreateUserCommand, MintCommand, TransferCommand};
     use rust_decimal::Decimal;
     use std::str::FromStr;
     use uuid::Uuid;
@@ -22,7 +20,7 @@ mod tests {
 
     #[test]
     fn test_create_user_command_validation() {
-        let user_id = Uuid::new_v4();
+        let user_id = UserId::new_v4();
         let cmd = CreateUserCommand::new(
             user_id,
             "testuser".to_string(),
@@ -38,7 +36,7 @@ mod tests {
     #[test]
     fn test_create_user_command_with_display_name() {
         let cmd = CreateUserCommand::new(
-            Uuid::new_v4(),
+            UserId::new_v4(),
             "alice".to_string(),
             "alice@example.com".to_string(),
         )
@@ -53,19 +51,19 @@ mod tests {
 
     #[test]
     fn test_transfer_command_validation() {
-        let from = Uuid::new_v4();
-        let to = Uuid::new_v4();
-        let cmd = TransferCommand::new(from, to, "100.50".to_string());
+        let from = UserId::new_v4();
+        let to = UserId::new_v4();
+        let cmd = TransferCommand::new(from, to, "100.50".parse().unwrap());
 
         assert_eq!(cmd.from_user_id, from);
         assert_eq!(cmd.to_user_id, to);
-        assert_eq!(cmd.amount, "100.50");
+        assert_eq!(cmd.amount, "100.50".parse().unwrap());
         assert!(cmd.memo.is_none());
     }
 
     #[test]
     fn test_transfer_command_with_memo() {
-        let cmd = TransferCommand::new(Uuid::new_v4(), Uuid::new_v4(), "50.00".to_string())
+        let cmd = TransferCommand::new(UserId::new_v4(), UserId::new_v4(), "50.00".parse().unwrap())
             .with_memo("Payment for services".to_string());
 
         assert_eq!(cmd.memo, Some("Payment for services".to_string()));
@@ -82,7 +80,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         // Create account with initial balance of 0
-        let (account, _event) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _event) = Account::create(account_id, user_id, AccountType::UserWallet);
 
         // Try to debit 100 ATP from account with 0 balance
         let amount = Amount::new(Decimal::from_str("100.00").unwrap()).unwrap();
@@ -106,7 +104,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         // Create account
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
 
         // Credit 50 ATP
         let credit_amount = Amount::new(Decimal::from_str("50.00").unwrap()).unwrap();
@@ -138,7 +136,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         // Create account
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
 
         // Credit 100 ATP
         let credit_amount = Amount::new(Decimal::from_str("100.00").unwrap()).unwrap();
@@ -163,7 +161,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         // Create account
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
 
         // Credit 100 ATP
         let credit_amount = Amount::new(Decimal::from_str("100.00").unwrap()).unwrap();
@@ -189,7 +187,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         // Create account - version starts at 1
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         assert_eq!(account.version(), 1);
 
         // Credit - version increments
@@ -218,7 +216,7 @@ mod tests {
 
         // Create account
         let (account, create_event) =
-            Account::create(account_id, user_id, "user_wallet".to_string());
+            Account::create(account_id, user_id, AccountType::UserWallet);
 
         // Create operation with version 0 (expected for new aggregate)
         let op = AggregateOperation::new(
@@ -244,7 +242,7 @@ mod tests {
         let user_id = Uuid::new_v4();
 
         // Create account
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
 
         // Simulate two "transactions" loading the same account state
         let account_tx1 = account.clone();
@@ -274,15 +272,15 @@ mod tests {
 
     #[test]
     fn test_mint_command_validation() {
-        let recipient = Uuid::new_v4();
+        let recipient = UserId::new_v4();
         let cmd = MintCommand::new(
             recipient,
-            "1000.00".to_string(),
+            "1000.00".parse().unwrap(),
             "Initial balance grant".to_string(),
         );
 
         assert_eq!(cmd.recipient_user_id, recipient);
-        assert_eq!(cmd.amount, "1000.00");
+        assert_eq!(cmd.amount, "1000.00".parse().unwrap());
         assert_eq!(cmd.reason, "Initial balance grant");
     }
 
@@ -319,13 +317,11 @@ mod tests {
 
     #[test]
     fn test_frozen_account_cannot_receive_credit() {
-        use crate::domain::AccountEvent;
-
         let account_id = Uuid::new_v4();
         let user_id = Uuid::new_v4();
 
         // Create and freeze account
-        let (account, _) = Account::create(account_id, user_id, "user_wallet".to_string());
+        let (account, _) = Account::create(account_id, user_id, AccountType::UserWallet);
         let freeze_event = account.freeze("Suspicious activity".to_string()).unwrap();
         let account = account.apply(freeze_event);
 
@@ -342,4 +338,346 @@ mod tests {
             Ok(_) => panic!("Expected error, got success"),
         }
     }
+
+    // =========================================================================
+    // Handlers against in-memory fakes (no database)
+    // =========================================================================
+
+    use crate::aggregate::Transfer;
+    use crate::db::SystemAccounts;
+    use crate::domain::OperationContext;
+    use crate::event_store::{AggregateOperation, EventStoreTrait, InMemoryEventStore};
+    use crate::handlers::{
+        BurnCommand, BurnHandler, FreezeAccountCommand, FreezeAccountHandler, HoldCommand, HoldHandler, MintHandler,
+        ReverseTransferCommand, ReverseTransferHandler, TransferHandler,
+    };
+    use crate::limits::{InMemoryLimits, LimitOperation};
+    use crate::projection::{InMemoryProjection, ProjectionTrait};
+    use crate::restrictions::{InMemoryRestrictions, RestrictionMode};
+    use crate::test_util::unreachable_pool;
+
+    async fn append<A: Aggregate>(
+        store: &InMemoryEventStore,
+        aggregate: &A,
+        version: i64,
+        event: &impl serde::Serialize,
+        event_type: &str,
+    ) {
+        let op = AggregateOperation::new(A::aggregate_type(), aggregate.id(), version, event_type, event).unwrap();
+        store.append_atomic(vec![op], None, &OperationContext::new()).await.unwrap();
+    }
+
+    /// Open an account holding `balance`
+    async fn open_account(store: &InMemoryEventStore, projection: &InMemoryProjection, balance: &str) -> Account {
+        let (account, created) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::UserWallet);
+        append(store, &account, 0, &created, created.event_type()).await;
+
+        let amount = Amount::new(Decimal::from_str(balance).unwrap()).unwrap();
+        let credited = account.credit(&amount, Uuid::new_v4(), "Opening balance".to_string()).unwrap();
+        append(store, &account, account.version(), &credited, credited.event_type()).await;
+        projection.add_account(&account);
+        projection.set_balance(account.id(), amount.value());
+        account.apply(credited)
+    }
+
+    fn amount(value: i64) -> Amount {
+        Amount::new(Decimal::from(value)).unwrap()
+    }
+
+    /// SYSTEM_MINT and SYSTEM_BURN accounts, registered without events like
+    /// the ones the migrations insert
+    fn system_accounts(projection: &InMemoryProjection) -> SystemAccounts {
+        let (mint, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::MintSource);
+        let (burn, _) = Account::create(Uuid::new_v4(), Uuid::new_v4(), AccountType::MintSource);
+        projection.add_account(&mint);
+        projection.add_account(&burn);
+        SystemAccounts {
+            mint_user_id: mint.user_id(),
+            mint_account_id: mint.id(),
+            burn_user_id: burn.user_id(),
+            burn_account_id: burn.id(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_freeze_account_with_in_memory_store() {
+        let store = InMemoryEventStore::new();
+        let account = open_account(&store, &InMemoryProjection::new(), "10").await;
+        let handler = FreezeAccountHandler::with_event_store(store.clone(), unreachable_pool());
+        let context = OperationContext::new();
+
+        let unreasoned = FreezeAccountCommand {
+            account_id: account.id().into(),
+            freeze: true,
+            reason: Some("  ".to_string()),
+        };
+        assert!(matches!(handler.execute(unreasoned, &context).await, Err(AppError::InvalidRequest(_))));
+
+        let command = FreezeAccountCommand::freeze(account.id().into(), "Chargeback".to_string());
+        let result = handler.execute(command, &context).await.unwrap();
+        assert!(result.frozen);
+
+        let frozen: Account = store.load_aggregate(account.id()).await.unwrap().unwrap();
+        assert!(frozen.is_frozen());
+        assert_eq!(store.events().last().unwrap().event_type, "AccountFrozen");
+
+        let missing = FreezeAccountCommand::unfreeze(AccountId::new_v4());
+        assert!(matches!(handler.execute(missing, &context).await, Err(AppError::AccountNotFound(_))));
+    }
+
+    /// Sender (100) and recipient (5) accounts and a completed transfer of
+    /// 30 between them, projected as balances 70 and 35
+    async fn completed_transfer(
+        store: &InMemoryEventStore,
+        projection: &InMemoryProjection,
+    ) -> (Transfer, Account, Account) {
+        let sender = open_account(store, projection, "100").await;
+        let recipient = open_account(store, projection, "5").await;
+
+        let amount = Amount::new(Decimal::from(30)).unwrap();
+        let (transfer, initiated) =
+            Transfer::initiate(Uuid::new_v4(), &sender, &recipient, &amount, None, None, sender.user_id());
+        let completed = transfer.complete().unwrap();
+        let debited = sender.debit(&amount, transfer.id(), "Transfer".to_string()).unwrap();
+        let credited = recipient.credit(&amount, transfer.id(), "Transfer".to_string()).unwrap();
+        append(store, &sender, sender.version(), &debited, debited.event_type()).await;
+        append(store, &recipient, recipient.version(), &credited, credited.event_type()).await;
+        append(store, &transfer, 0, &initiated, initiated.event_type()).await;
+        append(store, &transfer, transfer.version(), &completed, completed.event_type()).await;
+        projection.set_balance(sender.id(), Decimal::from(70));
+        projection.set_balance(recipient.id(), Decimal::from(35));
+
+        (transfer, sender, recipient)
+    }
+
+    #[tokio::test]
+    async fn test_reverse_transfer_with_in_memory_store() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let (transfer, sender, recipient) = completed_transfer(&store, &projection).await;
+
+        let handler = ReverseTransferHandler::with_services(store.clone(), projection.clone(), unreachable_pool());
+        let context = OperationContext::new().with_request_user(sender.user_id());
+        let command = ReverseTransferCommand::new(transfer.id().into()).with_reason("Chargeback".to_string());
+
+        let result = handler.execute(command.clone(), None, &context).await.unwrap();
+        assert_eq!(result.from_user_id, recipient.user_id());
+        assert_eq!(result.to_user_id, sender.user_id());
+        assert_eq!(result.amount, Decimal::from(30));
+
+        assert_eq!(projection.get_balance(sender.id()).await.unwrap(), Decimal::from(100));
+        assert_eq!(projection.get_balance(recipient.id()).await.unwrap(), Decimal::from(5));
+        let ledger = projection.ledger();
+        assert_eq!(ledger.len(), 2);
+        assert!(ledger.iter().all(|entry| entry.journal_id == result.reversal_id));
+
+        let sender: Account = store.load_aggregate(sender.id()).await.unwrap().unwrap();
+        assert_eq!(sender.balance().value(), Decimal::from(100));
+
+        // A transfer is reversed at most once
+        assert!(handler.execute(command, None, &context).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reverse_transfer_replays_idempotency_key() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let (transfer, sender, _) = completed_transfer(&store, &projection).await;
+
+        let handler = ReverseTransferHandler::with_services(store.clone(), projection.clone(), unreachable_pool());
+        let context = OperationContext::new().with_request_user(sender.user_id());
+        let command = ReverseTransferCommand::new(transfer.id().into());
+        let key = Uuid::new_v4();
+
+        let first = handler.execute(command.clone(), Some(key), &context).await.unwrap();
+        let events = store.events().len();
+
+        // The retry returns the stored result without reversing or projecting again
+        let replayed = handler.execute(command, Some(key), &context).await.unwrap();
+        assert_eq!(replayed.reversal_id, first.reversal_id);
+        assert_eq!(replayed.amount, Decimal::from(30));
+        assert_eq!(store.events().len(), events);
+        assert_eq!(projection.ledger().len(), 2);
+        assert_eq!(projection.get_balance(sender.id()).await.unwrap(), Decimal::from(100));
+    }
+
+    #[tokio::test]
+    async fn test_transfer_with_in_memory_services() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let sender = open_account(&store, &projection, "100").await;
+        let recipient = open_account(&store, &projection, "5").await;
+
+        let handler = TransferHandler::with_services(
+            store.clone(),
+            projection.clone(),
+            InMemoryLimits::new(),
+            InMemoryRestrictions::new(),
+            unreachable_pool(),
+        );
+        let context = OperationContext::new().with_request_user(sender.user_id());
+        let command = TransferCommand::new(sender.user_id().into(), recipient.user_id().into(), amount(40));
+        let key = Uuid::new_v4();
+
+        let result = handler.execute(command.clone(), Some(key), &context).await.unwrap();
+        assert_eq!(result.status, "completed");
+        assert_eq!(projection.get_balance(sender.id()).await.unwrap(), Decimal::from(60));
+        assert_eq!(projection.get_balance(recipient.id()).await.unwrap(), Decimal::from(45));
+        assert!(projection.ledger().iter().all(|entry| entry.journal_id == result.transfer_id));
+
+        // The retry returns the stored result without moving funds again
+        let events = store.events().len();
+        let replayed = handler.execute(command, Some(key), &context).await.unwrap();
+        assert_eq!(replayed.transfer_id, result.transfer_id);
+        assert_eq!(store.events().len(), events);
+        assert_eq!(projection.get_balance(sender.id()).await.unwrap(), Decimal::from(60));
+
+        // Above the approval threshold only the pending transfer is recorded
+        let handler = handler.with_approval_threshold(Some(Decimal::from(50)));
+        let large = TransferCommand::new(sender.user_id().into(), recipient.user_id().into(), amount(55));
+        let pending = handler.execute(large, None, &context).await.unwrap();
+        assert_eq!(pending.status, "pending_approval");
+        assert_eq!(projection.pending_transfers(), vec![pending.transfer_id]);
+        assert_eq!(projection.get_balance(sender.id()).await.unwrap(), Decimal::from(60));
+    }
+
+    #[tokio::test]
+    async fn test_transfer_records_rejections_as_failed() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let limits = InMemoryLimits::new();
+        let restrictions = InMemoryRestrictions::new();
+        let sender = open_account(&store, &projection, "100").await;
+        let recipient = open_account(&store, &projection, "5").await;
+
+        let handler = TransferHandler::with_services(
+            store.clone(),
+            projection.clone(),
+            limits.clone(),
+            restrictions.clone(),
+            unreachable_pool(),
+        );
+        let context = OperationContext::new().with_request_user(sender.user_id());
+        let command = TransferCommand::new(sender.user_id().into(), recipient.user_id().into(), amount(20));
+
+        limits.set(LimitOperation::Transfer, None, Some(Decimal::from(10)));
+        assert!(matches!(
+            handler.execute(command.clone(), None, &context).await,
+            Err(AppError::AmountTooLarge(_))
+        ));
+
+        limits.set(LimitOperation::Transfer, None, None);
+        restrictions.set(sender.id(), RestrictionMode::ReceiveOnly, Vec::new());
+        assert!(matches!(
+            handler.execute(command, None, &context).await,
+            Err(AppError::TransferBlocked(_))
+        ));
+
+        assert_eq!(projection.failed_transfers().len(), 2);
+        assert!(projection.ledger().is_empty());
+        assert_eq!(projection.get_balance(sender.id()).await.unwrap(), Decimal::from(100));
+    }
+
+    #[tokio::test]
+    async fn test_mint_with_in_memory_services() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let system = system_accounts(&projection);
+        let recipient = open_account(&store, &projection, "5").await;
+
+        let handler = MintHandler::with_services(
+            store.clone(),
+            projection.clone(),
+            InMemoryLimits::new(),
+            system,
+            unreachable_pool(),
+        )
+        .with_supply_cap(Some(Decimal::from(100)));
+        let context = OperationContext::new();
+        let command = MintCommand::new(recipient.user_id().into(), amount(60), "Grant".to_string());
+
+        let result = handler.execute(command, None, &context).await.unwrap();
+        assert_eq!(result.recipient_account_version, Some(recipient.version() + 1));
+        assert_eq!(projection.get_balance(recipient.id()).await.unwrap(), Decimal::from(65));
+        assert_eq!(projection.get_balance(system.mint_account_id).await.unwrap(), Decimal::from(-60));
+        let ledger = projection.ledger();
+        assert_eq!(ledger.len(), 2);
+        assert!(ledger.iter().all(|entry| entry.journal_id == result.mint_id));
+
+        // The cap is checked against SYSTEM_MINT's liability
+        let over_cap = MintCommand::new(recipient.user_id().into(), amount(50), "Grant".to_string());
+        assert!(handler.execute(over_cap, None, &context).await.is_err());
+        assert_eq!(projection.get_balance(recipient.id()).await.unwrap(), Decimal::from(65));
+    }
+
+    #[tokio::test]
+    async fn test_burn_with_in_memory_services() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let system = system_accounts(&projection);
+        let wallet = open_account(&store, &projection, "50").await;
+
+        let handler = BurnHandler::with_services(
+            store.clone(),
+            projection.clone(),
+            InMemoryLimits::new(),
+            system,
+            unreachable_pool(),
+        );
+        let context = OperationContext::new();
+        let command = BurnCommand::new(wallet.user_id().into(), amount(20), "Expired".to_string());
+        let key = Uuid::new_v4();
+
+        let result = handler.execute(command.clone(), Some(key), &context).await.unwrap();
+        assert_eq!(projection.get_balance(wallet.id()).await.unwrap(), Decimal::from(30));
+        assert_eq!(projection.get_balance(system.burn_account_id).await.unwrap(), Decimal::from(20));
+
+        let replayed = handler.execute(command, Some(key), &context).await.unwrap();
+        assert_eq!(replayed.burn_id, result.burn_id);
+        assert_eq!(projection.ledger().len(), 2);
+
+        let too_much = BurnCommand::new(wallet.user_id().into(), amount(40), "Expired".to_string());
+        assert!(matches!(
+            handler.execute(too_much, None, &context).await,
+            Err(AppError::InsufficientBalance)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_hold_capture_with_in_memory_services() {
+        let store = InMemoryEventStore::new();
+        let projection = InMemoryProjection::new();
+        let payer = open_account(&store, &projection, "100").await;
+        let payee = open_account(&store, &projection, "5").await;
+
+        let handler = HoldHandler::with_services(store.clone(), projection.clone(), unreachable_pool());
+        let context = OperationContext::new().with_request_user(payer.user_id());
+        let command = HoldCommand::new(payer.user_id().into(), payee.user_id().into(), amount(30));
+
+        let held = handler.hold(command, None, &context).await.unwrap();
+        assert_eq!(held.status, "held");
+        assert_eq!(projection.get_hold(held.hold_id).await.unwrap().unwrap().status, "held");
+
+        // Only the payee settles the hold
+        assert!(matches!(
+            handler.capture(held.hold_id, None, &context).await,
+            Err(AppError::Forbidden(_))
+        ));
+        let payee_context = OperationContext::new().with_request_user(payee.user_id());
+        let key = Uuid::new_v4();
+        let captured = handler.capture(held.hold_id, Some(key), &payee_context).await.unwrap();
+        assert_eq!(captured.status, "captured");
+        assert_eq!(projection.get_hold(held.hold_id).await.unwrap().unwrap().status, "captured");
+        assert_eq!(projection.get_balance(payer.id()).await.unwrap(), Decimal::from(70));
+        assert_eq!(projection.get_balance(payee.id()).await.unwrap(), Decimal::from(35));
+        let ledger = projection.ledger();
+        assert_eq!(ledger.len(), 2);
+        assert!(ledger.iter().all(|entry| Some(entry.journal_id) == captured.transfer_id));
+
+        // The retry returns the capture's result; a release is refused
+        let replayed = handler.capture(held.hold_id, Some(key), &payee_context).await.unwrap();
+        assert_eq!(replayed.transfer_id, captured.transfer_id);
+        assert!(handler.release(held.hold_id, None, &payee_context).await.is_err());
+        assert_eq!(projection.ledger().len(), 2);
+    }
 }
@@ -0,0 +1,530 @@
+//! Transfer Approval Handler
+//!
+//! Second step of the maker-checker workflow: a transfer held for approval
+//! by TransferHandler is approved (and executed) or rejected by a different
+//! API key than the one that initiated it. Transfers left undecided past
+//! their expiry are failed as expired by the pending transfer expiry job.
+
+use chrono::Utc;
+use serde_json::json;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate, Transfer};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountEvent, Amount, OperationContext, TransferFailureReason, TransferId};
+use crate::error::AppError;
+use crate::event_store::{AggregateOperation, EventStore, PendingAppend};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService};
+use crate::restrictions::RestrictionService;
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
+
+use super::TransferResult;
+
+/// Loaded aggregates and events of one approval attempt
+struct PreparedApproval {
+    transfer: Transfer,
+    amount: Amount,
+    from_account: Account,
+    to_account: Account,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
+}
+
+/// Handler for approving and rejecting pending transfers
+pub struct TransferApprovalHandler {
+    event_store: EventStore,
+    projection: ProjectionService,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    restrictions: RestrictionService,
+}
+
+impl TransferApprovalHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            projection: ProjectionService::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            restrictions: RestrictionService::new(pool),
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            restrictions: state.restrictions.clone(),
+        }
+    }
+
+    /// Approve a pending transfer and execute it. If the transfer can no
+    /// longer be executed (e.g. the sender's balance dropped meanwhile) it is
+    /// recorded as approved and failed.
+    pub async fn approve(
+        &self,
+        transfer_id: TransferId,
+        context: &OperationContext,
+    ) -> Result<TransferResult, AppError> {
+        let approver = Self::approver(context)?;
+
+        // Reload the transfer and both accounts on each attempt; a concurrent
+        // approve/reject conflicts on the transfer version and is then
+        // rejected as no longer pending. Balances, ledger and the
+        // pending_transfers row commit with the events.
+        let result = self
+            .event_store
+            .append_with_retry_in_tx(
+                None,
+                context,
+                || self.prepare_approval(transfer_id, approver),
+                |mut pending: PendingAppend<PreparedApproval>| async move {
+                    let prepared = &pending.value;
+                    self.projection
+                        .apply_transfer_in_tx(
+                            &mut pending.tx,
+                            transfer_id.into(),
+                            pending.event_ids[0],
+                            prepared.from_account.id(),
+                            prepared.to_account.id(),
+                            &prepared.amount,
+                            LedgerDescriptions::from_events(&prepared.debit_event, &prepared.credit_event)
+                                .journal(
+                                    JournalType::Transfer,
+                                    prepared.transfer.memo(),
+                                    Some(prepared.transfer.initiated_by()),
+                                ),
+                            LegVersions::after(&prepared.from_account, &prepared.to_account),
+                        )
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    record_decision(&mut pending.tx, transfer_id, "approved", Some(approver), None).await?;
+                    Ok(pending)
+                },
+            )
+            .await;
+
+        let (event_ids, prepared) = match result {
+            Ok(appended) => appended,
+            Err(
+                e @ (AppError::InsufficientBalance
+                | AppError::AccountFrozen
+                | AppError::AccountClosed(_)
+                | AppError::AccountNotFound(_)
+                | AppError::AmountTooSmall(_)
+                | AppError::AmountTooLarge(_)
+                | AppError::TransferBlocked(_)),
+            ) => return Err(self.record_failure(transfer_id, approver, e, context).await),
+            Err(e) => return Err(e),
+        };
+        let PreparedApproval {
+            transfer,
+            amount,
+            from_account,
+            to_account,
+            debit_event,
+            credit_event,
+        } = prepared;
+
+        let from_balance_before = from_account.balance().value();
+        let to_balance_before = to_account.balance().value();
+
+        // Apply events to get updated accounts
+        let from_account = from_account.apply(debit_event);
+        let to_account = to_account.apply(credit_event);
+
+        // Save snapshots if needed
+        self.event_store
+            .save_snapshot_if_needed(&from_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        self.event_store
+            .save_snapshot_if_needed(&to_account)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::TransferApproved)
+            .resource_type("Transfer")
+            .resource_id(transfer_id)
+            .before_state(&json!({
+                "status": "pending_approval",
+                "from_balance": from_balance_before,
+                "to_balance": to_balance_before,
+            }))
+            .after_state(&json!({
+                "status": "completed",
+                "requested_by_api_key_id": transfer.approval_requested_by(),
+                "approved_by_api_key_id": approver,
+                "amount": amount.value(),
+                "from_balance": from_account.balance().value(),
+                "to_balance": to_account.balance().value(),
+            }))
+            .changed_fields(vec![
+                "status".to_string(),
+                "from_balance".to_string(),
+                "to_balance".to_string(),
+            ]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::TransferExecuted,
+                json!({
+                    "transfer_id": transfer_id,
+                    "from_user_id": transfer.from_user_id(),
+                    "to_user_id": transfer.to_user_id(),
+                    "amount": amount.value(),
+                }),
+            )
+            .await;
+
+        Ok(TransferResult {
+            transfer_id: transfer_id.into(),
+            from_user_id: transfer.from_user_id(),
+            to_user_id: transfer.to_user_id(),
+            amount: amount.value(),
+            status: "completed".to_string(),
+            from_account_version: Some(from_account.version()),
+            to_account_version: Some(to_account.version()),
+            event_ids,
+        })
+    }
+
+    /// Reject a pending transfer; no money moves
+    pub async fn reject(
+        &self,
+        transfer_id: TransferId,
+        reason: Option<String>,
+        context: &OperationContext,
+    ) -> Result<TransferResult, AppError> {
+        let rejecter = Self::approver(context)?;
+        let transfer = self.load_transfer(transfer_id).await?;
+
+        let rejected_event = transfer.reject(rejecter, reason.clone())?;
+        let operation = AggregateOperation::new(
+            "Transfer",
+            transfer_id.into(),
+            transfer.version(),
+            rejected_event.event_type(),
+            &rejected_event,
+        )?;
+
+        // The pending_transfers row commits with the event
+        let tx = self.event_store.begin().await?;
+        let (mut tx, event_ids) = self
+            .event_store
+            .append_atomic_in_tx(tx, &[operation], None, context)
+            .await?;
+        record_decision(&mut tx, transfer_id, "rejected", Some(rejecter), reason.as_deref()).await?;
+        tx.commit().await?;
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::TransferRejected)
+            .resource_type("Transfer")
+            .resource_id(transfer_id)
+            .before_state(&json!({ "status": "pending_approval" }))
+            .after_state(&json!({
+                "status": "rejected",
+                "requested_by_api_key_id": transfer.approval_requested_by(),
+                "rejected_by_api_key_id": rejecter,
+                "reason": reason,
+            }))
+            .changed_fields(vec!["status".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::TransferRejected,
+                json!({
+                    "transfer_id": transfer_id,
+                    "from_user_id": transfer.from_user_id(),
+                    "to_user_id": transfer.to_user_id(),
+                    "amount": transfer.amount(),
+                    "reason": reason,
+                }),
+            )
+            .await;
+
+        Ok(TransferResult {
+            transfer_id: transfer_id.into(),
+            from_user_id: transfer.from_user_id(),
+            to_user_id: transfer.to_user_id(),
+            amount: transfer.amount(),
+            status: "rejected".to_string(),
+            from_account_version: None,
+            to_account_version: None,
+            event_ids,
+        })
+    }
+
+    /// Fail a transfer whose approval expired (TransferFailed, reason
+    /// expired); no money moves
+    pub async fn expire(
+        &self,
+        transfer_id: TransferId,
+        context: &OperationContext,
+    ) -> Result<TransferResult, AppError> {
+        let transfer = self.load_transfer(transfer_id).await?;
+
+        let failed_event = transfer.expire(Utc::now())?;
+        let operation = AggregateOperation::new(
+            "Transfer",
+            transfer_id.into(),
+            transfer.version(),
+            failed_event.event_type(),
+            &failed_event,
+        )?;
+
+        let tx = self.event_store.begin().await?;
+        let (mut tx, event_ids) = self
+            .event_store
+            .append_atomic_in_tx(tx, &[operation], None, context)
+            .await?;
+        let failed = transfer.clone().apply(failed_event);
+        self.projection
+            .record_failed_transfer_in_tx(&mut tx, &failed)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+        let reason = TransferFailureReason::Expired;
+        record_decision(&mut tx, transfer_id, "expired", None, Some(reason.as_str())).await?;
+        tx.commit().await?;
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::TransferExpired)
+            .resource_type("Transfer")
+            .resource_id(transfer_id)
+            .before_state(&json!({ "status": "pending_approval" }))
+            .after_state(&json!({
+                "status": "failed",
+                "reason": reason.as_str(),
+                "requested_by_api_key_id": transfer.approval_requested_by(),
+                "expires_at": transfer.approval_expires_at(),
+            }))
+            .changed_fields(vec!["status".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::TransferExpired,
+                json!({
+                    "transfer_id": transfer_id,
+                    "from_user_id": transfer.from_user_id(),
+                    "to_user_id": transfer.to_user_id(),
+                    "amount": transfer.amount(),
+                    "expires_at": transfer.approval_expires_at(),
+                }),
+            )
+            .await;
+
+        Ok(TransferResult {
+            transfer_id: transfer_id.into(),
+            from_user_id: transfer.from_user_id(),
+            to_user_id: transfer.to_user_id(),
+            amount: transfer.amount(),
+            status: "failed".to_string(),
+            from_account_version: None,
+            to_account_version: None,
+            event_ids,
+        })
+    }
+
+    /// Approvals are tied to an API key so the four-eyes rule can be checked
+    fn approver(context: &OperationContext) -> Result<Uuid, AppError> {
+        context.api_key_id.ok_or_else(|| {
+            AppError::Forbidden("Transfer approval requires an API key".to_string())
+        })
+    }
+
+    /// Load the transfer and both accounts and build TransferApproved, the
+    /// debit/credit and TransferCompleted
+    async fn prepare_approval(
+        &self,
+        transfer_id: TransferId,
+        approver: Uuid,
+    ) -> Result<(Vec<AggregateOperation>, PreparedApproval), AppError> {
+        let transfer = self.load_transfer(transfer_id).await?;
+
+        let approved_event = transfer.approve(approver)?;
+        let approved = transfer.clone().apply(approved_event.clone());
+        let completed_event = approved.complete()?;
+
+        let amount = Amount::new(transfer.amount())
+            .map_err(|e| AppError::Internal(format!("Invalid transfer amount: {}", e)))?;
+        // Restrictions may have changed since the transfer was requested
+        self.restrictions
+            .check_transfer(transfer.from_account_id(), transfer.to_account_id())
+            .await?;
+
+        let from_account = self.load_account(transfer.from_account_id()).await?;
+        let to_account = self.load_account(transfer.to_account_id()).await?;
+
+        let description = transfer.memo().unwrap_or("Transfer").to_string();
+        let category = transfer.category().map(str::to_string);
+        let debit_event = from_account
+            .debit(&amount, transfer_id.into(), description.clone())?
+            .with_category(category.clone());
+        let credit_event = to_account
+            .credit(&amount, transfer_id.into(), description)?
+            .with_category(category);
+
+        // Account events come first so event_ids[0] stays the debit event
+        let operations = vec![
+            AggregateOperation::new(
+                "Account",
+                from_account.id(),
+                from_account.version(),
+                debit_event.event_type(),
+                &debit_event,
+            )?,
+            AggregateOperation::new(
+                "Account",
+                to_account.id(),
+                to_account.version(),
+                credit_event.event_type(),
+                &credit_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                transfer_id.into(),
+                transfer.version(),
+                approved_event.event_type(),
+                &approved_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                transfer_id.into(),
+                approved.version(),
+                completed_event.event_type(),
+                &completed_event,
+            )?,
+        ];
+
+        Ok((
+            operations,
+            PreparedApproval {
+                transfer,
+                amount,
+                from_account,
+                to_account,
+                debit_event,
+                credit_event,
+            },
+        ))
+    }
+
+    /// Persist TransferApproved + TransferFailed for an approved transfer that
+    /// could not be executed and add it to failed_transfers. Returns the
+    /// original error.
+    async fn record_failure(
+        &self,
+        transfer_id: TransferId,
+        approver: Uuid,
+        error: AppError,
+        context: &OperationContext,
+    ) -> AppError {
+        let reason = match &error {
+            AppError::InsufficientBalance => TransferFailureReason::InsufficientBalance,
+            AppError::AccountFrozen => TransferFailureReason::AccountFrozen,
+            AppError::AccountClosed(_) => TransferFailureReason::AccountClosed,
+            AppError::AmountTooSmall(_) => TransferFailureReason::AmountTooSmall,
+            AppError::AmountTooLarge(_) => TransferFailureReason::AmountTooLarge,
+            AppError::AccountNotFound(_) => TransferFailureReason::AccountNotFound,
+            AppError::TransferBlocked(_) => TransferFailureReason::TransferBlocked,
+            _ => TransferFailureReason::InternalError,
+        };
+
+        let result: Result<(), AppError> = async {
+            let transfer = self.load_transfer(transfer_id).await?;
+            let approved_event = transfer.approve(approver)?;
+            let approved = transfer.clone().apply(approved_event.clone());
+            let failed_event = approved.fail(reason.clone())?;
+
+            let operations = vec![
+                AggregateOperation::new(
+                    "Transfer",
+                    transfer_id.into(),
+                    transfer.version(),
+                    approved_event.event_type(),
+                    &approved_event,
+                )?,
+                AggregateOperation::new(
+                    "Transfer",
+                    transfer_id.into(),
+                    approved.version(),
+                    failed_event.event_type(),
+                    &failed_event,
+                )?,
+            ];
+
+            let tx = self.event_store.begin().await?;
+            let (mut tx, _) = self
+                .event_store
+                .append_atomic_in_tx(tx, &operations, None, context)
+                .await?;
+            let failed = approved.apply(failed_event);
+            self.projection
+                .record_failed_transfer_in_tx(&mut tx, &failed)
+                .await
+                .map_err(|e| AppError::Internal(e.to_string()))?;
+            record_decision(&mut tx, transfer_id, "failed", Some(approver), Some(&reason.to_string())).await?;
+            tx.commit().await?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!(
+                transfer_id = %transfer_id,
+                "Failed to record failed approval ({}): {}",
+                reason,
+                e
+            );
+        }
+
+        error
+    }
+
+    async fn load_transfer(&self, transfer_id: TransferId) -> Result<Transfer, AppError> {
+        self.event_store
+            .load_aggregate(transfer_id.into())
+            .await?
+            .ok_or_else(|| AppError::InvalidRequest(format!("Transfer {} not found", transfer_id)))
+    }
+
+    async fn load_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        self.event_store
+            .load_aggregate(account_id)
+            .await?
+            .ok_or_else(|| AppError::AccountNotFound(account_id.to_string()))
+    }
+}
+
+/// Sync the pending_transfers projection, in the transaction that appends
+/// the decision's events
+async fn record_decision(
+    tx: &mut Transaction<'_, Postgres>,
+    transfer_id: TransferId,
+    status: &str,
+    decided_by: Option<Uuid>,
+    reason: Option<&str>,
+) -> Result<(), AppError> {
+    sqlx::query(
+        r#"
+        UPDATE pending_transfers
+        SET status = $2, decided_by_api_key_id = $3, decision_reason = $4, decided_at = NOW()
+        WHERE transfer_id = $1
+        "#,
+    )
+    .bind(transfer_id)
+    .bind(status)
+    .bind(decided_by)
+    .bind(reason)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
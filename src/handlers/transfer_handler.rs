@@ -2,41 +2,133 @@
 //!
 //! Handles ATP transfers between users with full validation.
 
+use std::time::Duration;
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::aggregate::{Account, Aggregate};
-use crate::domain::{Amount, OperationContext};
+use crate::aggregate::{Account, Aggregate, Transfer};
+use crate::alerts::{AlertRules, AlertService};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{
+    AccountEvent, AccountId, Amount, OperationContext, TransferCategories, TransferEvent, TransferFailureReason,
+    UserId,
+};
 use crate::error::AppError;
-use crate::event_store::{AggregateOperation, EventStore};
+use crate::event_store::{AggregateOperation, EventStore, EventStoreTrait, IdempotencyRequest, PendingAppend};
 use crate::idempotency::IdempotencyRepository;
-use crate::projection::ProjectionService;
+use crate::limits::{LimitOperation, LimitService, LimitTrait};
+use crate::projection::{JournalType, LedgerDescriptions, LegVersions, ProjectionService, ProjectionTrait};
+use crate::restrictions::{RestrictionService, RestrictionTrait};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
 
-use super::{TransferCommand, TransferResult};
+use super::replay::replayed_result;
+use super::{TransferCommand, TransferQuote, TransferResult};
 
 // =========================================================================
 // M102: TransferHandler
 // =========================================================================
 
+/// Accounts and events of one append attempt
+struct PreparedTransfer {
+    from_account: Account,
+    to_account: Account,
+    debit_event: AccountEvent,
+    credit_event: AccountEvent,
+}
+
+/// How long a transfer waits for approval unless PENDING_TRANSFER_TTL_HOURS is set
+const DEFAULT_APPROVAL_TTL: Duration = Duration::from_secs(72 * 3600);
+
 /// Handler for ATP transfers
-pub struct TransferHandler {
-    event_store: EventStore,
-    projection: ProjectionService,
+pub struct TransferHandler<
+    E = EventStore,
+    P = ProjectionService,
+    L = LimitService,
+    R = RestrictionService,
+> {
+    event_store: E,
+    projection: P,
     #[allow(dead_code)]
     idempotency: IdempotencyRepository,
-    pool: PgPool,
+    audit: AuditLogService,
+    webhooks: WebhookService,
+    alerts: AlertService,
+    limits: L,
+    restrictions: R,
+    /// Transfers above this amount wait for approval (maker-checker)
+    approval_threshold: Option<Decimal>,
+    /// How long a transfer waits for approval before it expires
+    approval_ttl: Duration,
+    /// Accepted transfer categories
+    categories: TransferCategories,
 }
 
 impl TransferHandler {
     pub fn new(pool: PgPool) -> Self {
+        Self::with_services(
+            EventStore::new(pool.clone()),
+            ProjectionService::new(pool.clone()),
+            LimitService::new(pool.clone()),
+            RestrictionService::new(pool.clone()),
+            pool,
+        )
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            idempotency: state.idempotency.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            alerts: state.alerts.clone(),
+            limits: state.limits.clone(),
+            restrictions: state.restrictions.clone(),
+            approval_threshold: state.config.transfer_approval_threshold,
+            approval_ttl: state.config.pending_transfer_ttl,
+            categories: state.config.transfer_categories.clone(),
+        }
+    }
+}
+
+impl<E, P, L, R> TransferHandler<E, P, L, R>
+where
+    E: EventStoreTrait,
+    P: ProjectionTrait<Tx = E::Tx>,
+    L: LimitTrait,
+    R: RestrictionTrait,
+{
+    /// Use the given event store, projections, limits and restrictions (e.g.
+    /// in-memory ones in tests); the audit log, webhooks and alerts still use
+    /// `pool`
+    pub fn with_services(event_store: E, projection: P, limits: L, restrictions: R, pool: PgPool) -> Self {
         Self {
-            event_store: EventStore::new(pool.clone()),
-            projection: ProjectionService::new(pool.clone()),
+            event_store,
+            projection,
             idempotency: IdempotencyRepository::new(pool.clone()),
-            pool,
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            alerts: AlertService::new(pool, AlertRules::default()),
+            limits,
+            restrictions,
+            approval_threshold: None,
+            approval_ttl: DEFAULT_APPROVAL_TTL,
+            categories: TransferCategories::default(),
         }
     }
 
+    /// Hold transfers above `threshold` for approval
+    pub fn with_approval_threshold(mut self, threshold: Option<Decimal>) -> Self {
+        self.approval_threshold = threshold;
+        self
+    }
+
     /// Execute the transfer command
     pub async fn execute(
         &self,
@@ -44,112 +136,180 @@ impl TransferHandler {
         idempotency_key: Option<Uuid>,
         context: &OperationContext,
     ) -> Result<TransferResult, AppError> {
-        // M103: Authorization check
-        if let Some(request_user_id) = context.request_user_id {
-            if request_user_id != command.from_user_id {
-                return Err(AppError::UnauthorizedTransfer);
-            }
-        } else {
-            return Err(AppError::MissingHeader("X-Request-User-Id".to_string()));
-        }
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyRequest::for_command(key, &command, context))
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        // Validate same account transfer
-        if command.from_user_id == command.to_user_id {
-            return Err(AppError::InvalidRequest(
-                "Cannot transfer to the same account".to_string(),
-            ));
+        // The caller must be the sender even when replaying
+        validate_command(&command, context)?;
+
+        // A completed key returns the original result, before the balance,
+        // limit and restriction checks that may no longer pass
+        if let Some(idempotency) = &idempotency {
+            if let Some(result) = self.event_store.stored_result(idempotency).await? {
+                return Ok(result);
+            }
         }
 
-        // Parse and validate amount
-        let amount: Amount = command
-            .amount
-            .parse()
-            .map_err(|e| AppError::InvalidRequest(format!("Invalid amount: {}", e)))?;
+        let amount = command.amount.clone();
+        let category = self.resolve_category(&command)?;
 
         // M104: Resolve user_id to account_id
-        let from_account_id = self.get_wallet_account_id(command.from_user_id).await?;
-        let to_account_id = self.get_wallet_account_id(command.to_user_id).await?;
+        let (from_account_id, to_account_id) = self.resolve_accounts(&command).await?;
 
-        // Load sender's account
-        let from_account: Account = self
-            .event_store
-            .load_aggregate(from_account_id)
+        // Load sender's and recipient's accounts
+        let from_account = self.load_account(from_account_id).await?;
+        let to_account = self.load_account(to_account_id).await?;
+
+        // Generate transfer ID and initiate the transfer saga
+        let transfer_id = Uuid::new_v4();
+        let initiated_by = context.request_user_id.unwrap_or(command.from_user_id.into());
+        let (transfer, initiated_event) = Transfer::initiate(
+            transfer_id,
+            &from_account,
+            &to_account,
+            &amount,
+            command.memo.clone(),
+            category.clone(),
+            initiated_by,
+        );
+
+        // Enforce configured transfer limits; rejections are recorded as failed transfers
+        if let Err(e) = self
+            .limits
+            .check(LimitOperation::Transfer, command.from_user_id.into(), amount.value())
             .await
-            .map_err(|e| AppError::Internal(e.to_string()))?
-            .ok_or_else(|| AppError::AccountNotFound(from_account_id.to_string()))?;
+        {
+            return Err(self.record_failure(&transfer, &initiated_event, e.into(), context).await);
+        }
 
-        // Load recipient's account
-        let to_account: Account = self
-            .event_store
-            .load_aggregate(to_account_id)
+        // Enforce receive-only / send-only / allowlist restrictions of both accounts
+        if let Err(e) = self
+            .restrictions
+            .check_transfer(from_account_id, to_account_id)
             .await
-            .map_err(|e| AppError::Internal(e.to_string()))?
-            .ok_or_else(|| AppError::AccountNotFound(to_account_id.to_string()))?;
+        {
+            return Err(self.record_failure(&transfer, &initiated_event, e.into(), context).await);
+        }
 
-        // Generate transfer ID
-        let transfer_id = Uuid::new_v4();
+        let description = command.memo.clone().unwrap_or_else(|| "Transfer".to_string());
 
-        // Generate debit event (from sender)
-        let debit_event = from_account.debit(
-            &amount,
-            transfer_id,
-            command.memo.clone().unwrap_or_else(|| "Transfer".to_string()),
-        )?;
+        // Transfers above the approval threshold are only recorded here and
+        // executed once a different API key approves them
+        if let Some(threshold) = self.approval_threshold.filter(|t| amount.value() > *t) {
+            // Fail early on what would also fail at approval time
+            let check = from_account
+                .debit(&amount, transfer_id, description.clone())
+                .and_then(|_| to_account.credit(&amount, transfer_id, description.clone()));
+            if let Err(e) = check {
+                return Err(self.record_failure(&transfer, &initiated_event, e, context).await);
+            }
 
-        // Generate credit event (to recipient)
-        let credit_event = to_account.credit(
-            &amount,
+            return self
+                .request_approval(&command, &transfer, &initiated_event, threshold, idempotency.as_ref(), context)
+                .await;
+        }
+
+        let completed_event = transfer.complete()?;
+        let transfer_result = |event_ids: &[Uuid], prepared: &PreparedTransfer| TransferResult {
             transfer_id,
-            command.memo.unwrap_or_else(|| "Transfer".to_string()),
-        )?;
+            from_user_id: command.from_user_id.into(),
+            to_user_id: command.to_user_id.into(),
+            amount: amount.value(),
+            status: "completed".to_string(),
+            from_account_version: Some(prepared.from_account.version() + 1),
+            to_account_version: Some(prepared.to_account.version() + 1),
+            event_ids: event_ids.to_vec(),
+        };
 
-        // Prepare atomic operations
-        let operations = vec![
-            AggregateOperation::new(
-                "Account",
-                from_account_id,
-                from_account.version(),
-                debit_event.event_type(),
-                &debit_event,
-            )
-            .map_err(|e| AppError::Internal(e.to_string()))?,
-            AggregateOperation::new(
-                "Account",
-                to_account_id,
-                to_account.version(),
-                credit_event.event_type(),
-                &credit_event,
+        // Persist events, update projections and store the result for replays
+        // of the idempotency key in one transaction. The first attempt uses
+        // the accounts loaded above; after a concurrency conflict they are
+        // reloaded and the debit/credit events rebuilt against the fresh
+        // balances.
+        let mut preloaded = Some((from_account, to_account));
+        let (amount_ref, memo_ref) = (&amount, command.memo.as_deref());
+        let idempotency_request = idempotency.as_ref();
+        let appended = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency_request,
+                context,
+                || {
+                    self.prepare_operations(
+                        preloaded.take(),
+                        &transfer,
+                        &initiated_event,
+                        &completed_event,
+                        amount_ref,
+                        &description,
+                    )
+                },
+                |mut pending: PendingAppend<PreparedTransfer, E::Tx>| async move {
+                    let prepared = &pending.value;
+                    self.projection
+                        .apply_transfer_in_tx(
+                            &mut pending.tx,
+                            transfer_id,
+                            pending.event_ids[0],
+                            from_account_id,
+                            to_account_id,
+                            amount_ref,
+                            LedgerDescriptions::from_events(&prepared.debit_event, &prepared.credit_event).journal(
+                                JournalType::Transfer,
+                                memo_ref,
+                                Some(initiated_by),
+                            ),
+                            LegVersions::after(&prepared.from_account, &prepared.to_account),
+                        )
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    if let Some(idempotency) = idempotency_request {
+                        let result = transfer_result(&pending.event_ids, &pending.value);
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, &result)
+                            .await?;
+                    }
+                    Ok(pending)
+                },
             )
-            .map_err(|e| AppError::Internal(e.to_string()))?,
-        ];
+            .await;
 
-        // Persist events atomically
-        let event_ids = self
-            .event_store
-            .append_atomic(operations, idempotency_key, context)
-            .await
-            .map_err(|e| match e {
-                crate::event_store::EventStoreError::ConcurrencyConflict { .. } => {
-                    AppError::VersionConflict
-                }
-                crate::event_store::EventStoreError::IdempotencyKeyExists(_) => {
-                    AppError::IdempotencyConflict
-                }
-                _ => AppError::Internal(e.to_string()),
-            })?;
-
-        // Update projections
-        self.projection
-            .apply_transfer(
+        let (event_ids, prepared) = match appended {
+            Ok(appended) => appended,
+            Err(e @ (AppError::IdempotencyConflict | AppError::Internal(_) | AppError::Database(_))) => {
+                return Err(e);
+            }
+            Err(e) => return Err(self.record_failure(&transfer, &initiated_event, e, context).await),
+        };
+
+        // Idempotent replay of a call that completed concurrently: nothing
+        // was appended or projected, return that call's result
+        if let Some(idempotency) = idempotency.as_ref().filter(|_| event_ids.len() == 1) {
+            return replayed_result(&self.event_store, idempotency, event_ids[0], |transfer_id| TransferResult {
                 transfer_id,
-                event_ids[0],
-                from_account_id,
-                to_account_id,
-                &amount,
-                from_account.version() + 1,
-            )
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+                from_user_id: command.from_user_id.into(),
+                to_user_id: command.to_user_id.into(),
+                amount: amount.value(),
+                status: "completed".to_string(),
+                from_account_version: None,
+                to_account_version: None,
+                event_ids: Vec::new(),
+            })
+            .await;
+        }
+
+        let result = transfer_result(&event_ids, &prepared);
+        let PreparedTransfer {
+            from_account,
+            to_account,
+            debit_event,
+            credit_event,
+        } = prepared;
+
+        let from_balance_before = from_account.balance().value();
+        let to_balance_before = to_account.balance().value();
 
         // Apply events to get updated accounts
         let from_account = from_account.apply(debit_event);
@@ -165,29 +325,428 @@ impl TransferHandler {
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        Ok(TransferResult {
-            transfer_id,
-            from_user_id: command.from_user_id,
-            to_user_id: command.to_user_id,
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::TransferExecuted)
+            .resource_type("Transfer")
+            .resource_id(transfer_id)
+            .before_state(&json!({
+                "from_balance": from_balance_before,
+                "to_balance": to_balance_before,
+            }))
+            .after_state(&json!({
+                "from_user_id": command.from_user_id,
+                "to_user_id": command.to_user_id,
+                "amount": amount.value(),
+                "category": category,
+                "from_balance": from_account.balance().value(),
+                "to_balance": to_account.balance().value(),
+            }))
+            .changed_fields(vec!["from_balance".to_string(), "to_balance".to_string()]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::TransferExecuted,
+                json!({
+                    "transfer_id": transfer_id,
+                    "from_user_id": command.from_user_id,
+                    "to_user_id": command.to_user_id,
+                    "amount": amount.value(),
+                    "category": category,
+                }),
+            )
+            .await;
+
+        self.alerts
+            .check_transfer(command.from_user_id.into(), transfer_id, amount.value())
+            .await;
+
+        Ok(result)
+    }
+
+    /// Run every check of `execute` (authorization, limits, restrictions,
+    /// frozen accounts, balance) without persisting anything. Fails with the
+    /// error the transfer itself would fail with.
+    pub async fn quote(
+        &self,
+        command: &TransferCommand,
+        context: &OperationContext,
+    ) -> Result<TransferQuote, AppError> {
+        validate_command(command, context)?;
+        let amount = command.amount.clone();
+        self.resolve_category(command)?;
+
+        let (from_account_id, to_account_id) = self.resolve_accounts(command).await?;
+        let from_account = self.load_account(from_account_id).await?;
+        let to_account = self.load_account(to_account_id).await?;
+
+        self.limits
+            .check(LimitOperation::Transfer, command.from_user_id.into(), amount.value())
+            .await?;
+        self.restrictions
+            .check_transfer(from_account_id, to_account_id)
+            .await?;
+
+        let description = command.memo.clone().unwrap_or_else(|| "Transfer".to_string());
+        let debit_event = from_account.debit(&amount, Uuid::nil(), description.clone())?;
+        let credit_event = to_account.credit(&amount, Uuid::nil(), description)?;
+
+        let status = if self.approval_threshold.is_some_and(|t| amount.value() > t) {
+            "pending_approval"
+        } else {
+            "completed"
+        };
+
+        Ok(TransferQuote {
+            from_user_id: command.from_user_id.into(),
+            to_user_id: command.to_user_id.into(),
             amount: amount.value(),
-            status: "completed".to_string(),
+            fee: Decimal::ZERO,
+            from_balance_after: from_account.apply(debit_event).balance().value(),
+            to_balance_after: to_account.apply(credit_event).balance().value(),
+            status: status.to_string(),
         })
     }
 
+    /// Persist TransferInitiated + TransferApprovalRequested and add the
+    /// transfer to pending_transfers; no money moves until it is approved
+    async fn request_approval(
+        &self,
+        command: &TransferCommand,
+        transfer: &Transfer,
+        initiated_event: &TransferEvent,
+        threshold: Decimal,
+        idempotency: Option<&IdempotencyRequest>,
+        context: &OperationContext,
+    ) -> Result<TransferResult, AppError> {
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(self.approval_ttl)
+                .map_err(|e| AppError::Internal(format!("Invalid approval TTL: {}", e)))?;
+        let requested_event = transfer.request_approval(threshold, context.api_key_id, expires_at)?;
+
+        let pending_result = |event_ids: Vec<Uuid>| TransferResult {
+            transfer_id: transfer.id(),
+            from_user_id: command.from_user_id.into(),
+            to_user_id: command.to_user_id.into(),
+            amount: transfer.amount(),
+            status: "pending_approval".to_string(),
+            from_account_version: None,
+            to_account_version: None,
+            event_ids,
+        };
+
+        // Events, the pending_transfers row and the result for replays of the
+        // idempotency key commit together. The value is set once projected and
+        // stays false when the append replayed an earlier call with the key.
+        let requested_event = &requested_event;
+        let (event_ids, projected) = self
+            .event_store
+            .append_with_retry_in_tx(
+                idempotency,
+                context,
+                || async move {
+                    let operations = vec![
+                        AggregateOperation::new(
+                            "Transfer",
+                            transfer.id(),
+                            0,
+                            initiated_event.event_type(),
+                            initiated_event,
+                        )?,
+                        AggregateOperation::new(
+                            "Transfer",
+                            transfer.id(),
+                            transfer.version(),
+                            requested_event.event_type(),
+                            requested_event,
+                        )?,
+                    ];
+                    Ok::<_, AppError>((operations, false))
+                },
+                |mut pending: PendingAppend<bool, E::Tx>| async move {
+                    self.projection
+                        .record_pending_transfer_in_tx(&mut pending.tx, transfer, context.api_key_id, expires_at)
+                        .await
+                        .map_err(|e| AppError::Internal(e.to_string()))?;
+                    if let Some(idempotency) = idempotency {
+                        self.event_store
+                            .store_result_in_tx(&mut pending.tx, idempotency, &pending_result(pending.event_ids.clone()))
+                            .await?;
+                    }
+                    pending.value = true;
+                    Ok(pending)
+                },
+            )
+            .await?;
+
+        // Idempotent replay: report the transfer created by the first request
+        if let Some(idempotency) = idempotency.filter(|_| !projected) {
+            return replayed_result(&self.event_store, idempotency, event_ids[0], |transfer_id| TransferResult {
+                transfer_id,
+                ..pending_result(Vec::new())
+            })
+            .await;
+        }
+
+        let audit_entry = AuditLogBuilder::new(AuditAction::TransferApprovalRequested)
+            .resource_type("Transfer")
+            .resource_id(transfer.id())
+            .after_state(&json!({
+                "from_user_id": command.from_user_id,
+                "to_user_id": command.to_user_id,
+                "amount": transfer.amount(),
+                "threshold": threshold,
+                "status": "pending_approval",
+                "expires_at": expires_at,
+            }));
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::TransferPendingApproval,
+                json!({
+                    "transfer_id": transfer.id(),
+                    "from_user_id": command.from_user_id,
+                    "to_user_id": command.to_user_id,
+                    "amount": transfer.amount(),
+                }),
+            )
+            .await;
+
+        Ok(pending_result(event_ids))
+    }
+
+    /// Build the transfer's operations from `accounts`, or from freshly loaded
+    /// accounts when retrying after a concurrency conflict
+    async fn prepare_operations(
+        &self,
+        accounts: Option<(Account, Account)>,
+        transfer: &Transfer,
+        initiated_event: &TransferEvent,
+        completed_event: &TransferEvent,
+        amount: &Amount,
+        description: &str,
+    ) -> Result<(Vec<AggregateOperation>, PreparedTransfer), AppError> {
+        let (from_account, to_account) = match accounts {
+            Some(accounts) => accounts,
+            None => (
+                self.load_account(transfer.from_account_id()).await?,
+                self.load_account(transfer.to_account_id()).await?,
+            ),
+        };
+
+        // Generate debit event (from sender) and credit event (to recipient)
+        let category = transfer.category().map(str::to_string);
+        let debit_event = from_account
+            .debit(amount, transfer.id(), description.to_string())?
+            .with_category(category.clone());
+        let credit_event = to_account
+            .credit(amount, transfer.id(), description.to_string())?
+            .with_category(category);
+
+        // Account events come first so event_ids[0] stays the debit event
+        // (used by projections and idempotency replay)
+        let operations = vec![
+            AggregateOperation::new(
+                "Account",
+                from_account.id(),
+                from_account.version(),
+                debit_event.event_type(),
+                &debit_event,
+            )?,
+            AggregateOperation::new(
+                "Account",
+                to_account.id(),
+                to_account.version(),
+                credit_event.event_type(),
+                &credit_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                transfer.id(),
+                0,
+                initiated_event.event_type(),
+                initiated_event,
+            )?,
+            AggregateOperation::new(
+                "Transfer",
+                transfer.id(),
+                transfer.version(),
+                completed_event.event_type(),
+                completed_event,
+            )?,
+        ];
+
+        Ok((
+            operations,
+            PreparedTransfer {
+                from_account,
+                to_account,
+                debit_event,
+                credit_event,
+            },
+        ))
+    }
+
+    /// Persist TransferInitiated + TransferFailed for a transfer that could not be executed
+    /// and add it to failed_transfers. Returns the original error so callers can propagate
+    /// it unchanged.
+    async fn record_failure(
+        &self,
+        transfer: &Transfer,
+        initiated_event: &TransferEvent,
+        error: AppError,
+        context: &OperationContext,
+    ) -> AppError {
+        let reason = match &error {
+            AppError::InsufficientBalance => TransferFailureReason::InsufficientBalance,
+            AppError::AccountFrozen => TransferFailureReason::AccountFrozen,
+            AppError::AccountClosed(_) => TransferFailureReason::AccountClosed,
+            AppError::AmountTooSmall(_) => TransferFailureReason::AmountTooSmall,
+            AppError::AmountTooLarge(_) => TransferFailureReason::AmountTooLarge,
+            AppError::AccountNotFound(_) => TransferFailureReason::AccountNotFound,
+            AppError::TransferBlocked(_) => TransferFailureReason::TransferBlocked,
+            AppError::VersionConflict => TransferFailureReason::ConcurrencyConflict,
+            _ => TransferFailureReason::InternalError,
+        };
+
+        let result: Result<(), AppError> = async {
+            let failed_event = transfer.fail(reason.clone())?;
+
+            let failed = transfer.clone().apply(failed_event.clone());
+            let (failed_event, failed) = (&failed_event, &failed);
+
+            // Events and the failed_transfers row commit together
+            self.event_store
+                .append_with_retry_in_tx(
+                    None,
+                    context,
+                    || async move {
+                        let operations = vec![
+                            AggregateOperation::new(
+                                "Transfer",
+                                transfer.id(),
+                                0,
+                                initiated_event.event_type(),
+                                initiated_event,
+                            )
+                            .map_err(|e| AppError::Internal(e.to_string()))?,
+                            AggregateOperation::new(
+                                "Transfer",
+                                transfer.id(),
+                                transfer.version(),
+                                failed_event.event_type(),
+                                failed_event,
+                            )
+                            .map_err(|e| AppError::Internal(e.to_string()))?,
+                        ];
+                        Ok::<_, AppError>((operations, ()))
+                    },
+                    |mut pending: PendingAppend<(), E::Tx>| async move {
+                        self.projection
+                            .record_failed_transfer_in_tx(&mut pending.tx, failed)
+                            .await
+                            .map_err(|e| AppError::Internal(e.to_string()))?;
+                        Ok(pending)
+                    },
+                )
+                .await?;
+
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            tracing::error!(
+                transfer_id = %transfer.id(),
+                "Failed to record TransferFailed event ({}): {}",
+                reason,
+                e
+            );
+        }
+
+        error
+    }
+
+    /// Canonical category of the command, checked against the taxonomy
+    fn resolve_category(&self, command: &TransferCommand) -> Result<Option<String>, AppError> {
+        command
+            .category
+            .as_deref()
+            .map(|category| self.categories.resolve(category))
+            .transpose()
+    }
+
+    async fn load_account(&self, account_id: Uuid) -> Result<Account, AppError> {
+        self.event_store
+            .load_aggregate(account_id)
+            .await?
+            .ok_or_else(|| AppError::AccountNotFound(account_id.to_string()))
+    }
+
+    /// Debited and credited accounts of the command; the same account on
+    /// both sides is rejected
+    async fn resolve_accounts(&self, command: &TransferCommand) -> Result<(Uuid, Uuid), AppError> {
+        let from_account_id = match command.from_account_id {
+            Some(account_id) => self.get_user_account_id(command.from_user_id, account_id).await?,
+            None => self.get_wallet_account_id(command.from_user_id).await?,
+        };
+        let to_account_id = match command.to_account_id {
+            Some(account_id) => self.get_user_account_id(command.to_user_id, account_id).await?,
+            None => self.get_wallet_account_id(command.to_user_id).await?,
+        };
+
+        if from_account_id == to_account_id {
+            return Err(AppError::InvalidRequest(
+                "Cannot transfer to the same account".to_string(),
+            ));
+        }
+        Ok((from_account_id, to_account_id))
+    }
+
+    /// Check that `account_id` is a wallet account of `user_id`
+    async fn get_user_account_id(&self, user_id: UserId, account_id: AccountId) -> Result<Uuid, AppError> {
+        self.projection
+            .user_wallet_account_id(user_id.into(), account_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::AccountNotFound(account_id.to_string()))
+    }
+
     // M104: user_id → account_id conversion
-    async fn get_wallet_account_id(&self, user_id: Uuid) -> Result<Uuid, AppError> {
-        let account_id: Option<Uuid> = sqlx::query_scalar(
-            r#"
-            SELECT id FROM accounts 
-            WHERE user_id = $1 AND account_type = 'user_wallet'
-            "#,
-        )
-        .bind(user_id)
-        .fetch_optional(&self.pool)
-        .await?;
+    async fn get_wallet_account_id(&self, user_id: UserId) -> Result<Uuid, AppError> {
+        self.projection
+            .wallet_account_id(user_id.into())
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?
+            .ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+    }
+}
 
-        account_id.ok_or_else(|| AppError::UserNotFound(user_id.to_string()))
+/// Checks that need no database access: the request user must be the
+/// sender and the recipient must differ
+fn validate_command(command: &TransferCommand, context: &OperationContext) -> Result<(), AppError> {
+    // M103: Authorization check
+    if let Some(request_user_id) = context.request_user_id {
+        if UserId::from(request_user_id) != command.from_user_id {
+            return Err(AppError::UnauthorizedTransfer);
+        }
+    } else {
+        return Err(AppError::MissingHeader("X-Request-User-Id".to_string()));
     }
+
+    // Validate same account transfer; a user may move funds between their
+    // own accounts when at least one of them is named explicitly
+    if command.from_user_id == command.to_user_id
+        && command.from_account_id.is_none()
+        && command.to_account_id.is_none()
+    {
+        return Err(AppError::InvalidRequest(
+            "Cannot transfer to the same account".to_string(),
+        ));
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
@@ -197,13 +756,40 @@ mod tests {
     #[test]
     fn test_transfer_command() {
         let cmd = TransferCommand::new(
-            Uuid::new_v4(),
-            Uuid::new_v4(),
-            "100.00".to_string(),
+            UserId::new_v4(),
+            UserId::new_v4(),
+            "100.00".parse().unwrap(),
         )
         .with_memo("Test payment".to_string());
 
-        assert_eq!(cmd.amount, "100.00");
+        assert_eq!(cmd.amount, "100.00".parse().unwrap());
         assert_eq!(cmd.memo, Some("Test payment".to_string()));
+        assert!(!serde_json::to_string(&cmd).unwrap().contains("category"));
+
+        let cmd = cmd.with_category("reward".to_string());
+        assert_eq!(cmd.category.as_deref(), Some("reward"));
+    }
+
+    #[test]
+    fn test_validate_command() {
+        let from = UserId::new_v4();
+        let to = UserId::new_v4();
+        let context = OperationContext::new().with_request_user(from);
+
+        let command = TransferCommand::new(from, to, "10.00".parse().unwrap());
+        assert!(validate_command(&command, &context).is_ok());
+
+        let to_self = TransferCommand::new(from, from, "10.00".parse().unwrap());
+        assert!(matches!(validate_command(&to_self, &context), Err(AppError::InvalidRequest(_))));
+
+        // Between the user's own accounts
+        let to_savings = to_self.with_accounts(None, Some(AccountId::new_v4()));
+        assert!(validate_command(&to_savings, &context).is_ok());
+
+        let other_sender = TransferCommand::new(to, from, "10.00".parse().unwrap());
+        assert!(matches!(
+            validate_command(&other_sender, &context),
+            Err(AppError::UnauthorizedTransfer)
+        ));
     }
 }
@@ -3,13 +3,17 @@
 //! Handles user profile updates with event sourcing.
 
 use chrono::{DateTime, Utc};
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::aggregate::{Aggregate, User};
-use crate::domain::{OperationContext, UserChanges};
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{OperationContext, UserChanges, UserId};
 use crate::error::AppError;
 use crate::event_store::{AggregateOperation, EventStore};
+use crate::state::AppState;
+use crate::webhooks::{WebhookEventType, WebhookService};
 
 // =========================================================================
 // UpdateUserCommand
@@ -18,12 +22,12 @@ use crate::event_store::{AggregateOperation, EventStore};
 /// Command to update a user's profile
 #[derive(Debug, Clone)]
 pub struct UpdateUserCommand {
-    pub user_id: Uuid,
+    pub user_id: UserId,
     pub changes: UserChanges,
 }
 
 impl UpdateUserCommand {
-    pub fn new(user_id: Uuid, changes: UserChanges) -> Self {
+    pub fn new(user_id: UserId, changes: UserChanges) -> Self {
         Self { user_id, changes }
     }
 }
@@ -35,6 +39,16 @@ pub struct UpdateUserResult {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Unique constraint on (users.tenant_id, users.email)
+const EMAIL_UNIQUE_CONSTRAINT: &str = "users_tenant_email_key";
+
+/// Check whether a users write failed because the email belongs to another user
+fn is_email_taken(e: &sqlx::Error) -> bool {
+    e.as_database_error()
+        .and_then(|db| db.constraint())
+        .is_some_and(|constraint| constraint == EMAIL_UNIQUE_CONSTRAINT)
+}
+
 // =========================================================================
 // UpdateUserHandler
 // =========================================================================
@@ -42,6 +56,8 @@ pub struct UpdateUserResult {
 /// Handler for user updates
 pub struct UpdateUserHandler {
     event_store: EventStore,
+    audit: AuditLogService,
+    webhooks: WebhookService,
     pool: PgPool,
 }
 
@@ -49,10 +65,22 @@ impl UpdateUserHandler {
     pub fn new(pool: PgPool) -> Self {
         Self {
             event_store: EventStore::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
             pool,
         }
     }
 
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            pool: state.pool.clone(),
+        }
+    }
+
     /// Execute the update user command
     pub async fn execute(
         &self,
@@ -62,11 +90,36 @@ impl UpdateUserHandler {
         // Load user aggregate from event store
         let user: User = self
             .event_store
-            .load_aggregate(command.user_id)
+            .load_aggregate(command.user_id.into())
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?
             .ok_or_else(|| AppError::UserNotFound(command.user_id.to_string()))?;
 
+        let mut changed_fields = Vec::new();
+        if command.changes.display_name.is_some() {
+            changed_fields.push("display_name".to_string());
+        }
+        if let Some(email) = &command.changes.email {
+            changed_fields.push("email".to_string());
+
+            let taken: bool = sqlx::query_scalar(
+                r#"
+                SELECT EXISTS(
+                    SELECT 1 FROM users u
+                    JOIN users updated ON updated.id = $2 AND updated.tenant_id = u.tenant_id
+                    WHERE u.email = $1 AND u.id <> $2
+                )
+                "#,
+            )
+            .bind(email)
+            .bind(command.user_id)
+            .fetch_one(&self.pool)
+            .await?;
+            if taken {
+                return Err(AppError::EmailTaken(email.clone()));
+            }
+        }
+
         // Generate update event
         let event = user.update(command.changes)?;
         let updated_at = match &event {
@@ -84,11 +137,18 @@ impl UpdateUserHandler {
         )
         .map_err(|e| AppError::Internal(e.to_string()))?;
 
-        // Persist event
-        self.event_store
-            .append_atomic(vec![operation], None, context)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+        // Event and users row commit together, so an email claimed by another
+        // user since the check above leaves no UserUpdated event behind
+        let tx = self.event_store.begin().await?;
+        let (mut tx, _event_ids) = self
+            .event_store
+            .append_atomic_in_tx(tx, &[operation], None, context)
+            .await?;
+
+        let before_state = json!({
+            "display_name": user.display_name(),
+            "email": user.email(),
+        });
 
         // Sync users table (projection)
         let applied_user = user.apply(event);
@@ -103,11 +163,39 @@ impl UpdateUserHandler {
         .bind(applied_user.display_name())
         .bind(applied_user.email())
         .bind(updated_at)
-        .execute(&self.pool)
-        .await?;
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| {
+            if is_email_taken(&e) {
+                AppError::EmailTaken(applied_user.email().to_string())
+            } else {
+                AppError::Database(e)
+            }
+        })?;
+
+        tx.commit().await?;
+
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::UserUpdated)
+            .resource_type("User")
+            .resource_id(command.user_id)
+            .before_state(&before_state)
+            .after_state(&json!({
+                "display_name": applied_user.display_name(),
+                "email": applied_user.email(),
+            }))
+            .changed_fields(changed_fields);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::UserUpdated,
+                json!({ "user_id": command.user_id, "updated_at": updated_at }),
+            )
+            .await;
 
         Ok(UpdateUserResult {
-            user_id: command.user_id,
+            user_id: command.user_id.into(),
             updated_at,
         })
     }
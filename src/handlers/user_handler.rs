@@ -2,14 +2,19 @@
 //!
 //! Handles user creation with automatic wallet account creation.
 
+use serde_json::json;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::aggregate::{Account, Aggregate, User};
-use crate::domain::OperationContext;
+use crate::audit::{AuditAction, AuditLogBuilder, AuditLogService};
+use crate::domain::{AccountType, OperationContext};
 use crate::error::AppError;
-use crate::event_store::{AggregateOperation, EventStore};
+use crate::event_store::{AggregateOperation, EventStore, IdempotencyRequest};
 use crate::projection::ProjectionService;
+use crate::state::AppState;
+use crate::tenants::DEFAULT_TENANT_ID;
+use crate::webhooks::{WebhookEventType, WebhookService};
 
 use super::{CreateUserCommand, CreateUserResult};
 
@@ -22,6 +27,8 @@ pub struct CreateUserHandler {
     event_store: EventStore,
     #[allow(dead_code)]
     projection: ProjectionService,
+    audit: AuditLogService,
+    webhooks: WebhookService,
     pool: PgPool,
 }
 
@@ -30,26 +37,58 @@ impl CreateUserHandler {
         Self {
             event_store: EventStore::new(pool.clone()),
             projection: ProjectionService::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
             pool,
         }
     }
 
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            event_store: state.event_store.clone(),
+            projection: state.projection.clone(),
+            audit: state.audit.clone(),
+            webhooks: state.webhooks.clone(),
+            pool: state.pool.clone(),
+        }
+    }
+
     /// Execute the create user command
     pub async fn execute(
         &self,
         command: CreateUserCommand,
+        idempotency_key: Option<Uuid>,
         context: &OperationContext,
     ) -> Result<CreateUserResult, AppError> {
+        let idempotency = idempotency_key
+            .map(|key| IdempotencyRequest::for_command(key, &command, context))
+            .transpose()
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        // A retried request finds its own user already created: return the
+        // original result instead of failing the uniqueness check below
+        if let Some(ref idempotency) = idempotency {
+            if let Some(result) = self.replay(idempotency).await? {
+                return Ok(result);
+            }
+        }
+
         // Start transaction for consistency
         let mut tx = self.pool.begin().await?;
 
+        // Users are created in the caller's tenant; usernames and emails
+        // are unique per tenant, user IDs across all tenants
+        let tenant_id = context.tenant_id.unwrap_or(DEFAULT_TENANT_ID);
+
         // Check if user already exists
         let existing: Option<(Uuid,)> = sqlx::query_as(
-            "SELECT id FROM users WHERE id = $1 OR username = $2 OR email = $3"
+            "SELECT id FROM users WHERE id = $1 OR (tenant_id = $4 AND (username = $2 OR email = $3))"
         )
         .bind(command.user_id)
         .bind(&command.username)
         .bind(&command.email)
+        .bind(tenant_id)
         .fetch_optional(&mut *tx)
         .await?;
 
@@ -61,7 +100,7 @@ impl CreateUserHandler {
 
         // Create user aggregate and event
         let (user, user_event) = User::create(
-            command.user_id,
+            command.user_id.into(),
             command.username.clone(),
             command.email,
             command.display_name,
@@ -71,8 +110,8 @@ impl CreateUserHandler {
         let account_id = Uuid::new_v4();
         let (account, account_event) = Account::create(
             account_id,
-            command.user_id,
-            "user_wallet".to_string(),
+            command.user_id.into(),
+            AccountType::UserWallet,
         );
 
         // Prepare atomic operations
@@ -98,21 +137,21 @@ impl CreateUserHandler {
         // Persist events atomically
         let event_ids = self
             .event_store
-            .append_atomic(operations, None, context)
-            .await
-            .map_err(|e| AppError::Internal(e.to_string()))?;
+            .append_atomic(operations, idempotency.as_ref(), context)
+            .await?;
 
         // Insert user record (for queries) - within transaction
         sqlx::query(
             r#"
-            INSERT INTO users (id, username, email, display_name, created_at, updated_at)
-            VALUES ($1, $2, $3, $4, NOW(), NOW())
+            INSERT INTO users (id, username, email, display_name, tenant_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, NOW(), NOW())
             "#,
         )
         .bind(command.user_id)
         .bind(&command.username)
-        .bind(&user.email())
+        .bind(user.email())
         .bind(user.display_name())
+        .bind(tenant_id)
         .execute(&mut *tx)
         .await?;
 
@@ -120,11 +159,12 @@ impl CreateUserHandler {
         sqlx::query(
             r#"
             INSERT INTO accounts (id, user_id, account_type)
-            VALUES ($1, $2, 'user_wallet')
+            VALUES ($1, $2, $3)
             "#,
         )
         .bind(account_id)
         .bind(command.user_id)
+        .bind(account.account_type().as_str())
         .execute(&mut *tx)
         .await?;
 
@@ -154,22 +194,105 @@ impl CreateUserHandler {
             .await
             .map_err(|e| AppError::Internal(e.to_string()))?;
 
+        // Record audit log entry
+        let audit_entry = AuditLogBuilder::new(AuditAction::UserCreated)
+            .resource_type("User")
+            .resource_id(command.user_id)
+            .after_state(&json!({
+                "username": user.username(),
+                "email": user.email(),
+                "display_name": user.display_name(),
+                "account_id": account_id,
+            }))
+            .changed_fields(vec![
+                "username".to_string(),
+                "email".to_string(),
+                "display_name".to_string(),
+            ]);
+        self.audit.record(audit_entry, context).await;
+
+        self.webhooks
+            .notify(
+                WebhookEventType::UserCreated,
+                json!({
+                    "user_id": command.user_id,
+                    "account_id": account_id,
+                    "username": user.username(),
+                }),
+            )
+            .await;
+
         Ok(CreateUserResult {
-            user_id: command.user_id,
+            user_id: command.user_id.into(),
             account_id,
             username: command.username,
         })
     }
+
+    /// Result of an earlier request with the same idempotency key, if it
+    /// created a user
+    async fn replay(&self, idempotency: &IdempotencyRequest) -> Result<Option<CreateUserResult>, AppError> {
+        let key: Option<(String, Option<Uuid>, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT processing_status, event_id, command_hash FROM idempotency_keys
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+            "#,
+        )
+        .bind(idempotency.key)
+        .bind(idempotency.scope.api_key_id)
+        .bind(idempotency.scope.tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let event_id = match key {
+            Some((status, Some(event_id), command_hash)) if status == "completed" => {
+                if command_hash.is_some_and(|hash| hash != idempotency.command_hash) {
+                    return Err(AppError::IdempotencyConflict);
+                }
+                event_id
+            }
+            _ => return Ok(None),
+        };
+
+        // The key must belong to a user creation, not another operation
+        let event = self.event_store.get_event(event_id).await?;
+        let Some(event) = event.filter(|e| e.aggregate_type == "User") else {
+            return Err(AppError::IdempotencyConflict);
+        };
+
+        let user: Option<(String, Uuid)> = sqlx::query_as(
+            r#"
+            SELECT u.username, a.id
+            FROM users u
+            JOIN accounts a ON a.user_id = u.id AND a.account_type = 'user_wallet' AND a.name IS NULL
+            WHERE u.id = $1
+            "#,
+        )
+        .bind(event.aggregate_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        let (username, account_id) =
+            user.ok_or_else(|| AppError::UserNotFound(event.aggregate_id.to_string()))?;
+
+        tracing::info!(idempotency_key = %idempotency.key, user_id = %event.aggregate_id, "Replaying user creation");
+
+        Ok(Some(CreateUserResult {
+            user_id: event.aggregate_id,
+            account_id,
+            username,
+        }))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::UserId;
 
     #[test]
     fn test_create_user_command() {
         let cmd = CreateUserCommand::new(
-            Uuid::new_v4(),
+            UserId::new_v4(),
             "alice".to_string(),
             "alice@example.com".to_string(),
         )
@@ -0,0 +1,117 @@
+//! In-Memory Idempotency Store
+//!
+//! [`IdempotencyTrait`] implementation keeping keys in process memory, for
+//! tests that run without PostgreSQL. Follows the key lifecycle of
+//! [`super::IdempotencyRepository`].
+
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use super::repository::STUCK_PROCESSING_MINUTES;
+use super::{IdempotencyError, IdempotencyKey, IdempotencyScope, IdempotencyStatus, IdempotencyTrait};
+
+/// Idempotency keys (per scope) shared between clones
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryIdempotencyStore {
+    keys: Arc<Mutex<HashMap<(IdempotencyScope, Uuid), IdempotencyKey>>>,
+}
+
+impl InMemoryIdempotencyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<(IdempotencyScope, Uuid), IdempotencyKey>> {
+        self.keys.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl IdempotencyTrait for InMemoryIdempotencyStore {
+    async fn get(&self, key: Uuid, scope: IdempotencyScope) -> Result<Option<IdempotencyKey>, IdempotencyError> {
+        Ok(self.lock().get(&(scope, key)).cloned())
+    }
+
+    async fn start_processing(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        request_hash: &str,
+    ) -> Result<Option<IdempotencyKey>, IdempotencyError> {
+        let now = Utc::now();
+        let mut keys = self.lock();
+
+        let Some(existing) = keys.get_mut(&(scope, key)) else {
+            keys.insert(
+                (scope, key),
+                IdempotencyKey {
+                    key,
+                    api_key_id: scope.api_key_id,
+                    tenant_id: scope.tenant_id,
+                    request_hash: request_hash.to_string(),
+                    event_id: None,
+                    response_status: None,
+                    response_body: None,
+                    status: IdempotencyStatus::Processing,
+                    processing_started_at: Some(now),
+                    created_at: now,
+                    expires_at: now + Duration::hours(24),
+                },
+            );
+            return Ok(None);
+        };
+
+        if existing.request_hash != request_hash {
+            return Err(IdempotencyError::HashMismatch(key));
+        }
+        match existing.status {
+            IdempotencyStatus::Completed => return Ok(Some(existing.clone())),
+            IdempotencyStatus::Processing
+                if existing
+                    .processing_started_at
+                    .is_some_and(|started| (now - started).num_minutes() < STUCK_PROCESSING_MINUTES) =>
+            {
+                return Err(IdempotencyError::KeyInProgress);
+            }
+            _ => {}
+        }
+
+        existing.status = IdempotencyStatus::Processing;
+        existing.processing_started_at = Some(now);
+        Ok(None)
+    }
+
+    async fn store_response(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: i32,
+        response_body: serde_json::Value,
+    ) -> Result<(), IdempotencyError> {
+        let mut keys = self.lock();
+        let existing = keys.get_mut(&(scope, key)).ok_or(IdempotencyError::NotFound(key))?;
+        existing.status = IdempotencyStatus::Completed;
+        existing.response_status = Some(response_status);
+        existing.response_body = Some(response_body);
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: Option<i32>,
+        response_body: Option<serde_json::Value>,
+    ) -> Result<(), IdempotencyError> {
+        let mut keys = self.lock();
+        let existing = keys.get_mut(&(scope, key)).ok_or(IdempotencyError::NotFound(key))?;
+        // Keys whose events were committed stay completed
+        if existing.event_id.is_none() {
+            existing.status = IdempotencyStatus::Failed;
+            existing.response_status = response_status;
+            existing.response_body = response_body;
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,12 @@
 //!
 //! Prevents duplicate request processing using idempotency keys.
 
+#[cfg(any(test, feature = "test-util"))]
+mod memory;
 mod repository;
+mod traits;
 
-pub use repository::{IdempotencyRepository, IdempotencyKey, IdempotencyStatus};
+#[cfg(any(test, feature = "test-util"))]
+pub use memory::InMemoryIdempotencyStore;
+pub use repository::{IdempotencyRepository, IdempotencyKey, IdempotencyScope, IdempotencyStatus, IdempotencyError};
+pub use traits::IdempotencyTrait;
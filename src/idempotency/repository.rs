@@ -7,6 +7,8 @@ use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use uuid::Uuid;
 
+use crate::domain::OperationContext;
+
 /// Idempotency key status
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -40,14 +42,36 @@ impl std::fmt::Display for IdempotencyStatus {
     }
 }
 
+/// Caller an idempotency key belongs to. The same key sent by another API
+/// key or tenant is an unrelated key; internal callers (CLI, jobs) have
+/// neither.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IdempotencyScope {
+    pub api_key_id: Option<Uuid>,
+    pub tenant_id: Option<Uuid>,
+}
+
+impl IdempotencyScope {
+    /// Scope of the caller behind `context`
+    pub fn from_context(context: &OperationContext) -> Self {
+        Self {
+            api_key_id: context.api_key_id,
+            tenant_id: context.tenant_id,
+        }
+    }
+}
+
 /// Stored idempotency key information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, sqlx::FromRow)]
 pub struct IdempotencyKey {
     pub key: Uuid,
+    pub api_key_id: Option<Uuid>,
+    pub tenant_id: Option<Uuid>,
     pub request_hash: String,
     pub event_id: Option<Uuid>,
     pub response_status: Option<i32>,
     pub response_body: Option<serde_json::Value>,
+    #[sqlx(rename = "processing_status", try_from = "String")]
     pub status: IdempotencyStatus,
     pub processing_started_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
@@ -70,6 +94,10 @@ pub enum IdempotencyError {
     NotFound(Uuid),
 }
 
+/// Minutes after which a key still marked processing is considered stuck
+/// and may be claimed again
+pub const STUCK_PROCESSING_MINUTES: i64 = 5;
+
 /// Repository for managing idempotency keys
 #[derive(Debug, Clone)]
 pub struct IdempotencyRepository {
@@ -87,45 +115,45 @@ impl IdempotencyRepository {
     // =========================================================================
 
     /// Get an existing idempotency key
-    pub async fn get(&self, key: Uuid) -> Result<Option<IdempotencyKey>, IdempotencyError> {
-        let result: Option<(
-            Uuid,
-            String,
-            Option<Uuid>,
-            Option<i32>,
-            Option<serde_json::Value>,
-            String,
-            Option<DateTime<Utc>>,
-            DateTime<Utc>,
-            DateTime<Utc>,
-        )> = sqlx::query_as(
+    pub async fn get(&self, key: Uuid, scope: IdempotencyScope) -> Result<Option<IdempotencyKey>, IdempotencyError> {
+        let result: Option<IdempotencyKey> = sqlx::query_as(
             r#"
             SELECT 
-                key, request_hash, event_id, response_status, response_body,
+                key, api_key_id, tenant_id, request_hash, event_id, response_status, response_body,
                 processing_status, processing_started_at, created_at, expires_at
             FROM idempotency_keys
-            WHERE key = $1
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
             "#,
         )
         .bind(key)
+        .bind(scope.api_key_id)
+        .bind(scope.tenant_id)
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(result.map(
-            |(key, request_hash, event_id, response_status, response_body, status, processing_started_at, created_at, expires_at)| {
-                IdempotencyKey {
-                    key,
-                    request_hash,
-                    event_id,
-                    response_status,
-                    response_body,
-                    status: IdempotencyStatus::from(status),
-                    processing_started_at,
-                    created_at,
-                    expires_at,
-                }
-            },
-        ))
+        Ok(result)
+    }
+
+    /// Keys that produced one of `event_ids`, oldest first. Every event
+    /// appended with an idempotency key is the event_id of its key, so
+    /// matching on event_id never picks up another caller's key with the
+    /// same value.
+    pub async fn get_linked(&self, event_ids: &[Uuid]) -> Result<Vec<IdempotencyKey>, IdempotencyError> {
+        let linked: Vec<IdempotencyKey> = sqlx::query_as(
+            r#"
+            SELECT
+                key, api_key_id, tenant_id, request_hash, event_id, response_status, response_body,
+                processing_status, processing_started_at, created_at, expires_at
+            FROM idempotency_keys
+            WHERE event_id = ANY($1)
+            ORDER BY created_at ASC
+            "#,
+        )
+        .bind(event_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(linked)
     }
 
     // =========================================================================
@@ -139,10 +167,38 @@ impl IdempotencyRepository {
     pub async fn start_processing(
         &self,
         key: Uuid,
+        scope: IdempotencyScope,
         request_hash: &str,
     ) -> Result<Option<IdempotencyKey>, IdempotencyError> {
-        // First, check if key exists
-        if let Some(existing) = self.get(key).await? {
+        // Claim the key atomically; concurrent requests with the same key see the existing row
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO idempotency_keys (
+                key, api_key_id, tenant_id, request_hash, processing_status, processing_started_at
+            )
+            VALUES ($1, $2, $3, $4, 'processing', NOW())
+            ON CONFLICT (
+                key,
+                COALESCE(api_key_id, '00000000-0000-0000-0000-000000000000'::uuid),
+                COALESCE(tenant_id, '00000000-0000-0000-0000-000000000000'::uuid)
+            ) DO NOTHING
+            "#,
+        )
+        .bind(key)
+        .bind(scope.api_key_id)
+        .bind(scope.tenant_id)
+        .bind(request_hash)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if inserted == 1 {
+            return Ok(None);
+        }
+
+        {
+            let existing = self.get(key, scope).await?.ok_or(IdempotencyError::NotFound(key))?;
+
             // Verify request hash matches
             if existing.request_hash != request_hash {
                 return Err(IdempotencyError::HashMismatch(key));
@@ -155,7 +211,7 @@ impl IdempotencyRepository {
                 // Check if stuck (processing for more than 5 minutes)
                 if let Some(started) = existing.processing_started_at {
                     let duration = Utc::now() - started;
-                    if duration.num_minutes() < 5 {
+                    if duration.num_minutes() < STUCK_PROCESSING_MINUTES {
                         return Err(IdempotencyError::KeyInProgress);
                     }
                     // If stuck, reset and allow retry below
@@ -167,33 +223,29 @@ impl IdempotencyRepository {
             }
 
             // Failed or stuck processing - update to processing
-            sqlx::query(
+            // (only if nobody else re-claimed it in the meantime)
+            let reclaimed = sqlx::query(
                 r#"
                 UPDATE idempotency_keys
                 SET processing_status = 'processing', processing_started_at = NOW()
-                WHERE key = $1
+                WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+                  AND processing_status = $4
                 "#,
             )
             .bind(key)
+            .bind(scope.api_key_id)
+            .bind(scope.tenant_id)
+            .bind(existing.status.to_string())
             .execute(&self.pool)
-            .await?;
-
-            return Ok(None);
-        }
+            .await?
+            .rows_affected();
 
-        // Key doesn't exist - create new
-        sqlx::query(
-            r#"
-            INSERT INTO idempotency_keys (key, request_hash, processing_status, processing_started_at)
-            VALUES ($1, $2, 'processing', NOW())
-            "#,
-        )
-        .bind(key)
-        .bind(request_hash)
-        .execute(&self.pool)
-        .await?;
+            if reclaimed == 0 {
+                return Err(IdempotencyError::KeyInProgress);
+            }
 
-        Ok(None)
+            Ok(None)
+        }
     }
 
     // =========================================================================
@@ -204,6 +256,7 @@ impl IdempotencyRepository {
     pub async fn mark_completed(
         &self,
         key: Uuid,
+        scope: IdempotencyScope,
         event_id: Uuid,
         response_status: i32,
         response_body: serde_json::Value,
@@ -213,13 +266,15 @@ impl IdempotencyRepository {
             UPDATE idempotency_keys
             SET 
                 processing_status = 'completed',
-                event_id = $2,
-                response_status = $3,
-                response_body = $4
-            WHERE key = $1
+                event_id = $4,
+                response_status = $5,
+                response_body = $6
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
             "#,
         )
         .bind(key)
+        .bind(scope.api_key_id)
+        .bind(scope.tenant_id)
         .bind(event_id)
         .bind(response_status)
         .bind(response_body)
@@ -234,14 +289,52 @@ impl IdempotencyRepository {
         Ok(())
     }
 
+    /// Store the response for a key whose events were recorded by the EventStore
+    /// (event_id is left untouched)
+    pub async fn store_response(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: i32,
+        response_body: serde_json::Value,
+    ) -> Result<(), IdempotencyError> {
+        let rows = sqlx::query(
+            r#"
+            UPDATE idempotency_keys
+            SET 
+                processing_status = 'completed',
+                response_status = $4,
+                response_body = $5
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+            "#,
+        )
+        .bind(key)
+        .bind(scope.api_key_id)
+        .bind(scope.tenant_id)
+        .bind(response_status)
+        .bind(response_body)
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        if rows == 0 {
+            return Err(IdempotencyError::NotFound(key));
+        }
+
+        Ok(())
+    }
+
     // =========================================================================
     // M095: mark_failed
     // =========================================================================
 
-    /// Mark an idempotency key as failed
+    /// Mark an idempotency key as failed.
+    /// Keys whose events were already committed (event_id set) stay completed,
+    /// so a retry replays the operation instead of executing it twice.
     pub async fn mark_failed(
         &self,
         key: Uuid,
+        scope: IdempotencyScope,
         response_status: Option<i32>,
         response_body: Option<serde_json::Value>,
     ) -> Result<(), IdempotencyError> {
@@ -250,19 +343,22 @@ impl IdempotencyRepository {
             UPDATE idempotency_keys
             SET 
                 processing_status = 'failed',
-                response_status = $2,
-                response_body = $3
-            WHERE key = $1
+                response_status = $4,
+                response_body = $5
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
+              AND event_id IS NULL
             "#,
         )
         .bind(key)
+        .bind(scope.api_key_id)
+        .bind(scope.tenant_id)
         .bind(response_status)
         .bind(response_body)
         .execute(&self.pool)
         .await?
         .rows_affected();
 
-        if rows == 0 {
+        if rows == 0 && self.get(key, scope).await?.is_none() {
             return Err(IdempotencyError::NotFound(key));
         }
 
@@ -270,13 +366,16 @@ impl IdempotencyRepository {
     }
 
     /// Check if a key exists and is completed
-    pub async fn is_completed(&self, key: Uuid) -> Result<bool, IdempotencyError> {
+    pub async fn is_completed(&self, key: Uuid, scope: IdempotencyScope) -> Result<bool, IdempotencyError> {
         let status: Option<String> = sqlx::query_scalar(
             r#"
-            SELECT processing_status FROM idempotency_keys WHERE key = $1
+            SELECT processing_status FROM idempotency_keys
+            WHERE key = $1 AND api_key_id IS NOT DISTINCT FROM $2 AND tenant_id IS NOT DISTINCT FROM $3
             "#,
         )
         .bind(key)
+        .bind(scope.api_key_id)
+        .bind(scope.tenant_id)
         .fetch_optional(&self.pool)
         .await?;
 
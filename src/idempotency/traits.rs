@@ -0,0 +1,76 @@
+//! Idempotency Trait
+//!
+//! The key lifecycle the idempotency middleware depends on, implemented by
+//! [`IdempotencyRepository`] and by an in-memory fake for tests.
+
+use std::future::Future;
+use uuid::Uuid;
+
+use super::{IdempotencyError, IdempotencyKey, IdempotencyRepository, IdempotencyScope};
+
+/// Idempotency key storage used by the idempotency middleware
+pub trait IdempotencyTrait: Send + Sync {
+    /// Get an existing idempotency key
+    fn get(&self, key: Uuid, scope: IdempotencyScope) -> impl Future<Output = Result<Option<IdempotencyKey>, IdempotencyError>> + Send;
+
+    /// Claim a key for processing (see [`IdempotencyRepository::start_processing`])
+    fn start_processing(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        request_hash: &str,
+    ) -> impl Future<Output = Result<Option<IdempotencyKey>, IdempotencyError>> + Send;
+
+    /// Complete a key with the response to replay
+    fn store_response(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: i32,
+        response_body: serde_json::Value,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send;
+
+    /// Fail a key so it can be retried
+    fn mark_failed(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: Option<i32>,
+        response_body: Option<serde_json::Value>,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send;
+}
+
+impl IdempotencyTrait for IdempotencyRepository {
+    fn get(&self, key: Uuid, scope: IdempotencyScope) -> impl Future<Output = Result<Option<IdempotencyKey>, IdempotencyError>> + Send {
+        IdempotencyRepository::get(self, key, scope)
+    }
+
+    fn start_processing(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        request_hash: &str,
+    ) -> impl Future<Output = Result<Option<IdempotencyKey>, IdempotencyError>> + Send {
+        IdempotencyRepository::start_processing(self, key, scope, request_hash)
+    }
+
+    fn store_response(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: i32,
+        response_body: serde_json::Value,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send {
+        IdempotencyRepository::store_response(self, key, scope, response_status, response_body)
+    }
+
+    fn mark_failed(
+        &self,
+        key: Uuid,
+        scope: IdempotencyScope,
+        response_status: Option<i32>,
+        response_body: Option<serde_json::Value>,
+    ) -> impl Future<Output = Result<(), IdempotencyError>> + Send {
+        IdempotencyRepository::mark_failed(self, key, scope, response_status, response_body)
+    }
+}
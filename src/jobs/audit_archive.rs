@@ -0,0 +1,233 @@
+//! Audit Log Archival Job
+//!
+//! Moves audit_logs entries older than the retention period out of the
+//! database. Each run takes the oldest remaining segment, verifies its hash
+//! chain from the previous archive's anchor, writes it to a gzip-compressed
+//! NDJSON file, records the file's SHA-256 and the segment's boundary hashes
+//! in audit_archives and deletes the rows in the same transaction. The last
+//! hash of the newest archive anchors the entries left in audit_logs.
+
+use chrono::{DateTime, Duration, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::io::Write;
+use std::path::Path;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::audit::{AuditLogEntry, AuditLogService};
+
+/// Most entries archived per file (the next run continues)
+const MAX_ENTRIES_PER_ARCHIVE: i64 = 10_000;
+
+/// Recorded archive file
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AuditArchive {
+    pub id: Uuid,
+    pub first_sequence: i64,
+    pub last_sequence: i64,
+    pub entry_count: i64,
+    /// previous_hash of the first archived entry
+    pub anchor_hash: String,
+    /// current_hash of the last archived entry
+    pub last_hash: String,
+    pub archive_path: String,
+    pub archive_sha256: String,
+    pub archived_before: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+const ARCHIVE_COLUMNS: &str = "id, first_sequence, last_sequence, entry_count, anchor_hash, last_hash, \
+     archive_path, archive_sha256, archived_before, created_at";
+
+/// Archive the oldest segment of entries created more than `retention_days`
+/// ago into `dir`. Returns None when no entry is old enough.
+///
+/// Fails without deleting anything when the segment's hash chain does not
+/// verify.
+pub async fn archive_audit_logs(
+    pool: &PgPool,
+    dir: &Path,
+    retention_days: u32,
+) -> Result<Option<AuditArchive>, JobError> {
+    let audit = AuditLogService::new(pool.clone());
+    let archived_before = Utc::now() - Duration::days(i64::from(retention_days));
+
+    // Oldest contiguous run of expired entries after the current anchor
+    let start = audit.chain_start().await?;
+    let entries: Vec<AuditLogEntry> = audit
+        .entries_after(start.sequence_number, MAX_ENTRIES_PER_ARCHIVE)
+        .await?
+        .into_iter()
+        .take_while(|entry| entry.created_at < archived_before)
+        .collect();
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        return Ok(None);
+    };
+    let (first_sequence, last_sequence) = (first.sequence_number, last.sequence_number);
+
+    let (verification, head) = audit.verify_hash_chain_after(&start, entries.len() as i64).await?;
+    if !verification.is_valid || head.sequence_number != last_sequence || head.hash != last.current_hash {
+        tracing::error!(
+            first_sequence,
+            last_sequence,
+            first_invalid_entry = ?verification.first_invalid_entry,
+            "Audit log segment failed verification; not archiving"
+        );
+        return Err(JobError::AuditChainInvalid(first_sequence, last_sequence));
+    }
+
+    let path = dir.join(archive_file_name(first_sequence, last_sequence));
+    let archive_sha256 = write_archive(&path, &entries).await?;
+
+    let mut tx = pool.begin().await?;
+
+    // Serialize with calculate_audit_hash() so no entry is appended while
+    // the chain head may be moving into the archive
+    sqlx::query("SELECT pg_advisory_xact_lock(hashtext('audit_logs_chain'))")
+        .execute(&mut *tx)
+        .await?;
+
+    let archive: AuditArchive = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO audit_archives (
+            first_sequence, last_sequence, entry_count, anchor_hash, last_hash,
+            archive_path, archive_sha256, archived_before
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING {}
+        "#,
+        ARCHIVE_COLUMNS
+    ))
+    .bind(first_sequence)
+    .bind(last_sequence)
+    .bind(entries.len() as i64)
+    .bind(&first.previous_hash)
+    .bind(&last.current_hash)
+    .bind(path.display().to_string())
+    .bind(&archive_sha256)
+    .bind(archived_before)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    // no_modify_audit only lets this transaction delete archived entries
+    sqlx::query("SELECT set_config('finance_atp.audit_archival', 'on', true)")
+        .execute(&mut *tx)
+        .await?;
+    let deleted = sqlx::query("DELETE FROM audit_logs WHERE sequence_number >= $1 AND sequence_number <= $2")
+        .bind(first_sequence)
+        .bind(last_sequence)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+    if deleted != entries.len() as u64 {
+        // Entries changed since they were verified; keep them and the file for inspection
+        tx.rollback().await?;
+        return Err(JobError::AuditChainInvalid(first_sequence, last_sequence));
+    }
+
+    tx.commit().await?;
+
+    tracing::info!(
+        first_sequence,
+        last_sequence,
+        entry_count = archive.entry_count,
+        path = %archive.archive_path,
+        "Archived audit log entries"
+    );
+
+    Ok(Some(archive))
+}
+
+/// Load the most recent archives, newest first
+pub async fn recent_audit_archives(pool: &PgPool, limit: i64) -> Result<Vec<AuditArchive>, JobError> {
+    let archives: Vec<AuditArchive> = sqlx::query_as(&format!(
+        "SELECT {} FROM audit_archives ORDER BY last_sequence DESC LIMIT $1",
+        ARCHIVE_COLUMNS
+    ))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(archives)
+}
+
+/// File name of the archive of [first, last]
+fn archive_file_name(first_sequence: i64, last_sequence: i64) -> String {
+    format!("audit-{:012}-{:012}.ndjson.gz", first_sequence, last_sequence)
+}
+
+/// Gzip-compressed NDJSON of `entries` (one entry per line)
+fn encode_archive(entries: &[AuditLogEntry]) -> Result<Vec<u8>, JobError> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    for entry in entries {
+        serde_json::to_writer(&mut encoder, entry)?;
+        encoder.write_all(b"\n")?;
+    }
+    Ok(encoder.finish()?)
+}
+
+/// Write the archive file (durably) and return its SHA-256
+async fn write_archive(path: &Path, entries: &[AuditLogEntry]) -> Result<String, JobError> {
+    let bytes = encode_archive(entries)?;
+    let sha256 = hex::encode(Sha256::digest(&bytes));
+
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    let mut file = tokio::fs::File::create(path).await?;
+    tokio::io::AsyncWriteExt::write_all(&mut file, &bytes).await?;
+    file.sync_all().await?;
+
+    Ok(sha256)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    fn entry(sequence_number: i64) -> AuditLogEntry {
+        AuditLogEntry {
+            id: Uuid::new_v4(),
+            sequence_number,
+            api_key_id: None,
+            request_user_id: None,
+            correlation_id: None,
+            action: "user.created".to_string(),
+            resource_type: Some("User".to_string()),
+            resource_id: Some(Uuid::new_v4()),
+            before_state: None,
+            after_state: Some(serde_json::json!({ "username": "alice" })),
+            changed_fields: None,
+            client_ip: None,
+            previous_hash: "0".repeat(64),
+            current_hash: "1".repeat(64),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_archive_file_name() {
+        assert_eq!(archive_file_name(1, 250), "audit-000000000001-000000000250.ndjson.gz");
+    }
+
+    #[test]
+    fn test_encode_archive_round_trip() {
+        let entries = vec![entry(1), entry(2)];
+        let bytes = encode_archive(&entries).unwrap();
+
+        let mut ndjson = String::new();
+        GzDecoder::new(bytes.as_slice()).read_to_string(&mut ndjson).unwrap();
+        let decoded: Vec<AuditLogEntry> = ndjson.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].sequence_number, 2);
+        assert_eq!(decoded[0].after_state, entries[0].after_state);
+    }
+}
@@ -0,0 +1,203 @@
+//! Audit Chain Verification Job
+//!
+//! Verifies the audit log hash chain incrementally: each run resumes after
+//! the last valid checkpoint, records a new checkpoint in
+//! audit_chain_checkpoints and, when an anchor target is configured,
+//! publishes the verified chain head there.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::audit::{AuditAnchor, AuditLogService, ChainAnchor, ChainPosition};
+
+/// Entries hashed per query
+const VERIFY_BATCH_SIZE: i64 = 1000;
+
+/// Most entries verified by one run (the next run continues)
+const MAX_ENTRIES_PER_RUN: i64 = 50_000;
+
+/// Stored verification checkpoint
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct AuditCheckpoint {
+    pub id: i64,
+    /// Last entry verified
+    pub sequence_number: i64,
+    pub chain_head_hash: String,
+    /// Entries verified by this run
+    pub entries_verified: i64,
+    pub is_valid: bool,
+    pub first_invalid_entry: Option<Uuid>,
+    pub anchor_target: Option<String>,
+    pub anchored_at: Option<DateTime<Utc>>,
+    pub anchor_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+const CHECKPOINT_COLUMNS: &str = "id, sequence_number, chain_head_hash, entries_verified, is_valid, \
+     first_invalid_entry, anchor_target, anchored_at, anchor_error, created_at";
+
+/// Verify the entries appended since the last valid checkpoint.
+///
+/// Returns the new checkpoint, or None when there was nothing new to verify
+/// (or the same tampered entry was already recorded). Anchor failures are
+/// stored on the checkpoint and do not fail the job.
+pub async fn verify_audit_chain(
+    pool: &PgPool,
+    http: &reqwest::Client,
+    anchor: Option<&AuditAnchor>,
+) -> Result<Option<AuditCheckpoint>, JobError> {
+    let audit = AuditLogService::new(pool.clone());
+
+    let start: Option<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT sequence_number, chain_head_hash
+        FROM audit_chain_checkpoints
+        WHERE is_valid
+        ORDER BY sequence_number DESC, id DESC
+        LIMIT 1
+        "#,
+    )
+    .fetch_optional(pool)
+    .await?;
+    // Entries up to the newest archive were verified before being archived
+    let archived = audit.chain_start().await?;
+    let start = match start {
+        Some((sequence_number, hash)) if sequence_number >= archived.sequence_number => {
+            ChainPosition { sequence_number, hash }
+        }
+        _ => archived,
+    };
+
+    let mut head = start.clone();
+    let mut invalid = None;
+    let mut remaining = MAX_ENTRIES_PER_RUN;
+    while remaining > 0 {
+        let batch = VERIFY_BATCH_SIZE.min(remaining);
+        let (result, next) = audit.verify_hash_chain_after(&head, batch).await?;
+        let advanced = next != head;
+        head = next;
+
+        if !result.is_valid {
+            invalid = Some(result);
+            break;
+        }
+        if !advanced || (result.entries_checked as i64) < batch {
+            break;
+        }
+        remaining -= batch;
+    }
+
+    if invalid.is_none() && head == start {
+        return Ok(None);
+    }
+
+    let entries_verified: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM audit_logs WHERE sequence_number > $1 AND sequence_number <= $2",
+    )
+    .bind(start.sequence_number)
+    .bind(head.sequence_number)
+    .fetch_one(pool)
+    .await?;
+
+    if let Some(result) = &invalid {
+        tracing::error!(
+            first_invalid_entry = ?result.first_invalid_entry,
+            last_valid_sequence = head.sequence_number,
+            "Audit log hash chain verification failed"
+        );
+
+        // Record a tampered entry once, not on every run
+        let already_recorded: bool = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE((
+                SELECT NOT is_valid AND first_invalid_entry = $1
+                FROM audit_chain_checkpoints
+                ORDER BY id DESC
+                LIMIT 1
+            ), false)
+            "#,
+        )
+        .bind(result.first_invalid_entry)
+        .fetch_one(pool)
+        .await?;
+        if already_recorded {
+            return Ok(None);
+        }
+    }
+
+    let mut checkpoint: AuditCheckpoint = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO audit_chain_checkpoints (
+            sequence_number, chain_head_hash, entries_verified, is_valid, first_invalid_entry
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        CHECKPOINT_COLUMNS
+    ))
+    .bind(head.sequence_number)
+    .bind(&head.hash)
+    .bind(entries_verified)
+    .bind(invalid.is_none())
+    .bind(invalid.as_ref().and_then(|r| r.first_invalid_entry))
+    .fetch_one(pool)
+    .await?;
+
+    tracing::info!(
+        sequence_number = checkpoint.sequence_number,
+        entries_verified = checkpoint.entries_verified,
+        is_valid = checkpoint.is_valid,
+        "Recorded audit chain checkpoint"
+    );
+
+    // Only verified heads are published
+    if let (Some(anchor), true) = (anchor, checkpoint.is_valid) {
+        let published = ChainAnchor {
+            sequence_number: checkpoint.sequence_number,
+            chain_head_hash: checkpoint.chain_head_hash.clone(),
+            verified_at: checkpoint.created_at,
+        };
+        let anchor_error = match anchor.publish(http, &published).await {
+            Ok(()) => None,
+            Err(e) => {
+                tracing::warn!(target = %anchor, error = %e, "Failed to publish audit chain anchor");
+                Some(e.to_string())
+            }
+        };
+
+        checkpoint = sqlx::query_as(&format!(
+            r#"
+            UPDATE audit_chain_checkpoints
+            SET anchor_target = $2,
+                anchored_at = CASE WHEN $3::text IS NULL THEN NOW() END,
+                anchor_error = $3
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            CHECKPOINT_COLUMNS
+        ))
+        .bind(checkpoint.id)
+        .bind(anchor.to_string())
+        .bind(anchor_error)
+        .fetch_one(pool)
+        .await?;
+    }
+
+    Ok(Some(checkpoint))
+}
+
+/// Load the most recent checkpoints, newest first
+pub async fn recent_audit_checkpoints(pool: &PgPool, limit: i64) -> Result<Vec<AuditCheckpoint>, JobError> {
+    let checkpoints: Vec<AuditCheckpoint> = sqlx::query_as(&format!(
+        "SELECT {} FROM audit_chain_checkpoints ORDER BY id DESC LIMIT $1",
+        CHECKPOINT_COLUMNS
+    ))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(checkpoints)
+}
@@ -0,0 +1,321 @@
+//! Event Integrity Job
+//!
+//! Hashes each completed UTC day of the event store into
+//! event_integrity_digests. The events table itself rejects UPDATE and
+//! DELETE (no_modify_events trigger); the digests catch changes that bypass
+//! the trigger, such as a superuser disabling it, truncating a partition or
+//! inserting backdated rows. `verify_event_integrity` recomputes stored days
+//! and reports every day whose events no longer match.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::stats::{day_bounds, pending_days};
+use super::JobError;
+
+/// Events hashed per query
+const DIGEST_BATCH_SIZE: i64 = 1000;
+
+/// How long after midnight a day is treated as complete (events are stamped
+/// before their transaction commits)
+const DAY_SETTLE_MINUTES: i64 = 10;
+
+/// Stored digest of one UTC day of events
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct EventDayDigest {
+    pub day: NaiveDate,
+    pub event_count: i64,
+    pub first_global_sequence: Option<i64>,
+    pub last_global_sequence: Option<i64>,
+    /// Hex SHA-256 over the day's events in (created_at, id) order
+    pub digest: String,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Outcome of checking one day
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DayIntegrity {
+    /// The day's events still hash to the stored digest
+    Valid,
+    /// The day's events changed since the digest was stored
+    Tampered,
+    /// No digest stored yet (the current day, or the job has not caught up)
+    NotDigested,
+}
+
+/// Check result of one day
+#[derive(Debug, Clone, Serialize)]
+pub struct EventDayIntegrity {
+    pub day: NaiveDate,
+    pub status: DayIntegrity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stored: Option<EventDayDigest>,
+    /// Events found now (omitted for days without a digest)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_count: Option<i64>,
+    /// Digest of the events found now (omitted for days without a digest)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+/// Result of verifying a range of days
+#[derive(Debug, Clone, Serialize)]
+pub struct EventIntegrityReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// No digested day in the range was tampered with and the guard is on
+    pub valid: bool,
+    /// no_modify_events is enabled (always) on events and every partition
+    pub guard_enabled: bool,
+    pub days: Vec<EventDayIntegrity>,
+    pub verified_at: DateTime<Utc>,
+}
+
+/// Digest of a day computed from the events table
+#[derive(Debug, Clone, PartialEq)]
+struct ComputedDigest {
+    event_count: i64,
+    first_global_sequence: Option<i64>,
+    last_global_sequence: Option<i64>,
+    digest: String,
+}
+
+/// Store digests for the completed days since the last digested one.
+/// Returns the number of days digested.
+pub async fn digest_pending_event_days(pool: &PgPool) -> Result<u64, JobError> {
+    let last_day: Option<NaiveDate> = sqlx::query_scalar("SELECT MAX(day) FROM event_integrity_digests")
+        .fetch_one(pool)
+        .await?;
+
+    let first_day = match last_day {
+        Some(day) => day.succ_opt(),
+        None => sqlx::query_scalar("SELECT (MIN(created_at) AT TIME ZONE 'UTC')::date FROM events")
+            .fetch_one(pool)
+            .await?,
+    };
+    let Some(first_day) = first_day else {
+        return Ok(0);
+    };
+
+    let today = (Utc::now() - Duration::minutes(DAY_SETTLE_MINUTES)).date_naive();
+    let days = pending_days(first_day, today);
+    for day in &days {
+        let computed = compute_day_digest(pool, *day).await?;
+
+        // A concurrent run may have stored the day first; digests never change
+        sqlx::query(
+            r#"
+            INSERT INTO event_integrity_digests (
+                day, event_count, first_global_sequence, last_global_sequence, digest
+            )
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (day) DO NOTHING
+            "#,
+        )
+        .bind(day)
+        .bind(computed.event_count)
+        .bind(computed.first_global_sequence)
+        .bind(computed.last_global_sequence)
+        .bind(&computed.digest)
+        .execute(pool)
+        .await?;
+
+        tracing::info!(day = %day, event_count = computed.event_count, "Stored event integrity digest");
+    }
+
+    Ok(days.len() as u64)
+}
+
+/// Recompute the digested days in [from, to] and compare them with the
+/// stored digests
+pub async fn verify_event_integrity(
+    pool: &PgPool,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<EventIntegrityReport, JobError> {
+    let stored: Vec<EventDayDigest> = sqlx::query_as(
+        r#"
+        SELECT day, event_count, first_global_sequence, last_global_sequence, digest, computed_at
+        FROM event_integrity_digests
+        WHERE day >= $1 AND day <= $2
+        ORDER BY day
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+    let mut stored = stored.into_iter().peekable();
+
+    let mut days = Vec::new();
+    for day in from.iter_days().take_while(|day| *day <= to) {
+        let Some(digest) = stored.next_if(|digest| digest.day == day) else {
+            days.push(EventDayIntegrity {
+                day,
+                status: DayIntegrity::NotDigested,
+                stored: None,
+                event_count: None,
+                digest: None,
+            });
+            continue;
+        };
+
+        let computed = compute_day_digest(pool, day).await?;
+        let status = if computed.digest == digest.digest && computed.event_count == digest.event_count {
+            DayIntegrity::Valid
+        } else {
+            tracing::error!(
+                day = %day,
+                stored_digest = %digest.digest,
+                computed_digest = %computed.digest,
+                "Event store integrity check failed"
+            );
+            DayIntegrity::Tampered
+        };
+        days.push(EventDayIntegrity {
+            day,
+            status,
+            stored: Some(digest),
+            event_count: Some(computed.event_count),
+            digest: Some(computed.digest),
+        });
+    }
+
+    let guard_enabled = event_guard_enabled(pool).await?;
+    Ok(EventIntegrityReport {
+        from,
+        to,
+        valid: guard_enabled && days.iter().all(|day| day.status != DayIntegrity::Tampered),
+        guard_enabled,
+        days,
+        verified_at: Utc::now(),
+    })
+}
+
+/// Whether no_modify_events is enabled (always) on events and every
+/// partition of it
+pub async fn event_guard_enabled(pool: &PgPool) -> Result<bool, JobError> {
+    let enabled: bool = sqlx::query_scalar(
+        r#"
+        SELECT bool_and(EXISTS (
+            SELECT 1 FROM pg_trigger t
+            WHERE t.tgrelid = r.oid
+              AND t.tgname = 'no_modify_events'
+              AND t.tgenabled = 'A'
+        ))
+        FROM (
+            SELECT 'events'::regclass::oid AS oid
+            UNION ALL
+            SELECT inhrelid FROM pg_inherits WHERE inhparent = 'events'::regclass
+        ) r
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(enabled)
+}
+
+/// Hash the events of `day`: one line per event with every stored column
+/// (JSONB in its canonical text form), in (created_at, id) order
+async fn compute_day_digest(pool: &PgPool, day: NaiveDate) -> Result<ComputedDigest, JobError> {
+    let (start, end) = day_bounds(day);
+    let mut hasher = Sha256::new();
+    let mut event_count = 0i64;
+    let mut first_global_sequence = None;
+    let mut last_global_sequence = None;
+    let mut after: Option<(DateTime<Utc>, Uuid)> = None;
+
+    loop {
+        let batch: Vec<(DateTime<Utc>, Uuid, i64, String)> = sqlx::query_as(
+            r#"
+            SELECT created_at, id, global_sequence,
+                   concat_ws('|', id, aggregate_type, aggregate_id, version, event_type,
+                             event_data::text, context::text, COALESCE(idempotency_key::text, ''),
+                             global_sequence, tenant_id)
+            FROM events
+            WHERE created_at >= $1 AND created_at < $2
+              AND ($3::timestamptz IS NULL OR (created_at, id) > ($3, $4))
+            ORDER BY created_at, id
+            LIMIT $5
+            "#,
+        )
+        .bind(start)
+        .bind(end)
+        .bind(after.map(|(created_at, _)| created_at))
+        .bind(after.map(|(_, id)| id))
+        .bind(DIGEST_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        let batch_len = batch.len() as i64;
+        for (created_at, id, global_sequence, line) in batch {
+            hash_event(&mut hasher, created_at, &line);
+            event_count += 1;
+            first_global_sequence.get_or_insert(global_sequence);
+            last_global_sequence = Some(global_sequence);
+            after = Some((created_at, id));
+        }
+
+        if batch_len < DIGEST_BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(ComputedDigest {
+        event_count,
+        first_global_sequence,
+        last_global_sequence,
+        digest: hex::encode(hasher.finalize()),
+    })
+}
+
+/// Add one event to a day digest
+fn hash_event(hasher: &mut Sha256, created_at: DateTime<Utc>, line: &str) {
+    hasher.update(created_at.timestamp_micros().to_string().as_bytes());
+    hasher.update(b"|");
+    hasher.update(line.as_bytes());
+    hasher.update(b"\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digest_of(events: &[(DateTime<Utc>, &str)]) -> String {
+        let mut hasher = Sha256::new();
+        for (created_at, line) in events {
+            hash_event(&mut hasher, *created_at, line);
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    #[test]
+    fn test_day_digest_detects_changes() {
+        let at = Utc::now();
+        let original = digest_of(&[(at, "a|Account|1"), (at, "b|Account|2")]);
+
+        assert_eq!(original, digest_of(&[(at, "a|Account|1"), (at, "b|Account|2")]));
+        // Edited, removed, reordered or re-stamped events change the digest
+        assert_ne!(original, digest_of(&[(at, "a|Account|1"), (at, "b|Account|3")]));
+        assert_ne!(original, digest_of(&[(at, "a|Account|1")]));
+        assert_ne!(original, digest_of(&[(at, "b|Account|2"), (at, "a|Account|1")]));
+        assert_ne!(
+            original,
+            digest_of(&[(at, "a|Account|1"), (at + Duration::microseconds(1), "b|Account|2")])
+        );
+    }
+
+    #[test]
+    fn test_empty_day_digest() {
+        // SHA-256 of the empty input
+        assert_eq!(
+            digest_of(&[]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
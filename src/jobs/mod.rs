@@ -4,15 +4,56 @@
 //! These jobs are run on a schedule to clean up expired data and maintain system health.
 
 use chrono::{DateTime, Datelike, Utc};
+use serde::Serialize;
 use sqlx::PgPool;
+use std::path::PathBuf;
 use std::time::Duration;
 use tokio::time::interval;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use crate::audit::{AuditAnchor, AuditLogError};
+use crate::config::{Config, SystemUserIds};
+use crate::projection::project_notifications;
+
+mod audit_archive;
+mod audit_chain;
+mod event_integrity;
+mod partitions;
+mod pending_transfers;
+mod reconciliation;
+mod retention;
+mod runs;
+mod snapshots;
+mod stats;
+mod webhooks;
+
+pub use audit_archive::{archive_audit_logs, recent_audit_archives, AuditArchive};
+pub use audit_chain::{recent_audit_checkpoints, verify_audit_chain, AuditCheckpoint};
+pub use event_integrity::{
+    digest_pending_event_days, event_guard_enabled, verify_event_integrity, DayIntegrity, EventDayDigest,
+    EventDayIntegrity, EventIntegrityReport,
+};
+pub use partitions::{backfill_partitions, default_partition_policies, maintain_partitions, PartitionPolicy, PartitionResult};
+pub use pending_transfers::expire_pending_transfers;
+pub use reconciliation::{
+    reconcile_ledger, recent_reconciliation_reports, Discrepancy, DiscrepancyKind,
+    ReconciliationReport,
+};
+pub use retention::{anonymize_expired_users, RETENTION_REASON};
+pub use runs::{last_job_runs, prune_job_runs, recent_job_runs, JobRun, JobTrigger, JOB_RUN_RETENTION_DAYS};
+pub use snapshots::{
+    maintain_snapshots, prune_orphaned_snapshots, snapshot_coverage, snapshot_hot_aggregates,
+    SnapshotCoverage, SnapshotMaintenanceReport, SNAPSHOT_EVENT_THRESHOLD,
+};
+pub use stats::{daily_stats, materialize_daily_stats, materialize_pending_daily_stats, DailyStats};
+pub use webhooks::{dispatch_webhooks, webhook_retry_delay, MAX_WEBHOOK_ATTEMPTS, WEBHOOK_REQUEST_TIMEOUT};
 
 // =========================================================================
 // M144: Rate Limit Bucket Cleanup Job
 // =========================================================================
 
-/// Clean up expired rate limit buckets
+/// Clean up expired rate limit buckets (per key and per user)
 /// Removes buckets older than 2 minutes to prevent unbounded growth
 pub async fn cleanup_rate_limit_buckets(pool: &PgPool) -> Result<u64, JobError> {
     let result = sqlx::query(
@@ -24,7 +65,16 @@ pub async fn cleanup_rate_limit_buckets(pool: &PgPool) -> Result<u64, JobError>
     .execute(pool)
     .await?;
 
-    let rows_deleted = result.rows_affected();
+    let user_result = sqlx::query(
+        r#"
+        DELETE FROM user_rate_limit_buckets
+        WHERE window_start < NOW() - INTERVAL '2 minutes'
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let rows_deleted = result.rows_affected() + user_result.rows_affected();
     
     if rows_deleted > 0 {
         tracing::info!(
@@ -94,96 +144,6 @@ pub async fn delete_expired_idempotency_keys(pool: &PgPool) -> Result<u64, JobEr
     Ok(rows_deleted)
 }
 
-// =========================================================================
-// M147: Monthly Partition Creation
-// =========================================================================
-
-/// Create partitions for the next month
-/// Should be run near the end of each month to ensure partitions exist
-pub async fn create_next_month_partitions(pool: &PgPool) -> Result<PartitionResult, JobError> {
-    let now = Utc::now();
-    let next_month = if now.month() == 12 {
-        (now.year() + 1, 1)
-    } else {
-        (now.year(), now.month() + 1)
-    };
-    
-    let month_after = if next_month.1 == 12 {
-        (next_month.0 + 1, 1)
-    } else {
-        (next_month.0, next_month.1 + 1)
-    };
-
-    let partition_suffix = format!("{}_{:02}", next_month.0, next_month.1);
-    let start_date = format!("{}-{:02}-01", next_month.0, next_month.1);
-    let end_date = format!("{}-{:02}-01", month_after.0, month_after.1);
-
-    let mut partitions_created = Vec::new();
-
-    // Create events partition
-    let events_partition = format!("events_{}", partition_suffix);
-    if !partition_exists(pool, &events_partition).await? {
-        let sql = format!(
-            r#"
-            CREATE TABLE IF NOT EXISTS {} PARTITION OF events
-            FOR VALUES FROM ('{}') TO ('{}')
-            "#,
-            events_partition, start_date, end_date
-        );
-        sqlx::query(&sql).execute(pool).await?;
-        partitions_created.push(events_partition.clone());
-        tracing::info!(partition = %events_partition, "Created events partition");
-    }
-
-    // Create ledger_entries partition
-    let ledger_partition = format!("ledger_entries_{}", partition_suffix);
-    if !partition_exists(pool, &ledger_partition).await? {
-        let sql = format!(
-            r#"
-            CREATE TABLE IF NOT EXISTS {} PARTITION OF ledger_entries
-            FOR VALUES FROM ('{}') TO ('{}')
-            "#,
-            ledger_partition, start_date, end_date
-        );
-        sqlx::query(&sql).execute(pool).await?;
-        partitions_created.push(ledger_partition.clone());
-        tracing::info!(partition = %ledger_partition, "Created ledger_entries partition");
-    }
-
-    Ok(PartitionResult {
-        partition_suffix,
-        start_date,
-        end_date,
-        partitions_created,
-    })
-}
-
-/// Check if a partition table already exists
-async fn partition_exists(pool: &PgPool, table_name: &str) -> Result<bool, JobError> {
-    let exists: bool = sqlx::query_scalar(
-        r#"
-        SELECT EXISTS (
-            SELECT 1 FROM information_schema.tables 
-            WHERE table_schema = 'public' AND table_name = $1
-        )
-        "#,
-    )
-    .bind(table_name)
-    .fetch_one(pool)
-    .await?;
-
-    Ok(exists)
-}
-
-/// Result of partition creation
-#[derive(Debug, Clone)]
-pub struct PartitionResult {
-    pub partition_suffix: String,
-    pub start_date: String,
-    pub end_date: String,
-    pub partitions_created: Vec<String>,
-}
-
 // =========================================================================
 // Job Scheduler
 // =========================================================================
@@ -197,6 +157,40 @@ pub struct JobSchedulerConfig {
     pub idempotency_maintenance_interval: Duration,
     /// Interval for partition check (default: 1 hour)
     pub partition_check_interval: Duration,
+    /// Partitioned tables and their retention
+    pub partitions: Vec<PartitionPolicy>,
+    /// Interval for ledger reconciliation (default: 1 hour)
+    pub reconciliation_interval: Duration,
+    /// Interval for webhook dispatch (default: 5 seconds)
+    pub webhook_dispatch_interval: Duration,
+    /// Interval for snapshot maintenance (default: 15 minutes)
+    pub snapshot_maintenance_interval: Duration,
+    /// Interval for the user retention job (default: 1 hour)
+    pub user_retention_interval: Duration,
+    /// Interval for projecting user notifications (default: 5 seconds)
+    pub notification_projection_interval: Duration,
+    /// Interval for materializing completed days into stats_daily (default: 1 hour)
+    pub daily_stats_interval: Duration,
+    /// Interval for incremental audit chain verification (default: 15 minutes)
+    pub audit_verification_interval: Duration,
+    /// Where verified audit chain heads are published (None: not published)
+    pub audit_anchor: Option<AuditAnchor>,
+    /// Interval for audit log archival (default: 1 hour)
+    pub audit_archive_interval: Duration,
+    /// Interval for pruning the job run history (default: 1 hour)
+    pub job_run_cleanup_interval: Duration,
+    /// Interval for expiring transfers pending approval (default: 1 minute)
+    pub pending_transfer_expiry_interval: Duration,
+    /// Interval for digesting completed days of events (default: 1 hour)
+    pub event_integrity_interval: Duration,
+    /// Days before audit log entries are archived (None: never)
+    pub audit_archive_after_days: Option<u32>,
+    /// Directory audit log archives are written to
+    pub audit_archive_dir: PathBuf,
+    /// Days after deactivation before users are anonymized (None: never)
+    pub user_retention_days: Option<u32>,
+    /// SYSTEM_MINT and SYSTEM_BURN users, excluded from daily stats
+    pub system_users: SystemUserIds,
 }
 
 impl Default for JobSchedulerConfig {
@@ -205,6 +199,38 @@ impl Default for JobSchedulerConfig {
             rate_limit_cleanup_interval: Duration::from_secs(60),
             idempotency_maintenance_interval: Duration::from_secs(60),
             partition_check_interval: Duration::from_secs(3600),
+            partitions: default_partition_policies(None),
+            reconciliation_interval: Duration::from_secs(3600),
+            webhook_dispatch_interval: Duration::from_secs(5),
+            snapshot_maintenance_interval: Duration::from_secs(900),
+            user_retention_interval: Duration::from_secs(3600),
+            notification_projection_interval: Duration::from_secs(5),
+            daily_stats_interval: Duration::from_secs(3600),
+            audit_verification_interval: Duration::from_secs(900),
+            audit_anchor: None,
+            audit_archive_interval: Duration::from_secs(3600),
+            job_run_cleanup_interval: Duration::from_secs(3600),
+            pending_transfer_expiry_interval: Duration::from_secs(60),
+            event_integrity_interval: Duration::from_secs(3600),
+            audit_archive_after_days: None,
+            audit_archive_dir: PathBuf::from("audit-archives"),
+            user_retention_days: None,
+            system_users: SystemUserIds::default(),
+        }
+    }
+}
+
+impl JobSchedulerConfig {
+    /// Default intervals with the jobs' settings from the application config
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            user_retention_days: config.user_retention_days,
+            audit_anchor: config.audit_anchor.clone(),
+            audit_archive_after_days: config.audit_archive_after_days,
+            audit_archive_dir: config.audit_archive_dir.clone(),
+            partitions: default_partition_policies(config.audit_log_partition_retention_months),
+            system_users: config.system_users,
+            ..Self::default()
         }
     }
 }
@@ -213,95 +239,399 @@ impl Default for JobSchedulerConfig {
 pub struct JobScheduler {
     pool: PgPool,
     config: JobSchedulerConfig,
+    http: reqwest::Client,
 }
 
 impl JobScheduler {
     /// Create a new job scheduler
     pub fn new(pool: PgPool) -> Self {
-        Self {
-            pool,
-            config: JobSchedulerConfig::default(),
-        }
+        Self::with_config(pool, JobSchedulerConfig::default())
     }
 
     /// Create with custom configuration
     pub fn with_config(pool: PgPool, config: JobSchedulerConfig) -> Self {
-        Self { pool, config }
+        Self {
+            pool,
+            config,
+            http: reqwest::Client::new(),
+        }
     }
 
     /// Start the job scheduler in the background
-    /// Returns a handle that can be used to abort the scheduler
-    pub fn start(self) -> tokio::task::JoinHandle<()> {
-        tokio::spawn(async move {
-            self.run().await;
-        })
+    ///
+    /// The scheduler stops ticking once `shutdown` is cancelled; a job that
+    /// is already running finishes first. The handle resolves to the report
+    /// of everything the scheduler did while it ran.
+    pub fn start(self, shutdown: CancellationToken) -> tokio::task::JoinHandle<MaintenanceReport> {
+        tokio::spawn(async move { self.run(shutdown).await })
     }
 
-    /// Run the scheduler loop
-    async fn run(&self) {
+    /// Run the scheduler loop until `shutdown` is cancelled
+    async fn run(&self, shutdown: CancellationToken) -> MaintenanceReport {
         tracing::info!("Job scheduler started");
 
-        let mut rate_limit_interval = interval(self.config.rate_limit_cleanup_interval);
-        let mut idempotency_interval = interval(self.config.idempotency_maintenance_interval);
-        let mut partition_interval = interval(self.config.partition_check_interval);
-
-        loop {
-            tokio::select! {
-                _ = rate_limit_interval.tick() => {
-                    if let Err(e) = cleanup_rate_limit_buckets(&self.pool).await {
-                        tracing::error!(error = %e, "Rate limit cleanup failed");
-                    }
-                }
-                _ = idempotency_interval.tick() => {
-                    if let Err(e) = reset_stale_idempotency_keys(&self.pool).await {
-                        tracing::error!(error = %e, "Idempotency key reset failed");
-                    }
-                    if let Err(e) = delete_expired_idempotency_keys(&self.pool).await {
-                        tracing::error!(error = %e, "Idempotency key deletion failed");
-                    }
-                }
-                _ = partition_interval.tick() => {
-                    if should_create_partitions() {
-                        if let Err(e) = create_next_month_partitions(&self.pool).await {
-                            tracing::error!(error = %e, "Partition creation failed");
-                        }
-                    }
-                }
+        let mut report = MaintenanceReport::default();
+        let mut rate_limit_interval = interval(self.interval_of(Job::RateLimitCleanup));
+        let mut idempotency_interval = interval(self.interval_of(Job::IdempotencyMaintenance));
+        let mut partition_interval = interval(self.interval_of(Job::PartitionCheck));
+        let mut reconciliation_interval = interval(self.interval_of(Job::Reconciliation));
+        let mut webhook_interval = interval(self.interval_of(Job::WebhookDispatch));
+        let mut snapshot_interval = interval(self.interval_of(Job::SnapshotMaintenance));
+        let mut retention_interval = interval(self.interval_of(Job::UserRetention));
+        let mut notification_interval = interval(self.interval_of(Job::NotificationProjection));
+        let mut stats_interval = interval(self.interval_of(Job::DailyStats));
+        let mut audit_interval = interval(self.interval_of(Job::AuditVerification));
+        let mut audit_archive_interval = interval(self.interval_of(Job::AuditArchive));
+        let mut job_run_cleanup_interval = interval(self.interval_of(Job::JobRunCleanup));
+        let mut pending_transfer_interval = interval(self.interval_of(Job::PendingTransferExpiry));
+        let mut event_integrity_interval = interval(self.interval_of(Job::EventIntegrity));
+
+        // Cancellation is only observed between jobs and never interrupts one
+        while !shutdown.is_cancelled() {
+            let job = tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = rate_limit_interval.tick() => Job::RateLimitCleanup,
+                _ = idempotency_interval.tick() => Job::IdempotencyMaintenance,
+                _ = partition_interval.tick() => Job::PartitionCheck,
+                _ = reconciliation_interval.tick() => Job::Reconciliation,
+                _ = webhook_interval.tick() => Job::WebhookDispatch,
+                _ = snapshot_interval.tick() => Job::SnapshotMaintenance,
+                _ = retention_interval.tick() => Job::UserRetention,
+                _ = notification_interval.tick() => Job::NotificationProjection,
+                _ = stats_interval.tick() => Job::DailyStats,
+                _ = audit_interval.tick() => Job::AuditVerification,
+                _ = audit_archive_interval.tick() => Job::AuditArchive,
+                _ = job_run_cleanup_interval.tick() => Job::JobRunCleanup,
+                _ = pending_transfer_interval.tick() => Job::PendingTransferExpiry,
+                _ = event_integrity_interval.tick() => Job::EventIntegrity,
+            };
+            if let Err(e) = self.run_job(job, JobTrigger::Scheduled, &mut report).await {
+                tracing::warn!(job = job.as_str(), error = %e, "Failed to record job run");
             }
         }
+
+        tracing::info!("Job scheduler stopped");
+        report.completed_at = Utc::now();
+        report
     }
 
     /// Run all maintenance jobs once (for manual trigger or testing)
     pub async fn run_all_once(&self) -> MaintenanceReport {
         let mut report = MaintenanceReport::default();
+        for job in Job::ALL {
+            if let Err(e) = self.run_job(job, JobTrigger::Manual(None), &mut report).await {
+                tracing::warn!(job = job.as_str(), error = %e, "Failed to record job run");
+            }
+        }
+        report.completed_at = Utc::now();
+        report
+    }
+
+    /// Run one job now on behalf of `api_key_id` and return the recorded run
+    ///
+    /// Jobs that are not due yet (the partition check) run anyway; disabled
+    /// jobs fail with `JobError::Disabled`.
+    pub async fn run_manually(&self, job: Job, api_key_id: Option<Uuid>) -> Result<JobRun, JobError> {
+        if !self.is_enabled(job) {
+            return Err(JobError::Disabled(job.as_str()));
+        }
+        let mut report = MaintenanceReport::default();
+        self.run_job(job, JobTrigger::Manual(api_key_id), &mut report)
+            .await?
+            .ok_or(JobError::Disabled(job.as_str()))
+    }
+
+    /// Every job with its schedule and latest run
+    pub async fn job_statuses(&self) -> Result<Vec<JobStatus>, JobError> {
+        let mut last_runs = last_job_runs(&self.pool).await?;
+        Ok(Job::ALL
+            .into_iter()
+            .map(|job| JobStatus {
+                name: job.as_str(),
+                interval_secs: self.interval_of(job).as_secs(),
+                enabled: self.is_enabled(job),
+                last_run: last_runs
+                    .iter()
+                    .position(|run| run.job_name == job.as_str())
+                    .map(|i| last_runs.swap_remove(i)),
+            })
+            .collect())
+    }
 
-        match cleanup_rate_limit_buckets(&self.pool).await {
-            Ok(count) => report.rate_limit_buckets_cleaned = count,
-            Err(e) => report.errors.push(format!("Rate limit cleanup: {}", e)),
+    /// How often the scheduler runs `job`
+    pub fn interval_of(&self, job: Job) -> Duration {
+        match job {
+            Job::RateLimitCleanup => self.config.rate_limit_cleanup_interval,
+            Job::IdempotencyMaintenance => self.config.idempotency_maintenance_interval,
+            Job::PartitionCheck => self.config.partition_check_interval,
+            Job::Reconciliation => self.config.reconciliation_interval,
+            Job::WebhookDispatch => self.config.webhook_dispatch_interval,
+            Job::SnapshotMaintenance => self.config.snapshot_maintenance_interval,
+            Job::UserRetention => self.config.user_retention_interval,
+            Job::NotificationProjection => self.config.notification_projection_interval,
+            Job::DailyStats => self.config.daily_stats_interval,
+            Job::AuditVerification => self.config.audit_verification_interval,
+            Job::AuditArchive => self.config.audit_archive_interval,
+            Job::JobRunCleanup => self.config.job_run_cleanup_interval,
+            Job::PendingTransferExpiry => self.config.pending_transfer_expiry_interval,
+            Job::EventIntegrity => self.config.event_integrity_interval,
         }
+    }
 
-        match reset_stale_idempotency_keys(&self.pool).await {
-            Ok(count) => report.idempotency_keys_reset = count,
-            Err(e) => report.errors.push(format!("Idempotency reset: {}", e)),
+    /// Whether `job` is configured to do anything
+    pub fn is_enabled(&self, job: Job) -> bool {
+        match job {
+            Job::UserRetention => self.config.user_retention_days.is_some(),
+            Job::AuditArchive => self.config.audit_archive_after_days.is_some(),
+            _ => true,
         }
+    }
 
-        match delete_expired_idempotency_keys(&self.pool).await {
-            Ok(count) => report.idempotency_keys_deleted = count,
-            Err(e) => report.errors.push(format!("Idempotency deletion: {}", e)),
+    /// Run one job, add its results (or error) to `report` and record the
+    /// run. None when the job is disabled or, for scheduled runs, not due.
+    async fn run_job(
+        &self,
+        job: Job,
+        trigger: JobTrigger,
+        report: &mut MaintenanceReport,
+    ) -> Result<Option<JobRun>, JobError> {
+        let started_at = Utc::now();
+        let Some(outcome) = self.execute(job, trigger, report).await else {
+            return Ok(None);
+        };
+        if let Some(error) = &outcome.error {
+            report.record_error(job.as_str(), error);
         }
 
-        if should_create_partitions() {
-            match create_next_month_partitions(&self.pool).await {
-                Ok(result) => report.partitions_created = result.partitions_created,
-                Err(e) => report.errors.push(format!("Partition creation: {}", e)),
+        runs::record_job_run(&self.pool, job, trigger, started_at, outcome.rows_affected, outcome.error.as_deref())
+            .await
+            .map(Some)
+    }
+
+    /// Execute `job`, adding its results to `report`
+    async fn execute(&self, job: Job, trigger: JobTrigger, report: &mut MaintenanceReport) -> Option<JobOutcome> {
+        let outcome = match job {
+            Job::RateLimitCleanup => JobOutcome::from(
+                cleanup_rate_limit_buckets(&self.pool)
+                    .await
+                    .inspect(|count| report.rate_limit_buckets_cleaned += count),
+            ),
+            Job::IdempotencyMaintenance => {
+                let reset = reset_stale_idempotency_keys(&self.pool)
+                    .await
+                    .inspect(|count| report.idempotency_keys_reset += count);
+                let deleted = delete_expired_idempotency_keys(&self.pool)
+                    .await
+                    .inspect(|count| report.idempotency_keys_deleted += count);
+                JobOutcome::from(reset).and(JobOutcome::from(deleted))
             }
+            Job::PartitionCheck => {
+                // Next month's partitions are created near the end of the month
+                // (or whenever the job is run manually)
+                let create_next_month = trigger != JobTrigger::Scheduled || should_create_partitions();
+                JobOutcome::from(
+                    maintain_partitions(&self.pool, &self.config.partitions, create_next_month)
+                        .await
+                        .map(|result| {
+                            let changed = (result.partitions_created.len() + result.partitions_dropped.len()) as u64;
+                            report.partitions_created.extend(result.partitions_created);
+                            report.partitions_dropped.extend(result.partitions_dropped);
+                            changed
+                        }),
+                )
+            }
+            Job::Reconciliation => JobOutcome::from(reconcile_ledger(&self.pool).await.map(|result| {
+                report.reconciliation_discrepancies += result.discrepancies.len();
+                result.discrepancies.len() as u64
+            })),
+            Job::WebhookDispatch => JobOutcome::from(
+                dispatch_webhooks(&self.pool, &self.http)
+                    .await
+                    .inspect(|count| report.webhook_attempts += count),
+            ),
+            Job::SnapshotMaintenance => JobOutcome::from(maintain_snapshots(&self.pool).await.map(|result| {
+                report.snapshots_created += result.snapshots_created;
+                report.snapshots_pruned += result.snapshots_pruned;
+                result.snapshots_created + result.snapshots_pruned
+            })),
+            Job::UserRetention => {
+                let days = self.config.user_retention_days?;
+                JobOutcome::from(
+                    anonymize_expired_users(&self.pool, days)
+                        .await
+                        .inspect(|count| report.users_anonymized += count),
+                )
+            }
+            Job::NotificationProjection => JobOutcome::from(
+                project_notifications(&self.pool)
+                    .await
+                    .inspect(|count| report.notification_events_processed += count),
+            ),
+            Job::DailyStats => JobOutcome::from(
+                materialize_pending_daily_stats(&self.pool, self.config.system_users)
+                    .await
+                    .inspect(|count| report.daily_stats_days += count),
+            ),
+            Job::AuditVerification => {
+                match verify_audit_chain(&self.pool, &self.http, self.config.audit_anchor.as_ref()).await {
+                    Ok(Some(checkpoint)) => {
+                        let verified = checkpoint.entries_verified as u64;
+                        report.audit_entries_verified += verified;
+                        JobOutcome {
+                            rows_affected: verified,
+                            error: (!checkpoint.is_valid)
+                                .then(|| format!("invalid entry after sequence {}", checkpoint.sequence_number)),
+                        }
+                    }
+                    Ok(None) => JobOutcome {
+                        rows_affected: 0,
+                        error: None,
+                    },
+                    Err(e) => JobOutcome {
+                        rows_affected: 0,
+                        error: Some(e.to_string()),
+                    },
+                }
+            }
+            Job::AuditArchive => {
+                let days = self.config.audit_archive_after_days?;
+                JobOutcome::from(
+                    archive_audit_logs(&self.pool, &self.config.audit_archive_dir, days)
+                        .await
+                        .map(|archive| archive.map_or(0, |a| a.entry_count as u64))
+                        .inspect(|count| report.audit_entries_archived += count),
+                )
+            }
+            Job::JobRunCleanup => JobOutcome::from(prune_job_runs(&self.pool).await),
+            Job::PendingTransferExpiry => JobOutcome::from(
+                expire_pending_transfers(&self.pool)
+                    .await
+                    .inspect(|count| report.pending_transfers_expired += count),
+            ),
+            Job::EventIntegrity => {
+                let digested = JobOutcome::from(
+                    digest_pending_event_days(&self.pool)
+                        .await
+                        .inspect(|count| report.event_days_digested += count),
+                );
+                // A disabled guard is reported on every run until fixed
+                let guard = match event_guard_enabled(&self.pool).await {
+                    Ok(true) => None,
+                    Ok(false) => Some("no_modify_events is not enabled on every events partition".to_string()),
+                    Err(e) => Some(e.to_string()),
+                };
+                digested.and(JobOutcome {
+                    rows_affected: 0,
+                    error: guard,
+                })
+            }
+        };
+        Some(outcome)
+    }
+}
+
+/// Result of one job execution
+struct JobOutcome {
+    rows_affected: u64,
+    error: Option<String>,
+}
+
+impl JobOutcome {
+    /// Combine the outcomes of a job's steps (rows added, first error kept)
+    fn and(self, other: JobOutcome) -> JobOutcome {
+        JobOutcome {
+            rows_affected: self.rows_affected + other.rows_affected,
+            error: self.error.or(other.error),
         }
+    }
+}
 
-        report.completed_at = Utc::now();
-        report
+impl<E: std::fmt::Display> From<Result<u64, E>> for JobOutcome {
+    fn from(result: Result<u64, E>) -> Self {
+        match result {
+            Ok(rows_affected) => JobOutcome { rows_affected, error: None },
+            Err(e) => JobOutcome {
+                rows_affected: 0,
+                error: Some(e.to_string()),
+            },
+        }
     }
 }
 
+/// Maintenance jobs run by the scheduler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Job {
+    RateLimitCleanup,
+    IdempotencyMaintenance,
+    PartitionCheck,
+    Reconciliation,
+    WebhookDispatch,
+    SnapshotMaintenance,
+    UserRetention,
+    NotificationProjection,
+    DailyStats,
+    AuditVerification,
+    AuditArchive,
+    JobRunCleanup,
+    PendingTransferExpiry,
+    EventIntegrity,
+}
+
+impl Job {
+    /// Every job, in the order run_all_once runs them
+    pub const ALL: [Job; 14] = [
+        Job::RateLimitCleanup,
+        Job::IdempotencyMaintenance,
+        Job::PartitionCheck,
+        Job::Reconciliation,
+        Job::WebhookDispatch,
+        Job::SnapshotMaintenance,
+        Job::UserRetention,
+        Job::NotificationProjection,
+        Job::DailyStats,
+        Job::AuditVerification,
+        Job::AuditArchive,
+        Job::JobRunCleanup,
+        Job::PendingTransferExpiry,
+        Job::EventIntegrity,
+    ];
+
+    /// Name used in job_runs and the admin API
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Job::RateLimitCleanup => "rate_limit_cleanup",
+            Job::IdempotencyMaintenance => "idempotency_maintenance",
+            Job::PartitionCheck => "partition_check",
+            Job::Reconciliation => "reconciliation",
+            Job::WebhookDispatch => "webhook_dispatch",
+            Job::SnapshotMaintenance => "snapshot_maintenance",
+            Job::UserRetention => "user_retention",
+            Job::NotificationProjection => "notification_projection",
+            Job::DailyStats => "daily_stats",
+            Job::AuditVerification => "audit_verification",
+            Job::AuditArchive => "audit_archive",
+            Job::JobRunCleanup => "job_run_cleanup",
+            Job::PendingTransferExpiry => "pending_transfer_expiry",
+            Job::EventIntegrity => "event_integrity",
+        }
+    }
+
+    /// Job with the given name
+    pub fn from_name(name: &str) -> Option<Job> {
+        Job::ALL.into_iter().find(|job| job.as_str() == name)
+    }
+}
+
+/// A job's schedule and latest run
+#[derive(Debug, Clone, Serialize)]
+pub struct JobStatus {
+    pub name: &'static str,
+    pub interval_secs: u64,
+    /// False when the job is not configured (user retention, audit archival)
+    pub enabled: bool,
+    pub last_run: Option<JobRun>,
+}
+
 /// Check if we should create partitions (last 3 days of month)
 fn should_create_partitions() -> bool {
     let now = Utc::now();
@@ -321,6 +651,9 @@ fn days_in_month(year: i32, month: u32) -> u32 {
     .num_days() as u32
 }
 
+/// Most errors kept by a long-running scheduler's report
+const MAX_REPORT_ERRORS: usize = 100;
+
 /// Report from running maintenance jobs
 #[derive(Debug, Clone, Default)]
 pub struct MaintenanceReport {
@@ -328,15 +661,52 @@ pub struct MaintenanceReport {
     pub idempotency_keys_reset: u64,
     pub idempotency_keys_deleted: u64,
     pub partitions_created: Vec<String>,
+    pub partitions_dropped: Vec<String>,
+    pub reconciliation_discrepancies: usize,
+    pub webhook_attempts: u64,
+    pub snapshots_created: u64,
+    pub snapshots_pruned: u64,
+    pub users_anonymized: u64,
+    pub notification_events_processed: u64,
+    pub daily_stats_days: u64,
+    pub audit_entries_verified: u64,
+    pub audit_entries_archived: u64,
+    pub pending_transfers_expired: u64,
+    pub event_days_digested: u64,
     pub errors: Vec<String>,
     pub completed_at: DateTime<Utc>,
 }
 
+impl MaintenanceReport {
+    /// Log a job failure and keep it in the report (up to MAX_REPORT_ERRORS)
+    fn record_error(&mut self, job: &str, error: impl std::fmt::Display) {
+        tracing::error!(job, error = %error, "Maintenance job failed");
+        if self.errors.len() < MAX_REPORT_ERRORS {
+            self.errors.push(format!("{}: {}", job, error));
+        }
+    }
+}
+
 /// Job execution errors
 #[derive(Debug, thiserror::Error)]
 pub enum JobError {
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Audit log error: {0}")]
+    Audit(#[from] AuditLogError),
+
+    #[error("Audit log entries {0}..={1} failed hash chain verification")]
+    AuditChainInvalid(i64, i64),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Job {0} is disabled")]
+    Disabled(&'static str),
 }
 
 // =========================================================================
@@ -365,6 +735,18 @@ mod tests {
         assert_eq!(config.rate_limit_cleanup_interval, Duration::from_secs(60));
         assert_eq!(config.idempotency_maintenance_interval, Duration::from_secs(60));
         assert_eq!(config.partition_check_interval, Duration::from_secs(3600));
+        assert_eq!(config.reconciliation_interval, Duration::from_secs(3600));
+        assert_eq!(config.webhook_dispatch_interval, Duration::from_secs(5));
+        assert_eq!(config.notification_projection_interval, Duration::from_secs(5));
+        assert_eq!(config.daily_stats_interval, Duration::from_secs(3600));
+        assert_eq!(config.audit_verification_interval, Duration::from_secs(900));
+        assert_eq!(config.pending_transfer_expiry_interval, Duration::from_secs(60));
+        assert_eq!(config.event_integrity_interval, Duration::from_secs(3600));
+        assert_eq!(config.audit_anchor, None);
+        assert_eq!(config.audit_archive_after_days, None);
+        assert_eq!(config.user_retention_days, None);
+        assert_eq!(config.system_users.mint.to_string(), "00000000-0000-0000-0000-000000000001");
+        assert_eq!(config.system_users.burn.to_string(), "00000000-0000-0000-0000-000000000002");
     }
 
     #[test]
@@ -374,6 +756,7 @@ mod tests {
             start_date: "2026-02-01".to_string(),
             end_date: "2026-03-01".to_string(),
             partitions_created: vec!["events_2026_02".to_string()],
+            partitions_dropped: vec![],
         };
 
         assert_eq!(result.partitions_created.len(), 1);
@@ -385,4 +768,38 @@ mod tests {
         assert_eq!(report.rate_limit_buckets_cleaned, 0);
         assert_eq!(report.errors.len(), 0);
     }
+
+    #[test]
+    fn test_job_names_round_trip() {
+        for job in Job::ALL {
+            assert_eq!(Job::from_name(job.as_str()), Some(job));
+        }
+        assert_eq!(Job::from_name("webhook_dispatch"), Some(Job::WebhookDispatch));
+        assert_eq!(Job::from_name("WebhookDispatch"), None);
+    }
+
+    #[test]
+    fn test_maintenance_report_caps_errors() {
+        let mut report = MaintenanceReport::default();
+        for i in 0..MAX_REPORT_ERRORS + 5 {
+            report.record_error("webhook_dispatch", i);
+        }
+        assert_eq!(report.errors.len(), MAX_REPORT_ERRORS);
+        assert_eq!(report.errors[0], "webhook_dispatch: 0");
+    }
+
+    #[tokio::test]
+    async fn test_scheduler_stops_when_cancelled() {
+        // Never connects: no job runs once shutdown is requested
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+
+        let report = tokio::time::timeout(Duration::from_secs(1), JobScheduler::new(pool).start(shutdown))
+            .await
+            .expect("scheduler did not stop")
+            .unwrap();
+        assert!(report.errors.is_empty());
+        assert_eq!(report.webhook_attempts, 0);
+    }
 }
@@ -0,0 +1,324 @@
+//! Partition Maintenance
+//!
+//! Table-driven management of the monthly range partitions (`<table>_YYYY_MM`)
+//! of events, ledger_entries and audit_logs. Each run creates any partition
+//! missing between the oldest month still needed and the current month (next
+//! month too near the end of the month) and detaches and drops partitions
+//! older than the table's retention window.
+
+use chrono::{Datelike, Utc};
+use sqlx::PgPool;
+
+use super::JobError;
+
+/// How the partitions of one table are maintained
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartitionPolicy {
+    /// Parent table, partitioned by month on created_at
+    pub table: &'static str,
+    /// Months partitions are kept after their month ends (None: forever)
+    pub retention_months: Option<u32>,
+    /// Only drop partitions without rows (their data is removed by another
+    /// job, e.g. audit archival)
+    pub require_empty: bool,
+}
+
+/// Default policies: events and ledger_entries are the system of record and
+/// are never dropped; audit_logs partitions are dropped once archival has
+/// emptied them and they are older than `audit_log_retention_months`
+pub fn default_partition_policies(audit_log_retention_months: Option<u32>) -> Vec<PartitionPolicy> {
+    vec![
+        PartitionPolicy {
+            table: "events",
+            retention_months: None,
+            require_empty: true,
+        },
+        PartitionPolicy {
+            table: "ledger_entries",
+            retention_months: None,
+            require_empty: true,
+        },
+        PartitionPolicy {
+            table: "audit_logs",
+            retention_months: audit_log_retention_months,
+            require_empty: true,
+        },
+    ]
+}
+
+/// Result of partition maintenance
+#[derive(Debug, Clone)]
+pub struct PartitionResult {
+    /// Suffix of next month's partitions
+    pub partition_suffix: String,
+    pub start_date: String,
+    pub end_date: String,
+    pub partitions_created: Vec<String>,
+    pub partitions_dropped: Vec<String>,
+}
+
+/// Create missing partitions (next month's too when `create_next_month`)
+/// and drop expired ones for every policy
+pub async fn maintain_partitions(
+    pool: &PgPool,
+    policies: &[PartitionPolicy],
+    create_next_month: bool,
+) -> Result<PartitionResult, JobError> {
+    let now = Utc::now();
+    let current = (now.year(), now.month());
+    let next = next_month(current);
+
+    let mut partitions_created = Vec::new();
+    let mut partitions_dropped = Vec::new();
+
+    let last = if create_next_month { next } else { current };
+    for policy in policies {
+        partitions_created.extend(backfill(pool, policy, current, last).await?);
+
+        if let Some(retention_months) = policy.retention_months {
+            for (partition, month) in list_partitions(pool, policy.table).await? {
+                if is_expired(month, current, retention_months)
+                    && drop_partition(pool, policy, &partition).await?
+                {
+                    partitions_dropped.push(partition);
+                }
+            }
+        }
+    }
+
+    let (start_date, end_date) = month_bounds(next);
+    Ok(PartitionResult {
+        partition_suffix: partition_suffix(next),
+        start_date,
+        end_date,
+        partitions_created,
+        partitions_dropped,
+    })
+}
+
+/// Create every missing partition from the oldest month still needed through
+/// next month, e.g. after the service was down across a month boundary
+pub async fn backfill_partitions(pool: &PgPool, policies: &[PartitionPolicy]) -> Result<Vec<String>, JobError> {
+    let now = Utc::now();
+    let current = (now.year(), now.month());
+
+    let mut created = Vec::new();
+    for policy in policies {
+        created.extend(backfill(pool, policy, current, next_month(current)).await?);
+    }
+    Ok(created)
+}
+
+/// Create the missing partitions of `policy.table` through `last`, starting
+/// at its oldest partition (or the oldest month its retention keeps)
+async fn backfill(
+    pool: &PgPool,
+    policy: &PartitionPolicy,
+    current: (i32, u32),
+    last: (i32, u32),
+) -> Result<Vec<String>, JobError> {
+    let existing: Vec<(i32, u32)> = list_partitions(pool, policy.table)
+        .await?
+        .into_iter()
+        .map(|(_, month)| month)
+        .collect();
+
+    let mut month = first_needed_month(&existing, current, policy.retention_months);
+    let mut created = Vec::new();
+    while month_index(month) <= month_index(last) {
+        if !existing.contains(&month) {
+            created.extend(create_partition(pool, policy.table, month).await?);
+        }
+        month = next_month(month);
+    }
+    Ok(created)
+}
+
+/// Create the partition of `table` for `month` unless it exists
+async fn create_partition(pool: &PgPool, table: &str, month: (i32, u32)) -> Result<Option<String>, JobError> {
+    let partition = format!("{}_{}", table, partition_suffix(month));
+    if partition_exists(pool, &partition).await? {
+        return Ok(None);
+    }
+
+    let (start_date, end_date) = month_bounds(month);
+    let sql = format!(
+        r#"
+        CREATE TABLE IF NOT EXISTS {} PARTITION OF {}
+        FOR VALUES FROM ('{}') TO ('{}')
+        "#,
+        partition, table, start_date, end_date
+    );
+    sqlx::query(&sql).execute(pool).await?;
+    tracing::info!(table, partition = %partition, "Created partition");
+
+    Ok(Some(partition))
+}
+
+/// Detach and drop `partition`. Returns false (and keeps it) when the
+/// policy requires it to be empty and it is not.
+async fn drop_partition(pool: &PgPool, policy: &PartitionPolicy, partition: &str) -> Result<bool, JobError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(&format!("ALTER TABLE {} DETACH PARTITION {}", policy.table, partition))
+        .execute(&mut *tx)
+        .await?;
+
+    if policy.require_empty {
+        let has_rows: bool = sqlx::query_scalar(&format!("SELECT EXISTS (SELECT 1 FROM {})", partition))
+            .fetch_one(&mut *tx)
+            .await?;
+        if has_rows {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+    }
+
+    sqlx::query(&format!("DROP TABLE {}", partition)).execute(&mut *tx).await?;
+    tx.commit().await?;
+
+    tracing::info!(table = policy.table, partition, "Dropped expired partition");
+    Ok(true)
+}
+
+/// Monthly partitions of `table` with the month they hold
+async fn list_partitions(pool: &PgPool, table: &str) -> Result<Vec<(String, (i32, u32))>, JobError> {
+    let names: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT c.relname::text
+        FROM pg_inherits i
+        JOIN pg_class c ON c.oid = i.inhrelid
+        WHERE i.inhparent = $1::regclass
+        ORDER BY c.relname
+        "#,
+    )
+    .bind(table)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(names
+        .into_iter()
+        .filter_map(|name| partition_month(table, &name).map(|month| (name, month)))
+        .collect())
+}
+
+/// Check if a partition table already exists
+async fn partition_exists(pool: &PgPool, table_name: &str) -> Result<bool, JobError> {
+    let exists: bool = sqlx::query_scalar(
+        r#"
+        SELECT EXISTS (
+            SELECT 1 FROM information_schema.tables
+            WHERE table_schema = 'public' AND table_name = $1
+        )
+        "#,
+    )
+    .bind(table_name)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+/// Month held by `partition` of `table` (`<table>_YYYY_MM`)
+fn partition_month(table: &str, partition: &str) -> Option<(i32, u32)> {
+    let suffix = partition.strip_prefix(table)?.strip_prefix('_')?;
+    let (year, month) = suffix.split_once('_')?;
+    if year.len() != 4 || month.len() != 2 {
+        return None;
+    }
+    let (year, month) = (year.parse().ok()?, month.parse().ok()?);
+    (1..=12).contains(&month).then_some((year, month))
+}
+
+/// Whether `month` ended more than `retention_months` months before `current`
+fn is_expired(month: (i32, u32), current: (i32, u32), retention_months: u32) -> bool {
+    month_index(month) < month_index(current) - i64::from(retention_months)
+}
+
+/// Oldest month that must have a partition: the oldest existing one, but
+/// never one retention would drop again, and never later than `current`
+fn first_needed_month(existing: &[(i32, u32)], current: (i32, u32), retention_months: Option<u32>) -> (i32, u32) {
+    let oldest_kept = retention_months.map_or(i64::MIN, |months| month_index(current) - i64::from(months));
+    let first = existing
+        .iter()
+        .map(|&month| month_index(month))
+        .min()
+        .unwrap_or(i64::MAX)
+        .max(oldest_kept)
+        .min(month_index(current));
+    from_month_index(first)
+}
+
+/// Months since year 0
+fn month_index((year, month): (i32, u32)) -> i64 {
+    i64::from(year) * 12 + i64::from(month) - 1
+}
+
+fn from_month_index(index: i64) -> (i32, u32) {
+    (index.div_euclid(12) as i32, index.rem_euclid(12) as u32 + 1)
+}
+
+fn next_month((year, month): (i32, u32)) -> (i32, u32) {
+    if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    }
+}
+
+fn partition_suffix((year, month): (i32, u32)) -> String {
+    format!("{}_{:02}", year, month)
+}
+
+/// First day of `month` and of the month after it
+fn month_bounds(month: (i32, u32)) -> (String, String) {
+    let end = next_month(month);
+    (
+        format!("{}-{:02}-01", month.0, month.1),
+        format!("{}-{:02}-01", end.0, end.1),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partition_month() {
+        assert_eq!(partition_month("audit_logs", "audit_logs_2026_03"), Some((2026, 3)));
+        assert_eq!(partition_month("events", "events_2025_12"), Some((2025, 12)));
+        assert_eq!(partition_month("events", "events_default"), None);
+        assert_eq!(partition_month("events", "events_2026_13"), None);
+        assert_eq!(partition_month("events", "ledger_entries_2026_01"), None);
+    }
+
+    #[test]
+    fn test_is_expired() {
+        // Kept for 3 months after it ends
+        assert!(!is_expired((2026, 7), (2026, 10), 3));
+        assert!(is_expired((2026, 6), (2026, 10), 3));
+        assert!(is_expired((2025, 12), (2026, 2), 1));
+        assert!(!is_expired((2026, 10), (2026, 10), 0));
+        assert!(is_expired((2026, 9), (2026, 10), 0));
+    }
+
+    #[test]
+    fn test_first_needed_month() {
+        let current = (2026, 10);
+        // Gap after the oldest partition is filled from there
+        assert_eq!(first_needed_month(&[(2026, 8), (2026, 1)], current, None), (2026, 1));
+        // No partitions yet: start with the current month
+        assert_eq!(first_needed_month(&[], current, None), current);
+        // Months retention would drop again are skipped
+        assert_eq!(first_needed_month(&[(2025, 11)], current, Some(3)), (2026, 7));
+        // Partitions only ahead of the current month
+        assert_eq!(first_needed_month(&[(2026, 11)], current, None), current);
+        assert_eq!(from_month_index(month_index((2026, 12))), (2026, 12));
+    }
+
+    #[test]
+    fn test_month_bounds() {
+        assert_eq!(month_bounds((2026, 12)), ("2026-12-01".to_string(), "2027-01-01".to_string()));
+        assert_eq!(partition_suffix(next_month((2026, 12))), "2027_01");
+    }
+}
@@ -0,0 +1,52 @@
+//! Pending Transfer Expiry Job
+//!
+//! Fails transfers that waited for approval past their expiry
+//! (PENDING_TRANSFER_TTL_HOURS). Each transfer goes through
+//! TransferApprovalHandler, so the TransferFailed event (reason expired),
+//! failed_transfers row, audit entry and webhook are recorded as for any
+//! other decision.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::domain::OperationContext;
+use crate::handlers::TransferApprovalHandler;
+
+/// Transfers expired per run
+const EXPIRY_BATCH_SIZE: i64 = 100;
+
+/// Fail transfers still pending approval after their expiry.
+/// Returns the number of transfers expired.
+pub async fn expire_pending_transfers(pool: &PgPool) -> Result<u64, JobError> {
+    let transfer_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT transfer_id
+        FROM pending_transfers
+        WHERE status = 'pending_approval'
+          AND expires_at <= NOW()
+        ORDER BY expires_at
+        LIMIT $1
+        "#,
+    )
+    .bind(EXPIRY_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let handler = TransferApprovalHandler::new(pool.clone());
+    let context = OperationContext::new();
+    let mut expired = 0;
+
+    for transfer_id in transfer_ids {
+        match handler.expire(transfer_id.into(), &context).await {
+            Ok(_) => expired += 1,
+            Err(e) => tracing::warn!(transfer_id = %transfer_id, error = %e, "Failed to expire pending transfer"),
+        }
+    }
+
+    if expired > 0 {
+        tracing::info!(expired, "Expired pending transfers");
+    }
+
+    Ok(expired)
+}
@@ -0,0 +1,306 @@
+//! Ledger Reconciliation Job
+//!
+//! Checks the invariants that tie the event store, the double-entry ledger
+//! and the balance projection together, and records the outcome of each run
+//! in reconciliation_reports.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::aggregate::{Account, AccountInvariantViolation};
+use crate::event_store::EventStore;
+
+/// Kind of invariant violation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiscrepancyKind {
+    /// sum(debits) != sum(credits) for a journal
+    JournalImbalance,
+    /// account_balances.balance != sum of the account's ledger entries
+    LedgerBalanceMismatch,
+    /// account_balances.balance != balance replayed from Account events
+    EventBalanceMismatch,
+    /// Replayed Account aggregate fails Account::check_invariants
+    AccountInvariantViolation,
+}
+
+/// A single invariant violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discrepancy {
+    pub kind: DiscrepancyKind,
+    /// journal_id or account_id, depending on kind
+    pub subject_id: Uuid,
+    pub expected: Decimal,
+    pub actual: Decimal,
+}
+
+/// Result of a reconciliation run
+#[derive(Debug, Clone, Serialize)]
+pub struct ReconciliationReport {
+    pub id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: DateTime<Utc>,
+    pub journals_checked: i64,
+    pub accounts_checked: i64,
+    pub discrepancies: Vec<Discrepancy>,
+}
+
+impl ReconciliationReport {
+    pub fn is_clean(&self) -> bool {
+        self.discrepancies.is_empty()
+    }
+}
+
+/// Verify ledger invariants and store the result in reconciliation_reports.
+///
+/// All checks read from a single REPEATABLE READ snapshot. Projections are
+/// updated after events are committed, so a transfer in flight at snapshot
+/// time can show up as a transient EventBalanceMismatch.
+pub async fn reconcile_ledger(pool: &PgPool) -> Result<ReconciliationReport, JobError> {
+    let started_at = Utc::now();
+    let mut discrepancies = Vec::new();
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ READ ONLY")
+        .execute(&mut *tx)
+        .await?;
+
+    // 1. Every journal must balance
+    let journals_checked: i64 = sqlx::query_scalar("SELECT COUNT(DISTINCT journal_id) FROM ledger_entries")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let unbalanced: Vec<(Uuid, Decimal, Decimal)> = sqlx::query_as(
+        r#"
+        SELECT journal_id,
+               COALESCE(SUM(amount) FILTER (WHERE entry_type = 'debit'), 0),
+               COALESCE(SUM(amount) FILTER (WHERE entry_type = 'credit'), 0)
+        FROM ledger_entries
+        GROUP BY journal_id
+        HAVING COALESCE(SUM(amount) FILTER (WHERE entry_type = 'debit'), 0)
+            <> COALESCE(SUM(amount) FILTER (WHERE entry_type = 'credit'), 0)
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    discrepancies.extend(unbalanced.into_iter().map(|(journal_id, debits, credits)| Discrepancy {
+        kind: DiscrepancyKind::JournalImbalance,
+        subject_id: journal_id,
+        expected: debits,
+        actual: credits,
+    }));
+
+    // 2. Projected balances must match the ledger
+    let accounts_checked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM account_balances")
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let ledger_mismatches: Vec<(Uuid, Decimal, Decimal)> = sqlx::query_as(
+        r#"
+        SELECT ab.account_id, COALESCE(le.net, 0), ab.balance
+        FROM account_balances ab
+        LEFT JOIN (
+            SELECT account_id,
+                   SUM(CASE WHEN entry_type = 'credit' THEN amount ELSE -amount END) AS net
+            FROM ledger_entries
+            GROUP BY account_id
+        ) le ON le.account_id = ab.account_id
+        WHERE ab.balance <> COALESCE(le.net, 0)
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    discrepancies.extend(ledger_mismatches.into_iter().map(|(account_id, expected, actual)| Discrepancy {
+        kind: DiscrepancyKind::LedgerBalanceMismatch,
+        subject_id: account_id,
+        expected,
+        actual,
+    }));
+
+    // 3. Projected balances must match the event stream
+    let event_mismatches: Vec<(Uuid, Decimal, Decimal)> = sqlx::query_as(
+        r#"
+        SELECT ab.account_id, COALESCE(ev.net, 0), ab.balance
+        FROM account_balances ab
+        LEFT JOIN (
+            SELECT aggregate_id AS account_id,
+                   SUM(CASE event_type
+                           WHEN 'MoneyCredited' THEN (event_data->>'amount')::numeric
+                           ELSE -(event_data->>'amount')::numeric
+                       END) AS net
+            FROM events
+            WHERE aggregate_type = 'Account'
+              AND event_type IN ('MoneyCredited', 'MoneyDebited', 'HoldCaptured')
+            GROUP BY aggregate_id
+        ) ev ON ev.account_id = ab.account_id
+        WHERE ab.balance <> COALESCE(ev.net, 0)
+        "#,
+    )
+    .fetch_all(&mut *tx)
+    .await?;
+
+    discrepancies.extend(event_mismatches.into_iter().map(|(account_id, expected, actual)| Discrepancy {
+        kind: DiscrepancyKind::EventBalanceMismatch,
+        subject_id: account_id,
+        expected,
+        actual,
+    }));
+
+    let account_ids: Vec<Uuid> = sqlx::query_scalar("SELECT account_id FROM account_balances")
+        .fetch_all(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    // 4. Replayed accounts must satisfy the aggregate invariants (outside the
+    //    snapshot; aggregates are loaded through the event store)
+    let event_store = EventStore::new(pool.clone());
+    for account_id in account_ids {
+        match event_store.load_aggregate::<Account>(account_id).await {
+            Ok(Some(account)) => {
+                if let Err(violation) = account.check_invariants() {
+                    discrepancies.push(invariant_discrepancy(account_id, &violation));
+                }
+            }
+            Ok(None) => {}
+            // One unreadable stream must not abort the rest of the run
+            Err(e) => tracing::error!(
+                account_id = %account_id,
+                error = %e,
+                "Failed to load account for invariant check"
+            ),
+        }
+    }
+
+    let completed_at = Utc::now();
+    let id: Uuid = sqlx::query_scalar(
+        r#"
+        INSERT INTO reconciliation_reports (
+            started_at, completed_at, journals_checked, accounts_checked,
+            discrepancy_count, discrepancies
+        )
+        VALUES ($1, $2, $3, $4, $5, $6)
+        RETURNING id
+        "#,
+    )
+    .bind(started_at)
+    .bind(completed_at)
+    .bind(journals_checked)
+    .bind(accounts_checked)
+    .bind(discrepancies.len() as i32)
+    .bind(serde_json::to_value(&discrepancies)?)
+    .fetch_one(pool)
+    .await?;
+
+    if discrepancies.is_empty() {
+        tracing::info!(
+            journals_checked = journals_checked,
+            accounts_checked = accounts_checked,
+            "Ledger reconciliation passed"
+        );
+    } else {
+        tracing::error!(
+            report_id = %id,
+            discrepancies = discrepancies.len(),
+            "Ledger reconciliation found discrepancies"
+        );
+    }
+
+    Ok(ReconciliationReport {
+        id,
+        started_at,
+        completed_at,
+        journals_checked,
+        accounts_checked,
+        discrepancies,
+    })
+}
+
+/// Discrepancy for a broken account invariant: `expected` is the bound the
+/// invariant requires, `actual` the offending value
+fn invariant_discrepancy(account_id: Uuid, violation: &AccountInvariantViolation) -> Discrepancy {
+    let (expected, actual) = match *violation {
+        AccountInvariantViolation::NegativeBalance { balance } => (Decimal::ZERO, balance),
+        AccountInvariantViolation::HoldsExceedBalance { held, balance } => (balance, held),
+        AccountInvariantViolation::NonPositiveHold { amount, .. } => (Decimal::ZERO, amount),
+    };
+    Discrepancy {
+        kind: DiscrepancyKind::AccountInvariantViolation,
+        subject_id: account_id,
+        expected,
+        actual,
+    }
+}
+
+/// Load the most recent reconciliation reports, newest first
+#[allow(clippy::type_complexity)]
+pub async fn recent_reconciliation_reports(
+    pool: &PgPool,
+    limit: i64,
+) -> Result<Vec<ReconciliationReport>, JobError> {
+    let rows: Vec<(Uuid, DateTime<Utc>, DateTime<Utc>, i64, i64, serde_json::Value)> = sqlx::query_as(
+        r#"
+        SELECT id, started_at, completed_at, journals_checked, accounts_checked, discrepancies
+        FROM reconciliation_reports
+        ORDER BY completed_at DESC
+        LIMIT $1
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|(id, started_at, completed_at, journals_checked, accounts_checked, discrepancies)| {
+            Ok(ReconciliationReport {
+                id,
+                started_at,
+                completed_at,
+                journals_checked,
+                accounts_checked,
+                discrepancies: serde_json::from_value(discrepancies)?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discrepancy_serialization() {
+        let discrepancy = Discrepancy {
+            kind: DiscrepancyKind::JournalImbalance,
+            subject_id: Uuid::nil(),
+            expected: Decimal::new(100, 0),
+            actual: Decimal::new(90, 0),
+        };
+
+        let value = serde_json::to_value(&discrepancy).unwrap();
+        assert_eq!(value["kind"], "journal_imbalance");
+
+        let parsed: Discrepancy = serde_json::from_value(value).unwrap();
+        assert_eq!(parsed.kind, DiscrepancyKind::JournalImbalance);
+        assert_eq!(parsed.actual, Decimal::new(90, 0));
+    }
+
+    #[test]
+    fn test_invariant_discrepancy() {
+        let violation = AccountInvariantViolation::HoldsExceedBalance {
+            held: Decimal::new(120, 0),
+            balance: Decimal::new(100, 0),
+        };
+        let discrepancy = invariant_discrepancy(Uuid::nil(), &violation);
+
+        assert_eq!(discrepancy.kind, DiscrepancyKind::AccountInvariantViolation);
+        assert_eq!(discrepancy.expected, Decimal::new(100, 0));
+        assert_eq!(discrepancy.actual, Decimal::new(120, 0));
+    }
+}
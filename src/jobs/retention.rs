@@ -0,0 +1,58 @@
+//! User Retention Job
+//!
+//! Anonymizes users that have been deactivated for longer than the
+//! configured retention period (USER_RETENTION_DAYS). Each user goes
+//! through AnonymizeUserHandler, so the UserAnonymized event, audit entry
+//! and webhook are recorded exactly as for the admin endpoint.
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::domain::OperationContext;
+use crate::handlers::{AnonymizeUserCommand, AnonymizeUserHandler};
+
+/// Users anonymized per run
+const RETENTION_BATCH_SIZE: i64 = 100;
+
+/// Reason recorded on UserAnonymized events created by this job
+pub const RETENTION_REASON: &str = "retention_period_expired";
+
+/// Anonymize users deactivated more than `retention_days` days ago.
+/// Returns the number of users anonymized.
+pub async fn anonymize_expired_users(pool: &PgPool, retention_days: u32) -> Result<u64, JobError> {
+    let user_ids: Vec<Uuid> = sqlx::query_scalar(
+        r#"
+        SELECT id
+        FROM users
+        WHERE is_active = FALSE
+          AND is_system = FALSE
+          AND anonymized_at IS NULL
+          AND deactivated_at < NOW() - make_interval(days => $1)
+        ORDER BY deactivated_at
+        LIMIT $2
+        "#,
+    )
+    .bind(retention_days as i32)
+    .bind(RETENTION_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let handler = AnonymizeUserHandler::new(pool.clone());
+    let context = OperationContext::new();
+    let mut anonymized = 0;
+
+    for user_id in user_ids {
+        let command = AnonymizeUserCommand::new(user_id.into()).with_reason(RETENTION_REASON.to_string());
+        match handler.execute(command, &context).await {
+            Ok(_) => anonymized += 1,
+            Err(e) => tracing::warn!(user_id = %user_id, error = %e, "Failed to anonymize user"),
+        }
+    }
+
+    if anonymized > 0 {
+        tracing::info!(anonymized, retention_days, "Anonymized users past retention period");
+    }
+
+    Ok(anonymized)
+}
@@ -0,0 +1,145 @@
+//! Job Run History
+//!
+//! Every execution of a maintenance job, scheduled or triggered through the
+//! admin API, is recorded in job_runs with its duration, the rows it
+//! affected and its error. Runs older than JOB_RUN_RETENTION_DAYS are pruned
+//! by the job_run_cleanup job.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{Job, JobError};
+
+/// Days job runs are kept
+pub const JOB_RUN_RETENTION_DAYS: i32 = 7;
+
+/// What started a job run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobTrigger {
+    /// The job scheduler's interval
+    Scheduled,
+    /// POST /admin/jobs/{name}/run, by the given API key
+    Manual(Option<Uuid>),
+}
+
+impl JobTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobTrigger::Scheduled => "scheduled",
+            JobTrigger::Manual(_) => "manual",
+        }
+    }
+}
+
+/// Recorded job execution
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct JobRun {
+    pub id: i64,
+    pub job_name: String,
+    /// "scheduled" or "manual"
+    pub trigger: String,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub duration_ms: i64,
+    pub rows_affected: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub triggered_by: Option<Uuid>,
+}
+
+const JOB_RUN_COLUMNS: &str =
+    "id, job_name, trigger, started_at, finished_at, duration_ms, rows_affected, error, triggered_by";
+
+/// Record one execution of `job`
+pub(super) async fn record_job_run(
+    pool: &PgPool,
+    job: Job,
+    trigger: JobTrigger,
+    started_at: DateTime<Utc>,
+    rows_affected: u64,
+    error: Option<&str>,
+) -> Result<JobRun, JobError> {
+    let finished_at = Utc::now();
+    let duration_ms = (finished_at - started_at).num_milliseconds().max(0);
+    let triggered_by = match trigger {
+        JobTrigger::Manual(api_key_id) => api_key_id,
+        JobTrigger::Scheduled => None,
+    };
+
+    let run: JobRun = sqlx::query_as(&format!(
+        r#"
+        INSERT INTO job_runs (
+            job_name, trigger, started_at, finished_at, duration_ms, rows_affected, error, triggered_by
+        )
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        RETURNING {}
+        "#,
+        JOB_RUN_COLUMNS
+    ))
+    .bind(job.as_str())
+    .bind(trigger.as_str())
+    .bind(started_at)
+    .bind(finished_at)
+    .bind(duration_ms)
+    .bind(rows_affected as i64)
+    .bind(error)
+    .bind(triggered_by)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(run)
+}
+
+/// Load the most recent runs (of one job, or of all jobs), newest first
+pub async fn recent_job_runs(pool: &PgPool, job: Option<Job>, limit: i64) -> Result<Vec<JobRun>, JobError> {
+    let runs: Vec<JobRun> = sqlx::query_as(&format!(
+        r#"
+        SELECT {}
+        FROM job_runs
+        WHERE $1::text IS NULL OR job_name = $1
+        ORDER BY started_at DESC, id DESC
+        LIMIT $2
+        "#,
+        JOB_RUN_COLUMNS
+    ))
+    .bind(job.map(|j| j.as_str()))
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(runs)
+}
+
+/// Latest run of every job that has run
+pub async fn last_job_runs(pool: &PgPool) -> Result<Vec<JobRun>, JobError> {
+    let runs: Vec<JobRun> = sqlx::query_as(&format!(
+        r#"
+        SELECT DISTINCT ON (job_name) {}
+        FROM job_runs
+        ORDER BY job_name, started_at DESC, id DESC
+        "#,
+        JOB_RUN_COLUMNS
+    ))
+    .fetch_all(pool)
+    .await?;
+
+    Ok(runs)
+}
+
+/// Delete runs older than JOB_RUN_RETENTION_DAYS
+pub async fn prune_job_runs(pool: &PgPool) -> Result<u64, JobError> {
+    let result = sqlx::query("DELETE FROM job_runs WHERE started_at < NOW() - make_interval(days => $1)")
+        .bind(JOB_RUN_RETENTION_DAYS)
+        .execute(pool)
+        .await?;
+
+    let rows_deleted = result.rows_affected();
+    if rows_deleted > 0 {
+        tracing::info!(rows_deleted, "Pruned job run history");
+    }
+
+    Ok(rows_deleted)
+}
@@ -0,0 +1,191 @@
+//! Snapshot Maintenance Job
+//!
+//! Keeps aggregate snapshots useful: snapshots aggregates that were active
+//! recently and have many events since their last snapshot, removes
+//! snapshots whose aggregate no longer has any events, and reports how much
+//! of the event store is covered by snapshots.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::aggregate::{Account, Aggregate, Transfer, User};
+use crate::event_store::{EventStore, EventStoreError};
+
+/// Events since the last snapshot before an aggregate is snapshotted proactively
+pub const SNAPSHOT_EVENT_THRESHOLD: i64 = 50;
+
+/// Aggregates snapshotted per run
+const SNAPSHOT_BATCH_SIZE: i64 = 100;
+
+/// Result of one snapshot maintenance run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SnapshotMaintenanceReport {
+    pub snapshots_created: u64,
+    pub snapshots_pruned: u64,
+    pub coverage: SnapshotCoverage,
+}
+
+/// Snapshot coverage of the event store
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotCoverage {
+    pub aggregates_total: i64,
+    pub aggregates_with_snapshot: i64,
+    pub events_total: i64,
+    /// Events that must be replayed on load because they follow the snapshot
+    pub events_after_snapshot: i64,
+    /// Largest replay needed to load a single aggregate
+    pub max_events_after_snapshot: i64,
+}
+
+/// Create snapshots for hot aggregates, prune orphans and report coverage
+pub async fn maintain_snapshots(pool: &PgPool) -> Result<SnapshotMaintenanceReport, JobError> {
+    let snapshots_created = snapshot_hot_aggregates(pool).await?;
+    let snapshots_pruned = prune_orphaned_snapshots(pool).await?;
+    let coverage = snapshot_coverage(pool).await?;
+
+    if snapshots_created > 0 || snapshots_pruned > 0 {
+        tracing::info!(
+            snapshots_created,
+            snapshots_pruned,
+            events_after_snapshot = coverage.events_after_snapshot,
+            "Snapshot maintenance completed"
+        );
+    }
+
+    Ok(SnapshotMaintenanceReport {
+        snapshots_created,
+        snapshots_pruned,
+        coverage,
+    })
+}
+
+/// Snapshot aggregates with events in the last day and at least
+/// SNAPSHOT_EVENT_THRESHOLD events since their last snapshot
+pub async fn snapshot_hot_aggregates(pool: &PgPool) -> Result<u64, JobError> {
+    let hot: Vec<(String, Uuid)> = sqlx::query_as(
+        r#"
+        WITH recent AS (
+            SELECT DISTINCT aggregate_type, aggregate_id
+            FROM events
+            WHERE created_at > NOW() - INTERVAL '1 day'
+        ),
+        heads AS (
+            SELECT r.aggregate_type, r.aggregate_id,
+                   (SELECT MAX(e.version) FROM events e WHERE e.aggregate_id = r.aggregate_id) AS head
+            FROM recent r
+        )
+        SELECT h.aggregate_type, h.aggregate_id
+        FROM heads h
+        LEFT JOIN event_snapshots s
+            ON s.aggregate_type = h.aggregate_type AND s.aggregate_id = h.aggregate_id
+        WHERE h.head - COALESCE(s.version, 0) >= $1
+        ORDER BY h.head - COALESCE(s.version, 0) DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(SNAPSHOT_EVENT_THRESHOLD)
+    .bind(SNAPSHOT_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let event_store = EventStore::new(pool.clone());
+    let mut created = 0;
+
+    for (aggregate_type, aggregate_id) in hot {
+        let saved = match aggregate_type.as_str() {
+            "Account" => snapshot::<Account>(&event_store, aggregate_id).await,
+            "User" => snapshot::<User>(&event_store, aggregate_id).await,
+            "Transfer" => snapshot::<Transfer>(&event_store, aggregate_id).await,
+            _ => Ok(false),
+        };
+
+        match saved {
+            Ok(true) => created += 1,
+            Ok(false) => {}
+            // One bad stream must not block snapshots for the rest
+            Err(e) => tracing::error!(
+                aggregate_type = %aggregate_type,
+                aggregate_id = %aggregate_id,
+                error = %e,
+                "Failed to snapshot aggregate"
+            ),
+        }
+    }
+
+    Ok(created)
+}
+
+/// Load an aggregate and snapshot it at its current version
+async fn snapshot<A>(event_store: &EventStore, aggregate_id: Uuid) -> Result<bool, EventStoreError>
+where
+    A: Aggregate + serde::de::DeserializeOwned + Serialize,
+    A::Event: serde::de::DeserializeOwned,
+{
+    match event_store.load_aggregate::<A>(aggregate_id).await? {
+        Some(aggregate) => event_store.save_snapshot(&aggregate).await,
+        None => Ok(false),
+    }
+}
+
+/// Delete snapshots whose aggregate has no events left
+pub async fn prune_orphaned_snapshots(pool: &PgPool) -> Result<u64, JobError> {
+    let result = sqlx::query(
+        r#"
+        DELETE FROM event_snapshots s
+        WHERE NOT EXISTS (
+            SELECT 1 FROM events e
+            WHERE e.aggregate_id = s.aggregate_id AND e.aggregate_type = s.aggregate_type
+        )
+        "#,
+    )
+    .execute(pool)
+    .await?;
+
+    let rows_deleted = result.rows_affected();
+
+    if rows_deleted > 0 {
+        tracing::warn!(rows_deleted, "Pruned orphaned snapshots");
+    }
+
+    Ok(rows_deleted)
+}
+
+/// Compute snapshot coverage across all aggregates
+pub async fn snapshot_coverage(pool: &PgPool) -> Result<SnapshotCoverage, JobError> {
+    let (aggregates_total, aggregates_with_snapshot, events_total, events_after_snapshot, max_events_after_snapshot): (
+        i64,
+        i64,
+        i64,
+        i64,
+        i64,
+    ) = sqlx::query_as(
+        r#"
+        WITH heads AS (
+            SELECT aggregate_type, aggregate_id, MAX(version) AS head, COUNT(*) AS event_count
+            FROM events
+            GROUP BY aggregate_type, aggregate_id
+        )
+        SELECT
+            COUNT(*),
+            COUNT(s.aggregate_id),
+            COALESCE(SUM(h.event_count), 0)::BIGINT,
+            COALESCE(SUM(h.head - COALESCE(s.version, 0)), 0)::BIGINT,
+            COALESCE(MAX(h.head - COALESCE(s.version, 0)), 0)::BIGINT
+        FROM heads h
+        LEFT JOIN event_snapshots s
+            ON s.aggregate_type = h.aggregate_type AND s.aggregate_id = h.aggregate_id
+        "#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(SnapshotCoverage {
+        aggregates_total,
+        aggregates_with_snapshot,
+        events_total,
+        events_after_snapshot,
+        max_events_after_snapshot,
+    })
+}
@@ -0,0 +1,199 @@
+//! Daily Statistics Job
+//!
+//! Materializes per-day totals (transfers, mints, burns, active and new
+//! users) into stats_daily from the ledger and users tables. Only completed
+//! UTC days are written; each run catches up on the days missing since the
+//! last materialized one.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use super::JobError;
+use crate::config::SystemUserIds;
+
+/// Most days materialized by one run (bounds the initial backfill)
+const MAX_DAYS_PER_RUN: usize = 31;
+
+/// Totals of one UTC day
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct DailyStats {
+    pub day: NaiveDate,
+    /// Journals between two non-system users
+    pub transfer_count: i64,
+    pub transfer_volume: Decimal,
+    pub mint_total: Decimal,
+    pub burn_total: Decimal,
+    /// Distinct non-system users with a ledger entry that day
+    pub active_users: i64,
+    /// Non-system users created that day
+    pub new_users: i64,
+    pub computed_at: DateTime<Utc>,
+}
+
+/// Compute the stats of `day` and store them (replacing an earlier row)
+pub async fn materialize_daily_stats(
+    pool: &PgPool,
+    day: NaiveDate,
+    system_users: SystemUserIds,
+) -> Result<DailyStats, JobError> {
+    let (start, end) = day_bounds(day);
+
+    let stats: DailyStats = sqlx::query_as(
+        r#"
+        WITH journals AS (
+            SELECT d.amount,
+                   du.id AS from_user_id, du.is_system AS from_system,
+                   cu.id AS to_user_id, cu.is_system AS to_system
+            FROM ledger_entries d
+            JOIN ledger_entries c ON c.journal_id = d.journal_id AND c.entry_type = 'credit'
+            JOIN accounts da ON da.id = d.account_id
+            JOIN accounts ca ON ca.id = c.account_id
+            JOIN users du ON du.id = da.user_id
+            JOIN users cu ON cu.id = ca.user_id
+            WHERE d.entry_type = 'debit'
+              AND d.created_at >= $2 AND d.created_at < $3
+        ),
+        totals AS (
+            SELECT
+                COUNT(*) FILTER (WHERE NOT from_system AND NOT to_system) AS transfer_count,
+                COALESCE(SUM(amount) FILTER (WHERE NOT from_system AND NOT to_system), 0) AS transfer_volume,
+                COALESCE(SUM(amount) FILTER (WHERE from_user_id = $4), 0) AS mint_total,
+                COALESCE(SUM(amount) FILTER (WHERE to_user_id = $5), 0) AS burn_total
+            FROM journals
+        )
+        INSERT INTO stats_daily (
+            day, transfer_count, transfer_volume, mint_total, burn_total, active_users, new_users, computed_at
+        )
+        SELECT
+            $1,
+            totals.transfer_count,
+            totals.transfer_volume,
+            totals.mint_total,
+            totals.burn_total,
+            (
+                SELECT COUNT(DISTINCT user_id) FROM (
+                    SELECT from_user_id AS user_id FROM journals WHERE NOT from_system
+                    UNION
+                    SELECT to_user_id FROM journals WHERE NOT to_system
+                ) active
+            ),
+            (
+                SELECT COUNT(*) FROM users
+                WHERE NOT is_system AND created_at >= $2 AND created_at < $3
+            ),
+            NOW()
+        FROM totals
+        ON CONFLICT (day) DO UPDATE SET
+            transfer_count = EXCLUDED.transfer_count,
+            transfer_volume = EXCLUDED.transfer_volume,
+            mint_total = EXCLUDED.mint_total,
+            burn_total = EXCLUDED.burn_total,
+            active_users = EXCLUDED.active_users,
+            new_users = EXCLUDED.new_users,
+            computed_at = EXCLUDED.computed_at
+        RETURNING day, transfer_count, transfer_volume, mint_total, burn_total, active_users, new_users, computed_at
+        "#,
+    )
+    .bind(day)
+    .bind(start)
+    .bind(end)
+    .bind(system_users.mint)
+    .bind(system_users.burn)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(stats)
+}
+
+/// Materialize every completed day after the last stored one (starting at
+/// the first ledger entry when stats_daily is empty). Returns the number of
+/// days written.
+pub async fn materialize_pending_daily_stats(pool: &PgPool, system_users: SystemUserIds) -> Result<u64, JobError> {
+    let last_day: Option<NaiveDate> = sqlx::query_scalar("SELECT MAX(day) FROM stats_daily")
+        .fetch_one(pool)
+        .await?;
+
+    let first_day = match last_day {
+        Some(day) => day.succ_opt(),
+        None => sqlx::query_scalar("SELECT (MIN(created_at) AT TIME ZONE 'UTC')::date FROM ledger_entries")
+            .fetch_one(pool)
+            .await?,
+    };
+    let Some(first_day) = first_day else {
+        return Ok(0);
+    };
+
+    let days = pending_days(first_day, Utc::now().date_naive());
+    for day in &days {
+        let stats = materialize_daily_stats(pool, *day, system_users).await?;
+        tracing::info!(
+            day = %stats.day,
+            transfer_count = stats.transfer_count,
+            "Materialized daily stats"
+        );
+    }
+
+    Ok(days.len() as u64)
+}
+
+/// Stored stats of the days in [from, to], oldest first
+pub async fn daily_stats(pool: &PgPool, from: NaiveDate, to: NaiveDate) -> Result<Vec<DailyStats>, JobError> {
+    let stats: Vec<DailyStats> = sqlx::query_as(
+        r#"
+        SELECT day, transfer_count, transfer_volume, mint_total, burn_total, active_users, new_users, computed_at
+        FROM stats_daily
+        WHERE day >= $1 AND day <= $2
+        ORDER BY day
+        "#,
+    )
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(stats)
+}
+
+/// Completed days from `first` up to (excluding) `today`, at most MAX_DAYS_PER_RUN
+pub(super) fn pending_days(first: NaiveDate, today: NaiveDate) -> Vec<NaiveDate> {
+    first
+        .iter_days()
+        .take_while(|day| *day < today)
+        .take(MAX_DAYS_PER_RUN)
+        .collect()
+}
+
+/// [start, end) of a UTC day
+pub(super) fn day_bounds(day: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = day.and_time(chrono::NaiveTime::MIN).and_utc();
+    (start, start + chrono::Duration::days(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_pending_days() {
+        assert_eq!(
+            pending_days(date("2026-02-27"), date("2026-03-02")),
+            vec![date("2026-02-27"), date("2026-02-28"), date("2026-03-01")]
+        );
+        // Today is never materialized
+        assert!(pending_days(date("2026-03-02"), date("2026-03-02")).is_empty());
+        assert_eq!(pending_days(date("2025-01-01"), date("2026-01-01")).len(), MAX_DAYS_PER_RUN);
+    }
+
+    #[test]
+    fn test_day_bounds() {
+        let (start, end) = day_bounds(date("2026-03-01"));
+        assert_eq!(start.to_rfc3339(), "2026-03-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-03-02T00:00:00+00:00");
+    }
+}
@@ -0,0 +1,222 @@
+//! Webhook Dispatcher Job
+//!
+//! Sends due webhook deliveries and reschedules failures with exponential
+//! backoff. Every HTTP attempt is recorded in webhook_delivery_attempts.
+
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::JobError;
+use crate::webhooks::sign_payload;
+
+/// Deliveries claimed per dispatcher run
+const WEBHOOK_BATCH_SIZE: i64 = 50;
+
+/// Attempts before a delivery is marked failed
+pub const MAX_WEBHOOK_ATTEMPTS: i32 = 8;
+
+/// Timeout for a single HTTP attempt
+pub const WEBHOOK_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the next attempt: 30s doubled per failed attempt, capped at 6 hours
+pub fn webhook_retry_delay(attempt_count: i32) -> chrono::Duration {
+    let exponent = attempt_count.saturating_sub(1).clamp(0, 16) as u32;
+    let seconds = 30i64.saturating_mul(1 << exponent);
+    chrono::Duration::seconds(seconds.min(6 * 3600))
+}
+
+/// Outcome of a single HTTP attempt
+struct AttemptOutcome {
+    response_status: Option<i32>,
+    error: Option<String>,
+    duration_ms: i64,
+}
+
+impl AttemptOutcome {
+    fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Send due webhook deliveries. Returns the number of attempts made.
+///
+/// Claimed deliveries are leased for 5 minutes (next_attempt_at is pushed
+/// forward) so concurrent dispatchers do not send the same delivery twice.
+#[allow(clippy::type_complexity)]
+pub async fn dispatch_webhooks(pool: &PgPool, client: &reqwest::Client) -> Result<u64, JobError> {
+    let due: Vec<(Uuid, Uuid, String, serde_json::Value, i32, String, String)> = sqlx::query_as(
+        r#"
+        UPDATE webhook_deliveries d
+        SET next_attempt_at = NOW() + INTERVAL '5 minutes'
+        FROM (
+            SELECT id FROM webhook_deliveries
+            WHERE status = 'pending' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        ) due, webhook_endpoints e
+        WHERE d.id = due.id AND e.id = d.endpoint_id
+        RETURNING d.id, d.event_id, d.event_type, d.payload, d.attempt_count, e.url, e.secret
+        "#,
+    )
+    .bind(WEBHOOK_BATCH_SIZE)
+    .fetch_all(pool)
+    .await?;
+
+    let mut attempts = 0;
+    for (delivery_id, event_id, event_type, payload, attempt_count, url, secret) in due {
+        let body = serde_json::to_vec(&payload)?;
+        let outcome = send_webhook(client, &url, &secret, delivery_id, &event_type, body).await;
+        let attempt_number = attempt_count + 1;
+        attempts += 1;
+
+        record_attempt(pool, delivery_id, attempt_number, &outcome).await?;
+
+        if outcome.is_success() {
+            tracing::debug!(delivery_id = %delivery_id, event_id = %event_id, "Webhook delivered");
+        } else if attempt_number >= MAX_WEBHOOK_ATTEMPTS {
+            tracing::error!(
+                delivery_id = %delivery_id,
+                event_id = %event_id,
+                url = %url,
+                error = ?outcome.error,
+                "Webhook delivery failed permanently"
+            );
+        } else {
+            tracing::warn!(
+                delivery_id = %delivery_id,
+                attempt = attempt_number,
+                error = ?outcome.error,
+                "Webhook delivery failed, will retry"
+            );
+        }
+    }
+
+    Ok(attempts)
+}
+
+/// POST a signed payload to an endpoint
+async fn send_webhook(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    delivery_id: Uuid,
+    event_type: &str,
+    body: Vec<u8>,
+) -> AttemptOutcome {
+    let timestamp = Utc::now().timestamp();
+    let signature = sign_payload(secret, timestamp, &body);
+    let start = Instant::now();
+
+    let result = client
+        .post(url)
+        .timeout(WEBHOOK_REQUEST_TIMEOUT)
+        .header("Content-Type", "application/json")
+        .header("X-Webhook-Id", delivery_id.to_string())
+        .header("X-Webhook-Event", event_type)
+        .header("X-Webhook-Timestamp", timestamp.to_string())
+        .header("X-Webhook-Signature", format!("sha256={}", signature))
+        .body(body)
+        .send()
+        .await;
+
+    let duration_ms = start.elapsed().as_millis() as i64;
+
+    match result {
+        Ok(response) if response.status().is_success() => AttemptOutcome {
+            response_status: Some(response.status().as_u16() as i32),
+            error: None,
+            duration_ms,
+        },
+        Ok(response) => AttemptOutcome {
+            response_status: Some(response.status().as_u16() as i32),
+            error: Some(format!("Endpoint responded with {}", response.status())),
+            duration_ms,
+        },
+        Err(e) => AttemptOutcome {
+            response_status: None,
+            error: Some(e.to_string()),
+            duration_ms,
+        },
+    }
+}
+
+/// Store the attempt and advance the delivery state
+async fn record_attempt(
+    pool: &PgPool,
+    delivery_id: Uuid,
+    attempt_number: i32,
+    outcome: &AttemptOutcome,
+) -> Result<(), JobError> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO webhook_delivery_attempts (
+            delivery_id, attempt_number, response_status, error, duration_ms
+        )
+        VALUES ($1, $2, $3, $4, $5)
+        "#,
+    )
+    .bind(delivery_id)
+    .bind(attempt_number)
+    .bind(outcome.response_status)
+    .bind(&outcome.error)
+    .bind(outcome.duration_ms)
+    .execute(&mut *tx)
+    .await?;
+
+    if outcome.is_success() {
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'delivered', attempt_count = $2, last_error = NULL, delivered_at = NOW()
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(attempt_number)
+        .execute(&mut *tx)
+        .await?;
+    } else {
+        let status = if attempt_number >= MAX_WEBHOOK_ATTEMPTS {
+            "failed"
+        } else {
+            "pending"
+        };
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = $3, attempt_count = $2, last_error = $4, next_attempt_at = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery_id)
+        .bind(attempt_number)
+        .bind(status)
+        .bind(&outcome.error)
+        .bind(Utc::now() + webhook_retry_delay(attempt_number))
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webhook_retry_delay() {
+        assert_eq!(webhook_retry_delay(1), chrono::Duration::seconds(30));
+        assert_eq!(webhook_retry_delay(2), chrono::Duration::seconds(60));
+        assert_eq!(webhook_retry_delay(5), chrono::Duration::seconds(480));
+        assert_eq!(webhook_retry_delay(MAX_WEBHOOK_ATTEMPTS), chrono::Duration::seconds(3840));
+        assert_eq!(webhook_retry_delay(30), chrono::Duration::hours(6));
+    }
+}
@@ -3,6 +3,7 @@
 //! Re-exports modules for integration testing and external use.
 
 pub mod aggregate;
+pub mod alerts;
 pub mod api;
 pub mod audit;
 pub mod domain;
@@ -10,7 +11,19 @@ pub mod event_store;
 pub mod handlers;
 pub mod idempotency;
 pub mod jobs;
+pub mod limits;
 pub mod projection;
+pub mod queries;
+pub mod receipts;
+pub mod restrictions;
+pub mod self_test;
+#[cfg(feature = "simulator")]
+pub mod simulator;
+pub mod state;
+pub mod tenants;
+#[cfg(any(test, feature = "test-util"))]
+pub mod test_util;
+pub mod webhooks;
 
 // Private modules (used only by main.rs binary)
 pub mod config;
@@ -18,6 +31,7 @@ pub mod db;
 mod error;
 
 pub use config::Config;
-pub use error::{AppError, AppResult};
+pub use state::{AppState, SharedState};
+pub use error::{AppError, AppResult, FieldError};
 pub use domain::{Amount, AmountError, Balance, OperationContext, DomainError};
 pub use domain::{AccountEvent, TransferEvent, UserEvent};
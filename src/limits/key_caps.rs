@@ -0,0 +1,285 @@
+//! API Key Caps
+//!
+//! Per-key mint/burn caps stored on api_keys (migration 026): a maximum per
+//! command and a maximum total over a rolling 24 hours. They apply on top of
+//! the global limits and bound what a leaked admin key can do. Usage is
+//! measured from the events the key appended to the system mint/burn
+//! account, and like the global daily check it runs before the command's
+//! events are appended.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::ApiKeyId;
+
+use super::{LimitError, LimitOperation, LimitService};
+use crate::config::SystemUserIds;
+
+/// Cap of one operation (None = uncapped)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct KeyCap {
+    #[serde(default)]
+    pub max_amount: Option<Decimal>,
+    /// Maximum total over a rolling 24 hours
+    #[serde(default)]
+    pub daily_limit: Option<Decimal>,
+}
+
+impl KeyCap {
+    fn validate(&self) -> Result<(), LimitError> {
+        if [self.max_amount, self.daily_limit]
+            .into_iter()
+            .flatten()
+            .any(|value| value <= Decimal::ZERO)
+        {
+            return Err(LimitError::InvalidLimits("caps must be positive".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Check an amount given the key's 24h usage
+    pub fn check(&self, amount: Decimal, used: Decimal) -> Result<(), LimitError> {
+        if let Some(max) = self.max_amount {
+            if amount > max {
+                return Err(LimitError::KeyMaxExceeded { max });
+            }
+        }
+        if let Some(limit) = self.daily_limit {
+            if used + amount > limit {
+                return Err(LimitError::KeyDailyLimitExceeded { limit, used });
+            }
+        }
+        Ok(())
+    }
+
+    /// What is left of the daily cap
+    fn remaining(&self, used: Decimal) -> Option<Decimal> {
+        self.daily_limit.map(|limit| (limit - used).max(Decimal::ZERO))
+    }
+}
+
+/// Caps of one API key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ApiKeyCaps {
+    #[serde(default)]
+    pub mint: KeyCap,
+    #[serde(default)]
+    pub burn: KeyCap,
+}
+
+impl ApiKeyCaps {
+    /// Cap of an operation (transfers are not capped per key)
+    pub fn cap(&self, operation: LimitOperation) -> Option<&KeyCap> {
+        match operation {
+            LimitOperation::Mint => Some(&self.mint),
+            LimitOperation::Burn => Some(&self.burn),
+            LimitOperation::Transfer => None,
+        }
+    }
+}
+
+/// Cap and 24h usage of one operation
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyAllowance {
+    pub max_amount: Option<Decimal>,
+    pub daily_limit: Option<Decimal>,
+    pub used_24h: Decimal,
+    /// None when there is no daily cap
+    pub remaining_24h: Option<Decimal>,
+}
+
+/// Caps and remaining allowance of an API key
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiKeyLimits {
+    pub api_key_id: ApiKeyId,
+    pub mint: KeyAllowance,
+    pub burn: KeyAllowance,
+}
+
+/// System account and event type through which the key's usage of an
+/// operation is measured
+fn usage_pattern(operation: LimitOperation, system_users: SystemUserIds) -> Option<(Uuid, &'static str)> {
+    match operation {
+        LimitOperation::Mint => Some((system_users.mint, "MoneyDebited")),
+        LimitOperation::Burn => Some((system_users.burn, "MoneyCredited")),
+        LimitOperation::Transfer => None,
+    }
+}
+
+impl LimitService {
+    /// Caps of an API key (None if the key does not exist)
+    #[allow(clippy::type_complexity)]
+    pub async fn api_key_caps(&self, api_key_id: ApiKeyId) -> Result<Option<ApiKeyCaps>, LimitError> {
+        let row: Option<(Option<Decimal>, Option<Decimal>, Option<Decimal>, Option<Decimal>)> =
+            sqlx::query_as(
+                r#"
+                SELECT mint_max_amount, mint_daily_limit, burn_max_amount, burn_daily_limit
+                FROM api_keys
+                WHERE id = $1
+                "#,
+            )
+            .bind(api_key_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|(mint_max_amount, mint_daily_limit, burn_max_amount, burn_daily_limit)| {
+            ApiKeyCaps {
+                mint: KeyCap {
+                    max_amount: mint_max_amount,
+                    daily_limit: mint_daily_limit,
+                },
+                burn: KeyCap {
+                    max_amount: burn_max_amount,
+                    daily_limit: burn_daily_limit,
+                },
+            }
+        }))
+    }
+
+    /// Replace the caps of an API key. Returns false if the key does not exist.
+    pub async fn set_api_key_caps(&self, api_key_id: ApiKeyId, caps: &ApiKeyCaps) -> Result<bool, LimitError> {
+        caps.mint.validate()?;
+        caps.burn.validate()?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE api_keys
+            SET mint_max_amount = $2, mint_daily_limit = $3,
+                burn_max_amount = $4, burn_daily_limit = $5
+            WHERE id = $1
+            "#,
+        )
+        .bind(api_key_id)
+        .bind(caps.mint.max_amount)
+        .bind(caps.mint.daily_limit)
+        .bind(caps.burn.max_amount)
+        .bind(caps.burn.daily_limit)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Amount an API key minted or burned over the last 24 hours
+    pub async fn api_key_daily_usage(
+        &self,
+        operation: LimitOperation,
+        api_key_id: ApiKeyId,
+    ) -> Result<Decimal, LimitError> {
+        let Some((system_user_id, event_type)) = usage_pattern(operation, self.system_users) else {
+            return Ok(Decimal::ZERO);
+        };
+
+        let used: Decimal = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM((e.event_data->>'amount')::numeric), 0)
+            FROM events e
+            JOIN accounts a ON a.id = e.aggregate_id
+            WHERE a.user_id = $1
+              AND e.aggregate_type = 'Account'
+              AND e.event_type = $2
+              AND e.context->>'api_key_id' = $3
+              AND e.created_at > NOW() - INTERVAL '24 hours'
+            "#,
+        )
+        .bind(system_user_id)
+        .bind(event_type)
+        .bind(api_key_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(used)
+    }
+
+    /// Caps and 24h usage of an API key (None if the key does not exist)
+    pub async fn api_key_limits(&self, api_key_id: ApiKeyId) -> Result<Option<ApiKeyLimits>, LimitError> {
+        let Some(caps) = self.api_key_caps(api_key_id).await? else {
+            return Ok(None);
+        };
+
+        let mint_used = self.api_key_daily_usage(LimitOperation::Mint, api_key_id).await?;
+        let burn_used = self.api_key_daily_usage(LimitOperation::Burn, api_key_id).await?;
+        let allowance = |cap: &KeyCap, used: Decimal| KeyAllowance {
+            max_amount: cap.max_amount,
+            daily_limit: cap.daily_limit,
+            used_24h: used,
+            remaining_24h: cap.remaining(used),
+        };
+
+        Ok(Some(ApiKeyLimits {
+            api_key_id,
+            mint: allowance(&caps.mint, mint_used),
+            burn: allowance(&caps.burn, burn_used),
+        }))
+    }
+
+    /// Enforce the caps of the API key issuing a mint or burn
+    /// (no-op without an API key, e.g. JWT-authenticated requests)
+    pub async fn check_api_key(
+        &self,
+        operation: LimitOperation,
+        api_key_id: Option<Uuid>,
+        amount: Decimal,
+    ) -> Result<(), LimitError> {
+        let Some(api_key_id) = api_key_id.map(ApiKeyId::from) else {
+            return Ok(());
+        };
+        let Some(caps) = self.api_key_caps(api_key_id).await? else {
+            return Ok(());
+        };
+        let Some(cap) = caps.cap(operation) else {
+            return Ok(());
+        };
+        if *cap == KeyCap::default() {
+            return Ok(());
+        }
+
+        let used = if cap.daily_limit.is_some() {
+            self.api_key_daily_usage(operation, api_key_id).await?
+        } else {
+            Decimal::ZERO
+        };
+        cap.check(amount, used)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_cap_check() {
+        let cap = KeyCap {
+            max_amount: Some(Decimal::from(100)),
+            daily_limit: Some(Decimal::from(250)),
+        };
+
+        assert!(cap.check(Decimal::from(100), Decimal::from(150)).is_ok());
+        assert!(matches!(
+            cap.check(Decimal::from(101), Decimal::ZERO),
+            Err(LimitError::KeyMaxExceeded { .. })
+        ));
+        assert!(matches!(
+            cap.check(Decimal::from(60), Decimal::from(200)),
+            Err(LimitError::KeyDailyLimitExceeded { .. })
+        ));
+        assert!(KeyCap::default().check(Decimal::from(1_000_000), Decimal::from(1_000_000)).is_ok());
+
+        assert_eq!(cap.remaining(Decimal::from(200)), Some(Decimal::from(50)));
+        assert_eq!(cap.remaining(Decimal::from(300)), Some(Decimal::ZERO));
+        assert_eq!(KeyCap::default().remaining(Decimal::ONE), None);
+    }
+
+    #[test]
+    fn test_key_cap_validate() {
+        assert!(KeyCap::default().validate().is_ok());
+        assert!(KeyCap {
+            max_amount: Some(Decimal::ZERO),
+            daily_limit: None,
+        }
+        .validate()
+        .is_err());
+        assert!(ApiKeyCaps::default().cap(LimitOperation::Transfer).is_none());
+    }
+}
@@ -0,0 +1,60 @@
+//! In-Memory Limits
+//!
+//! [`LimitTrait`] implementation for handler tests that run without
+//! PostgreSQL. Only per-transaction minimums and maximums can be configured;
+//! daily limits and API key caps are measured from the ledger and events and
+//! have no in-memory equivalent.
+
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use super::{LimitError, LimitOperation, LimitTrait, TransferLimit};
+
+/// Per-transaction limits shared between clones; nothing is limited until set
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryLimits {
+    limits: Arc<Mutex<HashMap<LimitOperation, TransferLimit>>>,
+}
+
+impl InMemoryLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum and maximum amount of an operation
+    pub fn set(&self, operation: LimitOperation, min_amount: Option<Decimal>, max_amount: Option<Decimal>) {
+        let limit = TransferLimit {
+            operation,
+            min_amount,
+            max_amount,
+            daily_limit: None,
+            updated_at: Utc::now(),
+        };
+        self.limits
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(operation, limit);
+    }
+}
+
+impl LimitTrait for InMemoryLimits {
+    async fn check(&self, operation: LimitOperation, _user_id: Uuid, amount: Decimal) -> Result<(), LimitError> {
+        let limits = self.limits.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match limits.get(&operation) {
+            Some(limit) => limit.check_amount(amount),
+            None => Ok(()),
+        }
+    }
+
+    async fn check_api_key(
+        &self,
+        _operation: LimitOperation,
+        _api_key_id: Option<Uuid>,
+        _amount: Decimal,
+    ) -> Result<(), LimitError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+//! Transfer Limits
+//!
+//! Admin-configured amount limits for transfers, mints and burns: a
+//! minimum and maximum per transaction and a per-user cap over a rolling
+//! 24 hours. Usage is measured from ledger_entries.
+//!
+//! Admin keys can additionally be capped for mints and burns (see
+//! key_caps).
+//!
+//! The daily check runs before the command's events are appended, so two
+//! concurrent commands from the same user can together exceed the cap.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::config::SystemUserIds;
+use crate::error::AppError;
+
+mod key_caps;
+#[cfg(any(test, feature = "test-util"))]
+mod memory;
+mod traits;
+
+pub use key_caps::{ApiKeyCaps, ApiKeyLimits, KeyAllowance, KeyCap};
+#[cfg(any(test, feature = "test-util"))]
+pub use memory::InMemoryLimits;
+pub use traits::LimitTrait;
+
+// =========================================================================
+// Operations
+// =========================================================================
+
+/// Money movements that can be limited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitOperation {
+    Transfer,
+    Mint,
+    Burn,
+}
+
+impl LimitOperation {
+    pub const ALL: [LimitOperation; 3] = [
+        LimitOperation::Transfer,
+        LimitOperation::Mint,
+        LimitOperation::Burn,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LimitOperation::Transfer => "transfer",
+            LimitOperation::Mint => "mint",
+            LimitOperation::Burn => "burn",
+        }
+    }
+
+    /// Ledger side of the user's wallet and the counterparty user
+    /// (None = another user wallet) that identify this operation
+    fn ledger_pattern(&self, system_users: SystemUserIds) -> (&'static str, Option<Uuid>) {
+        match self {
+            LimitOperation::Transfer => ("debit", None),
+            LimitOperation::Mint => ("credit", Some(system_users.mint)),
+            LimitOperation::Burn => ("debit", Some(system_users.burn)),
+        }
+    }
+}
+
+impl fmt::Display for LimitOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LimitOperation {
+    type Err = LimitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|op| op.as_str() == s)
+            .ok_or_else(|| LimitError::UnknownOperation(s.to_string()))
+    }
+}
+
+// =========================================================================
+// Records
+// =========================================================================
+
+/// Limits configured for one operation (None = unlimited)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TransferLimit {
+    pub operation: LimitOperation,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    /// Maximum total per user over a rolling 24 hours
+    pub daily_limit: Option<Decimal>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl TransferLimit {
+    /// Check an amount against the per-transaction limits
+    pub fn check_amount(&self, amount: Decimal) -> Result<(), LimitError> {
+        if let Some(min) = self.min_amount {
+            if amount < min {
+                return Err(LimitError::BelowMinimum { min });
+            }
+        }
+        if let Some(max) = self.max_amount {
+            if amount > max {
+                return Err(LimitError::AboveMaximum { max });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check an amount against the daily cap given the user's 24h usage
+    pub fn check_daily(&self, amount: Decimal, used: Decimal) -> Result<(), LimitError> {
+        match self.daily_limit {
+            Some(limit) if used + amount > limit => {
+                Err(LimitError::DailyLimitExceeded { limit, used })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// =========================================================================
+// LimitService
+// =========================================================================
+
+/// Limit Service
+#[derive(Debug, Clone)]
+pub struct LimitService {
+    pool: PgPool,
+    /// Counterparties that identify mints and burns in the ledger
+    system_users: SystemUserIds,
+}
+
+impl LimitService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            system_users: SystemUserIds::default(),
+        }
+    }
+
+    /// Measure mints and burns against these system users
+    pub fn with_system_users(mut self, system_users: SystemUserIds) -> Self {
+        self.system_users = system_users;
+        self
+    }
+
+    /// All configured limits
+    #[allow(clippy::type_complexity)]
+    pub async fn list(&self) -> Result<Vec<TransferLimit>, LimitError> {
+        let rows: Vec<(String, Option<Decimal>, Option<Decimal>, Option<Decimal>, DateTime<Utc>)> =
+            sqlx::query_as(
+                r#"
+                SELECT operation, min_amount, max_amount, daily_limit, updated_at
+                FROM transfer_limits
+                ORDER BY operation
+                "#,
+            )
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|(operation, min_amount, max_amount, daily_limit, updated_at)| {
+                Ok(TransferLimit {
+                    operation: operation.parse()?,
+                    min_amount,
+                    max_amount,
+                    daily_limit,
+                    updated_at,
+                })
+            })
+            .collect()
+    }
+
+    /// Limits for one operation, if configured
+    #[allow(clippy::type_complexity)]
+    pub async fn get(&self, operation: LimitOperation) -> Result<Option<TransferLimit>, LimitError> {
+        let row: Option<(Option<Decimal>, Option<Decimal>, Option<Decimal>, DateTime<Utc>)> =
+            sqlx::query_as(
+                r#"
+                SELECT min_amount, max_amount, daily_limit, updated_at
+                FROM transfer_limits
+                WHERE operation = $1
+                "#,
+            )
+            .bind(operation.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|(min_amount, max_amount, daily_limit, updated_at)| TransferLimit {
+            operation,
+            min_amount,
+            max_amount,
+            daily_limit,
+            updated_at,
+        }))
+    }
+
+    /// Create or replace the limits for an operation
+    pub async fn upsert(
+        &self,
+        operation: LimitOperation,
+        min_amount: Option<Decimal>,
+        max_amount: Option<Decimal>,
+        daily_limit: Option<Decimal>,
+    ) -> Result<TransferLimit, LimitError> {
+        for value in [min_amount, max_amount, daily_limit].into_iter().flatten() {
+            if value <= Decimal::ZERO {
+                return Err(LimitError::InvalidLimits("limits must be positive".to_string()));
+            }
+        }
+        if let (Some(min), Some(max)) = (min_amount, max_amount) {
+            if min > max {
+                return Err(LimitError::InvalidLimits(
+                    "min_amount must not exceed max_amount".to_string(),
+                ));
+            }
+        }
+
+        let updated_at: DateTime<Utc> = sqlx::query_scalar(
+            r#"
+            INSERT INTO transfer_limits (operation, min_amount, max_amount, daily_limit)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (operation) DO UPDATE
+            SET min_amount = EXCLUDED.min_amount,
+                max_amount = EXCLUDED.max_amount,
+                daily_limit = EXCLUDED.daily_limit,
+                updated_at = NOW()
+            RETURNING updated_at
+            "#,
+        )
+        .bind(operation.as_str())
+        .bind(min_amount)
+        .bind(max_amount)
+        .bind(daily_limit)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(TransferLimit {
+            operation,
+            min_amount,
+            max_amount,
+            daily_limit,
+            updated_at,
+        })
+    }
+
+    /// Remove the limits for an operation
+    pub async fn delete(&self, operation: LimitOperation) -> Result<(), LimitError> {
+        let result = sqlx::query("DELETE FROM transfer_limits WHERE operation = $1")
+            .bind(operation.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(LimitError::NotConfigured(operation));
+        }
+        Ok(())
+    }
+
+    /// Amount moved by the user for this operation over the last 24 hours
+    pub async fn daily_usage(&self, operation: LimitOperation, user_id: Uuid) -> Result<Decimal, LimitError> {
+        let (entry_type, counterparty_user_id) = operation.ledger_pattern(self.system_users);
+
+        let used: Decimal = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(e.amount), 0)
+            FROM ledger_entries e
+            JOIN accounts a ON a.id = e.account_id
+            JOIN ledger_entries o ON o.journal_id = e.journal_id AND o.entry_type <> e.entry_type
+            JOIN accounts oa ON oa.id = o.account_id
+            WHERE a.user_id = $1
+              AND a.account_type = 'user_wallet'
+              AND e.entry_type = $2
+              AND e.created_at > NOW() - INTERVAL '24 hours'
+              AND (($3::uuid IS NULL AND oa.account_type = 'user_wallet') OR oa.user_id = $3)
+            "#,
+        )
+        .bind(user_id)
+        .bind(entry_type)
+        .bind(counterparty_user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(used)
+    }
+
+    /// Enforce the configured limits for a user's command
+    pub async fn check(
+        &self,
+        operation: LimitOperation,
+        user_id: Uuid,
+        amount: Decimal,
+    ) -> Result<(), LimitError> {
+        let Some(limit) = self.get(operation).await? else {
+            return Ok(());
+        };
+
+        limit.check_amount(amount)?;
+
+        if limit.daily_limit.is_some() {
+            let used = self.daily_usage(operation, user_id).await?;
+            limit.check_daily(amount, used)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Limit errors
+#[derive(Debug, thiserror::Error)]
+pub enum LimitError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Unknown limit operation: {0}")]
+    UnknownOperation(String),
+
+    #[error("Invalid limits: {0}")]
+    InvalidLimits(String),
+
+    #[error("No limits configured for {0}")]
+    NotConfigured(LimitOperation),
+
+    #[error("Amount is below the minimum of {min}")]
+    BelowMinimum { min: Decimal },
+
+    #[error("Amount exceeds the maximum of {max}")]
+    AboveMaximum { max: Decimal },
+
+    #[error("Amount exceeds the 24h limit of {limit} ({used} already used)")]
+    DailyLimitExceeded { limit: Decimal, used: Decimal },
+
+    #[error("Amount exceeds this API key's maximum of {max}")]
+    KeyMaxExceeded { max: Decimal },
+
+    #[error("Amount exceeds this API key's 24h limit of {limit} ({used} already used)")]
+    KeyDailyLimitExceeded { limit: Decimal, used: Decimal },
+}
+
+impl From<LimitError> for AppError {
+    fn from(e: LimitError) -> Self {
+        match e {
+            LimitError::BelowMinimum { .. } => AppError::AmountTooSmall(e.to_string()),
+            LimitError::AboveMaximum { .. }
+            | LimitError::DailyLimitExceeded { .. }
+            | LimitError::KeyMaxExceeded { .. }
+            | LimitError::KeyDailyLimitExceeded { .. } => AppError::AmountTooLarge(e.to_string()),
+            LimitError::Database(e) => AppError::Internal(e.to_string()),
+            e => AppError::InvalidRequest(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limit(min: Option<i64>, max: Option<i64>, daily: Option<i64>) -> TransferLimit {
+        TransferLimit {
+            operation: LimitOperation::Transfer,
+            min_amount: min.map(Decimal::from),
+            max_amount: max.map(Decimal::from),
+            daily_limit: daily.map(Decimal::from),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_check_amount() {
+        let limit = limit(Some(1), Some(100), None);
+
+        assert!(limit.check_amount(Decimal::from(50)).is_ok());
+        assert!(limit.check_amount(Decimal::from(100)).is_ok());
+        assert!(matches!(
+            limit.check_amount(Decimal::new(5, 1)),
+            Err(LimitError::BelowMinimum { .. })
+        ));
+        assert!(matches!(
+            limit.check_amount(Decimal::from(101)),
+            Err(LimitError::AboveMaximum { .. })
+        ));
+    }
+
+    #[test]
+    fn test_check_daily() {
+        let limit = limit(None, None, Some(500));
+
+        assert!(limit.check_daily(Decimal::from(100), Decimal::from(400)).is_ok());
+        assert!(matches!(
+            limit.check_daily(Decimal::from(101), Decimal::from(400)),
+            Err(LimitError::DailyLimitExceeded { .. })
+        ));
+        assert!(self::limit(None, None, None)
+            .check_daily(Decimal::from(1_000_000), Decimal::from(1_000_000))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_limit_error_into_app_error() {
+        let err: AppError = LimitError::BelowMinimum { min: Decimal::ONE }.into();
+        assert!(matches!(err, AppError::AmountTooSmall(_)));
+
+        let err: AppError = LimitError::DailyLimitExceeded {
+            limit: Decimal::ONE,
+            used: Decimal::ONE,
+        }
+        .into();
+        assert!(matches!(err, AppError::AmountTooLarge(_)));
+    }
+}
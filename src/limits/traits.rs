@@ -0,0 +1,49 @@
+//! Limit Trait
+//!
+//! The limit checks money-moving handlers depend on, implemented by
+//! [`LimitService`] and by an in-memory fake for tests.
+
+use rust_decimal::Decimal;
+use std::future::Future;
+use uuid::Uuid;
+
+use super::{LimitError, LimitOperation, LimitService};
+
+/// Limit enforcement used by command handlers
+pub trait LimitTrait: Send + Sync {
+    /// Enforce the configured limits for a user's command
+    fn check(
+        &self,
+        operation: LimitOperation,
+        user_id: Uuid,
+        amount: Decimal,
+    ) -> impl Future<Output = Result<(), LimitError>> + Send;
+
+    /// Enforce the API key's cap for the operation (mints and burns)
+    fn check_api_key(
+        &self,
+        operation: LimitOperation,
+        api_key_id: Option<Uuid>,
+        amount: Decimal,
+    ) -> impl Future<Output = Result<(), LimitError>> + Send;
+}
+
+impl LimitTrait for LimitService {
+    fn check(
+        &self,
+        operation: LimitOperation,
+        user_id: Uuid,
+        amount: Decimal,
+    ) -> impl Future<Output = Result<(), LimitError>> + Send {
+        LimitService::check(self, operation, user_id, amount)
+    }
+
+    fn check_api_key(
+        &self,
+        operation: LimitOperation,
+        api_key_id: Option<Uuid>,
+        amount: Decimal,
+    ) -> impl Future<Output = Result<(), LimitError>> + Send {
+        LimitService::check_api_key(self, operation, api_key_id, amount)
+    }
+}
@@ -5,53 +5,172 @@
 
 use std::net::SocketAddr;
 
-use axum::{middleware, Router};
+use axum::{extract::DefaultBodyLimit, middleware, Router};
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand};
+use serde::Serialize;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use finance_atp::{api, Config, db};
+use finance_atp::api::routes::CreateApiKeyRequest;
+use finance_atp::audit::{AuditAction, AuditLogBuilder};
+use finance_atp::config::LogFormat;
+use finance_atp::jobs::{self, JobScheduler, JobSchedulerConfig, MaintenanceReport};
+use finance_atp::{api, self_test, AppState, Config, OperationContext, SharedState, db};
+use uuid::Uuid;
+
+/// How long shutdown waits for an in-progress background job
+const JOB_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 /// Initialize tracing/logging
-fn init_tracing() {
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "finance_atp=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+fn init_tracing(format: LogFormat) {
+    let filter = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "finance_atp=debug,tower_http=debug".into());
+    let registry = tracing_subscriber::registry().with(filter);
+
+    match format {
+        LogFormat::Text => registry.with(tracing_subscriber::fmt::layer()).init(),
+        // Span fields (correlation_id, api_key_id, path) go on every line
+        LogFormat::Json => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .with_current_span(true)
+                    .with_span_list(false),
+            )
+            .init(),
+    }
 }
 
 /// Build the application router
-fn build_router(pool: PgPool) -> Router {
+fn build_router(state: SharedState) -> Router {
     // Create API router with all routes
     let api_router = api::create_router();
 
     // Apply middleware to API routes
     // Note: Axum layers are applied in reverse order (last added = first executed)
-    // Order: logging -> auth -> rate_limit -> handler
+    // Order: logging -> latency budget -> auth -> rate_limit -> tenant scope -> idempotency -> handler
     let protected_routes = api_router
+        // Route layers: run after routing, when path parameters are known.
+        // Idempotent replays are only served once the tenant scope passed.
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::middleware::idempotency_middleware,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::middleware::tenant_scope_middleware,
+        ))
+        .layer(DefaultBodyLimit::max(state.config.max_body_bytes))
         .layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             api::middleware::rate_limit_middleware,
         ))
         .layer(middleware::from_fn_with_state(
-            pool.clone(),
+            state.clone(),
             api::middleware::auth_middleware,
         ))
-        .layer(middleware::from_fn(
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            api::middleware::latency_budget_middleware,
+        ))
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
             api::middleware::logging_middleware,
         ));
 
-    Router::new()
+    let mut router = Router::new()
         // Health check (no auth)
         .route("/health", axum::routing::get(health_check))
         // Protected API routes
-        .nest("/api/v1", protected_routes)
+        .nest("/api/v1", protected_routes);
+
+    // Outside auth so browser preflight requests are answered without a key
+    if let Some(cors) = &state.config.cors {
+        router = router.layer(cors.layer());
+    }
+
+    router
         .layer(TraceLayer::new_for_http())
-        .with_state(pool)
+        .with_state(state)
+}
+
+/// Command line interface
+#[derive(Debug, Parser)]
+#[command(name = "finance_atp", version, about = "ATP currency management backend API")]
+struct Cli {
+    /// Same as the `migrate` command
+    #[arg(long, hide = true)]
+    migrate_only: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Run the HTTP server and background jobs (default)
+    Serve,
+    /// Apply pending database migrations and exit
+    Migrate {
+        /// First record migrations up to this version as applied without
+        /// running them (for databases set up manually with psql)
+        #[arg(long)]
+        baseline: Option<i64>,
+    },
+    /// Create any missing system users and accounts
+    SeedSystemAccounts,
+    /// Verify the audit log hash chain; exits non-zero if it is broken
+    VerifyAuditChain {
+        /// Check only the most recent entries
+        #[arg(long)]
+        limit: Option<i64>,
+    },
+    /// Rebuild balance and ledger projections from the event stream
+    RebuildProjections {
+        /// Rebuild a single account instead of all accounts
+        #[arg(long, conflicts_with = "shadow")]
+        account_id: Option<Uuid>,
+        /// Rebuild balances only, into a shadow table swapped in without
+        /// blocking reads
+        #[arg(long)]
+        shadow: bool,
+        /// With --shadow: verify and report drift, but keep the live table
+        #[arg(long, requires = "shadow")]
+        dry_run: bool,
+    },
+    /// Issue an API key and print it (the key is shown only once)
+    CreateApiKey {
+        #[arg(long)]
+        name: String,
+        /// Comma-separated permissions, e.g. read:users,write:transfers
+        #[arg(long, value_delimiter = ',', required = true)]
+        permissions: Vec<String>,
+        #[arg(long, default_value_t = 1000)]
+        rate_limit_per_minute: i32,
+        /// Requests allowed above the per-minute limit (defaults to RATE_LIMIT_BURST)
+        #[arg(long)]
+        rate_limit_burst: Option<i32>,
+        /// Skip rate limiting for this key (internal trusted services)
+        #[arg(long)]
+        rate_limit_exempt: bool,
+        /// RFC 3339 expiry time
+        #[arg(long)]
+        expires_at: Option<DateTime<Utc>>,
+        /// Comma-separated users the key may act for
+        #[arg(long, value_delimiter = ',')]
+        allowed_user_ids: Vec<Uuid>,
+        /// Tenant of the key (defaults to the default tenant)
+        #[arg(long)]
+        tenant_id: Option<Uuid>,
+    },
+    /// Run a scripted create/mint/transfer/burn scenario against the live
+    /// database and verify balances; exits non-zero if any step fails
+    SelfTest,
 }
 
 /// Health check endpoint
@@ -64,14 +183,19 @@ async fn main() -> anyhow::Result<()> {
     // Load environment variables
     dotenvy::dotenv().ok();
 
-    // Initialize tracing
-    init_tracing();
+    let cli = Cli::parse();
+    let command = match cli.command {
+        None if cli.migrate_only => Command::Migrate { baseline: None },
+        None => Command::Serve,
+        Some(command) => command,
+    };
 
     // Load configuration
     let config = Config::from_env()?;
-    let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
 
-    tracing::info!("Starting financeATP server");
+    // Initialize tracing
+    init_tracing(config.log_format);
+
     tracing::info!("Connecting to database...");
 
     // Create database pool
@@ -80,33 +204,242 @@ async fn main() -> anyhow::Result<()> {
         .connect(&config.database_url)
         .await?;
 
+    let result = match command {
+        Command::Serve => serve(pool.clone(), config).await,
+        Command::Migrate { baseline } => migrate(&pool, baseline).await,
+        // Runs before the system accounts it creates can be resolved
+        Command::SeedSystemAccounts => seed_system_accounts(&pool).await,
+        command => match db::resolve_system_accounts(&pool, config.system_users).await {
+            Ok(Some(system_accounts)) => {
+                run_admin_command(command, AppState::new(pool.clone(), config, system_accounts)).await
+            }
+            Ok(None) => Err(anyhow::anyhow!("System accounts missing; run `finance_atp seed-system-accounts`")),
+            Err(e) => Err(e.into()),
+        },
+    };
+
+    pool.close().await;
+    result
+}
+
+/// Apply pending migrations, optionally recording a baseline first
+async fn migrate(pool: &PgPool, baseline: Option<i64>) -> anyhow::Result<()> {
+    // Record migrations applied manually before migration tracking
+    if let Some(version) = baseline {
+        db::baseline_migrations(pool, version).await?;
+    }
+
+    let applied = db::run_migrations(pool).await?;
+    tracing::info!(applied = applied.len(), "Database migrations up to date");
+    Ok(())
+}
+
+/// Create any missing system users and accounts
+async fn seed_system_accounts(pool: &PgPool) -> anyhow::Result<()> {
+    let created = db::seed_system_accounts(pool).await?;
+    print_json(&serde_json::json!({ "rows_created": created }))
+}
+
+/// One-shot maintenance commands; results are printed as JSON
+async fn run_admin_command(command: Command, state: AppState) -> anyhow::Result<()> {
+    // Actions taken from the CLI are audited without an API key
+    let context = OperationContext::new();
+
+    match command {
+        Command::VerifyAuditChain { limit } => {
+            let result = state.audit.verify_hash_chain(limit).await?;
+            print_json(&result)?;
+            if !result.is_valid {
+                return Err(anyhow::anyhow!("Audit log hash chain is broken"));
+            }
+            Ok(())
+        }
+        Command::RebuildProjections { account_id, shadow, dry_run } => {
+            tracing::warn!(account_id = ?account_id, shadow, dry_run, "Projection rebuild requested from CLI");
+            if shadow {
+                return print_json(&state.projection.rebuild_balances_shadow(dry_run).await?);
+            }
+            let report = match account_id {
+                Some(account_id) => state.projection.rebuild_account(account_id).await?,
+                None => state.projection.rebuild_all().await?,
+            };
+            print_json(&report)
+        }
+        Command::CreateApiKey {
+            name,
+            permissions,
+            rate_limit_per_minute,
+            rate_limit_burst,
+            rate_limit_exempt,
+            expires_at,
+            allowed_user_ids,
+            tenant_id,
+        } => {
+            let request = CreateApiKeyRequest {
+                name,
+                permissions,
+                rate_limit_per_minute,
+                rate_limit_burst,
+                rate_limit_exempt,
+                expires_at,
+                allowed_user_ids: Some(allowed_user_ids),
+                tenant_id,
+            };
+            let created = api::keys::issue_api_key(&state.pool, request).await?;
+
+            let audit_entry = AuditLogBuilder::new(AuditAction::ApiKeyCreated)
+                .resource_type("ApiKey")
+                .resource_id(created.id)
+                .after_state(&serde_json::json!({
+                    "name": created.name,
+                    "key_prefix": created.key_prefix,
+                    "permissions": created.permissions,
+                    "rate_limit_per_minute": created.rate_limit_per_minute,
+                    "rate_limit_burst": created.rate_limit_burst,
+                    "rate_limit_exempt": created.rate_limit_exempt,
+                    "expires_at": created.expires_at,
+                    "allowed_user_ids": created.allowed_user_ids,
+                    "tenant_id": created.tenant_id,
+                    "source": "cli",
+                }));
+            state.audit.record(audit_entry, &context).await;
+
+            print_json(&created)
+        }
+        Command::SelfTest => {
+            let report = self_test::run(&state).await;
+            print_json(&report)?;
+            if !report.passed {
+                return Err(anyhow::anyhow!("Self-test failed"));
+            }
+            Ok(())
+        }
+        Command::Serve | Command::Migrate { .. } | Command::SeedSystemAccounts => unreachable!("handled in main"),
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Run the HTTP server until a shutdown signal
+async fn serve(pool: PgPool, config: Config) -> anyhow::Result<()> {
+    let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
+
+    tracing::info!("Starting financeATP server");
+
+    // Apply pending migrations
+    if config.auto_migrate {
+        migrate(&pool, None).await?;
+    }
+
     // Verify database schema
     if !db::check_schema(&pool).await? {
-        tracing::error!("Database schema is not complete. Please run migrations.");
+        tracing::error!("Database schema is not complete. Set AUTO_MIGRATE=true or run `finance_atp migrate`.");
         return Err(anyhow::anyhow!("Database schema incomplete"));
     }
 
+    // Keep the account_types registry in line with AccountType
+    db::sync_account_types(&pool).await?;
+
+    // Resolve SYSTEM_MINT / SYSTEM_BURN once; mints and burns use these accounts
+    let Some(system_accounts) = db::resolve_system_accounts(&pool, config.system_users).await? else {
+        tracing::error!("System accounts missing. Run `finance_atp seed-system-accounts`.");
+        return Err(anyhow::anyhow!("System accounts missing"));
+    };
+
+    // Inserts fail without a partition for the current month, e.g. after
+    // the service was down across a month boundary
+    let backfilled = jobs::backfill_partitions(&pool, &JobSchedulerConfig::from_config(&config).partitions).await?;
+    if !backfilled.is_empty() {
+        tracing::warn!(partitions = ?backfilled, "Created missing partitions");
+    }
+
     tracing::info!("Database connected successfully");
     tracing::info!("Listening on http://{}", addr);
 
+    // Start background jobs (maintenance, reconciliation, webhook dispatch, notifications)
+    let shutdown = CancellationToken::new();
+    let scheduler =
+        JobScheduler::with_config(pool.clone(), JobSchedulerConfig::from_config(&config)).start(shutdown.clone());
+
     // Build router and start server
-    let app = build_router(pool.clone());
+    let state = AppState::new(pool.clone(), config, system_accounts).shared();
+    let notifier = state.event_notifier.listen(pool);
+    let cache_invalidation = state
+        .projection
+        .balance_cache()
+        .map(|cache| cache.invalidate_on_events(state.event_notifier.subscribe()));
+    let app = build_router(state);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
     
     // M140: Graceful shutdown
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown({
+            let shutdown = shutdown.clone();
+            async move {
+                shutdown_signal().await;
+                // Stop scheduling jobs while in-flight requests drain
+                shutdown.cancel();
+            }
+        })
         .await?;
 
     // Cleanup
     tracing::info!("Server shutting down...");
-    pool.close().await;
-    tracing::info!("Database connections closed. Goodbye!");
+    shutdown.cancel();
+    notifier.abort();
+    if let Some(cache_invalidation) = cache_invalidation {
+        cache_invalidation.abort();
+    }
+    drain_scheduler(scheduler).await;
+    tracing::info!("Server stopped");
 
     Ok(())
 }
 
+/// Wait for the job scheduler to finish its in-progress job and log what it did
+async fn drain_scheduler(mut scheduler: JoinHandle<MaintenanceReport>) {
+    let report = match tokio::time::timeout(JOB_DRAIN_TIMEOUT, &mut scheduler).await {
+        Ok(Ok(report)) => report,
+        Ok(Err(e)) => {
+            tracing::error!(error = %e, "Job scheduler task failed");
+            return;
+        }
+        Err(_) => {
+            tracing::warn!(
+                timeout_secs = JOB_DRAIN_TIMEOUT.as_secs(),
+                "Background job did not finish in time; aborting it"
+            );
+            scheduler.abort();
+            return;
+        }
+    };
+
+    tracing::info!(
+        rate_limit_buckets_cleaned = report.rate_limit_buckets_cleaned,
+        idempotency_keys_reset = report.idempotency_keys_reset,
+        idempotency_keys_deleted = report.idempotency_keys_deleted,
+        partitions_created = report.partitions_created.len(),
+        partitions_dropped = report.partitions_dropped.len(),
+        reconciliation_discrepancies = report.reconciliation_discrepancies,
+        webhook_attempts = report.webhook_attempts,
+        snapshots_created = report.snapshots_created,
+        snapshots_pruned = report.snapshots_pruned,
+        users_anonymized = report.users_anonymized,
+        notification_events_processed = report.notification_events_processed,
+        daily_stats_days = report.daily_stats_days,
+        audit_entries_verified = report.audit_entries_verified,
+        audit_entries_archived = report.audit_entries_archived,
+        pending_transfers_expired = report.pending_transfers_expired,
+        event_days_digested = report.event_days_digested,
+        errors = report.errors.len(),
+        "Background jobs stopped"
+    );
+}
+
 /// M140: Shutdown signal handler for graceful shutdown
 async fn shutdown_signal() {
     let ctrl_c = async {
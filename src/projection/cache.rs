@@ -0,0 +1,154 @@
+//! Balance Cache
+//!
+//! Optional in-process cache in front of `ProjectionService::get_user_balance`,
+//! the most frequent projection read. Balances are keyed by account; the
+//! user to primary wallet mapping never changes and is cached separately.
+//!
+//! An account's entry is dropped whenever the projection updates its
+//! balance, and again when the committed event reaches the event
+//! notification stream (`invalidate_on_events`), which also covers writes
+//! made by other instances. The TTL bounds staleness if a notification is
+//! lost.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use moka::sync::Cache;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use tokio::sync::broadcast::{self, error::RecvError};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::event_store::EventNotification;
+
+/// Cached primary wallet balances
+#[derive(Debug, Clone)]
+pub struct BalanceCache {
+    /// user_id -> primary wallet account_id
+    wallets: Cache<Uuid, Uuid>,
+    /// account_id -> balance
+    balances: Cache<Uuid, Decimal>,
+    stats: Arc<CacheCounters>,
+}
+
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+/// Point-in-time copy of the cache counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct BalanceCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub invalidations: u64,
+    /// Balances currently cached
+    pub entries: u64,
+}
+
+impl BalanceCache {
+    /// Cache up to `capacity` balances, each for at most `ttl`
+    pub fn new(capacity: u64, ttl: Duration) -> Self {
+        Self {
+            wallets: Cache::new(capacity),
+            balances: Cache::builder().max_capacity(capacity).time_to_live(ttl).build(),
+            stats: Arc::default(),
+        }
+    }
+
+    /// Cache configured by BALANCE_CACHE_CAPACITY (None if disabled)
+    pub fn from_config(config: &Config) -> Option<Self> {
+        config
+            .balance_cache_capacity
+            .map(|capacity| Self::new(capacity, Duration::from_secs(config.balance_cache_ttl_secs)))
+    }
+
+    /// Cached balance of the user's primary wallet
+    pub fn get(&self, user_id: Uuid) -> Option<Decimal> {
+        let balance = self
+            .wallets
+            .get(&user_id)
+            .and_then(|account_id| self.balances.get(&account_id));
+
+        let counter = if balance.is_some() { &self.stats.hits } else { &self.stats.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        balance
+    }
+
+    /// Remember a balance read from the projection
+    pub fn insert(&self, user_id: Uuid, account_id: Uuid, balance: Decimal) {
+        self.wallets.insert(user_id, account_id);
+        self.balances.insert(account_id, balance);
+    }
+
+    /// Drop the balance of an account whose projection changed
+    pub fn invalidate(&self, account_id: Uuid) {
+        self.balances.invalidate(&account_id);
+        self.stats.invalidations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Drop every balance (e.g. after a projection rebuild)
+    pub fn invalidate_all(&self) {
+        self.balances.invalidate_all();
+        self.stats.invalidations.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn stats(&self) -> BalanceCacheStats {
+        // Apply pending inserts and evictions so entry_count is current
+        self.balances.run_pending_tasks();
+        BalanceCacheStats {
+            hits: self.stats.hits.load(Ordering::Relaxed),
+            misses: self.stats.misses.load(Ordering::Relaxed),
+            invalidations: self.stats.invalidations.load(Ordering::Relaxed),
+            entries: self.balances.entry_count(),
+        }
+    }
+
+    /// Drop account balances as their events are committed, from any
+    /// instance. Runs until the notifier is dropped.
+    pub fn invalidate_on_events(
+        &self,
+        mut events: broadcast::Receiver<EventNotification>,
+    ) -> tokio::task::JoinHandle<()> {
+        let cache = self.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) if event.aggregate_type == "Account" => cache.invalidate(event.aggregate_id),
+                    Ok(_) => {}
+                    // Missed notifications may have touched any account
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Balance cache missed event notifications, clearing");
+                        cache.invalidate_all();
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_balance_cache_hit_miss_and_invalidate() {
+        let cache = BalanceCache::new(100, Duration::from_secs(60));
+        let (user_id, account_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(cache.get(user_id), None);
+        cache.insert(user_id, account_id, Decimal::from(10));
+        assert_eq!(cache.get(user_id), Some(Decimal::from(10)));
+
+        cache.invalidate(account_id);
+        assert_eq!(cache.get(user_id), None);
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.invalidations), (1, 2, 1));
+    }
+}
@@ -0,0 +1,281 @@
+//! In-Memory Projection
+//!
+//! [`ProjectionTrait`] implementation keeping accounts, balances, holds and
+//! ledger legs in process memory, for handler tests that run without
+//! PostgreSQL.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::aggregate::{Account, Aggregate, Transfer};
+use crate::domain::{AccountType, Amount};
+
+use super::{AccountHold, AccountRecord, LedgerDescriptions, LegVersions, ProjectionError, ProjectionTrait};
+
+/// One ledger leg written by [`InMemoryProjection::apply_transfer`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryLedgerEntry {
+    pub journal_id: Uuid,
+    pub account_id: Uuid,
+    pub amount: Decimal,
+    /// "debit" or "credit"
+    pub entry_type: &'static str,
+    pub description: Option<String>,
+}
+
+/// Balances and ledger shared between clones
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryProjection {
+    state: Arc<Mutex<ProjectionState>>,
+}
+
+#[derive(Debug, Default)]
+struct ProjectionState {
+    /// account ID -> (user ID, type, name)
+    accounts: HashMap<Uuid, (Uuid, AccountType, Option<String>)>,
+    balances: HashMap<Uuid, Decimal>,
+    ledger: Vec<MemoryLedgerEntry>,
+    /// hold ID -> (account ID, to account ID, amount, status)
+    holds: HashMap<Uuid, (Uuid, Uuid, Decimal, &'static str)>,
+    failed_transfers: Vec<Uuid>,
+    pending_transfers: Vec<Uuid>,
+}
+
+impl ProjectionState {
+    fn write_legs(
+        &mut self,
+        journal_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: Decimal,
+        descriptions: LedgerDescriptions<'_>,
+    ) {
+        *self.balances.entry(from_account_id).or_insert(Decimal::ZERO) -= amount;
+        *self.balances.entry(to_account_id).or_insert(Decimal::ZERO) += amount;
+
+        let legs = [
+            (from_account_id, "debit", descriptions.debit),
+            (to_account_id, "credit", descriptions.credit),
+        ];
+        for (account_id, entry_type, description) in legs {
+            self.ledger.push(MemoryLedgerEntry {
+                journal_id,
+                account_id,
+                amount,
+                entry_type,
+                description: description.map(str::to_string),
+            });
+        }
+    }
+}
+
+impl InMemoryProjection {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an account, as creating it adds it to accounts (test setup)
+    pub fn add_account(&self, account: &Account) {
+        self.lock().accounts.insert(
+            account.id(),
+            (account.user_id(), account.account_type(), account.name().map(str::to_string)),
+        );
+    }
+
+    /// Set an account's balance directly (test setup)
+    pub fn set_balance(&self, account_id: Uuid, balance: Decimal) {
+        self.lock().balances.insert(account_id, balance);
+    }
+
+    /// Ledger legs written so far, oldest first
+    pub fn ledger(&self) -> Vec<MemoryLedgerEntry> {
+        self.lock().ledger.clone()
+    }
+
+    /// Transfers recorded as failed, oldest first
+    pub fn failed_transfers(&self) -> Vec<Uuid> {
+        self.lock().failed_transfers.clone()
+    }
+
+    /// Transfers recorded as awaiting approval, oldest first
+    pub fn pending_transfers(&self) -> Vec<Uuid> {
+        self.lock().pending_transfers.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, ProjectionState> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl ProjectionTrait for InMemoryProjection {
+    /// Matches [`crate::event_store::InMemoryEventStore`]
+    type Tx = ();
+
+    async fn create_account_balance(&self, account_id: Uuid, _event_id: Uuid) -> Result<(), ProjectionError> {
+        self.lock().balances.entry(account_id).or_insert(Decimal::ZERO);
+        Ok(())
+    }
+
+    async fn apply_transfer(
+        &self,
+        transfer_id: Uuid,
+        _event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        _versions: LegVersions,
+    ) -> Result<(), ProjectionError> {
+        self.lock()
+            .write_legs(transfer_id, from_account_id, to_account_id, amount.value(), descriptions);
+        Ok(())
+    }
+
+    async fn apply_transfer_in_tx(
+        &self,
+        _tx: &mut Self::Tx,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> Result<(), ProjectionError> {
+        self.apply_transfer(transfer_id, event_id, from_account_id, to_account_id, amount, descriptions, versions)
+            .await
+    }
+
+    async fn apply_mint(
+        &self,
+        mint_id: Uuid,
+        event_id: Uuid,
+        mint_source_account_id: Uuid,
+        recipient_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> Result<(), ProjectionError> {
+        self.apply_transfer(mint_id, event_id, mint_source_account_id, recipient_account_id, amount, descriptions, versions)
+            .await
+    }
+
+    async fn record_failed_transfer_in_tx(&self, _tx: &mut Self::Tx, transfer: &Transfer) -> Result<(), ProjectionError> {
+        if transfer.failure_reason().is_none() {
+            return Err(ProjectionError::InvalidEvent(transfer.id(), "transfer has not failed".to_string()));
+        }
+        let mut state = self.lock();
+        if !state.failed_transfers.contains(&transfer.id()) {
+            state.failed_transfers.push(transfer.id());
+        }
+        Ok(())
+    }
+
+    async fn record_pending_transfer_in_tx(
+        &self,
+        _tx: &mut Self::Tx,
+        transfer: &Transfer,
+        _requested_by_api_key_id: Option<Uuid>,
+        _expires_at: DateTime<Utc>,
+    ) -> Result<(), ProjectionError> {
+        self.lock().pending_transfers.push(transfer.id());
+        Ok(())
+    }
+
+    async fn apply_hold_in_tx(
+        &self,
+        _tx: &mut Self::Tx,
+        hold_id: Uuid,
+        account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        _description: &str,
+    ) -> Result<(), ProjectionError> {
+        self.lock()
+            .holds
+            .entry(hold_id)
+            .or_insert((account_id, to_account_id, amount.value(), "held"));
+        Ok(())
+    }
+
+    async fn apply_hold_capture_in_tx(
+        &self,
+        _tx: &mut Self::Tx,
+        hold_id: Uuid,
+        transfer_id: Uuid,
+        _event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        _versions: LegVersions,
+    ) -> Result<(), ProjectionError> {
+        let mut state = self.lock();
+        state.write_legs(transfer_id, from_account_id, to_account_id, amount.value(), descriptions);
+        if let Some(hold) = state.holds.get_mut(&hold_id) {
+            hold.3 = "captured";
+        }
+        Ok(())
+    }
+
+    async fn apply_hold_release_in_tx(&self, _tx: &mut Self::Tx, hold_id: Uuid) -> Result<(), ProjectionError> {
+        if let Some(hold) = self.lock().holds.get_mut(&hold_id) {
+            hold.3 = "released";
+        }
+        Ok(())
+    }
+
+    async fn get_balance(&self, account_id: Uuid) -> Result<Decimal, ProjectionError> {
+        Ok(self.lock().balances.get(&account_id).copied().unwrap_or(Decimal::ZERO))
+    }
+
+    async fn wallet_account_id(&self, user_id: Uuid) -> Result<Option<Uuid>, ProjectionError> {
+        Ok(self.lock().accounts.iter().find_map(|(account_id, (owner, account_type, name))| {
+            (*owner == user_id && *account_type == AccountType::UserWallet && name.is_none()).then_some(*account_id)
+        }))
+    }
+
+    async fn user_wallet_account_id(&self, user_id: Uuid, account_id: Uuid) -> Result<Option<Uuid>, ProjectionError> {
+        Ok(self
+            .lock()
+            .accounts
+            .get(&account_id)
+            .filter(|(owner, account_type, _)| *owner == user_id && *account_type == AccountType::UserWallet)
+            .map(|_| account_id))
+    }
+
+    async fn account_record(&self, account_id: Uuid) -> Result<Option<AccountRecord>, ProjectionError> {
+        let state = self.lock();
+        Ok(state.accounts.get(&account_id).map(|(user_id, account_type, _)| AccountRecord {
+            id: account_id,
+            user_id: *user_id,
+            account_type: account_type.as_str().to_string(),
+            balance: state.balances.get(&account_id).copied().unwrap_or(Decimal::ZERO),
+        }))
+    }
+
+    async fn get_hold(&self, hold_id: Uuid) -> Result<Option<AccountHold>, ProjectionError> {
+        let state = self.lock();
+        let Some(&(account_id, to_account_id, amount, status)) = state.holds.get(&hold_id) else {
+            return Ok(None);
+        };
+        let owner = |account_id: Uuid| state.accounts.get(&account_id).map(|(user_id, _, _)| *user_id);
+        // Like the join on accounts, a hold between unknown accounts is not found
+        let (Some(from_user_id), Some(to_user_id)) = (owner(account_id), owner(to_account_id)) else {
+            return Ok(None);
+        };
+
+        Ok(Some(AccountHold {
+            id: hold_id,
+            account_id,
+            to_account_id,
+            from_user_id,
+            to_user_id,
+            amount,
+            status: status.to_string(),
+        }))
+    }
+}
@@ -3,6 +3,24 @@
 //! Updates read-model tables (projections) from events.
 //! Projections are optimized for queries and derived from events.
 
+mod cache;
+#[cfg(any(test, feature = "test-util"))]
+mod memory;
+mod notifications;
 mod service;
+mod traits;
 
-pub use service::ProjectionService;
+#[cfg(any(test, feature = "test-util"))]
+pub use memory::{InMemoryProjection, MemoryLedgerEntry};
+
+pub use cache::{BalanceCache, BalanceCacheStats};
+pub use notifications::{
+    project_notifications, user_notifications, Notification, NotificationKind,
+    NOTIFICATIONS_SUBSCRIPTION,
+};
+
+pub use service::{
+    AccountBalance, AccountHold, AccountLag, AccountRecord, BalanceDrift, LagBucket, ProjectionError, ProjectionService, ProjectionStatus, RebuildReport, ShadowRebuildReport, StatementCursor, StatementLine, SupplyReport,
+    JournalType, LedgerDescriptions, LegVersions, TransferCursor, TransferFilter, TransferStatusFilter, TransferSummary, WalletBalance,
+};
+pub use traits::ProjectionTrait;
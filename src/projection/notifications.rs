@@ -0,0 +1,238 @@
+//! User Notifications
+//!
+//! Activity feed of user wallets (credits received, debits executed,
+//! freezes), projected from Account events by a catch-up subscription so the
+//! frontend does not have to interpret raw events. Rows are keyed by the
+//! source event, which keeps at-least-once delivery idempotent.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::ProjectionError;
+use crate::domain::AccountEvent;
+use crate::event_store::{StoredEvent, Subscription};
+
+/// Subscription name (row in the subscriptions table)
+pub const NOTIFICATIONS_SUBSCRIPTION: &str = "user_notifications";
+
+/// Events fetched per batch
+const NOTIFICATIONS_BATCH_SIZE: i64 = 500;
+
+/// What happened to the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    CreditReceived,
+    DebitExecuted,
+    AccountFrozen,
+    AccountClosed,
+}
+
+impl NotificationKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationKind::CreditReceived => "credit_received",
+            NotificationKind::DebitExecuted => "debit_executed",
+            NotificationKind::AccountFrozen => "account_frozen",
+            NotificationKind::AccountClosed => "account_closed",
+        }
+    }
+}
+
+/// One entry of a user's activity feed
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    pub event_id: Uuid,
+    pub kind: String,
+    pub account_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transfer_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Notification fields derived from one Account event
+#[derive(Debug, Clone, PartialEq)]
+struct NotificationRecord {
+    kind: NotificationKind,
+    amount: Option<Decimal>,
+    transfer_id: Option<Uuid>,
+    description: Option<String>,
+}
+
+/// Map an Account event to a notification (None for events not in the feed)
+fn notification_for(event: &AccountEvent) -> Option<NotificationRecord> {
+    match event {
+        AccountEvent::MoneyCredited {
+            amount,
+            transfer_id,
+            description,
+            ..
+        } => Some(NotificationRecord {
+            kind: NotificationKind::CreditReceived,
+            amount: Some(*amount),
+            transfer_id: Some(*transfer_id),
+            description: Some(description.clone()),
+        }),
+        AccountEvent::MoneyDebited {
+            amount,
+            transfer_id,
+            description,
+            ..
+        } => Some(NotificationRecord {
+            kind: NotificationKind::DebitExecuted,
+            amount: Some(*amount),
+            transfer_id: Some(*transfer_id),
+            description: Some(description.clone()),
+        }),
+        AccountEvent::HoldCaptured {
+            amount, transfer_id, ..
+        } => Some(NotificationRecord {
+            kind: NotificationKind::DebitExecuted,
+            amount: Some(*amount),
+            transfer_id: Some(*transfer_id),
+            description: None,
+        }),
+        AccountEvent::AccountFrozen { reason, .. } => Some(NotificationRecord {
+            kind: NotificationKind::AccountFrozen,
+            amount: None,
+            transfer_id: None,
+            description: Some(reason.clone()),
+        }),
+        AccountEvent::AccountClosed { reason, .. } => Some(NotificationRecord {
+            kind: NotificationKind::AccountClosed,
+            amount: None,
+            transfer_id: None,
+            description: reason.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Write the notification for one stored event (no-op for other events,
+/// non-wallet accounts, and events already projected)
+async fn apply_notification(pool: &PgPool, event: &StoredEvent) -> Result<(), ProjectionError> {
+    if event.aggregate_type != "Account" {
+        return Ok(());
+    }
+
+    let account_event: AccountEvent = serde_json::from_value(event.event_data.clone())
+        .map_err(|e| ProjectionError::InvalidEvent(event.id, e.to_string()))?;
+    let Some(record) = notification_for(&account_event) else {
+        return Ok(());
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO user_notifications (event_id, user_id, account_id, kind, amount, transfer_id, description, created_at)
+        SELECT $1, a.user_id, a.id, $3, $4, $5, $6, $7
+        FROM accounts a
+        WHERE a.id = $2 AND a.account_type = 'user_wallet'
+        ON CONFLICT (event_id) DO NOTHING
+        "#,
+        event.id,
+        event.aggregate_id,
+        record.kind.as_str(),
+        record.amount,
+        record.transfer_id,
+        record.description,
+        event.created_at
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Project all events since the subscription's checkpoint.
+/// Returns the number of events processed.
+pub async fn project_notifications(pool: &PgPool) -> Result<u64, ProjectionError> {
+    let mut subscription = Subscription::open(pool.clone(), NOTIFICATIONS_SUBSCRIPTION).await?;
+    subscription
+        .catch_up(NOTIFICATIONS_BATCH_SIZE, |event| async move {
+            apply_notification(pool, &event).await
+        })
+        .await
+}
+
+/// Notifications of a user newer than `since`, newest first
+pub async fn user_notifications(
+    pool: &PgPool,
+    user_id: Uuid,
+    since: Option<DateTime<Utc>>,
+    limit: i64,
+) -> Result<Vec<Notification>, ProjectionError> {
+    let notifications = sqlx::query_as!(
+        Notification,
+        r#"
+        SELECT event_id, kind, account_id, amount, transfer_id, description, created_at
+        FROM user_notifications
+        WHERE user_id = $1
+          AND ($2::timestamptz IS NULL OR created_at > $2)
+        ORDER BY created_at DESC, event_id DESC
+        LIMIT $3
+        "#,
+        user_id,
+        since,
+        limit
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(notifications)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notification_for_account_events() {
+        let account_id = Uuid::new_v4();
+        let transfer_id = Uuid::new_v4();
+
+        let credited = AccountEvent::MoneyCredited {
+            account_id,
+            amount: Decimal::new(1500, 2),
+            transfer_id,
+            description: "Lunch".to_string(),
+            credited_at: Utc::now(),
+            category: None,
+        };
+        let record = notification_for(&credited).unwrap();
+        assert_eq!(record.kind, NotificationKind::CreditReceived);
+        assert_eq!(record.amount, Some(Decimal::new(1500, 2)));
+        assert_eq!(record.transfer_id, Some(transfer_id));
+
+        let frozen = AccountEvent::AccountFrozen {
+            account_id,
+            reason: "Suspicious activity".to_string(),
+            frozen_at: Utc::now(),
+        };
+        let record = notification_for(&frozen).unwrap();
+        assert_eq!(record.kind, NotificationKind::AccountFrozen);
+        assert_eq!(record.amount, None);
+
+        let unfrozen = AccountEvent::AccountUnfrozen {
+            account_id,
+            unfrozen_at: Utc::now(),
+        };
+        assert!(notification_for(&unfrozen).is_none());
+
+        let closed = AccountEvent::AccountClosed {
+            account_id,
+            reason: Some("Customer request".to_string()),
+            swept_to_account_id: None,
+            closed_at: Utc::now(),
+        };
+        let record = notification_for(&closed).unwrap();
+        assert_eq!(record.kind, NotificationKind::AccountClosed);
+        assert_eq!(record.description, Some("Customer request".to_string()));
+    }
+}
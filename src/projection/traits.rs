@@ -0,0 +1,314 @@
+//! Projection Trait
+//!
+//! The read-model updates command handlers depend on, implemented by
+//! [`ProjectionService`] and by an in-memory fake for tests.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sqlx::{Postgres, Transaction};
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::aggregate::Transfer;
+use crate::domain::Amount;
+
+use super::{AccountHold, AccountRecord, LedgerDescriptions, LegVersions, ProjectionError, ProjectionService};
+
+/// Read-model updates used by command handlers
+pub trait ProjectionTrait: Send + Sync {
+    /// Transaction the `*_in_tx` updates write in (the event store's append
+    /// transaction, see [`crate::event_store::EventStoreTrait::Tx`])
+    type Tx: Send;
+
+    /// Create the zero balance of a new account
+    fn create_account_balance(
+        &self,
+        account_id: Uuid,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Move `amount` between two accounts and write the ledger legs
+    #[allow(clippy::too_many_arguments)]
+    fn apply_transfer(
+        &self,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// [`Self::apply_transfer`] in the transaction that appends its events
+    #[allow(clippy::too_many_arguments)]
+    fn apply_transfer_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Move `amount` from SYSTEM_MINT (which may go negative) to the
+    /// recipient and write the ledger legs
+    #[allow(clippy::too_many_arguments)]
+    fn apply_mint(
+        &self,
+        mint_id: Uuid,
+        event_id: Uuid,
+        mint_source_account_id: Uuid,
+        recipient_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Record a failed transfer, in the transaction that appends its
+    /// TransferFailed event
+    fn record_failed_transfer_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        transfer: &Transfer,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Add a transfer awaiting approval, in the transaction that appends its
+    /// TransferApprovalRequested event
+    fn record_pending_transfer_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        transfer: &Transfer,
+        requested_by_api_key_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Add a hold placed on `account_id` for `to_account_id`
+    fn apply_hold_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        hold_id: Uuid,
+        account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        description: &str,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Move a captured hold's amount like a transfer and close the hold
+    #[allow(clippy::too_many_arguments)]
+    fn apply_hold_capture_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        hold_id: Uuid,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Mark a hold as released
+    fn apply_hold_release_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        hold_id: Uuid,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send;
+
+    /// Current balance of an account (zero when it has none)
+    fn get_balance(&self, account_id: Uuid) -> impl Future<Output = Result<Decimal, ProjectionError>> + Send;
+
+    /// Primary wallet of a user
+    fn wallet_account_id(&self, user_id: Uuid) -> impl Future<Output = Result<Option<Uuid>, ProjectionError>> + Send;
+
+    /// `account_id` if it is one of the user's wallet accounts
+    fn user_wallet_account_id(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+    ) -> impl Future<Output = Result<Option<Uuid>, ProjectionError>> + Send;
+
+    /// Owner, type and projected balance of an account
+    fn account_record(
+        &self,
+        account_id: Uuid,
+    ) -> impl Future<Output = Result<Option<AccountRecord>, ProjectionError>> + Send;
+
+    /// Hold by ID
+    fn get_hold(&self, hold_id: Uuid) -> impl Future<Output = Result<Option<AccountHold>, ProjectionError>> + Send;
+}
+
+impl ProjectionTrait for ProjectionService {
+    type Tx = Transaction<'static, Postgres>;
+
+    fn create_account_balance(
+        &self,
+        account_id: Uuid,
+        event_id: Uuid,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::create_account_balance(self, account_id, event_id)
+    }
+
+    fn apply_transfer(
+        &self,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::apply_transfer(
+            self,
+            transfer_id,
+            event_id,
+            from_account_id,
+            to_account_id,
+            amount,
+            descriptions,
+            versions,
+        )
+    }
+
+    fn apply_transfer_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::apply_transfer_in_tx(
+            self,
+            tx,
+            transfer_id,
+            event_id,
+            from_account_id,
+            to_account_id,
+            amount,
+            descriptions,
+            versions,
+        )
+    }
+
+    fn apply_mint(
+        &self,
+        mint_id: Uuid,
+        event_id: Uuid,
+        mint_source_account_id: Uuid,
+        recipient_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::apply_mint(
+            self,
+            mint_id,
+            event_id,
+            mint_source_account_id,
+            recipient_account_id,
+            amount,
+            descriptions,
+            versions,
+        )
+    }
+
+    fn record_failed_transfer_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        transfer: &Transfer,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::record_failed_transfer_in_tx(self, tx, transfer)
+    }
+
+    fn record_pending_transfer_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        transfer: &Transfer,
+        requested_by_api_key_id: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::record_pending_transfer_in_tx(self, tx, transfer, requested_by_api_key_id, expires_at)
+    }
+
+    fn apply_hold_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        hold_id: Uuid,
+        account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        description: &str,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::apply_hold_in_tx(self, tx, hold_id, account_id, to_account_id, amount, description)
+    }
+
+    fn apply_hold_capture_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        hold_id: Uuid,
+        transfer_id: Uuid,
+        event_id: Uuid,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+        amount: &Amount,
+        descriptions: LedgerDescriptions<'_>,
+        versions: LegVersions,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::apply_hold_capture_in_tx(
+            self,
+            tx,
+            hold_id,
+            transfer_id,
+            event_id,
+            from_account_id,
+            to_account_id,
+            amount,
+            descriptions,
+            versions,
+        )
+    }
+
+    fn apply_hold_release_in_tx(
+        &self,
+        tx: &mut Self::Tx,
+        hold_id: Uuid,
+    ) -> impl Future<Output = Result<(), ProjectionError>> + Send {
+        ProjectionService::apply_hold_release_in_tx(self, tx, hold_id)
+    }
+
+    fn get_balance(&self, account_id: Uuid) -> impl Future<Output = Result<Decimal, ProjectionError>> + Send {
+        ProjectionService::get_balance(self, account_id)
+    }
+
+    fn wallet_account_id(&self, user_id: Uuid) -> impl Future<Output = Result<Option<Uuid>, ProjectionError>> + Send {
+        ProjectionService::wallet_account_id(self, user_id)
+    }
+
+    fn user_wallet_account_id(
+        &self,
+        user_id: Uuid,
+        account_id: Uuid,
+    ) -> impl Future<Output = Result<Option<Uuid>, ProjectionError>> + Send {
+        ProjectionService::user_wallet_account_id(self, user_id, account_id)
+    }
+
+    fn account_record(
+        &self,
+        account_id: Uuid,
+    ) -> impl Future<Output = Result<Option<AccountRecord>, ProjectionError>> + Send {
+        ProjectionService::account_record(self, account_id)
+    }
+
+    fn get_hold(&self, hold_id: Uuid) -> impl Future<Output = Result<Option<AccountHold>, ProjectionError>> + Send {
+        ProjectionService::get_hold(self, hold_id)
+    }
+}
@@ -0,0 +1,204 @@
+//! Account freeze status query
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::QueryHandler;
+use crate::domain::{AccountEvent, OperationContext};
+use crate::error::AppError;
+
+/// Current freeze state of an account and how it got there
+#[derive(Debug, Clone, Copy)]
+pub struct GetAccountStatusQuery {
+    pub account_id: Uuid,
+}
+
+/// Who requested a freeze or unfreeze (from the event context)
+#[derive(Debug, Clone, Serialize)]
+pub struct FreezeActor {
+    pub api_key_id: Option<Uuid>,
+    pub request_user_id: Option<Uuid>,
+    pub correlation_id: Option<Uuid>,
+}
+
+/// One AccountFrozen / AccountUnfrozen / AccountClosed event
+#[derive(Debug, Clone, Serialize)]
+pub struct FreezeChange {
+    pub event_id: Uuid,
+    pub version: i64,
+    /// "frozen", "unfrozen" or "closed"
+    pub action: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub changed_by: FreezeActor,
+    pub changed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountFreezeStatus {
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    /// "active", "frozen" or "closed"
+    pub status: &'static str,
+    pub frozen: bool,
+    /// Reason of the current freeze
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub freeze_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frozen_by: Option<FreezeActor>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frozen_at: Option<DateTime<Utc>>,
+    /// Freezes and unfreezes, oldest first
+    pub history: Vec<FreezeChange>,
+}
+
+impl FreezeChange {
+    /// Build a change from a stored event; None for other event types
+    fn from_event(event_id: Uuid, version: i64, event: AccountEvent, context: OperationContext) -> Option<Self> {
+        let (action, reason, changed_at) = match event {
+            AccountEvent::AccountFrozen { reason, frozen_at, .. } => ("frozen", Some(reason), frozen_at),
+            AccountEvent::AccountUnfrozen { unfrozen_at, .. } => ("unfrozen", None, unfrozen_at),
+            AccountEvent::AccountClosed { reason, closed_at, .. } => ("closed", reason, closed_at),
+            _ => return None,
+        };
+
+        Some(Self {
+            event_id,
+            version,
+            action,
+            reason,
+            changed_by: FreezeActor {
+                api_key_id: context.api_key_id,
+                request_user_id: context.request_user_id,
+                correlation_id: context.correlation_id,
+            },
+            changed_at,
+        })
+    }
+}
+
+impl AccountFreezeStatus {
+    /// Current state from the chronological freeze history
+    fn from_history(account_id: Uuid, user_id: Uuid, history: Vec<FreezeChange>) -> Self {
+        let closed = history.last().is_some_and(|change| change.action == "closed");
+        let current = history.last().filter(|change| change.action == "frozen");
+
+        Self {
+            account_id,
+            user_id,
+            status: match (closed, current) {
+                (true, _) => "closed",
+                (false, Some(_)) => "frozen",
+                (false, None) => "active",
+            },
+            frozen: current.is_some(),
+            freeze_reason: current.and_then(|change| change.reason.clone()),
+            frozen_by: current.map(|change| change.changed_by.clone()),
+            frozen_at: current.map(|change| change.changed_at),
+            history,
+        }
+    }
+}
+
+impl QueryHandler {
+    pub async fn get_account_status(&self, query: &GetAccountStatusQuery) -> Result<AccountFreezeStatus, AppError> {
+        let user_id: Uuid = sqlx::query_scalar("SELECT user_id FROM accounts WHERE id = $1")
+            .bind(query.account_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::AccountNotFound(query.account_id.to_string()))?;
+
+        let events: Vec<(Uuid, i64, serde_json::Value, serde_json::Value)> = sqlx::query_as(
+            r#"
+            SELECT id, version, event_data, context
+            FROM events
+            WHERE aggregate_type = 'Account'
+              AND aggregate_id = $1
+              AND event_type IN ('AccountFrozen', 'AccountUnfrozen', 'AccountClosed')
+            ORDER BY version ASC
+            "#,
+        )
+        .bind(query.account_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut history = Vec::with_capacity(events.len());
+        for (event_id, version, data, context) in events {
+            let event: AccountEvent =
+                serde_json::from_value(data).map_err(|e| AppError::Internal(format!("Invalid event data: {}", e)))?;
+            let context: OperationContext = serde_json::from_value(context).unwrap_or_default();
+            history.extend(FreezeChange::from_event(event_id, version, event, context));
+        }
+
+        Ok(AccountFreezeStatus::from_history(query.account_id, user_id, history))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(version: i64, event: AccountEvent, api_key_id: Uuid) -> FreezeChange {
+        let context = OperationContext::new().with_api_key(api_key_id);
+        FreezeChange::from_event(Uuid::new_v4(), version, event, context).unwrap()
+    }
+
+    #[test]
+    fn test_freeze_status_from_history() {
+        let account_id = Uuid::new_v4();
+        let (first_admin, second_admin) = (Uuid::new_v4(), Uuid::new_v4());
+        let frozen = |reason: &str| AccountEvent::AccountFrozen {
+            account_id,
+            reason: reason.to_string(),
+            frozen_at: Utc::now(),
+        };
+        let unfrozen = AccountEvent::AccountUnfrozen {
+            account_id,
+            unfrozen_at: Utc::now(),
+        };
+
+        let history = vec![
+            change(2, frozen("Chargeback"), first_admin),
+            change(3, unfrozen.clone(), first_admin),
+            change(4, frozen("Suspicious activity"), second_admin),
+        ];
+        let status = AccountFreezeStatus::from_history(account_id, Uuid::new_v4(), history);
+        assert!(status.frozen);
+        assert_eq!(status.status, "frozen");
+        assert_eq!(status.freeze_reason.as_deref(), Some("Suspicious activity"));
+        assert_eq!(status.frozen_by.unwrap().api_key_id, Some(second_admin));
+        assert_eq!(status.history.len(), 3);
+        assert_eq!(status.history[1].action, "unfrozen");
+
+        let history = vec![change(2, frozen("Chargeback"), first_admin), change(3, unfrozen, first_admin)];
+        let status = AccountFreezeStatus::from_history(account_id, Uuid::new_v4(), history);
+        assert!(!status.frozen);
+        assert_eq!(status.status, "active");
+        assert!(status.freeze_reason.is_none() && status.frozen_at.is_none());
+
+        let closed = AccountEvent::AccountClosed {
+            account_id,
+            reason: Some("Customer request".to_string()),
+            swept_to_account_id: None,
+            closed_at: Utc::now(),
+        };
+        let history = vec![change(2, closed, first_admin)];
+        let status = AccountFreezeStatus::from_history(account_id, Uuid::new_v4(), history);
+        assert!(!status.frozen);
+        assert_eq!(status.status, "closed");
+        assert_eq!(status.history[0].reason.as_deref(), Some("Customer request"));
+    }
+
+    #[test]
+    fn test_freeze_change_ignores_other_events() {
+        let event = AccountEvent::AccountCreated {
+            account_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            account_type: crate::domain::AccountType::UserWallet,
+            name: None,
+            created_at: Utc::now(),
+        };
+        assert!(FreezeChange::from_event(Uuid::new_v4(), 1, event, OperationContext::new()).is_none());
+    }
+}
@@ -0,0 +1,157 @@
+//! Event list query
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{Postgres, QueryBuilder};
+use uuid::Uuid;
+
+use super::{default_limit, QueryHandler};
+use crate::error::AppError;
+
+/// Filtered page of events, newest first
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListEventsQuery {
+    #[serde(default)]
+    pub aggregate_type: Option<String>,
+    #[serde(default)]
+    pub aggregate_id: Option<Uuid>,
+    #[serde(default)]
+    pub event_type: Option<String>,
+    #[serde(default)]
+    pub from_date: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to_date: Option<DateTime<Utc>>,
+    /// Include event_data, context and idempotency_key in each item
+    #[serde(default)]
+    pub include_data: bool,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct EventSummary {
+    pub id: Uuid,
+    pub aggregate_type: String,
+    pub aggregate_id: Uuid,
+    pub event_type: String,
+    pub version: i64,
+    pub created_at: DateTime<Utc>,
+    /// Only present with include_data=true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_data: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventPage {
+    pub events: Vec<EventSummary>,
+    pub total: i64,
+}
+
+impl ListEventsQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if let (Some(from), Some(to)) = (self.from_date, self.to_date) {
+            if from > to {
+                return Err(AppError::InvalidRequest(
+                    "from_date must not be after to_date".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Columns of an event list item; payload columns are only read when
+/// include_data is set
+fn event_columns(include_data: bool) -> &'static str {
+    if include_data {
+        "id, aggregate_type, aggregate_id, event_type, version, created_at, event_data, context, idempotency_key"
+    } else {
+        "id, aggregate_type, aggregate_id, event_type, version, created_at, \
+         NULL::jsonb AS event_data, NULL::jsonb AS context, NULL::uuid AS idempotency_key"
+    }
+}
+
+/// Append a WHERE clause with the filters that are set
+fn push_event_filters(builder: &mut QueryBuilder<'_, Postgres>, query: &ListEventsQuery) {
+    builder.push(" WHERE TRUE");
+    if let Some(aggregate_type) = &query.aggregate_type {
+        builder.push(" AND aggregate_type = ").push_bind(aggregate_type.clone());
+    }
+    if let Some(aggregate_id) = query.aggregate_id {
+        builder.push(" AND aggregate_id = ").push_bind(aggregate_id);
+    }
+    if let Some(event_type) = &query.event_type {
+        builder.push(" AND event_type = ").push_bind(event_type.clone());
+    }
+    if let Some(from_date) = query.from_date {
+        builder.push(" AND created_at >= ").push_bind(from_date);
+    }
+    if let Some(to_date) = query.to_date {
+        builder.push(" AND created_at < ").push_bind(to_date);
+    }
+}
+
+impl QueryHandler {
+    pub async fn list_events(&self, query: &ListEventsQuery) -> Result<EventPage, AppError> {
+        query.validate()?;
+        let limit = query.limit.min(1000);
+
+        let mut builder = QueryBuilder::new("SELECT ");
+        builder.push(event_columns(query.include_data)).push(" FROM events");
+        push_event_filters(&mut builder, query);
+        builder
+            .push(" ORDER BY created_at DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(query.offset);
+        let events: Vec<EventSummary> = builder.build_query_as().fetch_all(&self.pool).await?;
+
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM events");
+        push_event_filters(&mut builder, query);
+        let total: i64 = builder.build_query_scalar().fetch_one(&self.pool).await?;
+
+        Ok(EventPage { events, total })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_events_query_defaults() {
+        let query: ListEventsQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.limit, 50);
+        assert_eq!(query.offset, 0);
+        assert!(query.aggregate_type.is_none());
+        assert!(query.validate().is_ok());
+    }
+
+    #[test]
+    fn test_list_events_query_rejects_inverted_range() {
+        let query: ListEventsQuery = serde_json::from_str(
+            r#"{"from_date": "2026-02-01T00:00:00Z", "to_date": "2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(matches!(query.validate(), Err(AppError::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_event_filters_only_include_set_fields() {
+        let query: ListEventsQuery =
+            serde_json::from_str(r#"{"aggregate_type": "Account", "from_date": "2026-01-01T00:00:00Z"}"#).unwrap();
+        let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM events");
+        push_event_filters(&mut builder, &query);
+        assert_eq!(
+            builder.sql(),
+            "SELECT COUNT(*) FROM events WHERE TRUE AND aggregate_type = $1 AND created_at >= $2"
+        );
+        assert!(event_columns(false).contains("NULL::jsonb AS event_data"));
+    }
+}
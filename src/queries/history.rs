@@ -0,0 +1,180 @@
+//! Wallet history query
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::QueryHandler;
+use crate::error::AppError;
+
+/// Largest page of history entries
+const MAX_HISTORY_LIMIT: i64 = 200;
+
+/// Page of a user's primary wallet ledger, newest first
+#[derive(Debug, Clone, Copy)]
+pub struct GetHistoryQuery {
+    pub user_id: Uuid,
+    /// Only entries older than this ledger entry (next_before_id of the
+    /// previous page)
+    pub before_id: Option<Uuid>,
+    pub limit: i64,
+}
+
+/// Whether money came into or left the wallet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryDirection {
+    Incoming,
+    Outgoing,
+}
+
+impl HistoryDirection {
+    /// Credits to the wallet are incoming, debits outgoing
+    fn from_entry_type(entry_type: &str) -> Self {
+        if entry_type == "credit" {
+            HistoryDirection::Incoming
+        } else {
+            HistoryDirection::Outgoing
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    /// Ledger entry ID (pass as before_id to continue after it)
+    pub entry_id: Uuid,
+    /// Transfer, mint or burn ID
+    pub journal_id: Uuid,
+    /// transfer, reversal, mint, burn, hold_capture or sweep
+    pub journal_type: Option<String>,
+    pub direction: HistoryDirection,
+    pub amount: Decimal,
+    /// Account on the other side of the journal
+    pub counterparty_account_id: Option<Uuid>,
+    pub counterparty_user_id: Option<Uuid>,
+    pub description: Option<String>,
+    /// Wallet balance after this entry
+    pub running_balance: Decimal,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct History {
+    pub user_id: Uuid,
+    pub account_id: Uuid,
+    pub entries: Vec<HistoryEntry>,
+    /// before_id of the next (older) page; None on the last page
+    pub next_before_id: Option<Uuid>,
+}
+
+impl QueryHandler {
+    pub async fn get_history(&self, query: &GetHistoryQuery) -> Result<History, AppError> {
+        let limit = query.limit.clamp(1, MAX_HISTORY_LIMIT);
+
+        let account_id: Option<Uuid> = sqlx::query_scalar!(
+            "SELECT id FROM accounts WHERE user_id = $1 AND account_type = 'user_wallet' AND name IS NULL",
+            query.user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let account_id = account_id.ok_or_else(|| AppError::UserNotFound(query.user_id.to_string()))?;
+
+        let before = match query.before_id {
+            Some(before_id) => Some(
+                sqlx::query_scalar!(
+                    "SELECT created_at FROM ledger_entries WHERE id = $1 AND account_id = $2",
+                    before_id,
+                    account_id
+                )
+                .fetch_optional(&self.pool)
+                .await?
+                .ok_or_else(|| AppError::InvalidRequest(format!("Unknown before_id {}", before_id)))?,
+            ),
+            None => None,
+        };
+
+        // Running balances are summed over the whole ledger so they stay
+        // correct across pages; one extra row tells whether another page exists
+        let rows = sqlx::query!(
+            r#"
+            WITH wallet_entries AS (
+                SELECT id, journal_id, entry_type, amount, description, created_at,
+                       SUM(CASE WHEN entry_type = 'credit' THEN amount ELSE -amount END)
+                           OVER (ORDER BY created_at, id) AS running_balance
+                FROM ledger_entries
+                WHERE account_id = $1
+            )
+            SELECT e.id AS "id!", e.journal_id AS "journal_id!", e.entry_type AS "entry_type!",
+                   e.amount AS "amount!", e.description, e.running_balance AS "running_balance!",
+                   j.journal_type AS "journal_type?",
+                   o.account_id AS "counterparty_account_id?",
+                   oa.user_id AS "counterparty_user_id?",
+                   e.created_at AS "created_at!"
+            FROM wallet_entries e
+            LEFT JOIN journals j ON j.id = e.journal_id
+            LEFT JOIN LATERAL (
+                SELECT account_id FROM ledger_entries
+                WHERE journal_id = e.journal_id AND entry_type <> e.entry_type
+                LIMIT 1
+            ) o ON TRUE
+            LEFT JOIN accounts oa ON oa.id = o.account_id
+            WHERE ($2::timestamptz IS NULL OR (e.created_at, e.id) < ($2, $3))
+            ORDER BY e.created_at DESC, e.id DESC
+            LIMIT $4
+            "#,
+            account_id,
+            before,
+            query.before_id,
+            limit + 1
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut entries: Vec<HistoryEntry> = rows
+            .into_iter()
+            .map(|row| HistoryEntry {
+                entry_id: row.id,
+                journal_id: row.journal_id,
+                journal_type: row.journal_type,
+                direction: HistoryDirection::from_entry_type(&row.entry_type),
+                amount: row.amount,
+                counterparty_account_id: row.counterparty_account_id,
+                counterparty_user_id: row.counterparty_user_id,
+                description: row.description,
+                running_balance: row.running_balance,
+                created_at: row.created_at,
+            })
+            .collect();
+
+        let next_before_id = if entries.len() as i64 > limit {
+            entries.truncate(limit as usize);
+            entries.last().map(|e| e.entry_id)
+        } else {
+            None
+        };
+
+        Ok(History {
+            user_id: query.user_id,
+            account_id,
+            entries,
+            next_before_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_direction_from_entry_type() {
+        assert_eq!(HistoryDirection::from_entry_type("credit"), HistoryDirection::Incoming);
+        assert_eq!(HistoryDirection::from_entry_type("debit"), HistoryDirection::Outgoing);
+        assert_eq!(
+            serde_json::to_value(HistoryDirection::Incoming).unwrap(),
+            serde_json::json!("incoming")
+        );
+    }
+}
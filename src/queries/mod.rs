@@ -0,0 +1,66 @@
+//! Query Handlers module
+//!
+//! CQRS read side: typed queries over the users table, read models and the
+//! event store. They are independent of Axum so the HTTP routes, the CLI or
+//! any other interface can share them.
+
+mod account_status;
+mod events;
+mod history;
+mod reports;
+mod transfers;
+mod users;
+
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::event_store::EventStore;
+use crate::state::AppState;
+
+pub use account_status::{AccountFreezeStatus, FreezeActor, FreezeChange, GetAccountStatusQuery};
+pub use events::{EventPage, EventSummary, ListEventsQuery};
+pub use history::{GetHistoryQuery, History, HistoryDirection, HistoryEntry};
+pub use reports::{CategoryReport, CategoryReportQuery, CategoryVolume};
+pub use transfers::{
+    GetTransferQuery, ListPendingTransfersQuery, PendingTransfer, PendingTransferPage, TransferDetail,
+};
+pub use users::{GetUserQuery, ListUsersQuery, MatchMode, SearchUsersQuery, SortOrder, UserPage, UserSort, UserView};
+
+/// Default page size of list queries
+pub(crate) fn default_limit() -> i64 {
+    50
+}
+
+/// Runs read-side queries
+#[derive(Debug, Clone)]
+pub struct QueryHandler {
+    pool: PgPool,
+    event_store: EventStore,
+    /// Tenant whose users the queries see (None = all tenants)
+    tenant_id: Option<Uuid>,
+}
+
+impl QueryHandler {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            event_store: EventStore::new(pool.clone()),
+            pool,
+            tenant_id: None,
+        }
+    }
+
+    /// Reuse the services held in the shared application state
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            pool: state.pool.clone(),
+            event_store: state.event_store.clone(),
+            tenant_id: None,
+        }
+    }
+
+    /// Restrict user-facing queries to one tenant (None = all tenants)
+    pub fn for_tenant(mut self, tenant_id: Option<Uuid>) -> Self {
+        self.tenant_id = tenant_id;
+        self
+    }
+}
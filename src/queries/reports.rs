@@ -0,0 +1,128 @@
+//! Finance reports
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use super::QueryHandler;
+use crate::error::AppError;
+
+/// Transfer volume per category, over transfers created in [from, to)
+#[derive(Debug, Clone, Deserialize)]
+pub struct CategoryReportQuery {
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryVolume {
+    pub category: String,
+    pub transfer_count: i64,
+    pub volume: Decimal,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryReport {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Categorized transfers only, by category name
+    pub categories: Vec<CategoryVolume>,
+    pub total_count: i64,
+    pub total_volume: Decimal,
+}
+
+impl CategoryReportQuery {
+    fn validate(&self) -> Result<(), AppError> {
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from > to {
+                return Err(AppError::InvalidRequest("from must not be after to".to_string()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl CategoryReport {
+    fn new(query: &CategoryReportQuery, categories: Vec<CategoryVolume>) -> Self {
+        Self {
+            from: query.from,
+            to: query.to,
+            total_count: categories.iter().map(|c| c.transfer_count).sum(),
+            total_volume: categories.iter().map(|c| c.volume).sum(),
+            categories,
+        }
+    }
+}
+
+impl QueryHandler {
+    pub async fn category_report(&self, query: &CategoryReportQuery) -> Result<CategoryReport, AppError> {
+        query.validate()?;
+
+        // One debit entry per transfer; reversals are uncategorized and do
+        // not net off the original's category
+        let rows: Vec<(String, i64, Decimal)> = sqlx::query_as(
+            r#"
+            SELECT category, COUNT(*), COALESCE(SUM(amount), 0)
+            FROM ledger_entries
+            WHERE category IS NOT NULL
+              AND entry_type = 'debit'
+              AND ($1::timestamptz IS NULL OR created_at >= $1)
+              AND ($2::timestamptz IS NULL OR created_at < $2)
+            GROUP BY category
+            ORDER BY category
+            "#,
+        )
+        .bind(query.from)
+        .bind(query.to)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let categories = rows
+            .into_iter()
+            .map(|(category, transfer_count, volume)| CategoryVolume {
+                category,
+                transfer_count,
+                volume,
+            })
+            .collect();
+
+        Ok(CategoryReport::new(query, categories))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_report_totals() {
+        let query: CategoryReportQuery = serde_json::from_str("{}").unwrap();
+        assert!(query.validate().is_ok());
+
+        let report = CategoryReport::new(
+            &query,
+            vec![
+                CategoryVolume {
+                    category: "purchase".to_string(),
+                    transfer_count: 2,
+                    volume: Decimal::new(3050, 2),
+                },
+                CategoryVolume {
+                    category: "reward".to_string(),
+                    transfer_count: 1,
+                    volume: Decimal::new(5, 0),
+                },
+            ],
+        );
+        assert_eq!(report.total_count, 3);
+        assert_eq!(report.total_volume, Decimal::new(3550, 2));
+
+        let inverted: CategoryReportQuery = serde_json::from_str(
+            r#"{"from": "2026-02-01T00:00:00Z", "to": "2026-01-01T00:00:00Z"}"#,
+        )
+        .unwrap();
+        assert!(matches!(inverted.validate(), Err(AppError::InvalidRequest(_))));
+    }
+}
@@ -0,0 +1,189 @@
+//! Transfer queries
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{default_limit, QueryHandler};
+use crate::aggregate::{Aggregate, Transfer};
+use crate::error::AppError;
+use crate::projection::JournalType;
+
+/// Load one transfer, mint or burn
+#[derive(Debug, Clone, Copy)]
+pub struct GetTransferQuery {
+    pub transfer_id: Uuid,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferDetail {
+    pub id: Uuid,
+    pub from_account_id: Uuid,
+    pub to_account_id: Uuid,
+    pub amount: Decimal,
+    pub description: String,
+    /// transfer, reversal, mint, burn, hold_capture or sweep
+    pub journal_type: String,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    /// User who requested the operation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initiated_by: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_reason: Option<String>,
+    /// Original transfer when this transfer is a reversal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversal_of: Option<Uuid>,
+    /// Compensating transfer when this transfer was reversed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversed_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Transfer> for TransferDetail {
+    fn from(transfer: Transfer) -> Self {
+        Self {
+            id: transfer.id(),
+            from_account_id: transfer.from_account_id(),
+            to_account_id: transfer.to_account_id(),
+            amount: transfer.amount(),
+            description: transfer.memo().unwrap_or_default().to_string(),
+            journal_type: match transfer.reversal_of() {
+                Some(_) => JournalType::Reversal,
+                None => JournalType::Transfer,
+            }
+            .as_str()
+            .to_string(),
+            status: transfer.status().as_str().to_string(),
+            category: transfer.category().map(str::to_string),
+            initiated_by: Some(transfer.initiated_by()).filter(|id| !id.is_nil()),
+            failure_reason: transfer.failure_reason().map(|r| r.to_string()),
+            reversal_of: transfer.reversal_of(),
+            reversed_by: transfer.reversed_by_transfer_id(),
+            created_at: transfer.initiated_at().unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+/// Transfers waiting for approval, oldest first
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListPendingTransfersQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct PendingTransfer {
+    pub transfer_id: Uuid,
+    pub from_user_id: Uuid,
+    pub to_user_id: Uuid,
+    pub amount: Decimal,
+    pub memo: Option<String>,
+    pub requested_by_api_key_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// When the transfer fails as expired unless decided before
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingTransferPage {
+    pub transfers: Vec<PendingTransfer>,
+    pub total: i64,
+}
+
+impl QueryHandler {
+    pub async fn get_transfer(&self, query: &GetTransferQuery) -> Result<TransferDetail, AppError> {
+        let transfer_id = query.transfer_id;
+
+        // Transfers record their lifecycle (including failures) in the Transfer aggregate
+        let aggregate: Option<Transfer> = self
+            .event_store
+            .load_aggregate(transfer_id)
+            .await
+            .map_err(|e| AppError::Internal(e.to_string()))?;
+
+        if let Some(transfer) = aggregate {
+            return Ok(TransferDetail::from(transfer));
+        }
+
+        // Mints, burns and hold captures (and transfers recorded before the
+        // saga) only exist as ledger journals
+        let journal = sqlx::query!(
+            r#"
+            SELECT id, journal_type, from_account_id, to_account_id, amount, memo, category,
+                   initiated_by, created_at
+            FROM journals
+            WHERE id = $1
+            "#,
+            transfer_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::InvalidRequest(format!("Transfer {} not found", transfer_id)))?;
+
+        Ok(TransferDetail {
+            id: journal.id,
+            from_account_id: journal.from_account_id,
+            to_account_id: journal.to_account_id,
+            amount: journal.amount,
+            description: journal.memo.unwrap_or_default(),
+            journal_type: journal.journal_type,
+            status: "completed".to_string(),
+            category: journal.category,
+            initiated_by: journal.initiated_by,
+            failure_reason: None,
+            reversal_of: None,
+            reversed_by: None,
+            created_at: journal.created_at,
+        })
+    }
+
+    pub async fn list_pending_transfers(
+        &self,
+        query: &ListPendingTransfersQuery,
+    ) -> Result<PendingTransferPage, AppError> {
+        let limit = query.limit.clamp(1, 200);
+
+        let transfers: Vec<PendingTransfer> = sqlx::query_as(
+            r#"
+            SELECT transfer_id, from_user_id, to_user_id, amount, memo, requested_by_api_key_id, created_at,
+                   expires_at
+            FROM pending_transfers p
+            WHERE status = 'pending_approval'
+              AND ($3::uuid IS NULL OR EXISTS (
+                  SELECT 1 FROM users u WHERE u.id = p.from_user_id AND u.tenant_id = $3
+              ))
+            ORDER BY created_at ASC
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+        .bind(limit)
+        .bind(query.offset)
+        .bind(self.tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM pending_transfers p
+            WHERE status = 'pending_approval'
+              AND ($1::uuid IS NULL OR EXISTS (
+                  SELECT 1 FROM users u WHERE u.id = p.from_user_id AND u.tenant_id = $1
+              ))
+            "#,
+        )
+        .bind(self.tenant_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(PendingTransferPage {
+            transfers,
+            total,
+        })
+    }
+}
@@ -0,0 +1,300 @@
+//! User queries
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{default_limit, QueryHandler};
+use crate::error::AppError;
+
+/// A user as stored in the users table
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct UserView {
+    pub id: Uuid,
+    pub tenant_id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub is_system: bool,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Load one user
+#[derive(Debug, Clone, Copy)]
+pub struct GetUserQuery {
+    pub user_id: Uuid,
+}
+
+/// List users with keyset pagination
+#[derive(Debug, Clone, Deserialize)]
+pub struct ListUsersQuery {
+    #[serde(default)]
+    pub is_active: Option<bool>,
+    /// Only users created at or after this time
+    #[serde(default)]
+    pub created_after: Option<DateTime<Utc>>,
+    /// Case-sensitive username prefix
+    #[serde(default)]
+    pub username_prefix: Option<String>,
+    #[serde(default)]
+    pub sort: UserSort,
+    #[serde(default)]
+    pub order: SortOrder,
+    #[serde(default)]
+    pub cursor: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// Sort key of ListUsersQuery
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSort {
+    #[default]
+    CreatedAt,
+    Username,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// One page of users
+#[derive(Debug, Clone, Serialize)]
+pub struct UserPage {
+    pub users: Vec<UserView>,
+    pub next_cursor: Option<String>,
+}
+
+/// Resolve users by username and/or email
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchUsersQuery {
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Compared case-insensitively
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default, rename = "match")]
+    pub match_mode: MatchMode,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// How search terms are compared
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    #[default]
+    Exact,
+    Prefix,
+}
+
+/// Keyset pagination cursor of ListUsersQuery: position of the last user on
+/// the previous page (valid for either sort key)
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct UserCursor {
+    created_at: DateTime<Utc>,
+    id: Uuid,
+    username: String,
+}
+
+impl UserCursor {
+    fn encode(&self) -> String {
+        hex::encode(format!(
+            "{}:{}:{}",
+            self.created_at.timestamp_micros(),
+            self.id,
+            self.username
+        ))
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let raw = String::from_utf8(hex::decode(cursor).ok()?).ok()?;
+        let (micros, rest) = raw.split_once(':')?;
+        let (id, username) = rest.split_once(':')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: Uuid::parse_str(id).ok()?,
+            username: username.to_string(),
+        })
+    }
+}
+
+impl QueryHandler {
+    pub async fn get_user(&self, query: &GetUserQuery) -> Result<UserView, AppError> {
+        let user: Option<UserView> = sqlx::query_as(
+            r#"
+            SELECT id, tenant_id, username, email, display_name, is_system, is_active, created_at, updated_at
+            FROM users
+            WHERE id = $1 AND ($2::uuid IS NULL OR tenant_id = $2)
+            "#,
+        )
+        .bind(query.user_id)
+        .bind(self.tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        user.ok_or_else(|| AppError::UserNotFound(query.user_id.to_string()))
+    }
+
+    pub async fn list_users(&self, query: &ListUsersQuery) -> Result<UserPage, AppError> {
+        let limit = query.limit.clamp(1, 200);
+
+        let cursor = match query.cursor.as_deref() {
+            Some(raw) => Some(
+                UserCursor::decode(raw)
+                    .ok_or_else(|| AppError::InvalidRequest("Invalid cursor".to_string()))?,
+            ),
+            None => None,
+        };
+
+        let username_prefix = query.username_prefix.as_deref().filter(|p| !p.is_empty());
+
+        // Sort column and direction come from closed enums, never from input text
+        let direction = match query.order {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        let comparison = match query.order {
+            SortOrder::Asc => ">",
+            SortOrder::Desc => "<",
+        };
+        let keyset = match query.sort {
+            UserSort::CreatedAt => format!("($4::timestamptz IS NULL OR (created_at, id) {} ($4, $6))", comparison),
+            UserSort::Username => format!("($5::text IS NULL OR (username, id) {} ($5, $6))", comparison),
+        };
+        let sort_column = match query.sort {
+            UserSort::CreatedAt => "created_at",
+            UserSort::Username => "username",
+        };
+
+        let sql = format!(
+            r#"
+            SELECT id, tenant_id, username, email, display_name, is_system, is_active, created_at, updated_at
+            FROM users
+            WHERE ($1::bool IS NULL OR is_active = $1)
+              AND ($2::timestamptz IS NULL OR created_at >= $2)
+              AND ($3::text IS NULL OR starts_with(username, $3))
+              AND {keyset}
+              AND ($8::uuid IS NULL OR tenant_id = $8)
+            ORDER BY {sort_column} {direction}, id {direction}
+            LIMIT $7
+            "#,
+        );
+
+        // Fetch one extra row to know whether another page exists
+        let mut users: Vec<UserView> = sqlx::query_as(&sql)
+            .bind(query.is_active)
+            .bind(query.created_after)
+            .bind(username_prefix)
+            .bind(cursor.as_ref().map(|c| c.created_at))
+            .bind(cursor.as_ref().map(|c| c.username.as_str()))
+            .bind(cursor.as_ref().map(|c| c.id))
+            .bind(limit + 1)
+            .bind(self.tenant_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let next_cursor = if users.len() as i64 > limit {
+            users.truncate(limit as usize);
+            users.last().map(|user| {
+                UserCursor {
+                    created_at: user.created_at,
+                    id: user.id,
+                    username: user.username.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
+
+        Ok(UserPage {
+            users,
+            next_cursor,
+        })
+    }
+
+    pub async fn search_users(&self, query: &SearchUsersQuery) -> Result<Vec<UserView>, AppError> {
+        let username = query.username.as_deref().map(str::trim).filter(|u| !u.is_empty());
+        let email = query.email.as_deref().map(str::trim).filter(|e| !e.is_empty());
+        if username.is_none() && email.is_none() {
+            return Err(AppError::InvalidRequest(
+                "username or email is required".to_string(),
+            ));
+        }
+        if username.is_some_and(|u| u.chars().count() > 50) || email.is_some_and(|e| e.chars().count() > 100) {
+            return Err(AppError::InvalidRequest("search term is too long".to_string()));
+        }
+
+        let limit = query.limit.clamp(1, 200);
+        let prefix = query.match_mode == MatchMode::Prefix;
+
+        let users: Vec<UserView> = sqlx::query_as(
+            r#"
+            SELECT id, tenant_id, username, email, display_name, is_system, is_active, created_at, updated_at
+            FROM users
+            WHERE ($1::text IS NULL OR CASE WHEN $3 THEN starts_with(username, $1) ELSE username = $1 END)
+              AND ($2::text IS NULL OR CASE WHEN $3 THEN starts_with(lower(email), lower($2)) ELSE lower(email) = lower($2) END)
+              AND ($5::uuid IS NULL OR tenant_id = $5)
+            ORDER BY username, id
+            LIMIT $4
+            "#,
+        )
+        .bind(username)
+        .bind(email)
+        .bind(prefix)
+        .bind(limit)
+        .bind(self.tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_cursor_round_trip() {
+        let cursor = UserCursor {
+            created_at: DateTime::from_timestamp_micros(1_714_566_896_123_456).unwrap(),
+            id: Uuid::new_v4(),
+            username: "alice_01".to_string(),
+        };
+
+        assert_eq!(UserCursor::decode(&cursor.encode()), Some(cursor));
+        assert!(UserCursor::decode("not-a-cursor").is_none());
+    }
+
+    #[test]
+    fn test_list_users_query_defaults() {
+        let query: ListUsersQuery = serde_json::from_str("{}").unwrap();
+        assert_eq!(query.sort, UserSort::CreatedAt);
+        assert_eq!(query.order, SortOrder::Desc);
+        assert_eq!(query.limit, 50);
+
+        let query: ListUsersQuery =
+            serde_json::from_str(r#"{"sort": "username", "order": "asc"}"#).unwrap();
+        assert_eq!(query.sort, UserSort::Username);
+        assert_eq!(query.order, SortOrder::Asc);
+    }
+
+    #[test]
+    fn test_search_users_query_match_mode() {
+        let query: SearchUsersQuery = serde_json::from_str(r#"{"username": "ali"}"#).unwrap();
+        assert_eq!(query.match_mode, MatchMode::Exact);
+
+        let query: SearchUsersQuery =
+            serde_json::from_str(r#"{"username": "ali", "match": "prefix"}"#).unwrap();
+        assert_eq!(query.match_mode, MatchMode::Prefix);
+    }
+}
@@ -0,0 +1,182 @@
+//! Transfer Receipts
+//!
+//! Signed receipt documents for dispute handling. A receipt lists both
+//! ledger legs of a transfer (or mint/burn) with amounts and memo, and is
+//! anchored to the audit log hash chain by the current_hash of the audit
+//! entry that recorded the transfer.
+//!
+//! The signature is detached: the receipt JSON is returned as the response
+//! body and `X-Receipt-Signature: sha256=<hex>` carries the HMAC-SHA256 of
+//! those exact bytes under RECEIPT_SIGNING_SECRET.
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::aggregate::Transfer;
+use crate::event_store::{EventStore, EventStoreError};
+
+/// Receipt document format version
+pub const RECEIPT_VERSION: u32 = 1;
+
+/// Receipt for one transfer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferReceipt {
+    pub receipt_version: u32,
+    pub transfer_id: Uuid,
+    pub status: String,
+    pub amount: Decimal,
+    pub memo: Option<String>,
+    /// Debit and credit ledger entries (empty if the transfer never posted)
+    pub legs: Vec<ReceiptLeg>,
+    /// Audit log entry that recorded the transfer
+    pub audit_anchor: Option<AuditAnchor>,
+    pub created_at: DateTime<Utc>,
+    pub issued_at: DateTime<Utc>,
+}
+
+/// One side of the double-entry posting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiptLeg {
+    pub entry_type: String,
+    pub account_id: Uuid,
+    pub user_id: Uuid,
+    pub amount: Decimal,
+    pub posted_at: DateTime<Utc>,
+}
+
+/// Position of the transfer in the audit log hash chain
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditAnchor {
+    pub audit_log_id: Uuid,
+    pub sequence_number: i64,
+    pub action: String,
+    pub current_hash: String,
+}
+
+/// Build the receipt for a transfer, mint or burn (None if unknown)
+pub async fn build_receipt(
+    pool: &PgPool,
+    event_store: &EventStore,
+    transfer_id: Uuid,
+) -> Result<Option<TransferReceipt>, ReceiptError> {
+    let legs: Vec<ReceiptLeg> = sqlx::query_as::<_, (String, Uuid, Uuid, Decimal, DateTime<Utc>)>(
+        r#"
+        SELECT le.entry_type, le.account_id, a.user_id, le.amount, le.created_at
+        FROM ledger_entries le
+        JOIN accounts a ON a.id = le.account_id
+        WHERE le.journal_id = $1
+        ORDER BY le.entry_type DESC, le.created_at
+        "#,
+    )
+    .bind(transfer_id)
+    .fetch_all(pool)
+    .await?
+    .into_iter()
+    .map(|(entry_type, account_id, user_id, amount, posted_at)| ReceiptLeg {
+        entry_type,
+        account_id,
+        user_id,
+        amount,
+        posted_at,
+    })
+    .collect();
+
+    let transfer: Option<Transfer> = event_store.load_aggregate(transfer_id).await?;
+
+    // Mints, burns and transfers recorded before the saga exist only in the ledger
+    let (status, amount, memo, created_at) = match &transfer {
+        Some(transfer) => (
+            transfer.status().as_str().to_string(),
+            transfer.amount(),
+            transfer.memo().map(str::to_string),
+            transfer.initiated_at().unwrap_or_else(Utc::now),
+        ),
+        None => match legs.iter().find(|leg| leg.entry_type == "debit") {
+            Some(debit) => ("completed".to_string(), debit.amount, None, debit.posted_at),
+            None => return Ok(None),
+        },
+    };
+
+    let audit_anchor = sqlx::query_as::<_, (Uuid, i64, String, String)>(
+        r#"
+        SELECT id, sequence_number, action, current_hash
+        FROM audit_logs
+        WHERE resource_id = $1
+        ORDER BY sequence_number
+        LIMIT 1
+        "#,
+    )
+    .bind(transfer_id)
+    .fetch_optional(pool)
+    .await?
+    .map(|(audit_log_id, sequence_number, action, current_hash)| AuditAnchor {
+        audit_log_id,
+        sequence_number,
+        action,
+        current_hash,
+    });
+
+    Ok(Some(TransferReceipt {
+        receipt_version: RECEIPT_VERSION,
+        transfer_id,
+        status,
+        amount,
+        memo,
+        legs,
+        audit_anchor,
+        created_at,
+        issued_at: Utc::now(),
+    }))
+}
+
+/// HMAC-SHA256 of the serialized receipt, hex encoded.
+/// Sent as `X-Receipt-Signature: sha256=<signature>`.
+pub fn sign_receipt(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Check a detached signature against the exact receipt bytes
+pub fn verify_receipt(secret: &str, body: &[u8], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature.trim_start_matches("sha256=")) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
+/// Receipt errors
+#[derive(Debug, thiserror::Error)]
+pub enum ReceiptError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Event store error: {0}")]
+    EventStore(#[from] EventStoreError),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify_receipt() {
+        let body = br#"{"transfer_id":"00000000-0000-0000-0000-000000000001"}"#;
+        let signature = sign_receipt("secret", body);
+
+        assert!(verify_receipt("secret", body, &signature));
+        assert!(verify_receipt("secret", body, &format!("sha256={}", signature)));
+        assert!(!verify_receipt("other", body, &signature));
+        assert!(!verify_receipt("secret", b"{}", &signature));
+        assert!(!verify_receipt("secret", body, "not-hex"));
+    }
+}
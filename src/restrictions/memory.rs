@@ -0,0 +1,52 @@
+//! In-Memory Restrictions
+//!
+//! [`RestrictionTrait`] implementation keeping account restrictions in
+//! process memory, for handler tests that run without PostgreSQL.
+
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use super::{AccountRestriction, RestrictionError, RestrictionMode, RestrictionTrait};
+
+/// Restrictions by account ID, shared between clones
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryRestrictions {
+    restrictions: Arc<Mutex<HashMap<Uuid, AccountRestriction>>>,
+}
+
+impl InMemoryRestrictions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict an account (test setup; not validated like
+    /// [`super::RestrictionService::upsert`])
+    pub fn set(&self, account_id: Uuid, mode: RestrictionMode, allowed_counterparties: Vec<Uuid>) {
+        let restriction = AccountRestriction {
+            account_id,
+            mode,
+            allowed_counterparties,
+            reason: None,
+            updated_at: Utc::now(),
+        };
+        self.lock().insert(account_id, restriction);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, AccountRestriction>> {
+        self.restrictions.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RestrictionTrait for InMemoryRestrictions {
+    async fn check_transfer(&self, from_account_id: Uuid, to_account_id: Uuid) -> Result<(), RestrictionError> {
+        let restrictions = self.lock();
+        for account_id in [from_account_id, to_account_id] {
+            if let Some(restriction) = restrictions.get(&account_id) {
+                restriction.check_transfer(from_account_id, to_account_id)?;
+            }
+        }
+        Ok(())
+    }
+}
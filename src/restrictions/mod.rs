@@ -0,0 +1,388 @@
+//! Account Restrictions
+//!
+//! Admin-configured per-account transfer restrictions: an account can be
+//! receive-only, send-only, or limited to an allowlist of counterparty
+//! accounts. Enforced by TransferHandler (and when a pending transfer is
+//! approved); mints, burns and hold captures are not affected.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+#[cfg(any(test, feature = "test-util"))]
+mod memory;
+mod traits;
+
+#[cfg(any(test, feature = "test-util"))]
+pub use memory::InMemoryRestrictions;
+pub use traits::RestrictionTrait;
+
+// =========================================================================
+// Modes
+// =========================================================================
+
+/// What an account may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RestrictionMode {
+    /// May receive but not send
+    ReceiveOnly,
+    /// May send but not receive
+    SendOnly,
+    /// May send to and receive from the listed accounts only
+    Allowlist,
+}
+
+impl RestrictionMode {
+    pub const ALL: [RestrictionMode; 3] = [
+        RestrictionMode::ReceiveOnly,
+        RestrictionMode::SendOnly,
+        RestrictionMode::Allowlist,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RestrictionMode::ReceiveOnly => "receive_only",
+            RestrictionMode::SendOnly => "send_only",
+            RestrictionMode::Allowlist => "allowlist",
+        }
+    }
+}
+
+impl fmt::Display for RestrictionMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RestrictionMode {
+    type Err = RestrictionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.as_str() == s)
+            .ok_or_else(|| RestrictionError::UnknownMode(s.to_string()))
+    }
+}
+
+// =========================================================================
+// Records
+// =========================================================================
+
+/// Restriction configured for one account
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AccountRestriction {
+    pub account_id: Uuid,
+    pub mode: RestrictionMode,
+    /// Counterparty accounts (only used by the allowlist mode)
+    pub allowed_counterparties: Vec<Uuid>,
+    pub reason: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AccountRestriction {
+    /// Check that this account may send to `to_account_id`
+    pub fn check_send(&self, to_account_id: Uuid) -> Result<(), RestrictionError> {
+        match self.mode {
+            RestrictionMode::ReceiveOnly => Err(RestrictionError::Blocked(format!(
+                "account {} is receive-only",
+                self.account_id
+            ))),
+            RestrictionMode::Allowlist if !self.allowed_counterparties.contains(&to_account_id) => {
+                Err(RestrictionError::Blocked(format!(
+                    "account {} may not send to account {}",
+                    self.account_id, to_account_id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that this account may receive from `from_account_id`
+    pub fn check_receive(&self, from_account_id: Uuid) -> Result<(), RestrictionError> {
+        match self.mode {
+            RestrictionMode::SendOnly => Err(RestrictionError::Blocked(format!(
+                "account {} is send-only",
+                self.account_id
+            ))),
+            RestrictionMode::Allowlist if !self.allowed_counterparties.contains(&from_account_id) => {
+                Err(RestrictionError::Blocked(format!(
+                    "account {} may not receive from account {}",
+                    self.account_id, from_account_id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a transfer from `from_account_id` to `to_account_id` against
+    /// this restriction of either side
+    pub fn check_transfer(&self, from_account_id: Uuid, to_account_id: Uuid) -> Result<(), RestrictionError> {
+        if self.account_id == from_account_id {
+            self.check_send(to_account_id)
+        } else {
+            self.check_receive(from_account_id)
+        }
+    }
+}
+
+// =========================================================================
+// RestrictionService
+// =========================================================================
+
+/// Row of `SELECT account_id, mode, allowed_counterparties, reason, updated_at`
+#[derive(Debug, sqlx::FromRow)]
+struct RestrictionRow {
+    account_id: Uuid,
+    mode: String,
+    allowed_counterparties: Vec<Uuid>,
+    reason: Option<String>,
+    updated_at: DateTime<Utc>,
+}
+
+impl TryFrom<RestrictionRow> for AccountRestriction {
+    type Error = RestrictionError;
+
+    fn try_from(row: RestrictionRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            account_id: row.account_id,
+            mode: row.mode.parse()?,
+            allowed_counterparties: row.allowed_counterparties,
+            reason: row.reason,
+            updated_at: row.updated_at,
+        })
+    }
+}
+
+/// Restriction Service
+#[derive(Debug, Clone)]
+pub struct RestrictionService {
+    pool: PgPool,
+}
+
+impl RestrictionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Restricted accounts, of one tenant or all (None)
+    pub async fn list(&self, tenant_id: Option<Uuid>) -> Result<Vec<AccountRestriction>, RestrictionError> {
+        let rows: Vec<RestrictionRow> = sqlx::query_as(
+            r#"
+            SELECT r.account_id, r.mode, r.allowed_counterparties, r.reason, r.updated_at
+            FROM account_restrictions r
+            JOIN accounts a ON a.id = r.account_id
+            WHERE $1::uuid IS NULL OR a.tenant_id = $1
+            ORDER BY r.updated_at DESC
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.into_iter().map(AccountRestriction::try_from).collect()
+    }
+
+    /// Restriction of one account, if any
+    pub async fn get(&self, account_id: Uuid) -> Result<Option<AccountRestriction>, RestrictionError> {
+        let row: Option<RestrictionRow> = sqlx::query_as(
+            r#"
+            SELECT account_id, mode, allowed_counterparties, reason, updated_at
+            FROM account_restrictions
+            WHERE account_id = $1
+            "#,
+        )
+        .bind(account_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        row.map(AccountRestriction::try_from).transpose()
+    }
+
+    /// Create or replace the restriction of an account
+    pub async fn upsert(
+        &self,
+        account_id: Uuid,
+        mode: RestrictionMode,
+        allowed_counterparties: Vec<Uuid>,
+        reason: Option<String>,
+    ) -> Result<AccountRestriction, RestrictionError> {
+        match mode {
+            RestrictionMode::Allowlist if allowed_counterparties.is_empty() => {
+                return Err(RestrictionError::InvalidRestriction(
+                    "allowlist requires at least one counterparty".to_string(),
+                ));
+            }
+            RestrictionMode::ReceiveOnly | RestrictionMode::SendOnly
+                if !allowed_counterparties.is_empty() =>
+            {
+                return Err(RestrictionError::InvalidRestriction(format!(
+                    "counterparties are only used by the allowlist mode, not {}",
+                    mode
+                )));
+            }
+            _ => {}
+        }
+        if allowed_counterparties.contains(&account_id) {
+            return Err(RestrictionError::InvalidRestriction(
+                "an account cannot be its own counterparty".to_string(),
+            ));
+        }
+
+        let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM accounts WHERE id = $1)")
+            .bind(account_id)
+            .fetch_one(&self.pool)
+            .await?;
+        if !exists {
+            return Err(RestrictionError::AccountNotFound(account_id));
+        }
+
+        let updated_at: DateTime<Utc> = sqlx::query_scalar(
+            r#"
+            INSERT INTO account_restrictions (account_id, mode, allowed_counterparties, reason)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (account_id) DO UPDATE
+            SET mode = EXCLUDED.mode,
+                allowed_counterparties = EXCLUDED.allowed_counterparties,
+                reason = EXCLUDED.reason,
+                updated_at = NOW()
+            RETURNING updated_at
+            "#,
+        )
+        .bind(account_id)
+        .bind(mode.as_str())
+        .bind(&allowed_counterparties)
+        .bind(&reason)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(AccountRestriction {
+            account_id,
+            mode,
+            allowed_counterparties,
+            reason,
+            updated_at,
+        })
+    }
+
+    /// Remove the restriction of an account
+    pub async fn delete(&self, account_id: Uuid) -> Result<(), RestrictionError> {
+        let result = sqlx::query("DELETE FROM account_restrictions WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(RestrictionError::NotConfigured(account_id));
+        }
+        Ok(())
+    }
+
+    /// Enforce both accounts' restrictions for a transfer
+    pub async fn check_transfer(
+        &self,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+    ) -> Result<(), RestrictionError> {
+        let rows: Vec<RestrictionRow> = sqlx::query_as(
+            r#"
+            SELECT account_id, mode, allowed_counterparties, reason, updated_at
+            FROM account_restrictions
+            WHERE account_id = $1 OR account_id = $2
+            "#,
+        )
+        .bind(from_account_id)
+        .bind(to_account_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for restriction in rows.into_iter().map(AccountRestriction::try_from) {
+            restriction?.check_transfer(from_account_id, to_account_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Restriction errors
+#[derive(Debug, thiserror::Error)]
+pub enum RestrictionError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Unknown restriction mode: {0}")]
+    UnknownMode(String),
+
+    #[error("Invalid restriction: {0}")]
+    InvalidRestriction(String),
+
+    #[error("Account not found: {0}")]
+    AccountNotFound(Uuid),
+
+    #[error("No restriction configured for account {0}")]
+    NotConfigured(Uuid),
+
+    #[error("Transfer blocked: {0}")]
+    Blocked(String),
+}
+
+impl From<RestrictionError> for AppError {
+    fn from(e: RestrictionError) -> Self {
+        match e {
+            RestrictionError::Blocked(msg) => AppError::TransferBlocked(msg),
+            RestrictionError::AccountNotFound(id) => AppError::AccountNotFound(id.to_string()),
+            RestrictionError::Database(e) => AppError::Internal(e.to_string()),
+            e => AppError::InvalidRequest(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn restriction(mode: RestrictionMode, allowed: Vec<Uuid>) -> AccountRestriction {
+        AccountRestriction {
+            account_id: Uuid::new_v4(),
+            mode,
+            allowed_counterparties: allowed,
+            reason: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_receive_and_send_only() {
+        let other = Uuid::new_v4();
+
+        let receive_only = restriction(RestrictionMode::ReceiveOnly, vec![]);
+        assert!(matches!(receive_only.check_send(other), Err(RestrictionError::Blocked(_))));
+        assert!(receive_only.check_receive(other).is_ok());
+
+        let send_only = restriction(RestrictionMode::SendOnly, vec![]);
+        assert!(send_only.check_send(other).is_ok());
+        assert!(matches!(send_only.check_receive(other), Err(RestrictionError::Blocked(_))));
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let allowed = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let allowlist = restriction(RestrictionMode::Allowlist, vec![allowed]);
+
+        assert!(allowlist.check_send(allowed).is_ok());
+        assert!(allowlist.check_receive(allowed).is_ok());
+        assert!(allowlist.check_send(other).is_err());
+        assert!(allowlist.check_receive(other).is_err());
+
+        let err: AppError = allowlist.check_send(other).unwrap_err().into();
+        assert!(matches!(err, AppError::TransferBlocked(_)));
+    }
+}
@@ -0,0 +1,29 @@
+//! Restriction Trait
+//!
+//! The restriction check TransferHandler depends on, implemented by
+//! [`RestrictionService`] and by an in-memory fake for tests.
+
+use std::future::Future;
+use uuid::Uuid;
+
+use super::{RestrictionError, RestrictionService};
+
+/// Restriction enforcement used by command handlers
+pub trait RestrictionTrait: Send + Sync {
+    /// Enforce both accounts' restrictions for a transfer
+    fn check_transfer(
+        &self,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+    ) -> impl Future<Output = Result<(), RestrictionError>> + Send;
+}
+
+impl RestrictionTrait for RestrictionService {
+    fn check_transfer(
+        &self,
+        from_account_id: Uuid,
+        to_account_id: Uuid,
+    ) -> impl Future<Output = Result<(), RestrictionError>> + Send {
+        RestrictionService::check_transfer(self, from_account_id, to_account_id)
+    }
+}
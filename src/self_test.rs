@@ -0,0 +1,302 @@
+//! Self-Test
+//!
+//! Scripted end-to-end scenario for post-deploy smoke verification. Two
+//! throwaway users (username prefix `selftest_`) are created; one is minted
+//! to and transfers to the other, then both are burned back to zero. After
+//! every step the wallets' projected balances are compared with the
+//! expected ones, and at the end with the ledger and the event stream. The
+//! users are deactivated afterwards, so a run leaves no balance behind.
+
+use std::future::Future;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::aggregate::Account;
+use crate::domain::{Amount, OperationContext};
+use crate::handlers::{
+    BurnCommand, BurnHandler, CreateUserCommand, CreateUserHandler, DeactivateUserCommand,
+    DeactivateUserHandler, MintCommand, MintHandler, TransferCommand, TransferHandler,
+};
+use crate::state::AppState;
+
+/// Reason recorded on the run's mints, burns and deactivations
+const SELF_TEST_REASON: &str = "self-test";
+
+/// Amounts moved by the scenario (kept small to stay under limits and the
+/// transfer approval threshold)
+const MINT_AMOUNT: Decimal = Decimal::from_parts(10, 0, 0, false, 0);
+const TRANSFER_AMOUNT: Decimal = Decimal::from_parts(4, 0, 0, false, 0);
+
+/// Outcome of one step of the scenario
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestStep {
+    pub name: &'static str,
+    pub passed: bool,
+    /// Why the step failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Result of a self-test run
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfTestReport {
+    pub run_id: Uuid,
+    pub passed: bool,
+    pub steps: Vec<SelfTestStep>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+}
+
+/// The two users of a run
+#[derive(Debug, Clone, Copy)]
+struct Participants {
+    sender: Uuid,
+    recipient: Uuid,
+}
+
+/// Run the scenario against the application's handlers. Steps stop at the
+/// first failure; cleanup runs regardless once the users exist.
+pub async fn run(state: &AppState) -> SelfTestReport {
+    let run_id = Uuid::new_v4();
+    let started_at = Utc::now();
+    let started = Instant::now();
+    let mut steps = Vec::new();
+
+    let users = Participants {
+        sender: Uuid::new_v4(),
+        recipient: Uuid::new_v4(),
+    };
+    let tag = run_id.simple().to_string();
+    let tag = &tag[..12];
+
+    tracing::info!(%run_id, "Self-test started");
+
+    let created = step(&mut steps, "create_users", async {
+        create_user(state, users.sender, &format!("selftest_{}_a", tag)).await?;
+        create_user(state, users.recipient, &format!("selftest_{}_b", tag)).await
+    })
+    .await;
+
+    if created {
+        let _ = step(&mut steps, "mint", async {
+            MintHandler::from_state(state)
+                .execute(
+                    MintCommand::new(users.sender.into(), amount(MINT_AMOUNT)?, SELF_TEST_REASON.to_string()),
+                    None,
+                    &OperationContext::new(),
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            expect_balances(state, users, MINT_AMOUNT, Decimal::ZERO).await
+        })
+        .await
+            && step(&mut steps, "transfer", async {
+                let result = TransferHandler::from_state(state)
+                    .execute(
+                        TransferCommand::new(users.sender.into(), users.recipient.into(), amount(TRANSFER_AMOUNT)?),
+                        None,
+                        &OperationContext::new().with_request_user(users.sender),
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                if result.status != "completed" {
+                    return Err(format!("transfer is {} instead of completed", result.status));
+                }
+                expect_balances(state, users, MINT_AMOUNT - TRANSFER_AMOUNT, TRANSFER_AMOUNT).await
+            })
+            .await
+            && step(&mut steps, "burn", async {
+                burn(state, users.sender, MINT_AMOUNT - TRANSFER_AMOUNT).await?;
+                burn(state, users.recipient, TRANSFER_AMOUNT).await?;
+                expect_balances(state, users, Decimal::ZERO, Decimal::ZERO).await
+            })
+            .await
+            && step(&mut steps, "ledger_parity", async {
+                check_parity(state, users.sender).await?;
+                check_parity(state, users.recipient).await
+            })
+            .await;
+
+        step(&mut steps, "cleanup", async {
+            cleanup(state, users.sender).await?;
+            cleanup(state, users.recipient).await
+        })
+        .await;
+    }
+
+    let passed = steps.iter().all(|step| step.passed);
+    let report = SelfTestReport {
+        run_id,
+        passed,
+        steps,
+        started_at,
+        duration_ms: started.elapsed().as_millis() as u64,
+    };
+
+    if passed {
+        tracing::info!(%run_id, duration_ms = report.duration_ms, "Self-test passed");
+    } else {
+        tracing::error!(%run_id, steps = ?report.steps, "Self-test failed");
+    }
+
+    report
+}
+
+/// Run one step and record its outcome; returns whether it passed
+async fn step<F>(steps: &mut Vec<SelfTestStep>, name: &'static str, body: F) -> bool
+where
+    F: Future<Output = Result<(), String>>,
+{
+    let started = Instant::now();
+    let result = body.await;
+    let passed = result.is_ok();
+
+    steps.push(SelfTestStep {
+        name,
+        passed,
+        error: result.err(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    });
+
+    passed
+}
+
+fn amount(value: Decimal) -> Result<Amount, String> {
+    Amount::new(value).map_err(|e| e.to_string())
+}
+
+async fn create_user(state: &AppState, user_id: Uuid, username: &str) -> Result<(), String> {
+    let email = format!("{}@self-test.invalid", username);
+    CreateUserHandler::from_state(state)
+        .execute(
+            CreateUserCommand::new(user_id.into(), username.to_string(), email),
+            None,
+            &OperationContext::new(),
+        )
+        .await
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+/// Burn as the wallet owner, so the burn passes either consent policy
+async fn burn(state: &AppState, user_id: Uuid, value: Decimal) -> Result<(), String> {
+    BurnHandler::from_state(state)
+        .execute(
+            BurnCommand::new(user_id.into(), amount(value)?, SELF_TEST_REASON.to_string()),
+            None,
+            &OperationContext::new().with_request_user(user_id),
+        )
+        .await
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+async fn balance(state: &AppState, user_id: Uuid) -> Result<Decimal, String> {
+    state
+        .projection
+        .get_user_balance(user_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("user {} has no wallet", user_id))
+}
+
+async fn expect_balances(
+    state: &AppState,
+    users: Participants,
+    sender: Decimal,
+    recipient: Decimal,
+) -> Result<(), String> {
+    for (user_id, expected) in [(users.sender, sender), (users.recipient, recipient)] {
+        let actual = balance(state, user_id).await?;
+        if actual != expected {
+            return Err(format!("balance of {} is {}, expected {}", user_id, actual, expected));
+        }
+    }
+    Ok(())
+}
+
+/// The projected balance of each of the user's wallets must equal its
+/// ledger entries and its event-sourced balance
+async fn check_parity(state: &AppState, user_id: Uuid) -> Result<(), String> {
+    let wallets: Vec<(Uuid, Decimal, Decimal)> = sqlx::query_as(
+        r#"
+        SELECT a.id,
+               COALESCE(ab.balance, 0),
+               COALESCE((
+                   SELECT SUM(CASE WHEN le.entry_type = 'credit' THEN le.amount ELSE -le.amount END)
+                   FROM ledger_entries le
+                   WHERE le.account_id = a.id
+               ), 0)
+        FROM accounts a
+        LEFT JOIN account_balances ab ON ab.account_id = a.id
+        WHERE a.user_id = $1 AND a.account_type = 'user_wallet'
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(&state.pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    for (account_id, projected, ledger) in wallets {
+        let account: Account = state
+            .event_store
+            .load_aggregate(account_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("account {} has no events", account_id))?;
+        let replayed = account.balance().value();
+
+        if projected != ledger || projected != replayed {
+            return Err(format!(
+                "account {}: projected {}, ledger {}, events {}",
+                account_id, projected, ledger, replayed
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Burn whatever an earlier failure left behind and deactivate the user
+async fn cleanup(state: &AppState, user_id: Uuid) -> Result<(), String> {
+    let remaining = balance(state, user_id).await?;
+    if remaining > Decimal::ZERO {
+        burn(state, user_id, remaining).await?;
+    }
+
+    DeactivateUserHandler::from_state(state)
+        .execute(
+            DeactivateUserCommand::new(user_id.into()).with_reason(SELF_TEST_REASON.to_string()),
+            &OperationContext::new(),
+        )
+        .await
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenario_amounts() {
+        assert_eq!(MINT_AMOUNT, Decimal::new(10, 0));
+        assert_eq!(TRANSFER_AMOUNT, Decimal::new(4, 0));
+        assert!(amount(MINT_AMOUNT - TRANSFER_AMOUNT).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_step_records_outcome() {
+        let mut steps = Vec::new();
+        assert!(step(&mut steps, "ok", async { Ok(()) }).await);
+        assert!(!step(&mut steps, "fails", async { Err("boom".to_string()) }).await);
+
+        assert!(steps[0].passed && steps[0].error.is_none());
+        assert_eq!(steps[1].name, "fails");
+        assert_eq!(steps[1].error.as_deref(), Some("boom"));
+    }
+}
@@ -0,0 +1,563 @@
+//! Load Simulator (`simulator` feature)
+//!
+//! Drives N concurrent simulated users doing random transfers and mints,
+//! either against a running instance over HTTP or directly against the
+//! command handlers with a database pool, and reports throughput, latency,
+//! conflict and retry rates, and whether the ledger is still consistent
+//! afterwards. Used to size the database; see `src/bin/simulate.rs`.
+//!
+//! Every user starts with `initial_balance` (minted during setup). Transfers
+//! conserve money and failed operations move none, so at the end the users'
+//! balances must add up to the initial balances plus the successful mints.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::api::validation::AmountInput;
+use crate::domain::Amount;
+use crate::error::AppError;
+use crate::handlers::{CreateUserCommand, CreateUserHandler, MintCommand, MintHandler, TransferCommand, TransferHandler};
+use crate::{jobs, OperationContext, SharedState};
+
+/// Simulation parameters
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    /// Concurrent simulated users
+    pub users: usize,
+    /// Operations each user performs
+    pub operations_per_user: usize,
+    /// Share of operations that are mints (the rest are transfers)
+    pub mint_ratio: f64,
+    /// Balance minted to every user before the run
+    pub initial_balance: Decimal,
+    /// Upper bound of a random operation amount
+    pub max_amount: Decimal,
+    /// RNG seed for reproducible operation sequences
+    pub seed: Option<u64>,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            users: 10,
+            operations_per_user: 100,
+            mint_ratio: 0.05,
+            initial_balance: Decimal::new(1000, 0),
+            max_amount: Decimal::new(50, 0),
+            seed: None,
+        }
+    }
+}
+
+/// Where operations are sent
+#[derive(Debug, Clone)]
+pub enum Target {
+    /// A running instance; the API key needs users:write, transfers:write,
+    /// admin:mint and accounts:read (admin:reconciliation for the ledger check)
+    Http {
+        base_url: String,
+        api_key: String,
+        client: reqwest::Client,
+    },
+    /// The command handlers, in process
+    Handlers(SharedState),
+}
+
+impl Target {
+    pub fn http(base_url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Target::Http {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+/// One simulated operation
+#[derive(Debug, Clone, Copy)]
+enum Operation {
+    Transfer { from: Uuid, to: Uuid, amount: Decimal },
+    Mint { to: Uuid, amount: Decimal },
+}
+
+/// Outcome class of an operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Succeeded,
+    /// 409: concurrency conflict that survived the handler's retries
+    Conflict,
+    /// Other 4xx, e.g. insufficient balance or a limit
+    Rejected,
+    /// 5xx or transport error
+    Failed,
+}
+
+impl Outcome {
+    fn from_status(status: StatusCode) -> Self {
+        if status.is_success() {
+            Outcome::Succeeded
+        } else if status == StatusCode::CONFLICT {
+            Outcome::Conflict
+        } else if status.is_client_error() {
+            Outcome::Rejected
+        } else {
+            Outcome::Failed
+        }
+    }
+}
+
+/// Result of one simulated user's run
+#[derive(Debug, Default)]
+struct UserRun {
+    latencies: Vec<Duration>,
+    succeeded: u64,
+    conflicts: u64,
+    rejected: u64,
+    failed: u64,
+    minted: Decimal,
+}
+
+/// Latency percentiles in milliseconds
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct LatencySummary {
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Ledger consistency after the run
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsistencyCheck {
+    /// Initial balances plus successful mints
+    pub expected_total: Decimal,
+    /// Sum of the simulated users' balances
+    pub actual_total: Decimal,
+    /// Discrepancies found by a reconciliation run (None if it could not run)
+    pub reconciliation_discrepancies: Option<usize>,
+}
+
+impl ConsistencyCheck {
+    pub fn is_consistent(&self) -> bool {
+        self.expected_total == self.actual_total && self.reconciliation_discrepancies.unwrap_or(0) == 0
+    }
+}
+
+/// Simulation results
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationReport {
+    pub users: usize,
+    pub operations: u64,
+    pub succeeded: u64,
+    pub conflicts: u64,
+    pub rejected: u64,
+    pub failed: u64,
+    /// Retries after concurrency conflicts inside the handlers (Handlers target only)
+    pub conflict_retries: Option<u64>,
+    pub elapsed_secs: f64,
+    pub operations_per_sec: f64,
+    pub latency: LatencySummary,
+    pub consistency: ConsistencyCheck,
+}
+
+impl SimulationReport {
+    /// Conflicts returned to clients per operation
+    pub fn conflict_rate(&self) -> f64 {
+        ratio(self.conflicts, self.operations)
+    }
+
+    /// Handler retries per operation
+    pub fn retry_rate(&self) -> Option<f64> {
+        self.conflict_retries.map(|retries| ratio(retries, self.operations))
+    }
+}
+
+fn ratio(count: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+/// Percentiles of unsorted latencies
+fn summarize_latencies(mut latencies: Vec<Duration>) -> LatencySummary {
+    latencies.sort_unstable();
+    let percentile = |p: f64| {
+        if latencies.is_empty() {
+            return 0.0;
+        }
+        let index = ((latencies.len() as f64 * p).ceil() as usize).clamp(1, latencies.len()) - 1;
+        latencies[index].as_secs_f64() * 1000.0
+    };
+    LatencySummary {
+        p50_ms: percentile(0.50),
+        p95_ms: percentile(0.95),
+        p99_ms: percentile(0.99),
+        max_ms: percentile(1.0),
+    }
+}
+
+/// Random amount in [0.01, max_amount] with two decimal places
+fn random_amount(rng: &mut StdRng, max_amount: Decimal) -> Decimal {
+    let max_cents = (max_amount * Decimal::ONE_HUNDRED)
+        .trunc()
+        .try_into()
+        .unwrap_or(1i64)
+        .max(1);
+    Decimal::new(rng.gen_range(1..=max_cents), 2)
+}
+
+/// Pick the next operation of `user`
+fn next_operation(rng: &mut StdRng, config: &SimulationConfig, user: Uuid, users: &[Uuid]) -> Operation {
+    let amount = random_amount(rng, config.max_amount);
+    if users.len() < 2 || rng.gen_bool(config.mint_ratio.clamp(0.0, 1.0)) {
+        return Operation::Mint { to: user, amount };
+    }
+    let to = loop {
+        let candidate = users[rng.gen_range(0..users.len())];
+        if candidate != user {
+            break candidate;
+        }
+    };
+    Operation::Transfer { from: user, to, amount }
+}
+
+/// Run a simulation
+pub async fn run(target: &Target, config: &SimulationConfig) -> Result<SimulationReport, SimulationError> {
+    let run_id = Uuid::new_v4().simple().to_string();
+    let mut users = Vec::with_capacity(config.users);
+    for index in 0..config.users {
+        let user_id = Uuid::new_v4();
+        create_user(target, user_id, &format!("sim_{}_{}", &run_id[..8], index)).await?;
+        let seeded = execute(
+            target,
+            Operation::Mint {
+                to: user_id,
+                amount: config.initial_balance,
+            },
+        )
+        .await;
+        if seeded != StatusCode::CREATED && seeded != StatusCode::OK {
+            return Err(SimulationError::Setup(format!("initial mint failed with {}", seeded)));
+        }
+        users.push(user_id);
+    }
+    let users = Arc::new(users);
+
+    let retries_before = conflict_retries(target);
+    let started = Instant::now();
+
+    let tasks: Vec<_> = users
+        .iter()
+        .enumerate()
+        .map(|(index, &user)| {
+            let target = target.clone();
+            let config = config.clone();
+            let users = Arc::clone(&users);
+            let mut rng = match config.seed {
+                Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(index as u64)),
+                None => StdRng::from_entropy(),
+            };
+            tokio::spawn(async move {
+                let mut run = UserRun::default();
+                for _ in 0..config.operations_per_user {
+                    let operation = next_operation(&mut rng, &config, user, &users);
+                    let started = Instant::now();
+                    let outcome = Outcome::from_status(execute(&target, operation).await);
+                    run.latencies.push(started.elapsed());
+                    match outcome {
+                        Outcome::Succeeded => {
+                            run.succeeded += 1;
+                            if let Operation::Mint { amount, .. } = operation {
+                                run.minted += amount;
+                            }
+                        }
+                        Outcome::Conflict => run.conflicts += 1,
+                        Outcome::Rejected => run.rejected += 1,
+                        Outcome::Failed => run.failed += 1,
+                    }
+                }
+                run
+            })
+        })
+        .collect();
+
+    let mut total = UserRun::default();
+    for task in tasks {
+        let run = task.await.map_err(|e| SimulationError::Setup(e.to_string()))?;
+        total.latencies.extend(run.latencies);
+        total.succeeded += run.succeeded;
+        total.conflicts += run.conflicts;
+        total.rejected += run.rejected;
+        total.failed += run.failed;
+        total.minted += run.minted;
+    }
+
+    let elapsed = started.elapsed();
+    let conflict_retries = conflict_retries(target)
+        .zip(retries_before)
+        .map(|(after, before)| after - before);
+    let operations = total.latencies.len() as u64;
+
+    let mut actual_total = Decimal::ZERO;
+    for &user in users.iter() {
+        actual_total += user_balance(target, user).await?;
+    }
+    let consistency = ConsistencyCheck {
+        expected_total: config.initial_balance * Decimal::from(users.len()) + total.minted,
+        actual_total,
+        reconciliation_discrepancies: reconcile(target).await,
+    };
+
+    Ok(SimulationReport {
+        users: users.len(),
+        operations,
+        succeeded: total.succeeded,
+        conflicts: total.conflicts,
+        rejected: total.rejected,
+        failed: total.failed,
+        conflict_retries,
+        elapsed_secs: elapsed.as_secs_f64(),
+        operations_per_sec: operations as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+        latency: summarize_latencies(total.latencies),
+        consistency,
+    })
+}
+
+// =========================================================================
+// Targets
+// =========================================================================
+
+async fn create_user(target: &Target, user_id: Uuid, username: &str) -> Result<(), SimulationError> {
+    let email = format!("{}@simulator.invalid", username);
+    match target {
+        Target::Http { .. } => {
+            let body = json!({ "user_id": user_id, "username": username, "email": email });
+            let status = http_send(target, reqwest::Method::POST, "/users", Some(body), None)
+                .await
+                .map(|response| response.status().as_u16())
+                .map_err(|e| SimulationError::Setup(e.to_string()))?;
+            if status != StatusCode::CREATED.as_u16() {
+                return Err(SimulationError::Setup(format!("creating {} failed with {}", username, status)));
+            }
+        }
+        Target::Handlers(state) => {
+            CreateUserHandler::from_state(state)
+                .execute(
+                    CreateUserCommand::new(user_id.into(), username.to_string(), email),
+                    None,
+                    &OperationContext::new(),
+                )
+                .await
+                .map_err(|e| SimulationError::Setup(e.to_string()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Execute an operation; the status is what the HTTP API would answer
+async fn execute(target: &Target, operation: Operation) -> StatusCode {
+    match target {
+        Target::Http { .. } => {
+            let (path, body, request_user) = match operation {
+                Operation::Transfer { from, to, amount } => (
+                    "/transfers",
+                    json!({ "from_user_id": from, "to_user_id": to, "amount": amount.to_string() }),
+                    Some(from),
+                ),
+                Operation::Mint { to, amount } => (
+                    "/admin/mint",
+                    json!({ "recipient_user_id": to, "amount": amount.to_string(), "reason": "simulation" }),
+                    None,
+                ),
+            };
+            match http_send(target, reqwest::Method::POST, path, Some(body), request_user).await {
+                Ok(response) => {
+                    StatusCode::from_u16(response.status().as_u16()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+                }
+                Err(_) => StatusCode::BAD_GATEWAY,
+            }
+        }
+        Target::Handlers(state) => {
+            let result = async {
+                match operation {
+                    Operation::Transfer { from, to, amount } => TransferHandler::from_state(state)
+                        .execute(
+                            TransferCommand::new(from.into(), to.into(), command_amount(amount)?),
+                            None,
+                            &OperationContext::new().with_request_user(from),
+                        )
+                        .await
+                        .map(drop),
+                    Operation::Mint { to, amount } => MintHandler::from_state(state)
+                        .execute(
+                            MintCommand::new(to.into(), command_amount(amount)?, "simulation".to_string()),
+                            None,
+                            &OperationContext::new(),
+                        )
+                        .await
+                        .map(drop),
+                }
+            }
+            .await;
+            match result {
+                Ok(()) => StatusCode::OK,
+                Err(e) => status_of(e),
+            }
+        }
+    }
+}
+
+/// Amount parsed as the API parses it, so both targets reject the same amounts
+fn command_amount(amount: Decimal) -> Result<Amount, AppError> {
+    AmountInput::from(amount.to_string()).into_amount("amount")
+}
+
+fn status_of(error: AppError) -> StatusCode {
+    error.into_response().status()
+}
+
+async fn user_balance(target: &Target, user_id: Uuid) -> Result<Decimal, SimulationError> {
+    match target {
+        Target::Http { .. } => {
+            let path = format!("/users/{}/balance", user_id);
+            let body: serde_json::Value = http_send(target, reqwest::Method::GET, &path, None, None)
+                .await
+                .map_err(|e| SimulationError::Balance(e.to_string()))?
+                .json()
+                .await
+                .map_err(|e| SimulationError::Balance(e.to_string()))?;
+            body["balance"]
+                .as_str()
+                .and_then(|balance| balance.parse().ok())
+                .ok_or_else(|| SimulationError::Balance(format!("unexpected response: {}", body)))
+        }
+        Target::Handlers(state) => state
+            .projection
+            .get_user_balance(user_id)
+            .await
+            .map_err(|e| SimulationError::Balance(e.to_string()))?
+            .ok_or_else(|| SimulationError::Balance(format!("user {} has no balance", user_id))),
+    }
+}
+
+/// Run a reconciliation and count its discrepancies
+async fn reconcile(target: &Target) -> Option<usize> {
+    match target {
+        Target::Http { .. } => {
+            let response = http_send(target, reqwest::Method::GET, "/admin/reconciliation?run=true&limit=1", None, None)
+                .await
+                .ok()?;
+            let body: serde_json::Value = response.json().await.ok()?;
+            body.get(0)?["discrepancies"].as_array().map(Vec::len)
+        }
+        Target::Handlers(state) => match jobs::reconcile_ledger(&state.pool).await {
+            Ok(report) => Some(report.discrepancies.len()),
+            Err(e) => {
+                tracing::error!(error = %e, "Simulation reconciliation failed");
+                None
+            }
+        },
+    }
+}
+
+fn conflict_retries(target: &Target) -> Option<u64> {
+    match target {
+        Target::Http { .. } => None,
+        Target::Handlers(state) => Some(state.event_store.conflict_retries()),
+    }
+}
+
+async fn http_send(
+    target: &Target,
+    method: reqwest::Method,
+    path: &str,
+    body: Option<serde_json::Value>,
+    request_user: Option<Uuid>,
+) -> Result<reqwest::Response, reqwest::Error> {
+    let Target::Http {
+        base_url,
+        api_key,
+        client,
+    } = target
+    else {
+        unreachable!("http_send is only called for HTTP targets");
+    };
+
+    let mut request = client
+        .request(method, format!("{}/api/v1{}", base_url, path))
+        .header("X-API-Key", api_key);
+    if let Some(user_id) = request_user {
+        request = request.header("X-Request-User-Id", user_id.to_string());
+    }
+    if let Some(body) = body {
+        request = request.json(&body);
+    }
+    request.send().await
+}
+
+/// Simulation errors (failed operations are counted, not returned)
+#[derive(Debug, thiserror::Error)]
+pub enum SimulationError {
+    #[error("Setup failed: {0}")]
+    Setup(String),
+
+    #[error("Could not read balance: {0}")]
+    Balance(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_outcome_from_status() {
+        assert_eq!(Outcome::from_status(StatusCode::CREATED), Outcome::Succeeded);
+        assert_eq!(Outcome::from_status(StatusCode::CONFLICT), Outcome::Conflict);
+        assert_eq!(Outcome::from_status(StatusCode::BAD_REQUEST), Outcome::Rejected);
+        assert_eq!(Outcome::from_status(StatusCode::BAD_GATEWAY), Outcome::Failed);
+        assert_eq!(status_of(AppError::InsufficientBalance), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_summarize_latencies() {
+        let latencies = (1..=100).map(Duration::from_millis).collect();
+        let summary = summarize_latencies(latencies);
+
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert_eq!(summarize_latencies(Vec::new()).max_ms, 0.0);
+    }
+
+    #[test]
+    fn test_next_operation_never_targets_self() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let users: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        let config = SimulationConfig {
+            mint_ratio: 0.0,
+            ..Default::default()
+        };
+
+        for _ in 0..100 {
+            match next_operation(&mut rng, &config, users[0], &users) {
+                Operation::Transfer { from, to, amount } => {
+                    assert_eq!(from, users[0]);
+                    assert_ne!(to, users[0]);
+                    assert!(amount > Decimal::ZERO && amount <= config.max_amount);
+                }
+                Operation::Mint { .. } => panic!("mint_ratio is 0"),
+            }
+        }
+    }
+}
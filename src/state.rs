@@ -0,0 +1,163 @@
+//! Application State
+//!
+//! Shared state for the HTTP layer: database pool, configuration and the
+//! services built on top of the pool. Constructed once at startup and shared
+//! between requests via Arc.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use serde::Serialize;
+use sqlx::PgPool;
+
+use crate::alerts::AlertService;
+use crate::api::jwt::JwtValidator;
+use crate::api::rate_limit::{self, RateLimitStore};
+use crate::audit::AuditLogService;
+use crate::config::Config;
+use crate::db::SystemAccounts;
+use crate::event_store::{DeadLetterRepository, EventNotifier, EventStore};
+use crate::idempotency::IdempotencyRepository;
+use crate::limits::LimitService;
+use crate::projection::{BalanceCache, ProjectionService};
+use crate::restrictions::RestrictionService;
+use crate::tenants::TenantService;
+use crate::webhooks::WebhookService;
+
+/// State shared by all routes and middleware
+pub type SharedState = Arc<AppState>;
+
+/// Application state
+#[derive(Debug)]
+pub struct AppState {
+    pub pool: PgPool,
+    pub config: Config,
+    /// SYSTEM_MINT and SYSTEM_BURN accounts, resolved at startup
+    pub system_accounts: SystemAccounts,
+    pub event_store: EventStore,
+    pub event_notifier: EventNotifier,
+    pub dead_letters: DeadLetterRepository,
+    pub projection: ProjectionService,
+    pub idempotency: IdempotencyRepository,
+    pub audit: AuditLogService,
+    pub webhooks: WebhookService,
+    pub limits: LimitService,
+    pub restrictions: RestrictionService,
+    pub tenants: TenantService,
+    pub alerts: AlertService,
+    pub rate_limits: Arc<dyn RateLimitStore>,
+    /// Bearer token validation (None when bearer tokens are not configured)
+    pub jwt: Option<JwtValidator>,
+    pub metrics: Metrics,
+}
+
+impl AppState {
+    /// Build services from the pool
+    pub fn new(pool: PgPool, config: Config, system_accounts: SystemAccounts) -> Self {
+        let mut projection = ProjectionService::new(pool.clone()).with_system_users(system_accounts.users());
+        if let Some(cache) = BalanceCache::from_config(&config) {
+            projection = projection.with_balance_cache(cache);
+        }
+
+        Self {
+            event_store: EventStore::new(pool.clone())
+                .with_isolation(config.event_store_isolation)
+                .with_poison_policy(config.event_poison_policy),
+            event_notifier: EventNotifier::new(),
+            dead_letters: DeadLetterRepository::new(pool.clone()),
+            projection,
+            idempotency: IdempotencyRepository::new(pool.clone()),
+            audit: AuditLogService::new(pool.clone()),
+            webhooks: WebhookService::new(pool.clone()),
+            limits: LimitService::new(pool.clone()).with_system_users(system_accounts.users()),
+            restrictions: RestrictionService::new(pool.clone()),
+            tenants: TenantService::new(pool.clone()),
+            alerts: AlertService::new(pool.clone(), config.alert_rules.clone()),
+            rate_limits: rate_limit::from_config(&config, pool.clone()),
+            jwt: JwtValidator::from_config(&config),
+            metrics: Metrics::default(),
+            pool,
+            config,
+            system_accounts,
+        }
+    }
+
+    /// Wrap in Arc for use as router state
+    pub fn shared(self) -> SharedState {
+        Arc::new(self)
+    }
+}
+
+// =========================================================================
+// Metrics
+// =========================================================================
+
+/// In-process request counters
+#[derive(Debug, Default)]
+pub struct Metrics {
+    requests_total: AtomicU64,
+    client_errors_total: AtomicU64,
+    server_errors_total: AtomicU64,
+    timeouts_total: AtomicU64,
+}
+
+/// Point-in-time copy of the request counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct MetricsSnapshot {
+    pub requests_total: u64,
+    pub client_errors_total: u64,
+    pub server_errors_total: u64,
+    /// Requests that ran past their latency budget (also counted as server errors)
+    pub timeouts_total: u64,
+}
+
+impl Metrics {
+    /// Count a completed request by its response status
+    pub fn record_response(&self, status: StatusCode) {
+        self.requests_total.fetch_add(1, Ordering::Relaxed);
+        if status.is_client_error() {
+            self.client_errors_total.fetch_add(1, Ordering::Relaxed);
+        } else if status.is_server_error() {
+            self.server_errors_total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Count a request answered 503 by its latency budget
+    pub fn record_timeout(&self) {
+        self.timeouts_total.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            requests_total: self.requests_total.load(Ordering::Relaxed),
+            client_errors_total: self.client_errors_total.load(Ordering::Relaxed),
+            server_errors_total: self.server_errors_total.load(Ordering::Relaxed),
+            timeouts_total: self.timeouts_total.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics_record_response() {
+        let metrics = Metrics::default();
+        metrics.record_response(StatusCode::OK);
+        metrics.record_response(StatusCode::NOT_FOUND);
+        metrics.record_response(StatusCode::INTERNAL_SERVER_ERROR);
+        metrics.record_timeout();
+
+        assert_eq!(
+            metrics.snapshot(),
+            MetricsSnapshot {
+                requests_total: 3,
+                client_errors_total: 1,
+                server_errors_total: 1,
+                timeouts_total: 1,
+            }
+        );
+    }
+}
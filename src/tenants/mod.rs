@@ -0,0 +1,328 @@
+//! Tenants
+//!
+//! Several isolated ATP economies can share one deployment. Every user,
+//! account, event, journal and API key belongs to a tenant; a request is
+//! resolved to the tenant of its API key and only sees that tenant's data.
+//! Existing data and the system users belong to the default tenant, whose
+//! keys are also the only ones allowed in platform-wide admin areas.
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::AppError;
+
+/// Tenant of all data created before multi-tenancy (seeded by migration 042)
+pub const DEFAULT_TENANT_ID: Uuid = Uuid::nil();
+
+// =========================================================================
+// Records
+// =========================================================================
+
+/// A tenant
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct Tenant {
+    pub id: Uuid,
+    pub name: String,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Tenant {
+    pub fn is_default(&self) -> bool {
+        self.id == DEFAULT_TENANT_ID
+    }
+}
+
+/// A tenant with the size of its economy
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantSummary {
+    #[serde(flatten)]
+    pub tenant: Tenant,
+    /// Non-system users
+    pub user_count: i64,
+    /// Sum of the tenant's user wallet balances
+    pub total_balance: Decimal,
+}
+
+/// Changes to a tenant (None = unchanged)
+#[derive(Debug, Clone, Default)]
+pub struct TenantUpdate {
+    pub name: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+/// Resource kinds named by request paths and bodies
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TenantResource {
+    User,
+    Account,
+    Transfer,
+    Hold,
+    ApiKey,
+}
+
+impl TenantResource {
+    /// Resource named by a route path parameter (None for parameters that
+    /// are not tenant-scoped)
+    pub fn from_path_param(name: &str) -> Option<Self> {
+        match name {
+            "user_id" => Some(TenantResource::User),
+            "account_id" => Some(TenantResource::Account),
+            "transfer_id" => Some(TenantResource::Transfer),
+            "hold_id" => Some(TenantResource::Hold),
+            "key_id" => Some(TenantResource::ApiKey),
+            _ => None,
+        }
+    }
+
+    /// Query returning the tenant of the resource with ID $1
+    fn tenant_sql(&self) -> &'static str {
+        match self {
+            TenantResource::User => "SELECT tenant_id FROM users WHERE id = $1",
+            TenantResource::Account => "SELECT tenant_id FROM accounts WHERE id = $1",
+            TenantResource::Transfer => {
+                r#"
+                SELECT tenant_id FROM journals WHERE id = $1
+                UNION ALL
+                SELECT u.tenant_id FROM pending_transfers p JOIN users u ON u.id = p.from_user_id
+                WHERE p.transfer_id = $1
+                UNION ALL
+                SELECT u.tenant_id FROM failed_transfers f JOIN users u ON u.id = f.from_user_id
+                WHERE f.transfer_id = $1
+                LIMIT 1
+                "#
+            }
+            TenantResource::Hold => {
+                "SELECT a.tenant_id FROM account_holds h JOIN accounts a ON a.id = h.account_id WHERE h.id = $1"
+            }
+            TenantResource::ApiKey => "SELECT tenant_id FROM api_keys WHERE id = $1",
+        }
+    }
+}
+
+// =========================================================================
+// TenantService
+// =========================================================================
+
+/// Tenant Service
+#[derive(Debug, Clone)]
+pub struct TenantService {
+    pool: PgPool,
+}
+
+impl TenantService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// All tenants, default tenant first
+    pub async fn list(&self) -> Result<Vec<Tenant>, TenantError> {
+        let tenants = sqlx::query_as(
+            r#"
+            SELECT id, name, is_active, created_at, updated_at
+            FROM tenants
+            ORDER BY id <> $1, created_at
+            "#,
+        )
+        .bind(DEFAULT_TENANT_ID)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(tenants)
+    }
+
+    pub async fn get(&self, tenant_id: Uuid) -> Result<Tenant, TenantError> {
+        let tenant: Option<Tenant> = sqlx::query_as(
+            "SELECT id, name, is_active, created_at, updated_at FROM tenants WHERE id = $1",
+        )
+        .bind(tenant_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        tenant.ok_or(TenantError::NotFound(tenant_id))
+    }
+
+    /// A tenant with its user count and total wallet balance
+    pub async fn summary(&self, tenant_id: Uuid) -> Result<TenantSummary, TenantError> {
+        let tenant = self.get(tenant_id).await?;
+
+        let (user_count, total_balance): (i64, Decimal) = sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM users WHERE tenant_id = $1 AND NOT is_system),
+                COALESCE((
+                    SELECT SUM(ab.balance)
+                    FROM accounts a
+                    JOIN account_balances ab ON ab.account_id = a.id
+                    WHERE a.tenant_id = $1 AND a.account_type = 'user_wallet'
+                ), 0)
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(TenantSummary {
+            tenant,
+            user_count,
+            total_balance,
+        })
+    }
+
+    pub async fn create(&self, name: &str) -> Result<Tenant, TenantError> {
+        sqlx::query_as(
+            r#"
+            INSERT INTO tenants (name)
+            VALUES ($1)
+            RETURNING id, name, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| TenantError::from_write(e, name))
+    }
+
+    /// Rename and/or (de)activate a tenant. The default tenant cannot be
+    /// deactivated.
+    pub async fn update(&self, tenant_id: Uuid, update: TenantUpdate) -> Result<Tenant, TenantError> {
+        if tenant_id == DEFAULT_TENANT_ID && update.is_active == Some(false) {
+            return Err(TenantError::Invalid(
+                "the default tenant cannot be deactivated".to_string(),
+            ));
+        }
+
+        let tenant: Option<Tenant> = sqlx::query_as(
+            r#"
+            UPDATE tenants
+            SET name = COALESCE($2, name),
+                is_active = COALESCE($3, is_active),
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING id, name, is_active, created_at, updated_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&update.name)
+        .bind(update.is_active)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| TenantError::from_write(e, update.name.as_deref().unwrap_or_default()))?;
+
+        tenant.ok_or(TenantError::NotFound(tenant_id))
+    }
+
+    /// Tenant of a resource (None if it does not exist)
+    pub async fn tenant_of(&self, resource: TenantResource, id: Uuid) -> Result<Option<Uuid>, TenantError> {
+        let tenant_id = sqlx::query_scalar(resource.tenant_sql())
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(tenant_id)
+    }
+
+    /// Fail as if the resource did not exist when it belongs to a tenant
+    /// other than `scope`. Missing resources pass, so the caller reports them
+    /// as usual; `None` (internal callers) is not scoped.
+    pub async fn ensure(&self, scope: Option<Uuid>, resource: TenantResource, id: Uuid) -> Result<(), TenantError> {
+        let Some(scope) = scope else {
+            return Ok(());
+        };
+
+        match self.tenant_of(resource, id).await? {
+            Some(tenant_id) if tenant_id != scope => Err(TenantError::OutOfScope(resource, id)),
+            _ => Ok(()),
+        }
+    }
+
+    /// `ensure` for several resources of one kind
+    pub async fn ensure_all(
+        &self,
+        scope: Option<Uuid>,
+        resource: TenantResource,
+        ids: impl IntoIterator<Item = Uuid>,
+    ) -> Result<(), TenantError> {
+        for id in ids {
+            self.ensure(scope, resource, id).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Tenant errors
+#[derive(Debug, thiserror::Error)]
+pub enum TenantError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Tenant not found: {0}")]
+    NotFound(Uuid),
+
+    #[error("Tenant name already exists: {0}")]
+    NameTaken(String),
+
+    #[error("Invalid tenant: {0}")]
+    Invalid(String),
+
+    /// The resource belongs to another tenant (reported as not found)
+    #[error("{0:?} {1} belongs to another tenant")]
+    OutOfScope(TenantResource, Uuid),
+}
+
+impl TenantError {
+    /// Map a unique violation on tenants.name
+    fn from_write(e: sqlx::Error, name: &str) -> Self {
+        match &e {
+            sqlx::Error::Database(db) if db.is_unique_violation() => TenantError::NameTaken(name.to_string()),
+            _ => TenantError::Database(e),
+        }
+    }
+}
+
+impl From<TenantError> for AppError {
+    fn from(e: TenantError) -> Self {
+        match e {
+            TenantError::OutOfScope(resource, id) => match resource {
+                TenantResource::User => AppError::UserNotFound(id.to_string()),
+                TenantResource::Account => AppError::AccountNotFound(id.to_string()),
+                TenantResource::Hold => AppError::HoldNotFound(id.to_string()),
+                TenantResource::Transfer => AppError::InvalidRequest(format!("Transfer {} not found", id)),
+                TenantResource::ApiKey => AppError::InvalidRequest("API key not found".to_string()),
+            },
+            TenantError::NotFound(id) => AppError::InvalidRequest(format!("Tenant not found: {}", id)),
+            TenantError::Database(e) => AppError::Internal(e.to_string()),
+            e => AppError::InvalidRequest(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_from_path_param() {
+        assert_eq!(TenantResource::from_path_param("user_id"), Some(TenantResource::User));
+        assert_eq!(TenantResource::from_path_param("key_id"), Some(TenantResource::ApiKey));
+        assert_eq!(TenantResource::from_path_param("event_id"), None);
+        assert_eq!(TenantResource::from_path_param("tenant_id"), None);
+    }
+
+    #[test]
+    fn test_out_of_scope_reads_as_not_found() {
+        let id = Uuid::new_v4();
+        assert!(matches!(
+            AppError::from(TenantError::OutOfScope(TenantResource::User, id)),
+            AppError::UserNotFound(_)
+        ));
+        assert!(matches!(
+            AppError::from(TenantError::OutOfScope(TenantResource::Account, id)),
+            AppError::AccountNotFound(_)
+        ));
+    }
+}
@@ -0,0 +1,28 @@
+//! Test Utilities (feature `test-util`)
+//!
+//! In-memory fakes of the event store, projections, idempotency keys, limits
+//! and restrictions, so command handlers built with them can run without
+//! PostgreSQL:
+//!
+//! ```ignore
+//! let store = InMemoryEventStore::new();
+//! let handler = FreezeAccountHandler::with_event_store(store.clone(), unreachable_pool());
+//! ```
+
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+use std::time::Duration;
+
+pub use crate::event_store::InMemoryEventStore;
+pub use crate::idempotency::InMemoryIdempotencyStore;
+pub use crate::limits::InMemoryLimits;
+pub use crate::projection::{InMemoryProjection, MemoryLedgerEntry};
+pub use crate::restrictions::InMemoryRestrictions;
+
+/// Pool that never connects, for the services a handler still takes from a
+/// pool. Best-effort writes (audit log, webhooks) fail fast and are logged.
+pub fn unreachable_pool() -> PgPool {
+    PgPoolOptions::new()
+        .acquire_timeout(Duration::from_millis(50))
+        .connect_lazy_with(PgConnectOptions::new().host("127.0.0.1").port(1))
+}
@@ -0,0 +1,407 @@
+//! Webhooks
+//!
+//! Outbound notifications of domain events to admin-registered endpoints.
+//! Command handlers queue one delivery per matching endpoint in
+//! webhook_deliveries; the dispatcher job (jobs::dispatch_webhooks) sends
+//! them as HMAC-signed JSON and retries failures with exponential backoff.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+// =========================================================================
+// Event types
+// =========================================================================
+
+/// Domain events that can be delivered to webhook endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEventType {
+    TransferExecuted,
+    TransferReversed,
+    TransferPendingApproval,
+    TransferRejected,
+    TransferExpired,
+    MintExecuted,
+    BurnExecuted,
+    UserCreated,
+    UserUpdated,
+    UserDeactivated,
+    UserReactivated,
+    UserAnonymized,
+    UserRenamed,
+    AccountFrozen,
+    AccountUnfrozen,
+    AccountClosed,
+    AlertRaised,
+}
+
+impl WebhookEventType {
+    pub const ALL: [WebhookEventType; 17] = [
+        WebhookEventType::TransferExecuted,
+        WebhookEventType::TransferReversed,
+        WebhookEventType::TransferPendingApproval,
+        WebhookEventType::TransferRejected,
+        WebhookEventType::TransferExpired,
+        WebhookEventType::MintExecuted,
+        WebhookEventType::BurnExecuted,
+        WebhookEventType::UserCreated,
+        WebhookEventType::UserUpdated,
+        WebhookEventType::UserDeactivated,
+        WebhookEventType::UserReactivated,
+        WebhookEventType::UserAnonymized,
+        WebhookEventType::UserRenamed,
+        WebhookEventType::AccountFrozen,
+        WebhookEventType::AccountUnfrozen,
+        WebhookEventType::AccountClosed,
+        WebhookEventType::AlertRaised,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEventType::TransferExecuted => "TransferExecuted",
+            WebhookEventType::TransferReversed => "TransferReversed",
+            WebhookEventType::TransferPendingApproval => "TransferPendingApproval",
+            WebhookEventType::TransferRejected => "TransferRejected",
+            WebhookEventType::TransferExpired => "TransferExpired",
+            WebhookEventType::MintExecuted => "MintExecuted",
+            WebhookEventType::BurnExecuted => "BurnExecuted",
+            WebhookEventType::UserCreated => "UserCreated",
+            WebhookEventType::UserUpdated => "UserUpdated",
+            WebhookEventType::UserDeactivated => "UserDeactivated",
+            WebhookEventType::UserReactivated => "UserReactivated",
+            WebhookEventType::UserAnonymized => "UserAnonymized",
+            WebhookEventType::UserRenamed => "UserRenamed",
+            WebhookEventType::AccountFrozen => "AccountFrozen",
+            WebhookEventType::AccountUnfrozen => "AccountUnfrozen",
+            WebhookEventType::AccountClosed => "AccountClosed",
+            WebhookEventType::AlertRaised => "AlertRaised",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEventType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for WebhookEventType {
+    type Err = WebhookError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|t| t.as_str() == s)
+            .ok_or_else(|| WebhookError::UnknownEventType(s.to_string()))
+    }
+}
+
+// =========================================================================
+// Records
+// =========================================================================
+
+/// Registered webhook endpoint (the signing secret is never returned)
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookEndpoint {
+    pub id: Uuid,
+    pub url: String,
+    pub event_types: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Queued or completed delivery of one event to one endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub endpoint_id: Uuid,
+    pub event_id: Uuid,
+    pub event_type: String,
+    pub status: String,
+    pub attempt_count: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+// =========================================================================
+// Signing
+// =========================================================================
+
+/// HMAC-SHA256 signature of "{timestamp}.{body}", hex encoded.
+/// Sent as `X-Webhook-Signature: sha256=<signature>` with `X-Webhook-Timestamp`.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Generate a random signing secret
+pub fn generate_secret() -> String {
+    use rand::Rng;
+    let random_bytes: [u8; 24] = rand::thread_rng().gen();
+    format!("whsec_{}", hex::encode(random_bytes))
+}
+
+// =========================================================================
+// WebhookService
+// =========================================================================
+
+/// Webhook Service
+#[derive(Debug, Clone)]
+pub struct WebhookService {
+    pool: PgPool,
+}
+
+impl WebhookService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Register a new endpoint
+    pub async fn register_endpoint(
+        &self,
+        url: &str,
+        secret: &str,
+        event_types: &[WebhookEventType],
+    ) -> Result<WebhookEndpoint, WebhookError> {
+        let parsed = reqwest::Url::parse(url).map_err(|_| WebhookError::InvalidUrl(url.to_string()))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err(WebhookError::InvalidUrl(url.to_string()));
+        }
+
+        let event_types: Vec<String> = event_types.iter().map(|t| t.as_str().to_string()).collect();
+
+        let (id, created_at): (Uuid, DateTime<Utc>) = sqlx::query_as(
+            r#"
+            INSERT INTO webhook_endpoints (url, secret, event_types)
+            VALUES ($1, $2, $3)
+            RETURNING id, created_at
+            "#,
+        )
+        .bind(url)
+        .bind(secret)
+        .bind(&event_types)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(WebhookEndpoint {
+            id,
+            url: url.to_string(),
+            event_types,
+            is_active: true,
+            created_at,
+        })
+    }
+
+    /// List all endpoints, newest first
+    #[allow(clippy::type_complexity)]
+    pub async fn list_endpoints(&self) -> Result<Vec<WebhookEndpoint>, WebhookError> {
+        let rows: Vec<(Uuid, String, Vec<String>, bool, DateTime<Utc>)> = sqlx::query_as(
+            r#"
+            SELECT id, url, event_types, is_active, created_at
+            FROM webhook_endpoints
+            ORDER BY created_at DESC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, url, event_types, is_active, created_at)| WebhookEndpoint {
+                id,
+                url,
+                event_types,
+                is_active,
+                created_at,
+            })
+            .collect())
+    }
+
+    /// Deactivate an endpoint; its pending deliveries are abandoned
+    pub async fn deactivate_endpoint(&self, id: Uuid) -> Result<(), WebhookError> {
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            "UPDATE webhook_endpoints SET is_active = false, updated_at = NOW() WHERE id = $1",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(WebhookError::EndpointNotFound(id));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE webhook_deliveries
+            SET status = 'failed', last_error = 'Endpoint deactivated'
+            WHERE endpoint_id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Recent deliveries for an endpoint, newest first
+    #[allow(clippy::type_complexity)]
+    pub async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<WebhookDelivery>, WebhookError> {
+        let rows: Vec<(
+            Uuid,
+            Uuid,
+            Uuid,
+            String,
+            String,
+            i32,
+            DateTime<Utc>,
+            Option<String>,
+            DateTime<Utc>,
+            Option<DateTime<Utc>>,
+        )> = sqlx::query_as(
+            r#"
+            SELECT id, endpoint_id, event_id, event_type, status, attempt_count,
+                   next_attempt_at, last_error, created_at, delivered_at
+            FROM webhook_deliveries
+            WHERE endpoint_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(endpoint_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(
+                    id,
+                    endpoint_id,
+                    event_id,
+                    event_type,
+                    status,
+                    attempt_count,
+                    next_attempt_at,
+                    last_error,
+                    created_at,
+                    delivered_at,
+                )| WebhookDelivery {
+                    id,
+                    endpoint_id,
+                    event_id,
+                    event_type,
+                    status,
+                    attempt_count,
+                    next_attempt_at,
+                    last_error,
+                    created_at,
+                    delivered_at,
+                },
+            )
+            .collect())
+    }
+
+    /// Queue an event for every active endpoint subscribed to its type.
+    /// Returns the number of deliveries queued.
+    pub async fn enqueue(
+        &self,
+        event_type: WebhookEventType,
+        data: serde_json::Value,
+    ) -> Result<u64, WebhookError> {
+        let event_id = Uuid::new_v4();
+        let payload = serde_json::json!({
+            "id": event_id,
+            "type": event_type.as_str(),
+            "created_at": Utc::now(),
+            "data": data,
+        });
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO webhook_deliveries (endpoint_id, event_id, event_type, payload)
+            SELECT id, $1, $2, $3
+            FROM webhook_endpoints
+            WHERE is_active
+              AND (cardinality(event_types) = 0 OR $2 = ANY(event_types))
+            "#,
+        )
+        .bind(event_id)
+        .bind(event_type.as_str())
+        .bind(&payload)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Queue an event from a command handler.
+    /// The command has already been committed, so failures are logged, not propagated.
+    pub async fn notify(&self, event_type: WebhookEventType, data: serde_json::Value) {
+        if let Err(e) = self.enqueue(event_type, data).await {
+            tracing::error!(event_type = %event_type, "Failed to queue webhook deliveries: {}", e);
+        }
+    }
+}
+
+/// Webhook errors
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("Database error: {0}")]
+    Database(#[from] sqlx::Error),
+
+    #[error("Invalid webhook URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Unknown webhook event type: {0}")]
+    UnknownEventType(String),
+
+    #[error("Webhook endpoint not found: {0}")]
+    EndpointNotFound(Uuid),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_type_round_trip() {
+        for event_type in WebhookEventType::ALL {
+            assert_eq!(event_type.as_str().parse::<WebhookEventType>().unwrap(), event_type);
+        }
+        assert!("TransferInitiated".parse::<WebhookEventType>().is_err());
+    }
+
+    #[test]
+    fn test_sign_payload() {
+        let signature = sign_payload("secret", 1700000000, b"{\"id\":1}");
+
+        assert_eq!(
+            signature,
+            "3dd1b9aef568d75f6790a84bd2e5dfa1f44409eef3cbdbd3f10b837376100c11"
+        );
+        assert_eq!(signature, sign_payload("secret", 1700000000, b"{\"id\":1}"));
+        assert_ne!(signature, sign_payload("other", 1700000000, b"{\"id\":1}"));
+        assert_ne!(signature, sign_payload("secret", 1700000001, b"{\"id\":1}"));
+    }
+}
@@ -3,6 +3,9 @@
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 
+use finance_atp::config::SystemUserIds;
+use finance_atp::{db, AppState, Config, SharedState};
+
 /// Setup test database - truncate tables and seed test data
 pub async fn setup_test_db() -> PgPool {
     dotenvy::dotenv().ok();
@@ -48,8 +51,9 @@ pub async fn setup_test_db() -> PgPool {
     .expect("Failed to seed API key");
 
     // Seed SYSTEM_MINT user and account (required for Mint operations)
-    let system_user_id: uuid::Uuid = "00000000-0000-0000-0000-000000000001".parse().unwrap();
-    let system_account_id: uuid::Uuid = "00000000-0000-0000-0000-000000000002".parse().unwrap();
+    // (the account ID must not reuse a system user's ID)
+    let system_user_id = SystemUserIds::SEEDED.mint;
+    let system_account_id = uuid::Uuid::new_v4();
 
     // 1. Insert System User
     sqlx::query(
@@ -103,5 +107,21 @@ pub async fn setup_test_db() -> PgPool {
 
     tx.commit().await.expect("Failed to commit transaction");
 
+    // SYSTEM_BURN and the other system users and accounts
+    db::seed_system_accounts(&pool)
+        .await
+        .expect("Failed to seed system accounts");
+
     pool
 }
+
+/// Shared application state over the test pool
+#[allow(dead_code)]
+pub async fn test_state(pool: PgPool) -> SharedState {
+    let config = Config::from_env().expect("Failed to load config");
+    let system_accounts = db::resolve_system_accounts(&pool, config.system_users)
+        .await
+        .expect("Failed to resolve system accounts")
+        .expect("System accounts missing");
+    AppState::new(pool, config, system_accounts).shared()
+}
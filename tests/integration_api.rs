@@ -14,10 +14,10 @@ mod common;
 
 #[tokio::test]
 async fn test_transfer_e2e() {
-    let pool = common::setup_test_db().await;
+    let state = common::test_state(common::setup_test_db().await).await;
     let app = api::create_router()
-        .layer(middleware::from_fn_with_state(pool.clone(), finance_atp::api::middleware::auth_middleware))
-        .with_state(pool.clone());
+        .layer(middleware::from_fn_with_state(state.clone(), finance_atp::api::middleware::auth_middleware))
+        .with_state(state);
     let api_key = "test_key_123";
 
     // 1. Create User A
@@ -63,7 +63,7 @@ async fn test_transfer_e2e() {
         .header("X-Request-User-Id", user_a_id.to_string())
         .body(Body::from(serde_json::to_string(&MintRequest {
             recipient_user_id: user_a_id,
-            amount: "1000.00".to_string(),
+            amount: "1000.00".into(),
             reason: "Initial mint".to_string(),
         }).unwrap()))
         .unwrap();
@@ -80,8 +80,11 @@ async fn test_transfer_e2e() {
         .body(Body::from(serde_json::to_string(&TransferRequest {
             from_user_id: user_a_id,
             to_user_id: user_b_id,
-            amount: "300.00".to_string(),
+            amount: "300.00".into(),
             memo: Some("Payment for goods".to_string()),
+            category: Some("purchase".to_string()),
+            from_account_id: None,
+            to_account_id: None,
         }).unwrap()))
         .unwrap();
     let response = app.clone().oneshot(req).await.unwrap();
@@ -112,14 +115,99 @@ async fn test_transfer_e2e() {
     let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
     let json: Value = serde_json::from_slice(&body).unwrap();
     assert_eq!(json["balance"], "300.00000000");
+
+    // 7. User B's own views resolve the user from X-Request-User-Id
+    let req = Request::builder()
+        .method("GET")
+        .uri("/me/balance")
+        .header("X-API-Key", api_key)
+        .header("X-Request-User-Id", user_b_id.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["user_id"], user_b_id.to_string());
+    assert_eq!(json["balance"], "300.00000000");
+
+    let req = Request::builder()
+        .method("GET")
+        .uri("/me/transfers")
+        .header("X-API-Key", api_key)
+        .header("X-Request-User-Id", user_b_id.to_string())
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["transfers"].as_array().unwrap().len(), 1);
+    assert_eq!(json["transfers"][0]["from_user_id"], user_a_id.to_string());
+
+    // Without a request user there is no "me"
+    let req = Request::builder()
+        .method("GET")
+        .uri("/me/balance")
+        .header("X-API-Key", api_key)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // 8. User B moves funds into a named sub-wallet
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/users/{}/accounts", user_b_id))
+        .header("content-type", "application/json")
+        .header("X-API-Key", api_key)
+        .body(Body::from(r#"{"name":"savings"}"#))
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED, "Account creation failed");
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    let savings_id: Uuid = json["account_id"].as_str().unwrap().parse().unwrap();
+
+    let req = Request::builder()
+        .method("POST")
+        .uri("/transfers")
+        .header("content-type", "application/json")
+        .header("X-API-Key", api_key)
+        .header("X-Request-User-Id", user_b_id.to_string())
+        .body(Body::from(serde_json::to_string(&TransferRequest {
+            from_user_id: user_b_id,
+            to_user_id: user_b_id,
+            amount: "100.00".into(),
+            memo: None,
+            category: None,
+            from_account_id: None,
+            to_account_id: Some(savings_id),
+        }).unwrap()))
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK, "Transfer to sub-wallet failed");
+
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/users/{}/balance", user_b_id))
+        .header("X-API-Key", api_key)
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(req).await.unwrap();
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["balance"], "200.00000000");
+    assert_eq!(json["accounts"][1]["name"], "savings");
+    assert_eq!(json["accounts"][1]["balance"], "100.00000000");
 }
 
 #[tokio::test]
 async fn test_idempotency_api() {
-    let pool = common::setup_test_db().await;
+    let state = common::test_state(common::setup_test_db().await).await;
     let app = api::create_router()
-        .layer(middleware::from_fn_with_state(pool.clone(), finance_atp::api::middleware::auth_middleware))
-        .with_state(pool.clone());
+        .layer(middleware::from_fn_with_state(state.clone(), finance_atp::api::middleware::auth_middleware))
+        .with_state(state);
     let api_key = "test_key_123";
 
     // Create user
@@ -142,7 +230,7 @@ async fn test_idempotency_api() {
     let idempotency_key = Uuid::new_v4();
     let mint_req = MintRequest {
         recipient_user_id: user_id,
-        amount: "50.00".to_string(),
+        amount: "50.00".into(),
         reason: "Idempotent mint".to_string(),
     };
 
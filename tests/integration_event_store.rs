@@ -1,9 +1,10 @@
 //! Integration tests for Event Store (M155, M159)
 
-use finance_atp::aggregate::Aggregate;
-use finance_atp::domain::{AccountEvent, OperationContext};
-use finance_atp::event_store::{EventStore, AggregateOperation};
+use finance_atp::aggregate::{Account, Aggregate};
+use finance_atp::domain::{AccountEvent, AccountType, OperationContext};
+use finance_atp::event_store::{EventStore, AggregateOperation, EventStoreError, Subscription};
 use chrono::Utc;
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
 mod common;
@@ -20,7 +21,8 @@ async fn test_event_store_append_and_load() {
     let event = AccountEvent::AccountCreated {
         account_id,
         user_id,
-        account_type: "user_wallet".to_string(),
+        account_type: AccountType::UserWallet,
+        name: None,
         created_at: Utc::now(),
     };
 
@@ -58,7 +60,8 @@ async fn test_event_store_concurrency_conflict() {
     let event1 = AccountEvent::AccountCreated {
         account_id,
         user_id,
-        account_type: "user_wallet".to_string(),
+        account_type: AccountType::UserWallet,
+        name: None,
         created_at: Utc::now(),
     };
 
@@ -79,3 +82,228 @@ async fn test_event_store_concurrency_conflict() {
     let result = event_store.append_atomic(vec![op2], None, &context).await;
     assert!(result.is_err(), "Should fail due to version conflict");
 }
+
+#[tokio::test]
+async fn test_event_store_load_aggregate_at() {
+    let pool = common::setup_test_db().await;
+    let event_store = EventStore::new(pool);
+
+    let account_id = Uuid::new_v4();
+    let context = OperationContext::new().with_correlation_id(Uuid::new_v4());
+    let before_creation = Utc::now();
+
+    let created = AccountEvent::AccountCreated {
+        account_id,
+        user_id: Uuid::new_v4(),
+        account_type: AccountType::UserWallet,
+        name: None,
+        created_at: Utc::now(),
+    };
+    let op = AggregateOperation::new("Account", account_id, 0, "AccountCreated", &created).unwrap();
+    event_store.append_atomic(vec![op], None, &context).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    let before_credit = Utc::now();
+
+    let credited = AccountEvent::MoneyCredited {
+        account_id,
+        amount: Decimal::new(100, 0),
+        transfer_id: Uuid::new_v4(),
+        description: "Test credit".to_string(),
+        credited_at: Utc::now(),
+        category: None,
+    };
+    let op = AggregateOperation::new("Account", account_id, 1, "MoneyCredited", &credited).unwrap();
+    event_store.append_atomic(vec![op], None, &context).await.unwrap();
+
+    let account: Option<Account> = event_store.load_aggregate_at(account_id, before_creation).await.unwrap();
+    assert!(account.is_none());
+
+    let account: Account = event_store.load_aggregate_at(account_id, before_credit).await.unwrap().unwrap();
+    assert_eq!(account.balance().value(), Decimal::ZERO);
+
+    let account: Account = event_store.load_aggregate_at(account_id, Utc::now()).await.unwrap().unwrap();
+    assert_eq!(account.balance().value(), Decimal::new(100, 0));
+}
+
+#[tokio::test]
+async fn test_event_store_read_all_and_subscription() {
+    let pool = common::setup_test_db().await;
+    let event_store = EventStore::new(pool.clone());
+    let context = OperationContext::new().with_correlation_id(Uuid::new_v4());
+
+    let head = event_store.head_sequence().await.unwrap();
+
+    let account_id = Uuid::new_v4();
+    let created = AccountEvent::AccountCreated {
+        account_id,
+        user_id: Uuid::new_v4(),
+        account_type: AccountType::UserWallet,
+        name: None,
+        created_at: Utc::now(),
+    };
+    let frozen = AccountEvent::AccountFrozen {
+        account_id,
+        reason: "Test freeze".to_string(),
+        frozen_at: Utc::now(),
+    };
+    let ops = vec![
+        AggregateOperation::new("Account", account_id, 0, "AccountCreated", &created).unwrap(),
+    ];
+    event_store.append_atomic(ops, None, &context).await.unwrap();
+    let ops = vec![
+        AggregateOperation::new("Account", account_id, 1, "AccountFrozen", &frozen).unwrap(),
+    ];
+    event_store.append_atomic(ops, None, &context).await.unwrap();
+
+    let events = event_store.read_all(head, 10).await.unwrap();
+    assert_eq!(events.len(), 2);
+    assert!(events[0].global_sequence < events[1].global_sequence);
+    assert_eq!(events[1].event_type, "AccountFrozen");
+
+    let name = format!("test-{}", Uuid::new_v4());
+    let mut subscription = Subscription::open(pool.clone(), &name).await.unwrap();
+    let processed = subscription
+        .catch_up(1, |_event| async { Ok::<_, EventStoreError>(()) })
+        .await
+        .unwrap();
+    assert!(processed >= 2);
+    assert!(subscription.position() >= events[1].global_sequence);
+
+    // The checkpoint survives reopening
+    let reopened = Subscription::open(pool, &name).await.unwrap();
+    assert_eq!(reopened.position(), subscription.position());
+}
+
+#[tokio::test]
+async fn test_read_all_waits_for_append_in_flight() {
+    let pool = common::setup_test_db().await;
+    let event_store = EventStore::new(pool);
+    let context = OperationContext::new().with_correlation_id(Uuid::new_v4());
+
+    let head = event_store.head_sequence().await.unwrap();
+    let created = |account_id| AccountEvent::AccountCreated {
+        account_id,
+        user_id: Uuid::new_v4(),
+        account_type: AccountType::UserWallet,
+        name: None,
+        created_at: Utc::now(),
+    };
+
+    // First append takes its sequence but does not commit yet
+    let pending_account = Uuid::new_v4();
+    let op = AggregateOperation::new("Account", pending_account, 0, "AccountCreated", &created(pending_account)).unwrap();
+    let tx = event_store.begin().await.unwrap();
+    let (tx, pending_ids) = event_store.append_atomic_in_tx(tx, &[op], None, &context).await.unwrap();
+
+    // A later append commits first
+    let later_account = Uuid::new_v4();
+    let op = AggregateOperation::new("Account", later_account, 0, "AccountCreated", &created(later_account)).unwrap();
+    let later_ids = event_store.append_atomic(vec![op], None, &context).await.unwrap();
+
+    // The reader sees the gap and waits for the append in flight
+    let reader = {
+        let event_store = event_store.clone();
+        tokio::spawn(async move { event_store.read_all(head, 1000).await })
+    };
+    tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    assert!(!reader.is_finished());
+
+    tx.commit().await.unwrap();
+    let events = reader.await.unwrap().unwrap();
+    let position = |id| events.iter().position(|e| e.id == id).unwrap();
+    assert!(position(pending_ids[0]) < position(later_ids[0]));
+}
+
+#[tokio::test]
+async fn test_append_with_retry_rebuilds_after_conflict() {
+    let pool = common::setup_test_db().await;
+    let event_store = EventStore::new(pool);
+    let context = OperationContext::new().with_correlation_id(Uuid::new_v4());
+
+    let account_id = Uuid::new_v4();
+    let created = AccountEvent::AccountCreated {
+        account_id,
+        user_id: Uuid::new_v4(),
+        account_type: AccountType::UserWallet,
+        name: None,
+        created_at: Utc::now(),
+    };
+    let ops = vec![
+        AggregateOperation::new("Account", account_id, 0, "AccountCreated", &created).unwrap(),
+    ];
+    event_store.append_atomic(ops, None, &context).await.unwrap();
+
+    // The first attempt uses a stale version; the retry reloads the account
+    let store = &event_store;
+    let mut attempts = 0;
+    let (event_ids, version) = store
+        .append_with_retry(None, &context, || {
+            attempts += 1;
+            let stale = attempts == 1;
+            async move {
+                let account: Account = store.load_aggregate(account_id).await?.unwrap();
+                let expected = if stale { account.version() - 1 } else { account.version() };
+                let frozen = AccountEvent::AccountFrozen {
+                    account_id,
+                    reason: "Test freeze".to_string(),
+                    frozen_at: Utc::now(),
+                };
+                let op = AggregateOperation::new("Account", account_id, expected, "AccountFrozen", &frozen)?;
+                Ok::<_, EventStoreError>((vec![op], expected))
+            }
+        })
+        .await
+        .unwrap();
+
+    assert_eq!(attempts, 2);
+    assert_eq!(event_ids.len(), 1);
+    assert_eq!(version, 1);
+
+    // A single-attempt append with a stale version is not retried
+    let frozen = AccountEvent::AccountFrozen {
+        account_id,
+        reason: "Stale".to_string(),
+        frozen_at: Utc::now(),
+    };
+    let ops = vec![
+        AggregateOperation::new("Account", account_id, 1, "AccountFrozen", &frozen).unwrap(),
+    ];
+    let err = event_store.append_atomic(ops, None, &context).await.unwrap_err();
+    assert!(err.is_concurrency_conflict());
+}
+
+#[tokio::test]
+async fn test_append_rejects_duplicate_and_skipped_versions() {
+    let pool = common::setup_test_db().await;
+    let event_store = EventStore::new(pool);
+    let context = OperationContext::new().with_correlation_id(Uuid::new_v4());
+
+    let account_id = Uuid::new_v4();
+    let created = AccountEvent::AccountCreated {
+        account_id,
+        user_id: Uuid::new_v4(),
+        account_type: AccountType::UserWallet,
+        name: None,
+        created_at: Utc::now(),
+    };
+    let ops = vec![
+        AggregateOperation::new("Account", account_id, 0, "AccountCreated", &created).unwrap(),
+    ];
+    event_store.append_atomic(ops, None, &context).await.unwrap();
+
+    for expected in [0, 5] {
+        let frozen = AccountEvent::AccountFrozen {
+            account_id,
+            reason: "Test freeze".to_string(),
+            frozen_at: Utc::now(),
+        };
+        let ops = vec![
+            AggregateOperation::new("Account", account_id, expected, "AccountFrozen", &frozen).unwrap(),
+        ];
+        match event_store.append_atomic(ops, None, &context).await {
+            Err(EventStoreError::ConcurrencyConflict { actual, .. }) => assert_eq!(actual, 1),
+            other => panic!("expected concurrency conflict, got {:?}", other),
+        }
+    }
+}